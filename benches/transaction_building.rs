@@ -0,0 +1,118 @@
+//! Time and allocations of building the transactions of one trade
+//!
+//! `cargo bench --bench transaction_building`. A trade sent through several swqos
+//! providers builds one transaction per provider from the same instructions. `owned`
+//! clones them into a `Vec` per provider before building, `shared` clones them from a
+//! shared slice straight into the transaction's buffer, as `parallel_execute_with_tips`
+//! does.
+
+use std::sync::Arc;
+
+use divan::{AllocProfiler, Bencher};
+use sol_trade_sdk::common::{PriorityFee, TradeSigner};
+use sol_trade_sdk::trading::common::build_tip_transaction;
+use sol_trade_sdk::trading::factory::DexType;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use tokio::runtime::Runtime;
+
+#[global_allocator]
+static ALLOC: AllocProfiler = AllocProfiler::system();
+
+const PROVIDERS: &[usize] = &[1, 4, 8];
+
+fn main() {
+    divan::main();
+}
+
+/// Shaped like a PumpSwap buy wrapping SOL: six instructions, the swap with 20 accounts
+fn buy_instructions(payer: &Pubkey) -> Vec<Instruction> {
+    let instruction = |accounts: usize, data: usize| Instruction {
+        program_id: Pubkey::new_unique(),
+        accounts: (0..accounts)
+            .map(|i| match i {
+                0 => AccountMeta::new(*payer, true),
+                _ if i % 3 == 0 => AccountMeta::new_readonly(Pubkey::new_unique(), false),
+                _ => AccountMeta::new(Pubkey::new_unique(), false),
+            })
+            .collect(),
+        data: vec![7; data],
+    };
+    vec![
+        instruction(6, 1),
+        instruction(2, 12),
+        instruction(1, 1),
+        instruction(6, 1),
+        instruction(20, 24),
+        instruction(3, 1),
+    ]
+}
+
+struct Setup {
+    runtime: Runtime,
+    payer: Arc<dyn TradeSigner>,
+    priority_fee: PriorityFee,
+    tip_account: Pubkey,
+    instructions: Vec<Instruction>,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let payer = Keypair::new();
+        let instructions = buy_instructions(&payer.pubkey());
+        Self {
+            runtime: tokio::runtime::Builder::new_current_thread().build().unwrap(),
+            payer: Arc::new(payer),
+            priority_fee: PriorityFee::default(),
+            tip_account: Pubkey::new_unique(),
+            instructions,
+        }
+    }
+
+    async fn build(&self, instructions: impl IntoIterator<Item = Instruction>) {
+        build_tip_transaction(
+            self.payer.clone(),
+            None,
+            &self.priority_fee,
+            instructions,
+            &self.tip_account,
+            0.001,
+            None,
+            Hash::default(),
+            256 * 1024,
+            None,
+            DexType::PumpSwap,
+            true,
+        )
+        .await
+        .unwrap();
+    }
+}
+
+#[divan::bench(args = PROVIDERS)]
+fn owned(bencher: Bencher, providers: usize) {
+    let setup = Setup::new();
+    bencher.bench_local(|| {
+        setup.runtime.block_on(async {
+            for _ in 0..providers {
+                setup.build(setup.instructions.clone()).await;
+            }
+        })
+    });
+}
+
+#[divan::bench(args = PROVIDERS)]
+fn shared(bencher: Bencher, providers: usize) {
+    let setup = Setup::new();
+    let instructions: Arc<[Instruction]> = setup.instructions.clone().into();
+    bencher.bench_local(|| {
+        setup.runtime.block_on(async {
+            for _ in 0..providers {
+                setup.build(instructions.iter().cloned()).await;
+            }
+        })
+    });
+}
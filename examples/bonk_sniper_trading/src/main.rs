@@ -89,9 +89,16 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         priority_fee: priority_fee,
         swqos_configs,
         lookup_table_key: None,
+        rpc_pool: None,
+        core_affinity: Default::default(),
+        swqos_http_client: None,
+        proxy: None,
+        swqos_proxies: vec![],
+        confirmation: Default::default(),
+        jito_simulate_bundles: false,
     };
 
-    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await;
+    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await?;
     println!("SolanaTrade client created successfully!");
 
     Ok(solana_trade_client)
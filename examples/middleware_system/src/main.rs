@@ -32,7 +32,7 @@ impl InstructionMiddleware for CustomMiddleware {
     fn process_protocol_instructions(
         &self,
         protocol_instructions: Vec<Instruction>,
-        protocol_name: String,
+        dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>> {
         // do anything you want here
@@ -43,7 +43,7 @@ impl InstructionMiddleware for CustomMiddleware {
     fn process_full_instructions(
         &self,
         full_instructions: Vec<Instruction>,
-        protocol_name: String,
+        dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>> {
         // do anything you want here
@@ -73,9 +73,16 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         priority_fee: PriorityFee::default(),
         swqos_configs,
         lookup_table_key: None,
+        rpc_pool: None,
+        core_affinity: Default::default(),
+        swqos_http_client: None,
+        proxy: None,
+        swqos_proxies: vec![],
+        confirmation: Default::default(),
+        jito_simulate_bundles: false,
     };
 
-    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await;
+    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await?;
     println!("SolanaTrade client created successfully!");
 
     Ok(solana_trade_client)
@@ -14,7 +14,7 @@ use sol_trade_sdk::solana_streamer_sdk::streaming::yellowstone_grpc::{
 };
 use sol_trade_sdk::solana_streamer_sdk::streaming::YellowstoneGrpc;
 use sol_trade_sdk::{
-    common::{AnyResult, PriorityFee, TradeConfig},
+    common::{bonding_curve_cache::BondingCurveCache, AnyResult, PriorityFee, TradeConfig},
     swqos::SwqosConfig,
     trading::{core::params::PumpFunParams, factory::DexType},
     SolanaTrade,
@@ -79,6 +79,8 @@ fn create_event_callback() -> impl Fn(Box<dyn UnifiedEvent>) {
     |event: Box<dyn UnifiedEvent>| {
         match_event!(event, {
             PumpFunTradeEvent => |e: PumpFunTradeEvent| {
+                // Keep the bonding curve cache warm so later trades can skip the RPC fetch
+                BondingCurveCache::get_instance().update_from_trade(&e);
                 // Test code, only test one transaction
                 if !ALREADY_EXECUTED.swap(true, Ordering::SeqCst) {
                     let event_clone = e.clone();
@@ -114,9 +116,16 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         priority_fee: priority_fee,
         swqos_configs,
         lookup_table_key: None,
+        rpc_pool: None,
+        core_affinity: Default::default(),
+        swqos_http_client: None,
+        proxy: None,
+        swqos_proxies: vec![],
+        confirmation: Default::default(),
+        jito_simulate_bundles: false,
     };
 
-    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await;
+    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await?;
     println!("SolanaTrade client created successfully!");
 
     Ok(solana_trade_client)
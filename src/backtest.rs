@@ -0,0 +1,581 @@
+//! Backtesting over recorded events
+//!
+//! Trade events are persisted as JSON lines through an [`EventSink`] while a strategy runs
+//! live, and replayed later with [`run`]. The strategy callback sees every event and can
+//! trade through the [`Backtest`] it is handed; orders fill against the reserves left by the
+//! events so far, using the same bonding curve / PumpSwap math as the trading code, plus the
+//! fee, tip and slippage assumptions of the `BacktestConfig`. Positions and PnL are kept in a
+//! `PositionTracker` and summarised in a [`BacktestReport`].
+//!
+//! ```ignore
+//! let sink = JsonlEventSink::create("events.jsonl")?;
+//! // in the stream callback
+//! sink.write(&RecordedEvent::PumpFunTrade(event))?;
+//!
+//! let events = read_event_log("events.jsonl")?;
+//! let report = backtest::run(BacktestConfig::default(), events, |event, bt| {
+//!     if bt.position(&event.mint()).is_none() {
+//!         let _ = bt.buy(event.mint(), 100_000_000);
+//!     }
+//! })
+//! .await;
+//! println!("PnL: {} lamports", report.total_pnl_lamports());
+//! ```
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::common::AnyResult;
+use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+use crate::solana_streamer_sdk::streaming::event_parser::protocols::{
+    pumpfun::PumpFunTradeEvent,
+    pumpswap::{PumpSwapBuyEvent, PumpSwapSellEvent},
+};
+use crate::trading::{
+    factory::DexType,
+    paper::{PaperFill, Side},
+    Position, PositionTracker,
+};
+use crate::utils::calc::{common::calculate_with_slippage_sell, pumpfun, pumpswap};
+
+/// Event kept in an event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "event")]
+pub enum RecordedEvent {
+    PumpFunTrade(PumpFunTradeEvent),
+    PumpSwapBuy(PumpSwapBuyEvent),
+    PumpSwapSell(PumpSwapSellEvent),
+}
+
+impl RecordedEvent {
+    pub fn mint(&self) -> Pubkey {
+        match self {
+            RecordedEvent::PumpFunTrade(e) => e.mint,
+            RecordedEvent::PumpSwapBuy(e) => e.base_mint,
+            RecordedEvent::PumpSwapSell(e) => e.base_mint,
+        }
+    }
+
+    pub fn signature(&self) -> &str {
+        match self {
+            RecordedEvent::PumpFunTrade(e) => &e.metadata.signature,
+            RecordedEvent::PumpSwapBuy(e) => &e.metadata.signature,
+            RecordedEvent::PumpSwapSell(e) => &e.metadata.signature,
+        }
+    }
+
+    /// Block time in milliseconds, from the metadata or the event's own timestamp
+    pub fn time_ms(&self) -> i64 {
+        let (block_time_ms, timestamp) = match self {
+            RecordedEvent::PumpFunTrade(e) => (e.metadata.block_time_ms, e.timestamp),
+            RecordedEvent::PumpSwapBuy(e) => (e.metadata.block_time_ms, e.timestamp),
+            RecordedEvent::PumpSwapSell(e) => (e.metadata.block_time_ms, e.timestamp),
+        };
+        if block_time_ms > 0 {
+            block_time_ms
+        } else {
+            timestamp.saturating_mul(1000)
+        }
+    }
+
+    /// Convert an event delivered by `streaming::subscribe_token`
+    #[cfg(feature = "grpc")]
+    pub fn from_token_event(event: &crate::streaming::TokenEvent) -> Option<Self> {
+        use crate::streaming::TokenEvent;
+        match event {
+            TokenEvent::PumpFunTrade(e) => Some(RecordedEvent::PumpFunTrade(e.clone())),
+            TokenEvent::PumpSwapBuy(e) => Some(RecordedEvent::PumpSwapBuy(e.clone())),
+            TokenEvent::PumpSwapSell(e) => Some(RecordedEvent::PumpSwapSell(e.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Destination for recorded events
+pub trait EventSink: Send + Sync {
+    fn write(&self, event: &RecordedEvent) -> AnyResult<()>;
+
+    fn flush(&self) -> AnyResult<()> {
+        Ok(())
+    }
+}
+
+/// Appends events to a file, one JSON object per line
+pub struct JsonlEventSink {
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl JsonlEventSink {
+    /// Open `path` for appending, creating it if needed
+    pub fn create(path: impl AsRef<Path>) -> AnyResult<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { writer: Mutex::new(BufWriter::new(file)) })
+    }
+}
+
+impl EventSink for JsonlEventSink {
+    fn write(&self, event: &RecordedEvent) -> AnyResult<()> {
+        let mut writer = self.writer.lock().unwrap();
+        serde_json::to_writer(&mut *writer, event)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    fn flush(&self) -> AnyResult<()> {
+        self.writer.lock().unwrap().flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for JsonlEventSink {
+    fn drop(&mut self) {
+        let _ = self.writer.lock().map(|mut writer| writer.flush());
+    }
+}
+
+/// Read an event log written by `JsonlEventSink`, ordered by block time
+pub fn read_event_log(path: impl AsRef<Path>) -> AnyResult<Vec<RecordedEvent>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut events = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event =
+            serde_json::from_str(&line).map_err(|e| anyhow!("event log line {}: {}", i + 1, e))?;
+        events.push(event);
+    }
+    // Stable, so events of the same block keep their recorded order
+    events.sort_by_key(RecordedEvent::time_ms);
+    Ok(events)
+}
+
+/// How fast events are replayed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// No waiting between events
+    Max,
+    /// Keep the recorded gaps between events, divided by the factor (1.0 = real time)
+    Scaled(f64),
+}
+
+/// Fill model assumptions
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestConfig {
+    pub speed: ReplaySpeed,
+    /// Signature and priority fee paid per trade, in lamports
+    pub fee_lamports: u64,
+    /// Tip paid per trade, in lamports
+    pub tip_lamports: u64,
+    /// Adverse slippage applied to every fill on top of the price impact, in basis points
+    pub slippage_basis_points: u64,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            speed: ReplaySpeed::Max,
+            fee_lamports: 5_000 + 150_000,
+            tip_lamports: 1_000_000,
+            slippage_basis_points: 0,
+        }
+    }
+}
+
+/// Latest reserves of a token
+#[derive(Debug, Clone, PartialEq)]
+enum Market {
+    BondingCurve {
+        virtual_token_reserves: u64,
+        virtual_sol_reserves: u64,
+        real_token_reserves: u64,
+        creator: Pubkey,
+    },
+    PumpSwap {
+        base_reserve: u64,
+        quote_reserve: u64,
+        coin_creator: Pubkey,
+    },
+}
+
+impl Market {
+    fn dex_type(&self) -> DexType {
+        match self {
+            Market::BondingCurve { .. } => DexType::PumpFun,
+            Market::PumpSwap { .. } => DexType::PumpSwap,
+        }
+    }
+
+    fn buy_out(&self, sol_amount: u64) -> u64 {
+        match self {
+            Market::BondingCurve {
+                virtual_token_reserves,
+                virtual_sol_reserves,
+                real_token_reserves,
+                creator,
+            } => pumpfun::get_buy_token_amount_from_sol_amount(
+                *virtual_token_reserves as u128,
+                *virtual_sol_reserves as u128,
+                *real_token_reserves as u128,
+                *creator,
+                sol_amount,
+            ),
+            Market::PumpSwap { base_reserve, quote_reserve, coin_creator } => {
+                pumpswap::get_buy_token_amount_from_quote_amount(
+                    *base_reserve,
+                    *quote_reserve,
+                    coin_creator,
+                    sol_amount,
+                )
+            }
+        }
+    }
+
+    fn sell_out(&self, token_amount: u64) -> u64 {
+        match self {
+            Market::BondingCurve {
+                virtual_token_reserves, virtual_sol_reserves, creator, ..
+            } => pumpfun::get_sell_sol_amount_from_token_amount(
+                *virtual_token_reserves as u128,
+                *virtual_sol_reserves as u128,
+                *creator,
+                token_amount,
+            ),
+            Market::PumpSwap { base_reserve, quote_reserve, coin_creator } => {
+                pumpswap::get_sell_quote_amount_from_token_amount(
+                    *base_reserve,
+                    *quote_reserve,
+                    coin_creator,
+                    token_amount,
+                )
+            }
+        }
+    }
+
+    /// Reserves after `event`, `None` for pools not quoted in SOL
+    fn after(event: &RecordedEvent) -> Option<Market> {
+        match event {
+            // PumpFun trade events carry the reserves after the trade
+            RecordedEvent::PumpFunTrade(e) => Some(Market::BondingCurve {
+                virtual_token_reserves: e.virtual_token_reserves,
+                virtual_sol_reserves: e.virtual_sol_reserves,
+                real_token_reserves: e.real_token_reserves,
+                creator: e.creator,
+            }),
+            // PumpSwap events carry the reserves before the trade
+            RecordedEvent::PumpSwapBuy(e) if e.quote_mint == WSOL_TOKEN_ACCOUNT => {
+                Some(Market::PumpSwap {
+                    base_reserve: e.pool_base_token_reserves.saturating_sub(e.base_amount_out),
+                    quote_reserve: e
+                        .pool_quote_token_reserves
+                        .saturating_add(e.quote_amount_in_with_lp_fee),
+                    coin_creator: e.coin_creator,
+                })
+            }
+            RecordedEvent::PumpSwapSell(e) if e.quote_mint == WSOL_TOKEN_ACCOUNT => {
+                Some(Market::PumpSwap {
+                    base_reserve: e.pool_base_token_reserves.saturating_add(e.base_amount_in),
+                    quote_reserve: e
+                        .pool_quote_token_reserves
+                        .saturating_sub(e.quote_amount_out_without_lp_fee),
+                    coin_creator: e.coin_creator,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Apply a simulated fill so later orders see its price impact
+    fn apply(&mut self, side: Side, sol_amount: u64, token_amount: u64) {
+        match (self, side) {
+            (
+                Market::BondingCurve {
+                    virtual_token_reserves,
+                    virtual_sol_reserves,
+                    real_token_reserves,
+                    ..
+                },
+                Side::Buy,
+            ) => {
+                *virtual_token_reserves = virtual_token_reserves.saturating_sub(token_amount);
+                *real_token_reserves = real_token_reserves.saturating_sub(token_amount);
+                *virtual_sol_reserves = virtual_sol_reserves.saturating_add(sol_amount);
+            }
+            (
+                Market::BondingCurve {
+                    virtual_token_reserves,
+                    virtual_sol_reserves,
+                    real_token_reserves,
+                    ..
+                },
+                Side::Sell,
+            ) => {
+                *virtual_token_reserves = virtual_token_reserves.saturating_add(token_amount);
+                *real_token_reserves = real_token_reserves.saturating_add(token_amount);
+                *virtual_sol_reserves = virtual_sol_reserves.saturating_sub(sol_amount);
+            }
+            (Market::PumpSwap { base_reserve, quote_reserve, .. }, Side::Buy) => {
+                *base_reserve = base_reserve.saturating_sub(token_amount);
+                *quote_reserve = quote_reserve.saturating_add(sol_amount);
+            }
+            (Market::PumpSwap { base_reserve, quote_reserve, .. }, Side::Sell) => {
+                *base_reserve = base_reserve.saturating_add(token_amount);
+                *quote_reserve = quote_reserve.saturating_sub(sol_amount);
+            }
+        }
+    }
+}
+
+/// Simulated exchange handed to the strategy callback
+pub struct Backtest {
+    config: BacktestConfig,
+    markets: HashMap<Pubkey, Market>,
+    tracker: PositionTracker,
+    fills: Vec<PaperFill>,
+    now_ms: i64,
+}
+
+impl Backtest {
+    fn new(config: BacktestConfig) -> Self {
+        Self {
+            config,
+            markets: HashMap::new(),
+            tracker: PositionTracker::new(),
+            fills: Vec::new(),
+            now_ms: 0,
+        }
+    }
+
+    /// Block time of the event being replayed, in milliseconds
+    pub fn now_ms(&self) -> i64 {
+        self.now_ms
+    }
+
+    pub fn position(&self, mint: &Pubkey) -> Option<Position> {
+        self.tracker.position(mint).filter(Position::is_open)
+    }
+
+    /// Lamports `token_amount` would sell for now, before fees and slippage assumptions
+    pub fn quote_sell(&self, mint: &Pubkey, token_amount: u64) -> Option<u64> {
+        self.markets.get(mint).map(|market| market.sell_out(token_amount))
+    }
+
+    /// Buy `mint` for `sol_amount` lamports at the current reserves
+    pub fn buy(&mut self, mint: Pubkey, sol_amount: u64) -> AnyResult<PaperFill> {
+        let market =
+            self.markets.get_mut(&mint).ok_or_else(|| anyhow!("no market for {}", mint))?;
+        let token_amount = calculate_with_slippage_sell(
+            market.buy_out(sol_amount),
+            self.config.slippage_basis_points,
+        )
+        .map_err(|e| anyhow!(e))?;
+        if token_amount == 0 {
+            return Err(anyhow!("buy of {} lamports of {} fills nothing", sol_amount, mint));
+        }
+        market.apply(Side::Buy, sol_amount, token_amount);
+        let dex_type = market.dex_type();
+        let fee_lamports = self.config.fee_lamports + self.config.tip_lamports;
+        self.tracker.record_buy(mint, sol_amount, token_amount, fee_lamports);
+        Ok(self.push_fill(dex_type, mint, Side::Buy, sol_amount, token_amount, fee_lamports, 0))
+    }
+
+    /// Sell `token_amount` of `mint`, capped at the position, at the current reserves
+    pub fn sell(&mut self, mint: Pubkey, token_amount: u64) -> AnyResult<PaperFill> {
+        let held = self.tracker.position(&mint).map_or(0, |position| position.token_amount);
+        let token_amount = token_amount.min(held);
+        if token_amount == 0 {
+            return Err(anyhow!("no position in {}", mint));
+        }
+        let market =
+            self.markets.get_mut(&mint).ok_or_else(|| anyhow!("no market for {}", mint))?;
+        let sol_amount = calculate_with_slippage_sell(
+            market.sell_out(token_amount),
+            self.config.slippage_basis_points,
+        )
+        .map_err(|e| anyhow!(e))?;
+        market.apply(Side::Sell, sol_amount, token_amount);
+        let dex_type = market.dex_type();
+        let fee_lamports = self.config.fee_lamports + self.config.tip_lamports;
+        let pnl = self.tracker.record_sell(mint, token_amount, sol_amount, fee_lamports);
+        Ok(self.push_fill(dex_type, mint, Side::Sell, sol_amount, token_amount, fee_lamports, pnl))
+    }
+
+    /// Sell the whole position in `mint`
+    pub fn sell_all(&mut self, mint: Pubkey) -> AnyResult<PaperFill> {
+        self.sell(mint, u64::MAX)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push_fill(
+        &mut self,
+        dex_type: DexType,
+        mint: Pubkey,
+        side: Side,
+        sol_amount: u64,
+        token_amount: u64,
+        fee_lamports: u64,
+        realized_pnl_lamports: i64,
+    ) -> PaperFill {
+        let fill = PaperFill {
+            dex_type,
+            mint,
+            side,
+            sol_amount,
+            token_amount,
+            fee_lamports,
+            realized_pnl_lamports,
+            simulation: None,
+            timestamp: UNIX_EPOCH + Duration::from_millis(self.now_ms.max(0) as u64),
+        };
+        self.fills.push(fill.clone());
+        fill
+    }
+
+    fn report(self, events: usize) -> BacktestReport {
+        let positions: Vec<Position> =
+            self.tracker.positions().into_iter().filter(Position::is_open).collect();
+        let unrealized_pnl_lamports = positions
+            .iter()
+            .map(|position| {
+                let value = self
+                    .markets
+                    .get(&position.mint)
+                    .map_or(0, |market| market.sell_out(position.token_amount));
+                position.unrealized_pnl(value)
+            })
+            .sum();
+        BacktestReport {
+            events,
+            realized_pnl_lamports: self.tracker.realized_pnl(),
+            unrealized_pnl_lamports,
+            fees_lamports: self.fills.iter().map(|fill| fill.fee_lamports).sum(),
+            fills: self.fills,
+            open_positions: positions,
+        }
+    }
+}
+
+/// Outcome of a backtest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    /// Events replayed
+    pub events: usize,
+    pub fills: Vec<PaperFill>,
+    /// Positions still open at the end
+    pub open_positions: Vec<Position>,
+    /// PnL of closed trades net of fees, in lamports
+    pub realized_pnl_lamports: i64,
+    /// Open positions marked at the final reserves, in lamports
+    pub unrealized_pnl_lamports: i64,
+    /// Fees and tips paid, in lamports
+    pub fees_lamports: u64,
+}
+
+impl BacktestReport {
+    pub fn total_pnl_lamports(&self) -> i64 {
+        self.realized_pnl_lamports + self.unrealized_pnl_lamports
+    }
+
+    /// Share of sells that realised a profit
+    pub fn win_rate(&self) -> Option<f64> {
+        let sells: Vec<&PaperFill> =
+            self.fills.iter().filter(|fill| fill.side == Side::Sell).collect();
+        if sells.is_empty() {
+            return None;
+        }
+        let wins = sells.iter().filter(|fill| fill.realized_pnl_lamports > 0).count();
+        Some(wins as f64 / sells.len() as f64)
+    }
+}
+
+/// Replay `events` through `strategy`
+///
+/// Each event first updates the reserves of its token, then `strategy` is called and may
+/// trade through the `Backtest`; fills see the reserves left by the event.
+pub async fn run<F>(
+    config: BacktestConfig,
+    events: Vec<RecordedEvent>,
+    mut strategy: F,
+) -> BacktestReport
+where
+    F: FnMut(&RecordedEvent, &mut Backtest),
+{
+    let speed = config.speed;
+    let mut backtest = Backtest::new(config);
+    let mut previous_ms: Option<i64> = None;
+    let count = events.len();
+    for event in events {
+        let time_ms = event.time_ms();
+        if let (ReplaySpeed::Scaled(factor), Some(previous_ms)) = (speed, previous_ms) {
+            let gap_ms = (time_ms - previous_ms).max(0) as f64 / factor.max(f64::MIN_POSITIVE);
+            if gap_ms >= 1.0 {
+                tokio::time::sleep(Duration::from_millis(gap_ms as u64)).await;
+            }
+        }
+        previous_ms = Some(time_ms);
+        backtest.now_ms = time_ms;
+        if let Some(market) = Market::after(&event) {
+            backtest.markets.insert(event.mint(), market);
+        }
+        strategy(&event, &mut backtest);
+    }
+    backtest.report(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(mint: Pubkey, time_ms: i64, virtual_sol_reserves: u64) -> RecordedEvent {
+        let mut event = PumpFunTradeEvent {
+            mint,
+            virtual_sol_reserves,
+            virtual_token_reserves: (1_073_000_000_000_000u128 * 30_000_000_000
+                / virtual_sol_reserves as u128) as u64,
+            real_token_reserves: 793_100_000_000_000,
+            ..Default::default()
+        };
+        event.metadata.block_time_ms = time_ms;
+        RecordedEvent::PumpFunTrade(event)
+    }
+
+    #[tokio::test]
+    async fn test_replay_round_trip_and_pnl() {
+        let mint = Pubkey::new_unique();
+        let path = std::env::temp_dir().join(format!("backtest-{}.jsonl", mint));
+        {
+            let sink = JsonlEventSink::create(&path).unwrap();
+            // Written out of order, the log is sorted by block time
+            sink.write(&trade(mint, 2_000, 60_000_000_000)).unwrap();
+            sink.write(&trade(mint, 1_000, 30_000_000_000)).unwrap();
+        }
+        let events = read_event_log(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].time_ms(), 1_000);
+
+        let report = run(BacktestConfig::default(), events, |event, bt| {
+            if bt.position(&event.mint()).is_none() {
+                bt.buy(event.mint(), 1_000_000_000).unwrap();
+            } else {
+                bt.sell_all(event.mint()).unwrap();
+            }
+        })
+        .await;
+        assert_eq!(report.events, 2);
+        assert_eq!(report.fills.len(), 2);
+        assert!(report.open_positions.is_empty());
+        // The price doubled between the two events
+        assert!(report.realized_pnl_lamports > 500_000_000);
+        assert_eq!(report.win_rate(), Some(1.0));
+        assert_eq!(report.fills[1].timestamp, UNIX_EPOCH + Duration::from_millis(2_000));
+    }
+}
@@ -0,0 +1,672 @@
+//! Builders for `TradeConfig` and `SolanaTrade`
+//!
+//! Instead of filling the `TradeConfig` struct and the `SwqosConfig` list by hand, enable
+//! each submission service with its own method; the configuration is checked when `build`
+//! is called.
+
+use anyhow::anyhow;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::sync::Arc;
+
+#[cfg(any(
+    feature = "jito",
+    feature = "nextblock",
+    feature = "bloxroute",
+    feature = "temporal",
+    feature = "zeroslot",
+    feature = "node1",
+    feature = "flashblock",
+    feature = "blockrazor"
+))]
+use crate::swqos::SwqosRegion;
+use crate::{
+    common::{
+        proxy::ProxyConfig, rate_limit::RateLimitConfig, rpc_pool::RpcPoolConfig,
+        runtime::CoreAffinity, PriorityFee, TradeConfig, TradeSigner,
+    },
+    notify::Notifications,
+    pricing::SolUsdPriceConfig,
+    swqos::{common::ConfirmationConfig, http::SwqosHttpConfig, SwqosConfig, SwqosType},
+    trading::{
+        common::metadata_uploader::MetadataUploaderConfig, MiddlewareManager, PaperTradingConfig,
+    },
+    SolanaTrade,
+};
+
+/// Builder for `TradeConfig`
+///
+/// Defaults: `confirmed` commitment, `PriorityFee::default()`, no lookup table and a single
+/// RPC endpoint. When no swqos service is enabled, transactions are sent through the RPC node.
+#[derive(Debug, Clone)]
+pub struct TradeConfigBuilder {
+    rpc_url: String,
+    swqos_configs: Vec<SwqosConfig>,
+    priority_fee: PriorityFee,
+    commitment: CommitmentConfig,
+    lookup_table_key: Option<Pubkey>,
+    rpc_pool: Option<RpcPoolConfig>,
+    core_affinity: CoreAffinity,
+    swqos_http: Option<SwqosHttp>,
+    proxy: Option<ProxyConfig>,
+    swqos_proxies: Vec<(SwqosType, ProxyConfig)>,
+    confirmation: ConfirmationConfig,
+    jito_simulate_bundles: bool,
+}
+
+/// Shared swqos HTTP client, or the settings to build it with in `build`
+#[derive(Debug, Clone)]
+enum SwqosHttp {
+    Client(reqwest::Client),
+    Config(SwqosHttpConfig),
+}
+
+impl TradeConfigBuilder {
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            swqos_configs: vec![],
+            priority_fee: PriorityFee::default(),
+            commitment: CommitmentConfig::confirmed(),
+            lookup_table_key: None,
+            rpc_pool: None,
+            core_affinity: CoreAffinity::Disabled,
+            swqos_http: None,
+            proxy: None,
+            swqos_proxies: vec![],
+            confirmation: ConfirmationConfig::default(),
+            jito_simulate_bundles: false,
+        }
+    }
+
+    #[cfg(feature = "jito")]
+    /// Send through Jito; an empty auth token is allowed
+    pub fn with_jito(mut self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.swqos_configs.push(SwqosConfig::Jito(auth_token.into(), region));
+        self
+    }
+
+    #[cfg(feature = "jito")]
+    /// Send through Jito, failing over between the regions in the given order
+    pub fn with_jito_failover(
+        mut self,
+        auth_token: impl Into<String>,
+        regions: Vec<SwqosRegion>,
+    ) -> Self {
+        self.swqos_configs.push(SwqosConfig::JitoFailover(auth_token.into(), regions));
+        self
+    }
+
+    #[cfg(feature = "jito")]
+    /// Simulate Jito bundles of more than one transaction with `simulateBundle` before
+    /// sending them, and fail the send without paying the tip when the simulation fails
+    pub fn with_jito_bundle_simulation(mut self, enabled: bool) -> Self {
+        self.jito_simulate_bundles = enabled;
+        self
+    }
+
+    #[cfg(feature = "nextblock")]
+    pub fn with_nextblock(mut self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.swqos_configs.push(SwqosConfig::NextBlock(auth_token.into(), region));
+        self
+    }
+
+    #[cfg(feature = "bloxroute")]
+    pub fn with_bloxroute(mut self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.swqos_configs.push(SwqosConfig::Bloxroute(auth_token.into(), region));
+        self
+    }
+
+    #[cfg(feature = "temporal")]
+    pub fn with_temporal(mut self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.swqos_configs.push(SwqosConfig::Temporal(auth_token.into(), region));
+        self
+    }
+
+    #[cfg(feature = "zeroslot")]
+    pub fn with_zeroslot(mut self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.swqos_configs.push(SwqosConfig::ZeroSlot(auth_token.into(), region));
+        self
+    }
+
+    #[cfg(feature = "node1")]
+    pub fn with_node1(mut self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.swqos_configs.push(SwqosConfig::Node1(auth_token.into(), region));
+        self
+    }
+
+    #[cfg(feature = "flashblock")]
+    pub fn with_flashblock(mut self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.swqos_configs.push(SwqosConfig::FlashBlock(auth_token.into(), region));
+        self
+    }
+
+    #[cfg(feature = "blockrazor")]
+    pub fn with_blockrazor(mut self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.swqos_configs.push(SwqosConfig::BlockRazor(auth_token.into(), region));
+        self
+    }
+
+    #[cfg(all(feature = "grpc", feature = "jito"))]
+    /// Send through Jito over gRPC instead of JSON-RPC; an empty auth token is allowed
+    pub fn with_jito_grpc(mut self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.swqos_configs.push(SwqosConfig::JitoGrpc(auth_token.into(), region));
+        self
+    }
+
+    #[cfg(all(feature = "grpc", feature = "nextblock"))]
+    /// Send through NextBlock over gRPC instead of HTTP
+    pub fn with_nextblock_grpc(
+        mut self,
+        auth_token: impl Into<String>,
+        region: SwqosRegion,
+    ) -> Self {
+        self.swqos_configs.push(SwqosConfig::NextBlockGrpc(auth_token.into(), region));
+        self
+    }
+
+    /// Also send through the given RPC endpoint (the builder's RPC URL when `None`)
+    pub fn with_rpc_submission(mut self, rpc_url: Option<String>) -> Self {
+        let rpc_url = rpc_url.unwrap_or_else(|| self.rpc_url.clone());
+        self.swqos_configs.push(SwqosConfig::Default(rpc_url));
+        self
+    }
+
+    /// Add an already built swqos config
+    pub fn with_swqos(mut self, swqos_config: SwqosConfig) -> Self {
+        self.swqos_configs.push(swqos_config);
+        self
+    }
+
+    pub fn with_priority_fee(mut self, priority_fee: PriorityFee) -> Self {
+        self.priority_fee = priority_fee;
+        self
+    }
+
+    pub fn with_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    pub fn with_lookup_table(mut self, lookup_table_key: Pubkey) -> Self {
+        self.lookup_table_key = Some(lookup_table_key);
+        self
+    }
+
+    /// Add an RPC endpoint used when the primary one fails
+    pub fn with_fallback_rpc(mut self, rpc_url: impl Into<String>) -> Self {
+        self.rpc_pool.get_or_insert_with(RpcPoolConfig::default).fallback_urls.push(rpc_url.into());
+        self
+    }
+
+    /// Send RPC requests through an `RpcPool` with these settings, replacing what
+    /// `with_fallback_rpc` / `with_rate_limit` set before
+    pub fn with_rpc_pool(mut self, rpc_pool: RpcPoolConfig) -> Self {
+        self.rpc_pool = Some(rpc_pool);
+        self
+    }
+
+    /// Limit concurrent RPC requests and back off on `429 Too Many Requests`
+    pub fn with_rate_limit(mut self, rate_limit: RateLimitConfig) -> Self {
+        self.rpc_pool.get_or_insert_with(RpcPoolConfig::default).rate_limit = rate_limit;
+        self
+    }
+
+    /// Pin the swqos submission tasks to cores, see `common::runtime`
+    pub fn with_core_affinity(mut self, core_affinity: CoreAffinity) -> Self {
+        self.core_affinity = core_affinity;
+        self
+    }
+
+    /// Submit through `http_client` for every HTTP based swqos service, see `swqos::http`
+    pub fn with_swqos_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.swqos_http = Some(SwqosHttp::Client(http_client));
+        self
+    }
+
+    /// Share one HTTP client built from `config` between the swqos services
+    pub fn with_swqos_http_config(mut self, config: SwqosHttpConfig) -> Self {
+        self.swqos_http = Some(SwqosHttp::Config(config));
+        self
+    }
+
+    /// Send RPC, swqos and metadata upload requests through `proxy`, see `common::proxy`
+    ///
+    /// A client given to `with_swqos_http_client` is used as is.
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Send the submissions of `swqos_type` through `proxy` instead
+    pub fn with_swqos_proxy(mut self, swqos_type: SwqosType, proxy: ProxyConfig) -> Self {
+        self.swqos_proxies.retain(|(existing, _)| *existing != swqos_type);
+        self.swqos_proxies.push((swqos_type, proxy));
+        self
+    }
+
+    /// Poll interval, timeout and commitment of the confirmation polling
+    pub fn with_confirmation(mut self, confirmation: ConfirmationConfig) -> Self {
+        self.confirmation = confirmation;
+        self
+    }
+
+    /// Validate and build the `TradeConfig`
+    ///
+    /// Fails on an empty or non-http RPC / fallback RPC URL, a missing auth token for a
+    /// service that requires one, a service enabled twice, an empty Jito failover region list,
+    /// negative / non-finite tip fees, an invalid proxy URL, or a zero confirmation poll
+    /// interval or timeout.
+    pub fn build(self) -> Result<TradeConfig, anyhow::Error> {
+        validate_url("RPC URL", &self.rpc_url)?;
+        if self.confirmation.poll_interval.is_zero() || self.confirmation.timeout.is_zero() {
+            return Err(anyhow!("Confirmation poll interval and timeout must be positive"));
+        }
+        for proxy in self.proxy.iter().chain(self.swqos_proxies.iter().map(|(_, proxy)| proxy)) {
+            proxy.validate()?;
+        }
+        for url in self.rpc_pool.iter().flat_map(|pool| &pool.fallback_urls) {
+            validate_url("Fallback RPC URL", url)?;
+        }
+
+        let mut swqos_configs = self.swqos_configs;
+        if swqos_configs.is_empty() {
+            swqos_configs.push(SwqosConfig::Default(self.rpc_url.clone()));
+        }
+        for (i, swqos_config) in swqos_configs.iter().enumerate() {
+            validate_swqos_config(swqos_config)?;
+            if swqos_configs[..i].contains(swqos_config) {
+                return Err(anyhow!("Swqos service enabled twice: {:?}", swqos_config));
+            }
+        }
+
+        let fee = &self.priority_fee;
+        let tip_fees = [fee.buy_tip_fee, fee.smart_buy_tip_fee, fee.sell_tip_fee];
+        if tip_fees.iter().chain(fee.buy_tip_fees.iter()).any(|tip| !tip.is_finite() || *tip < 0.0)
+        {
+            return Err(anyhow!("Tip fees must be non-negative"));
+        }
+        if fee.buy_tip_fees.len() > swqos_configs.len() {
+            return Err(anyhow!(
+                "{} buy tip fees given for {} swqos services",
+                fee.buy_tip_fees.len(),
+                swqos_configs.len()
+            ));
+        }
+
+        let mut config = TradeConfig::new(
+            self.rpc_url,
+            swqos_configs,
+            self.priority_fee,
+            self.commitment,
+            self.lookup_table_key,
+        );
+        config.rpc_pool = self.rpc_pool;
+        config.core_affinity = self.core_affinity;
+        config.swqos_http_client = match self.swqos_http {
+            Some(SwqosHttp::Client(http_client)) => Some(http_client),
+            Some(SwqosHttp::Config(mut http_config)) => {
+                http_config.proxy = http_config.proxy.or_else(|| self.proxy.clone());
+                Some(http_config.build()?)
+            }
+            None => None,
+        };
+        config.proxy = self.proxy;
+        config.swqos_proxies = self.swqos_proxies;
+        config.confirmation = self.confirmation;
+        config.jito_simulate_bundles = self.jito_simulate_bundles;
+        Ok(config)
+    }
+}
+
+fn validate_url(name: &str, url: &str) -> Result<(), anyhow::Error> {
+    if url.trim().is_empty() {
+        return Err(anyhow!("{} cannot be empty", name));
+    }
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(anyhow!("{} must start with http:// or https://: {}", name, url));
+    }
+    Ok(())
+}
+
+fn validate_swqos_config(swqos_config: &SwqosConfig) -> Result<(), anyhow::Error> {
+    match swqos_config {
+        SwqosConfig::Default(rpc_url) => validate_url("Swqos RPC URL", rpc_url),
+        // Jito works without a UUID, only with lower rate limits
+        #[cfg(feature = "jito")]
+        SwqosConfig::Jito(..) => Ok(()),
+        #[cfg(feature = "jito")]
+        SwqosConfig::JitoFailover(_, regions) => {
+            if regions.is_empty() {
+                return Err(anyhow!("Jito failover needs at least one region"));
+            }
+            Ok(())
+        }
+        #[cfg(feature = "nextblock")]
+        SwqosConfig::NextBlock(auth_token, _) => validate_auth_token("NextBlock", auth_token),
+        #[cfg(feature = "bloxroute")]
+        SwqosConfig::Bloxroute(auth_token, _) => validate_auth_token("Bloxroute", auth_token),
+        #[cfg(feature = "temporal")]
+        SwqosConfig::Temporal(auth_token, _) => validate_auth_token("Temporal", auth_token),
+        #[cfg(feature = "zeroslot")]
+        SwqosConfig::ZeroSlot(auth_token, _) => validate_auth_token("ZeroSlot", auth_token),
+        #[cfg(feature = "node1")]
+        SwqosConfig::Node1(auth_token, _) => validate_auth_token("Node1", auth_token),
+        #[cfg(feature = "flashblock")]
+        SwqosConfig::FlashBlock(auth_token, _) => validate_auth_token("FlashBlock", auth_token),
+        #[cfg(feature = "blockrazor")]
+        SwqosConfig::BlockRazor(auth_token, _) => validate_auth_token("BlockRazor", auth_token),
+        #[cfg(all(feature = "grpc", feature = "jito"))]
+        SwqosConfig::JitoGrpc(..) => Ok(()),
+        #[cfg(all(feature = "grpc", feature = "nextblock"))]
+        SwqosConfig::NextBlockGrpc(auth_token, _) => validate_auth_token("NextBlock", auth_token),
+    }
+}
+
+#[cfg(any(
+    feature = "nextblock",
+    feature = "bloxroute",
+    feature = "temporal",
+    feature = "zeroslot",
+    feature = "node1",
+    feature = "flashblock",
+    feature = "blockrazor"
+))]
+fn validate_auth_token(name: &str, auth_token: &str) -> Result<(), anyhow::Error> {
+    if auth_token.trim().is_empty() {
+        return Err(anyhow!("{} requires an auth token", name));
+    }
+    Ok(())
+}
+
+/// Builder for `SolanaTrade`
+///
+/// Wraps a `TradeConfigBuilder` and adds the optional fee payer, middleware, metadata
+/// uploader, SOL/USD price feed, paper trading and notifications, so a client can be set up
+/// in one chain.
+pub struct SolanaTradeBuilder {
+    payer: Arc<dyn TradeSigner>,
+    config: TradeConfigBuilder,
+    fee_payer: Option<Arc<dyn TradeSigner>>,
+    middleware_manager: Option<MiddlewareManager>,
+    metadata_uploader: Option<MetadataUploaderConfig>,
+    sol_usd_price: Option<SolUsdPriceConfig>,
+    paper_trading: Option<PaperTradingConfig>,
+    notifications: Option<Notifications>,
+    balance_check: bool,
+    route_migrated_pumpfun: bool,
+    sell_dust_threshold: u64,
+}
+
+impl SolanaTradeBuilder {
+    pub fn new(payer: Arc<dyn TradeSigner>, rpc_url: impl Into<String>) -> Self {
+        Self {
+            payer,
+            config: TradeConfigBuilder::new(rpc_url),
+            fee_payer: None,
+            middleware_manager: None,
+            metadata_uploader: None,
+            sol_usd_price: None,
+            paper_trading: None,
+            notifications: None,
+            balance_check: false,
+            route_migrated_pumpfun: false,
+            sell_dust_threshold: 0,
+        }
+    }
+
+    /// Configure the underlying `TradeConfigBuilder`
+    pub fn config(mut self, f: impl FnOnce(TradeConfigBuilder) -> TradeConfigBuilder) -> Self {
+        self.config = f(self.config);
+        self
+    }
+
+    #[cfg(feature = "jito")]
+    pub fn with_jito(self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.config(|config| config.with_jito(auth_token, region))
+    }
+
+    #[cfg(feature = "jito")]
+    pub fn with_jito_failover(
+        self,
+        auth_token: impl Into<String>,
+        regions: Vec<SwqosRegion>,
+    ) -> Self {
+        self.config(|config| config.with_jito_failover(auth_token, regions))
+    }
+
+    #[cfg(feature = "nextblock")]
+    pub fn with_nextblock(self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.config(|config| config.with_nextblock(auth_token, region))
+    }
+
+    #[cfg(feature = "bloxroute")]
+    pub fn with_bloxroute(self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.config(|config| config.with_bloxroute(auth_token, region))
+    }
+
+    #[cfg(feature = "temporal")]
+    pub fn with_temporal(self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.config(|config| config.with_temporal(auth_token, region))
+    }
+
+    #[cfg(feature = "zeroslot")]
+    pub fn with_zeroslot(self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.config(|config| config.with_zeroslot(auth_token, region))
+    }
+
+    #[cfg(feature = "node1")]
+    pub fn with_node1(self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.config(|config| config.with_node1(auth_token, region))
+    }
+
+    #[cfg(feature = "flashblock")]
+    pub fn with_flashblock(self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.config(|config| config.with_flashblock(auth_token, region))
+    }
+
+    #[cfg(feature = "blockrazor")]
+    pub fn with_blockrazor(self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.config(|config| config.with_blockrazor(auth_token, region))
+    }
+
+    #[cfg(all(feature = "grpc", feature = "jito"))]
+    pub fn with_jito_grpc(self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.config(|config| config.with_jito_grpc(auth_token, region))
+    }
+
+    #[cfg(all(feature = "grpc", feature = "nextblock"))]
+    pub fn with_nextblock_grpc(self, auth_token: impl Into<String>, region: SwqosRegion) -> Self {
+        self.config(|config| config.with_nextblock_grpc(auth_token, region))
+    }
+
+    pub fn with_rpc_submission(self, rpc_url: Option<String>) -> Self {
+        self.config(|config| config.with_rpc_submission(rpc_url))
+    }
+
+    pub fn with_priority_fee(self, priority_fee: PriorityFee) -> Self {
+        self.config(|config| config.with_priority_fee(priority_fee))
+    }
+
+    pub fn with_commitment(self, commitment: CommitmentConfig) -> Self {
+        self.config(|config| config.with_commitment(commitment))
+    }
+
+    pub fn with_lookup_table(self, lookup_table_key: Pubkey) -> Self {
+        self.config(|config| config.with_lookup_table(lookup_table_key))
+    }
+
+    pub fn with_fallback_rpc(self, rpc_url: impl Into<String>) -> Self {
+        self.config(|config| config.with_fallback_rpc(rpc_url))
+    }
+
+    pub fn with_rpc_pool(self, rpc_pool: RpcPoolConfig) -> Self {
+        self.config(|config| config.with_rpc_pool(rpc_pool))
+    }
+
+    pub fn with_rate_limit(self, rate_limit: RateLimitConfig) -> Self {
+        self.config(|config| config.with_rate_limit(rate_limit))
+    }
+
+    pub fn with_core_affinity(self, core_affinity: CoreAffinity) -> Self {
+        self.config(|config| config.with_core_affinity(core_affinity))
+    }
+
+    pub fn with_swqos_http_client(self, http_client: reqwest::Client) -> Self {
+        self.config(|config| config.with_swqos_http_client(http_client))
+    }
+
+    pub fn with_swqos_http_config(self, config: SwqosHttpConfig) -> Self {
+        self.config(|builder| builder.with_swqos_http_config(config))
+    }
+
+    pub fn with_proxy(self, proxy: ProxyConfig) -> Self {
+        self.config(|builder| builder.with_proxy(proxy))
+    }
+
+    pub fn with_swqos_proxy(self, swqos_type: SwqosType, proxy: ProxyConfig) -> Self {
+        self.config(|builder| builder.with_swqos_proxy(swqos_type, proxy))
+    }
+
+    pub fn with_confirmation(self, confirmation: ConfirmationConfig) -> Self {
+        self.config(|builder| builder.with_confirmation(confirmation))
+    }
+
+    #[cfg(feature = "jito")]
+    pub fn with_jito_bundle_simulation(self, enabled: bool) -> Self {
+        self.config(|builder| builder.with_jito_bundle_simulation(enabled))
+    }
+
+    pub fn with_fee_payer(mut self, fee_payer: Arc<dyn TradeSigner>) -> Self {
+        self.fee_payer = Some(fee_payer);
+        self
+    }
+
+    pub fn with_middleware_manager(mut self, middleware_manager: MiddlewareManager) -> Self {
+        self.middleware_manager = Some(middleware_manager);
+        self
+    }
+
+    pub fn with_metadata_uploader(mut self, config: MetadataUploaderConfig) -> Self {
+        self.metadata_uploader = Some(config);
+        self
+    }
+
+    /// Track SOL/USD, see `pricing`
+    pub fn with_sol_usd_price(mut self, config: SolUsdPriceConfig) -> Self {
+        self.sol_usd_price = Some(config);
+        self
+    }
+
+    /// Fill trades virtually instead of sending them, see `trading::paper`
+    pub fn with_paper_trading(mut self, config: PaperTradingConfig) -> Self {
+        self.paper_trading = Some(config);
+        self
+    }
+
+    /// Send trade notifications, see `notify`
+    pub fn with_notifications(mut self, notifications: Notifications) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Check the payer balances before submitting trades, disabled by default
+    pub fn with_balance_check(mut self, enabled: bool) -> Self {
+        self.balance_check = enabled;
+        self
+    }
+
+    /// Trade graduated PumpFun tokens on their PumpSwap pool, disabled by default
+    pub fn with_pumpfun_migration_routing(mut self, enabled: bool) -> Self {
+        self.route_migrated_pumpfun = enabled;
+        self
+    }
+
+    /// Sell the whole balance in `sell_by_bps` when at most `dust_threshold` would remain
+    pub fn with_sell_dust_threshold(mut self, dust_threshold: u64) -> Self {
+        self.sell_dust_threshold = dust_threshold;
+        self
+    }
+
+    /// Validate the configuration and create the `SolanaTrade` client
+    pub async fn build(self) -> Result<SolanaTrade, anyhow::Error> {
+        let trade_config = self.config.build()?;
+        let mut client = SolanaTrade::new(self.payer, trade_config).await?;
+        if let Some(fee_payer) = self.fee_payer {
+            client = client.with_fee_payer(fee_payer);
+        }
+        if let Some(middleware_manager) = self.middleware_manager {
+            client = client.with_middleware_manager(middleware_manager);
+        }
+        if let Some(metadata_uploader) = self.metadata_uploader {
+            client = client.with_metadata_uploader(metadata_uploader)?;
+        }
+        if let Some(sol_usd_price) = self.sol_usd_price {
+            client = client.with_sol_usd_price(sol_usd_price)?;
+        }
+        if let Some(paper_trading) = self.paper_trading {
+            client = client.with_paper_trading(paper_trading);
+        }
+        if let Some(notifications) = self.notifications {
+            client = client.with_notifications(notifications);
+        }
+        Ok(client
+            .with_balance_check(self.balance_check)
+            .with_pumpfun_migration_routing(self.route_migrated_pumpfun)
+            .with_sell_dust_threshold(self.sell_dust_threshold))
+    }
+}
+
+#[cfg(all(test, feature = "jito", feature = "nextblock"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trade_config_builder_validation() {
+        let config = TradeConfigBuilder::new("https://api.mainnet-beta.solana.com")
+            .with_jito("", SwqosRegion::Frankfurt)
+            .build()
+            .unwrap();
+        assert_eq!(config.swqos_configs.len(), 1);
+        assert!(!config.jito_simulate_bundles);
+
+        let config = TradeConfigBuilder::new("https://api.mainnet-beta.solana.com")
+            .with_jito("", SwqosRegion::Frankfurt)
+            .with_jito_bundle_simulation(true)
+            .build()
+            .unwrap();
+        assert!(config.jito_simulate_bundles);
+
+        let config =
+            TradeConfigBuilder::new("https://api.mainnet-beta.solana.com").build().unwrap();
+        assert!(matches!(config.swqos_configs[0], SwqosConfig::Default(_)));
+
+        assert!(TradeConfigBuilder::new("").build().is_err());
+        assert!(TradeConfigBuilder::new("https://api.mainnet-beta.solana.com")
+            .with_nextblock("", SwqosRegion::Frankfurt)
+            .build()
+            .is_err());
+        assert!(TradeConfigBuilder::new("https://api.mainnet-beta.solana.com")
+            .with_jito("", SwqosRegion::Frankfurt)
+            .with_jito("", SwqosRegion::Frankfurt)
+            .build()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_proxy_is_an_error() {
+        use crate::common::TradeError;
+        use solana_sdk::{commitment_config::CommitmentConfig, signature::Keypair};
+
+        // Built by hand, so not validated by `TradeConfigBuilder::build`
+        let mut config = TradeConfig::new(
+            "https://api.mainnet-beta.solana.com".to_string(),
+            vec![],
+            PriorityFee::default(),
+            CommitmentConfig::confirmed(),
+            None,
+        );
+        config.proxy = Some(ProxyConfig::new("not a proxy url"));
+        let result = SolanaTrade::new(Arc::new(Keypair::new()), config).await;
+        assert!(matches!(result, Err(TradeError::InvalidParams(_))));
+    }
+}
@@ -114,7 +114,7 @@ pub async fn get_address_lookup_table(
     };
 
     for (i, addr) in address_lookup_table_account.addresses.iter().enumerate() {
-        println!("地址 {}: {}", i, addr);
+        tracing::debug!(index = i, address = %addr, "查找表地址");
     }
 
     Ok(address_lookup_table_account)
@@ -141,7 +141,7 @@ pub async fn send_transaction_with_lut(
 
     let signature = client.send_and_confirm_transaction(&tx).await?;
 
-    println!("交易已确认: {}", signature);
+    tracing::info!(%signature, "交易已确认");
     Ok(())
 }
 
@@ -160,12 +160,9 @@ pub async fn send_transaction_with_filtered_lut(
         .filter_map(|&index| lookup_table.addresses.get(index).copied())
         .collect();
 
-    println!(
-        "从查找表中选择了 {} 个地址用于交易",
-        filtered_addresses.len()
-    );
+    tracing::debug!(count = filtered_addresses.len(), "从查找表中选择地址用于交易");
     for (i, addr) in filtered_addresses.iter().enumerate() {
-        println!("使用地址 {}: {}", i, addr);
+        tracing::debug!(index = i, address = %addr, "使用地址");
     }
 
     let filtered_lookup_table = AddressLookupTableAccount {
@@ -186,7 +183,7 @@ pub async fn send_transaction_with_filtered_lut(
 
     let signature = client.send_and_confirm_transaction(&tx).await?;
 
-    println!("交易已确认: {}", signature);
+    tracing::info!(%signature, "交易已确认");
     Ok(())
 }
 
@@ -240,10 +237,11 @@ pub async fn send_transaction_with_addresses(
 
     // 检查是否有地址未找到
     if !missing_addresses.is_empty() {
-        println!("警告: {} 个地址未在查找表中找到", missing_addresses.len());
-        for (i, addr) in missing_addresses.iter().enumerate() {
-            println!("未找到的地址 {}: {}", i, addr);
-        }
+        tracing::warn!(
+            count = missing_addresses.len(),
+            addresses = ?missing_addresses,
+            "部分地址未在查找表中找到"
+        );
     }
 
     // 如果没有找到任何地址，返回错误
@@ -260,12 +258,9 @@ pub async fn send_transaction_with_addresses(
         .filter_map(|&index| lookup_table.addresses.get(index).copied())
         .collect();
 
-    println!(
-        "从查找表中选择了 {} 个地址用于交易",
-        filtered_addresses.len()
-    );
+    tracing::debug!(count = filtered_addresses.len(), "从查找表中选择地址用于交易");
     for (i, addr) in filtered_addresses.iter().enumerate() {
-        println!("使用地址 {}: {}", i, addr);
+        tracing::debug!(index = i, address = %addr, "使用地址");
     }
 
     let filtered_lookup_table = AddressLookupTableAccount {
@@ -286,7 +281,7 @@ pub async fn send_transaction_with_addresses(
 
     let signature = client.send_and_confirm_transaction(&tx).await?;
 
-    println!("交易已确认: {}", signature);
+    tracing::info!(%signature, "交易已确认");
     Ok(signature.to_string())
 }
 
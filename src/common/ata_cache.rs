@@ -0,0 +1,73 @@
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// 用户 ATA 创建策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AtaCreationMode {
+    /// 每次都添加幂等创建指令（默认）
+    #[default]
+    AlwaysIdempotent,
+    /// 已知存在的 ATA 不再添加创建指令，减少指令数量和计算单元
+    SkipKnownExisting,
+}
+
+/// AtaCache 单例，记录已知存在的 (owner, mint) ATA
+pub struct AtaCache {
+    mode: Mutex<AtaCreationMode>,
+    known: Mutex<HashSet<(Pubkey, Pubkey)>>,
+}
+
+static ATA_CACHE: OnceLock<Arc<AtaCache>> = OnceLock::new();
+
+impl AtaCache {
+    /// 获取 AtaCache 单例实例
+    pub fn get_instance() -> Arc<AtaCache> {
+        ATA_CACHE
+            .get_or_init(|| {
+                Arc::new(AtaCache {
+                    mode: Mutex::new(AtaCreationMode::default()),
+                    known: Mutex::new(HashSet::new()),
+                })
+            })
+            .clone()
+    }
+
+    /// 设置 ATA 创建策略
+    pub fn set_mode(&self, mode: AtaCreationMode) {
+        *self.mode.lock().unwrap() = mode;
+    }
+
+    /// 获取 ATA 创建策略
+    pub fn get_mode(&self) -> AtaCreationMode {
+        *self.mode.lock().unwrap()
+    }
+
+    /// 标记 ATA 已存在
+    pub fn mark_existing(&self, owner: &Pubkey, mint: &Pubkey) {
+        self.known.lock().unwrap().insert((*owner, *mint));
+    }
+
+    /// 移除标记（例如 ATA 被关闭后）
+    pub fn remove(&self, owner: &Pubkey, mint: &Pubkey) {
+        self.known.lock().unwrap().remove(&(*owner, *mint));
+    }
+
+    /// 检查 ATA 是否已知存在
+    pub fn is_known(&self, owner: &Pubkey, mint: &Pubkey) -> bool {
+        self.known.lock().unwrap().contains(&(*owner, *mint))
+    }
+
+    /// 当前策略下是否需要添加创建指令
+    pub fn needs_create(&self, owner: &Pubkey, mint: &Pubkey) -> bool {
+        match self.get_mode() {
+            AtaCreationMode::AlwaysIdempotent => true,
+            AtaCreationMode::SkipKnownExisting => !self.is_known(owner, mint),
+        }
+    }
+
+    /// 清空缓存
+    pub fn clear(&self) {
+        self.known.lock().unwrap().clear();
+    }
+}
@@ -24,11 +24,16 @@
 //! - `get_market_cap_sol`: Calculates the current market cap in SOL
 //! - `get_final_market_cap_sol`: Calculates the final market cap in SOL after all tokens are sold
 //! - `get_buy_out_price`: Calculates the price to buy out all remaining tokens
+//! - `get_spot_price_sol`: Current spot price in SOL per token
+//! - `get_market_cap_in_sol`: Current market cap in SOL
+//! - `get_market_cap_usd`: Current market cap in USD for a given SOL/USD price
+//! - `get_graduation_progress`: Percent progress toward graduation
 
 use serde::{Serialize, Deserialize};
 use solana_sdk::pubkey::Pubkey;
 
-use crate::{constants::pumpfun::global_constants::{INITIAL_REAL_TOKEN_RESERVES, INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES, TOKEN_TOTAL_SUPPLY}, trading::pumpfun::common::{get_bonding_curve_pda, get_creator_vault_pda}};
+use crate::constants::decimals::{DEFAULT_TOKEN_DECIMALS, SOL_DECIMALS};
+use crate::{constants::pumpfun::global_constants::{INITIAL_REAL_TOKEN_RESERVES, INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES, LAMPORTS_PER_SOL, TOKEN_TOTAL_SUPPLY}, trading::pumpfun::common::{get_bonding_curve_pda, get_creator_vault_pda}};
 use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunTradeEvent;
 
 /// Represents the global configuration account for token pricing and fees
@@ -83,6 +88,23 @@ impl BondingCurveAccount {
         }
     }
 
+    pub fn from_bonding_curve(
+        mint: &Pubkey,
+        bonding_curve: &crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::types::BondingCurve,
+    ) -> Self {
+        Self {
+            discriminator: 0,
+            account: get_bonding_curve_pda(mint).unwrap(),
+            virtual_token_reserves: bonding_curve.virtual_token_reserves,
+            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+            real_token_reserves: bonding_curve.real_token_reserves,
+            real_sol_reserves: bonding_curve.real_sol_reserves,
+            token_total_supply: bonding_curve.token_total_supply,
+            complete: bonding_curve.complete,
+            creator: bonding_curve.creator,
+        }
+    }
+
     pub fn get_creator_vault_pda(&self) -> Pubkey {
         get_creator_vault_pda(&self.creator).unwrap()
     }
@@ -213,4 +235,99 @@ impl BondingCurveAccount {
         let token_price = v_sol / v_tokens;
         token_price
     }
+
+    /// Current spot price in SOL per whole token, derived from the virtual reserves
+    pub fn get_spot_price_sol(&self) -> f64 {
+        if self.virtual_token_reserves == 0 {
+            return 0.0;
+        }
+        let v_sol = self.virtual_sol_reserves as f64 / 10f64.powi(SOL_DECIMALS as i32);
+        let v_tokens =
+            self.virtual_token_reserves as f64 / 10f64.powi(DEFAULT_TOKEN_DECIMALS as i32);
+        v_sol / v_tokens
+    }
+
+    /// Current market cap in SOL (`get_market_cap_sol` returns lamports)
+    pub fn get_market_cap_in_sol(&self) -> f64 {
+        self.get_market_cap_sol() as f64 / LAMPORTS_PER_SOL as f64
+    }
+
+    /// Current market cap in USD at `sol_usd` dollars per SOL, see `pricing::SolUsdPrice`
+    pub fn get_market_cap_usd(&self, sol_usd: f64) -> f64 {
+        self.get_market_cap_in_sol() * sol_usd
+    }
+
+    /// Progress toward graduation in percent (0.0 - 100.0)
+    ///
+    /// The curve graduates once all real token reserves have been bought.
+    pub fn get_graduation_progress(&self) -> f64 {
+        if self.complete || self.real_token_reserves == 0 {
+            return 100.0;
+        }
+        let sold = INITIAL_REAL_TOKEN_RESERVES.saturating_sub(self.real_token_reserves);
+        (sold as f64 / INITIAL_REAL_TOKEN_RESERVES as f64 * 100.0).clamp(0.0, 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn initial_curve() -> BondingCurveAccount {
+        BondingCurveAccount {
+            virtual_token_reserves: INITIAL_VIRTUAL_TOKEN_RESERVES,
+            virtual_sol_reserves: INITIAL_VIRTUAL_SOL_RESERVES,
+            real_token_reserves: INITIAL_REAL_TOKEN_RESERVES,
+            token_total_supply: TOKEN_TOTAL_SUPPLY,
+            ..Default::default()
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() <= expected.abs() * 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_spot_price_and_market_cap() {
+        let curve = initial_curve();
+        // 30 SOL of virtual reserves against 1.073 billion virtual tokens
+        assert_close(curve.get_spot_price_sol(), 30.0 / 1_073_000_000.0);
+        assert_eq!(curve.get_market_cap_sol(), 27_958_993_476);
+        assert_close(curve.get_market_cap_in_sol(), 27.958993476);
+        assert_close(curve.get_market_cap_usd(200.0), 27.958993476 * 200.0);
+
+        let curve = BondingCurveAccount {
+            virtual_token_reserves: 500_000_000_000_000,
+            virtual_sol_reserves: 60_000_000_000,
+            ..initial_curve()
+        };
+        assert_close(curve.get_spot_price_sol(), 60.0 / 500_000_000.0);
+        assert_eq!(curve.get_market_cap_sol(), 120_000_000_000);
+        assert_close(curve.get_market_cap_usd(150.0), 120.0 * 150.0);
+    }
+
+    #[test]
+    fn test_graduation_progress() {
+        let curve = initial_curve();
+        assert_eq!(curve.get_graduation_progress(), 0.0);
+
+        let half_sold =
+            BondingCurveAccount { real_token_reserves: INITIAL_REAL_TOKEN_RESERVES / 2, ..curve };
+        assert_close(half_sold.get_graduation_progress(), 50.0);
+
+        let sold_out = BondingCurveAccount { real_token_reserves: 0, ..initial_curve() };
+        assert_eq!(sold_out.get_graduation_progress(), 100.0);
+        let complete = BondingCurveAccount { complete: true, ..initial_curve() };
+        assert_eq!(complete.get_graduation_progress(), 100.0);
+    }
+
+    #[test]
+    fn test_zero_reserves() {
+        let curve = BondingCurveAccount::default();
+        assert_eq!(curve.get_spot_price_sol(), 0.0);
+        assert_eq!(curve.get_market_cap_sol(), 0);
+        assert_eq!(curve.get_market_cap_in_sol(), 0.0);
+        assert_eq!(curve.get_market_cap_usd(200.0), 0.0);
+        assert_eq!(curve.get_graduation_progress(), 100.0);
+    }
 }
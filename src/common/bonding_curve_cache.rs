@@ -0,0 +1,98 @@
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::common::bonding_curve::BondingCurveAccount;
+use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::{
+    PumpFunMigrateEvent, PumpFunTradeEvent,
+};
+
+/// 默认缓存有效期
+pub const DEFAULT_BONDING_CURVE_CACHE_TTL: Duration = Duration::from_secs(5);
+
+struct CachedBondingCurve {
+    bonding_curve: Arc<BondingCurveAccount>,
+    updated_at: Instant,
+}
+
+/// BondingCurveCache 单例，按 mint 缓存 PumpFun bonding curve 账户
+///
+/// 可通过交易事件流持续刷新，迁移（毕业）事件会使对应条目失效，
+/// 从而在热路径上避免每次买入都请求 RPC。
+pub struct BondingCurveCache {
+    entries: Mutex<HashMap<Pubkey, CachedBondingCurve>>,
+    ttl: Mutex<Duration>,
+}
+
+static BONDING_CURVE_CACHE: OnceLock<Arc<BondingCurveCache>> = OnceLock::new();
+
+impl BondingCurveCache {
+    /// 获取 BondingCurveCache 单例实例
+    pub fn get_instance() -> Arc<BondingCurveCache> {
+        BONDING_CURVE_CACHE
+            .get_or_init(|| {
+                Arc::new(BondingCurveCache {
+                    entries: Mutex::new(HashMap::new()),
+                    ttl: Mutex::new(DEFAULT_BONDING_CURVE_CACHE_TTL),
+                })
+            })
+            .clone()
+    }
+
+    /// 设置缓存有效期
+    pub fn set_ttl(&self, ttl: Duration) {
+        *self.ttl.lock().unwrap() = ttl;
+    }
+
+    /// 获取未过期且未完成的 bonding curve
+    pub fn get(&self, mint: &Pubkey) -> Option<Arc<BondingCurveAccount>> {
+        let ttl = *self.ttl.lock().unwrap();
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(mint)
+            .filter(|entry| entry.updated_at.elapsed() <= ttl && !entry.bonding_curve.complete)
+            .map(|entry| entry.bonding_curve.clone())
+    }
+
+    /// 写入或覆盖 bonding curve，已完成的曲线会直接移除
+    pub fn insert(&self, mint: Pubkey, bonding_curve: Arc<BondingCurveAccount>) {
+        let mut entries = self.entries.lock().unwrap();
+        if bonding_curve.complete {
+            entries.remove(&mint);
+            return;
+        }
+        entries.insert(mint, CachedBondingCurve { bonding_curve, updated_at: Instant::now() });
+    }
+
+    /// 使用解析出的交易事件刷新缓存
+    pub fn update_from_trade(&self, event: &PumpFunTradeEvent) {
+        if event.real_token_reserves == 0 {
+            // 代币已全部售出，曲线即将毕业
+            self.invalidate(&event.mint);
+            return;
+        }
+        self.insert(event.mint, Arc::new(BondingCurveAccount::from_trade(event)));
+    }
+
+    /// 迁移事件到达时使缓存失效
+    pub fn update_from_migrate(&self, event: &PumpFunMigrateEvent) {
+        self.invalidate(&event.mint);
+    }
+
+    /// 移除指定 mint 的缓存
+    pub fn invalidate(&self, mint: &Pubkey) -> bool {
+        self.entries.lock().unwrap().remove(mint).is_some()
+    }
+
+    /// 清理所有过期条目
+    pub fn purge_expired(&self) {
+        let ttl = *self.ttl.lock().unwrap();
+        self.entries.lock().unwrap().retain(|_, entry| entry.updated_at.elapsed() <= ttl);
+    }
+
+    /// 清空缓存
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
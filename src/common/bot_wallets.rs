@@ -0,0 +1,142 @@
+use solana_sdk::pubkey::Pubkey;
+use solana_streamer_sdk::streaming::event_parser::{
+    protocols::{bonk::BonkTradeEvent, pumpfun::PumpFunTradeEvent},
+    UnifiedEvent,
+};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// 交易分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TradeCategory {
+    /// 创建代币的 dev 在同一笔交易中的买卖
+    Dev,
+    /// 机器人钱包的交易
+    Bot,
+    /// 其他用户的交易
+    User,
+}
+
+/// BotWallets 单例，记录用于交易分类的机器人钱包集合，可在运行时更新
+///
+/// 解析器只接受单个 `bot_wallet`，订阅时传入 `None`，由 `classify` 按集合重新设置事件的
+/// `is_bot` 标记。`subscribe_tokens` 和 `subscribe_events_stream` 会自动分类，直接使用
+/// streamer SDK 订阅时用 `classify_callback` 包装回调。
+pub struct BotWallets {
+    wallets: Mutex<HashSet<Pubkey>>,
+}
+
+static BOT_WALLETS: OnceLock<Arc<BotWallets>> = OnceLock::new();
+
+impl BotWallets {
+    fn new() -> Self {
+        Self { wallets: Mutex::new(HashSet::new()) }
+    }
+
+    /// 获取 BotWallets 单例实例
+    pub fn get_instance() -> Arc<BotWallets> {
+        BOT_WALLETS.get_or_init(|| Arc::new(BotWallets::new())).clone()
+    }
+
+    /// 添加机器人钱包
+    pub fn insert(&self, wallet: Pubkey) {
+        self.wallets.lock().unwrap().insert(wallet);
+    }
+
+    /// 移除机器人钱包
+    pub fn remove(&self, wallet: &Pubkey) {
+        self.wallets.lock().unwrap().remove(wallet);
+    }
+
+    /// 用给定集合替换全部机器人钱包
+    pub fn set(&self, wallets: impl IntoIterator<Item = Pubkey>) {
+        *self.wallets.lock().unwrap() = wallets.into_iter().collect();
+    }
+
+    /// 是否为机器人钱包
+    pub fn contains(&self, wallet: &Pubkey) -> bool {
+        self.wallets.lock().unwrap().contains(wallet)
+    }
+
+    /// 当前的机器人钱包
+    pub fn wallets(&self) -> Vec<Pubkey> {
+        self.wallets.lock().unwrap().iter().copied().collect()
+    }
+
+    /// 清空机器人钱包
+    pub fn clear(&self) {
+        self.wallets.lock().unwrap().clear();
+    }
+
+    /// PumpFun 交易的分类，按交易用户判断
+    pub fn classify_pumpfun(&self, event: &PumpFunTradeEvent) -> TradeCategory {
+        self.category(event.is_dev_create_token_trade, &event.user)
+    }
+
+    /// Bonk 交易的分类，按付款账户判断
+    pub fn classify_bonk(&self, event: &BonkTradeEvent) -> TradeCategory {
+        self.category(event.is_dev_create_token_trade, &event.payer)
+    }
+
+    fn category(&self, is_dev: bool, wallet: &Pubkey) -> TradeCategory {
+        if is_dev {
+            TradeCategory::Dev
+        } else if self.contains(wallet) {
+            TradeCategory::Bot
+        } else {
+            TradeCategory::User
+        }
+    }
+
+    /// 按当前集合设置交易事件的 `is_bot` 标记，返回其分类；非交易事件返回 `None`
+    pub fn classify(&self, event: &mut dyn UnifiedEvent) -> Option<TradeCategory> {
+        let any = event.as_any_mut();
+        if let Some(trade) = any.downcast_mut::<PumpFunTradeEvent>() {
+            let category = self.classify_pumpfun(trade);
+            trade.is_bot = category == TradeCategory::Bot;
+            return Some(category);
+        }
+        if let Some(trade) = any.downcast_mut::<BonkTradeEvent>() {
+            let category = self.classify_bonk(trade);
+            trade.is_bot = category == TradeCategory::Bot;
+            return Some(category);
+        }
+        None
+    }
+
+    /// 包装订阅回调，事件在传给 `callback` 前先经过 `classify`
+    pub fn classify_callback<F>(callback: F) -> impl Fn(Box<dyn UnifiedEvent>) + Send + Sync
+    where
+        F: Fn(Box<dyn UnifiedEvent>) + Send + Sync + 'static,
+    {
+        move |mut event: Box<dyn UnifiedEvent>| {
+            BotWallets::get_instance().classify(event.as_mut());
+            callback(event)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_trades() {
+        let wallets = BotWallets::new();
+        let bot = Pubkey::new_unique();
+        let mut trade = PumpFunTradeEvent { user: bot, ..Default::default() };
+        assert_eq!(wallets.classify(&mut trade), Some(TradeCategory::User));
+
+        wallets.set([bot, Pubkey::new_unique()]);
+        assert_eq!(wallets.classify(&mut trade), Some(TradeCategory::Bot));
+        assert!(trade.is_bot);
+        let mut bonk =
+            BonkTradeEvent { payer: bot, is_dev_create_token_trade: true, ..Default::default() };
+        assert_eq!(wallets.classify(&mut bonk), Some(TradeCategory::Dev));
+        assert!(!bonk.is_bot);
+
+        wallets.remove(&bot);
+        assert_eq!(wallets.classify(&mut trade), Some(TradeCategory::User));
+        assert!(!trade.is_bot);
+    }
+}
@@ -0,0 +1,466 @@
+//! Load `TradeConfig` from a TOML file or environment variables
+//!
+//! Keeps RPC URLs and swqos auth tokens out of Rust source. String values in the TOML file
+//! may reference environment variables as `${NAME}`, so the file itself can be committed
+//! while the secrets stay in the environment:
+//!
+//! ```toml
+//! rpc_url = "https://mainnet.helius-rpc.com/?api-key=${HELIUS_API_KEY}"
+//! commitment = "confirmed"
+//! fallback_rpc_urls = ["https://api.mainnet-beta.solana.com"]
+//! core_affinity = { cores = [2, 3] }
+//! proxy = { url = "socks5h://${PROXY_AUTH}@10.0.0.2:1080", no_proxy = "localhost" }
+//!
+//! [priority_fee]
+//! buy_tip_fee = 0.001
+//! sell_tip_fee = 0.0001
+//!
+//! [[swqos]]
+//! provider = "jito"
+//! region = "frankfurt"
+//!
+//! [[swqos]]
+//! provider = "nextblock"
+//! region = "frankfurt"
+//! auth_token = "${NEXTBLOCK_TOKEN}"
+//! ```
+//!
+//! Jito and NextBlock can also be reached over gRPC with the `jito_grpc` and
+//! `nextblock_grpc` providers.
+//!
+//! Everything is validated through `TradeConfigBuilder`.
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{path::Path, str::FromStr};
+
+use crate::{
+    common::{
+        proxy::ProxyConfig, rpc_pool::RpcPoolConfig, runtime::CoreAffinity, PriorityFee,
+        TradeConfig,
+    },
+    swqos::{SwqosConfig, SwqosRegion},
+    TradeConfigBuilder,
+};
+
+/// Prefix of the environment variables read by `TradeConfig::from_env`
+pub const ENV_PREFIX: &str = "SOL_TRADE_";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    rpc_url: String,
+    #[serde(default)]
+    commitment: Option<String>,
+    #[serde(default)]
+    lookup_table_key: Option<String>,
+    /// Endpoints tried when `rpc_url` fails
+    #[serde(default)]
+    fallback_rpc_urls: Vec<String>,
+    /// Race latency critical reads across all RPC endpoints
+    #[serde(default)]
+    race_rpc_reads: bool,
+    #[serde(default)]
+    priority_fee: PriorityFeeOverrides,
+    #[serde(default)]
+    swqos: Vec<SwqosEntry>,
+    /// `"disabled"`, `"all_cores"` or `{ cores = [2, 3] }`
+    #[serde(default)]
+    core_affinity: CoreAffinity,
+    /// Proxy all outbound HTTP requests go through
+    #[serde(default)]
+    proxy: Option<ProxyConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SwqosEntry {
+    provider: String,
+    #[serde(default)]
+    region: Option<String>,
+    /// Regions in priority order, for `jito_failover`
+    #[serde(default)]
+    regions: Vec<String>,
+    #[serde(default)]
+    auth_token: Option<String>,
+    /// Endpoint for the `rpc` provider, the top level RPC URL when unset
+    #[serde(default)]
+    url: Option<String>,
+}
+
+/// Priority fee fields to override on top of `PriorityFee::default()`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PriorityFeeOverrides {
+    tip_unit_limit: Option<u32>,
+    tip_unit_price: Option<u64>,
+    rpc_unit_limit: Option<u32>,
+    rpc_unit_price: Option<u64>,
+    buy_tip_fee: Option<f64>,
+    buy_tip_fees: Option<Vec<f64>>,
+    smart_buy_tip_fee: Option<f64>,
+    sell_tip_fee: Option<f64>,
+}
+
+impl PriorityFeeOverrides {
+    fn apply(self) -> PriorityFee {
+        let mut fee = PriorityFee::default();
+        if let Some(value) = self.tip_unit_limit {
+            fee.tip_unit_limit = value;
+        }
+        if let Some(value) = self.tip_unit_price {
+            fee.tip_unit_price = value;
+        }
+        if let Some(value) = self.rpc_unit_limit {
+            fee.rpc_unit_limit = value;
+        }
+        if let Some(value) = self.rpc_unit_price {
+            fee.rpc_unit_price = value;
+        }
+        if let Some(value) = self.buy_tip_fee {
+            fee.buy_tip_fee = value;
+        }
+        if let Some(value) = self.buy_tip_fees {
+            fee.buy_tip_fees = value;
+        }
+        if let Some(value) = self.smart_buy_tip_fee {
+            fee.smart_buy_tip_fee = value;
+        }
+        if let Some(value) = self.sell_tip_fee {
+            fee.sell_tip_fee = value;
+        }
+        fee
+    }
+}
+
+impl TradeConfig {
+    /// Load the configuration from a TOML file
+    pub fn from_toml<P: AsRef<Path>>(path: P) -> Result<TradeConfig, anyhow::Error> {
+        let content = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            anyhow!("Failed to read config file {}: {}", path.as_ref().display(), e)
+        })?;
+        Self::from_toml_str(&content)
+    }
+
+    /// Parse the configuration from TOML text
+    pub fn from_toml_str(content: &str) -> Result<TradeConfig, anyhow::Error> {
+        let file: FileConfig =
+            toml::from_str(content).map_err(|e| anyhow!("Invalid config file: {}", e))?;
+        let rpc_url = expand_env(&file.rpc_url)?;
+
+        let mut builder =
+            TradeConfigBuilder::new(rpc_url.clone()).with_priority_fee(file.priority_fee.apply());
+        if let Some(commitment) = &file.commitment {
+            builder = builder.with_commitment(parse_commitment(commitment)?);
+        }
+        if let Some(lookup_table_key) = &file.lookup_table_key {
+            builder = builder.with_lookup_table(parse_pubkey(&expand_env(lookup_table_key)?)?);
+        }
+        let fallback_urls =
+            file.fallback_rpc_urls.iter().map(|url| expand_env(url)).collect::<Result<_, _>>()?;
+        builder = with_rpc_pool(builder, fallback_urls, file.race_rpc_reads);
+        builder = builder.with_core_affinity(file.core_affinity);
+        if let Some(proxy) = file.proxy {
+            let no_proxy = proxy.no_proxy.as_deref().map(expand_env).transpose()?;
+            builder = builder.with_proxy(ProxyConfig { url: expand_env(&proxy.url)?, no_proxy });
+        }
+        for entry in file.swqos {
+            let auth_token = entry.auth_token.as_deref().map(expand_env).transpose()?;
+            let url = entry.url.as_deref().map(expand_env).transpose()?;
+            let regions = match entry.region {
+                Some(region) => vec![region],
+                None => entry.regions,
+            };
+            builder = builder.with_swqos(build_swqos_config(
+                &entry.provider,
+                &regions,
+                auth_token.unwrap_or_default(),
+                url.unwrap_or_else(|| rpc_url.clone()),
+            )?);
+        }
+        builder.build()
+    }
+
+    /// Load the configuration from `SOL_TRADE_*` environment variables
+    ///
+    /// * `SOL_TRADE_RPC_URL` - required
+    /// * `SOL_TRADE_COMMITMENT` - `processed`, `confirmed` (default) or `finalized`
+    /// * `SOL_TRADE_LOOKUP_TABLE_KEY`
+    /// * `SOL_TRADE_FALLBACK_RPC_URLS` - comma separated endpoints tried when the RPC URL fails
+    /// * `SOL_TRADE_RACE_RPC_READS` - `true` to race latency critical reads across endpoints
+    /// * `SOL_TRADE_CORES` - pin the submission tasks: `all` or comma separated core ids
+    /// * `SOL_TRADE_PROXY` - `http://`, `https://` or `socks5(h)://` proxy for all outbound
+    ///   HTTP requests, `SOL_TRADE_NO_PROXY` - comma separated hosts reached directly
+    /// * `SOL_TRADE_SWQOS` - comma separated `provider:region` list, e.g.
+    ///   `jito:frankfurt,nextblock:frankfurt,rpc`; `jito_failover` takes regions separated
+    ///   by `|`, e.g. `jito_failover:frankfurt|amsterdam`
+    /// * `SOL_TRADE_<PROVIDER>_AUTH_TOKEN` - auth token per provider, e.g.
+    ///   `SOL_TRADE_NEXTBLOCK_AUTH_TOKEN`; `jito_failover` and `jito_grpc` use
+    ///   `SOL_TRADE_JITO_AUTH_TOKEN`, `nextblock_grpc` uses `SOL_TRADE_NEXTBLOCK_AUTH_TOKEN`
+    /// * `SOL_TRADE_TIP_UNIT_LIMIT`, `SOL_TRADE_TIP_UNIT_PRICE`, `SOL_TRADE_RPC_UNIT_LIMIT`,
+    ///   `SOL_TRADE_RPC_UNIT_PRICE`, `SOL_TRADE_BUY_TIP_FEE`, `SOL_TRADE_BUY_TIP_FEES`
+    ///   (comma separated), `SOL_TRADE_SMART_BUY_TIP_FEE`, `SOL_TRADE_SELL_TIP_FEE`
+    pub fn from_env() -> Result<TradeConfig, anyhow::Error> {
+        let rpc_url = env_var("RPC_URL")?
+            .ok_or_else(|| anyhow!("Environment variable {}RPC_URL is not set", ENV_PREFIX))?;
+
+        let priority_fee = PriorityFeeOverrides {
+            tip_unit_limit: env_parse("TIP_UNIT_LIMIT")?,
+            tip_unit_price: env_parse("TIP_UNIT_PRICE")?,
+            rpc_unit_limit: env_parse("RPC_UNIT_LIMIT")?,
+            rpc_unit_price: env_parse("RPC_UNIT_PRICE")?,
+            buy_tip_fee: env_parse("BUY_TIP_FEE")?,
+            buy_tip_fees: env_var("BUY_TIP_FEES")?
+                .map(|fees| {
+                    fees.split(',')
+                        .map(|fee| fee.trim().parse::<f64>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| anyhow!("Invalid {}BUY_TIP_FEES: {}", ENV_PREFIX, e))
+                })
+                .transpose()?,
+            smart_buy_tip_fee: env_parse("SMART_BUY_TIP_FEE")?,
+            sell_tip_fee: env_parse("SELL_TIP_FEE")?,
+        };
+
+        let mut builder =
+            TradeConfigBuilder::new(rpc_url.clone()).with_priority_fee(priority_fee.apply());
+        if let Some(commitment) = env_var("COMMITMENT")? {
+            builder = builder.with_commitment(parse_commitment(&commitment)?);
+        }
+        if let Some(lookup_table_key) = env_var("LOOKUP_TABLE_KEY")? {
+            builder = builder.with_lookup_table(parse_pubkey(&lookup_table_key)?);
+        }
+        let fallback_urls = env_var("FALLBACK_RPC_URLS")?
+            .map(|urls| urls.split(',').map(|url| url.trim().to_string()).collect())
+            .unwrap_or_default();
+        let race_reads = env_parse("RACE_RPC_READS")?.unwrap_or(false);
+        builder = with_rpc_pool(builder, fallback_urls, race_reads);
+        if let Some(cores) = env_var("CORES")? {
+            builder = builder.with_core_affinity(parse_core_affinity(&cores)?);
+        }
+        if let Some(url) = env_var("PROXY")? {
+            builder = builder.with_proxy(ProxyConfig { url, no_proxy: env_var("NO_PROXY")? });
+        }
+        for entry in env_var("SWQOS")?.unwrap_or_default().split(',') {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let (provider, regions) = match entry.split_once(':') {
+                Some((provider, regions)) => {
+                    (provider, regions.split('|').map(str::to_string).collect::<Vec<_>>())
+                }
+                None => (entry, vec![]),
+            };
+            let token_provider = normalize_provider(provider);
+            let token_provider = match token_provider.as_str() {
+                "jitofailover" | "jitogrpc" => "jito".to_string(),
+                "nextblockgrpc" => "nextblock".to_string(),
+                _ => token_provider,
+            };
+            let auth_token =
+                env_var(&format!("{}_AUTH_TOKEN", token_provider.to_ascii_uppercase()))?;
+            builder = builder.with_swqos(build_swqos_config(
+                provider,
+                &regions,
+                auth_token.unwrap_or_default(),
+                rpc_url.clone(),
+            )?);
+        }
+        builder.build()
+    }
+}
+
+fn with_rpc_pool(
+    builder: TradeConfigBuilder,
+    fallback_urls: Vec<String>,
+    race_reads: bool,
+) -> TradeConfigBuilder {
+    if fallback_urls.is_empty() && !race_reads {
+        return builder;
+    }
+    builder.with_rpc_pool(RpcPoolConfig { fallback_urls, race_reads, ..Default::default() })
+}
+
+fn normalize_provider(provider: &str) -> String {
+    provider.trim().to_ascii_lowercase().replace(['_', '-'], "")
+}
+
+#[cfg_attr(
+    not(any(
+        feature = "jito",
+        feature = "nextblock",
+        feature = "bloxroute",
+        feature = "temporal",
+        feature = "zeroslot",
+        feature = "node1",
+        feature = "flashblock",
+        feature = "blockrazor"
+    )),
+    allow(unused_variables)
+)]
+fn build_swqos_config(
+    provider: &str,
+    regions: &[String],
+    auth_token: String,
+    rpc_url: String,
+) -> Result<SwqosConfig, anyhow::Error> {
+    let regions = regions
+        .iter()
+        .map(|region| SwqosRegion::from_str(region.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let region = || -> Result<SwqosRegion, anyhow::Error> {
+        match regions.as_slice() {
+            [] => Ok(SwqosRegion::Default),
+            [region] => Ok(region.clone()),
+            _ => Err(anyhow!("Swqos provider {} takes a single region", provider)),
+        }
+    };
+    let swqos_config = match normalize_provider(provider).as_str() {
+        "rpc" | "default" => SwqosConfig::Default(rpc_url),
+        #[cfg(feature = "jito")]
+        "jito" => SwqosConfig::Jito(auth_token, region()?),
+        #[cfg(feature = "jito")]
+        "jitofailover" => SwqosConfig::JitoFailover(auth_token, regions.clone()),
+        #[cfg(feature = "nextblock")]
+        "nextblock" => SwqosConfig::NextBlock(auth_token, region()?),
+        #[cfg(feature = "bloxroute")]
+        "bloxroute" => SwqosConfig::Bloxroute(auth_token, region()?),
+        #[cfg(feature = "temporal")]
+        "temporal" => SwqosConfig::Temporal(auth_token, region()?),
+        #[cfg(feature = "zeroslot")]
+        "zeroslot" => SwqosConfig::ZeroSlot(auth_token, region()?),
+        #[cfg(feature = "node1")]
+        "node1" => SwqosConfig::Node1(auth_token, region()?),
+        #[cfg(feature = "flashblock")]
+        "flashblock" => SwqosConfig::FlashBlock(auth_token, region()?),
+        #[cfg(feature = "blockrazor")]
+        "blockrazor" => SwqosConfig::BlockRazor(auth_token, region()?),
+        #[cfg(all(feature = "grpc", feature = "jito"))]
+        "jitogrpc" => SwqosConfig::JitoGrpc(auth_token, region()?),
+        #[cfg(all(feature = "grpc", feature = "nextblock"))]
+        "nextblockgrpc" => SwqosConfig::NextBlockGrpc(auth_token, region()?),
+        _ => {
+            return Err(anyhow!(
+                "Unknown swqos provider {} (or its cargo feature is not enabled)",
+                provider
+            ))
+        }
+    };
+    Ok(swqos_config)
+}
+
+fn parse_core_affinity(cores: &str) -> Result<CoreAffinity, anyhow::Error> {
+    match cores.trim() {
+        "" | "none" => Ok(CoreAffinity::Disabled),
+        "all" => Ok(CoreAffinity::AllCores),
+        cores => cores
+            .split(',')
+            .map(|core| core.trim().parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map(CoreAffinity::Cores)
+            .map_err(|e| anyhow!("Invalid {}CORES: {}", ENV_PREFIX, e)),
+    }
+}
+
+fn parse_commitment(commitment: &str) -> Result<CommitmentConfig, anyhow::Error> {
+    match commitment.trim().to_ascii_lowercase().as_str() {
+        "processed" => Ok(CommitmentConfig::processed()),
+        "confirmed" => Ok(CommitmentConfig::confirmed()),
+        "finalized" => Ok(CommitmentConfig::finalized()),
+        _ => Err(anyhow!("Unknown commitment: {}", commitment)),
+    }
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey, anyhow::Error> {
+    Pubkey::from_str(value.trim()).map_err(|e| anyhow!("Invalid pubkey {}: {}", value, e))
+}
+
+/// Read `SOL_TRADE_<name>`, treating an empty value as unset
+fn env_var(name: &str) -> Result<Option<String>, anyhow::Error> {
+    match std::env::var(format!("{}{}", ENV_PREFIX, name)) {
+        Ok(value) if value.trim().is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(e) => Err(anyhow!("Invalid {}{}: {}", ENV_PREFIX, name, e)),
+    }
+}
+
+fn env_parse<T: FromStr>(name: &str) -> Result<Option<T>, anyhow::Error>
+where
+    T::Err: std::fmt::Display,
+{
+    env_var(name)?
+        .map(|value| {
+            value.trim().parse::<T>().map_err(|e| anyhow!("Invalid {}{}: {}", ENV_PREFIX, name, e))
+        })
+        .transpose()
+}
+
+/// Replace `${NAME}` with the value of the environment variable `NAME`
+fn expand_env(value: &str) -> Result<String, anyhow::Error> {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| anyhow!("Unterminated ${{...}} in config value: {}", value))?;
+        let name = &rest[start + 2..start + end];
+        let var =
+            std::env::var(name).map_err(|_| anyhow!("Environment variable {} is not set", name))?;
+        out.push_str(&rest[..start]);
+        out.push_str(&var);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(all(test, feature = "jito", feature = "nextblock"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml_str() {
+        std::env::set_var("SOL_TRADE_TEST_NEXTBLOCK_TOKEN", "secret");
+        let config = TradeConfig::from_toml_str(
+            r#"
+            rpc_url = "https://api.mainnet-beta.solana.com"
+            commitment = "processed"
+            fallback_rpc_urls = ["https://rpc.example.com"]
+            core_affinity = { cores = [2, 3] }
+            proxy = { url = "socks5h://${SOL_TRADE_TEST_NEXTBLOCK_TOKEN}@127.0.0.1:1080" }
+
+            [priority_fee]
+            buy_tip_fee = 0.002
+
+            [[swqos]]
+            provider = "jito"
+            region = "frankfurt"
+
+            [[swqos]]
+            provider = "nextblock"
+            region = "new_york"
+            auth_token = "${SOL_TRADE_TEST_NEXTBLOCK_TOKEN}"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.commitment, CommitmentConfig::processed());
+        assert_eq!(
+            config.rpc_pool.map(|pool| pool.fallback_urls),
+            Some(vec!["https://rpc.example.com".to_string()])
+        );
+        assert_eq!(config.core_affinity, CoreAffinity::Cores(vec![2, 3]));
+        assert_eq!(config.proxy, Some(ProxyConfig::new("socks5h://secret@127.0.0.1:1080")));
+        assert_eq!(config.priority_fee.buy_tip_fee, 0.002);
+        assert_eq!(config.priority_fee.sell_tip_fee, PriorityFee::default().sell_tip_fee);
+        assert_eq!(
+            config.swqos_configs,
+            vec![
+                SwqosConfig::Jito(String::new(), SwqosRegion::Frankfurt),
+                SwqosConfig::NextBlock("secret".to_string(), SwqosRegion::NewYork),
+            ]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_sdk::{instruction::InstructionError, pubkey::Pubkey, transaction::TransactionError};
+use thiserror::Error;
+
+use crate::trading::factory::DexType;
+
+/// Error returned by the trading API
+///
+/// Internal code still uses `anyhow`; errors are converted at the `SolanaTrade` boundary.
+/// Layers that know what went wrong raise a `TradeError` inside the `anyhow::Error`, which is
+/// recovered by downcasting, and RPC / transaction errors are classified from their types.
+#[derive(Debug, Error)]
+pub enum TradeError {
+    /// RPC request failed (network, node error, rate limit)
+    #[error("RPC error: {0}")]
+    RpcError(String),
+    /// The program rejected the trade because the price moved past the slippage limit
+    #[error("Slippage exceeded: {0}")]
+    SlippageExceeded(String),
+    /// The PumpFun bonding curve is complete and the token can no longer be traded on it
+    ///
+    /// `migrated_pool` is the PumpSwap pool the token now trades on, when it was found.
+    #[error(
+        "Bonding curve complete{}{}",
+        .mint.map(|mint| format!(" for {}", mint)).unwrap_or_default(),
+        .migrated_pool.map(|pool| format!(", migrated to PumpSwap pool {}", pool)).unwrap_or_default()
+    )]
+    CurveComplete { mint: Option<Pubkey>, migrated_pool: Option<Pubkey> },
+    #[error("Pool not found: {0}")]
+    PoolNotFound(String),
+    /// A swqos service refused or failed to accept the transaction
+    #[error("{provider} rejected the transaction: {message}")]
+    SwqosRejected { provider: String, message: String },
+    /// Amounts in lamports for SOL, base units for tokens
+    #[error("Insufficient balance: needed {needed}, available {available}")]
+    InsufficientBalance { needed: u64, available: u64 },
+    #[error("Timed out: {0}")]
+    Timeout(String),
+    /// The transaction landed (or was simulated) and failed
+    #[error("Transaction failed: {0}")]
+    TransactionFailed(TransactionError),
+    /// The simulation raced against the submission failed with a program error before the
+    /// transaction landed, see `SolanaTrade::with_simulate_race`
+    #[error("Predicted to fail: {0}")]
+    PredictedFailure(TransactionError),
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+    /// Every swqos submission failed
+    #[error("All submissions failed: {}", .0.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; "))]
+    SubmissionFailed(Vec<TradeError>),
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+pub type TradeResult<T> = Result<T, TradeError>;
+
+impl TradeError {
+    /// Whether sending the same trade again (with a fresh blockhash) can succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            TradeError::RpcError(_) | TradeError::Timeout(_) | TradeError::SwqosRejected { .. } => {
+                true
+            }
+            TradeError::TransactionFailed(err) => matches!(
+                err,
+                TransactionError::BlockhashNotFound
+                    | TransactionError::AccountInUse
+                    | TransactionError::WouldExceedMaxBlockCostLimit
+                    | TransactionError::WouldExceedMaxAccountCostLimit
+                    | TransactionError::WouldExceedMaxVoteCostLimit
+                    | TransactionError::WouldExceedAccountDataBlockLimit
+                    | TransactionError::ClusterMaintenance
+            ),
+            TradeError::SubmissionFailed(errors) => errors.iter().all(TradeError::is_retryable),
+            _ => false,
+        }
+    }
+
+    /// Convert an error raised while sending through a swqos service
+    pub fn from_swqos(provider: &str, err: anyhow::Error) -> Self {
+        match TradeError::from(err) {
+            TradeError::Other(err) => TradeError::SwqosRejected {
+                provider: provider.to_string(),
+                message: err.to_string(),
+            },
+            err => err,
+        }
+    }
+
+    /// Merge the per-service errors of a parallel submission
+    ///
+    /// If the transaction landed and failed, that failure is what the caller cares about;
+    /// otherwise all submission errors are returned.
+    pub fn from_submissions(mut errors: Vec<TradeError>) -> Self {
+        if let Some(i) = errors.iter().position(|e| matches!(e, TradeError::TransactionFailed(_))) {
+            return errors.swap_remove(i);
+        }
+        if errors.len() == 1 {
+            return errors.remove(0);
+        }
+        TradeError::SubmissionFailed(errors)
+    }
+
+    /// Map protocol specific program errors of a trade on `dex_type` to typed variants
+    pub fn for_trade(self, dex_type: &DexType, mint: &Pubkey) -> Self {
+        match self {
+            TradeError::TransactionFailed(TransactionError::InstructionError(
+                index,
+                InstructionError::Custom(code),
+            )) => match (dex_type, code) {
+                #[cfg(feature = "pumpfun")]
+                (DexType::PumpFun, 6002) => {
+                    TradeError::SlippageExceeded("PumpFun TooMuchSolRequired".to_string())
+                }
+                #[cfg(feature = "pumpfun")]
+                (DexType::PumpFun, 6003) => {
+                    TradeError::SlippageExceeded("PumpFun TooLittleSolReceived".to_string())
+                }
+                #[cfg(feature = "pumpfun")]
+                (DexType::PumpFun, 6005) => {
+                    TradeError::CurveComplete { mint: Some(*mint), migrated_pool: None }
+                }
+                // Bonk trades on the Raydium Launchpad program
+                #[cfg(feature = "bonk")]
+                (DexType::Bonk, 6004) => {
+                    TradeError::SlippageExceeded("Bonk ExceededSlippage".to_string())
+                }
+                #[cfg(feature = "pumpswap")]
+                (DexType::PumpSwap, 6004) => {
+                    TradeError::SlippageExceeded("PumpSwap ExceededSlippage".to_string())
+                }
+                #[cfg(feature = "raydium")]
+                (DexType::RaydiumCpmm, 6005) => {
+                    TradeError::SlippageExceeded("RaydiumCpmm ExceededSlippage".to_string())
+                }
+                #[cfg(feature = "raydium")]
+                (DexType::RaydiumAmmV4, 30) => {
+                    TradeError::SlippageExceeded("RaydiumAmmV4 ExceededSlippage".to_string())
+                }
+                _ => TradeError::TransactionFailed(TransactionError::InstructionError(
+                    index,
+                    InstructionError::Custom(code),
+                )),
+            },
+            TradeError::CurveComplete { mint: None, migrated_pool } => {
+                TradeError::CurveComplete { mint: Some(*mint), migrated_pool }
+            }
+            TradeError::SubmissionFailed(errors) => TradeError::SubmissionFailed(
+                errors.into_iter().map(|e| e.for_trade(dex_type, mint)).collect(),
+            ),
+            err => err,
+        }
+    }
+}
+
+impl From<anyhow::Error> for TradeError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<TradeError>() {
+            Ok(err) => return err,
+            Err(err) => err,
+        };
+        let err = match err.downcast::<ClientError>() {
+            Ok(err) => return err.into(),
+            Err(err) => err,
+        };
+        let err = match err.downcast::<TransactionError>() {
+            Ok(err) => return TradeError::TransactionFailed(err),
+            Err(err) => err,
+        };
+        match err.downcast::<reqwest::Error>() {
+            Ok(err) if err.is_timeout() => TradeError::Timeout(err.to_string()),
+            Ok(err) => TradeError::RpcError(err.to_string()),
+            Err(err) => TradeError::Other(err),
+        }
+    }
+}
+
+impl From<ClientError> for TradeError {
+    fn from(err: ClientError) -> Self {
+        if let Some(tx_err) = err.get_transaction_error() {
+            return TradeError::TransactionFailed(tx_err);
+        }
+        match err.kind() {
+            // Middleware errors are RPC endpoints that stayed rate limited
+            ClientErrorKind::Io(_)
+            | ClientErrorKind::Reqwest(_)
+            | ClientErrorKind::Middleware(_)
+            | ClientErrorKind::RpcError(_) => TradeError::RpcError(err.to_string()),
+            _ => TradeError::Other(err.into()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "pumpfun", feature = "bonk", feature = "raydium"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_trade_classification() {
+        let mint = Pubkey::new_unique();
+        let custom = |code| {
+            TradeError::TransactionFailed(TransactionError::InstructionError(
+                2,
+                InstructionError::Custom(code),
+            ))
+        };
+        assert!(matches!(
+            custom(6002).for_trade(&DexType::PumpFun, &mint),
+            TradeError::SlippageExceeded(_)
+        ));
+        assert!(matches!(
+            custom(6005).for_trade(&DexType::PumpFun, &mint),
+            TradeError::CurveComplete { mint: Some(m), migrated_pool: None } if m == mint
+        ));
+        assert!(matches!(
+            custom(6005).for_trade(&DexType::RaydiumCpmm, &mint),
+            TradeError::SlippageExceeded(_)
+        ));
+        assert!(matches!(
+            custom(6004).for_trade(&DexType::Bonk, &mint),
+            TradeError::SlippageExceeded(_)
+        ));
+        assert!(matches!(
+            custom(6005).for_trade(&DexType::Bonk, &mint),
+            TradeError::TransactionFailed(_)
+        ));
+
+        let wrapped: anyhow::Error = TradeError::Timeout("confirmation".to_string()).into();
+        let err = TradeError::from(wrapped);
+        assert!(matches!(err, TradeError::Timeout(_)));
+        assert!(err.is_retryable());
+        assert!(!custom(6002).is_retryable());
+    }
+}
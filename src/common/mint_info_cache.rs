@@ -0,0 +1,114 @@
+use anyhow::anyhow;
+use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::Mint;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::common::SolanaRpcClient;
+use crate::trading::common::batch_fetcher::get_multiple_accounts;
+
+/// Mint 账户的关键信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MintInfo {
+    pub decimals: u8,
+    pub supply: u64,
+    /// 拥有该 mint 的 token 程序（spl_token 或 spl_token_2022）
+    pub token_program: Pubkey,
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+}
+
+impl MintInfo {
+    /// 从链上 mint 账户解析，兼容 Token-2022（只读取基础 Mint 布局）
+    pub fn from_account(account: &Account) -> Result<Self, anyhow::Error> {
+        if account.owner != spl_token::ID && account.owner != spl_token_2022::ID {
+            return Err(anyhow!("Account is not owned by a token program"));
+        }
+        let data = account.data.get(..Mint::LEN).ok_or_else(|| anyhow!("Invalid mint data"))?;
+        let mint = Mint::unpack_from_slice(data)?;
+        if !mint.is_initialized {
+            return Err(anyhow!("Mint is not initialized"));
+        }
+        Ok(Self {
+            decimals: mint.decimals,
+            supply: mint.supply,
+            token_program: account.owner,
+            mint_authority: mint.mint_authority.into(),
+            freeze_authority: mint.freeze_authority.into(),
+        })
+    }
+
+    #[inline]
+    pub fn is_token_2022(&self) -> bool {
+        self.token_program == spl_token_2022::ID
+    }
+}
+
+/// MintInfoCache 单例，懒加载缓存 mint 信息，支持批量刷新
+pub struct MintInfoCache {
+    mints: Mutex<HashMap<Pubkey, MintInfo>>,
+}
+
+static MINT_INFO_CACHE: OnceLock<Arc<MintInfoCache>> = OnceLock::new();
+
+impl MintInfoCache {
+    /// 获取 MintInfoCache 单例实例
+    pub fn get_instance() -> Arc<MintInfoCache> {
+        MINT_INFO_CACHE
+            .get_or_init(|| Arc::new(MintInfoCache { mints: Mutex::new(HashMap::new()) }))
+            .clone()
+    }
+
+    /// 获取缓存中的 mint 信息
+    pub fn get(&self, mint: &Pubkey) -> Option<MintInfo> {
+        self.mints.lock().unwrap().get(mint).copied()
+    }
+
+    /// 写入 mint 信息
+    pub fn insert(&self, mint: Pubkey, info: MintInfo) {
+        self.mints.lock().unwrap().insert(mint, info);
+    }
+
+    /// 移除 mint 信息
+    pub fn remove(&self, mint: &Pubkey) -> bool {
+        self.mints.lock().unwrap().remove(mint).is_some()
+    }
+
+    /// 获取 mint 信息，未命中时从 RPC 加载
+    pub async fn get_or_fetch(
+        &self,
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+    ) -> Result<MintInfo, anyhow::Error> {
+        if let Some(info) = self.get(mint) {
+            return Ok(info);
+        }
+        let account = rpc.get_account(mint).await?;
+        let info = MintInfo::from_account(&account)?;
+        self.insert(*mint, info);
+        Ok(info)
+    }
+
+    /// 通过 getMultipleAccounts 批量刷新，返回成功刷新的数量
+    pub async fn refresh(
+        &self,
+        rpc: &SolanaRpcClient,
+        mints: &[Pubkey],
+    ) -> Result<usize, anyhow::Error> {
+        let accounts = get_multiple_accounts(rpc, mints).await?;
+        let mut refreshed = 0;
+        let mut cache = self.mints.lock().unwrap();
+        for (mint, account) in mints.iter().zip(accounts) {
+            if let Some(info) = account.and_then(|account| MintInfo::from_account(&account).ok()) {
+                cache.insert(*mint, info);
+                refreshed += 1;
+            }
+        }
+        Ok(refreshed)
+    }
+
+    /// 清空缓存
+    pub fn clear(&self) {
+        self.mints.lock().unwrap().clear();
+    }
+}
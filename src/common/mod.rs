@@ -1,10 +1,28 @@
 pub mod address_lookup;
 pub mod nonce_cache;
+pub mod proxy;
+pub mod rate_limit;
 pub mod tip_cache;
 pub mod types;
 pub mod address_lookup_cache;
+pub mod ata_cache;
+pub mod bot_wallets;
 pub mod subscription_handle;
+#[cfg(feature = "pumpfun")]
 pub mod bonding_curve;
+pub mod config_loader;
+pub mod error;
+#[cfg(feature = "pumpfun")]
+pub mod bonding_curve_cache;
 pub mod global;
+pub mod mint_info_cache;
+pub mod rpc_pool;
+pub mod runtime;
+pub mod signer;
+pub mod task_manager;
+pub mod wallet;
+pub mod wsol_cache;
 
+pub use error::{TradeError, TradeResult};
+pub use signer::TradeSigner;
 pub use types::*;
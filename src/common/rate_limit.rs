@@ -0,0 +1,184 @@
+//! Rate limit handling for RPC requests
+//!
+//! Public RPC nodes answer bursts with `429 Too Many Requests`. [`RateLimitMiddleware`]
+//! caps the number of requests in flight per endpoint, honours `Retry-After` and otherwise
+//! retries with exponential backoff and full jitter. While one request waits out a rate
+//! limit, the other requests to the same endpoint wait as well instead of piling on.
+//!
+//! It is installed on every endpoint of an `RpcPool`; when the retries run out the request
+//! fails with a middleware error, which makes the pool fail over to the next endpoint.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use http::Extensions;
+use rand::Rng;
+use reqwest::{header::RETRY_AFTER, Request, Response, StatusCode};
+use reqwest_middleware::{ClientBuilder, Middleware, Next};
+use solana_rpc_client::http_sender::HttpSender;
+use tokio::sync::Semaphore;
+
+use super::proxy::{rpc_http_client, ProxyConfig};
+
+/// Longest `Retry-After` that is honoured, larger values are capped to `max_delay`
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(120);
+
+/// Concurrency and retry settings per RPC endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    /// Requests in flight per endpoint, unlimited when `None`
+    pub max_concurrent_requests: Option<usize>,
+    /// Retries of a rate limited request before giving up
+    pub max_retries: u32,
+    /// First backoff delay, doubled on every retry
+    pub base_delay: Duration,
+    /// Upper bound of a single backoff delay
+    pub max_delay: Duration,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: None,
+            max_retries: 5,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Backoff before retry number `attempt` (starting at 0), jittered in `[0, delay]`
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay =
+            self.base_delay.saturating_mul(2u32.saturating_pow(attempt)).min(self.max_delay);
+        delay.mul_f64(rand::rng().random_range(0.0..=1.0))
+    }
+}
+
+/// Middleware applying a `RateLimitConfig` to the requests of one endpoint
+pub struct RateLimitMiddleware {
+    config: RateLimitConfig,
+    permits: Option<Semaphore>,
+    paused_until: Mutex<Option<Instant>>,
+}
+
+impl RateLimitMiddleware {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            permits: config.max_concurrent_requests.map(|permits| Semaphore::new(permits.max(1))),
+            config,
+            paused_until: Mutex::new(None),
+        }
+    }
+
+    /// Wait until a rate limit seen by any request has passed
+    async fn wait_pause(&self) {
+        loop {
+            let until = *self.paused_until.lock().unwrap();
+            match until {
+                Some(until) if until > Instant::now() => {
+                    tokio::time::sleep_until(until.into()).await
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn pause(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut paused_until = self.paused_until.lock().unwrap();
+        if paused_until.is_none_or(|current| current < until) {
+            *paused_until = Some(until);
+        }
+    }
+
+    fn retry_delay(&self, response: &Response, attempt: u32) -> Duration {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .filter(|retry_after| *retry_after <= MAX_RETRY_AFTER);
+        match retry_after {
+            Some(retry_after) => retry_after.min(self.config.max_delay),
+            None => self.config.backoff(attempt),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for RateLimitMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let _permit = match &self.permits {
+            Some(permits) => Some(permits.acquire().await.map_err(anyhow::Error::from)?),
+            None => None,
+        };
+        let mut attempt = 0;
+        loop {
+            self.wait_pause().await;
+            let retry = req.try_clone();
+            let response = next.clone().run(req, extensions).await?;
+            let status = response.status();
+            if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::SERVICE_UNAVAILABLE
+            {
+                return Ok(response);
+            }
+            let Some(retry) = retry else { return Ok(response) };
+            if attempt >= self.config.max_retries {
+                return Err(anyhow::anyhow!(
+                    "{} still rate limited after {} retries ({})",
+                    response.url(),
+                    attempt,
+                    status
+                )
+                .into());
+            }
+            let delay = self.retry_delay(&response, attempt);
+            tracing::debug!(url = %response.url(), %status, attempt, ?delay, "rpc rate limited");
+            self.pause(delay);
+            attempt += 1;
+            req = retry;
+        }
+    }
+}
+
+/// `HttpSender` for `url` with rate limit handling
+pub fn rate_limited_sender(
+    url: impl ToString,
+    config: RateLimitConfig,
+) -> Result<HttpSender, anyhow::Error> {
+    rate_limited_sender_with_proxy(url, config, None)
+}
+
+/// Like `rate_limited_sender`, sending the requests through `proxy` when set
+pub fn rate_limited_sender_with_proxy(
+    url: impl ToString,
+    config: RateLimitConfig,
+    proxy: Option<&ProxyConfig>,
+) -> Result<HttpSender, anyhow::Error> {
+    let client = rpc_http_client(proxy)?;
+    let client = ClientBuilder::new(client).with(RateLimitMiddleware::new(config)).build();
+    Ok(HttpSender::new_with_client_with_middleware(url, client))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let config = RateLimitConfig::default();
+        for attempt in 0..40 {
+            let delay = config.backoff(attempt);
+            assert!(delay <= config.max_delay);
+            assert!(delay <= config.base_delay * 2u32.saturating_pow(attempt));
+        }
+    }
+}
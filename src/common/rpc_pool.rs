@@ -0,0 +1,268 @@
+//! Pool of RPC endpoints behind a single `SolanaRpcClient`
+//!
+//! `RpcPool` implements `RpcSender`, so the client returned by [`RpcPool::client`] is a
+//! drop-in replacement for a single URL client and every SDK call goes through the pool.
+//!
+//! Requests go to the first healthy endpoint and fail over to the next one on transport
+//! errors or when the node reports itself unhealthy / behind. With `race_reads` the
+//! latency critical reads (blockhash, accounts, balances, signature statuses) are sent to
+//! every healthy endpoint at once and the first success wins. A failed endpoint is skipped
+//! for `unhealthy_cooldown`, or until a health check sees it recover.
+//!
+//! Each endpoint retries rate limited requests on its own, see `rate_limit`.
+
+use anyhow::anyhow;
+use futures::future::select_ok;
+use solana_rpc_client::{
+    http_sender::HttpSender,
+    rpc_client::RpcClientConfig,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use solana_rpc_client_api::{
+    client_error::{Error as ClientError, ErrorKind, Result as ClientResult},
+    custom_error::{
+        JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+        JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+    },
+    request::{RpcError, RpcRequest},
+};
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+use super::proxy::ProxyConfig;
+use super::rate_limit::{rate_limited_sender_with_proxy, RateLimitConfig};
+use super::task_manager::TaskManager;
+use super::SolanaRpcClient;
+
+/// Reads that are raced across endpoints when `race_reads` is on
+const RACE_REQUESTS: &[RpcRequest] = &[
+    RpcRequest::GetLatestBlockhash,
+    RpcRequest::GetAccountInfo,
+    RpcRequest::GetMultipleAccounts,
+    RpcRequest::GetBalance,
+    RpcRequest::GetTokenAccountBalance,
+    RpcRequest::GetSignatureStatuses,
+    RpcRequest::GetSlot,
+];
+
+/// Settings of an `RpcPool`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcPoolConfig {
+    /// Endpoints tried after the primary RPC URL, in order
+    pub fallback_urls: Vec<String>,
+    /// Race latency critical reads across all healthy endpoints
+    pub race_reads: bool,
+    /// How long an endpoint is skipped after it failed
+    pub unhealthy_cooldown: Duration,
+    /// Poll `getHealth` on every endpoint at this interval
+    pub health_check_interval: Option<Duration>,
+    /// Concurrency limit and 429 backoff, applied to each endpoint separately
+    pub rate_limit: RateLimitConfig,
+    /// Proxy the endpoints are reached through
+    pub proxy: Option<ProxyConfig>,
+}
+
+impl Default for RpcPoolConfig {
+    fn default() -> Self {
+        Self {
+            fallback_urls: vec![],
+            race_reads: false,
+            unhealthy_cooldown: Duration::from_secs(10),
+            health_check_interval: None,
+            rate_limit: RateLimitConfig::default(),
+            proxy: None,
+        }
+    }
+}
+
+struct Endpoint {
+    url: String,
+    sender: HttpSender,
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+impl Endpoint {
+    fn is_healthy(&self) -> bool {
+        self.unhealthy_until.lock().unwrap().is_none_or(|until| Instant::now() >= until)
+    }
+
+    fn mark(&self, healthy: bool, cooldown: Duration) {
+        *self.unhealthy_until.lock().unwrap() =
+            if healthy { None } else { Some(Instant::now() + cooldown) };
+    }
+}
+
+struct PoolInner {
+    endpoints: Vec<Endpoint>,
+    config: RpcPoolConfig,
+}
+
+/// RPC endpoints with health tracking, failover and optional read racing
+#[derive(Clone)]
+pub struct RpcPool {
+    inner: Arc<PoolInner>,
+}
+
+impl RpcPool {
+    /// Create a pool over `urls`, the first one being the primary endpoint
+    pub fn new(urls: Vec<String>, config: RpcPoolConfig) -> Result<Self, anyhow::Error> {
+        if urls.is_empty() {
+            return Err(anyhow!("RpcPool needs at least one endpoint"));
+        }
+        let endpoints = urls
+            .into_iter()
+            .map(|url| {
+                let sender = rate_limited_sender_with_proxy(
+                    url.clone(),
+                    config.rate_limit.clone(),
+                    config.proxy.as_ref(),
+                )?;
+                Ok(Endpoint { sender, url, unhealthy_until: Mutex::new(None) })
+            })
+            .collect::<Result<_, anyhow::Error>>()?;
+        Ok(Self { inner: Arc::new(PoolInner { endpoints, config }) })
+    }
+
+    /// Create a pool with `primary` followed by `config.fallback_urls`
+    pub fn with_primary(primary: &str, config: RpcPoolConfig) -> Result<Self, anyhow::Error> {
+        let mut urls = vec![primary.to_string()];
+        urls.extend(config.fallback_urls.iter().cloned());
+        Self::new(urls, config)
+    }
+
+    /// RPC client that sends every request through the pool
+    pub fn client(&self, commitment: CommitmentConfig) -> SolanaRpcClient {
+        SolanaRpcClient::new_sender(self.clone(), RpcClientConfig::with_commitment(commitment))
+    }
+
+    pub fn urls(&self) -> Vec<String> {
+        self.inner.endpoints.iter().map(|endpoint| endpoint.url.clone()).collect()
+    }
+
+    /// Endpoints currently considered healthy
+    pub fn healthy_urls(&self) -> Vec<String> {
+        self.inner
+            .endpoints
+            .iter()
+            .filter(|endpoint| endpoint.is_healthy())
+            .map(|endpoint| endpoint.url.clone())
+            .collect()
+    }
+
+    /// Call `getHealth` on every endpoint and update their state
+    pub async fn check_health(&self) {
+        check_health(&self.inner).await;
+    }
+
+    /// Run `check_health` every `interval` until the pool and all its clients are dropped
+    pub fn spawn_health_check(&self, interval: Duration) -> JoinHandle<()> {
+        let inner: Weak<PoolInner> = Arc::downgrade(&self.inner);
+        TaskManager::get_instance().spawn("rpc_health_check", async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match inner.upgrade() {
+                    Some(inner) => check_health(&inner).await,
+                    None => break,
+                }
+            }
+        })
+    }
+}
+
+async fn check_health(inner: &PoolInner) {
+    let checks = inner.endpoints.iter().map(|endpoint| async move {
+        let result = endpoint.sender.send(RpcRequest::GetHealth, serde_json::Value::Null).await;
+        if let Err(e) = &result {
+            tracing::warn!(url = %endpoint.url, error = %e, "rpc endpoint unhealthy");
+        }
+        endpoint.mark(result.is_ok(), inner.config.unhealthy_cooldown);
+    });
+    futures::future::join_all(checks).await;
+}
+
+/// Whether another endpoint may answer where this one failed
+fn should_fail_over(err: &ClientError) -> bool {
+    match err.kind() {
+        ErrorKind::Io(_) | ErrorKind::Reqwest(_) | ErrorKind::Middleware(_) => true,
+        ErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => matches!(
+            *code,
+            JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                | JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE
+                | JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+        ),
+        _ => false,
+    }
+}
+
+impl PoolInner {
+    async fn send_to(
+        &self,
+        endpoint: &Endpoint,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        let result = endpoint.sender.send(request, params).await;
+        if let Err(e) = &result {
+            if should_fail_over(e) {
+                tracing::warn!(url = %endpoint.url, %request, error = %e, "rpc request failed");
+                endpoint.mark(false, self.config.unhealthy_cooldown);
+            }
+        }
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl RpcSender for RpcPool {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        let inner = &self.inner;
+        let (healthy, unhealthy): (Vec<&Endpoint>, Vec<&Endpoint>) =
+            inner.endpoints.iter().partition(|endpoint| endpoint.is_healthy());
+
+        if inner.config.race_reads && healthy.len() > 1 && RACE_REQUESTS.contains(&request) {
+            let races = healthy
+                .iter()
+                .map(|endpoint| Box::pin(inner.send_to(endpoint, request, params.clone())));
+            if let Ok((value, _)) = select_ok(races).await {
+                return Ok(value);
+            }
+        }
+
+        // Healthy endpoints first, the rest as a last resort
+        let mut last_error = None;
+        for endpoint in healthy.into_iter().chain(unhealthy) {
+            match inner.send_to(endpoint, request, params.clone()).await {
+                Ok(value) => {
+                    endpoint.mark(true, inner.config.unhealthy_cooldown);
+                    return Ok(value);
+                }
+                Err(e) if should_fail_over(&e) => last_error = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.expect("pool has at least one endpoint"))
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.endpoints.iter().map(|endpoint| endpoint.sender.get_transport_stats()).fold(
+            RpcTransportStats::default(),
+            |mut total, stats| {
+                total.request_count += stats.request_count;
+                total.elapsed_time += stats.elapsed_time;
+                total.rate_limited_time += stats.rate_limited_time;
+                total
+            },
+        )
+    }
+
+    fn url(&self) -> String {
+        self.inner.endpoints[0].url.clone()
+    }
+}
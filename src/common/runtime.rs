@@ -0,0 +1,137 @@
+//! Core pinning and tokio runtime tuning
+//!
+//! Submission tasks are only pinned to CPU cores when [`CoreAffinity`] asks for it: pinning
+//! every task to all host cores misbehaves in containers limited to a CPU share, where the
+//! reported cores are not the ones the process may run on. The setting is process wide and
+//! applied by `SolanaTrade::new` from `TradeConfig::core_affinity`.
+//!
+//! [`RuntimeConfig`] builds a multi-threaded tokio runtime with the worker count, scheduler
+//! intervals and worker pinning chosen by the application:
+//!
+//! ```ignore
+//! let runtime = RuntimeConfig {
+//!     worker_threads: Some(4),
+//!     core_affinity: CoreAffinity::Cores(vec![2, 3, 4, 5]),
+//!     ..Default::default()
+//! }
+//! .build()?;
+//! runtime.block_on(run_bot());
+//! ```
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+use core_affinity::CoreId;
+use serde::Deserialize;
+
+/// Which cores tasks and threads are pinned to
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreAffinity {
+    /// No pinning, the OS schedules freely
+    #[default]
+    Disabled,
+    /// Round robin over every core reported by the OS
+    AllCores,
+    /// Round robin over the given core ids
+    Cores(Vec<usize>),
+}
+
+impl CoreAffinity {
+    /// Cores to pin to, empty when pinning is disabled or no core is available
+    pub fn core_ids(&self) -> Vec<CoreId> {
+        match self {
+            CoreAffinity::Disabled => vec![],
+            // Core ids are unavailable on some platforms, run without pinning then
+            CoreAffinity::AllCores => core_affinity::get_core_ids().unwrap_or_default(),
+            CoreAffinity::Cores(ids) => ids.iter().map(|&id| CoreId { id }).collect(),
+        }
+    }
+}
+
+static PINNED_CORES: RwLock<Option<Arc<[CoreId]>>> = RwLock::new(None);
+
+/// Set the cores the swqos submission tasks are pinned to
+pub fn set_core_affinity(affinity: &CoreAffinity) {
+    let cores = affinity.core_ids();
+    *PINNED_CORES.write().unwrap() = (!cores.is_empty()).then(|| cores.into());
+}
+
+/// Cores the submission tasks are pinned to, `None` when pinning is disabled
+pub fn pinned_cores() -> Option<Arc<[CoreId]>> {
+    PINNED_CORES.read().unwrap().clone()
+}
+
+/// Settings of a multi-threaded tokio runtime, unset fields keep the tokio defaults
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuntimeConfig {
+    /// Number of worker threads, the number of cores by default
+    pub worker_threads: Option<usize>,
+    /// Maximum number of threads for blocking operations
+    pub max_blocking_threads: Option<usize>,
+    /// Scheduler ticks between polls of the global task queue
+    pub global_queue_interval: Option<u32>,
+    /// Scheduler ticks between polls for I/O and timer events
+    pub event_interval: Option<u32>,
+    /// Disable the LIFO slot optimisation, only applied when built with `--cfg tokio_unstable`
+    pub disable_lifo_slot: bool,
+    /// Name of the worker threads
+    pub thread_name: Option<String>,
+    /// Pin the worker threads, round robin over the cores
+    pub core_affinity: CoreAffinity,
+}
+
+impl RuntimeConfig {
+    /// Build the runtime, with I/O and time drivers enabled
+    pub fn build(&self) -> std::io::Result<tokio::runtime::Runtime> {
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder.enable_all();
+        if let Some(worker_threads) = self.worker_threads {
+            builder.worker_threads(worker_threads);
+        }
+        if let Some(max_blocking_threads) = self.max_blocking_threads {
+            builder.max_blocking_threads(max_blocking_threads);
+        }
+        if let Some(global_queue_interval) = self.global_queue_interval {
+            builder.global_queue_interval(global_queue_interval);
+        }
+        if let Some(event_interval) = self.event_interval {
+            builder.event_interval(event_interval);
+        }
+        #[cfg(tokio_unstable)]
+        if self.disable_lifo_slot {
+            builder.disable_lifo_slot();
+        }
+        if let Some(thread_name) = &self.thread_name {
+            builder.thread_name(thread_name);
+        }
+        let cores = self.core_affinity.core_ids();
+        if !cores.is_empty() {
+            let next = AtomicUsize::new(0);
+            builder.on_thread_start(move || {
+                let core = cores[next.fetch_add(1, Ordering::Relaxed) % cores.len()];
+                core_affinity::set_for_current(core);
+            });
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_core_affinity_and_runtime() {
+        assert!(CoreAffinity::Disabled.core_ids().is_empty());
+        let cores = CoreAffinity::Cores(vec![0, 2]).core_ids();
+        assert_eq!(cores.iter().map(|core| core.id).collect::<Vec<_>>(), vec![0, 2]);
+
+        let config: RuntimeConfig =
+            toml::from_str("worker_threads = 2\ncore_affinity = { cores = [0] }").unwrap();
+        assert_eq!(config.core_affinity, CoreAffinity::Cores(vec![0]));
+        let runtime = config.build().unwrap();
+        assert_eq!(runtime.block_on(async { 1 + 1 }), 2);
+    }
+}
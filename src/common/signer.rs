@@ -0,0 +1,22 @@
+use solana_sdk::{
+    message::VersionedMessage, signer::Signer, signer::SignerError,
+    transaction::VersionedTransaction,
+};
+
+/// Signer used for trade transactions
+///
+/// Any `solana_sdk` [`Signer`] that is `Send + Sync` is a `TradeSigner`, so besides an
+/// in-memory `Keypair` this covers `Presigner`, remote wallets and custom HSM / KMS backed
+/// signers: implement `Signer` (pubkey and `try_sign_message`) for the backend and pass it
+/// as `Arc<dyn TradeSigner>`.
+pub trait TradeSigner: Signer + Send + Sync {
+    /// Sign a compiled message with this signer as the only signer
+    fn sign_transaction(
+        &self,
+        message: VersionedMessage,
+    ) -> Result<VersionedTransaction, SignerError> {
+        VersionedTransaction::try_new(message, &[self])
+    }
+}
+
+impl<T: Signer + Send + Sync> TradeSigner for T {}
@@ -0,0 +1,102 @@
+//! Background task ownership
+//!
+//! The SDK runs a few long lived tasks of its own: the Node1 keep-alive ping, the RPC pool
+//! health check and the SOL/USD price refresh. They are spawned through the process wide
+//! [`TaskManager`], which keeps track of them so an application can stop all of them and
+//! wait until they are gone before its runtime shuts down:
+//!
+//! ```ignore
+//! run_bot(&client).await;
+//! client.shutdown().await; // or TaskManager::get_instance().shutdown().await
+//! ```
+//!
+//! Tasks still stop on their own when the objects they serve are dropped; the manager only
+//! makes stopping them explicit.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tokio::sync::oneshot;
+use tokio::task::{AbortHandle, JoinHandle};
+
+static INSTANCE: OnceLock<Arc<TaskManager>> = OnceLock::new();
+
+struct ManagedTask {
+    name: &'static str,
+    abort: AbortHandle,
+    /// Resolves once the task's future completed or was dropped
+    done: oneshot::Receiver<()>,
+}
+
+/// Owner of the SDK's background tasks
+#[derive(Default)]
+pub struct TaskManager {
+    tasks: Mutex<Vec<ManagedTask>>,
+}
+
+impl TaskManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The manager the SDK spawns its own tasks on
+    pub fn get_instance() -> Arc<Self> {
+        INSTANCE.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+
+    /// Spawn `task` on the current tokio runtime and track it under `name`
+    pub fn spawn<F>(&self, name: &'static str, task: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (done_tx, done) = oneshot::channel::<()>();
+        let handle = tokio::spawn(async move {
+            // Dropped with the future, also when the task is aborted
+            let _done = done_tx;
+            task.await
+        });
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|task| !task.abort.is_finished());
+        tasks.push(ManagedTask { name, abort: handle.abort_handle(), done });
+        handle
+    }
+
+    /// Names of the tasks still running
+    pub fn running(&self) -> Vec<&'static str> {
+        let tasks = self.tasks.lock().unwrap();
+        tasks.iter().filter(|task| !task.abort.is_finished()).map(|task| task.name).collect()
+    }
+
+    /// Abort every tracked task and wait until all of them stopped
+    ///
+    /// Tasks spawned afterwards are tracked again, so the manager stays usable.
+    pub async fn shutdown(&self) {
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        for task in &tasks {
+            task.abort.abort();
+        }
+        for task in tasks {
+            let _ = task.done.await;
+            tracing::debug!(task = task.name, "background task stopped");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_shutdown_stops_tasks() {
+        let manager = TaskManager::new();
+        manager.spawn("forever", std::future::pending::<()>());
+        let finished = manager.spawn("finished", async { 42 });
+        assert_eq!(finished.await.unwrap(), 42);
+        assert_eq!(manager.running(), vec!["forever"]);
+
+        tokio::time::timeout(Duration::from_secs(1), manager.shutdown()).await.unwrap();
+        assert!(manager.running().is_empty());
+    }
+}
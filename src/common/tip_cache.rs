@@ -1,38 +1,98 @@
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 默认 tip 金额（SOL）
+const DEFAULT_TIP: f64 = 0.001;
 
 /// TipCache 单例，用于存储和管理 tip 金额
+///
+/// 位于提交热路径上，读写均为原子操作、无锁。`update_tip` 记录更新时间，
+/// 构建交易时可用 `get_tip_or` 在 tip 数据源过期时回退到固定 tip。
 pub struct TipCache {
-    /// tip 金额
-    tip_amount: Mutex<f64>,
+    /// tip 金额，f64 的位表示
+    tip_amount: AtomicU64,
+    /// 最近一次 `update_tip` 的 Unix 毫秒时间戳，0 表示从未更新
+    updated_at_ms: AtomicU64,
 }
 
 static TIP_CACHE: OnceLock<Arc<TipCache>> = OnceLock::new();
 
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
 impl TipCache {
+    fn new() -> Self {
+        Self { tip_amount: AtomicU64::new(DEFAULT_TIP.to_bits()), updated_at_ms: AtomicU64::new(0) }
+    }
+
     /// 获取 TipCache 单例实例
     pub fn get_instance() -> Arc<TipCache> {
-        TIP_CACHE
-            .get_or_init(|| {
-                Arc::new(TipCache {
-                    tip_amount: Mutex::new(0.001),
-                })
-            })
-            .clone()
+        TIP_CACHE.get_or_init(|| Arc::new(TipCache::new())).clone()
     }
 
-    /// 初始化 tip 金额
+    /// 初始化 tip 金额，不记录更新时间，在数据源首次更新前视为过期
     pub fn init(&self, tip_amount: Option<f64>) {
-        let amount = tip_amount.unwrap_or(0.001);
-        self.update_tip(amount);
+        let amount = tip_amount.unwrap_or(DEFAULT_TIP);
+        self.tip_amount.store(amount.to_bits(), Ordering::Release);
     }
 
     /// 获取 tip 金额
     pub fn get_tip(&self) -> f64 {
-        *self.tip_amount.lock().unwrap()
+        f64::from_bits(self.tip_amount.load(Ordering::Acquire))
     }
 
-    /// 更新 tip 金额
+    /// 更新 tip 金额，并记录更新时间
     pub fn update_tip(&self, amount: f64) {
-        *self.tip_amount.lock().unwrap() = amount;
+        self.tip_amount.store(amount.to_bits(), Ordering::Release);
+        self.updated_at_ms.store(now_ms(), Ordering::Release);
     }
-}
\ No newline at end of file
+
+    /// 最近一次更新的时间，从未更新时为 `None`
+    pub fn last_update(&self) -> Option<SystemTime> {
+        match self.updated_at_ms.load(Ordering::Acquire) {
+            0 => None,
+            ms => Some(UNIX_EPOCH + Duration::from_millis(ms)),
+        }
+    }
+
+    /// 距最近一次更新的时长，从未更新时为 `None`
+    pub fn age(&self) -> Option<Duration> {
+        self.last_update().map(|updated_at| updated_at.elapsed().unwrap_or_default())
+    }
+
+    /// 从未更新或超过 `max_age` 未更新
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.age().is_none_or(|age| age > max_age)
+    }
+
+    /// tip 金额；数据源过期时返回 `fallback`
+    pub fn get_tip_or(&self, max_age: Duration, fallback: f64) -> f64 {
+        if self.is_stale(max_age) {
+            fallback
+        } else {
+            self.get_tip()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staleness_fallback() {
+        let cache = TipCache::new();
+        let max_age = Duration::from_secs(5);
+        cache.init(Some(0.002));
+        assert_eq!(cache.get_tip(), 0.002);
+        assert!(cache.is_stale(max_age));
+        assert_eq!(cache.get_tip_or(max_age, 0.005), 0.005);
+
+        cache.update_tip(0.003);
+        assert!(cache.last_update().is_some());
+        assert!(!cache.is_stale(max_age));
+        assert_eq!(cache.get_tip_or(max_age, 0.005), 0.003);
+    }
+}
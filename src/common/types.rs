@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
 use crate::{
+    common::{proxy::ProxyConfig, rpc_pool::RpcPoolConfig, runtime::CoreAffinity},
     constants::trade::trade::{
         DEFAULT_BUY_TIP_FEE, DEFAULT_RPC_UNIT_LIMIT, DEFAULT_RPC_UNIT_PRICE, DEFAULT_SELL_TIP_FEE,
         DEFAULT_TIP_UNIT_LIMIT, DEFAULT_TIP_UNIT_PRICE,
     },
-    swqos::{SwqosClient, SwqosConfig},
+    swqos::{common::ConfirmationConfig, SwqosClient, SwqosConfig, SwqosType},
 };
 use serde::Deserialize;
 use solana_client::rpc_client::RpcClient;
@@ -18,6 +19,20 @@ pub struct TradeConfig {
     pub priority_fee: PriorityFee,
     pub commitment: CommitmentConfig,
     pub lookup_table_key: Option<Pubkey>,
+    /// Fallback endpoints and failover settings; `None` uses `rpc_url` alone
+    pub rpc_pool: Option<RpcPoolConfig>,
+    /// Pinning of the swqos submission tasks, disabled by default
+    pub core_affinity: CoreAffinity,
+    /// HTTP client shared by all HTTP based swqos providers; `None` lets each build its own
+    pub swqos_http_client: Option<reqwest::Client>,
+    /// Proxy for RPC, swqos and metadata upload requests, see `common::proxy`
+    pub proxy: Option<ProxyConfig>,
+    /// Providers sent through their own proxy instead of `proxy` / `swqos_http_client`
+    pub swqos_proxies: Vec<(SwqosType, ProxyConfig)>,
+    /// How the swqos clients poll submitted transactions until they confirm
+    pub confirmation: ConfirmationConfig,
+    /// Simulate multi-transaction Jito bundles before sending them, disabled by default
+    pub jito_simulate_bundles: bool,
 }
 
 impl TradeConfig {
@@ -28,7 +43,25 @@ impl TradeConfig {
         commitment: CommitmentConfig,
         lookup_table_key: Option<Pubkey>,
     ) -> Self {
-        Self { rpc_url, swqos_configs, priority_fee, commitment, lookup_table_key }
+        Self {
+            rpc_url,
+            swqos_configs,
+            priority_fee,
+            commitment,
+            lookup_table_key,
+            rpc_pool: None,
+            core_affinity: CoreAffinity::Disabled,
+            swqos_http_client: None,
+            proxy: None,
+            swqos_proxies: vec![],
+            confirmation: ConfirmationConfig::default(),
+            jito_simulate_bundles: false,
+        }
+    }
+
+    /// Start a `TradeConfigBuilder` for the given RPC URL
+    pub fn builder(rpc_url: impl Into<String>) -> crate::TradeConfigBuilder {
+        crate::TradeConfigBuilder::new(rpc_url)
     }
 }
 
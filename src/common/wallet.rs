@@ -0,0 +1,252 @@
+//! Keypair loading for bots
+//!
+//! Keypairs can come from a Solana CLI keypair file, an environment variable, or an
+//! encrypted keystore so private keys don't have to sit in plaintext config.
+//!
+//! The keystore is a small JSON document: the 64-byte keypair is encrypted with
+//! XChaCha20-Poly1305 under a key derived from the password with Argon2id.
+
+use anyhow::anyhow;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    XChaCha20Poly1305, XNonce,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    signature::{read_keypair_file, Keypair},
+    signer::Signer,
+};
+use std::path::Path;
+
+const KEYSTORE_VERSION: u32 = 1;
+const KEYSTORE_KDF: &str = "argon2id";
+const KEYSTORE_CIPHER: &str = "xchacha20poly1305";
+
+/// Argon2id memory cost in KiB
+pub const DEFAULT_ARGON2_M_COST: u32 = 64 * 1024;
+pub const DEFAULT_ARGON2_T_COST: u32 = 3;
+pub const DEFAULT_ARGON2_P_COST: u32 = 1;
+
+/// Largest costs accepted from a keystore file, so a crafted file cannot make loading
+/// allocate or compute without bound
+const MAX_ARGON2_M_COST: u32 = 4 * DEFAULT_ARGON2_M_COST;
+const MAX_ARGON2_T_COST: u32 = 4 * DEFAULT_ARGON2_T_COST;
+const MAX_ARGON2_P_COST: u32 = 4 * DEFAULT_ARGON2_P_COST;
+
+/// Where to load a keypair from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeypairSource {
+    /// Solana CLI keypair file (JSON array of 64 bytes)
+    File(String),
+    /// Environment variable holding a base58 secret key or a JSON byte array
+    Env(String),
+    /// Encrypted keystore file, with the password read from `password_env`
+    Keystore { path: String, password_env: String },
+}
+
+impl KeypairSource {
+    pub fn load(&self) -> Result<Keypair, anyhow::Error> {
+        match self {
+            KeypairSource::File(path) => load_keypair_file(path),
+            KeypairSource::Env(var) => load_keypair_from_env(var),
+            KeypairSource::Keystore { path, password_env } => {
+                let password = std::env::var(password_env)
+                    .map_err(|_| anyhow!("Environment variable {} is not set", password_env))?;
+                load_keystore(path, &password)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Keystore {
+    version: u32,
+    pubkey: String,
+    kdf: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    salt: String,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Load a Solana CLI keypair file
+pub fn load_keypair_file<P: AsRef<Path>>(path: P) -> Result<Keypair, anyhow::Error> {
+    read_keypair_file(path.as_ref())
+        .map_err(|e| anyhow!("Failed to read keypair file {}: {}", path.as_ref().display(), e))
+}
+
+/// Parse a secret key given as base58 or as a JSON byte array
+pub fn parse_keypair(secret: &str) -> Result<Keypair, anyhow::Error> {
+    let secret = secret.trim();
+    let bytes = if secret.starts_with('[') {
+        serde_json::from_str::<Vec<u8>>(secret)?
+    } else {
+        bs58::decode(secret).into_vec()?
+    };
+    Keypair::try_from(bytes.as_slice()).map_err(|e| anyhow!("Invalid keypair: {}", e))
+}
+
+/// Load a keypair from an environment variable
+pub fn load_keypair_from_env(var: &str) -> Result<Keypair, anyhow::Error> {
+    let secret =
+        std::env::var(var).map_err(|_| anyhow!("Environment variable {} is not set", var))?;
+    parse_keypair(&secret)
+}
+
+fn derive_key(password: &str, salt: &[u8], params: Params) -> Result<[u8; 32], anyhow::Error> {
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt a keypair into keystore JSON
+pub fn encrypt_keypair(keypair: &Keypair, password: &str) -> Result<String, anyhow::Error> {
+    let salt: [u8; 16] = rand::random();
+    let nonce: [u8; 24] = rand::random();
+    let params =
+        Params::new(DEFAULT_ARGON2_M_COST, DEFAULT_ARGON2_T_COST, DEFAULT_ARGON2_P_COST, Some(32))
+            .map_err(|e| anyhow!("Invalid argon2 params: {}", e))?;
+    let key = derive_key(password, &salt, params)?;
+    let ciphertext = XChaCha20Poly1305::new(&key.into())
+        .encrypt(XNonce::from_slice(&nonce), keypair.to_bytes().as_ref())
+        .map_err(|_| anyhow!("Encryption failed"))?;
+
+    let keystore = Keystore {
+        version: KEYSTORE_VERSION,
+        pubkey: keypair.pubkey().to_string(),
+        kdf: KEYSTORE_KDF.to_string(),
+        m_cost: DEFAULT_ARGON2_M_COST,
+        t_cost: DEFAULT_ARGON2_T_COST,
+        p_cost: DEFAULT_ARGON2_P_COST,
+        salt: STANDARD.encode(salt),
+        cipher: KEYSTORE_CIPHER.to_string(),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    };
+    Ok(serde_json::to_string_pretty(&keystore)?)
+}
+
+/// Decrypt keystore JSON produced by `encrypt_keypair`
+pub fn decrypt_keystore(json: &str, password: &str) -> Result<Keypair, anyhow::Error> {
+    let keystore: Keystore = serde_json::from_str(json)?;
+    if keystore.version != KEYSTORE_VERSION
+        || keystore.kdf != KEYSTORE_KDF
+        || keystore.cipher != KEYSTORE_CIPHER
+    {
+        return Err(anyhow!("Unsupported keystore format"));
+    }
+    if keystore.m_cost > MAX_ARGON2_M_COST
+        || keystore.t_cost > MAX_ARGON2_T_COST
+        || keystore.p_cost > MAX_ARGON2_P_COST
+    {
+        return Err(anyhow!(
+            "Keystore argon2 costs (m {}, t {}, p {}) exceed the maximum (m {}, t {}, p {})",
+            keystore.m_cost,
+            keystore.t_cost,
+            keystore.p_cost,
+            MAX_ARGON2_M_COST,
+            MAX_ARGON2_T_COST,
+            MAX_ARGON2_P_COST
+        ));
+    }
+    let params = Params::new(keystore.m_cost, keystore.t_cost, keystore.p_cost, Some(32))
+        .map_err(|e| anyhow!("Invalid argon2 params: {}", e))?;
+    let key = derive_key(password, &STANDARD.decode(&keystore.salt)?, params)?;
+    let nonce = STANDARD.decode(&keystore.nonce)?;
+    if nonce.len() != 24 {
+        return Err(anyhow!("Invalid keystore nonce"));
+    }
+    let secret = XChaCha20Poly1305::new(&key.into())
+        .decrypt(XNonce::from_slice(&nonce), STANDARD.decode(&keystore.ciphertext)?.as_ref())
+        .map_err(|_| anyhow!("Wrong password or corrupted keystore"))?;
+    let keypair =
+        Keypair::try_from(secret.as_slice()).map_err(|e| anyhow!("Invalid keypair: {}", e))?;
+    if keypair.pubkey().to_string() != keystore.pubkey {
+        return Err(anyhow!("Keystore public key mismatch"));
+    }
+    Ok(keypair)
+}
+
+/// Write a keypair to an encrypted keystore file
+///
+/// On unix a new file is created readable by its owner only, like Solana CLI key files.
+pub fn save_keystore<P: AsRef<Path>>(
+    keypair: &Keypair,
+    path: P,
+    password: &str,
+) -> Result<(), anyhow::Error> {
+    use std::io::Write;
+
+    let json = encrypt_keypair(keypair, password)?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(json.as_bytes())?;
+    Ok(())
+}
+
+/// Load a keypair from an encrypted keystore file
+pub fn load_keystore<P: AsRef<Path>>(path: P, password: &str) -> Result<Keypair, anyhow::Error> {
+    decrypt_keystore(&std::fs::read_to_string(path)?, password)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let keypair = Keypair::new();
+        let json = encrypt_keypair(&keypair, "correct horse").unwrap();
+        let decrypted = decrypt_keystore(&json, "correct horse").unwrap();
+        assert_eq!(decrypted.to_bytes(), keypair.to_bytes());
+        assert!(decrypt_keystore(&json, "wrong password").is_err());
+    }
+
+    #[test]
+    fn test_keystore_rejects_excessive_costs() {
+        let keypair = Keypair::new();
+        let json = encrypt_keypair(&keypair, "correct horse").unwrap();
+        let mut keystore: serde_json::Value = serde_json::from_str(&json).unwrap();
+        keystore["m_cost"] = (MAX_ARGON2_M_COST + 1).into();
+        let err = decrypt_keystore(&keystore.to_string(), "correct horse").unwrap_err();
+        assert!(err.to_string().contains("exceed the maximum"));
+
+        let mut keystore: serde_json::Value = serde_json::from_str(&json).unwrap();
+        keystore["t_cost"] = u32::MAX.into();
+        assert!(decrypt_keystore(&keystore.to_string(), "correct horse").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_save_keystore_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("keystore-{}.json", std::process::id()));
+        let keypair = Keypair::new();
+        save_keystore(&keypair, &path, "correct horse").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        let loaded = load_keystore(&path, "correct horse");
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(loaded.unwrap().pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_parse_keypair_formats() {
+        let keypair = Keypair::new();
+        let from_base58 = parse_keypair(&keypair.to_base58_string()).unwrap();
+        let json = serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap();
+        let from_json = parse_keypair(&json).unwrap();
+        assert_eq!(from_base58.pubkey(), keypair.pubkey());
+        assert_eq!(from_json.pubkey(), keypair.pubkey());
+    }
+}
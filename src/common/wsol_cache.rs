@@ -0,0 +1,138 @@
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::common::SolanaRpcClient;
+use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+
+/// WSOL 账户使用策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WsolMode {
+    /// 每笔交易创建并关闭 WSOL ATA（默认）
+    #[default]
+    PerTrade,
+    /// 保留一个常驻的 WSOL ATA，余额不足时才补充
+    ///
+    /// 补充时至少转入 `top_up_lamports`，之后的交易直接使用账户中的余额，
+    /// 省去转账、同步和关闭指令。卖出所得保留为 WSOL。
+    Persistent { top_up_lamports: u64 },
+    /// 每笔交易使用 `create_account_with_seed` 创建临时 WSOL 账户，交易结束时关闭
+    ///
+    /// 每笔交易的账户地址不同，同一钱包的并行交易不会争用同一个 WSOL ATA。
+    SeedDerived,
+}
+
+/// WsolCache 单例，记录 WSOL 策略及常驻 WSOL 账户的余额
+///
+/// 余额在构建交易时按保守方向估计：买入扣除最大花费，卖出计入最小所得。交易失败时
+/// 余额被视为未知，下一笔交易会按全额补充；也可用 `refresh` 从链上重新读取。
+pub struct WsolCache {
+    mode: Mutex<WsolMode>,
+    balances: Mutex<HashMap<Pubkey, u64>>,
+}
+
+static WSOL_CACHE: OnceLock<Arc<WsolCache>> = OnceLock::new();
+
+impl WsolCache {
+    fn new() -> Self {
+        Self { mode: Mutex::new(WsolMode::default()), balances: Mutex::new(HashMap::new()) }
+    }
+
+    /// 获取 WsolCache 单例实例
+    pub fn get_instance() -> Arc<WsolCache> {
+        WSOL_CACHE.get_or_init(|| Arc::new(WsolCache::new())).clone()
+    }
+
+    /// 设置 WSOL 账户使用策略
+    pub fn set_mode(&self, mode: WsolMode) {
+        *self.mode.lock().unwrap() = mode;
+    }
+
+    /// 获取 WSOL 账户使用策略
+    pub fn get_mode(&self) -> WsolMode {
+        *self.mode.lock().unwrap()
+    }
+
+    /// 是否使用常驻 WSOL 账户
+    pub fn is_persistent(&self) -> bool {
+        matches!(self.get_mode(), WsolMode::Persistent { .. })
+    }
+
+    /// 常驻 WSOL 账户的估计余额，未知时为 `None`
+    pub fn balance(&self, owner: &Pubkey) -> Option<u64> {
+        self.balances.lock().unwrap().get(owner).copied()
+    }
+
+    /// 设置常驻 WSOL 账户的余额
+    pub fn set_balance(&self, owner: &Pubkey, lamports: u64) {
+        self.balances.lock().unwrap().insert(*owner, lamports);
+    }
+
+    /// 将余额标记为未知
+    pub fn invalidate(&self, owner: &Pubkey) {
+        self.balances.lock().unwrap().remove(owner);
+    }
+
+    /// 为花费 `amount` 准备余额，返回需要转入的 lamports，0 表示无需补充
+    ///
+    /// 记录的余额扣除 `amount`，并计入本次转入的数量。
+    pub fn reserve(&self, owner: &Pubkey, amount: u64) -> u64 {
+        let top_up_lamports = match self.get_mode() {
+            WsolMode::PerTrade | WsolMode::SeedDerived => return amount,
+            WsolMode::Persistent { top_up_lamports } => top_up_lamports,
+        };
+        let mut balances = self.balances.lock().unwrap();
+        let balance = balances.get(owner).copied().unwrap_or(0);
+        let top_up = if balance >= amount { 0 } else { (amount - balance).max(top_up_lamports) };
+        balances.insert(*owner, balance + top_up - amount);
+        top_up
+    }
+
+    /// 计入卖出所得
+    pub fn credit(&self, owner: &Pubkey, amount: u64) {
+        if let Some(balance) = self.balances.lock().unwrap().get_mut(owner) {
+            *balance = balance.saturating_add(amount);
+        }
+    }
+
+    /// 从链上读取常驻 WSOL ATA 的余额，账户不存在时为 0
+    pub async fn refresh(&self, rpc: &SolanaRpcClient, owner: &Pubkey) -> anyhow::Result<u64> {
+        let ata =
+            spl_associated_token_account::get_associated_token_address(owner, &WSOL_TOKEN_ACCOUNT);
+        let balance = match rpc.get_token_account_balance(&ata).await {
+            Ok(balance) => balance
+                .amount
+                .parse::<u64>()
+                .map_err(|e| anyhow!("Failed to parse WSOL balance: {}", e))?,
+            Err(e) if e.to_string().contains("could not find account") => 0,
+            Err(e) => return Err(e.into()),
+        };
+        self.set_balance(owner, balance);
+        Ok(balance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persistent_top_up() {
+        let cache = WsolCache::new();
+        let owner = Pubkey::new_unique();
+        assert_eq!(cache.reserve(&owner, 100), 100);
+        assert_eq!(cache.balance(&owner), None);
+
+        cache.set_mode(WsolMode::Persistent { top_up_lamports: 1_000 });
+        assert_eq!(cache.reserve(&owner, 100), 1_000);
+        assert_eq!(cache.reserve(&owner, 600), 0);
+        assert_eq!(cache.balance(&owner), Some(300));
+        cache.credit(&owner, 50);
+        assert_eq!(cache.reserve(&owner, 2_000), 1_650);
+        assert_eq!(cache.balance(&owner), Some(0));
+
+        cache.invalidate(&owner);
+        assert_eq!(cache.reserve(&owner, 100), 1_000);
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
     },
     trading::{
         bonk::common::{get_pool_pda, get_vault_pda},
-        common::utils::get_token_balance,
+        common::{add_create_ata_instruction, utils::get_token_balance},
         core::{
             params::{BonkParams, BuyParams, SellParams},
             traits::InstructionBuilder,
@@ -82,9 +82,10 @@ impl BonkInstructionBuilder {
             real_base,
             real_quote,
             params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
-        );
+        )
+        .map_err(|e| anyhow!("Bonk quote failed: {}", e))?;
 
-        let mut instructions = vec![];
+        let mut instructions = Vec::with_capacity(super::BUY_INSTRUCTIONS_CAPACITY);
 
         if protocol_params.auto_handle_wsol {
             // Handle wSOL
@@ -113,12 +114,13 @@ impl BonkInstructionBuilder {
         }
 
         // Create user's base token account
-        instructions.push(create_associated_token_account_idempotent(
+        add_create_ata_instruction(
+            &mut instructions,
             &params.payer.pubkey(),
             &params.payer.pubkey(),
             &params.mint,
             &protocol_params.mint_token_program,
-        ));
+        );
 
         // Create buy instruction
         let accounts = vec![
@@ -184,9 +186,7 @@ impl BonkInstructionBuilder {
         &self,
         params: &SellParams,
     ) -> Result<Vec<Instruction>> {
-        if params.rpc.is_none() {
-            return Err(anyhow!("RPC is not set"));
-        }
+        let rpc = params.rpc.clone().ok_or_else(|| anyhow!("RPC is not set"))?;
 
         let protocol_params = params
             .protocol_params
@@ -194,8 +194,6 @@ impl BonkInstructionBuilder {
             .downcast_ref::<BonkParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for Bonk"))?;
 
-        let rpc = params.rpc.as_ref().unwrap().clone();
-
         // Get token balance
         let mut amount = params.token_amount;
         if params.token_amount.is_none() || params.token_amount.unwrap_or(0) == 0 {
@@ -224,7 +222,8 @@ impl BonkInstructionBuilder {
             real_base,
             real_quote,
             params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
-        );
+        )
+        .map_err(|e| anyhow!("Bonk quote failed: {}", e))?;
 
         // Create user token accounts
         let user_base_token_account =
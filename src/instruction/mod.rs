@@ -1,5 +1,15 @@
+#[cfg(feature = "pumpfun")]
 pub mod pumpfun;
+#[cfg(feature = "pumpswap")]
 pub mod pumpswap;
+#[cfg(feature = "bonk")]
 pub mod bonk;
+#[cfg(feature = "raydium")]
 pub mod raydium_cpmm;
-pub mod raydium_amm_v4;
\ No newline at end of file
+#[cfg(feature = "raydium")]
+pub mod raydium_amm_v4;
+
+/// Most instructions a swap buy is built from: wSOL create, transfer and sync, the token
+/// account, the swap and the wSOL close
+#[cfg(any(feature = "pumpswap", feature = "bonk", feature = "raydium"))]
+const BUY_INSTRUCTIONS_CAPACITY: usize = 6;
@@ -1,14 +1,15 @@
 use anyhow::{anyhow, Result};
 use solana_sdk::instruction::Instruction;
-use spl_associated_token_account::{
-    get_associated_token_address, instruction::create_associated_token_account,
-};
+use spl_associated_token_account::get_associated_token_address;
 use spl_token::instruction::close_account;
 
 use crate::{
+    common::{ata_cache::AtaCache, TradeError, TradeSigner},
     constants,
+    trading::common::add_create_ata_instruction,
     trading::pumpfun::common::{
-        get_bonding_curve_pda, get_fee_config_pda, get_global_volume_accumulator_pda, get_user_volume_accumulator_pda
+        get_bonding_curve_pda, get_fee_config_pda, get_global_volume_accumulator_pda, get_metadata_pda,
+        get_mint_authority_pda, get_user_volume_accumulator_pda
     },
     utils::calc::{
         common::{calculate_with_slippage_buy, calculate_with_slippage_sell},
@@ -46,11 +47,18 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
         }
 
         let bonding_curve = protocol_params.bonding_curve.clone();
+        // Fail before paying fees and tips on a transaction the program will reject
+        if bonding_curve.complete {
+            return Err(
+                TradeError::CurveComplete { mint: Some(params.mint), migrated_pool: None }.into()
+            );
+        }
 
         let max_sol_cost = calculate_with_slippage_buy(
             params.sol_amount,
             params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-        );
+        )
+        .map_err(|e| anyhow!(e))?;
         let creator_vault_pda = protocol_params.creator_vault;
 
         let mut creator = Pubkey::default();
@@ -71,12 +79,13 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
         let mut instructions = vec![];
 
         // Create associated token account
-        instructions.push(create_associated_token_account(
+        add_create_ata_instruction(
+            &mut instructions,
             &params.payer.pubkey(),
             &params.payer.pubkey(),
             &params.mint,
             &constants::pumpfun::accounts::TOKEN_PROGRAM,
-        ));
+        );
 
         // Create buy instruction
         instructions.push(buy(
@@ -100,6 +109,11 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
             .ok_or_else(|| anyhow!("Invalid protocol params for PumpFun"))?;
 
         let bonding_curve = protocol_params.bonding_curve.clone();
+        if bonding_curve.complete {
+            return Err(
+                TradeError::CurveComplete { mint: Some(params.mint), migrated_pool: None }.into()
+            );
+        }
 
         let token_amount = if let Some(amount) = params.token_amount {
             if amount == 0 {
@@ -128,7 +142,8 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
         let min_sol_output = calculate_with_slippage_sell(
             sol_amount,
             params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-        );
+        )
+        .map_err(|e| anyhow!(e))?;
 
         let mut instructions = vec![sell(
             params.payer.as_ref(),
@@ -147,12 +162,35 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
                 &params.payer.pubkey(),
                 &[&params.payer.pubkey()],
             )?);
+            AtaCache::get_instance().remove(&params.payer.pubkey(), &params.mint);
         }
 
         Ok(instructions)
     }
 }
 
+pub struct Create {
+    pub _name: String,
+    pub _symbol: String,
+    pub _uri: String,
+    pub _creator: Pubkey,
+}
+
+impl Create {
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(
+            8 + 4 + self._name.len() + 4 + self._symbol.len() + 4 + self._uri.len() + 32,
+        );
+        data.extend_from_slice(&[24, 30, 200, 40, 5, 28, 7, 119]); // discriminator
+        for value in [&self._name, &self._symbol, &self._uri] {
+            data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            data.extend_from_slice(value.as_bytes());
+        }
+        data.extend_from_slice(self._creator.as_ref());
+        data
+    }
+}
+
 pub struct Buy {
     pub _amount: u64,
     pub _max_sol_cost: u64,
@@ -183,8 +221,33 @@ impl Sell {
     }
 }
 
+/// Create a new token with its bonding curve; `args._uri` must point to hosted metadata
+pub fn create(payer: &dyn TradeSigner, mint: &Keypair, args: Create) -> Instruction {
+    let bonding_curve = get_bonding_curve_pda(&mint.pubkey()).unwrap();
+    Instruction::new_with_bytes(
+        constants::pumpfun::accounts::PUMPFUN,
+        &args.data(),
+        vec![
+            AccountMeta::new(mint.pubkey(), true),
+            AccountMeta::new_readonly(get_mint_authority_pda(), false),
+            AccountMeta::new(bonding_curve, false),
+            AccountMeta::new(get_associated_token_address(&bonding_curve, &mint.pubkey()), false),
+            AccountMeta::new_readonly(constants::pumpfun::global_constants::GLOBAL_ACCOUNT, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::MPL_TOKEN_METADATA, false),
+            AccountMeta::new(get_metadata_pda(&mint.pubkey()), false),
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::SYSTEM_PROGRAM, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::ASSOCIATED_TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::RENT, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::EVENT_AUTHORITY, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::PUMPFUN, false),
+        ],
+    )
+}
+
 pub fn buy(
-    payer: &Keypair,
+    payer: &dyn TradeSigner,
     mint: &Pubkey,
     bonding_curve_pda: &Pubkey,
     creator_vault_pda: &Pubkey,
@@ -216,7 +279,7 @@ pub fn buy(
 }
 
 pub fn sell(
-    payer: &Keypair,
+    payer: &dyn TradeSigner,
     mint: &Pubkey,
     creator_vault_pda: &Pubkey,
     fee_recipient: &Pubkey,
@@ -244,3 +307,19 @@ pub fn sell(
         ],
     )
 }
+
+/// Withdraw the SOL accumulated in the creator vault to the creator
+pub fn collect_creator_fee(creator: &Pubkey) -> Instruction {
+    let creator_vault_pda = get_creator_vault_pda(creator).unwrap();
+    Instruction::new_with_bytes(
+        constants::pumpfun::accounts::PUMPFUN,
+        &[20, 22, 86, 123, 198, 28, 219, 132], // discriminator
+        vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new(creator_vault_pda, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::SYSTEM_PROGRAM, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::EVENT_AUTHORITY, false),
+            AccountMeta::new_readonly(constants::pumpfun::accounts::PUMPFUN, false),
+        ],
+    )
+}
@@ -1,15 +1,28 @@
 use anyhow::{anyhow, Result};
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signer::Signer};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+};
+use solana_streamer_sdk::streaming::event_parser::protocols::pumpswap::types::Pool;
 use solana_system_interface::instruction::transfer;
-use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
 use spl_token::instruction::close_account;
 
 use crate::{
+    common::wsol_cache::{WsolCache, WsolMode},
     constants::{
-        pumpswap::{accounts, BUY_DISCRIMINATOR, SELL_DISCRIMINATOR},
+        pumpswap::{
+            accounts, BUY_DISCRIMINATOR, COLLECT_COIN_CREATOR_FEE_DISCRIMINATOR,
+            DEPOSIT_DISCRIMINATOR, SELL_DISCRIMINATOR, WITHDRAW_DISCRIMINATOR,
+        },
         trade::trade::DEFAULT_SLIPPAGE,
     },
     trading::{
+        common::{add_create_ata_instruction, create_seeded_wsol_account},
         core::{
             params::{BuyParams, PumpSwapParams, SellParams},
             traits::InstructionBuilder,
@@ -111,13 +124,17 @@ impl PumpSwapInstructionBuilder {
         base_token_program: Pubkey,
         quote_token_program: Pubkey,
     ) -> Result<Vec<Instruction>> {
-        if base_mint != accounts::WSOL_TOKEN_ACCOUNT && quote_mint != accounts::WSOL_TOKEN_ACCOUNT {
-            return Err(anyhow!("Invalid base mint and quote mint"));
-        }
         if params.rpc.is_none() {
             return Err(anyhow!("RPC is not set"));
         }
-        let quote_mint_is_wsol = quote_mint == accounts::WSOL_TOKEN_ACCOUNT;
+        // The quote mint (WSOL, USDC, ...) is what the user pays with and receives,
+        // except for pools that hold WSOL as base where the roles are reversed
+        let quote_is_currency = base_mint != accounts::WSOL_TOKEN_ACCOUNT;
+        let currency_mint = if quote_is_currency { quote_mint } else { base_mint };
+        let currency_token_program =
+            if quote_is_currency { quote_token_program } else { base_token_program };
+        // Only wrap/unwrap when the currency side is actually WSOL
+        let handle_wsol = auto_handle_wsol && currency_mint == accounts::WSOL_TOKEN_ACCOUNT;
 
         let mut token_amount = 0;
         let mut sol_amount = 0;
@@ -127,7 +144,7 @@ impl PumpSwapInstructionBuilder {
         if default_creator_ata != params_coin_creator_vault_ata {
             creator = params_coin_creator_vault_ata;
         }
-        if quote_mint_is_wsol {
+        if quote_is_currency {
             let result = buy_quote_input_internal(
                 params.sol_amount,
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
@@ -135,7 +152,7 @@ impl PumpSwapInstructionBuilder {
                 pool_quote_token_reserves,
                 &creator,
             )
-            .unwrap();
+            .map_err(|e| anyhow!("PumpSwap quote failed: {}", e))?;
             // base_amount_out
             token_amount = result.base;
             // max_quote_amount_in
@@ -148,7 +165,7 @@ impl PumpSwapInstructionBuilder {
                 pool_quote_token_reserves,
                 &creator,
             )
-            .unwrap();
+            .map_err(|e| anyhow!("PumpSwap quote failed: {}", e))?;
             // min_quote_amount_out
             token_amount = result.min_quote;
             // base_amount_in
@@ -156,13 +173,13 @@ impl PumpSwapInstructionBuilder {
         }
 
         // Create user token accounts
-        let user_base_token_account =
+        let mut user_base_token_account =
             spl_associated_token_account::get_associated_token_address_with_program_id(
                 &params.payer.pubkey(),
                 &base_mint,
                 &base_token_program,
             );
-        let user_quote_token_account =
+        let mut user_quote_token_account =
             spl_associated_token_account::get_associated_token_address_with_program_id(
                 &params.payer.pubkey(),
                 &quote_mint,
@@ -184,29 +201,45 @@ impl PumpSwapInstructionBuilder {
                 &quote_token_program,
             );
 
-        let mut instructions = vec![];
-
-        if auto_handle_wsol {
+        let mut instructions = Vec::with_capacity(super::BUY_INSTRUCTIONS_CAPACITY);
+
+        let wsol_cache = WsolCache::get_instance();
+        // A persistent wSOL account is only topped up when its balance runs short
+        let persistent_wsol = handle_wsol && wsol_cache.is_persistent();
+        let seeded_wsol = handle_wsol && wsol_cache.get_mode() == WsolMode::SeedDerived;
+        let wsol_top_up =
+            if handle_wsol { wsol_cache.reserve(&params.payer.pubkey(), sol_amount) } else { 0 };
+        if seeded_wsol {
+            // Fresh wSOL account per trade, funded on creation and closed after the buy
+            let (wsol_account, create_instructions) =
+                create_seeded_wsol_account(&params.payer.pubkey(), sol_amount);
+            instructions.extend(create_instructions);
+            if quote_is_currency {
+                user_quote_token_account = wsol_account;
+            } else {
+                user_base_token_account = wsol_account;
+            }
+        } else if wsol_top_up > 0 {
             // Handle wSOL
             instructions.push(
                 // Create wSOL ATA account if it doesn't exist
                 create_associated_token_account_idempotent(
                     &params.payer.pubkey(),
                     &params.payer.pubkey(),
-                    &accounts::WSOL_TOKEN_ACCOUNT,
-                    &accounts::TOKEN_PROGRAM,
+                    &currency_mint,
+                    &currency_token_program,
                 ),
             );
             instructions.push(
                 // Transfer SOL to wSOL ATA account
                 transfer(
                     &params.payer.pubkey(),
-                    if quote_mint_is_wsol {
+                    if quote_is_currency {
                         &user_quote_token_account
                     } else {
                         &user_base_token_account
                     },
-                    sol_amount,
+                    wsol_top_up,
                 ),
             );
 
@@ -214,7 +247,7 @@ impl PumpSwapInstructionBuilder {
             instructions.push(
                 spl_token::instruction::sync_native(
                     &accounts::TOKEN_PROGRAM,
-                    if quote_mint_is_wsol {
+                    if quote_is_currency {
                         &user_quote_token_account
                     } else {
                         &user_base_token_account
@@ -225,12 +258,13 @@ impl PumpSwapInstructionBuilder {
         }
 
         // Create user's base token account
-        instructions.push(create_associated_token_account_idempotent(
+        add_create_ata_instruction(
+            &mut instructions,
             &params.payer.pubkey(),
             &params.payer.pubkey(),
-            if quote_mint_is_wsol { &base_mint } else { &quote_mint },
-            if quote_mint_is_wsol { &base_token_program } else { &quote_token_program },
-        ));
+            if quote_is_currency { &base_mint } else { &quote_mint },
+            if quote_is_currency { &base_token_program } else { &quote_token_program },
+        );
 
         let fee_recipient_ata = fee_recipient_ata(accounts::FEE_RECIPIENT, quote_mint);
 
@@ -262,7 +296,7 @@ impl PumpSwapInstructionBuilder {
                 false,
             ), // coin_creator_vault_authority (readonly)
         ];
-        if quote_mint_is_wsol {
+        if quote_is_currency {
             accounts.push(solana_sdk::instruction::AccountMeta::new(
                 get_global_volume_accumulator_pda().unwrap(),
                 false,
@@ -275,7 +309,7 @@ impl PumpSwapInstructionBuilder {
 
         // Create instruction data
         let mut data = vec![];
-        if quote_mint_is_wsol {
+        if quote_is_currency {
             data.extend_from_slice(&BUY_DISCRIMINATOR);
             // base_amount_out
             data.extend_from_slice(&token_amount.to_le_bytes());
@@ -290,12 +324,12 @@ impl PumpSwapInstructionBuilder {
         }
 
         instructions.push(Instruction { program_id: accounts::AMM_PROGRAM, accounts, data });
-        if auto_handle_wsol {
+        if handle_wsol && !persistent_wsol {
             // Close wSOL ATA account, reclaim rent
             instructions.push(
                 spl_token::instruction::close_account(
                     &accounts::TOKEN_PROGRAM,
-                    if quote_mint_is_wsol {
+                    if quote_is_currency {
                         &user_quote_token_account
                     } else {
                         &user_base_token_account
@@ -325,9 +359,6 @@ impl PumpSwapInstructionBuilder {
         base_token_program: Pubkey,
         quote_token_program: Pubkey,
     ) -> Result<Vec<Instruction>> {
-        if base_mint != accounts::WSOL_TOKEN_ACCOUNT && quote_mint != accounts::WSOL_TOKEN_ACCOUNT {
-            return Err(anyhow!("Invalid base mint and quote mint"));
-        }
         if params.rpc.is_none() {
             return Err(anyhow!("RPC is not set"));
         }
@@ -335,7 +366,14 @@ impl PumpSwapInstructionBuilder {
             return Err(anyhow!("Token amount is not set"));
         }
 
-        let quote_mint_is_wsol = quote_mint == accounts::WSOL_TOKEN_ACCOUNT;
+        // The quote mint (WSOL, USDC, ...) is what the user pays with and receives,
+        // except for pools that hold WSOL as base where the roles are reversed
+        let quote_is_currency = base_mint != accounts::WSOL_TOKEN_ACCOUNT;
+        let currency_mint = if quote_is_currency { quote_mint } else { base_mint };
+        let currency_token_program =
+            if quote_is_currency { quote_token_program } else { base_token_program };
+        // Only wrap/unwrap when the currency side is actually WSOL
+        let handle_wsol = auto_handle_wsol && currency_mint == accounts::WSOL_TOKEN_ACCOUNT;
 
         let mut token_amount = 0;
         let mut sol_amount = 0;
@@ -345,29 +383,31 @@ impl PumpSwapInstructionBuilder {
         if default_creator_ata != params_coin_creator_vault_ata {
             creator = params_coin_creator_vault_ata;
         }
+        let input_token_amount =
+            params.token_amount.ok_or_else(|| anyhow!("Token amount is not set"))?;
 
-        if quote_mint_is_wsol {
+        if quote_is_currency {
             let result = sell_base_input_internal(
-                params.token_amount.unwrap(),
+                input_token_amount,
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
                 pool_base_token_reserves,
                 pool_quote_token_reserves,
                 &creator,
             )
-            .unwrap();
+            .map_err(|e| anyhow!("PumpSwap quote failed: {}", e))?;
             // base_amount_in
-            token_amount = params.token_amount.unwrap();
+            token_amount = input_token_amount;
             // min_quote_amount_out
             sol_amount = result.min_quote;
         } else {
             let result = buy_quote_input_internal(
-                params.token_amount.unwrap(),
+                input_token_amount,
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
                 pool_base_token_reserves,
                 pool_quote_token_reserves,
                 &creator,
             )
-            .unwrap();
+            .map_err(|e| anyhow!("PumpSwap quote failed: {}", e))?;
             // max_quote_amount_in
             token_amount = result.max_quote;
             // base_amount_out
@@ -376,13 +416,13 @@ impl PumpSwapInstructionBuilder {
 
         let fee_recipient_ata = fee_recipient_ata(accounts::FEE_RECIPIENT, quote_mint);
 
-        let user_base_token_account =
+        let mut user_base_token_account =
             spl_associated_token_account::get_associated_token_address_with_program_id(
                 &params.payer.pubkey(),
                 &base_mint,
                 &base_token_program,
             );
-        let user_quote_token_account =
+        let mut user_quote_token_account =
             spl_associated_token_account::get_associated_token_address_with_program_id(
                 &params.payer.pubkey(),
                 &quote_mint,
@@ -403,23 +443,32 @@ impl PumpSwapInstructionBuilder {
 
         let mut instructions = vec![];
 
-        // Insert wSOL
-        instructions.push(
-            // Create wSOL ATA account if it doesn't exist
-            create_associated_token_account_idempotent(
+        if handle_wsol && WsolCache::get_instance().get_mode() == WsolMode::SeedDerived {
+            // Receive into a fresh wSOL account, closed after the sell
+            let (wsol_account, create_instructions) =
+                create_seeded_wsol_account(&params.payer.pubkey(), 0);
+            instructions.extend(create_instructions);
+            if quote_is_currency {
+                user_quote_token_account = wsol_account;
+            } else {
+                user_base_token_account = wsol_account;
+            }
+        } else {
+            // Create the currency (wSOL, USDC, ...) ATA if it doesn't exist
+            instructions.push(create_associated_token_account_idempotent(
                 &params.payer.pubkey(),
                 &params.payer.pubkey(),
-                &accounts::WSOL_TOKEN_ACCOUNT,
-                &accounts::TOKEN_PROGRAM,
-            ),
-        );
+                &currency_mint,
+                &currency_token_program,
+            ));
+        }
 
         // Create user's token account
         instructions.push(create_associated_token_account_idempotent(
             &params.payer.pubkey(),
             &params.payer.pubkey(),
-            if quote_mint_is_wsol { &base_mint } else { &quote_mint },
-            if quote_mint_is_wsol { &base_token_program } else { &quote_token_program },
+            if quote_is_currency { &base_mint } else { &quote_mint },
+            if quote_is_currency { &base_token_program } else { &quote_token_program },
         ));
 
         // Create sell instruction
@@ -450,7 +499,7 @@ impl PumpSwapInstructionBuilder {
                 false,
             ), // coin_creator_vault_authority (readonly)
         ];
-        if !quote_mint_is_wsol {
+        if !quote_is_currency {
             accounts.push(solana_sdk::instruction::AccountMeta::new(
                 get_global_volume_accumulator_pda().unwrap(),
                 false,
@@ -463,7 +512,7 @@ impl PumpSwapInstructionBuilder {
 
         // Create instruction data
         let mut data = vec![];
-        if quote_mint_is_wsol {
+        if quote_is_currency {
             data.extend_from_slice(&SELL_DISCRIMINATOR);
             // base_amount_in
             data.extend_from_slice(&token_amount.to_le_bytes());
@@ -479,11 +528,15 @@ impl PumpSwapInstructionBuilder {
 
         instructions.push(Instruction { program_id: accounts::AMM_PROGRAM, accounts, data });
 
-        if auto_handle_wsol {
+        let wsol_cache = WsolCache::get_instance();
+        if handle_wsol && wsol_cache.is_persistent() {
+            // Keep the proceeds in the persistent wSOL account
+            wsol_cache.credit(&params.payer.pubkey(), sol_amount);
+        } else if handle_wsol {
             instructions.push(
                 close_account(
                     &accounts::TOKEN_PROGRAM,
-                    if quote_mint_is_wsol {
+                    if quote_is_currency {
                         &user_quote_token_account
                     } else {
                         &user_base_token_account
@@ -498,3 +551,103 @@ impl PumpSwapInstructionBuilder {
         Ok(instructions)
     }
 }
+
+/// Accounts of a PumpSwap liquidity deposit or withdraw
+pub struct LiquidityAccounts<'a> {
+    pub pool_address: &'a Pubkey,
+    pub pool: &'a Pool,
+    /// Liquidity provider, signs the instruction
+    pub user: &'a Pubkey,
+    pub base_token_program: &'a Pubkey,
+    pub quote_token_program: &'a Pubkey,
+}
+
+fn liquidity_accounts(liquidity: &LiquidityAccounts) -> Vec<AccountMeta> {
+    let LiquidityAccounts { pool_address, pool, user, base_token_program, quote_token_program } =
+        *liquidity;
+    let user_base_token_account =
+        get_associated_token_address_with_program_id(user, &pool.base_mint, base_token_program);
+    let user_quote_token_account =
+        get_associated_token_address_with_program_id(user, &pool.quote_mint, quote_token_program);
+    let user_pool_token_account =
+        get_associated_token_address_with_program_id(user, &pool.lp_mint, &spl_token_2022::ID);
+    vec![
+        AccountMeta::new(*pool_address, false), // pool
+        AccountMeta::new_readonly(accounts::GLOBAL_ACCOUNT, false), // global_config (readonly)
+        AccountMeta::new_readonly(*user, true), // user (signer)
+        AccountMeta::new_readonly(pool.base_mint, false), // base_mint (readonly)
+        AccountMeta::new_readonly(pool.quote_mint, false), // quote_mint (readonly)
+        AccountMeta::new(pool.lp_mint, false),  // lp_mint
+        AccountMeta::new(user_base_token_account, false), // user_base_token_account
+        AccountMeta::new(user_quote_token_account, false), // user_quote_token_account
+        AccountMeta::new(user_pool_token_account, false), // user_pool_token_account
+        AccountMeta::new(pool.pool_base_token_account, false), // pool_base_token_account
+        AccountMeta::new(pool.pool_quote_token_account, false), // pool_quote_token_account
+        AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // token_program (readonly)
+        AccountMeta::new_readonly(spl_token_2022::ID, false), // token_2022_program (readonly)
+        AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false), // event_authority (readonly)
+        AccountMeta::new_readonly(accounts::AMM_PROGRAM, false), // program (readonly)
+    ]
+}
+
+/// Deposit liquidity into a PumpSwap pool, minting `lp_token_amount_out` LP tokens
+pub fn deposit(
+    liquidity: &LiquidityAccounts,
+    lp_token_amount_out: u64,
+    max_base_amount_in: u64,
+    max_quote_amount_in: u64,
+) -> Instruction {
+    let mut data = Vec::with_capacity(8 + 8 * 3);
+    data.extend_from_slice(&DEPOSIT_DISCRIMINATOR);
+    data.extend_from_slice(&lp_token_amount_out.to_le_bytes());
+    data.extend_from_slice(&max_base_amount_in.to_le_bytes());
+    data.extend_from_slice(&max_quote_amount_in.to_le_bytes());
+    Instruction {
+        program_id: accounts::AMM_PROGRAM,
+        accounts: liquidity_accounts(liquidity),
+        data,
+    }
+}
+
+/// Withdraw liquidity from a PumpSwap pool by burning `lp_token_amount_in` LP tokens
+pub fn withdraw(
+    liquidity: &LiquidityAccounts,
+    lp_token_amount_in: u64,
+    min_base_amount_out: u64,
+    min_quote_amount_out: u64,
+) -> Instruction {
+    let mut data = Vec::with_capacity(8 + 8 * 3);
+    data.extend_from_slice(&WITHDRAW_DISCRIMINATOR);
+    data.extend_from_slice(&lp_token_amount_in.to_le_bytes());
+    data.extend_from_slice(&min_base_amount_out.to_le_bytes());
+    data.extend_from_slice(&min_quote_amount_out.to_le_bytes());
+    Instruction {
+        program_id: accounts::AMM_PROGRAM,
+        accounts: liquidity_accounts(liquidity),
+        data,
+    }
+}
+
+/// Move the coin creator fees accumulated in the creator vault to the creator's
+/// `quote_mint` token account, which must exist
+pub fn collect_coin_creator_fee(coin_creator: &Pubkey, quote_mint: &Pubkey) -> Instruction {
+    let creator_token_account = get_associated_token_address_with_program_id(
+        coin_creator,
+        quote_mint,
+        &accounts::TOKEN_PROGRAM,
+    );
+    Instruction::new_with_bytes(
+        accounts::AMM_PROGRAM,
+        &COLLECT_COIN_CREATOR_FEE_DISCRIMINATOR,
+        vec![
+            AccountMeta::new_readonly(*quote_mint, false), // quote_mint (readonly)
+            AccountMeta::new_readonly(accounts::TOKEN_PROGRAM, false), // quote_token_program (readonly)
+            AccountMeta::new(*coin_creator, true), // coin_creator (signer)
+            AccountMeta::new_readonly(coin_creator_vault_authority(*coin_creator), false), // coin_creator_vault_authority (readonly)
+            AccountMeta::new(coin_creator_vault_ata(*coin_creator, *quote_mint), false), // coin_creator_vault_ata
+            AccountMeta::new(creator_token_account, false), // coin_creator_token_account
+            AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false), // event_authority (readonly)
+            AccountMeta::new_readonly(accounts::AMM_PROGRAM, false), // program (readonly)
+        ],
+    )
+}
@@ -9,9 +9,12 @@ use crate::{
         raydium_amm_v4::{accounts, SWAP_BASE_IN_DISCRIMINATOR},
         trade::trade::DEFAULT_SLIPPAGE,
     },
-    trading::core::{
-        params::{BuyParams, RaydiumAmmV4Params, SellParams},
-        traits::InstructionBuilder,
+    trading::{
+        common::add_create_ata_instruction,
+        core::{
+            params::{BuyParams, RaydiumAmmV4Params, SellParams},
+            traits::InstructionBuilder,
+        },
     },
     utils::calc::raydium_amm_v4::compute_swap_amount,
 };
@@ -62,7 +65,7 @@ impl RaydiumAmmV4InstructionBuilder {
         );
         let minimum_amount_out = swap_result.min_amount_out;
 
-        let mut instructions = vec![];
+        let mut instructions = Vec::with_capacity(super::BUY_INSTRUCTIONS_CAPACITY);
 
         if protocol_params.auto_handle_wsol {
             // Handle wSOL
@@ -87,12 +90,13 @@ impl RaydiumAmmV4InstructionBuilder {
             );
         }
 
-        instructions.push(create_associated_token_account_idempotent(
+        add_create_ata_instruction(
+            &mut instructions,
             &params.payer.pubkey(),
             &params.payer.pubkey(),
             &params.mint,
             &accounts::TOKEN_PROGRAM,
-        ));
+        );
 
         let user_source_token_account = spl_associated_token_account::get_associated_token_address(
             &params.payer.pubkey(),
@@ -10,11 +10,12 @@ use crate::{
         trade::trade::DEFAULT_SLIPPAGE,
     },
     trading::{
+        common::add_create_ata_instruction,
         core::{
             params::{BuyParams, RaydiumCpmmParams, SellParams},
             traits::InstructionBuilder,
         },
-        raydium_cpmm::common::{get_observation_state_pda, get_pool_pda, get_vault_pda},
+        raydium_cpmm::common::{get_observation_state_pda, get_vault_pda},
     },
     utils::calc::raydium_cpmm::compute_swap_amount,
 };
@@ -48,12 +49,9 @@ impl RaydiumCpmmInstructionBuilder {
             .downcast_ref::<RaydiumCpmmParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumCpmm"))?;
 
-        let pool_state = get_pool_pda(
-            &accounts::AMM_CONFIG,
-            &protocol_params.base_mint,
-            &protocol_params.quote_mint,
-        )
-        .unwrap();
+        let (pool_state, amm_config) = protocol_params
+            .pool_keys()
+            .ok_or_else(|| anyhow!("Failed to derive RaydiumCpmm pool state"))?;
 
         let is_base_in = protocol_params.base_mint == accounts::WSOL_TOKEN_ACCOUNT;
         let mint_token_program = if is_base_in {
@@ -89,7 +87,7 @@ impl RaydiumCpmmInstructionBuilder {
         );
         let minimum_amount_out = result.min_amount_out;
 
-        let mut instructions = vec![];
+        let mut instructions = Vec::with_capacity(super::BUY_INSTRUCTIONS_CAPACITY);
 
         if protocol_params.auto_handle_wsol {
             // Handle wSOL
@@ -114,18 +112,19 @@ impl RaydiumCpmmInstructionBuilder {
             );
         }
 
-        instructions.push(create_associated_token_account_idempotent(
+        add_create_ata_instruction(
+            &mut instructions,
             &params.payer.pubkey(),
             &params.payer.pubkey(),
             &params.mint,
             &mint_token_program,
-        ));
+        );
 
         // Create buy instruction
         let accounts = vec![
             solana_sdk::instruction::AccountMeta::new(params.payer.pubkey(), true), // Payer (signer)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::AMM_CONFIG, false), // Amm Config (readonly)
+            solana_sdk::instruction::AccountMeta::new_readonly(amm_config, false), // Amm Config (readonly)
             solana_sdk::instruction::AccountMeta::new(pool_state, false), // Pool State
             solana_sdk::instruction::AccountMeta::new(wsol_token_account, false), // Input Token Account
             solana_sdk::instruction::AccountMeta::new(mint_token_account, false), // Output Token Account
@@ -193,12 +192,9 @@ impl RaydiumCpmmInstructionBuilder {
         )
         .min_amount_out;
 
-        let pool_state = get_pool_pda(
-            &accounts::AMM_CONFIG,
-            &protocol_params.base_mint,
-            &protocol_params.quote_mint,
-        )
-        .unwrap();
+        let (pool_state, amm_config) = protocol_params
+            .pool_keys()
+            .ok_or_else(|| anyhow!("Failed to derive RaydiumCpmm pool state"))?;
 
         let wsol_token_account = spl_associated_token_account::get_associated_token_address(
             &params.payer.pubkey(),
@@ -234,7 +230,7 @@ impl RaydiumCpmmInstructionBuilder {
         let accounts = vec![
             solana_sdk::instruction::AccountMeta::new(params.payer.pubkey(), true), // Payer (signer)
             solana_sdk::instruction::AccountMeta::new_readonly(accounts::AUTHORITY, false), // Authority (readonly)
-            solana_sdk::instruction::AccountMeta::new_readonly(accounts::AMM_CONFIG, false), // Amm Config (readonly)
+            solana_sdk::instruction::AccountMeta::new_readonly(amm_config, false), // Amm Config (readonly)
             solana_sdk::instruction::AccountMeta::new(pool_state, false), // Pool State
             solana_sdk::instruction::AccountMeta::new(mint_token_account, false), // Input Token Account
             solana_sdk::instruction::AccountMeta::new(wsol_token_account, false), // Output Token Account
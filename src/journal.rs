@@ -0,0 +1,579 @@
+//! Trade journal
+//!
+//! [`TradeJournal`] keeps a SQLite record of every live buy and sell `SolanaTrade` executes:
+//! the parameters, the quote, the estimated fees and tip and the outcome, together with the
+//! signature, landing slot and stage latencies reported by the trade's lifecycle events.
+//! The records serve tax reports and strategy analysis:
+//!
+//! ```ignore
+//! let journal = Arc::new(TradeJournal::open("trades.db")?);
+//! let client = client.with_journal(journal.clone());
+//! // ... trade ...
+//! let query = JournalQuery { mint: Some(mint), ..Default::default() };
+//! for entry in journal.entries(&query)? {
+//!     println!("{:?} {} at slot {:?}", entry.side, entry.amount, entry.slot);
+//! }
+//! let totals = journal.totals(&JournalQuery::default())?;
+//! ```
+//!
+//! Entries are written on a blocking thread once the trade returned, a slow disk never holds
+//! up trading. Paper trades are not journaled, their fills are in `PaperTrader::fills`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use rusqlite::{types::Value, Connection, Row};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+use crate::common::TradeResult;
+use crate::swqos::TradeType;
+use crate::trading::{
+    core::{lifecycle, traits::ProtocolParams},
+    factory::DexType,
+    paper::{quote_buy, quote_sell, Side},
+    TradeCostEstimate, TradeLifecycleEvent, TradeStage,
+};
+use crate::SolanaTrade;
+
+/// Lifecycle events of trades that were never journaled are dropped after this long
+const PENDING_TRADE_TTL: Duration = Duration::from_secs(300);
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS trades (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    trade_id INTEGER,
+    timestamp_ms INTEGER NOT NULL,
+    dex TEXT NOT NULL,
+    mint TEXT NOT NULL,
+    side TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    slippage_basis_points INTEGER,
+    quoted_amount INTEGER,
+    swap_lamports INTEGER,
+    protocol_fee_lamports INTEGER,
+    signature_fee_lamports INTEGER,
+    priority_fee_lamports INTEGER,
+    tip_lamports INTEGER,
+    rent_lamports INTEGER,
+    signature TEXT,
+    provider TEXT,
+    slot INTEGER,
+    latency_us INTEGER NOT NULL,
+    stages TEXT NOT NULL,
+    error TEXT
+);
+CREATE INDEX IF NOT EXISTS trades_timestamp ON trades (timestamp_ms);
+CREATE INDEX IF NOT EXISTS trades_mint ON trades (mint, timestamp_ms);
+";
+
+const COLUMNS: &str = "id, trade_id, timestamp_ms, dex, mint, side, amount, \
+    slippage_basis_points, quoted_amount, swap_lamports, protocol_fee_lamports, \
+    signature_fee_lamports, priority_fee_lamports, tip_lamports, rent_lamports, signature, \
+    provider, slot, latency_us, stages, error";
+
+/// One journaled buy or sell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Row id, 0 until recorded
+    pub id: i64,
+    /// Lifecycle trade id, `None` when no lifecycle event of the trade was seen
+    pub trade_id: Option<u64>,
+    /// When the trade returned
+    pub timestamp: SystemTime,
+    pub dex: String,
+    pub mint: Pubkey,
+    pub side: Side,
+    /// Lamports spent by a buy, tokens sold by a sell
+    pub amount: u64,
+    pub slippage_basis_points: Option<u64>,
+    /// Tokens a buy was quoted to receive, lamports a sell was quoted to return
+    pub quoted_amount: Option<u64>,
+    /// Fees and tip as estimated before the trade, see `SolanaTrade::estimate_trade_cost`
+    pub cost: Option<TradeCostEstimate>,
+    /// Signature that landed, or the last one signed when none was seen landing
+    pub signature: Option<Signature>,
+    /// Provider that landed the trade, or the last one it was submitted to
+    pub provider: Option<String>,
+    pub slot: Option<u64>,
+    /// Time `buy` / `sell` took
+    pub latency: Duration,
+    pub stages: Vec<JournalStage>,
+    /// Error of a failed trade
+    pub error: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn new(
+        dex_type: &DexType,
+        mint: Pubkey,
+        side: Side,
+        amount: u64,
+        slippage_basis_points: Option<u64>,
+    ) -> Self {
+        Self {
+            id: 0,
+            trade_id: None,
+            timestamp: SystemTime::now(),
+            dex: dex_type.as_str().to_string(),
+            mint,
+            side,
+            amount,
+            slippage_basis_points,
+            quoted_amount: None,
+            cost: None,
+            signature: None,
+            provider: None,
+            slot: None,
+            latency: Duration::ZERO,
+            stages: vec![],
+            error: None,
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// Duration of one stage of a journaled trade, see `TradeStage::StageTimed`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalStage {
+    pub stage: String,
+    pub provider: Option<String>,
+    pub elapsed: Duration,
+}
+
+/// Filter of `TradeJournal::entries`, every set field must match
+#[derive(Debug, Clone, Default)]
+pub struct JournalQuery {
+    pub mint: Option<Pubkey>,
+    pub side: Option<Side>,
+    /// Trades at or after this time
+    pub since: Option<SystemTime>,
+    /// Trades before this time
+    pub until: Option<SystemTime>,
+    /// Only failed (`true`) or only completed (`false`) trades
+    pub failed: Option<bool>,
+    /// The most recent entries only
+    pub limit: Option<usize>,
+}
+
+/// Sums over journaled trades; amounts and fees count completed trades only
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JournalTotals {
+    pub trades: u64,
+    pub failed: u64,
+    pub bought_lamports: u64,
+    pub sold_tokens: u64,
+    pub protocol_fee_lamports: u64,
+    /// Signature and priority fees
+    pub network_fee_lamports: u64,
+    pub tip_lamports: u64,
+}
+
+impl JournalTotals {
+    pub fn from_entries(entries: &[JournalEntry]) -> Self {
+        let mut totals = Self::default();
+        for entry in entries {
+            totals.trades += 1;
+            if entry.is_failed() {
+                totals.failed += 1;
+                continue;
+            }
+            match entry.side {
+                Side::Buy => totals.bought_lamports += entry.amount,
+                Side::Sell => totals.sold_tokens += entry.amount,
+            }
+            if let Some(cost) = &entry.cost {
+                totals.protocol_fee_lamports += cost.protocol_fee_lamports;
+                totals.network_fee_lamports +=
+                    cost.signature_fee_lamports + cost.priority_fee_lamports;
+                totals.tip_lamports += cost.tip_lamports;
+            }
+        }
+        totals
+    }
+}
+
+/// Lifecycle events of a trade that was not journaled yet
+struct PendingTrade {
+    dex_type: DexType,
+    mint: Pubkey,
+    trade_type: TradeType,
+    signature: Option<Signature>,
+    provider: Option<String>,
+    slot: Option<u64>,
+    landed: bool,
+    stages: Vec<JournalStage>,
+    seen: Instant,
+}
+
+impl PendingTrade {
+    fn new(event: &TradeLifecycleEvent) -> Self {
+        Self {
+            dex_type: event.dex_type.clone(),
+            mint: event.mint,
+            trade_type: event.trade_type,
+            signature: None,
+            provider: None,
+            slot: None,
+            landed: false,
+            stages: vec![],
+            seen: Instant::now(),
+        }
+    }
+
+    fn apply(&mut self, stage: TradeStage) {
+        self.seen = Instant::now();
+        match stage {
+            TradeStage::Signed { signature } if !self.landed => self.signature = Some(signature),
+            TradeStage::Submitted { provider, signature } if !self.landed => {
+                self.signature = Some(signature);
+                self.provider = Some(format!("{:?}", provider));
+            }
+            TradeStage::Landed { provider, signature, slot } => {
+                self.signature = Some(signature);
+                self.provider = Some(format!("{:?}", provider));
+                self.slot = slot;
+                self.landed = true;
+            }
+            TradeStage::StageTimed { stage, provider, elapsed } => {
+                let provider = provider.map(|provider| format!("{:?}", provider));
+                self.stages.push(JournalStage { stage, provider, elapsed });
+            }
+            _ => {}
+        }
+    }
+
+    fn matches(&self, dex_type: &DexType, mint: &Pubkey, side: Side) -> bool {
+        let side_matches = match side {
+            Side::Buy => matches!(self.trade_type, TradeType::Buy),
+            Side::Sell => matches!(self.trade_type, TradeType::Sell),
+        };
+        side_matches && self.dex_type == *dex_type && self.mint == *mint
+    }
+}
+
+struct PendingTrades {
+    events: broadcast::Receiver<TradeLifecycleEvent>,
+    trades: HashMap<u64, PendingTrade>,
+}
+
+/// SQLite journal of executed trades
+pub struct TradeJournal {
+    connection: Mutex<Connection>,
+    pending: Mutex<PendingTrades>,
+}
+
+impl TradeJournal {
+    /// Open or create the journal database at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, anyhow::Error> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Journal kept in memory, gone when dropped
+    pub fn open_in_memory() -> Result<Self, anyhow::Error> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, anyhow::Error> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+            pending: Mutex::new(PendingTrades {
+                events: lifecycle::subscribe(),
+                trades: HashMap::new(),
+            }),
+        })
+    }
+
+    /// Fill in the trade id, signature, slot and stages of `entry` from the lifecycle
+    /// events of the latest matching trade seen since the journal was opened
+    pub fn attach_lifecycle(&self, entry: &mut JournalEntry, dex_type: &DexType) {
+        let mut pending = self.pending.lock().unwrap();
+        loop {
+            match pending.events.try_recv() {
+                Ok(event) => {
+                    let trade = pending
+                        .trades
+                        .entry(event.trade_id)
+                        .or_insert_with(|| PendingTrade::new(&event));
+                    trade.apply(event.stage);
+                }
+                Err(TryRecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "trade journal missed lifecycle events");
+                }
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => break,
+            }
+        }
+        pending.trades.retain(|_, trade| trade.seen.elapsed() < PENDING_TRADE_TTL);
+
+        let trade_id = pending
+            .trades
+            .iter()
+            .filter(|(_, trade)| trade.matches(dex_type, &entry.mint, entry.side))
+            .map(|(trade_id, _)| *trade_id)
+            .max();
+        let Some(trade) = trade_id.and_then(|trade_id| pending.trades.remove(&trade_id)) else {
+            return;
+        };
+        entry.trade_id = trade_id;
+        entry.signature = trade.signature;
+        entry.provider = trade.provider;
+        entry.slot = trade.slot;
+        entry.stages = trade.stages;
+    }
+
+    /// Store `entry`, returning its row id
+    pub fn record(&self, entry: &JournalEntry) -> Result<i64, anyhow::Error> {
+        let cost = entry.cost.as_ref();
+        let values: Vec<Value> = vec![
+            entry.trade_id.map(|trade_id| trade_id as i64).into(),
+            unix_ms(entry.timestamp).into(),
+            entry.dex.clone().into(),
+            entry.mint.to_string().into(),
+            side_str(entry.side).to_string().into(),
+            (entry.amount as i64).into(),
+            entry.slippage_basis_points.map(|bps| bps as i64).into(),
+            entry.quoted_amount.map(|amount| amount as i64).into(),
+            cost.map(|cost| cost.swap_lamports as i64).into(),
+            cost.map(|cost| cost.protocol_fee_lamports as i64).into(),
+            cost.map(|cost| cost.signature_fee_lamports as i64).into(),
+            cost.map(|cost| cost.priority_fee_lamports as i64).into(),
+            cost.map(|cost| cost.tip_lamports as i64).into(),
+            cost.map(|cost| cost.rent_lamports as i64).into(),
+            entry.signature.map(|signature| signature.to_string()).into(),
+            entry.provider.clone().into(),
+            entry.slot.map(|slot| slot as i64).into(),
+            (entry.latency.as_micros() as i64).into(),
+            serde_json::to_string(&entry.stages)?.into(),
+            entry.error.clone().into(),
+        ];
+        let connection = self.connection.lock().unwrap();
+        connection.execute(
+            &format!(
+                "INSERT INTO trades ({}) VALUES (?{})",
+                COLUMNS.trim_start_matches("id, "),
+                ", ?".repeat(values.len() - 1)
+            ),
+            rusqlite::params_from_iter(values),
+        )?;
+        Ok(connection.last_insert_rowid())
+    }
+
+    /// Entries matching `query`, oldest first
+    pub fn entries(&self, query: &JournalQuery) -> Result<Vec<JournalEntry>, anyhow::Error> {
+        let mut conditions: Vec<&str> = vec![];
+        let mut values: Vec<Value> = vec![];
+        if let Some(mint) = &query.mint {
+            conditions.push("mint = ?");
+            values.push(mint.to_string().into());
+        }
+        if let Some(side) = query.side {
+            conditions.push("side = ?");
+            values.push(side_str(side).to_string().into());
+        }
+        if let Some(since) = query.since {
+            conditions.push("timestamp_ms >= ?");
+            values.push(unix_ms(since).into());
+        }
+        if let Some(until) = query.until {
+            conditions.push("timestamp_ms < ?");
+            values.push(unix_ms(until).into());
+        }
+        match query.failed {
+            Some(true) => conditions.push("error IS NOT NULL"),
+            Some(false) => conditions.push("error IS NULL"),
+            None => {}
+        }
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        let limit = query.limit.map_or(-1, |limit| limit as i64);
+        let sql = format!(
+            "SELECT * FROM (SELECT {} FROM trades{} ORDER BY id DESC LIMIT {}) ORDER BY id",
+            COLUMNS, filter, limit
+        );
+
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(&sql)?;
+        let rows =
+            statement.query_map(rusqlite::params_from_iter(values), |row| Ok(read_row(row)))?;
+        rows.map(|row| row?).collect()
+    }
+
+    /// Totals of the entries matching `query`
+    pub fn totals(&self, query: &JournalQuery) -> Result<JournalTotals, anyhow::Error> {
+        Ok(JournalTotals::from_entries(&self.entries(query)?))
+    }
+}
+
+impl SolanaTrade {
+    /// Record every live buy and sell in `journal`
+    pub fn with_journal(mut self, journal: Arc<TradeJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Journal the outcome of a live buy / sell in the background
+    pub(crate) fn journal_trade(
+        &self,
+        mut entry: JournalEntry,
+        dex_type: &DexType,
+        protocol_params: &dyn ProtocolParams,
+        with_tip: bool,
+        latency: Duration,
+        result: &TradeResult<()>,
+    ) {
+        let Some(journal) = self.journal.clone() else {
+            return;
+        };
+        entry.timestamp = SystemTime::now();
+        entry.latency = latency;
+        entry.quoted_amount = match entry.side {
+            Side::Buy => quote_buy(dex_type, protocol_params, entry.amount).ok(),
+            Side::Sell => quote_sell(dex_type, protocol_params, entry.amount).ok(),
+        };
+        entry.cost = self
+            .estimate_trade_cost(dex_type, &entry.mint, entry.side, entry.amount, protocol_params)
+            .ok()
+            .map(
+                |cost| if with_tip { cost } else { TradeCostEstimate { tip_lamports: 0, ..cost } },
+            );
+        entry.error = result.as_ref().err().map(|e| e.to_string());
+        journal.attach_lifecycle(&mut entry, dex_type);
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = journal.record(&entry) {
+                tracing::warn!(mint = %entry.mint, error = %e, "trade journal write failed");
+            }
+        });
+    }
+}
+
+fn side_str(side: Side) -> &'static str {
+    match side {
+        Side::Buy => "buy",
+        Side::Sell => "sell",
+    }
+}
+
+fn unix_ms(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis() as i64)
+}
+
+fn read_row(row: &Row) -> Result<JournalEntry, anyhow::Error> {
+    let optional = |index: usize| -> rusqlite::Result<Option<u64>> {
+        Ok(row.get::<_, Option<i64>>(index)?.map(|value| value as u64))
+    };
+    let cost = match optional(9)? {
+        Some(swap_lamports) => Some(TradeCostEstimate {
+            swap_lamports,
+            protocol_fee_lamports: optional(10)?.unwrap_or(0),
+            signature_fee_lamports: optional(11)?.unwrap_or(0),
+            priority_fee_lamports: optional(12)?.unwrap_or(0),
+            tip_lamports: optional(13)?.unwrap_or(0),
+            rent_lamports: optional(14)?.unwrap_or(0),
+        }),
+        None => None,
+    };
+    let side = match row.get::<_, String>(5)?.as_str() {
+        "buy" => Side::Buy,
+        "sell" => Side::Sell,
+        side => return Err(anyhow!("Unknown journal side {}", side)),
+    };
+    Ok(JournalEntry {
+        id: row.get(0)?,
+        trade_id: optional(1)?,
+        timestamp: UNIX_EPOCH + Duration::from_millis(row.get::<_, i64>(2)?.max(0) as u64),
+        dex: row.get(3)?,
+        mint: row.get::<_, String>(4)?.parse()?,
+        side,
+        amount: row.get::<_, i64>(6)? as u64,
+        slippage_basis_points: optional(7)?,
+        quoted_amount: optional(8)?,
+        cost,
+        signature: row.get::<_, Option<String>>(15)?.map(|s| s.parse()).transpose()?,
+        provider: row.get(16)?,
+        slot: optional(17)?,
+        latency: Duration::from_micros(row.get::<_, i64>(18)?.max(0) as u64),
+        stages: serde_json::from_str(&row.get::<_, String>(19)?)?,
+        error: row.get(20)?,
+    })
+}
+
+#[cfg(all(test, feature = "pumpfun"))]
+mod tests {
+    use super::*;
+    use crate::swqos::SwqosType;
+    use crate::trading::core::lifecycle::TradeLifecycle;
+
+    #[test]
+    fn test_trade_journal() {
+        let journal = TradeJournal::open_in_memory().unwrap();
+        let mint = Pubkey::new_unique();
+        let signature = Signature::new_unique();
+        let lifecycle = TradeLifecycle::new(DexType::PumpFun, mint, TradeType::Buy);
+        lifecycle.emit(TradeStage::Submitted { provider: SwqosType::Jito, signature });
+        lifecycle.emit(TradeStage::StageTimed {
+            stage: "confirm".to_string(),
+            provider: Some(SwqosType::Jito),
+            elapsed: Duration::from_millis(420),
+        });
+
+        let mut buy = JournalEntry::new(&DexType::PumpFun, mint, Side::Buy, 100_000_000, Some(300));
+        buy.cost = Some(TradeCostEstimate {
+            swap_lamports: 99_009_900,
+            protocol_fee_lamports: 990_100,
+            signature_fee_lamports: 5_000,
+            priority_fee_lamports: 20_000,
+            tip_lamports: 1_000_000,
+            rent_lamports: 2_039_280,
+        });
+        journal.attach_lifecycle(&mut buy, &DexType::PumpFun);
+        assert_eq!(buy.trade_id, Some(lifecycle.trade_id()));
+        assert_eq!(buy.signature, Some(signature));
+        assert_eq!(buy.provider.as_deref(), Some("Jito"));
+        assert_eq!(buy.stages.len(), 1);
+        buy.id = journal.record(&buy).unwrap();
+
+        let mut sell = JournalEntry::new(&DexType::PumpFun, mint, Side::Sell, 5_000_000, None);
+        sell.error = Some("slippage exceeded".to_string());
+        sell.id = journal.record(&sell).unwrap();
+        journal
+            .record(&JournalEntry::new(&DexType::PumpFun, Pubkey::new_unique(), Side::Buy, 1, None))
+            .unwrap();
+
+        let query = JournalQuery { mint: Some(mint), ..Default::default() };
+        let mut entries = journal.entries(&query).unwrap();
+        // SQLite keeps milliseconds
+        for (entry, expected) in entries.iter_mut().zip([&buy, &sell]) {
+            entry.timestamp = expected.timestamp;
+        }
+        assert_eq!(entries, vec![buy.clone(), sell.clone()]);
+        let failed = JournalQuery { failed: Some(true), ..query.clone() };
+        assert_eq!(journal.entries(&failed).unwrap()[0].id, sell.id);
+        let latest = JournalQuery { limit: Some(1), ..Default::default() };
+        assert_eq!(journal.entries(&latest).unwrap()[0].amount, 1);
+
+        let totals = journal.totals(&query).unwrap();
+        assert_eq!(
+            totals,
+            JournalTotals {
+                trades: 2,
+                failed: 1,
+                bought_lamports: 100_000_000,
+                sold_tokens: 0,
+                protocol_fee_lamports: 990_100,
+                network_fee_lamports: 25_000,
+                tip_lamports: 1_000_000,
+            }
+        );
+    }
+}
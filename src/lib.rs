@@ -1,43 +1,132 @@
+#[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+pub mod backtest;
+pub mod builder;
 pub mod common;
 pub mod constants;
 pub mod instruction;
+#[cfg(feature = "journal")]
+pub mod journal;
+pub mod metrics;
+pub mod notify;
+pub mod pricing;
+pub mod publish;
+#[cfg(all(feature = "server", feature = "pumpfun", feature = "pumpswap"))]
+pub mod server;
+#[cfg(all(feature = "grpc", feature = "pumpfun", feature = "pumpswap"))]
+pub mod strategy;
+#[cfg(feature = "grpc")]
 pub mod protos;
+#[cfg(all(feature = "grpc", feature = "pumpfun", feature = "pumpswap"))]
+pub mod streaming;
 pub mod swqos;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod trading;
 pub mod utils;
+pub mod wallets;
 pub use solana_streamer_sdk;
+pub use builder::{SolanaTradeBuilder, TradeConfigBuilder};
 
+use crate::swqos::http::SwqosHttpConfig;
+use crate::swqos::latency::SwqosLatency;
+use crate::swqos::solana_rpc::SolRpcClient;
 use crate::swqos::SwqosConfig;
+#[cfg(all(feature = "bonk", feature = "raydium"))]
+use crate::trading::bonk::migration::reroute_if_migrated;
+#[cfg(feature = "bonk")]
 use crate::trading::core::params::BonkParams;
+#[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+use crate::trading::pumpfun::migration::reroute_if_complete;
+#[cfg(feature = "pumpfun")]
 use crate::trading::core::params::PumpFunParams;
+#[cfg(feature = "pumpswap")]
 use crate::trading::core::params::PumpSwapParams;
+#[cfg(feature = "raydium")]
 use crate::trading::core::params::RaydiumAmmV4Params;
+#[cfg(feature = "raydium")]
 use crate::trading::core::params::RaydiumCpmmParams;
+use crate::trading::common::metadata_uploader::{MetadataUploader, MetadataUploaderConfig};
+use crate::trading::common::{ContentionFeeConfig, DataSizeLimit};
+use crate::notify::{Notifications, TradeNotification};
+use crate::pricing::{SolUsdPrice, SolUsdPriceConfig};
 use crate::trading::core::traits::ProtocolParams;
 use crate::trading::factory::DexType;
+use crate::trading::middleware::builtin::CloseTokenAccountMiddleware;
 use crate::trading::BuyParams;
 use crate::trading::MiddlewareManager;
+use crate::trading::{PaperTrader, PaperTradingConfig};
 use crate::trading::SellParams;
+use crate::trading::core::lifecycle::TradeLifecycle;
+use crate::trading::TradeLifecycleEvent;
 use crate::trading::TradeFactory;
-use common::{PriorityFee, SolanaRpcClient, TradeConfig};
+use crate::constants::trade::trade::DEFAULT_SLIPPAGE;
+use crate::trading::paper::Side;
+use crate::utils::calc::common::{calculate_sell_amount_by_bps, calculate_with_slippage_buy};
+use common::rpc_pool::RpcPool;
+use common::{PriorityFee, SolanaRpcClient, TradeConfig, TradeError, TradeResult, TradeSigner};
 use rustls::crypto::{ring::default_provider, CryptoProvider};
 use solana_sdk::hash::Hash;
-use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use solana_sdk::{pubkey::Pubkey, signature::Signature, signer::Signer};
 use std::sync::Arc;
 use std::sync::Mutex;
-use swqos::SwqosClient;
+use std::time::Instant;
+use solana_rpc_client_nonce_utils::nonblocking;
+use swqos::{SwqosClient, SwqosType, TradeType};
+use trading::common::OfflineTransaction;
+use tracing::Instrument;
 
 pub struct SolanaTrade {
-    pub payer: Arc<Keypair>,
+    pub payer: Arc<dyn TradeSigner>,
     pub rpc: Arc<SolanaRpcClient>,
     pub swqos_clients: Vec<Arc<SwqosClient>>,
     pub priority_fee: PriorityFee,
     pub trade_config: TradeConfig,
     pub middleware_manager: Option<Arc<MiddlewareManager>>,
+    pub metadata_uploader: Option<Arc<dyn MetadataUploader>>,
+    pub fee_payer: Option<Arc<dyn TradeSigner>>,
+    pub sol_usd_price: Option<Arc<SolUsdPrice>>,
+    pub paper_trader: Option<Arc<PaperTrader>>,
+    pub notifications: Option<Arc<Notifications>>,
+    /// Check the payer balances before submitting a live trade, disabled by default
+    pub balance_check: bool,
+    /// Trade graduated PumpFun tokens on their PumpSwap pool, disabled by default
+    pub route_migrated_pumpfun: bool,
+    /// `sell_by_bps` sells the whole balance when at most this many base units would remain
+    pub sell_dust_threshold: u64,
+    /// Race one transaction paying every provider's tip, disabled by default
+    pub single_signature_racing: bool,
+    /// Simulate swqos submissions while they are in flight, disabled by default
+    pub simulate_race: bool,
+    /// Price compute units by the fees paid on each trade's accounts, see
+    /// `trading::common::contention_fee`
+    pub contention_fee: Option<ContentionFeeConfig>,
+    /// Loaded accounts data size limit of buys, the executor's fixed default by default
+    pub data_size_limit: DataSizeLimit,
+    /// Journal of the live trades, see `journal`
+    #[cfg(feature = "journal")]
+    pub journal: Option<Arc<journal::TradeJournal>>,
 }
 
 static INSTANCE: Mutex<Option<Arc<SolanaTrade>>> = Mutex::new(None);
 
+/// Span covering one buy/sell, the swqos submissions are recorded as its children
+///
+/// `trade_id` is the id of the trade's `TradeLifecycle`, so spans and lifecycle events of
+/// a trade can be matched.
+fn trade_span(
+    trade_id: u64,
+    dex_type: &DexType,
+    mint: &Pubkey,
+    side: &'static str,
+) -> tracing::Span {
+    tracing::info_span!("trade", trade_id, dex = %dex_type, %mint, side)
+}
+
+/// A proxy from `TradeConfig` the HTTP client rejected
+fn invalid_proxy(err: anyhow::Error) -> TradeError {
+    TradeError::InvalidParams(format!("invalid proxy configuration: {}", err))
+}
+
 impl Clone for SolanaTrade {
     fn clone(&self) -> Self {
         Self {
@@ -47,13 +136,31 @@ impl Clone for SolanaTrade {
             priority_fee: self.priority_fee.clone(),
             trade_config: self.trade_config.clone(),
             middleware_manager: self.middleware_manager.clone(),
+            metadata_uploader: self.metadata_uploader.clone(),
+            fee_payer: self.fee_payer.clone(),
+            sol_usd_price: self.sol_usd_price.clone(),
+            paper_trader: self.paper_trader.clone(),
+            notifications: self.notifications.clone(),
+            balance_check: self.balance_check,
+            route_migrated_pumpfun: self.route_migrated_pumpfun,
+            sell_dust_threshold: self.sell_dust_threshold,
+            single_signature_racing: self.single_signature_racing,
+            simulate_race: self.simulate_race,
+            contention_fee: self.contention_fee,
+            data_size_limit: self.data_size_limit,
+            #[cfg(feature = "journal")]
+            journal: self.journal.clone(),
         }
     }
 }
 
 impl SolanaTrade {
+    /// Create the client, failing when the RPC pool or a proxy in `trade_config` is invalid
     #[inline]
-    pub async fn new(payer: Arc<Keypair>, mut trade_config: TradeConfig) -> Self {
+    pub async fn new(
+        payer: Arc<dyn TradeSigner>,
+        mut trade_config: TradeConfig,
+    ) -> TradeResult<Self> {
         if CryptoProvider::get_default().is_none() {
             let _ = default_provider()
                 .install_default()
@@ -63,7 +170,7 @@ impl SolanaTrade {
         let rpc_url = trade_config.rpc_url.clone();
         let swqos_configs = trade_config.swqos_configs.clone();
         let mut priority_fee = trade_config.priority_fee.clone();
-        let commitment = trade_config.commitment.clone();
+        let commitment = trade_config.commitment;
         if priority_fee.buy_tip_fees.len() < swqos_configs.len() {
             // 补齐数组,只补齐缺少的
             let mut buy_tip_fees = priority_fee.buy_tip_fees.clone();
@@ -79,16 +186,78 @@ impl SolanaTrade {
             trade_config.priority_fee = priority_fee.clone();
         }
 
+        let proxy = trade_config.proxy.clone();
+        let rpc = match &trade_config.rpc_pool {
+            Some(pool_config) => {
+                let mut pool_config = pool_config.clone();
+                pool_config.proxy = pool_config.proxy.or_else(|| proxy.clone());
+                // 至少有主 RPC，失败只可能是代理配置无效
+                let pool = RpcPool::with_primary(&rpc_url, pool_config.clone()).map_err(|e| {
+                    TradeError::InvalidParams(format!("invalid rpc pool configuration: {}", e))
+                })?;
+                if let Some(interval) = pool_config.health_check_interval {
+                    pool.spawn_health_check(interval);
+                }
+                Arc::new(pool.client(commitment))
+            }
+            None => Arc::new(match &proxy {
+                Some(proxy) => common::proxy::proxied_rpc_client(&rpc_url, commitment, proxy)
+                    .map_err(invalid_proxy)?,
+                None => SolanaRpcClient::new_with_commitment(rpc_url.clone(), commitment),
+            }),
+        };
+
+        common::runtime::set_core_affinity(&trade_config.core_affinity);
+        swqos::common::set_confirmation_config(trade_config.confirmation);
+
         let mut swqos_clients: Vec<Arc<SwqosClient>> = vec![];
 
+        let shared_http_client = match (&trade_config.swqos_http_client, &proxy) {
+            (Some(http_client), _) => Some(http_client.clone()),
+            (None, Some(proxy)) => Some(
+                SwqosHttpConfig { proxy: Some(proxy.clone()), ..Default::default() }
+                    .build()
+                    .map_err(invalid_proxy)?,
+            ),
+            (None, None) => None,
+        };
         for swqos in swqos_configs {
-            let swqos_client =
-                SwqosConfig::get_swqos_client(rpc_url.clone(), commitment.clone(), swqos.clone());
+            let swqos_proxy = trade_config
+                .swqos_proxies
+                .iter()
+                .find(|(swqos_type, _)| *swqos_type == swqos.swqos_type())
+                .map(|(_, swqos_proxy)| swqos_proxy);
+            let swqos_client = match (&swqos, swqos_proxy.or(proxy.as_ref())) {
+                // 默认 RPC 提交使用独立的 RPC 客户端，同样走代理
+                (SwqosConfig::Default(endpoint), Some(swqos_proxy)) => {
+                    let rpc = common::proxy::proxied_rpc_client(endpoint, commitment, swqos_proxy)
+                        .map_err(invalid_proxy)?;
+                    Arc::new(SolRpcClient::new(Arc::new(rpc))) as Arc<SwqosClient>
+                }
+                _ => {
+                    let http_client = match swqos_proxy {
+                        Some(swqos_proxy) => {
+                            let config = SwqosHttpConfig {
+                                proxy: Some(swqos_proxy.clone()),
+                                ..Default::default()
+                            };
+                            Some(config.build().map_err(invalid_proxy)?)
+                        }
+                        None => shared_http_client.clone(),
+                    };
+                    SwqosConfig::build_swqos_client(
+                        rpc.clone(),
+                        commitment,
+                        swqos.clone(),
+                        http_client,
+                        trade_config.jito_simulate_bundles,
+                    )
+                    .map_err(|e| TradeError::InvalidParams(e.to_string()))?
+                }
+            };
             swqos_clients.push(swqos_client);
         }
 
-        let rpc = Arc::new(SolanaRpcClient::new_with_commitment(rpc_url.clone(), commitment));
-
         let instance = Self {
             payer,
             rpc,
@@ -96,12 +265,31 @@ impl SolanaTrade {
             priority_fee,
             trade_config: trade_config.clone(),
             middleware_manager: None,
+            metadata_uploader: None,
+            fee_payer: None,
+            sol_usd_price: None,
+            paper_trader: None,
+            notifications: None,
+            balance_check: false,
+            route_migrated_pumpfun: false,
+            sell_dust_threshold: 0,
+            single_signature_racing: false,
+            simulate_race: false,
+            contention_fee: None,
+            data_size_limit: DataSizeLimit::default(),
+            #[cfg(feature = "journal")]
+            journal: None,
         };
 
         let mut current = INSTANCE.lock().unwrap();
         *current = Some(Arc::new(instance.clone()));
 
-        instance
+        Ok(instance)
+    }
+
+    /// Start a `SolanaTradeBuilder` for the given payer and RPC URL
+    pub fn builder(payer: Arc<dyn TradeSigner>, rpc_url: impl Into<String>) -> SolanaTradeBuilder {
+        SolanaTradeBuilder::new(payer, rpc_url)
     }
 
     pub fn with_middleware_manager(mut self, middleware_manager: MiddlewareManager) -> Self {
@@ -109,6 +297,290 @@ impl SolanaTrade {
         self
     }
 
+    /// Pay transaction fees and tips from a separate wallet
+    ///
+    /// The payer keeps owning the token accounts and signs as token owner; the fee payer
+    /// becomes the transaction fee payer and funds the swqos tips.
+    pub fn with_fee_payer(mut self, fee_payer: Arc<dyn TradeSigner>) -> Self {
+        self.fee_payer = Some(fee_payer);
+        self
+    }
+
+    /// Use the given backend (Pinata, NFT.Storage or Irys) for token metadata uploads
+    ///
+    /// Uploads go through `TradeConfig::proxy`, failing when it is invalid.
+    pub fn with_metadata_uploader(mut self, config: MetadataUploaderConfig) -> TradeResult<Self> {
+        self.metadata_uploader = Some(match &self.trade_config.proxy {
+            Some(proxy) => config.build_with_proxy(proxy).map_err(invalid_proxy)?,
+            None => config.build(),
+        });
+        Ok(self)
+    }
+
+    /// Keep a SOL/USD price updated in the background, must be called inside a tokio runtime
+    pub fn with_sol_usd_price(mut self, config: SolUsdPriceConfig) -> TradeResult<Self> {
+        self.sol_usd_price = Some(SolUsdPrice::start(config)?);
+        Ok(self)
+    }
+
+    /// Latest SOL/USD price, `None` without a price feed or when the price is stale
+    pub fn sol_usd(&self) -> Option<f64> {
+        self.sol_usd_price.as_ref().and_then(|feed| feed.price())
+    }
+
+    /// Paper trade: `buy` and `sell` are simulated and filled virtually, nothing is sent
+    ///
+    /// The fills and positions are available through `paper_trader`.
+    pub fn with_paper_trading(mut self, config: PaperTradingConfig) -> Self {
+        self.paper_trader = Some(Arc::new(PaperTrader::new(config)));
+        self
+    }
+
+    /// Send `Filled` / `Failed` notifications for every buy and sell
+    pub fn with_notifications(mut self, notifications: Notifications) -> Self {
+        self.notifications = Some(Arc::new(notifications));
+        self
+    }
+
+    /// Stop the SDK's background tasks (Node1 ping, RPC health checks, price refresh) and
+    /// wait until they are gone, see `common::task_manager`
+    pub async fn shutdown(&self) {
+        common::task_manager::TaskManager::get_instance().shutdown().await;
+    }
+
+    /// Receive the lifecycle events of every trade executed from now on
+    ///
+    /// The channel is process wide, see `trading::core::lifecycle`.
+    pub fn subscribe_trade_events() -> tokio::sync::broadcast::Receiver<TradeLifecycleEvent> {
+        trading::core::lifecycle::subscribe()
+    }
+
+    /// Check that the payer can afford a trade before it is submitted, see `check_balance`
+    ///
+    /// The check costs one or two RPC round trips per trade before anything is sent, which is
+    /// why it is off by default.
+    pub fn with_balance_check(mut self, enabled: bool) -> Self {
+        self.balance_check = enabled;
+        self
+    }
+
+    /// Trade PumpFun tokens whose bonding curve is complete on their PumpSwap pool
+    ///
+    /// Without it such trades fail with `TradeError::CurveComplete`, carrying the PumpSwap
+    /// pool when the token has migrated. With it a buy or sell is sent to PumpSwap when the
+    /// given params show a complete curve, and resent there once when the curve completed
+    /// before the transaction landed.
+    pub fn with_pumpfun_migration_routing(mut self, enabled: bool) -> Self {
+        self.route_migrated_pumpfun = enabled;
+        self
+    }
+
+    /// Race a single transaction through all swqos providers instead of one per provider
+    ///
+    /// By default every provider gets its own transaction tipping only that provider, so in
+    /// edge cases more than one of them can land and the trade executes twice. With this
+    /// enabled one transaction, signed once, pays the tip of every provider and is submitted
+    /// through all of them; its single signature can land only once. The price is the sum of
+    /// the tips instead of the largest one.
+    pub fn with_single_signature_racing(mut self, enabled: bool) -> Self {
+        self.single_signature_racing = enabled;
+        self
+    }
+
+    /// Simulate each swqos trade concurrently with its submission
+    ///
+    /// When the simulation fails with a program error (slippage, a complete curve, ...)
+    /// before the transaction landed, the trade fails right away with
+    /// `TradeError::PredictedFailure` instead of waiting for the confirmation to time out, and
+    /// the submissions not yet sent are dropped. Only applies to trades that wait for
+    /// confirmation; costs one extra RPC request per trade.
+    pub fn with_simulate_race(mut self, enabled: bool) -> Self {
+        self.simulate_race = enabled;
+        self
+    }
+
+    /// Price compute units by what transactions locking the trade's accounts pay
+    ///
+    /// Before a swqos trade is sent, `getRecentPrioritizationFees` is asked for the writable
+    /// accounts of its instructions (the bonding curve or pool, the vaults, ...) and the
+    /// `tip_unit_price` / `rpc_unit_price` are raised to the configured percentile of the
+    /// answer, up to `max_unit_price`. Costs one RPC round trip per trade.
+    pub fn with_contention_fees(mut self, config: ContentionFeeConfig) -> Self {
+        self.contention_fee = Some(config);
+        self
+    }
+
+    /// Set the loaded accounts data size limit of buys
+    ///
+    /// `DataSizeLimit::Auto` sizes the limit to the accounts each buy loads, fetched once
+    /// and cached in `AccountSizeCache`, plus headroom; a lower limit costs less and is
+    /// scheduled earlier. `DataSizeLimit::Fixed` is the override when the estimate falls
+    /// short, e.g. with middlewares that add accounts; `Fixed(0)` is the 256KB default.
+    pub fn with_data_size_limit(mut self, data_size_limit: DataSizeLimit) -> Self {
+        self.data_size_limit = data_size_limit;
+        self
+    }
+
+    /// Sell the whole balance in `sell_by_bps` / `sell_by_percent` when at most
+    /// `dust_threshold` base units would be left over, 0 by default
+    pub fn with_sell_dust_threshold(mut self, dust_threshold: u64) -> Self {
+        self.sell_dust_threshold = dust_threshold;
+        self
+    }
+
+    /// Fill in the PumpSwap pool of a `CurveComplete` error
+    #[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+    async fn with_migrated_pool(&self, result: TradeResult<()>) -> TradeResult<()> {
+        use trading::pumpfun::migration::find_migrated_pool;
+
+        match result {
+            Err(TradeError::CurveComplete { mint: Some(mint), migrated_pool: None }) => {
+                let migrated_pool = find_migrated_pool(&self.rpc, &mint).await.ok().flatten();
+                Err(TradeError::CurveComplete { mint: Some(mint), migrated_pool })
+            }
+            result => result,
+        }
+    }
+
+    /// Fail with `InsufficientBalance` instead of submitting a trade that cannot succeed
+    ///
+    /// The payer must hold `sol_cost` lamports plus, unless a separate fee payer pays them,
+    /// the fees and tip of the most expensive submission, and for sells `token_amount`
+    /// tokens of `mint`. Submitting anyway would only burn the tip and the fees.
+    async fn check_balance(
+        &self,
+        mint: &Pubkey,
+        sol_cost: u64,
+        fee_lamports: u64,
+        token_amount: Option<u64>,
+    ) -> TradeResult<()> {
+        use trading::common::utils::{get_sol_balance, get_token_balance};
+
+        let payer = self.payer.pubkey();
+        if let Some(token_amount) = token_amount {
+            let available = get_token_balance(&self.rpc, &payer, mint).await?;
+            if available < token_amount {
+                return Err(TradeError::InsufficientBalance { needed: token_amount, available });
+            }
+        }
+        let payer_needed = match &self.fee_payer {
+            Some(fee_payer) => {
+                let available = get_sol_balance(&self.rpc, &fee_payer.pubkey()).await?;
+                if available < fee_lamports {
+                    return Err(TradeError::InsufficientBalance {
+                        needed: fee_lamports,
+                        available,
+                    });
+                }
+                sol_cost
+            }
+            None => sol_cost.saturating_add(fee_lamports),
+        };
+        if payer_needed > 0 {
+            let available = get_sol_balance(&self.rpc, &payer).await?;
+            if available < payer_needed {
+                return Err(TradeError::InsufficientBalance { needed: payer_needed, available });
+            }
+        }
+        Ok(())
+    }
+
+    /// Lamports a trade takes from the payer besides the fees
+    ///
+    /// `sol_amount` is the most a buy spends, zero for sells. Added is the rent of the
+    /// accounts the trade creates (see `rent_lamports`). The known balance of a persistent
+    /// PumpSwap WSOL account pays for the buy first.
+    fn sol_cost(&self, dex_type: &DexType, mint: &Pubkey, sol_amount: u64) -> u64 {
+        #[cfg(feature = "pumpswap")]
+        use common::wsol_cache::{WsolCache, WsolMode};
+
+        let rent = self.rent_lamports(dex_type, mint, sol_amount > 0);
+        #[cfg(feature = "pumpswap")]
+        if let (DexType::PumpSwap, WsolMode::Persistent { top_up_lamports }) =
+            (dex_type, WsolCache::get_instance().get_mode())
+        {
+            // Same top up as `WsolCache::reserve` will transfer
+            let balance = WsolCache::get_instance().balance(&self.payer.pubkey()).unwrap_or(0);
+            let top_up =
+                if balance >= sol_amount { 0 } else { (sol_amount - balance).max(top_up_lamports) };
+            return top_up.saturating_add(rent);
+        }
+        sol_amount.saturating_add(rent)
+    }
+
+    /// Rent of the accounts a trade creates
+    ///
+    /// That is the mint ATA a buy creates and the temporary WSOL account of the pool based
+    /// protocols, which is refunded when the account is closed. A persistent PumpSwap WSOL
+    /// account needs no rent.
+    pub(crate) fn rent_lamports(&self, dex_type: &DexType, mint: &Pubkey, is_buy: bool) -> u64 {
+        use common::ata_cache::AtaCache;
+        #[cfg(feature = "pumpswap")]
+        use common::wsol_cache::{WsolCache, WsolMode};
+        use trading::common::utils::TOKEN_ACCOUNT_RENT;
+
+        #[cfg(not(any(feature = "pumpfun", feature = "pumpswap")))]
+        let _ = dex_type;
+
+        let mut rent = 0;
+        if is_buy && AtaCache::get_instance().needs_create(&self.payer.pubkey(), mint) {
+            rent += TOKEN_ACCOUNT_RENT;
+        }
+        #[cfg(feature = "pumpfun")]
+        if matches!(dex_type, DexType::PumpFun) {
+            return rent;
+        }
+        #[cfg(feature = "pumpswap")]
+        if let (DexType::PumpSwap, WsolMode::Persistent { .. }) =
+            (dex_type, WsolCache::get_instance().get_mode())
+        {
+            return rent;
+        }
+        rent + TOKEN_ACCOUNT_RENT
+    }
+
+    /// Notify the outcome of a buy / sell, `amount` is lamports for buys and tokens for sells
+    fn notify_trade(
+        &self,
+        dex_type: &DexType,
+        mint: Pubkey,
+        side: &'static str,
+        amount: u64,
+        result: &TradeResult<()>,
+    ) {
+        if let Some(notifications) = &self.notifications {
+            let notification = TradeNotification::for_trade(dex_type, mint, side, amount, result);
+            notifications.notify(notification);
+        }
+    }
+
+    /// Whether any swqos client adds a tip to its transactions
+    fn sends_with_tip(&self) -> bool {
+        self.swqos_clients.iter().any(|client| client.get_swqos_type() != SwqosType::Default)
+    }
+
+    /// The swqos client whose provider confirmed fastest lately, see `swqos::latency`
+    ///
+    /// Before any provider landed a transaction the first tipping client is picked, then the
+    /// first client.
+    pub fn fastest_swqos_client(&self) -> Option<Arc<SwqosClient>> {
+        let types: Vec<_> =
+            self.swqos_clients.iter().map(|client| client.get_swqos_type()).collect();
+        let index = SwqosLatency::get_instance()
+            .fastest(&types)
+            .and_then(|fastest| types.iter().position(|swqos_type| swqos_type == fastest))
+            .or_else(|| types.iter().position(|swqos_type| *swqos_type != SwqosType::Default))
+            .unwrap_or(0);
+        self.swqos_clients.get(index).cloned()
+    }
+
+    /// Wait until the first of the racing `signatures` confirms, polling all of them at once
+    /// as set by `TradeConfigBuilder::with_confirmation`
+    pub async fn confirm_any(&self, signatures: &[Signature]) -> TradeResult<Signature> {
+        let config = swqos::common::confirmation_config();
+        Ok(swqos::common::poll_signatures_confirmation(&self.rpc, signatures, &config).await?)
+    }
+
     /// Get the RPC client instance
     pub fn get_rpc(&self) -> &Arc<SolanaRpcClient> {
         &self.rpc
@@ -159,56 +631,150 @@ impl SolanaTrade {
         extension_params: Box<dyn ProtocolParams>,
         lookup_table_key: Option<Pubkey>,
         wait_transaction_confirmed: bool,
-    ) -> Result<(), anyhow::Error> {
+    ) -> TradeResult<()> {
+        // Graduated Bonk tokens are traded on their Raydium pool
+        #[cfg(all(feature = "bonk", feature = "raydium"))]
+        let (dex_type, extension_params) =
+            reroute_if_migrated(&self.rpc, dex_type, &mint, extension_params).await?;
+        // Graduated PumpFun tokens are traded on PumpSwap when routing is enabled
+        #[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+        let (dex_type, extension_params) = if self.route_migrated_pumpfun {
+            reroute_if_complete(&self.rpc, dex_type, &mint, extension_params).await?
+        } else {
+            (dex_type, extension_params)
+        };
         let executor = TradeFactory::create_executor(dex_type.clone());
         let protocol_params = extension_params;
 
         let final_lookup_table_key = lookup_table_key.or(self.trade_config.lookup_table_key);
 
+        let lifecycle = TradeLifecycle::new(dex_type.clone(), mint, TradeType::Buy);
         let mut buy_params = BuyParams {
             rpc: Some(self.rpc.clone()),
             payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
             mint: mint,
             sol_amount: sol_amount,
             slippage_basis_points: slippage_basis_points,
             priority_fee: self.trade_config.priority_fee.clone(),
             lookup_table_key: final_lookup_table_key,
             recent_blockhash,
-            data_size_limit: 0,
+            data_size_limit: self.data_size_limit.fixed_limit(),
             wait_transaction_confirmed: wait_transaction_confirmed,
             protocol_params: protocol_params.clone(),
+            lifecycle: Some(lifecycle.clone()),
         };
-        if custom_buy_tip_fee.is_some() {
-            buy_params.priority_fee.buy_tip_fee = custom_buy_tip_fee.unwrap();
-            buy_params.priority_fee.buy_tip_fees = buy_params
-                .priority_fee
-                .buy_tip_fees
-                .iter()
-                .map(|_| custom_buy_tip_fee.unwrap())
-                .collect();
+        if let Some(custom_buy_tip_fee) = custom_buy_tip_fee {
+            buy_params.priority_fee.buy_tip_fee = custom_buy_tip_fee;
+            buy_params.priority_fee.buy_tip_fees =
+                buy_params.priority_fee.buy_tip_fees.iter().map(|_| custom_buy_tip_fee).collect();
+        }
+        let mut buy_with_tip_params = buy_params.clone().with_tip(self.swqos_clients.clone());
+        buy_with_tip_params.single_signature = self.single_signature_racing;
+        buy_with_tip_params.simulate_race = self.simulate_race;
+        buy_with_tip_params.contention_fee = self.contention_fee;
+        if let DataSizeLimit::Auto { headroom_bps } = self.data_size_limit {
+            buy_with_tip_params.data_size_headroom_bps = Some(headroom_bps);
         }
-        let buy_with_tip_params = buy_params.clone().with_tip(self.swqos_clients.clone());
 
         // Validate protocol params
         let is_valid_params = match dex_type {
+            #[cfg(feature = "pumpfun")]
             DexType::PumpFun => protocol_params.as_any().downcast_ref::<PumpFunParams>().is_some(),
+            #[cfg(feature = "pumpswap")]
             DexType::PumpSwap => {
                 protocol_params.as_any().downcast_ref::<PumpSwapParams>().is_some()
             }
+            #[cfg(feature = "bonk")]
             DexType::Bonk => protocol_params.as_any().downcast_ref::<BonkParams>().is_some(),
+            #[cfg(feature = "raydium")]
             DexType::RaydiumCpmm => {
                 protocol_params.as_any().downcast_ref::<RaydiumCpmmParams>().is_some()
             }
+            #[cfg(feature = "raydium")]
             DexType::RaydiumAmmV4 => {
                 protocol_params.as_any().downcast_ref::<RaydiumAmmV4Params>().is_some()
             }
         };
 
         if !is_valid_params {
-            return Err(anyhow::anyhow!("Invalid protocol params for Trade"));
+            return Err(TradeError::InvalidParams("Invalid protocol params for Trade".to_string()));
         }
 
-        executor.buy_with_tip(buy_with_tip_params, self.middleware_manager.clone()).await
+        if let Some(paper_trader) = &self.paper_trader {
+            let fee_lamports = self
+                .network_fees(&buy_params.priority_fee, Side::Buy, self.sends_with_tip())
+                .fees();
+            let result = paper_trader
+                .buy(dex_type.clone(), buy_params, fee_lamports, self.middleware_manager.clone())
+                .instrument(trade_span(lifecycle.trade_id(), &dex_type, &mint, "buy"))
+                .await
+                .map(|_| ())
+                .map_err(|e| TradeError::from(e).for_trade(&dex_type, &mint));
+            self.notify_trade(&dex_type, mint, "buy", sol_amount, &result);
+            return result;
+        }
+
+        if self.balance_check {
+            let slippage = slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
+            let max_sol_cost = calculate_with_slippage_buy(sol_amount, slippage)
+                .map_err(TradeError::InvalidParams)?;
+            let fee_lamports = self
+                .network_fees(&buy_with_tip_params.priority_fee, Side::Buy, self.sends_with_tip())
+                .fees();
+            let sol_cost = self.sol_cost(&dex_type, &mint, max_sol_cost);
+            self.check_balance(&mint, sol_cost, fee_lamports, None).await?;
+        }
+
+        let start_time = Instant::now();
+        let result = executor
+            .buy_with_tip(buy_with_tip_params, self.middleware_manager.clone())
+            .instrument(trade_span(lifecycle.trade_id(), &dex_type, &mint, "buy"))
+            .await
+            .map_err(|e| TradeError::from(e).for_trade(&dex_type, &mint));
+        #[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+        let result = self.with_migrated_pool(result).await;
+        metrics::record_trade(&dex_type, "buy", start_time.elapsed(), &result);
+        self.notify_trade(&dex_type, mint, "buy", sol_amount, &result);
+        #[cfg(feature = "journal")]
+        self.journal_trade(
+            journal::JournalEntry::new(
+                &dex_type,
+                mint,
+                trading::paper::Side::Buy,
+                sol_amount,
+                slippage_basis_points,
+            ),
+            &dex_type,
+            protocol_params.as_ref(),
+            self.sends_with_tip(),
+            start_time.elapsed(),
+            &result,
+        );
+        if result.is_err() {
+            // The estimated persistent WSOL balance assumed the trade went through
+            common::wsol_cache::WsolCache::get_instance().invalidate(&self.payer.pubkey());
+        }
+        // The curve completed after the params were fetched, buy on PumpSwap instead
+        #[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+        if let Err(TradeError::CurveComplete { migrated_pool: Some(pool), .. }) = &result {
+            if self.route_migrated_pumpfun {
+                let params = PumpSwapParams::from_pool_address_by_rpc(&self.rpc, pool).await?;
+                return Box::pin(self.buy(
+                    DexType::PumpSwap,
+                    mint,
+                    sol_amount,
+                    slippage_basis_points,
+                    recent_blockhash,
+                    custom_buy_tip_fee,
+                    Box::new(params),
+                    lookup_table_key,
+                    wait_transaction_confirmed,
+                ))
+                .await;
+            }
+        }
+        result
     }
 
     /// Execute a sell order for a specified token
@@ -250,15 +816,28 @@ impl SolanaTrade {
         extension_params: Box<dyn ProtocolParams>,
         lookup_table_key: Option<Pubkey>,
         wait_transaction_confirmed: bool,
-    ) -> Result<(), anyhow::Error> {
+    ) -> TradeResult<()> {
+        // Graduated Bonk tokens are traded on their Raydium pool
+        #[cfg(all(feature = "bonk", feature = "raydium"))]
+        let (dex_type, extension_params) =
+            reroute_if_migrated(&self.rpc, dex_type, &mint, extension_params).await?;
+        // Graduated PumpFun tokens are traded on PumpSwap when routing is enabled
+        #[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+        let (dex_type, extension_params) = if self.route_migrated_pumpfun {
+            reroute_if_complete(&self.rpc, dex_type, &mint, extension_params).await?
+        } else {
+            (dex_type, extension_params)
+        };
         let executor = TradeFactory::create_executor(dex_type.clone());
         let protocol_params = extension_params;
 
         let final_lookup_table_key = lookup_table_key.or(self.trade_config.lookup_table_key);
 
+        let lifecycle = TradeLifecycle::new(dex_type.clone(), mint, TradeType::Sell);
         let mut sell_params = SellParams {
             rpc: Some(self.rpc.clone()),
             payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
             mint: mint,
             token_amount: Some(token_amount),
             slippage_basis_points: slippage_basis_points,
@@ -267,49 +846,468 @@ impl SolanaTrade {
             recent_blockhash,
             wait_transaction_confirmed: wait_transaction_confirmed,
             protocol_params: protocol_params.clone(),
+            lifecycle: Some(lifecycle.clone()),
         };
-        if custom_buy_tip_fee.is_some() {
-            sell_params.priority_fee.buy_tip_fee = custom_buy_tip_fee.unwrap();
-            sell_params.priority_fee.buy_tip_fees = sell_params
-                .priority_fee
-                .buy_tip_fees
-                .iter()
-                .map(|_| custom_buy_tip_fee.unwrap())
-                .collect();
+        if let Some(custom_buy_tip_fee) = custom_buy_tip_fee {
+            sell_params.priority_fee.buy_tip_fee = custom_buy_tip_fee;
+            sell_params.priority_fee.buy_tip_fees =
+                sell_params.priority_fee.buy_tip_fees.iter().map(|_| custom_buy_tip_fee).collect();
         }
-        let sell_with_tip_params = sell_params.clone().with_tip(self.swqos_clients.clone());
+        let mut sell_with_tip_params = sell_params.clone().with_tip(self.swqos_clients.clone());
+        sell_with_tip_params.single_signature = self.single_signature_racing;
+        sell_with_tip_params.simulate_race = self.simulate_race;
+        sell_with_tip_params.contention_fee = self.contention_fee;
 
         // Validate protocol params
         let is_valid_params = match dex_type {
+            #[cfg(feature = "pumpfun")]
             DexType::PumpFun => protocol_params.as_any().downcast_ref::<PumpFunParams>().is_some(),
+            #[cfg(feature = "pumpswap")]
             DexType::PumpSwap => {
                 protocol_params.as_any().downcast_ref::<PumpSwapParams>().is_some()
             }
+            #[cfg(feature = "bonk")]
             DexType::Bonk => protocol_params.as_any().downcast_ref::<BonkParams>().is_some(),
+            #[cfg(feature = "raydium")]
             DexType::RaydiumCpmm => {
                 protocol_params.as_any().downcast_ref::<RaydiumCpmmParams>().is_some()
             }
+            #[cfg(feature = "raydium")]
             DexType::RaydiumAmmV4 => {
                 protocol_params.as_any().downcast_ref::<RaydiumAmmV4Params>().is_some()
             }
         };
 
         if !is_valid_params {
-            return Err(anyhow::anyhow!("Invalid protocol params for Trade"));
+            return Err(TradeError::InvalidParams("Invalid protocol params for Trade".to_string()));
         }
 
         // Execute sell based on tip preference
-        if with_tip {
-            executor.sell_with_tip(sell_with_tip_params, self.middleware_manager.clone()).await
+        let span = trade_span(lifecycle.trade_id(), &dex_type, &mint, "sell");
+        if let Some(paper_trader) = &self.paper_trader {
+            let with_tip = with_tip && self.sends_with_tip();
+            let fee_lamports =
+                self.network_fees(&sell_params.priority_fee, Side::Sell, with_tip).fees();
+            let result = paper_trader
+                .sell(dex_type.clone(), sell_params, fee_lamports, self.middleware_manager.clone())
+                .instrument(span)
+                .await
+                .map(|_| ())
+                .map_err(|e| TradeError::from(e).for_trade(&dex_type, &mint));
+            self.notify_trade(&dex_type, mint, "sell", token_amount, &result);
+            return result;
+        }
+        if self.balance_check {
+            let with_tip = with_tip && self.sends_with_tip();
+            let fee_lamports =
+                self.network_fees(&sell_params.priority_fee, Side::Sell, with_tip).fees();
+            let sol_cost = self.sol_cost(&dex_type, &mint, 0);
+            self.check_balance(&mint, sol_cost, fee_lamports, Some(token_amount)).await?;
+        }
+        let start_time = Instant::now();
+        let result = if with_tip {
+            executor
+                .sell_with_tip(sell_with_tip_params, self.middleware_manager.clone())
+                .instrument(span)
+                .await
         } else {
-            executor.sell(sell_params, self.middleware_manager.clone()).await
+            executor.sell(sell_params, self.middleware_manager.clone()).instrument(span).await
+        };
+        let result = result.map_err(|e| TradeError::from(e).for_trade(&dex_type, &mint));
+        #[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+        let result = self.with_migrated_pool(result).await;
+        metrics::record_trade(&dex_type, "sell", start_time.elapsed(), &result);
+        self.notify_trade(&dex_type, mint, "sell", token_amount, &result);
+        #[cfg(feature = "journal")]
+        self.journal_trade(
+            journal::JournalEntry::new(
+                &dex_type,
+                mint,
+                trading::paper::Side::Sell,
+                token_amount,
+                slippage_basis_points,
+            ),
+            &dex_type,
+            protocol_params.as_ref(),
+            with_tip && self.sends_with_tip(),
+            start_time.elapsed(),
+            &result,
+        );
+        if result.is_err() {
+            // The estimated persistent WSOL balance assumed the trade went through
+            common::wsol_cache::WsolCache::get_instance().invalidate(&self.payer.pubkey());
+        }
+        // The curve completed after the params were fetched, sell on PumpSwap instead
+        #[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+        if let Err(TradeError::CurveComplete { migrated_pool: Some(pool), .. }) = &result {
+            if self.route_migrated_pumpfun {
+                let params = PumpSwapParams::from_pool_address_by_rpc(&self.rpc, pool).await?;
+                return Box::pin(self.sell(
+                    DexType::PumpSwap,
+                    mint,
+                    token_amount,
+                    slippage_basis_points,
+                    recent_blockhash,
+                    custom_buy_tip_fee,
+                    with_tip,
+                    Box::new(params),
+                    lookup_table_key,
+                    wait_transaction_confirmed,
+                ))
+                .await;
+            }
         }
+        result
+    }
+
+    /// Sell the payer's entire balance of a token and close its token account
+    ///
+    /// The full ATA balance is sold and a `close_account` instruction is appended to the
+    /// same transaction, so the account rent is reclaimed without a second transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `dex_type` - The trading protocol to use
+    /// * `mint` - The public key of the token mint to sell
+    /// * `slippage_basis_points` - Optional slippage tolerance in basis points (e.g., 100 = 1%)
+    /// * `recent_blockhash` - Recent blockhash for transaction validity
+    /// * `custom_buy_tip_fee` - Optional custom tip fee for priority processing (in SOL)
+    /// * `with_tip` - Whether to use tip for priority processing
+    /// * `extension_params` - Protocol-specific parameters
+    /// * `lookup_table_key` - Optional lookup table key for address lookup optimization
+    /// * `wait_transaction_confirmed` - Whether to wait for the transaction to be confirmed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token balance is zero or the sell fails.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn sell_all_and_close(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+        custom_buy_tip_fee: Option<f64>,
+        with_tip: bool,
+        extension_params: Box<dyn ProtocolParams>,
+        lookup_table_key: Option<Pubkey>,
+        wait_transaction_confirmed: bool,
+    ) -> TradeResult<()> {
+        // Paper trading sells the virtual position, there is no account to close
+        if let Some(paper_trader) = &self.paper_trader {
+            let amount_token =
+                paper_trader.tracker().position(&mint).map_or(0, |position| position.token_amount);
+            if amount_token == 0 {
+                return Err(anyhow::anyhow!("No token balance to sell").into());
+            }
+            return self
+                .sell(
+                    dex_type,
+                    mint,
+                    amount_token,
+                    slippage_basis_points,
+                    recent_blockhash,
+                    custom_buy_tip_fee,
+                    with_tip,
+                    extension_params,
+                    lookup_table_key,
+                    wait_transaction_confirmed,
+                )
+                .await;
+        }
+        let payer = self.payer.pubkey();
+        let mint_info = common::mint_info_cache::MintInfoCache::get_instance()
+            .get_or_fetch(&self.rpc, &mint)
+            .await?;
+        let token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &payer,
+                &mint,
+                &mint_info.token_program,
+            );
+        let amount_token = trading::common::utils::get_token_account_balance(
+            &self.rpc,
+            &token_account,
+            &mint,
+        )
+        .await?;
+        if amount_token == 0 {
+            return Err(anyhow::anyhow!("No token balance to sell").into());
+        }
+
+        let middleware_manager = self
+            .middleware_manager
+            .as_ref()
+            .map(|manager| manager.as_ref().clone())
+            .unwrap_or_else(MiddlewareManager::new)
+            .add_middleware(Box::new(CloseTokenAccountMiddleware::new(
+                payer,
+                mint,
+                mint_info.token_program,
+            )));
+        let trader = self.clone().with_middleware_manager(middleware_manager);
+        trader
+            .sell(
+                dex_type,
+                mint,
+                amount_token,
+                slippage_basis_points,
+                recent_blockhash,
+                custom_buy_tip_fee,
+                with_tip,
+                extension_params,
+                lookup_table_key,
+                wait_transaction_confirmed,
+            )
+            .await?;
+        common::ata_cache::AtaCache::get_instance().remove(&payer, &mint);
+        Ok(())
+    }
+
+    /// Build the protocol instructions of a buy without sending anything
+    ///
+    /// Returns the ATA setup, WSOL handling and swap instructions for the payer, derived the
+    /// same way `buy` derives them, so they can be combined with custom instructions (e.g. a
+    /// transfer) in one transaction. Compute budget, tip and signing are left to the caller.
+    pub async fn build_buy_instructions(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        sol_amount: u64,
+        slippage_basis_points: Option<u64>,
+        extension_params: Box<dyn ProtocolParams>,
+    ) -> TradeResult<Vec<solana_sdk::instruction::Instruction>> {
+        let buy_params = BuyParams {
+            rpc: Some(self.rpc.clone()),
+            payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
+            mint,
+            sol_amount,
+            slippage_basis_points,
+            priority_fee: self.trade_config.priority_fee.clone(),
+            lookup_table_key: self.trade_config.lookup_table_key,
+            recent_blockhash: Hash::default(),
+            data_size_limit: 0,
+            wait_transaction_confirmed: false,
+            protocol_params: extension_params,
+            lifecycle: None,
+        };
+        TradeFactory::create_instruction_builder(dex_type.clone())
+            .build_buy_instructions(&buy_params)
+            .await
+            .map_err(|e| TradeError::from(e).for_trade(&dex_type, &mint))
+    }
+
+    /// Build the protocol instructions of a sell without sending anything
+    ///
+    /// The sell counterpart of `build_buy_instructions`.
+    pub async fn build_sell_instructions(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        token_amount: u64,
+        slippage_basis_points: Option<u64>,
+        extension_params: Box<dyn ProtocolParams>,
+    ) -> TradeResult<Vec<solana_sdk::instruction::Instruction>> {
+        let sell_params = SellParams {
+            rpc: Some(self.rpc.clone()),
+            payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
+            mint,
+            token_amount: Some(token_amount),
+            slippage_basis_points,
+            priority_fee: self.trade_config.priority_fee.clone(),
+            lookup_table_key: self.trade_config.lookup_table_key,
+            recent_blockhash: Hash::default(),
+            wait_transaction_confirmed: false,
+            protocol_params: extension_params,
+            lifecycle: None,
+        };
+        TradeFactory::create_instruction_builder(dex_type.clone())
+            .build_sell_instructions(&sell_params)
+            .await
+            .map_err(|e| TradeError::from(e).for_trade(&dex_type, &mint))
+    }
+
+    /// Transaction builder with this client's payer, fee payer, priority fees and lookup table
+    ///
+    /// Add the instructions from `build_buy_instructions` / `build_sell_instructions` and any
+    /// custom ones, then `build` a signed v0 transaction.
+    pub fn transaction_builder(&self) -> trading::common::TransactionBuilder {
+        trading::common::TransactionBuilder::new(
+            self.payer.clone(),
+            self.trade_config.priority_fee.clone(),
+        )
+        .with_fee_payer(self.fee_payer.clone())
+        .with_lookup_table_key(self.trade_config.lookup_table_key)
+    }
+
+    /// Build transactions of `instructions` for an offline signer, one per swqos provider
+    ///
+    /// The transactions advance the durable nonce of `nonce_account` instead of using a
+    /// recent blockhash, and only the payers that are not `NullSigner`s sign them. Providers
+    /// other than the default RPC get a tip of `tip_sol`. See
+    /// `trading::common::offline_signing` for the whole workflow.
+    pub async fn prepare_offline(
+        &self,
+        instructions: Vec<solana_sdk::instruction::Instruction>,
+        nonce_account: &Pubkey,
+        tip_sol: f64,
+    ) -> TradeResult<Vec<OfflineTransaction>> {
+        let account = nonblocking::get_account(&self.rpc, nonce_account)
+            .await
+            .map_err(anyhow::Error::from)?;
+        let nonce = nonblocking::data_from_account(&account).map_err(anyhow::Error::from)?;
+        let builder = self.transaction_builder().with_instructions(instructions).with_durable_nonce(
+            *nonce_account,
+            nonce.authority,
+            nonce.blockhash(),
+        );
+
+        let mut transactions = Vec::with_capacity(self.swqos_clients.len());
+        for client in &self.swqos_clients {
+            let swqos_type = client.get_swqos_type();
+            let builder = match swqos_type {
+                SwqosType::Default => builder.clone(),
+                _ => {
+                    let tip_account: Pubkey = client
+                        .get_tip_account()
+                        .and_then(|account| account.parse().map_err(anyhow::Error::from))?;
+                    builder.clone().with_tip(tip_account, tip_sol)
+                }
+            };
+            let transaction = builder.build(Hash::default()).await?;
+            transactions.push(OfflineTransaction::new(swqos_type, transaction));
+        }
+        Ok(transactions)
+    }
+
+    /// Submit transactions signed offline, each through the provider it was built for
+    ///
+    /// Returns the signature of the first one its provider landed. Fails without sending
+    /// anything when a signature is missing.
+    pub async fn submit_offline(
+        &self,
+        trade_type: TradeType,
+        transactions: &[OfflineTransaction],
+    ) -> TradeResult<Signature> {
+        let mut submissions = Vec::with_capacity(transactions.len());
+        for offline in transactions {
+            let missing = offline.missing_signers();
+            if !missing.is_empty() {
+                return Err(TradeError::InvalidParams(format!(
+                    "Transaction for {:?} is not signed by {:?}",
+                    offline.swqos_type, missing
+                )));
+            }
+            let client = self
+                .swqos_clients
+                .iter()
+                .find(|client| client.get_swqos_type() == offline.swqos_type)
+                .ok_or_else(|| {
+                    TradeError::InvalidParams(format!("No {:?} client", offline.swqos_type))
+                })?;
+            submissions.push(Box::pin(async move {
+                let provider = format!("{:?}", offline.swqos_type);
+                client
+                    .send_transaction(trade_type, &offline.transaction)
+                    .await
+                    .map(|_| offline.transaction.signatures[0])
+                    .map_err(|e| TradeError::from_swqos(&provider, e))
+            }));
+        }
+        if submissions.is_empty() {
+            return Err(TradeError::InvalidParams("No transactions to submit".to_string()));
+        }
+        let (signature, _) = futures::future::select_ok(submissions).await?;
+        Ok(signature)
+    }
+
+    /// Close every empty token account of the payer to reclaim its rent
+    ///
+    /// Bots accumulate one ATA per traded token; this closes all zero-balance SPL Token and
+    /// Token-2022 accounts of the payer in batched transactions and reports what was closed.
+    pub async fn sweep_empty_token_accounts(
+        &self,
+    ) -> TradeResult<trading::common::utils::SweepSummary> {
+        let summary = trading::common::utils::sweep_empty_token_accounts(
+            &self.rpc,
+            self.payer.as_ref(),
+            trading::common::utils::DEFAULT_SWEEP_BATCH_SIZE,
+        )
+        .await?;
+        Ok(summary)
+    }
+
+    /// Execute a sell order for a share of the specified token amount in basis points
+    ///
+    /// The amount is `amount_token * bps / 10000` computed without overflow and rounded down.
+    /// When at most `sell_dust_threshold` tokens would remain, the whole `amount_token` is
+    /// sold instead of leaving dust behind (see `with_sell_dust_threshold`).
+    ///
+    /// # Arguments
+    ///
+    /// * `dex_type` - The trading protocol to use (PumpFun, PumpSwap, or Bonk)
+    /// * `mint` - The public key of the token mint to sell
+    /// * `amount_token` - Total amount of tokens available (in smallest token units)
+    /// * `bps` - Share of tokens to sell in basis points (1-10000, where 10000 = 100%)
+    /// * `slippage_basis_points` - Optional slippage tolerance in basis points (e.g., 100 = 1%)
+    /// * `recent_blockhash` - Recent blockhash for transaction validity
+    /// * `custom_buy_tip_fee` - Optional custom tip fee for priority processing (in SOL)
+    /// * `with_tip` - Whether to use tip for priority processing
+    /// * `extension_params` - Optional protocol-specific parameters (uses defaults if None)
+    /// * `lookup_table_key` - Optional lookup table key for address lookup optimization
+    /// * `wait_transaction_confirmed` - Whether to wait for the transaction to be confirmed
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the sell order is successfully executed, or an error if the transaction fails.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - `bps` is 0 or greater than 10000
+    /// - The share of `amount_token` rounds down to zero
+    /// - Invalid protocol parameters are provided
+    /// - The transaction fails to execute
+    /// - Network or RPC errors occur
+    /// - Insufficient token balance for the calculated sale amount
+    /// - Token account doesn't exist or is not properly initialized
+    #[allow(clippy::too_many_arguments)]
+    pub async fn sell_by_bps(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        amount_token: u64,
+        bps: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+        custom_buy_tip_fee: Option<f64>,
+        with_tip: bool,
+        extension_params: Box<dyn ProtocolParams>,
+        lookup_table_key: Option<Pubkey>,
+        wait_transaction_confirmed: bool,
+    ) -> TradeResult<()> {
+        let amount = calculate_sell_amount_by_bps(amount_token, bps, self.sell_dust_threshold)
+            .map_err(TradeError::InvalidParams)?;
+        self.sell(
+            dex_type,
+            mint,
+            amount,
+            slippage_basis_points,
+            recent_blockhash,
+            custom_buy_tip_fee,
+            with_tip,
+            extension_params,
+            lookup_table_key,
+            wait_transaction_confirmed,
+        )
+        .await
     }
 
     /// Execute a sell order for a percentage of the specified token amount
     ///
-    /// This is a convenience function that calculates the exact amount to sell based on
-    /// a percentage of the total token amount and then calls the `sell` function.
+    /// This is a convenience function that calls `sell_by_bps` with `percent * 100`.
     ///
     /// # Arguments
     ///
@@ -336,6 +1334,7 @@ impl SolanaTrade {
     /// - Invalid protocol parameters are provided
     /// - The transaction fails to execute
     /// - Network or RPC errors occur
+    /// - The percentage of `amount_token` rounds down to zero
     /// - Insufficient token balance for the calculated sale amount
     /// - Token account doesn't exist or is not properly initialized
     pub async fn sell_by_percent(
@@ -351,15 +1350,17 @@ impl SolanaTrade {
         extension_params: Box<dyn ProtocolParams>,
         lookup_table_key: Option<Pubkey>,
         wait_transaction_confirmed: bool,
-    ) -> Result<(), anyhow::Error> {
+    ) -> TradeResult<()> {
         if percent == 0 || percent > 100 {
-            return Err(anyhow::anyhow!("Percentage must be between 1 and 100"));
+            return Err(TradeError::InvalidParams(
+                "Percentage must be between 1 and 100".to_string(),
+            ));
         }
-        let amount = amount_token * percent / 100;
-        self.sell(
+        self.sell_by_bps(
             dex_type,
             mint,
-            amount,
+            amount_token,
+            percent * 100,
             slippage_basis_points,
             recent_blockhash,
             custom_buy_tip_fee,
@@ -17,7 +17,10 @@ use sol_trade_sdk::{
         streaming::{
             event_parser::{
                 protocols::{
-                    bonk::{BonkPoolCreateEvent, BonkTradeEvent},
+                    bonk::{
+                        BonkMigrateToAmmEvent, BonkMigrateToCpswapEvent, BonkPoolCreateEvent,
+                        BonkTradeEvent,
+                    },
                     pumpfun::{PumpFunCreateTokenEvent, PumpFunTradeEvent},
                     pumpswap::{
                         PumpSwapBuyEvent, PumpSwapCreatePoolEvent, PumpSwapDepositEvent,
@@ -31,6 +34,8 @@ use sol_trade_sdk::{
         },
     },
     trading::core::params::RaydiumAmmV4Params,
+    trading::bonk::migration::BonkMigrationCache,
+    trading::pumpswap::{pool_cache::PoolCache, reserves_cache::PoolReservesCache},
 };
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair};
 use solana_streamer_sdk::streaming::{
@@ -67,7 +72,7 @@ async fn test_create_solana_trade_client() -> AnyResult<SolanaTrade> {
     let swqos_configs = create_swqos_configs(&rpc_url);
     let trade_config = create_trade_config(rpc_url, swqos_configs);
 
-    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await;
+    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await?;
     println!("SolanaTrade client created successfully!");
 
     Ok(solana_trade_client)
@@ -93,6 +98,13 @@ fn create_trade_config(rpc_url: String, swqos_configs: Vec<SwqosConfig>) -> Trad
         priority_fee: PriorityFee::default(),
         swqos_configs,
         lookup_table_key: None,
+        rpc_pool: None,
+        core_affinity: Default::default(),
+        swqos_http_client: None,
+        proxy: None,
+        swqos_proxies: vec![],
+        confirmation: Default::default(),
+        jito_simulate_bundles: false,
     }
 }
 async fn test_middleware() -> AnyResult<()> {
@@ -584,6 +596,16 @@ fn create_event_callback() -> impl Fn(Box<dyn UnifiedEvent>) {
             },
             BonkTradeEvent => |e: BonkTradeEvent| {
                 println!("BonkTradeEvent: {:?}", e);
+                BonkMigrationCache::get_instance().update_from_trade(&e);
+            },
+            BonkMigrateToAmmEvent => |e: BonkMigrateToAmmEvent| {
+                println!("BonkMigrateToAmmEvent: {:?}", e);
+                // Later Bonk trades on this mint are routed to the Raydium pool
+                BonkMigrationCache::get_instance().update_from_migrate_to_amm(&e);
+            },
+            BonkMigrateToCpswapEvent => |e: BonkMigrateToCpswapEvent| {
+                println!("BonkMigrateToCpswapEvent: {:?}", e);
+                BonkMigrationCache::get_instance().update_from_migrate_to_cpswap(&e);
             },
             PumpFunTradeEvent => |e: PumpFunTradeEvent| {
                 println!("PumpFunTradeEvent: {:?}", e);
@@ -593,18 +615,24 @@ fn create_event_callback() -> impl Fn(Box<dyn UnifiedEvent>) {
             },
             PumpSwapBuyEvent => |e: PumpSwapBuyEvent| {
                 println!("Buy event: {:?}", e);
+                PoolReservesCache::get_instance().update_from_buy(&e);
             },
             PumpSwapSellEvent => |e: PumpSwapSellEvent| {
                 println!("Sell event: {:?}", e);
+                PoolReservesCache::get_instance().update_from_sell(&e);
             },
             PumpSwapCreatePoolEvent => |e: PumpSwapCreatePoolEvent| {
                 println!("CreatePool event: {:?}", e);
+                // Remember mint -> pool so find_pool can skip the program-accounts scan
+                PoolCache::get_instance().update_from_create_pool(&e);
             },
             PumpSwapDepositEvent => |e: PumpSwapDepositEvent| {
                 println!("Deposit event: {:?}", e);
+                PoolReservesCache::get_instance().update_from_deposit(&e);
             },
             PumpSwapWithdrawEvent => |e: PumpSwapWithdrawEvent| {
                 println!("Withdraw event: {:?}", e);
+                PoolReservesCache::get_instance().update_from_withdraw(&e);
             },
             RaydiumCpmmSwapEvent => |e: RaydiumCpmmSwapEvent| {
                 println!("RaydiumCpmmSwapEvent: {:?}", e);
@@ -0,0 +1,226 @@
+//! Prometheus metrics
+//!
+//! Enabled with the `metrics` feature. Without it the recording functions are empty and
+//! compile away, so call sites need no `cfg`.
+//!
+//! Exported series:
+//! - `sol_trade_trades_total{dex, side, result}` - trades executed, `result` is `ok` or `err`
+//! - `sol_trade_trade_duration_seconds{dex, side}` - time spent in `buy` / `sell`
+//! - `sol_trade_swqos_submissions_total{provider, result}` - outcome per provider: `landed`,
+//!   `failed` (landed with an error), `timeout` or `rejected`. The landing rate of a provider is
+//!   `landed` over all results
+//! - `sol_trade_confirmation_seconds{provider}` - submission to confirmation latency of landed
+//!   transactions
+//! - `sol_trade_swqos_landings_total{provider}` - landed transactions attributed to the provider
+//!   whose tip account they paid. Racing providers each report their own confirmation, this
+//!   counts the one that actually landed the trade
+//! - `sol_trade_events_parsed_total{source}` - parsed stream events
+//! - `sol_trade_rpc_errors_total{source}` - RPC errors
+//!
+//! Call [`serve`] to expose them on `/metrics`, or use [`registry`] with your own exporter.
+
+use std::time::Duration;
+
+use crate::common::{TradeError, TradeResult};
+use crate::trading::factory::DexType;
+
+#[cfg(feature = "metrics")]
+pub use exporter::{gather, registry, serve};
+
+/// Record a finished `buy` / `sell`
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+#[inline]
+pub(crate) fn record_trade(
+    dex_type: &DexType,
+    side: &str,
+    elapsed: Duration,
+    result: &TradeResult<()>,
+) {
+    #[cfg(feature = "metrics")]
+    {
+        let metrics = exporter::metrics();
+        let dex = dex_type.as_str();
+        let outcome = if result.is_ok() { "ok" } else { "err" };
+        metrics.trades.with_label_values(&[dex, side, outcome]).inc();
+        metrics.trade_duration.with_label_values(&[dex, side]).observe(elapsed.as_secs_f64());
+        if let Err(TradeError::RpcError(_)) = result {
+            record_rpc_error("trade");
+        }
+    }
+}
+
+/// Record the outcome of sending a transaction through one swqos provider
+///
+/// `elapsed` is measured from submission, so for landed transactions it is the
+/// confirmation latency.
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+#[inline]
+pub(crate) fn record_submission(provider: &str, elapsed: Duration, error: Option<&TradeError>) {
+    #[cfg(feature = "metrics")]
+    {
+        let metrics = exporter::metrics();
+        let outcome = match error {
+            None => "landed",
+            Some(TradeError::TransactionFailed(_)) => "failed",
+            Some(TradeError::Timeout(_)) => "timeout",
+            Some(_) => "rejected",
+        };
+        metrics.submissions.with_label_values(&[provider, outcome]).inc();
+        if error.is_none() {
+            metrics.confirmation.with_label_values(&[provider]).observe(elapsed.as_secs_f64());
+        }
+    }
+}
+
+/// Count a landed transaction attributed to `provider` by its tip account
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+#[inline]
+pub(crate) fn record_landing(provider: &str) {
+    #[cfg(feature = "metrics")]
+    exporter::metrics().landings.with_label_values(&[provider]).inc();
+}
+
+/// Count an event parsed from a stream
+///
+/// Public so parsers driven by the application can report into the same series.
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+#[inline]
+pub fn record_parsed_event(source: &str) {
+    #[cfg(feature = "metrics")]
+    exporter::metrics().events_parsed.with_label_values(&[source]).inc();
+}
+
+/// Count a failed RPC request
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+#[inline]
+pub fn record_rpc_error(source: &str) {
+    #[cfg(feature = "metrics")]
+    exporter::metrics().rpc_errors.with_label_values(&[source]).inc();
+}
+
+#[cfg(feature = "metrics")]
+mod exporter {
+    use std::sync::OnceLock;
+
+    use prometheus::{
+        exponential_buckets, Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry,
+        TextEncoder,
+    };
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, ToSocketAddrs};
+    use tokio::task::JoinHandle;
+
+    pub(super) struct Metrics {
+        registry: Registry,
+        pub trades: IntCounterVec,
+        pub trade_duration: HistogramVec,
+        pub submissions: IntCounterVec,
+        pub confirmation: HistogramVec,
+        pub landings: IntCounterVec,
+        pub events_parsed: IntCounterVec,
+        pub rpc_errors: IntCounterVec,
+    }
+
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+    pub(super) fn metrics() -> &'static Metrics {
+        METRICS.get_or_init(|| {
+            let registry = Registry::new();
+            let counter = |name: &str, help: &str, labels: &[&str]| {
+                let counter = IntCounterVec::new(Opts::new(name, help), labels).unwrap();
+                registry.register(Box::new(counter.clone())).unwrap();
+                counter
+            };
+            // 5ms .. ~20s
+            let histogram = |name: &str, help: &str, labels: &[&str]| {
+                let opts = HistogramOpts::new(name, help)
+                    .buckets(exponential_buckets(0.005, 2.0, 13).unwrap());
+                let histogram = HistogramVec::new(opts, labels).unwrap();
+                registry.register(Box::new(histogram.clone())).unwrap();
+                histogram
+            };
+            Metrics {
+                trades: counter(
+                    "sol_trade_trades_total",
+                    "Trades executed",
+                    &["dex", "side", "result"],
+                ),
+                trade_duration: histogram(
+                    "sol_trade_trade_duration_seconds",
+                    "Time spent in buy / sell",
+                    &["dex", "side"],
+                ),
+                submissions: counter(
+                    "sol_trade_swqos_submissions_total",
+                    "Transaction submissions per swqos provider by outcome",
+                    &["provider", "result"],
+                ),
+                confirmation: histogram(
+                    "sol_trade_confirmation_seconds",
+                    "Submission to confirmation latency of landed transactions",
+                    &["provider"],
+                ),
+                landings: counter(
+                    "sol_trade_swqos_landings_total",
+                    "Landed transactions attributed to a swqos provider by the tip account paid",
+                    &["provider"],
+                ),
+                events_parsed: counter(
+                    "sol_trade_events_parsed_total",
+                    "Parsed stream events",
+                    &["source"],
+                ),
+                rpc_errors: counter("sol_trade_rpc_errors_total", "RPC errors", &["source"]),
+                registry,
+            }
+        })
+    }
+
+    /// Registry holding the SDK metrics, for use with your own exporter
+    pub fn registry() -> &'static Registry {
+        &metrics().registry
+    }
+
+    /// Current metrics in the Prometheus text format
+    pub fn gather() -> String {
+        let mut buffer = Vec::new();
+        let _ = TextEncoder::new().encode(&registry().gather(), &mut buffer);
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+
+    /// Serve the metrics on `GET /metrics` at `addr`
+    ///
+    /// A minimal HTTP/1.1 endpoint meant for a Prometheus scraper; every connection is
+    /// answered once and closed. The server runs until the returned handle is aborted.
+    pub async fn serve(addr: impl ToSocketAddrs) -> anyhow::Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(addr).await?;
+        Ok(tokio::spawn(async move {
+            loop {
+                let mut stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "metrics exporter accept failed");
+                        continue;
+                    }
+                };
+                tokio::spawn(async move {
+                    let mut request = [0u8; 1024];
+                    let Ok(n) = stream.read(&mut request).await else { return };
+                    let response = if request[..n].starts_with(b"GET /metrics") {
+                        let body = gather();
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\
+                             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        )
+                    } else {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    };
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        }))
+    }
+}
@@ -0,0 +1,353 @@
+//! Trade notifications
+//!
+//! A [`Notifier`] delivers [`TradeNotification`]s to a chat or an HTTP endpoint; Telegram,
+//! Discord and generic JSON webhooks are built in. [`Notifications`] fans a notification out
+//! to its notifiers in the background and rate limits each of them, so a burst of trades
+//! never blocks trading nor gets the bot banned by the chat service:
+//!
+//! ```ignore
+//! let notifications = Notifications::new(RateLimit::default())
+//!     .with_notifier(TelegramNotifier::new(bot_token, chat_id)?)
+//!     .with_notifier(WebhookNotifier::new("https://example.com/hooks/trades")?);
+//! let client = client.with_notifications(notifications);
+//! ```
+//!
+//! `SolanaTrade` sends `Filled` and `Failed` for every buy and sell, the strategy runner
+//! sends `DevSold` when the creator of a subscribed token sells. `StopLossTriggered` and any
+//! other notification can be sent by the application with [`Notifications::notify`].
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::{AnyResult, TradeResult};
+use crate::constants::pumpfun::global_constants::LAMPORTS_PER_SOL;
+use crate::trading::factory::DexType;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Trade lifecycle event worth telling someone about
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TradeNotification {
+    /// A buy or sell completed; `amount` is lamports spent for buys, tokens sold for sells
+    Filled { dex: &'static str, mint: Pubkey, side: &'static str, amount: u64 },
+    /// A buy or sell failed
+    Failed { dex: &'static str, mint: Pubkey, side: &'static str, error: String },
+    /// A stop loss fired for a position
+    StopLossTriggered { mint: Pubkey, pnl_lamports: i64 },
+    /// The creator of a token sold
+    DevSold { mint: Pubkey, dev: Pubkey, token_amount: u64, sol_amount: u64 },
+}
+
+impl TradeNotification {
+    /// `Filled` or `Failed` for the result of a `buy` / `sell`
+    pub fn for_trade(
+        dex_type: &DexType,
+        mint: Pubkey,
+        side: &'static str,
+        amount: u64,
+        result: &TradeResult<()>,
+    ) -> Self {
+        let dex = dex_type.as_str();
+        match result {
+            Ok(()) => TradeNotification::Filled { dex, mint, side, amount },
+            Err(e) => TradeNotification::Failed { dex, mint, side, error: e.to_string() },
+        }
+    }
+
+    /// Short name of the event, e.g. `filled`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TradeNotification::Filled { .. } => "filled",
+            TradeNotification::Failed { .. } => "failed",
+            TradeNotification::StopLossTriggered { .. } => "stop_loss_triggered",
+            TradeNotification::DevSold { .. } => "dev_sold",
+        }
+    }
+
+    pub fn mint(&self) -> Pubkey {
+        match self {
+            TradeNotification::Filled { mint, .. }
+            | TradeNotification::Failed { mint, .. }
+            | TradeNotification::StopLossTriggered { mint, .. }
+            | TradeNotification::DevSold { mint, .. } => *mint,
+        }
+    }
+
+    /// One line, human readable text
+    pub fn message(&self) -> String {
+        match self {
+            TradeNotification::Filled { dex, mint, side: "buy", amount } => {
+                format!("Bought {} for {} SOL on {}", mint, sol(*amount), dex)
+            }
+            TradeNotification::Filled { dex, mint, side, amount } => {
+                format!("{} {} tokens of {} on {}", capitalize(side), amount, mint, dex)
+            }
+            TradeNotification::Failed { dex, mint, side, error } => {
+                format!("{} of {} on {} failed: {}", capitalize(side), mint, dex, error)
+            }
+            TradeNotification::StopLossTriggered { mint, pnl_lamports } => {
+                format!("Stop loss triggered for {}, PnL {} SOL", mint, signed_sol(*pnl_lamports))
+            }
+            TradeNotification::DevSold { mint, dev, token_amount, sol_amount } => format!(
+                "Dev {} sold {} tokens of {} for {} SOL",
+                dev,
+                token_amount,
+                mint,
+                sol(*sol_amount)
+            ),
+        }
+    }
+
+    /// JSON body posted by `WebhookNotifier`
+    pub fn to_json(&self) -> Value {
+        let mut body = json!({
+            "event": self.kind(),
+            "mint": self.mint().to_string(),
+            "message": self.message(),
+        });
+        let fields = match self {
+            TradeNotification::Filled { dex, side, amount, .. } => {
+                json!({ "dex": dex, "side": side, "amount": amount })
+            }
+            TradeNotification::Failed { dex, side, error, .. } => {
+                json!({ "dex": dex, "side": side, "error": error })
+            }
+            TradeNotification::StopLossTriggered { pnl_lamports, .. } => {
+                json!({ "pnl_lamports": pnl_lamports })
+            }
+            TradeNotification::DevSold { dev, token_amount, sol_amount, .. } => json!({
+                "dev": dev.to_string(),
+                "token_amount": token_amount,
+                "sol_amount": sol_amount,
+            }),
+        };
+        if let (Some(body), Value::Object(fields)) = (body.as_object_mut(), fields) {
+            body.extend(fields);
+        }
+        body
+    }
+}
+
+fn sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+fn signed_sol(lamports: i64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+fn capitalize(side: &str) -> String {
+    let mut chars = side.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+/// Destination of notifications
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, notification: &TradeNotification) -> AnyResult<()>;
+}
+
+fn http_client() -> AnyResult<reqwest::Client> {
+    Ok(reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?)
+}
+
+/// Telegram bot message to a chat
+pub struct TelegramNotifier {
+    client: reqwest::Client,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl TelegramNotifier {
+    /// `chat_id` is the numeric id or `@channelusername` of the target chat
+    pub fn new(bot_token: impl Into<String>, chat_id: impl Into<String>) -> AnyResult<Self> {
+        Ok(Self { client: http_client()?, bot_token: bot_token.into(), chat_id: chat_id.into() })
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    async fn notify(&self, notification: &TradeNotification) -> AnyResult<()> {
+        // The URL holds the bot token, keep it out of the error
+        self.client
+            .post(format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token))
+            .json(&json!({ "chat_id": self.chat_id, "text": notification.message() }))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(reqwest::Error::without_url)?;
+        Ok(())
+    }
+}
+
+/// Message through a Discord channel webhook
+pub struct DiscordNotifier {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+impl DiscordNotifier {
+    pub fn new(webhook_url: impl Into<String>) -> AnyResult<Self> {
+        Ok(Self { client: http_client()?, webhook_url: webhook_url.into() })
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    async fn notify(&self, notification: &TradeNotification) -> AnyResult<()> {
+        self.client
+            .post(&self.webhook_url)
+            .json(&json!({ "content": notification.message() }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// `TradeNotification::to_json` posted to any URL
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>) -> AnyResult<Self> {
+        Ok(Self { client: http_client()?, url: url.into(), headers: vec![] })
+    }
+
+    /// Add a header to every request, e.g. for authentication
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, notification: &TradeNotification) -> AnyResult<()> {
+        let mut request = self.client.post(&self.url).json(&notification.to_json());
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// At most `max` notifications per `window` and notifier, the rest is dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max: u32,
+    pub window: Duration,
+}
+
+impl Default for RateLimit {
+    /// 20 per minute, the limit Telegram applies to messages in a group
+    fn default() -> Self {
+        Self { max: 20, window: Duration::from_secs(60) }
+    }
+}
+
+/// Fixed window counter
+#[derive(Debug)]
+struct Window {
+    started_at: Instant,
+    sent: u32,
+    dropped: u64,
+}
+
+impl Window {
+    fn new(now: Instant) -> Self {
+        Self { started_at: now, sent: 0, dropped: 0 }
+    }
+
+    /// Whether one more notification fits in the window at `now`
+    fn try_acquire(&mut self, limit: &RateLimit, now: Instant) -> bool {
+        if now.duration_since(self.started_at) >= limit.window {
+            self.started_at = now;
+            self.sent = 0;
+        }
+        if self.sent < limit.max {
+            self.sent += 1;
+            true
+        } else {
+            self.dropped += 1;
+            false
+        }
+    }
+}
+
+/// Rate limited fan-out of notifications
+pub struct Notifications {
+    rate_limit: RateLimit,
+    notifiers: Vec<(Arc<dyn Notifier>, Mutex<Window>)>,
+}
+
+impl Notifications {
+    pub fn new(rate_limit: RateLimit) -> Self {
+        Self { rate_limit, notifiers: vec![] }
+    }
+
+    pub fn with_notifier(mut self, notifier: impl Notifier + 'static) -> Self {
+        self.notifiers.push((Arc::new(notifier), Mutex::new(Window::new(Instant::now()))));
+        self
+    }
+
+    /// Send to every notifier below its rate limit, must be called inside a tokio runtime
+    ///
+    /// Delivery happens in the background; failures are logged and not retried.
+    pub fn notify(&self, notification: TradeNotification) {
+        let notification = Arc::new(notification);
+        let now = Instant::now();
+        for (notifier, window) in &self.notifiers {
+            if !window.lock().unwrap().try_acquire(&self.rate_limit, now) {
+                tracing::debug!(event = notification.kind(), "notification rate limited");
+                continue;
+            }
+            let notifier = notifier.clone();
+            let notification = notification.clone();
+            tokio::spawn(async move {
+                if let Err(e) = notifier.notify(&notification).await {
+                    tracing::warn!(event = notification.kind(), error = %e, "notification failed");
+                }
+            });
+        }
+    }
+
+    /// Notifications dropped by the rate limit so far, over all notifiers
+    pub fn dropped(&self) -> u64 {
+        self.notifiers.iter().map(|(_, window)| window.lock().unwrap().dropped).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_window_and_message() {
+        let limit = RateLimit { max: 2, window: Duration::from_secs(60) };
+        let start = Instant::now();
+        let mut window = Window::new(start);
+        assert!(window.try_acquire(&limit, start));
+        assert!(window.try_acquire(&limit, start + Duration::from_secs(1)));
+        assert!(!window.try_acquire(&limit, start + Duration::from_secs(2)));
+        assert!(window.try_acquire(&limit, start + Duration::from_secs(61)));
+        assert_eq!(window.dropped, 1);
+
+        let mint = Pubkey::new_unique();
+        let stop = TradeNotification::StopLossTriggered { mint, pnl_lamports: -500_000_000 };
+        assert_eq!(stop.message(), format!("Stop loss triggered for {}, PnL -0.5 SOL", mint));
+        let body = stop.to_json();
+        assert_eq!(body["event"], "stop_loss_triggered");
+        assert_eq!(body["pnl_lamports"], -500_000_000);
+    }
+}
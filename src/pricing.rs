@@ -0,0 +1,235 @@
+//! SOL/USD price feed
+//!
+//! [`SolUsdPrice`] keeps the latest SOL/USD price from Pyth (through a Hermes endpoint) or
+//! from any HTTP oracle returning JSON, refreshed in the background. It converts between
+//! lamports and USD, so PnL, market cap and spend limits can be expressed in dollars:
+//!
+//! ```ignore
+//! let price = SolUsdPrice::start(SolUsdPriceConfig::default())?;
+//! let market_cap_usd = price.lamports_to_usd(bonding_curve.get_market_cap_sol());
+//! let max_buy = price.usd_to_lamports(250.0);
+//! ```
+//!
+//! A price older than `max_age` is treated as unknown and the conversions return `None`.
+
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+
+use crate::common::{task_manager::TaskManager, AnyResult};
+use crate::constants::pumpfun::global_constants::LAMPORTS_PER_SOL;
+
+/// Public Hermes endpoint of the Pyth network
+pub const PYTH_HERMES_URL: &str = "https://hermes.pyth.network";
+
+/// Pyth price feed id of SOL/USD
+pub const PYTH_SOL_USD_FEED_ID: &str =
+    "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where the SOL/USD price comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceSource {
+    /// Latest update of a Pyth price feed, read from a Hermes endpoint
+    Pyth { hermes_url: String, feed_id: String },
+    /// JSON endpoint, the price is read at `pointer` (RFC 6901, e.g. `/solana/usd`) and may be
+    /// a number or a numeric string
+    Http { url: String, pointer: String },
+}
+
+impl PriceSource {
+    /// SOL/USD from the public Pyth Hermes endpoint
+    pub fn pyth() -> Self {
+        PriceSource::Pyth {
+            hermes_url: PYTH_HERMES_URL.to_string(),
+            feed_id: PYTH_SOL_USD_FEED_ID.to_string(),
+        }
+    }
+
+    /// Price at `pointer` in the JSON returned by `url`
+    pub fn http(url: impl Into<String>, pointer: impl Into<String>) -> Self {
+        PriceSource::Http { url: url.into(), pointer: pointer.into() }
+    }
+
+    fn request_url(&self) -> String {
+        match self {
+            PriceSource::Pyth { hermes_url, feed_id } => format!(
+                "{}/v2/updates/price/latest?ids[]={}&parsed=true",
+                hermes_url.trim_end_matches('/'),
+                feed_id.trim_start_matches("0x")
+            ),
+            PriceSource::Http { url, .. } => url.clone(),
+        }
+    }
+
+    /// Extract the price from a response body
+    fn parse(&self, body: &Value) -> AnyResult<f64> {
+        let price = match self {
+            PriceSource::Pyth { .. } => {
+                let price = body
+                    .pointer("/parsed/0/price")
+                    .ok_or_else(|| anyhow!("no parsed price in Hermes response"))?;
+                let mantissa = json_number(price.get("price"))?;
+                let expo = price
+                    .get("expo")
+                    .and_then(Value::as_i64)
+                    .ok_or_else(|| anyhow!("no exponent in Hermes response"))?;
+                mantissa * 10f64.powi(expo as i32)
+            }
+            PriceSource::Http { pointer, .. } => json_number(body.pointer(pointer))
+                .with_context(|| format!("reading {} from oracle response", pointer))?,
+        };
+        if !price.is_finite() || price <= 0.0 {
+            return Err(anyhow!("invalid SOL/USD price {}", price));
+        }
+        Ok(price)
+    }
+}
+
+fn json_number(value: Option<&Value>) -> AnyResult<f64> {
+    match value {
+        Some(Value::Number(n)) => n.as_f64().ok_or_else(|| anyhow!("price out of range")),
+        Some(Value::String(s)) => Ok(s.trim().parse::<f64>()?),
+        Some(other) => Err(anyhow!("price is not a number: {}", other)),
+        None => Err(anyhow!("price missing")),
+    }
+}
+
+/// Settings of a `SolUsdPrice` feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolUsdPriceConfig {
+    pub source: PriceSource,
+    /// How often the price is refreshed
+    pub poll_interval: Duration,
+    /// Prices older than this are not used
+    pub max_age: Duration,
+}
+
+impl Default for SolUsdPriceConfig {
+    fn default() -> Self {
+        Self {
+            source: PriceSource::pyth(),
+            poll_interval: Duration::from_secs(2),
+            max_age: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Latest SOL/USD price
+pub struct SolUsdPrice {
+    config: SolUsdPriceConfig,
+    client: reqwest::Client,
+    latest: Mutex<Option<(f64, Instant)>>,
+}
+
+impl SolUsdPrice {
+    /// Create a feed without starting it, call `refresh` or `set_price` to fill it
+    pub fn new(config: SolUsdPriceConfig) -> AnyResult<Arc<Self>> {
+        let client = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        Ok(Arc::new(Self { config, client, latest: Mutex::new(None) }))
+    }
+
+    /// Create a feed refreshed every `poll_interval`, must be called inside a tokio runtime
+    ///
+    /// The refresh task stops once the returned `Arc` and all its clones are dropped.
+    pub fn start(config: SolUsdPriceConfig) -> AnyResult<Arc<Self>> {
+        let feed = Self::new(config)?;
+        feed.spawn_refresh();
+        Ok(feed)
+    }
+
+    pub fn config(&self) -> &SolUsdPriceConfig {
+        &self.config
+    }
+
+    /// Fetch the price from the source now
+    pub async fn refresh(&self) -> AnyResult<f64> {
+        let body: Value = self
+            .client
+            .get(self.config.source.request_url())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let price = self.config.source.parse(&body)?;
+        self.set_price(price);
+        Ok(price)
+    }
+
+    /// Refresh every `poll_interval` until the feed is dropped
+    pub fn spawn_refresh(self: &Arc<Self>) -> JoinHandle<()> {
+        let feed: Weak<Self> = Arc::downgrade(self);
+        let interval = self.config.poll_interval;
+        TaskManager::get_instance().spawn("sol_usd_price", async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(feed) = feed.upgrade() else { break };
+                if let Err(e) = feed.refresh().await {
+                    tracing::warn!(error = %e, "SOL/USD price refresh failed");
+                }
+            }
+        })
+    }
+
+    /// Override the price, e.g. from a feed the application already subscribes to
+    pub fn set_price(&self, price: f64) {
+        *self.latest.lock().unwrap() = Some((price, Instant::now()));
+    }
+
+    /// Latest price in USD per SOL, `None` when unknown or older than `max_age`
+    pub fn price(&self) -> Option<f64> {
+        self.latest
+            .lock()
+            .unwrap()
+            .filter(|(_, updated_at)| updated_at.elapsed() <= self.config.max_age)
+            .map(|(price, _)| price)
+    }
+
+    /// Value of `lamports` in USD
+    pub fn lamports_to_usd(&self, lamports: u64) -> Option<f64> {
+        self.price().map(|price| lamports as f64 / LAMPORTS_PER_SOL as f64 * price)
+    }
+
+    /// Lamports worth `usd`, rounded down
+    pub fn usd_to_lamports(&self, usd: f64) -> Option<u64> {
+        self.price().map(|price| (usd / price * LAMPORTS_PER_SOL as f64) as u64)
+    }
+
+    /// Signed lamport amount (e.g. a PnL) in USD
+    pub fn signed_lamports_to_usd(&self, lamports: i64) -> Option<f64> {
+        self.price().map(|price| lamports as f64 / LAMPORTS_PER_SOL as f64 * price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sources_and_convert() {
+        let pyth = serde_json::json!({
+            "parsed": [{ "price": { "price": "15012345678", "conf": "1000", "expo": -8 } }]
+        });
+        let price = PriceSource::pyth().parse(&pyth).unwrap();
+        assert!((price - 150.12345678).abs() < 1e-9);
+
+        let http = serde_json::json!({ "solana": { "usd": 150.5 } });
+        let source = PriceSource::http("https://example.com", "/solana/usd");
+        assert_eq!(source.parse(&http).unwrap(), 150.5);
+        assert!(source.parse(&serde_json::json!({ "solana": {} })).is_err());
+
+        let feed = SolUsdPrice::new(SolUsdPriceConfig::default()).unwrap();
+        assert_eq!(feed.lamports_to_usd(LAMPORTS_PER_SOL), None);
+        feed.set_price(150.0);
+        assert_eq!(feed.lamports_to_usd(2 * LAMPORTS_PER_SOL), Some(300.0));
+        assert_eq!(feed.usd_to_lamports(75.0), Some(LAMPORTS_PER_SOL / 2));
+        assert_eq!(feed.signed_lamports_to_usd(-(LAMPORTS_PER_SOL as i64)), Some(-150.0));
+    }
+}
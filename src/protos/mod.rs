@@ -6,6 +6,7 @@ pub mod packet;
 pub mod relayer;
 pub mod searcher;
 pub mod shared;
+#[cfg(feature = "shreds")]
 pub mod trace_shred;
 pub mod convert;
 pub mod nextblock_grpc;
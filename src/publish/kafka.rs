@@ -0,0 +1,50 @@
+//! Kafka publisher
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
+
+use super::EventPublisher;
+use crate::common::AnyResult;
+
+/// How long a message may wait in the producer queue and for delivery
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Publishes to Kafka topics with an `rdkafka` producer
+#[derive(Clone)]
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+}
+
+impl KafkaPublisher {
+    /// Producer for the comma separated `brokers`
+    pub fn new(brokers: &str) -> AnyResult<Self> {
+        Self::with_config(ClientConfig::new().set("bootstrap.servers", brokers))
+    }
+
+    /// Producer from a full client config, for authentication, compression, acks etc.
+    pub fn with_config(config: &ClientConfig) -> AnyResult<Self> {
+        Ok(Self { producer: config.create()? })
+    }
+}
+
+#[async_trait]
+impl EventPublisher for KafkaPublisher {
+    async fn publish(&self, topic: &str, key: Option<&str>, payload: Vec<u8>) -> AnyResult<()> {
+        let mut record = FutureRecord::<str, [u8]>::to(topic).payload(&payload);
+        if let Some(key) = key {
+            record = record.key(key);
+        }
+        self.producer.send(record, SEND_TIMEOUT).await.map_err(|(e, _)| anyhow!(e))?;
+        Ok(())
+    }
+
+    async fn flush(&self) -> AnyResult<()> {
+        let producer = self.producer.clone();
+        tokio::task::spawn_blocking(move || producer.flush(SEND_TIMEOUT)).await??;
+        Ok(())
+    }
+}
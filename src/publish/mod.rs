@@ -0,0 +1,248 @@
+//! Publishing events to a message broker
+//!
+//! An [`EventPublisherSink`] serializes events to JSON and hands them to an
+//! [`EventPublisher`] from a background task, so publishing never blocks the stream callback
+//! or the trade that produced the event. Kafka (`kafka` feature) and NATS (`nats` feature)
+//! publishers are built in; any other broker only needs an `EventPublisher`.
+//!
+//! ```ignore
+//! let publisher = KafkaPublisher::new("localhost:9092")?;
+//! let sink = Arc::new(EventPublisherSink::new(publisher, PublishTopics::default()));
+//! // trade lifecycle events of every trade, keyed by mint
+//! sink.publish_lifecycle();
+//! // in the stream callback
+//! sink.write(&RecordedEvent::PumpFunTrade(event))?;
+//! ```
+//!
+//! Messages are keyed by mint, so a Kafka partition keeps the events of a token in order.
+//! The NATS publisher appends the key to the subject instead (`sol_trade.events.<mint>`).
+//! When the broker can't keep up the queue fills and further events are dropped and
+//! counted, see [`EventPublisherSink::dropped`].
+
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaPublisher;
+#[cfg(feature = "nats")]
+pub use nats::NatsPublisher;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+
+#[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+use crate::backtest::{EventSink, RecordedEvent};
+use crate::common::AnyResult;
+use crate::trading::core::lifecycle;
+
+/// Messages queued before events are dropped
+const QUEUE_CAPACITY: usize = 10_000;
+
+/// Message broker client
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    /// Publish `payload` to `topic`; `key` orders messages of the same key when the broker
+    /// supports it
+    async fn publish(&self, topic: &str, key: Option<&str>, payload: Vec<u8>) -> AnyResult<()>;
+
+    /// Wait until published messages were delivered
+    async fn flush(&self) -> AnyResult<()> {
+        Ok(())
+    }
+}
+
+/// Topics (Kafka) or subjects (NATS) events are published to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishTopics {
+    /// Parsed stream events
+    pub events: String,
+    /// Trade lifecycle events
+    pub lifecycle: String,
+}
+
+impl Default for PublishTopics {
+    fn default() -> Self {
+        Self {
+            events: "sol_trade.events".to_string(),
+            lifecycle: "sol_trade.lifecycle".to_string(),
+        }
+    }
+}
+
+struct Message {
+    topic: String,
+    key: Option<String>,
+    payload: Vec<u8>,
+}
+
+/// Publishes serialized events through an `EventPublisher` in the background
+///
+/// Must be created inside a tokio runtime. The background task publishes until the sink is
+/// dropped, then flushes the publisher.
+pub struct EventPublisherSink {
+    topics: PublishTopics,
+    queue: mpsc::Sender<Message>,
+    dropped: AtomicU64,
+    failed: Arc<AtomicU64>,
+}
+
+impl EventPublisherSink {
+    pub fn new(publisher: impl EventPublisher + 'static, topics: PublishTopics) -> Self {
+        Self::with_queue_capacity(publisher, topics, QUEUE_CAPACITY)
+    }
+
+    /// `new` with room for `capacity` unpublished messages
+    pub fn with_queue_capacity(
+        publisher: impl EventPublisher + 'static,
+        topics: PublishTopics,
+        capacity: usize,
+    ) -> Self {
+        let (queue, mut messages) = mpsc::channel::<Message>(capacity);
+        let failed = Arc::new(AtomicU64::new(0));
+        let failures = failed.clone();
+        tokio::spawn(async move {
+            while let Some(message) = messages.recv().await {
+                let key = message.key.as_deref();
+                if let Err(e) = publisher.publish(&message.topic, key, message.payload).await {
+                    failures.fetch_add(1, Ordering::Relaxed);
+                    tracing::warn!(topic = %message.topic, error = %e, "failed to publish event");
+                }
+            }
+            if let Err(e) = publisher.flush().await {
+                tracing::warn!(error = %e, "failed to flush event publisher");
+            }
+        });
+        Self { topics, queue, dropped: AtomicU64::new(0), failed }
+    }
+
+    pub fn topics(&self) -> &PublishTopics {
+        &self.topics
+    }
+
+    /// Queue `event` as JSON for `topic`
+    ///
+    /// Fails without waiting when the queue is full; the event is dropped and counted.
+    pub fn publish<T: Serialize + ?Sized>(
+        &self,
+        topic: &str,
+        key: Option<String>,
+        event: &T,
+    ) -> AnyResult<()> {
+        let payload = serde_json::to_vec(event)?;
+        let message = Message { topic: topic.to_string(), key, payload };
+        self.queue.try_send(message).map_err(|e| {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            match e {
+                mpsc::error::TrySendError::Full(_) => anyhow!("event publisher queue is full"),
+                mpsc::error::TrySendError::Closed(_) => anyhow!("event publisher stopped"),
+            }
+        })
+    }
+
+    /// Publish the lifecycle events of every trade from now on to the lifecycle topic
+    ///
+    /// The returned task ends once the sink is dropped and the next event arrives.
+    pub fn publish_lifecycle(self: &Arc<Self>) -> JoinHandle<()> {
+        let sink = Arc::downgrade(self);
+        let mut events = lifecycle::subscribe();
+        tokio::spawn(async move {
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "event publisher missed lifecycle events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let Some(sink) = sink.upgrade() else { break };
+                let _ = sink.publish(&sink.topics.lifecycle, Some(event.mint.to_string()), &event);
+            }
+        })
+    }
+
+    /// Events dropped because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Events the publisher failed to publish
+    pub fn failed(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+impl EventSink for EventPublisherSink {
+    fn write(&self, event: &RecordedEvent) -> AnyResult<()> {
+        self.publish(&self.topics.events, Some(event.mint().to_string()), event)
+    }
+}
+
+#[cfg(all(test, feature = "pumpfun", feature = "pumpswap"))]
+mod tests {
+    use super::*;
+    use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunTradeEvent;
+    use crate::swqos::TradeType;
+    use crate::trading::core::lifecycle::{TradeLifecycle, TradeStage};
+    use crate::trading::factory::DexType;
+    use solana_sdk::pubkey::Pubkey;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Topic, key and payload of each published message
+    type Published = Arc<Mutex<Vec<(String, Option<String>, serde_json::Value)>>>;
+
+    #[derive(Default)]
+    struct Recorder(Published);
+
+    #[async_trait]
+    impl EventPublisher for Recorder {
+        async fn publish(&self, topic: &str, key: Option<&str>, payload: Vec<u8>) -> AnyResult<()> {
+            let value = serde_json::from_slice(&payload)?;
+            self.0.lock().unwrap().push((topic.to_string(), key.map(str::to_string), value));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_events_and_lifecycle() {
+        let recorder = Recorder::default();
+        let published = recorder.0.clone();
+        let sink = Arc::new(EventPublisherSink::new(recorder, PublishTopics::default()));
+        sink.publish_lifecycle();
+
+        let mint = Pubkey::new_unique();
+        let event = PumpFunTradeEvent { mint, sol_amount: 1_000, ..Default::default() };
+        sink.write(&RecordedEvent::PumpFunTrade(event)).unwrap();
+        let lifecycle = TradeLifecycle::new(DexType::PumpFun, mint, TradeType::Buy);
+        lifecycle.emit(TradeStage::Failed { reason: "expired".to_string() });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Other tests may trade concurrently
+        let published: Vec<_> = published
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, key, _)| key.as_deref() == Some(&mint.to_string()))
+            .cloned()
+            .collect();
+        assert_eq!(published.len(), 2);
+        assert_eq!(published[0].0, "sol_trade.events");
+        assert_eq!(published[0].2["type"], "PumpFunTrade");
+        assert_eq!(published[0].2["event"]["sol_amount"], 1_000);
+        assert_eq!(published[1].0, "sol_trade.lifecycle");
+        assert_eq!(published[1].2["trade_id"], lifecycle.trade_id());
+        assert_eq!(published[1].2["stage"]["kind"], "failed");
+        assert_eq!(sink.dropped(), 0);
+        assert_eq!(sink.failed(), 0);
+    }
+}
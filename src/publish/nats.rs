@@ -0,0 +1,44 @@
+//! NATS publisher
+
+use async_trait::async_trait;
+
+use super::EventPublisher;
+use crate::common::AnyResult;
+
+/// Publishes to NATS subjects
+///
+/// The message key is appended to the subject as its last token, so subscribers can
+/// follow a single mint (`sol_trade.events.<mint>`) or all of them (`sol_trade.events.>`).
+#[derive(Clone)]
+pub struct NatsPublisher {
+    client: async_nats::Client,
+}
+
+impl NatsPublisher {
+    /// Connect to the NATS server at `url`
+    pub async fn connect(url: &str) -> AnyResult<Self> {
+        Ok(Self { client: async_nats::connect(url).await? })
+    }
+
+    /// Publisher on an existing client, for authentication, TLS etc.
+    pub fn from_client(client: async_nats::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl EventPublisher for NatsPublisher {
+    async fn publish(&self, topic: &str, key: Option<&str>, payload: Vec<u8>) -> AnyResult<()> {
+        let subject = match key {
+            Some(key) => format!("{}.{}", topic, key),
+            None => topic.to_string(),
+        };
+        self.client.publish(subject, payload.into()).await?;
+        Ok(())
+    }
+
+    async fn flush(&self) -> AnyResult<()> {
+        self.client.flush().await?;
+        Ok(())
+    }
+}
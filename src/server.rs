@@ -0,0 +1,445 @@
+//! JSON over HTTP / WebSocket service exposing a `SolanaTrade`
+//!
+//! Enabled with the `server` feature. Lets services written in other languages drive a
+//! trading engine running next to the RPC node:
+//!
+//! - `POST /buy`, `POST /sell` - [`TradeRequest`], answered once `buy` / `sell` returns
+//! - `POST /quote` - [`QuoteRequest`], prices from `quote_many`
+//! - `GET /portfolio` - SOL and token balances of the payer, valued with `quote_many`
+//! - `GET /subscribe` - WebSocket of every trade's lifecycle events, one JSON text frame each
+//!
+//! Pubkeys are base58 strings and amounts are lamports or token base units. Failures answer
+//! `{"error": "..."}` with status 400 for invalid requests and 500 otherwise. With
+//! [`ApiAuth::Bearer`] every request must carry `Authorization: Bearer <api_key>`; running
+//! without authentication takes an explicit [`ApiAuth::Disabled`], which `serve` only accepts
+//! on a loopback address.
+//!
+//! ```ignore
+//! let client = Arc::new(SolanaTrade::new(payer, trade_config).await?);
+//! let auth = ApiAuth::Bearer(api_key);
+//! let server = sol_trade_sdk::server::serve(client, "0.0.0.0:8900", auth).await?;
+//! ```
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_account_decoder::UiAccountData;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::common::{SolanaRpcClient, TradeError, TradeResult};
+use crate::trading::common::quote_many;
+use crate::trading::core::lifecycle;
+#[cfg(feature = "bonk")]
+use crate::trading::core::params::BonkParams;
+use crate::trading::core::params::{PumpFunParams, PumpSwapParams};
+#[cfg(feature = "raydium")]
+use crate::trading::core::params::{RaydiumAmmV4Params, RaydiumCpmmParams};
+use crate::trading::core::traits::ProtocolParams;
+use crate::trading::factory::DexType;
+use crate::trading::pumpswap::common::find_pool;
+use crate::SolanaTrade;
+
+/// Buy or sell of one mint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeRequest {
+    pub dex_type: DexType,
+    pub mint: String,
+    /// Lamports a buy spends, tokens a sell sells
+    pub amount: u64,
+    #[serde(default)]
+    pub slippage_basis_points: Option<u64>,
+    /// PumpSwap or Raydium CPMM pool, Raydium AMM V4 amm; PumpSwap pools are looked up from
+    /// the mint when missing
+    #[serde(default)]
+    pub pool: Option<String>,
+    #[serde(default)]
+    pub wait_transaction_confirmed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuoteRequest {
+    pub mints: Vec<String>,
+}
+
+/// Price of a mint, see `MintQuote`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuoteResponse {
+    pub mint: String,
+    pub dex_type: DexType,
+    pub market: String,
+    /// SOL per whole token
+    pub price: f64,
+    pub sol_reserves: u64,
+}
+
+/// Token balance of the payer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Holding {
+    pub mint: String,
+    pub amount: u64,
+    pub decimals: u8,
+    /// SOL per whole token, `None` when the mint has no bonding curve nor PumpSwap pool
+    pub price: Option<f64>,
+    pub value_lamports: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Portfolio {
+    pub wallet: String,
+    pub sol_lamports: u64,
+    pub holdings: Vec<Holding>,
+}
+
+/// Authentication of the requests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiAuth {
+    /// Requests must carry `Authorization: Bearer <api_key>`
+    Bearer(String),
+    /// Anyone who can reach the service can trade with the payer's wallet
+    Disabled,
+}
+
+struct ServerState {
+    client: Arc<SolanaTrade>,
+    auth: ApiAuth,
+}
+
+/// Error answered as `{"error": "..."}`
+struct ApiError(TradeError);
+
+impl<E: Into<TradeError>> From<E> for ApiError {
+    fn from(e: E) -> Self {
+        ApiError(e.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self.0 {
+            TradeError::InvalidParams(_) | TradeError::PoolNotFound(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(json!({ "error": self.0.to_string() }))).into_response()
+    }
+}
+
+/// Routes of the service, to mount in an existing axum server
+///
+/// Fails when the `ApiAuth::Bearer` key is empty or whitespace, which would let a request
+/// with an empty bearer token through.
+pub fn router(client: Arc<SolanaTrade>, auth: ApiAuth) -> anyhow::Result<Router> {
+    if matches!(&auth, ApiAuth::Bearer(api_key) if api_key.trim().is_empty()) {
+        return Err(anyhow::anyhow!("refusing an empty API key, use ApiAuth::Disabled"));
+    }
+    let state = Arc::new(ServerState { client, auth });
+    Ok(Router::new()
+        .route("/buy", post(buy))
+        .route("/sell", post(sell))
+        .route("/quote", post(quote))
+        .route("/portfolio", get(portfolio))
+        .route("/subscribe", get(subscribe))
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state))
+}
+
+/// Serve `router` at `addr` until the returned handle is aborted
+///
+/// Fails without authentication unless `addr` is a loopback address, and with an empty
+/// API key.
+pub async fn serve(
+    client: Arc<SolanaTrade>,
+    addr: impl ToSocketAddrs,
+    auth: ApiAuth,
+) -> anyhow::Result<JoinHandle<()>> {
+    let auth_disabled = auth == ApiAuth::Disabled;
+    let app = router(client, auth)?;
+    let listener = TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    if auth_disabled && !local_addr.ip().is_loopback() {
+        return Err(anyhow::anyhow!(
+            "refusing to serve {} without authentication, use ApiAuth::Bearer",
+            local_addr
+        ));
+    }
+    Ok(tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!(error = %e, "trade server stopped");
+        }
+    }))
+}
+
+async fn authorize(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let ApiAuth::Bearer(api_key) = &state.auth {
+        let bearer = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.as_bytes().strip_prefix(b"Bearer "));
+        if !bearer.is_some_and(|bearer| constant_time_eq(bearer, api_key.as_bytes())) {
+            return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "Unauthorized" })))
+                .into_response();
+        }
+    }
+    next.run(request).await
+}
+
+/// Compare without returning at the first differing byte, so timing doesn't reveal how much
+/// of a guessed key is right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn parse_pubkey(value: &str) -> TradeResult<Pubkey> {
+    Pubkey::from_str(value)
+        .map_err(|_| TradeError::InvalidParams(format!("Invalid pubkey: {}", value)))
+}
+
+async fn buy(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<TradeRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let client = &state.client;
+    let (mint, params) = trade_params(&client.rpc, &request).await?;
+    let recent_blockhash = client.rpc.get_latest_blockhash().await.map_err(anyhow::Error::from)?;
+    client
+        .buy(
+            request.dex_type,
+            mint,
+            request.amount,
+            request.slippage_basis_points,
+            recent_blockhash,
+            None,
+            params,
+            None,
+            request.wait_transaction_confirmed,
+        )
+        .await?;
+    Ok(Json(json!({ "ok": true })))
+}
+
+async fn sell(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<TradeRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let client = &state.client;
+    let (mint, params) = trade_params(&client.rpc, &request).await?;
+    let recent_blockhash = client.rpc.get_latest_blockhash().await.map_err(anyhow::Error::from)?;
+    client
+        .sell(
+            request.dex_type,
+            mint,
+            request.amount,
+            request.slippage_basis_points,
+            recent_blockhash,
+            None,
+            true,
+            params,
+            None,
+            request.wait_transaction_confirmed,
+        )
+        .await?;
+    Ok(Json(json!({ "ok": true })))
+}
+
+/// Mint and protocol params of `request`'s market, read from RPC
+async fn trade_params(
+    rpc: &SolanaRpcClient,
+    request: &TradeRequest,
+) -> TradeResult<(Pubkey, Box<dyn ProtocolParams>)> {
+    let mint = parse_pubkey(&request.mint)?;
+    let pool = request.pool.as_deref().map(parse_pubkey).transpose()?;
+    let pool_required =
+        || TradeError::InvalidParams(format!("{} trades need the pool address", request.dex_type));
+    let params: Box<dyn ProtocolParams> = match request.dex_type {
+        DexType::PumpFun => Box::new(PumpFunParams::from_mint_by_rpc(rpc, &mint, None).await?),
+        DexType::PumpSwap => {
+            let pool = match pool {
+                Some(pool) => pool,
+                None => find_pool(rpc, &mint).await?,
+            };
+            Box::new(PumpSwapParams::from_pool_address_by_rpc(rpc, &pool).await?)
+        }
+        #[cfg(feature = "bonk")]
+        DexType::Bonk => Box::new(BonkParams::from_mint_by_rpc(rpc, &mint).await?),
+        #[cfg(feature = "raydium")]
+        DexType::RaydiumCpmm => {
+            let pool = pool.ok_or_else(pool_required)?;
+            Box::new(RaydiumCpmmParams::from_pool_address_by_rpc(rpc, &pool).await?)
+        }
+        #[cfg(feature = "raydium")]
+        DexType::RaydiumAmmV4 => {
+            let amm = pool.ok_or_else(pool_required)?;
+            Box::new(RaydiumAmmV4Params::from_amm_address_by_rpc(rpc, amm).await?)
+        }
+    };
+    Ok((mint, params))
+}
+
+async fn quote(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<QuoteRequest>,
+) -> Result<Json<Vec<QuoteResponse>>, ApiError> {
+    let mints =
+        request.mints.iter().map(|mint| parse_pubkey(mint)).collect::<Result<Vec<_>, _>>()?;
+    let quotes = quote_many(&state.client.rpc, &mints).await?;
+    let response = mints
+        .iter()
+        .filter_map(|mint| {
+            let quote = quotes.get(mint)?;
+            Some(QuoteResponse {
+                mint: mint.to_string(),
+                dex_type: quote.dex_type.clone(),
+                market: quote.market.to_string(),
+                price: quote.price,
+                sol_reserves: quote.sol_reserves,
+            })
+        })
+        .collect();
+    Ok(Json(response))
+}
+
+async fn portfolio(State(state): State<Arc<ServerState>>) -> Result<Json<Portfolio>, ApiError> {
+    let rpc = &state.client.rpc;
+    let wallet = state.client.payer.pubkey();
+    let sol_lamports = rpc.get_balance(&wallet).await.map_err(anyhow::Error::from)?;
+
+    let mut balances = vec![];
+    for program_id in [spl_token::ID, spl_token_2022::ID] {
+        let accounts = rpc
+            .get_token_accounts_by_owner(&wallet, TokenAccountsFilter::ProgramId(program_id))
+            .await
+            .map_err(anyhow::Error::from)?;
+        balances.extend(accounts.iter().filter_map(|keyed| {
+            let UiAccountData::Json(parsed) = &keyed.account.data else {
+                return None;
+            };
+            let info = parsed.parsed.get("info")?;
+            let mint: Pubkey = info["mint"].as_str()?.parse().ok()?;
+            let amount: u64 = info["tokenAmount"]["amount"].as_str()?.parse().ok()?;
+            let decimals: u8 = info["tokenAmount"]["decimals"].as_u64()?.try_into().ok()?;
+            Some((mint, amount, decimals))
+        }));
+    }
+    // Several accounts of the same mint add up
+    let mut by_mint: HashMap<Pubkey, (u64, u8)> = HashMap::new();
+    for (mint, amount, decimals) in balances {
+        if mint == spl_token::native_mint::ID || amount == 0 {
+            continue;
+        }
+        by_mint.entry(mint).or_insert((0, decimals)).0 += amount;
+    }
+    let mints: Vec<Pubkey> = by_mint.keys().copied().collect();
+    let quotes = quote_many(rpc, &mints).await?;
+    let mut holdings: Vec<Holding> = by_mint
+        .into_iter()
+        .map(|(mint, (amount, decimals))| {
+            let price = quotes.get(&mint).map(|quote| quote.price);
+            Holding {
+                mint: mint.to_string(),
+                amount,
+                decimals,
+                price,
+                value_lamports: price.map(|price| holding_value(amount, decimals, price)),
+            }
+        })
+        .collect();
+    holdings.sort_by_key(|holding| std::cmp::Reverse(holding.value_lamports));
+    Ok(Json(Portfolio { wallet: wallet.to_string(), sol_lamports, holdings }))
+}
+
+/// Lamports `amount` base units are worth at `price` SOL per whole token
+fn holding_value(amount: u64, decimals: u8, price: f64) -> u64 {
+    (amount as f64 / 10f64.powi(decimals as i32) * price * 1e9).round() as u64
+}
+
+async fn subscribe(ws: WebSocketUpgrade) -> Response {
+    let events = lifecycle::subscribe();
+    ws.on_upgrade(move |socket| forward_lifecycle(socket, events))
+}
+
+async fn forward_lifecycle(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<lifecycle::TradeLifecycleEvent>,
+) {
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::warn!(skipped, "trade server subscriber missed lifecycle events");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let Ok(text) = serde_json::to_string(&event) else { continue };
+        if socket.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trade_request_and_holding_value() {
+        let request: TradeRequest = serde_json::from_value(json!({
+            "dex_type": "PumpSwap",
+            "mint": Pubkey::new_unique().to_string(),
+            "amount": 100_000_000,
+        }))
+        .unwrap();
+        assert_eq!(request.dex_type, DexType::PumpSwap);
+        assert_eq!(request.slippage_basis_points, None);
+        assert_eq!(request.pool, None);
+        assert!(!request.wait_transaction_confirmed);
+        assert!(matches!(parse_pubkey("not a pubkey"), Err(TradeError::InvalidParams(_))));
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+
+        // 2,000 whole tokens at 0.00005 SOL
+        assert_eq!(holding_value(2_000_000_000, 6, 0.000_05), 100_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_serve_without_auth_only_on_loopback() {
+        use crate::common::TradeConfig;
+        use solana_sdk::signature::Keypair;
+
+        let config = TradeConfig::builder("http://127.0.0.1:8899").build().unwrap();
+        let client = Arc::new(SolanaTrade::new(Arc::new(Keypair::new()), config).await.unwrap());
+        assert!(serve(client.clone(), "0.0.0.0:0", ApiAuth::Disabled).await.is_err());
+        let server = serve(client.clone(), "127.0.0.1:0", ApiAuth::Disabled).await.unwrap();
+        server.abort();
+        let auth = ApiAuth::Bearer("key".to_string());
+        serve(client.clone(), "0.0.0.0:0", auth).await.unwrap().abort();
+
+        // An unset key must not turn into "any empty bearer token is accepted"
+        for api_key in ["", "  "] {
+            let auth = ApiAuth::Bearer(api_key.to_string());
+            assert!(router(client.clone(), auth.clone()).is_err());
+            assert!(serve(client.clone(), "127.0.0.1:0", auth).await.is_err());
+        }
+    }
+}
@@ -0,0 +1,434 @@
+//! Strategies
+//!
+//! Implement [`Strategy`] and hand it to a [`StrategyRunner`]; the runner subscribes to the
+//! tokens, feeds every event to `on_event`, executes the returned orders through
+//! `SolanaTrade` and reports each result to `on_fill`. `on_timer` is called at a fixed
+//! interval for time based exits. Orders are routed to the PumpFun bonding curve, or to the
+//! PumpSwap pool once the token graduated, and sent with confirmation.
+//!
+//! The runner keeps positions in a `PositionTracker`, shared with the `PaperTrader` when the
+//! client paper trades, so a strategy can be validated risk-free before going live.
+//!
+//! ```ignore
+//! struct TakeProfit;
+//!
+//! impl Strategy for TakeProfit {
+//!     fn on_event(&mut self, mint: &Pubkey, _: &TokenEvent, ctx: &StrategyContext) -> Vec<Order> {
+//!         match ctx.position(mint) {
+//!             None => vec![Order::Buy { mint: *mint, sol_amount: 100_000_000 }],
+//!             Some(_) => vec![],
+//!         }
+//!     }
+//! }
+//!
+//! let handle = StrategyRunner::new(client, TakeProfit).start(&grpc, vec![mint]).await?;
+//! ```
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::common::{mint_info_cache::MintInfoCache, AnyResult};
+use crate::notify::TradeNotification;
+use crate::solana_streamer_sdk::streaming::YellowstoneGrpc;
+use crate::streaming::{subscribe_tokens, TokenEvent};
+use crate::trading::{
+    common::{fetch_pumpfun_trade_accounts, utils::get_token_account_balance},
+    core::{
+        params::{PumpFunParams, PumpSwapParams},
+        traits::ProtocolParams,
+    },
+    factory::DexType,
+    paper::{quote_buy, quote_sell},
+    pumpswap::common::find_pool,
+    Position, PositionTracker,
+};
+use crate::SolanaTrade;
+
+/// Order returned by a strategy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Order {
+    /// Buy for `sol_amount` lamports
+    Buy { mint: Pubkey, sol_amount: u64 },
+    /// Sell `token_amount` base units
+    Sell { mint: Pubkey, token_amount: u64 },
+    /// Sell the whole position
+    SellAll { mint: Pubkey },
+}
+
+impl Order {
+    pub fn mint(&self) -> Pubkey {
+        match self {
+            Order::Buy { mint, .. } | Order::Sell { mint, .. } | Order::SellAll { mint } => *mint,
+        }
+    }
+}
+
+/// Result of an order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub order: Order,
+    /// Venue the order was routed to, `None` when routing failed
+    pub dex_type: Option<DexType>,
+    /// Lamports spent, or received as quoted from the reserves for sells
+    pub sol_amount: u64,
+    /// Tokens received or sold
+    pub token_amount: u64,
+    /// Why the order failed, `None` when it was filled
+    pub error: Option<String>,
+}
+
+impl Fill {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// State shared between the runner and the strategy
+pub struct StrategyContext {
+    client: Arc<SolanaTrade>,
+    tracker: Arc<PositionTracker>,
+}
+
+impl StrategyContext {
+    pub fn client(&self) -> &Arc<SolanaTrade> {
+        &self.client
+    }
+
+    pub fn tracker(&self) -> &Arc<PositionTracker> {
+        &self.tracker
+    }
+
+    /// Open position in `mint`
+    pub fn position(&self, mint: &Pubkey) -> Option<Position> {
+        self.tracker.position(mint).filter(Position::is_open)
+    }
+
+    /// All open positions
+    pub fn positions(&self) -> Vec<Position> {
+        self.tracker.positions().into_iter().filter(Position::is_open).collect()
+    }
+
+    /// Send a notification through the client's notifiers, e.g. `StopLossTriggered`
+    pub fn notify(&self, notification: TradeNotification) {
+        if let Some(notifications) = &self.client.notifications {
+            notifications.notify(notification);
+        }
+    }
+}
+
+/// Trading logic driven by a `StrategyRunner`
+///
+/// The callbacks run on the runner task and should return quickly; the orders they return
+/// are executed concurrently.
+pub trait Strategy: Send + 'static {
+    /// Called for every event of a subscribed token
+    fn on_event(&mut self, mint: &Pubkey, event: &TokenEvent, ctx: &StrategyContext) -> Vec<Order>;
+
+    /// Called when an order completed or failed
+    fn on_fill(&mut self, _fill: &Fill, _ctx: &StrategyContext) -> Vec<Order> {
+        vec![]
+    }
+
+    /// Called every `timer_interval`
+    fn on_timer(&mut self, _ctx: &StrategyContext) -> Vec<Order> {
+        vec![]
+    }
+}
+
+/// Settings of a `StrategyRunner`
+#[derive(Debug, Clone, Default)]
+pub struct StrategyRunnerConfig {
+    /// Slippage of every order, the SDK default when `None`
+    pub slippage_basis_points: Option<u64>,
+    /// Interval of `on_timer`, never called when `None`
+    pub timer_interval: Option<Duration>,
+}
+
+/// Where a token trades
+#[derive(Debug, Clone, Copy)]
+enum Route {
+    PumpFun,
+    PumpSwap(Pubkey),
+}
+
+struct Executor {
+    ctx: Arc<StrategyContext>,
+    config: StrategyRunnerConfig,
+    routes: Mutex<HashMap<Pubkey, Route>>,
+}
+
+/// Runs a `Strategy` against live events
+pub struct StrategyRunner<S: Strategy> {
+    strategy: S,
+    executor: Arc<Executor>,
+}
+
+impl<S: Strategy> StrategyRunner<S> {
+    pub fn new(client: Arc<SolanaTrade>, strategy: S) -> Self {
+        Self::with_config(client, strategy, StrategyRunnerConfig::default())
+    }
+
+    pub fn with_config(
+        client: Arc<SolanaTrade>,
+        strategy: S,
+        config: StrategyRunnerConfig,
+    ) -> Self {
+        let tracker = match &client.paper_trader {
+            Some(paper_trader) => paper_trader.tracker().clone(),
+            None => Arc::new(PositionTracker::new()),
+        };
+        let ctx = Arc::new(StrategyContext { client, tracker });
+        Self { strategy, executor: Arc::new(Executor { ctx, config, routes: Default::default() }) }
+    }
+
+    pub fn context(&self) -> &Arc<StrategyContext> {
+        &self.executor.ctx
+    }
+
+    /// Subscribe to `mints` and run the strategy until the subscription ends
+    ///
+    /// The subscription replaces any other one on `grpc`; call `grpc.stop()` to end it.
+    /// The returned task finishes once the stream is closed and pending orders completed.
+    pub async fn start(
+        self,
+        grpc: &YellowstoneGrpc,
+        mints: Vec<Pubkey>,
+    ) -> AnyResult<JoinHandle<()>> {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        subscribe_tokens(grpc, mints, move |mint, event| {
+            let _ = event_tx.send((mint, event));
+        })
+        .await?;
+        Ok(tokio::spawn(self.run(event_rx)))
+    }
+
+    async fn run(mut self, mut events: mpsc::UnboundedReceiver<(Pubkey, TokenEvent)>) {
+        let (fill_tx, mut fills) = mpsc::unbounded_channel::<Fill>();
+        let mut timer = self.executor.config.timer_interval.map(|interval| {
+            let mut timer = tokio::time::interval(interval);
+            timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            timer
+        });
+        let ctx = self.executor.ctx.clone();
+        let mut streaming = true;
+        let mut pending = 0usize;
+        while streaming || pending > 0 {
+            let orders = tokio::select! {
+                event = events.recv(), if streaming => match event {
+                    Some((mint, event)) => {
+                        self.executor.observe(&mint, &event);
+                        if let Some(notification) = dev_sold(&mint, &event) {
+                            ctx.notify(notification);
+                        }
+                        self.strategy.on_event(&mint, &event, &ctx)
+                    }
+                    None => {
+                        streaming = false;
+                        vec![]
+                    }
+                },
+                Some(fill) = fills.recv() => {
+                    pending -= 1;
+                    self.strategy.on_fill(&fill, &ctx)
+                }
+                _ = async { timer.as_mut().unwrap().tick().await }, if timer.is_some() && streaming => {
+                    self.strategy.on_timer(&ctx)
+                }
+            };
+            for order in orders {
+                pending += 1;
+                let executor = self.executor.clone();
+                let fill_tx = fill_tx.clone();
+                tokio::spawn(async move {
+                    let _ = fill_tx.send(executor.execute(order).await);
+                });
+            }
+        }
+    }
+}
+
+/// `DevSold` when the creator of the token sells
+fn dev_sold(mint: &Pubkey, event: &TokenEvent) -> Option<TradeNotification> {
+    let (dev, token_amount, sol_amount) = match event {
+        TokenEvent::PumpFunTrade(e) if !e.is_buy && e.user == e.creator => {
+            (e.user, e.token_amount, e.sol_amount)
+        }
+        TokenEvent::PumpSwapSell(e) if e.user == e.coin_creator => {
+            (e.user, e.base_amount_in, e.quote_amount_out)
+        }
+        _ => return None,
+    };
+    Some(TradeNotification::DevSold { mint: *mint, dev, token_amount, sol_amount })
+}
+
+/// Tokens held: the virtual position when paper trading, the wallet balance otherwise
+pub(crate) async fn held_tokens(client: &SolanaTrade, mint: &Pubkey) -> u64 {
+    if let Some(paper_trader) = &client.paper_trader {
+        return paper_trader.tracker().position(mint).map_or(0, |position| position.token_amount);
+    }
+    let Ok(mint_info) = MintInfoCache::get_instance().get_or_fetch(&client.rpc, mint).await else {
+        return 0;
+    };
+    let token_account = spl_associated_token_account::get_associated_token_address_with_program_id(
+        &client.payer.pubkey(),
+        mint,
+        &mint_info.token_program,
+    );
+    get_token_account_balance(&client.rpc, &token_account, mint).await.unwrap_or(0)
+}
+
+impl Executor {
+    /// Follow graduation and the PumpSwap pool from the stream
+    fn observe(&self, mint: &Pubkey, event: &TokenEvent) {
+        let route = match event {
+            TokenEvent::Graduated(e) => Route::PumpSwap(e.pool),
+            TokenEvent::PumpSwapBuy(e) => Route::PumpSwap(e.pool),
+            TokenEvent::PumpSwapSell(e) => Route::PumpSwap(e.pool),
+            _ => return,
+        };
+        self.routes.lock().unwrap().insert(*mint, route);
+    }
+
+    /// Protocol params of `mint`'s market, on the bonding curve along with the payer's token
+    /// balance read in the same round trip
+    async fn params(
+        &self,
+        mint: &Pubkey,
+    ) -> AnyResult<(DexType, Box<dyn ProtocolParams>, Option<u64>)> {
+        let rpc = &self.ctx.client.rpc;
+        let route = self.routes.lock().unwrap().get(mint).copied().unwrap_or(Route::PumpFun);
+        let pool = match route {
+            Route::PumpSwap(pool) => pool,
+            Route::PumpFun => {
+                let payer = self.ctx.client.payer.pubkey();
+                let accounts = fetch_pumpfun_trade_accounts(rpc, mint, &payer).await?;
+                if !accounts.bonding_curve.complete {
+                    let params = PumpFunParams::from_trade_accounts(&accounts, None);
+                    let held = accounts.user_token_balance.unwrap_or(0);
+                    return Ok((DexType::PumpFun, Box::new(params), Some(held)));
+                }
+                let pool = find_pool(rpc, mint).await?;
+                self.routes.lock().unwrap().insert(*mint, Route::PumpSwap(pool));
+                pool
+            }
+        };
+        let params = PumpSwapParams::from_pool_address_by_rpc(rpc, &pool).await?;
+        Ok((DexType::PumpSwap, Box::new(params), None))
+    }
+
+    async fn balance(&self, mint: &Pubkey) -> u64 {
+        held_tokens(&self.ctx.client, mint).await
+    }
+
+    async fn execute(&self, order: Order) -> Fill {
+        let mut fill = Fill {
+            order: order.clone(),
+            dex_type: None,
+            sol_amount: 0,
+            token_amount: 0,
+            error: None,
+        };
+        if let Err(e) = self.try_execute(&order, &mut fill).await {
+            tracing::warn!(mint = %order.mint(), error = %e, "strategy order failed");
+            fill.error = Some(e.to_string());
+        }
+        fill
+    }
+
+    async fn try_execute(&self, order: &Order, fill: &mut Fill) -> AnyResult<()> {
+        let client = &self.ctx.client;
+        let mint = order.mint();
+        let (dex_type, params, wallet_balance) = self.params(&mint).await?;
+        fill.dex_type = Some(dex_type.clone());
+        let slippage = self.config.slippage_basis_points;
+        let paper = client.paper_trader.is_some();
+        let recent_blockhash = client.rpc.get_latest_blockhash().await?;
+        match *order {
+            Order::Buy { sol_amount, .. } => {
+                let before = match wallet_balance {
+                    _ if paper => 0,
+                    Some(balance) => balance,
+                    None => self.balance(&mint).await,
+                };
+                let quoted = quote_buy(&dex_type, params.as_ref(), sol_amount)?;
+                client
+                    .buy(
+                        dex_type,
+                        mint,
+                        sol_amount,
+                        slippage,
+                        recent_blockhash,
+                        None,
+                        params,
+                        None,
+                        true,
+                    )
+                    .await?;
+                fill.sol_amount = sol_amount;
+                fill.token_amount = if paper {
+                    quoted
+                } else {
+                    let received = self.balance(&mint).await.saturating_sub(before);
+                    self.ctx.tracker.record_buy(mint, sol_amount, received, 0);
+                    received
+                };
+            }
+            Order::Sell { .. } | Order::SellAll { .. } => {
+                let held = match wallet_balance {
+                    Some(balance) if !paper => balance,
+                    _ => self.balance(&mint).await,
+                };
+                let token_amount = match *order {
+                    Order::Sell { token_amount, .. } => token_amount.min(held),
+                    _ => held,
+                };
+                if token_amount == 0 {
+                    return Err(anyhow::anyhow!("No token balance to sell"));
+                }
+                let quoted = quote_sell(&dex_type, params.as_ref(), token_amount)?;
+                client
+                    .sell(
+                        dex_type,
+                        mint,
+                        token_amount,
+                        slippage,
+                        recent_blockhash,
+                        None,
+                        true,
+                        params,
+                        None,
+                        true,
+                    )
+                    .await?;
+                if !paper {
+                    self.ctx.tracker.record_sell(mint, token_amount, quoted, 0);
+                }
+                fill.sol_amount = quoted;
+                fill.token_amount = token_amount;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_mint() {
+        let mint = Pubkey::new_unique();
+        let orders = [
+            Order::Buy { mint, sol_amount: 1 },
+            Order::Sell { mint, token_amount: 1 },
+            Order::SellAll { mint },
+        ];
+        assert!(orders.iter().all(|order| order.mint() == mint));
+    }
+}
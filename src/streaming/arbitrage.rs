@@ -0,0 +1,121 @@
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::common::{AnyResult, SolanaRpcClient};
+use crate::solana_streamer_sdk::{
+    match_event,
+    streaming::{
+        event_parser::{
+            common::{filter::EventTypeFilter, EventType},
+            protocols::{
+                pumpswap::{PumpSwapBuyEvent, PumpSwapSellEvent},
+                raydium_amm_v4::RaydiumAmmV4SwapEvent,
+                raydium_cpmm::RaydiumCpmmSwapEvent,
+            },
+            Protocol, UnifiedEvent,
+        },
+        yellowstone_grpc::{AccountFilter, TransactionFilter},
+        YellowstoneGrpc,
+    },
+};
+use crate::trading::arbitrage::{ArbitrageWatcher, Venue};
+
+/// Swaps `watch_arbitrage` consumes, the parser skips every other instruction
+const SWAP_EVENT_TYPES: [EventType; 6] = [
+    EventType::PumpSwapBuy,
+    EventType::PumpSwapSell,
+    EventType::RaydiumCpmmSwapBaseInput,
+    EventType::RaydiumCpmmSwapBaseOutput,
+    EventType::RaydiumAmmV4SwapBaseIn,
+    EventType::RaydiumAmmV4SwapBaseOut,
+];
+
+/// Feed `watcher` from PumpSwap and Raydium activity on `mints`
+///
+/// PumpSwap quotes are taken from the trade events. A swap on a Raydium pool triggers an RPC
+/// reload of its reserves; CPMM pools trading one of `mints` are picked up automatically,
+/// while AMM V4 swaps don't reference the mint, so seed those pools with
+/// `ArbitrageWatcher::refresh_raydium_amm_v4` before subscribing.
+///
+/// # Arguments
+/// * `grpc` - Yellowstone gRPC client
+/// * `rpc` - RPC client used to reload Raydium reserves
+/// * `watcher` - Receives the quotes and reports opportunities to its callback
+/// * `mints` - Tokens to watch
+pub async fn watch_arbitrage(
+    grpc: &YellowstoneGrpc,
+    rpc: Arc<SolanaRpcClient>,
+    watcher: Arc<ArbitrageWatcher>,
+    mints: Vec<Pubkey>,
+) -> AnyResult<()> {
+    let mut accounts: Vec<String> = mints.iter().map(Pubkey::to_string).collect();
+    accounts.extend(watcher.pools().iter().map(Pubkey::to_string));
+    let transaction_filter = TransactionFilter {
+        account_include: accounts,
+        account_exclude: vec![],
+        account_required: vec![],
+    };
+    let account_filter = AccountFilter { account: vec![], owner: vec![] };
+
+    let mints: Arc<HashSet<Pubkey>> = Arc::new(mints.into_iter().collect());
+    // Pools with a reserve reload in flight, bursts of swaps need only one
+    let refreshing: Arc<Mutex<HashSet<Pubkey>>> = Arc::new(Mutex::new(HashSet::new()));
+    let refresh = {
+        let watcher = watcher.clone();
+        move |venue: Venue, pool: Pubkey| {
+            if !refreshing.lock().unwrap().insert(pool) {
+                return;
+            }
+            let (watcher, rpc, refreshing) = (watcher.clone(), rpc.clone(), refreshing.clone());
+            tokio::spawn(async move {
+                let result = match venue {
+                    Venue::RaydiumCpmm => watcher.refresh_raydium_cpmm(&rpc, &pool).await,
+                    _ => watcher.refresh_raydium_amm_v4(&rpc, &pool).await,
+                };
+                if let Err(e) = result {
+                    crate::metrics::record_rpc_error("arbitrage");
+                    tracing::debug!(%pool, error = %e, "raydium reserve reload failed");
+                }
+                refreshing.lock().unwrap().remove(&pool);
+            });
+        }
+    };
+
+    let event_callback = move |event: Box<dyn UnifiedEvent>| {
+        crate::metrics::record_parsed_event("arbitrage");
+        match_event!(event, {
+            PumpSwapBuyEvent => |e: PumpSwapBuyEvent| {
+                if mints.contains(&e.base_mint) {
+                    watcher.on_pumpswap_buy(&e);
+                }
+            },
+            PumpSwapSellEvent => |e: PumpSwapSellEvent| {
+                if mints.contains(&e.base_mint) {
+                    watcher.on_pumpswap_sell(&e);
+                }
+            },
+            RaydiumCpmmSwapEvent => |e: RaydiumCpmmSwapEvent| {
+                if mints.contains(&e.input_token_mint) || mints.contains(&e.output_token_mint) {
+                    refresh(Venue::RaydiumCpmm, e.pool_state);
+                }
+            },
+            RaydiumAmmV4SwapEvent => |e: RaydiumAmmV4SwapEvent| {
+                if watcher.pool_mint(&e.amm).is_some_and(|mint| mints.contains(&mint)) {
+                    refresh(Venue::RaydiumAmmV4, e.amm);
+                }
+            },
+        });
+    };
+
+    grpc.subscribe_events_immediate(
+        vec![Protocol::PumpSwap, Protocol::RaydiumCpmm, Protocol::RaydiumAmmV4],
+        None,
+        transaction_filter,
+        account_filter,
+        Some(EventTypeFilter { include: SWAP_EVENT_TYPES.to_vec() }),
+        None,
+        event_callback,
+    )
+    .await
+}
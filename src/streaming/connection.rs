@@ -0,0 +1,284 @@
+//! Connection options for private gRPC providers
+//!
+//! Private geyser providers authenticate with an `x-token`, basic auth or other headers, may
+//! use certificates signed by their own CA and drop idle HTTP/2 connections without
+//! keepalives. [`GrpcConnectOptions`] collects these settings:
+//!
+//! ```ignore
+//! let options = GrpcConnectOptions::default()
+//!     .with_x_token(token)
+//!     .with_header("x-api-key", api_key)
+//!     .with_ca_certificate(std::fs::read("provider-ca.pem")?)
+//!     .with_connect_timeout(Duration::from_secs(5))
+//!     .with_keepalive(Duration::from_secs(10), Duration::from_secs(5));
+//! let mut geyser = options.connect_geyser("https://geyser.example.com:443").await?;
+//! ```
+//!
+//! `connect_geyser` applies every option. The `YellowstoneGrpc` client of the streamer SDK
+//! builds its own connection and only carries the token and timeouts, so `yellowstone`
+//! refuses options it would silently drop.
+//!
+//! The same options connect to a remote shredstream-proxy. `shredstream` builds the SDK
+//! `ShredStreamGrpc` over TLS with a server name override instead of assuming a plaintext
+//! localhost endpoint; a proxy behind an authenticating gateway needs the token headers of
+//! `connect_shredstream`, which the SDK client cannot send.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use base64::Engine;
+use rustls::crypto::{ring::default_provider, CryptoProvider};
+use tonic::{
+    metadata::{AsciiMetadataKey, AsciiMetadataValue},
+    service::{interceptor::InterceptedService, Interceptor},
+    transport::{Certificate, Channel, ClientTlsConfig, Endpoint},
+    Request, Status,
+};
+use yellowstone_grpc_proto::prelude::geyser_client::GeyserClient;
+
+use crate::common::AnyResult;
+use crate::solana_streamer_sdk::{
+    protos::shredstream::shredstream_proxy_client::ShredstreamProxyClient,
+    streaming::{
+        common::{MetricsManager, PerformanceMetrics, StreamClientConfig},
+        ShredStreamGrpc, YellowstoneGrpc,
+    },
+};
+
+/// Authentication, TLS, timeout and keepalive settings of a gRPC connection
+#[derive(Debug, Clone, Default)]
+pub struct GrpcConnectOptions {
+    /// Sent as the `x-token` header
+    pub x_token: Option<String>,
+    /// Extra headers sent with every request
+    pub headers: Vec<(String, String)>,
+    /// PEM encoded CA certificate trusted besides the native roots
+    pub ca_certificate: Option<Vec<u8>>,
+    /// Name the server certificate is verified against, the endpoint host by default
+    pub domain_name: Option<String>,
+    pub connect_timeout: Option<Duration>,
+    pub request_timeout: Option<Duration>,
+    /// HTTP/2 keepalive ping interval, also sent while the connection is idle
+    pub keepalive_interval: Option<Duration>,
+    /// Time to wait for a keepalive ping acknowledgement before closing the connection
+    pub keepalive_timeout: Option<Duration>,
+}
+
+impl GrpcConnectOptions {
+    pub fn with_x_token(mut self, x_token: impl Into<String>) -> Self {
+        self.x_token = Some(x_token.into());
+        self
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// `authorization: Basic ...` header
+    pub fn with_basic_auth(self, username: &str, password: &str) -> Self {
+        let credentials =
+            base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+        self.with_header("authorization", format!("Basic {}", credentials))
+    }
+
+    pub fn with_ca_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.ca_certificate = Some(pem.into());
+        self
+    }
+
+    pub fn with_domain_name(mut self, domain_name: impl Into<String>) -> Self {
+        self.domain_name = Some(domain_name.into());
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_keepalive(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.keepalive_interval = Some(interval);
+        self.keepalive_timeout = Some(timeout);
+        self
+    }
+
+    /// Endpoint with TLS for `https` URLs, timeouts and keepalive applied
+    pub fn endpoint(&self, url: &str) -> AnyResult<Endpoint> {
+        let mut endpoint = Endpoint::from_shared(url.to_string())?;
+        if url.starts_with("https://") {
+            if CryptoProvider::get_default().is_none() {
+                // Fails only when another thread installed one meanwhile
+                let _ = default_provider().install_default();
+            }
+            let mut tls = ClientTlsConfig::new().with_native_roots();
+            if let Some(pem) = &self.ca_certificate {
+                tls = tls.ca_certificate(Certificate::from_pem(pem));
+            }
+            if let Some(domain_name) = &self.domain_name {
+                tls = tls.domain_name(domain_name);
+            }
+            endpoint = endpoint.tls_config(tls)?;
+        } else if self.ca_certificate.is_some() || self.domain_name.is_some() {
+            bail!("TLS options set for plaintext endpoint {}", url);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            endpoint = endpoint.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.request_timeout {
+            endpoint = endpoint.timeout(timeout);
+        }
+        if let Some(interval) = self.keepalive_interval {
+            endpoint = endpoint.http2_keep_alive_interval(interval).keep_alive_while_idle(true);
+        }
+        if let Some(timeout) = self.keepalive_timeout {
+            endpoint = endpoint.keep_alive_timeout(timeout);
+        }
+        Ok(endpoint)
+    }
+
+    /// Interceptor adding the token and headers to every request
+    pub fn interceptor(&self) -> AnyResult<HeaderInterceptor> {
+        let headers = self
+            .x_token
+            .iter()
+            .map(|x_token| ("x-token", x_token.as_str()))
+            .chain(self.headers.iter().map(|(name, value)| (name.as_str(), value.as_str())))
+            .map(|(name, value)| {
+                let key = AsciiMetadataKey::from_bytes(name.as_bytes())
+                    .map_err(|_| anyhow!("Invalid header name {}", name))?;
+                let value = AsciiMetadataValue::try_from(value)
+                    .map_err(|_| anyhow!("Invalid value for header {}", name))?;
+                Ok((key, value))
+            })
+            .collect::<AnyResult<Vec<_>>>()?;
+        Ok(HeaderInterceptor { headers })
+    }
+
+    /// Geyser client connected to `url` with all options applied
+    pub async fn connect_geyser(
+        &self,
+        url: &str,
+    ) -> AnyResult<GeyserClient<InterceptedService<Channel, HeaderInterceptor>>> {
+        let interceptor = self.interceptor()?;
+        let channel = self.endpoint(url)?.connect().await?;
+        Ok(GeyserClient::with_interceptor(channel, interceptor))
+    }
+
+    /// Streamer SDK client for `url`
+    ///
+    /// The SDK client applies the token and the timeouts, rounded up to whole seconds. It
+    /// connects over TLS with the native roots, so headers, a custom CA, a domain name or
+    /// keepalive are an error; use `connect_geyser` for those providers.
+    pub fn yellowstone(
+        &self,
+        url: &str,
+        mut config: StreamClientConfig,
+    ) -> AnyResult<YellowstoneGrpc> {
+        if !self.headers.is_empty()
+            || self.ca_certificate.is_some()
+            || self.domain_name.is_some()
+            || self.keepalive_interval.is_some()
+            || self.keepalive_timeout.is_some()
+        {
+            bail!("YellowstoneGrpc only supports the x-token and timeout options");
+        }
+        if let Some(timeout) = self.connect_timeout {
+            config.connection.connect_timeout = whole_seconds(timeout);
+        }
+        if let Some(timeout) = self.request_timeout {
+            config.connection.request_timeout = whole_seconds(timeout);
+        }
+        YellowstoneGrpc::new_with_config(url.to_string(), self.x_token.clone(), config)
+    }
+
+    /// ShredStream proxy client connected to `url` with all options applied
+    pub async fn connect_shredstream(
+        &self,
+        url: &str,
+    ) -> AnyResult<ShredstreamProxyClient<InterceptedService<Channel, HeaderInterceptor>>> {
+        let interceptor = self.interceptor()?;
+        let channel = self.endpoint(url)?.connect().await?;
+        Ok(ShredstreamProxyClient::with_interceptor(channel, interceptor))
+    }
+
+    /// Streamer SDK ShredStream client for `url`
+    ///
+    /// TLS, the domain name, timeouts and keepalive are applied. The SDK client takes a bare
+    /// channel, so a token or headers are an error; use `connect_shredstream` for those.
+    pub async fn shredstream(
+        &self,
+        url: &str,
+        config: StreamClientConfig,
+    ) -> AnyResult<ShredStreamGrpc> {
+        if self.x_token.is_some() || !self.headers.is_empty() {
+            bail!("ShredStreamGrpc cannot send a token or headers");
+        }
+        let channel = self.endpoint(url)?.connect().await?;
+        let metrics = Arc::new(tokio::sync::Mutex::new(PerformanceMetrics::new()));
+        let metrics_manager =
+            MetricsManager::new(metrics.clone(), Arc::new(config.clone()), "ShredStream".into());
+        Ok(ShredStreamGrpc {
+            shredstream_client: Arc::new(ShredstreamProxyClient::new(channel)),
+            config,
+            metrics,
+            metrics_manager,
+            subscription_handle: Default::default(),
+        })
+    }
+}
+
+fn whole_seconds(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// Adds fixed metadata to every request
+#[derive(Debug, Clone, Default)]
+pub struct HeaderInterceptor {
+    headers: Vec<(AsciiMetadataKey, AsciiMetadataValue)>,
+}
+
+impl Interceptor for HeaderInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        for (key, value) in &self.headers {
+            request.metadata_mut().insert(key.clone(), value.clone());
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headers_and_sdk_restrictions() {
+        let options = GrpcConnectOptions::default()
+            .with_x_token("secret")
+            .with_basic_auth("user", "pass")
+            .with_keepalive(Duration::from_secs(10), Duration::from_secs(5));
+        let request = options.interceptor().unwrap().call(Request::new(())).unwrap();
+        assert_eq!(request.metadata().get("x-token").unwrap(), "secret");
+        assert_eq!(request.metadata().get("authorization").unwrap(), "Basic dXNlcjpwYXNz");
+
+        assert!(options.endpoint("https://geyser.example.com").is_ok());
+        assert!(options.yellowstone("https://geyser.example.com", Default::default()).is_err());
+        let shredstream = options.shredstream("https://shreds.example.com", Default::default());
+        assert!(futures::executor::block_on(shredstream).is_err());
+        assert!(GrpcConnectOptions::default()
+            .with_domain_name("geyser.example.com")
+            .endpoint("http://127.0.0.1:10000")
+            .is_err());
+        assert_eq!(whole_seconds(Duration::from_millis(1500)), 2);
+        assert!(GrpcConnectOptions::default()
+            .with_header("bad header", "x")
+            .interceptor()
+            .is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! Dump positions when the creator sells
+//!
+//! A creator selling is usually the start of a rug. [`DevSellGuard`] links each held token to
+//! its creator and, when the stream shows the creator selling at least `min_sol_amount`,
+//! sells the whole position through the fastest configured swqos provider with an aggressive
+//! tip. The sell params are built from the creator's trade event, so the dump needs no RPC
+//! round trip besides the blockhash.
+//!
+//! ```ignore
+//! let guard = Arc::new(DevSellGuard::new(client.clone(), DevSellGuardConfig::default()));
+//! guard.guard_by_rpc(mint).await?;
+//! let handle = guard.clone().start(&grpc).await?;
+//! ```
+//!
+//! A guard that should share a stream with a strategy is fed from `Strategy::on_event`
+//! through `on_event` instead of `start`.
+
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::common::AnyResult;
+use crate::notify::TradeNotification;
+use crate::solana_streamer_sdk::streaming::YellowstoneGrpc;
+use crate::strategy::held_tokens;
+use crate::streaming::{subscribe_tokens, TokenEvent};
+use crate::trading::{
+    core::{
+        params::{PumpFunParams, PumpSwapParams},
+        traits::ProtocolParams,
+    },
+    factory::DexType,
+};
+use crate::SolanaTrade;
+
+/// Settings of a `DevSellGuard`
+#[derive(Debug, Clone)]
+pub struct DevSellGuardConfig {
+    /// Creator sells of at least this many lamports trigger the dump, 0 triggers on any sell
+    pub min_sol_amount: u64,
+    /// Tip of the dump in SOL, replaces `PriorityFee::sell_tip_fee`
+    pub tip_sol: f64,
+    /// Compute unit price of the dump in micro-lamports, the configured one when `None`
+    pub unit_price: Option<u64>,
+    /// Slippage of the dump, the SDK default when `None`
+    pub slippage_basis_points: Option<u64>,
+}
+
+impl Default for DevSellGuardConfig {
+    fn default() -> Self {
+        Self {
+            min_sol_amount: 0,
+            tip_sol: 0.01,
+            unit_price: None,
+            // The price is falling, a tight limit would leave the position behind
+            slippage_basis_points: Some(5_000),
+        }
+    }
+}
+
+/// Sells held tokens as soon as their creator sells
+pub struct DevSellGuard {
+    client: Arc<SolanaTrade>,
+    config: DevSellGuardConfig,
+    /// Creator of every guarded mint
+    creators: Mutex<HashMap<Pubkey, Pubkey>>,
+}
+
+/// Sell of a trade event
+struct Sell {
+    seller: Pubkey,
+    token_amount: u64,
+    sol_amount: u64,
+}
+
+impl Sell {
+    fn from_event(event: &TokenEvent) -> Option<Self> {
+        match event {
+            TokenEvent::PumpFunTrade(e) if !e.is_buy => Some(Self {
+                seller: e.user,
+                token_amount: e.token_amount,
+                sol_amount: e.sol_amount,
+            }),
+            TokenEvent::PumpSwapSell(e) => Some(Self {
+                seller: e.user,
+                token_amount: e.base_amount_in,
+                sol_amount: e.quote_amount_out,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Params to sell into the venue of the sell `event`, with its reserves after the sell
+    fn params(event: &TokenEvent) -> Option<(DexType, Box<dyn ProtocolParams>)> {
+        match event {
+            TokenEvent::PumpFunTrade(e) => {
+                Some((DexType::PumpFun, Box::new(PumpFunParams::from_trade(e, None))))
+            }
+            TokenEvent::PumpSwapSell(e) => {
+                Some((DexType::PumpSwap, Box::new(PumpSwapParams::from_sell_trade(e))))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl DevSellGuard {
+    pub fn new(client: Arc<SolanaTrade>, config: DevSellGuardConfig) -> Self {
+        Self { client, config, creators: Mutex::new(HashMap::new()) }
+    }
+
+    /// Guard the position in `mint` against sells by `creator`
+    pub fn guard(&self, mint: Pubkey, creator: Pubkey) {
+        self.creators.lock().unwrap().insert(mint, creator);
+    }
+
+    /// Guard the position in `mint`, reading the creator from its bonding curve
+    pub async fn guard_by_rpc(&self, mint: Pubkey) -> AnyResult<Pubkey> {
+        let creator = self.client.get_pumpfun_token_creator(&mint).await?;
+        self.guard(mint, creator);
+        Ok(creator)
+    }
+
+    /// Stop guarding `mint`, returns whether it was guarded
+    pub fn unguard(&self, mint: &Pubkey) -> bool {
+        self.creators.lock().unwrap().remove(mint).is_some()
+    }
+
+    /// Guarded mints with their creators
+    pub fn guarded(&self) -> Vec<(Pubkey, Pubkey)> {
+        self.creators.lock().unwrap().iter().map(|(mint, creator)| (*mint, *creator)).collect()
+    }
+
+    /// Check an event of `mint` and start the dump when it is a triggering creator sell
+    ///
+    /// The mint stops being guarded while the dump runs and is guarded again when it fails.
+    /// Returns whether the dump was started; must be called inside a tokio runtime.
+    pub fn on_event(self: &Arc<Self>, mint: &Pubkey, event: &TokenEvent) -> bool {
+        let Some(sell) = Sell::from_event(event) else {
+            return false;
+        };
+        if sell.sol_amount < self.config.min_sol_amount {
+            return false;
+        }
+        let creator = {
+            let mut creators = self.creators.lock().unwrap();
+            match creators.get(mint) {
+                Some(creator) if *creator == sell.seller => creators.remove(mint),
+                _ => None,
+            }
+        };
+        let (Some(creator), Some((dex_type, params))) = (creator, Sell::params(event)) else {
+            return false;
+        };
+        tracing::info!(%mint, %creator, sol_amount = sell.sol_amount, "creator sold, dumping");
+        if let Some(notifications) = &self.client.notifications {
+            notifications.notify(TradeNotification::DevSold {
+                mint: *mint,
+                dev: creator,
+                token_amount: sell.token_amount,
+                sol_amount: sell.sol_amount,
+            });
+        }
+        let guard = self.clone();
+        let mint = *mint;
+        tokio::spawn(async move {
+            if let Err(e) = guard.dump(mint, dex_type, params).await {
+                tracing::warn!(%mint, error = %e, "dev sell dump failed");
+                guard.creators.lock().unwrap().entry(mint).or_insert(creator);
+            }
+        });
+        true
+    }
+
+    /// Subscribe to the guarded mints and guard them until the subscription ends
+    ///
+    /// Mints guarded afterwards need a new subscription. The subscription replaces any other
+    /// one on `grpc`; call `grpc.stop()` to end it.
+    pub async fn start(self: Arc<Self>, grpc: &YellowstoneGrpc) -> AnyResult<JoinHandle<()>> {
+        let mints = self.guarded().into_iter().map(|(mint, _)| mint).collect();
+        let (event_tx, mut events) = mpsc::unbounded_channel();
+        subscribe_tokens(grpc, mints, move |mint, event| {
+            let _ = event_tx.send((mint, event));
+        })
+        .await?;
+        Ok(tokio::spawn(async move {
+            while let Some((mint, event)) = events.recv().await {
+                self.on_event(&mint, &event);
+            }
+        }))
+    }
+
+    /// Sell the whole position through the fastest provider with the aggressive fees
+    async fn dump(
+        &self,
+        mint: Pubkey,
+        dex_type: DexType,
+        params: Box<dyn ProtocolParams>,
+    ) -> AnyResult<()> {
+        let token_amount = held_tokens(&self.client, &mint).await;
+        if token_amount == 0 {
+            return Ok(());
+        }
+        let mut client = SolanaTrade::clone(&self.client);
+        client.swqos_clients = self.client.fastest_swqos_client().into_iter().collect();
+        let priority_fee = &mut client.trade_config.priority_fee;
+        priority_fee.sell_tip_fee = self.config.tip_sol;
+        if let Some(unit_price) = self.config.unit_price {
+            priority_fee.tip_unit_price = unit_price;
+            priority_fee.rpc_unit_price = unit_price;
+        }
+        let recent_blockhash = client.rpc.get_latest_blockhash().await?;
+        client
+            .sell(
+                dex_type,
+                mint,
+                token_amount,
+                self.config.slippage_basis_points,
+                recent_blockhash,
+                None,
+                true,
+                params,
+                None,
+                true,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunTradeEvent;
+
+    #[test]
+    fn test_sell_from_event() {
+        let creator = Pubkey::new_unique();
+        let trade = PumpFunTradeEvent {
+            user: creator,
+            is_buy: true,
+            sol_amount: 1_000_000_000,
+            ..Default::default()
+        };
+        assert!(Sell::from_event(&TokenEvent::PumpFunTrade(trade.clone())).is_none());
+
+        let sell = PumpFunTradeEvent { is_buy: false, ..trade };
+        let sell = Sell::from_event(&TokenEvent::PumpFunTrade(sell)).unwrap();
+        assert_eq!((sell.seller, sell.sol_amount), (creator, 1_000_000_000));
+    }
+}
@@ -0,0 +1,20 @@
+#[cfg(feature = "raydium")]
+pub mod arbitrage;
+pub mod connection;
+pub mod dev_sell_guard;
+pub mod stream;
+pub mod token_stats;
+pub mod token_subscription;
+pub mod whale_filter;
+
+#[cfg(feature = "raydium")]
+pub use arbitrage::watch_arbitrage;
+pub use connection::{GrpcConnectOptions, HeaderInterceptor};
+pub use dev_sell_guard::{DevSellGuard, DevSellGuardConfig};
+pub use stream::{
+    subscribe_events_stream, subscribe_token_stats_stream, subscribe_token_stream,
+    subscribe_tokens_stream,
+};
+pub use token_stats::{subscribe_token_stats, TokenStats, TokenStatsAggregator, WindowStats};
+pub use token_subscription::{subscribe_token, subscribe_tokens, TokenEvent};
+pub use whale_filter::{SolTrade, WhaleFilter};
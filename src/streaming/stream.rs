@@ -0,0 +1,98 @@
+//! Subscriptions as `futures::Stream`
+//!
+//! Each function starts the same subscription as its callback counterpart and returns the
+//! events as a stream, so they can be consumed with `select!`, buffering and the stream
+//! combinators of `futures` / `tokio-stream`:
+//!
+//! ```ignore
+//! let mut events = subscribe_token_stream(&grpc, mint).await?;
+//! loop {
+//!     tokio::select! {
+//!         Some(event) = events.next() => handle(event),
+//!         _ = shutdown.recv() => break,
+//!     }
+//! }
+//! grpc.stop().await;
+//! ```
+//!
+//! The streams are unbounded: events are queued until polled, so keep up with the feed.
+//! Dropping a stream discards further events but does not end the subscription, call
+//! `grpc.stop()` for that. The stream ends when the subscription is stopped.
+
+use futures::{channel::mpsc, Stream};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::{bot_wallets::BotWallets, AnyResult};
+use crate::solana_streamer_sdk::streaming::{
+    event_parser::{common::filter::EventTypeFilter, Protocol, UnifiedEvent},
+    yellowstone_grpc::{AccountFilter, TransactionFilter},
+    YellowstoneGrpc,
+};
+use crate::streaming::{
+    subscribe_token, subscribe_token_stats, subscribe_tokens, TokenEvent, TokenStats,
+};
+
+/// Parsed events of `protocols` matching the filters
+///
+/// Trade events are classified against `BotWallets` before they are queued.
+pub async fn subscribe_events_stream(
+    grpc: &YellowstoneGrpc,
+    protocols: Vec<Protocol>,
+    transaction_filter: TransactionFilter,
+    account_filter: AccountFilter,
+    event_type_filter: Option<EventTypeFilter>,
+) -> AnyResult<impl Stream<Item = Box<dyn UnifiedEvent>> + Send + Unpin + 'static> {
+    let (tx, rx) = mpsc::unbounded();
+    grpc.subscribe_events_immediate(
+        protocols,
+        None,
+        transaction_filter,
+        account_filter,
+        event_type_filter,
+        None,
+        BotWallets::classify_callback(move |event| {
+            let _ = tx.unbounded_send(event);
+        }),
+    )
+    .await?;
+    Ok(rx)
+}
+
+/// `subscribe_token` as a stream
+pub async fn subscribe_token_stream(
+    grpc: &YellowstoneGrpc,
+    mint: Pubkey,
+) -> AnyResult<impl Stream<Item = TokenEvent> + Send + Unpin + 'static> {
+    let (tx, rx) = mpsc::unbounded();
+    subscribe_token(grpc, mint, move |event| {
+        let _ = tx.unbounded_send(event);
+    })
+    .await?;
+    Ok(rx)
+}
+
+/// `subscribe_tokens` as a stream of `(mint, event)`
+pub async fn subscribe_tokens_stream(
+    grpc: &YellowstoneGrpc,
+    mints: Vec<Pubkey>,
+) -> AnyResult<impl Stream<Item = (Pubkey, TokenEvent)> + Send + Unpin + 'static> {
+    let (tx, rx) = mpsc::unbounded();
+    subscribe_tokens(grpc, mints, move |mint, event| {
+        let _ = tx.unbounded_send((mint, event));
+    })
+    .await?;
+    Ok(rx)
+}
+
+/// `subscribe_token_stats` as a stream
+pub async fn subscribe_token_stats_stream(
+    grpc: &YellowstoneGrpc,
+    mints: Vec<Pubkey>,
+) -> AnyResult<impl Stream<Item = TokenStats> + Send + Unpin + 'static> {
+    let (tx, rx) = mpsc::unbounded();
+    subscribe_token_stats(grpc, mints, move |stats| {
+        let _ = tx.unbounded_send(stats);
+    })
+    .await?;
+    Ok(rx)
+}
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::common::AnyResult;
+use crate::solana_streamer_sdk::streaming::YellowstoneGrpc;
+use crate::streaming::{subscribe_tokens, TokenEvent};
+
+/// Windows reported in every `TokenStats`, shortest first
+pub const STATS_WINDOWS: [Duration; 3] =
+    [Duration::from_secs(1), Duration::from_secs(10), Duration::from_secs(60)];
+
+/// A single trade of a mint, as fed to `TokenStatsAggregator`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TradeSample {
+    pub mint: Pubkey,
+    pub trader: Pubkey,
+    pub is_buy: bool,
+    /// SOL side of the trade in lamports
+    pub sol_amount: u64,
+    /// Token side of the trade in base units
+    pub token_amount: u64,
+    /// When the trade was received
+    #[serde(skip, default = "Instant::now")]
+    pub at: Instant,
+}
+
+impl TradeSample {
+    /// The trade carried by a `TokenEvent` for `mint`, received now
+    ///
+    /// PumpSwap trades are read from the side of the pool `mint` is on, so buying the
+    /// base of a pool quoted in `mint` counts as a sell of `mint`. Non-trade events
+    /// return `None`.
+    pub fn from_token_event(mint: Pubkey, event: &TokenEvent) -> Option<Self> {
+        let (trader, is_buy, sol_amount, token_amount) = match event {
+            TokenEvent::PumpFunTrade(e) => (e.user, e.is_buy, e.sol_amount, e.token_amount),
+            TokenEvent::PumpSwapBuy(e) if e.base_mint == mint => {
+                (e.user, true, e.quote_amount_in, e.base_amount_out)
+            }
+            TokenEvent::PumpSwapBuy(e) => (e.user, false, e.base_amount_out, e.quote_amount_in),
+            TokenEvent::PumpSwapSell(e) if e.base_mint == mint => {
+                (e.user, false, e.quote_amount_out, e.base_amount_in)
+            }
+            TokenEvent::PumpSwapSell(e) => (e.user, true, e.base_amount_in, e.quote_amount_out),
+            _ => return None,
+        };
+        Some(Self { mint, trader, is_buy, sol_amount, token_amount, at: Instant::now() })
+    }
+
+    /// Execution price in lamports per token base unit, `None` for empty trades
+    pub fn price(&self) -> Option<f64> {
+        (self.token_amount > 0).then(|| self.sol_amount as f64 / self.token_amount as f64)
+    }
+}
+
+/// Activity of a mint over one window
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowStats {
+    pub window: Duration,
+    pub buys: usize,
+    pub sells: usize,
+    /// Lamports spent on buys
+    pub buy_volume: u64,
+    /// Lamports received from sells
+    pub sell_volume: u64,
+    /// Distinct wallets that bought
+    pub unique_buyers: usize,
+    /// Relative change from the first to the last trade price, e.g. `0.05` for +5%
+    pub price_change: f64,
+}
+
+impl WindowStats {
+    /// Total lamports traded
+    pub fn volume(&self) -> u64 {
+        self.buy_volume.saturating_add(self.sell_volume)
+    }
+
+    /// Buy volume over sell volume; infinite with buys and no sells, 0 without trades
+    pub fn buy_sell_ratio(&self) -> f64 {
+        match (self.buy_volume, self.sell_volume) {
+            (0, _) => 0.0,
+            (_, 0) => f64::INFINITY,
+            (buy, sell) => buy as f64 / sell as f64,
+        }
+    }
+}
+
+/// Rolling statistics of a mint, emitted after each of its trades
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TokenStats {
+    pub mint: Pubkey,
+    /// Price of the latest trade in lamports per token base unit
+    pub price: f64,
+    pub one_second: WindowStats,
+    pub ten_seconds: WindowStats,
+    pub sixty_seconds: WindowStats,
+}
+
+/// Per-mint rolling windows over trades
+///
+/// Keeps the trades of the longest window per mint; a mint's history is dropped once
+/// all of its trades have aged out.
+#[derive(Debug, Default)]
+pub struct TokenStatsAggregator {
+    trades: HashMap<Pubkey, VecDeque<TradeSample>>,
+}
+
+impl TokenStatsAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a trade and return the mint's updated statistics
+    pub fn record(&mut self, sample: TradeSample) -> TokenStats {
+        self.trades.entry(sample.mint).or_default().push_back(sample);
+        self.prune(sample.at);
+        self.compute(&sample.mint, sample.at).expect("mint has the trade just recorded")
+    }
+
+    /// Current statistics of `mint`, `None` if it has no trade in the longest window
+    pub fn stats(&self, mint: &Pubkey, now: Instant) -> Option<TokenStats> {
+        self.compute(mint, now)
+    }
+
+    fn prune(&mut self, now: Instant) {
+        let longest = STATS_WINDOWS[STATS_WINDOWS.len() - 1];
+        self.trades.retain(|_, trades| {
+            while trades.front().is_some_and(|t| now.saturating_duration_since(t.at) > longest) {
+                trades.pop_front();
+            }
+            !trades.is_empty()
+        });
+    }
+
+    fn compute(&self, mint: &Pubkey, now: Instant) -> Option<TokenStats> {
+        let trades = self.trades.get(mint)?;
+        let [one_second, ten_seconds, sixty_seconds] =
+            STATS_WINDOWS.map(|window| Self::window(trades, window, now));
+        if sixty_seconds.buys + sixty_seconds.sells == 0 {
+            return None;
+        }
+        let price = trades.iter().rev().find_map(TradeSample::price).unwrap_or(0.0);
+        Some(TokenStats { mint: *mint, price, one_second, ten_seconds, sixty_seconds })
+    }
+
+    fn window(trades: &VecDeque<TradeSample>, window: Duration, now: Instant) -> WindowStats {
+        let mut stats = WindowStats {
+            window,
+            buys: 0,
+            sells: 0,
+            buy_volume: 0,
+            sell_volume: 0,
+            unique_buyers: 0,
+            price_change: 0.0,
+        };
+        let mut buyers = HashSet::new();
+        let mut first_price = None;
+        let mut last_price = None;
+        for trade in trades.iter().filter(|t| now.saturating_duration_since(t.at) <= window) {
+            if trade.is_buy {
+                stats.buys += 1;
+                stats.buy_volume = stats.buy_volume.saturating_add(trade.sol_amount);
+                buyers.insert(trade.trader);
+            } else {
+                stats.sells += 1;
+                stats.sell_volume = stats.sell_volume.saturating_add(trade.sol_amount);
+            }
+            if let Some(price) = trade.price() {
+                first_price.get_or_insert(price);
+                last_price = Some(price);
+            }
+        }
+        stats.unique_buyers = buyers.len();
+        if let (Some(first), Some(last)) = (first_price, last_price) {
+            stats.price_change = last / first - 1.0;
+        }
+        stats
+    }
+}
+
+/// Rolling statistics for `mints`, built on `subscribe_tokens`
+///
+/// `callback` receives the mint's updated `TokenStats` after each PumpFun or PumpSwap
+/// trade, so strategies can filter on momentum without windowing the trades themselves.
+pub async fn subscribe_token_stats<F>(
+    grpc: &YellowstoneGrpc,
+    mints: Vec<Pubkey>,
+    callback: F,
+) -> AnyResult<()>
+where
+    F: Fn(TokenStats) + Send + Sync + 'static,
+{
+    let aggregator = Arc::new(Mutex::new(TokenStatsAggregator::new()));
+    subscribe_tokens(grpc, mints, move |mint, event| {
+        if let Some(sample) = TradeSample::from_token_event(mint, &event) {
+            let stats = aggregator.lock().unwrap().record(sample);
+            callback(stats);
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_windows() {
+        let mint = Pubkey::new_unique();
+        let buyer = Pubkey::new_unique();
+        let start = Instant::now();
+        let trade = |secs: u64, is_buy: bool, trader: Pubkey, sol: u64, tokens: u64| TradeSample {
+            mint,
+            trader,
+            is_buy,
+            sol_amount: sol,
+            token_amount: tokens,
+            at: start + Duration::from_secs(secs),
+        };
+        let mut aggregator = TokenStatsAggregator::new();
+
+        aggregator.record(trade(0, true, buyer, 100, 1_000));
+        aggregator.record(trade(30, false, Pubkey::new_unique(), 50, 400));
+        aggregator.record(trade(55, true, buyer, 300, 2_000));
+        let stats = aggregator.record(trade(61, true, Pubkey::new_unique(), 200, 1_000));
+
+        assert_eq!(stats.price, 0.2);
+        assert_eq!((stats.one_second.buys, stats.one_second.sells), (1, 0));
+        assert_eq!(stats.one_second.buy_sell_ratio(), f64::INFINITY);
+        assert_eq!(stats.ten_seconds.unique_buyers, 2);
+        assert_eq!(stats.ten_seconds.volume(), 500);
+        assert!((stats.ten_seconds.price_change - (0.2 / 0.15 - 1.0)).abs() < 1e-12);
+        // The trade at 0s has aged out of the 60s window
+        assert_eq!((stats.sixty_seconds.buys, stats.sixty_seconds.sells), (2, 1));
+        assert_eq!(stats.sixty_seconds.buy_sell_ratio(), 10.0);
+        assert_eq!(stats.sixty_seconds.price_change, 0.2 / 0.125 - 1.0);
+
+        assert!(aggregator.stats(&mint, start + Duration::from_secs(200)).is_none());
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::common::{bonding_curve_cache::BondingCurveCache, bot_wallets::BotWallets, AnyResult};
+use crate::solana_streamer_sdk::{
+    match_event,
+    streaming::{
+        event_parser::{
+            common::{filter::EventTypeFilter, EventType},
+            protocols::{
+                pumpfun::{
+                    parser::PUMPFUN_PROGRAM_ID, types::BondingCurve,
+                    PumpFunBondingCurveAccountEvent, PumpFunMigrateEvent, PumpFunTradeEvent,
+                },
+                pumpswap::{
+                    parser::PUMPSWAP_PROGRAM_ID, PumpSwapBuyEvent, PumpSwapCreatePoolEvent,
+                    PumpSwapSellEvent,
+                },
+            },
+            Protocol, UnifiedEvent,
+        },
+        yellowstone_grpc::{AccountFilter, TransactionFilter},
+        YellowstoneGrpc,
+    },
+};
+use crate::trading::pumpfun::common::get_bonding_curve_pda;
+use crate::trading::pumpswap::{pool_cache::PoolCache, reserves_cache::PoolReservesCache};
+
+/// Event types `subscribe_tokens` consumes
+///
+/// Passed to the parser so creates, deposits, withdrawals and unrelated accounts in the
+/// matched transactions are skipped instead of being decoded into events that are dropped.
+const TOKEN_EVENT_TYPES: [EventType; 7] = [
+    EventType::PumpFunBuy,
+    EventType::PumpFunSell,
+    EventType::PumpFunMigrate,
+    EventType::PumpSwapBuy,
+    EventType::PumpSwapSell,
+    EventType::PumpSwapCreatePool,
+    EventType::AccountPumpFunBondingCurve,
+];
+
+/// Events delivered by `subscribe_token`, all concerning a single mint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "event")]
+pub enum TokenEvent {
+    /// Trade on the PumpFun bonding curve
+    PumpFunTrade(PumpFunTradeEvent),
+    /// Bonding curve account update (reserves changed)
+    BondingCurveUpdate(BondingCurve),
+    /// The bonding curve completed and liquidity migrated to PumpSwap
+    Graduated(PumpFunMigrateEvent),
+    /// Buy on the PumpSwap pool
+    PumpSwapBuy(PumpSwapBuyEvent),
+    /// Sell on the PumpSwap pool
+    PumpSwapSell(PumpSwapSellEvent),
+}
+
+/// Subscribe to PumpFun/PumpSwap activity for a single mint
+///
+/// Transactions are filtered server-side by requiring the mint account, and the bonding
+/// curve account is the only account subscribed, so the stream carries just this token.
+/// Events are additionally checked client-side before reaching `callback`. The shared
+/// `BondingCurveCache` is refreshed from trades and invalidated on graduation,
+/// `PoolCache` learns the PumpSwap pool on graduation or pool creation, and
+/// `PoolReservesCache` follows the PumpSwap pool reserves after migration. Trade events
+/// have `is_bot` set against the wallets in `BotWallets`.
+///
+/// # Arguments
+/// * `grpc` - Yellowstone gRPC client
+/// * `mint` - Token mint to follow
+/// * `callback` - Called for every event concerning `mint`
+pub async fn subscribe_token<F>(grpc: &YellowstoneGrpc, mint: Pubkey, callback: F) -> AnyResult<()>
+where
+    F: Fn(TokenEvent) + Send + Sync + 'static,
+{
+    subscribe_tokens(grpc, vec![mint], move |_, event| callback(event)).await
+}
+
+/// Like `subscribe_token`, for several mints on one subscription
+///
+/// With more than one mint the server-side filter matches transactions touching any of
+/// them. `callback` receives the mint each event concerns.
+pub async fn subscribe_tokens<F>(
+    grpc: &YellowstoneGrpc,
+    mints: Vec<Pubkey>,
+    callback: F,
+) -> AnyResult<()>
+where
+    F: Fn(Pubkey, TokenEvent) + Send + Sync + 'static,
+{
+    let bonding_curves: HashMap<String, Pubkey> = mints
+        .iter()
+        .map(|mint| (get_bonding_curve_pda(mint).unwrap_or_default().to_string(), *mint))
+        .collect();
+    let mint_set: HashSet<Pubkey> = mints.iter().copied().collect();
+
+    let transaction_filter = match mints.as_slice() {
+        [mint] => TransactionFilter {
+            account_include: vec![PUMPFUN_PROGRAM_ID.to_string(), PUMPSWAP_PROGRAM_ID.to_string()],
+            account_exclude: vec![],
+            account_required: vec![mint.to_string()],
+        },
+        _ => TransactionFilter {
+            account_include: mints.iter().map(Pubkey::to_string).collect(),
+            account_exclude: vec![],
+            account_required: vec![],
+        },
+    };
+    let account_filter =
+        AccountFilter { account: bonding_curves.keys().cloned().collect(), owner: vec![] };
+
+    let callback = Arc::new(callback);
+    let event_callback = move |mut event: Box<dyn UnifiedEvent>| {
+        let callback = callback.clone();
+        crate::metrics::record_parsed_event("token_subscription");
+        BotWallets::get_instance().classify(event.as_mut());
+        match_event!(event, {
+            PumpFunTradeEvent => |e: PumpFunTradeEvent| {
+                if mint_set.contains(&e.mint) {
+                    BondingCurveCache::get_instance().update_from_trade(&e);
+                    callback(e.mint, TokenEvent::PumpFunTrade(e));
+                }
+            },
+            PumpFunBondingCurveAccountEvent => |e: PumpFunBondingCurveAccountEvent| {
+                if let Some(mint) = bonding_curves.get(&e.pubkey) {
+                    callback(*mint, TokenEvent::BondingCurveUpdate(e.bonding_curve));
+                }
+            },
+            PumpFunMigrateEvent => |e: PumpFunMigrateEvent| {
+                if mint_set.contains(&e.mint) {
+                    BondingCurveCache::get_instance().update_from_migrate(&e);
+                    PoolCache::get_instance().insert(e.mint, e.pool);
+                    callback(e.mint, TokenEvent::Graduated(e));
+                }
+            },
+            PumpSwapCreatePoolEvent => |e: PumpSwapCreatePoolEvent| {
+                if mint_set.contains(&e.base_mint) || mint_set.contains(&e.quote_mint) {
+                    PoolCache::get_instance().update_from_create_pool(&e);
+                }
+            },
+            PumpSwapBuyEvent => |e: PumpSwapBuyEvent| {
+                let mint = [e.base_mint, e.quote_mint].into_iter().find(|m| mint_set.contains(m));
+                if let Some(mint) = mint {
+                    PoolReservesCache::get_instance().update_from_buy(&e);
+                    callback(mint, TokenEvent::PumpSwapBuy(e));
+                }
+            },
+            PumpSwapSellEvent => |e: PumpSwapSellEvent| {
+                let mint = [e.base_mint, e.quote_mint].into_iter().find(|m| mint_set.contains(m));
+                if let Some(mint) = mint {
+                    PoolReservesCache::get_instance().update_from_sell(&e);
+                    callback(mint, TokenEvent::PumpSwapSell(e));
+                }
+            },
+        });
+    };
+
+    grpc.subscribe_events_immediate(
+        vec![Protocol::PumpFun, Protocol::PumpSwap],
+        None,
+        transaction_filter,
+        account_filter,
+        Some(EventTypeFilter { include: TOKEN_EVENT_TYPES.to_vec() }),
+        None,
+        event_callback,
+    )
+    .await
+}
@@ -0,0 +1,189 @@
+use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+use crate::solana_streamer_sdk::streaming::event_parser::{
+    protocols::{
+        bonk::{BonkTradeEvent, TradeDirection},
+        pumpfun::PumpFunTradeEvent,
+        pumpswap::{PumpSwapBuyEvent, PumpSwapSellEvent},
+        raydium_cpmm::RaydiumCpmmSwapEvent,
+    },
+    UnifiedEvent,
+};
+use serde::{Deserialize, Serialize};
+
+/// SOL side of a trade event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolTrade {
+    /// Lamports paid or received by the trader
+    pub sol_amount: u64,
+    /// SOL reserves of the pool or curve before the trade, when the event carries them
+    pub sol_reserves: Option<u64>,
+}
+
+impl SolTrade {
+    /// The SOL side of `event`
+    ///
+    /// Covers PumpFun, PumpSwap and Bonk trades and Raydium CPMM swaps against (W)SOL.
+    /// Trades in pools without a SOL side, other protocols' swaps and non-trade events
+    /// return `None`.
+    pub fn from_event(event: &dyn UnifiedEvent) -> Option<Self> {
+        let any = event.as_any();
+        if let Some(e) = any.downcast_ref::<PumpFunTradeEvent>() {
+            let reserves = if e.is_buy {
+                e.virtual_sol_reserves.checked_sub(e.sol_amount)
+            } else {
+                e.virtual_sol_reserves.checked_add(e.sol_amount)
+            };
+            return Some(Self { sol_amount: e.sol_amount, sol_reserves: reserves });
+        }
+        if let Some(e) = any.downcast_ref::<PumpSwapBuyEvent>() {
+            return if e.quote_mint == WSOL_TOKEN_ACCOUNT {
+                Some(Self::new(e.quote_amount_in, e.pool_quote_token_reserves))
+            } else if e.base_mint == WSOL_TOKEN_ACCOUNT {
+                Some(Self::new(e.base_amount_out, e.pool_base_token_reserves))
+            } else {
+                None
+            };
+        }
+        if let Some(e) = any.downcast_ref::<PumpSwapSellEvent>() {
+            return if e.quote_mint == WSOL_TOKEN_ACCOUNT {
+                Some(Self::new(e.quote_amount_out, e.pool_quote_token_reserves))
+            } else if e.base_mint == WSOL_TOKEN_ACCOUNT {
+                Some(Self::new(e.base_amount_in, e.pool_base_token_reserves))
+            } else {
+                None
+            };
+        }
+        if let Some(e) = any.downcast_ref::<BonkTradeEvent>() {
+            if e.quote_token_mint != WSOL_TOKEN_ACCOUNT {
+                return None;
+            }
+            let sol_amount = match e.trade_direction {
+                TradeDirection::Buy => e.amount_in,
+                TradeDirection::Sell => e.amount_out,
+            };
+            return Some(Self::new(
+                sol_amount,
+                e.virtual_quote.saturating_add(e.real_quote_before),
+            ));
+        }
+        if let Some(e) = any.downcast_ref::<RaydiumCpmmSwapEvent>() {
+            let sol_amount = if e.input_token_mint == WSOL_TOKEN_ACCOUNT {
+                e.amount_in
+            } else if e.output_token_mint == WSOL_TOKEN_ACCOUNT {
+                e.amount_out
+            } else {
+                return None;
+            };
+            return Some(Self { sol_amount, sol_reserves: None });
+        }
+        None
+    }
+
+    fn new(sol_amount: u64, sol_reserves: u64) -> Self {
+        Self { sol_amount, sol_reserves: Some(sol_reserves) }
+    }
+
+    /// Trade size in basis points of the SOL reserves, `None` without reserves
+    pub fn reserve_share_bps(&self) -> Option<u64> {
+        let reserves = self.sol_reserves.filter(|r| *r > 0)?;
+        Some((self.sol_amount as u128 * 10_000 / reserves as u128).min(u64::MAX as u128) as u64)
+    }
+}
+
+/// Passes on only large trades
+///
+/// A trade passes when it reaches any of the configured thresholds; with none configured
+/// every trade with a SOL side passes. Non-trade events are always dropped.
+///
+/// ```ignore
+/// let filter =
+///     WhaleFilter::new().with_min_sol(10 * LAMPORTS_PER_SOL).with_min_reserve_share_bps(200);
+/// grpc.subscribe_events_immediate(
+///     protocols, None, tx_filter, account_filter, None, None, filter.wrap(callback),
+/// )
+/// .await?;
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WhaleFilter {
+    min_sol: Option<u64>,
+    min_reserve_share_bps: Option<u64>,
+}
+
+impl WhaleFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pass trades of at least `lamports`
+    pub fn with_min_sol(mut self, lamports: u64) -> Self {
+        self.min_sol = Some(lamports);
+        self
+    }
+
+    /// Pass trades of at least `bps` basis points of the pool's SOL reserves
+    pub fn with_min_reserve_share_bps(mut self, bps: u64) -> Self {
+        self.min_reserve_share_bps = Some(bps);
+        self
+    }
+
+    /// Whether `event` is a trade passing the filter
+    pub fn matches(&self, event: &dyn UnifiedEvent) -> bool {
+        let Some(trade) = SolTrade::from_event(event) else {
+            return false;
+        };
+        if self.min_sol.is_none() && self.min_reserve_share_bps.is_none() {
+            return true;
+        }
+        let by_size = self.min_sol.is_some_and(|min| trade.sol_amount >= min);
+        let by_share = self
+            .min_reserve_share_bps
+            .is_some_and(|min| trade.reserve_share_bps().is_some_and(|bps| bps >= min));
+        by_size || by_share
+    }
+
+    /// Wrap a subscription callback so it only receives the trades passing the filter
+    pub fn wrap<F>(self, callback: F) -> impl Fn(Box<dyn UnifiedEvent>) + Send + Sync
+    where
+        F: Fn(Box<dyn UnifiedEvent>) + Send + Sync + 'static,
+    {
+        move |event: Box<dyn UnifiedEvent>| {
+            if self.matches(event.as_ref()) {
+                callback(event)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunCreateTokenEvent;
+
+    #[test]
+    fn test_whale_filter() {
+        // 5 SOL buy into a curve that held 95 SOL before it: 526 bps
+        let buy = PumpFunTradeEvent {
+            is_buy: true,
+            sol_amount: 5_000_000_000,
+            virtual_sol_reserves: 100_000_000_000,
+            ..Default::default()
+        };
+        let swap = RaydiumCpmmSwapEvent {
+            input_token_mint: WSOL_TOKEN_ACCOUNT,
+            amount_in: 20_000_000_000,
+            ..Default::default()
+        };
+        assert_eq!(SolTrade::from_event(&buy).unwrap().reserve_share_bps(), Some(526));
+
+        let by_size = WhaleFilter::new().with_min_sol(10_000_000_000);
+        assert!(!by_size.matches(&buy));
+        assert!(by_size.matches(&swap));
+
+        let by_share = WhaleFilter::new().with_min_reserve_share_bps(500);
+        assert!(by_share.matches(&buy));
+        assert!(!by_share.matches(&swap));
+        assert!(by_size.with_min_reserve_share_bps(500).matches(&buy));
+
+        assert!(!WhaleFilter::new().matches(&PumpFunCreateTokenEvent::default()));
+    }
+}
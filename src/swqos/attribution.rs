@@ -0,0 +1,127 @@
+//! Landing attribution
+//!
+//! When several providers race the same trade, each is sent a transaction paying its own
+//! tip account. Inspecting the transaction that landed tells which provider's tip was paid,
+//! i.e. which provider actually landed the trade.
+
+use anyhow::{anyhow, Result};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::common::SolanaRpcClient;
+use crate::constants::swqos::{
+    BLOCKRAZOR_TIP_ACCOUNTS, BLOX_TIP_ACCOUNTS, FLASHBLOCK_TIP_ACCOUNTS, JITO_TIP_ACCOUNTS,
+    NEXTBLOCK_TIP_ACCOUNTS, NODE1_TIP_ACCOUNTS, NOZOMI_TIP_ACCOUNTS, ZEROSLOT_TIP_ACCOUNTS,
+};
+use crate::metrics;
+use crate::swqos::SwqosType;
+
+/// Provider owning the tip account `account`
+pub fn provider_for_tip_account(account: &Pubkey) -> Option<SwqosType> {
+    let providers = [
+        (SwqosType::Jito, JITO_TIP_ACCOUNTS),
+        (SwqosType::NextBlock, NEXTBLOCK_TIP_ACCOUNTS),
+        (SwqosType::ZeroSlot, ZEROSLOT_TIP_ACCOUNTS),
+        (SwqosType::Temporal, NOZOMI_TIP_ACCOUNTS),
+        (SwqosType::Bloxroute, BLOX_TIP_ACCOUNTS),
+        (SwqosType::Node1, NODE1_TIP_ACCOUNTS),
+        (SwqosType::FlashBlock, FLASHBLOCK_TIP_ACCOUNTS),
+        (SwqosType::BlockRazor, BLOCKRAZOR_TIP_ACCOUNTS),
+    ];
+    providers
+        .into_iter()
+        .find(|(_, accounts)| accounts.contains(account))
+        .map(|(provider, _)| provider)
+}
+
+/// Provider whose tip account `transaction` pays, `SwqosType::Default` without a tip
+///
+/// `None` when it pays the tips of several providers, as single signature racing does: the
+/// transaction alone doesn't tell which of them landed it. Tip accounts are referenced
+/// directly by the tip transfer, never through a lookup table, so the static account keys
+/// are enough.
+pub fn attribute_transaction(transaction: &VersionedTransaction) -> Option<SwqosType> {
+    let mut providers =
+        transaction.message.static_account_keys().iter().filter_map(provider_for_tip_account);
+    let provider = providers.next().unwrap_or(SwqosType::Default);
+    if providers.any(|other| other != provider) {
+        return None;
+    }
+    Some(provider)
+}
+
+/// Fetch the landed transaction `signature`, attribute it to a provider and record the
+/// landing in the `sol_trade_swqos_landings_total` metric
+///
+/// Fails without recording anything when the transaction pays several providers' tips.
+pub async fn attribute_landed(rpc: &SolanaRpcClient, signature: &Signature) -> Result<SwqosType> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let landed = rpc
+        .get_transaction_with_config(signature, config)
+        .await
+        .inspect_err(|_| metrics::record_rpc_error("get_transaction"))?;
+    let transaction = landed
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("Transaction {} could not be decoded", signature))?;
+    let provider = attribute_transaction(&transaction)
+        .ok_or_else(|| anyhow!("Transaction {} pays several providers' tips", signature))?;
+    metrics::record_landing(&format!("{:?}", provider));
+    tracing::info!(provider = ?provider, %signature, "landed transaction attributed");
+    Ok(provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        hash::Hash, message::Message, signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+    use solana_system_interface::instruction::transfer;
+
+    #[test]
+    fn test_attribute_by_tip_account() {
+        let payer = Keypair::new();
+        let tip = transfer(&payer.pubkey(), &NEXTBLOCK_TIP_ACCOUNTS[0], 1_000);
+        let message = Message::new(&[tip], Some(&payer.pubkey()));
+        let tipped = Transaction::new(&[&payer], message, Hash::default());
+        assert_eq!(attribute_transaction(&tipped.into()), Some(SwqosType::NextBlock));
+
+        let plain = transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000);
+        let message = Message::new(&[plain], Some(&payer.pubkey()));
+        let untipped = Transaction::new(&[&payer], message, Hash::default());
+        assert_eq!(attribute_transaction(&untipped.into()), Some(SwqosType::Default));
+    }
+
+    #[test]
+    fn test_merged_tips_are_not_attributed() {
+        // A single signature racing transaction pays every provider's tip
+        let payer = Keypair::new();
+        let tips = [
+            transfer(&payer.pubkey(), &JITO_TIP_ACCOUNTS[0], 1_000),
+            transfer(&payer.pubkey(), &NEXTBLOCK_TIP_ACCOUNTS[0], 1_000),
+            transfer(&payer.pubkey(), &BLOCKRAZOR_TIP_ACCOUNTS[0], 1_000),
+        ];
+        let message = Message::new(&tips, Some(&payer.pubkey()));
+        let merged = Transaction::new(&[&payer], message, Hash::default());
+        assert_eq!(attribute_transaction(&merged.into()), None);
+
+        // Two tip accounts of the same provider still attribute to it
+        let tips = [
+            transfer(&payer.pubkey(), &JITO_TIP_ACCOUNTS[0], 1_000),
+            transfer(&payer.pubkey(), &JITO_TIP_ACCOUNTS[1], 1_000),
+        ];
+        let message = Message::new(&tips, Some(&payer.pubkey()));
+        let tipped = Transaction::new(&[&payer], message, Hash::default());
+        assert_eq!(attribute_transaction(&tipped.into()), Some(SwqosType::Jito));
+    }
+}
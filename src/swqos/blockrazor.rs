@@ -0,0 +1,133 @@
+use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
+use rand::seq::IndexedRandom;
+use reqwest::Client;
+use serde_json::json;
+use std::{sync::Arc, time::Instant};
+
+use std::time::Duration;
+use solana_transaction_status::UiTransactionEncoding;
+
+use anyhow::Result;
+use solana_sdk::transaction::VersionedTransaction;
+use crate::swqos::{SwqosType, TradeType};
+use crate::swqos::SwqosClientTrait;
+
+use crate::{common::SolanaRpcClient, constants::swqos::BLOCKRAZOR_TIP_ACCOUNTS};
+
+
+#[derive(Clone)]
+pub struct BlockRazorClient {
+    pub endpoint: String,
+    pub auth_token: String,
+    pub rpc_client: Arc<SolanaRpcClient>,
+    pub http_client: Client,
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for BlockRazorClient {
+    async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
+        self.send_transaction(trade_type, transaction).await
+    }
+
+    async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
+        self.send_transactions(trade_type, transactions).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        let tip_account = *BLOCKRAZOR_TIP_ACCOUNTS
+            .choose(&mut rand::rng())
+            .ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
+        Ok(tip_account.to_string())
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        SwqosType::BlockRazor
+    }
+}
+
+impl BlockRazorClient {
+    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+        let rpc_client = SolanaRpcClient::new(rpc_url);
+        let http_client = Client::builder()
+            .pool_idle_timeout(Duration::from_secs(60))
+            .pool_max_idle_per_host(64)
+            .tcp_keepalive(Some(Duration::from_secs(1200)))
+            .http2_keep_alive_interval(Duration::from_secs(15))
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client }
+    }
+
+    /// Check that the relay is reachable and accepts the API key.
+    ///
+    /// Also warms up the connection pool, so calling it once at startup saves the handshake
+    /// on the first trade.
+    pub async fn health_check(&self) -> Result<()> {
+        let response = self.http_client
+            .get(format!("{}/health", self.endpoint))
+            .header("apikey", &self.auth_token)
+            .timeout(Duration::from_secs(2))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("blockrazor health check returned {}", response.status()));
+        }
+        Ok(())
+    }
+
+    pub async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
+        let start_time = Instant::now();
+        let (content, signature) = serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        tracing::debug!(provider = "BlockRazor", elapsed = ?start_time.elapsed(), "transaction encoded");
+
+        // "fast" forwards to the leader directly, "sandwichMitigation" avoids validators
+        // known to sandwich at the cost of latency
+        let request_body = serde_json::to_string(&json!({
+            "transaction": content,
+            "mode": "fast"
+        }))?;
+
+        let url = format!("{}/sendTransaction", self.endpoint);
+
+        let response_text = self.http_client.post(&url)
+            .body(request_body)
+            .header("apikey", &self.auth_token)
+            .header("Content-Type", "application/json")
+            .send()
+            .await?
+            .text()
+            .await?;
+
+        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
+            if response_json.get("signature").is_some() || response_json.get("result").is_some() {
+                tracing::info!(provider = "BlockRazor", %trade_type, elapsed = ?start_time.elapsed(), "transaction submitted");
+            } else if let Some(_error) = response_json.get("error").or_else(|| response_json.get("message")) {
+                tracing::warn!(provider = "BlockRazor", %trade_type, error = ?_error, "transaction submission failed");
+            }
+        } else {
+            tracing::warn!(provider = "BlockRazor", %trade_type, error = ?response_text, "transaction submission failed");
+        }
+
+        let start_time: Instant = Instant::now();
+        match poll_transaction_confirmation(&self.rpc_client, signature).await {
+            Ok(_) => (),
+            Err(e) => {
+                tracing::warn!(provider = "BlockRazor", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmation failed");
+                return Err(e);
+            },
+        }
+
+        tracing::info!(provider = "BlockRazor", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmed");
+
+        Ok(())
+    }
+
+    pub async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
+        for transaction in transactions {
+            self.send_transaction(trade_type, transaction).await?;
+        }
+        Ok(())
+    }
+}
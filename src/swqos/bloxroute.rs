@@ -33,7 +33,7 @@ impl SwqosClientTrait for BloxrouteClient {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        let tip_account = *BLOX_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| BLOX_TIP_ACCOUNTS.first()).unwrap();
+        let tip_account = *BLOX_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| BLOX_TIP_ACCOUNTS.first()).ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
         Ok(tip_account.to_string())
     }
 
@@ -60,7 +60,7 @@ impl BloxrouteClient {
     pub async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
         let start_time = Instant::now();
         let (content, signature) = serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
-        println!(" Transaction encoded to base64: {:?}", start_time.elapsed());
+        tracing::debug!(provider = "Bloxroute", elapsed = ?start_time.elapsed(), "transaction encoded");
 
         let body = serde_json::json!({
             "transaction": {
@@ -83,31 +83,31 @@ impl BloxrouteClient {
         // 5. Use `serde_json::from_str()` to parse JSON, reducing extra wait from `.json().await?`
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
-                println!(" bloxroute {} submitted: {:?}", trade_type, start_time.elapsed());
+                tracing::info!(provider = "Bloxroute", %trade_type, elapsed = ?start_time.elapsed(), "transaction submitted");
             } else if let Some(_error) = response_json.get("error") {
-                eprintln!(" bloxroute {} submission failed: {:?}", trade_type, _error);
+                tracing::warn!(provider = "Bloxroute", %trade_type, error = ?_error, "transaction submission failed");
             }
         } else {
-            eprintln!(" bloxroute {} submission failed: {:?}", trade_type, response_text);
+            tracing::warn!(provider = "Bloxroute", %trade_type, error = ?response_text, "transaction submission failed");
         }
 
         let start_time: Instant = Instant::now();
         match poll_transaction_confirmation(&self.rpc_client, signature).await {
             Ok(_) => (),
             Err(e) => {
-                println!(" bloxroute {} confirmation failed: {:?}", trade_type, start_time.elapsed());
+                tracing::warn!(provider = "Bloxroute", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmation failed");
                 return Err(e);
             },
         }
 
-        println!(" bloxroute {} confirmed: {:?}", trade_type, start_time.elapsed());
+        tracing::info!(provider = "Bloxroute", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmed");
 
         Ok(())
     }
 
     pub async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
         let start_time = Instant::now();
-        println!(" Transaction encoded to base64: {:?}", start_time.elapsed());
+        tracing::debug!(provider = "Bloxroute", elapsed = ?start_time.elapsed(), "transaction encoded");
 
         let body = serde_json::json!({
             "entries":  transactions
@@ -134,9 +134,9 @@ impl BloxrouteClient {
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
-                println!(" bloxroute {} submitted: {:?}", trade_type, start_time.elapsed());
+                tracing::info!(provider = "Bloxroute", %trade_type, elapsed = ?start_time.elapsed(), "transaction submitted");
             } else if let Some(_error) = response_json.get("error") {
-                eprintln!(" bloxroute {} submission failed: {:?}", trade_type, _error);
+                tracing::warn!(provider = "Bloxroute", %trade_type, error = ?_error, "transaction submission failed");
             }
         }
 
@@ -1,13 +1,16 @@
 use bincode::serialize;
 use serde_json::json;
 use solana_client::rpc_client::SerializableTransaction;
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::signature::Signature;
 use solana_sdk::transaction::Transaction;
-use solana_transaction_status::{TransactionConfirmationStatus, UiTransactionEncoding};
+use solana_transaction_status::UiTransactionEncoding;
 use std::str::FromStr;
+use std::sync::RwLock;
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
 use crate::common::types::SolanaRpcClient;
+use crate::common::TradeError;
 use anyhow::Result;
 use base64::Engine;
 use base64::engine::general_purpose::{self, STANDARD};
@@ -25,34 +28,93 @@ impl FormatBase64VersionedTransaction for VersionedTransaction {
     }
 }
 
-pub async fn poll_transaction_confirmation(rpc: &SolanaRpcClient, txt_sig: Signature) -> Result<Signature> {
-    let timeout: Duration = Duration::from_secs(5);
-    let interval: Duration = Duration::from_millis(1000);
-    let start: Instant = Instant::now();
+/// Signatures per `getSignatureStatuses` request, the RPC limit
+const MAX_SIGNATURE_STATUSES: usize = 256;
+
+/// How submitted transactions are polled until they confirm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfirmationConfig {
+    /// Delay between two `getSignatureStatuses` polls
+    pub poll_interval: Duration,
+    /// Time after which the transaction counts as not landed
+    pub timeout: Duration,
+    /// Commitment the transaction must reach
+    pub commitment: CommitmentConfig,
+}
 
-    loop {
-        if start.elapsed() >= timeout {
-            return Err(anyhow::anyhow!("Transaction {}'s confirmation timed out", txt_sig));
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(1000),
+            timeout: Duration::from_secs(5),
+            commitment: CommitmentConfig::confirmed(),
         }
+    }
+}
 
-        let status = rpc.get_signature_statuses(&[txt_sig]).await?;
+static CONFIRMATION_CONFIG: RwLock<Option<ConfirmationConfig>> = RwLock::new(None);
 
-        match status.value[0].clone() {
-            Some(status) => {
-                if status.err.is_none()
-                    && (status.confirmation_status == Some(TransactionConfirmationStatus::Confirmed)
-                        || status.confirmation_status == Some(TransactionConfirmationStatus::Finalized))
-                {
-                    return Ok(txt_sig);
-                }
-                if status.err.is_some() {
-                    return Err(anyhow::anyhow!(status.err.unwrap()));
+/// Set how the swqos clients poll for confirmation
+pub fn set_confirmation_config(config: ConfirmationConfig) {
+    *CONFIRMATION_CONFIG.write().unwrap() = Some(config);
+}
+
+/// The polling settings of the swqos clients
+pub fn confirmation_config() -> ConfirmationConfig {
+    CONFIRMATION_CONFIG.read().unwrap().unwrap_or_default()
+}
+
+/// Wait until `txt_sig` confirms, polling as set by `set_confirmation_config`
+pub async fn poll_transaction_confirmation(rpc: &SolanaRpcClient, txt_sig: Signature) -> Result<Signature> {
+    poll_signatures_confirmation(rpc, &[txt_sig], &confirmation_config()).await
+}
+
+/// Wait until the first of `signatures` confirms, returning its signature
+///
+/// Meant for racing transactions of the same trade: every poll fetches the statuses of all
+/// signatures in one `getSignatureStatuses` request. A transaction that landed with an error
+/// stops being polled; the call fails with that error once all of them failed.
+pub async fn poll_signatures_confirmation(
+    rpc: &SolanaRpcClient,
+    signatures: &[Signature],
+    config: &ConfirmationConfig,
+) -> Result<Signature> {
+    let start = Instant::now();
+    let mut pending = signatures.to_vec();
+    let mut last_error = None;
+
+    while !pending.is_empty() {
+        if start.elapsed() >= config.timeout {
+            let message = match signatures {
+                [signature] => format!("Transaction {}'s confirmation timed out", signature),
+                _ => format!("None of {} transactions confirmed in time", signatures.len()),
+            };
+            return Err(TradeError::Timeout(message).into());
+        }
+
+        let mut failed = vec![];
+        for chunk in pending.chunks(MAX_SIGNATURE_STATUSES) {
+            let statuses = rpc.get_signature_statuses(chunk).await.inspect_err(|_| {
+                crate::metrics::record_rpc_error("get_signature_statuses");
+            })?;
+            for (signature, status) in chunk.iter().zip(statuses.value) {
+                let Some(status) = status else { continue };
+                if let Some(err) = status.err {
+                    failed.push(*signature);
+                    last_error = Some(err);
+                } else if status.satisfies_commitment(config.commitment) {
+                    return Ok(*signature);
                 }
             }
-            None => {
-                sleep(interval).await;
-            }
         }
+        pending.retain(|signature| !failed.contains(signature));
+        if !pending.is_empty() {
+            sleep(config.poll_interval).await;
+        }
+    }
+    match last_error {
+        Some(err) => Err(TradeError::TransactionFailed(err).into()),
+        None => Err(anyhow::anyhow!("No signatures to confirm")),
     }
 }
 
@@ -135,4 +197,48 @@ pub async fn serialize_smart_transaction_and_encode(
         _ => return Err(anyhow::anyhow!("Unsupported encoding")),
     };
     Ok((serialized, *signature))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockRpc;
+    use solana_client::rpc_request::RpcRequest;
+
+    #[tokio::test]
+    async fn test_poll_racing_signatures() {
+        let statuses = json!({
+            "context": { "slot": 1 },
+            "value": [
+                {
+                    "slot": 1,
+                    "confirmations": 0,
+                    "err": null,
+                    "status": { "Ok": null },
+                    "confirmationStatus": "processed"
+                },
+                {
+                    "slot": 1,
+                    "confirmations": 0,
+                    "err": { "InstructionError": [0, { "Custom": 1 }] },
+                    "status": { "Err": { "InstructionError": [0, { "Custom": 1 }] } },
+                    "confirmationStatus": "confirmed"
+                }
+            ]
+        });
+        let mock = MockRpc::new().with_response(RpcRequest::GetSignatureStatuses, statuses);
+        let rpc = mock.client();
+        let signatures = [Signature::new_unique(), Signature::new_unique()];
+        let config = ConfirmationConfig {
+            poll_interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(50),
+            commitment: CommitmentConfig::processed(),
+        };
+
+        let confirmed = poll_signatures_confirmation(&rpc, &signatures, &config).await.unwrap();
+        assert_eq!(confirmed, signatures[0]);
+
+        let config = ConfirmationConfig { commitment: CommitmentConfig::confirmed(), ..config };
+        let error = poll_signatures_confirmation(&rpc, &signatures, &config).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<TradeError>(), Some(TradeError::Timeout(_))));
+    }
+}
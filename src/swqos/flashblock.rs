@@ -34,7 +34,7 @@ impl SwqosClientTrait for FlashBlockClient {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        let tip_account = *FLASHBLOCK_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| FLASHBLOCK_TIP_ACCOUNTS.first()).unwrap();
+        let tip_account = *FLASHBLOCK_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| FLASHBLOCK_TIP_ACCOUNTS.first()).ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
         Ok(tip_account.to_string())
     }
 
@@ -61,7 +61,7 @@ impl FlashBlockClient {
     pub async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
         let start_time = Instant::now();
         let (content, signature) = serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
-        println!(" Transaction encoded to base64: {:?}", start_time.elapsed());
+        tracing::debug!(provider = "FlashBlock", elapsed = ?start_time.elapsed(), "transaction encoded");
 
         // FlashBlock API format
         let request_body = serde_json::to_string(&json!({
@@ -83,24 +83,24 @@ impl FlashBlockClient {
         // Parse response
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("success").is_some() || response_json.get("result").is_some() {
-                println!(" FlashBlock {} submitted: {:?}", trade_type, start_time.elapsed());
+                tracing::info!(provider = "FlashBlock", %trade_type, elapsed = ?start_time.elapsed(), "transaction submitted");
             } else if let Some(_error) = response_json.get("error") {
-                eprintln!(" FlashBlock {} submission failed: {:?}", trade_type, _error);
+                tracing::warn!(provider = "FlashBlock", %trade_type, error = ?_error, "transaction submission failed");
             }
         } else {
-            eprintln!(" FlashBlock {} submission failed: {:?}", trade_type, response_text);
+            tracing::warn!(provider = "FlashBlock", %trade_type, error = ?response_text, "transaction submission failed");
         }
 
         let start_time: Instant = Instant::now();
         match poll_transaction_confirmation(&self.rpc_client, signature).await {
             Ok(_) => (),
             Err(e) => {
-                println!(" FlashBlock {} confirmation failed: {:?}", trade_type, start_time.elapsed());
+                tracing::warn!(provider = "FlashBlock", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmation failed");
                 return Err(e);
             },
         }
 
-        println!(" FlashBlock {} confirmed: {:?}", trade_type, start_time.elapsed());
+        tracing::info!(provider = "FlashBlock", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmed");
 
         Ok(())
     }
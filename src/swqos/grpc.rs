@@ -0,0 +1,265 @@
+//! gRPC submission transport
+//!
+//! Jito block engines and NextBlock also accept transactions over gRPC, which keeps one
+//! HTTP/2 connection open and sends protobuf instead of base64 JSON. These clients are used
+//! in place of the HTTP ones by `SwqosConfig::JitoGrpc` / `SwqosConfig::NextBlockGrpc` and
+//! report the same `SwqosType` and tip accounts.
+
+use anyhow::Result;
+use rand::seq::IndexedRandom;
+use solana_sdk::transaction::VersionedTransaction;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tonic::{
+    metadata::{Ascii, MetadataValue},
+    transport::{Channel, ClientTlsConfig, Endpoint},
+    Request,
+};
+
+use crate::{
+    common::SolanaRpcClient,
+    swqos::{common::poll_transaction_confirmation, SwqosClientTrait, SwqosType, TradeType},
+};
+
+/// Channel to `url` that connects on first use and reconnects after failures
+///
+/// Built lazily so clients can be created from synchronous configuration code.
+fn lazy_channel(url: &str) -> Result<Channel> {
+    let mut endpoint = Endpoint::from_shared(url.to_string())?
+        .tcp_nodelay(true)
+        .tcp_keepalive(Some(Duration::from_secs(10)))
+        .connect_timeout(Duration::from_secs(5))
+        .timeout(Duration::from_secs(10))
+        .http2_keep_alive_interval(Duration::from_secs(10));
+    if url.starts_with("https") {
+        endpoint = endpoint.tls_config(ClientTlsConfig::new().with_native_roots())?;
+    }
+    Ok(endpoint.connect_lazy())
+}
+
+/// `request` with `value` in the `key` header, unless `value` is empty
+fn with_auth<T>(request: T, key: &'static str, value: &Option<MetadataValue<Ascii>>) -> Request<T> {
+    let mut request = Request::new(request);
+    if let Some(value) = value {
+        request.metadata_mut().insert(key, value.clone());
+    }
+    request
+}
+
+fn auth_value(auth_token: &str) -> Result<Option<MetadataValue<Ascii>>> {
+    if auth_token.is_empty() {
+        return Ok(None);
+    }
+    // Header parsing lets non-ASCII bytes through
+    if !auth_token.is_ascii() {
+        return Err(anyhow::anyhow!("auth token must be ASCII"));
+    }
+    Ok(Some(auth_token.parse()?))
+}
+
+#[cfg(feature = "jito")]
+pub use jito::JitoGrpcClient;
+
+#[cfg(feature = "jito")]
+mod jito {
+    use super::*;
+    use crate::constants::swqos::JITO_TIP_ACCOUNTS;
+    use crate::protos::{
+        bundle::Bundle, convert::proto_packet_from_versioned_tx,
+        searcher::searcher_service_client::SearcherServiceClient, searcher::SendBundleRequest,
+    };
+
+    /// Jito block engine over gRPC, transactions are sent as bundles
+    #[derive(Clone)]
+    pub struct JitoGrpcClient {
+        pub endpoint: String,
+        pub rpc_client: Arc<SolanaRpcClient>,
+        client: SearcherServiceClient<Channel>,
+        auth: Option<MetadataValue<Ascii>>,
+    }
+
+    impl JitoGrpcClient {
+        /// `endpoint` is a block engine URL, `auth_token` the optional Jito UUID
+        pub fn new(rpc: Arc<SolanaRpcClient>, endpoint: String, auth_token: &str) -> Result<Self> {
+            let client = SearcherServiceClient::new(lazy_channel(&endpoint)?);
+            Ok(Self { endpoint, rpc_client: rpc, client, auth: auth_value(auth_token)? })
+        }
+
+        pub async fn send_bundle(&self, transactions: &[VersionedTransaction]) -> Result<String> {
+            let packets = transactions.iter().map(proto_packet_from_versioned_tx).collect();
+            let request = SendBundleRequest { bundle: Some(Bundle { header: None, packets }) };
+            let response = self
+                .client
+                .clone()
+                .send_bundle(with_auth(request, "x-jito-auth", &self.auth))
+                .await?;
+            Ok(response.into_inner().uuid)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SwqosClientTrait for JitoGrpcClient {
+        async fn send_transaction(
+            &self,
+            trade_type: TradeType,
+            transaction: &VersionedTransaction,
+        ) -> Result<()> {
+            let start_time = Instant::now();
+            let bundle_id = self.send_bundle(std::slice::from_ref(transaction)).await?;
+            tracing::info!(provider = "Jito", transport = "grpc", %trade_type, %bundle_id, elapsed = ?start_time.elapsed(), "transaction submitted");
+            let start_time = Instant::now();
+            poll_transaction_confirmation(&self.rpc_client, transaction.signatures[0])
+                .await
+                .inspect_err(|_| {
+                    tracing::warn!(provider = "Jito", transport = "grpc", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmation failed");
+                })?;
+            tracing::info!(provider = "Jito", transport = "grpc", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmed");
+            Ok(())
+        }
+
+        async fn send_transactions(
+            &self,
+            trade_type: TradeType,
+            transactions: &Vec<VersionedTransaction>,
+        ) -> Result<()> {
+            let start_time = Instant::now();
+            let bundle_id = self.send_bundle(transactions).await?;
+            tracing::info!(provider = "Jito", transport = "grpc", %trade_type, %bundle_id, elapsed = ?start_time.elapsed(), "bundle submitted");
+            Ok(())
+        }
+
+        fn get_tip_account(&self) -> Result<String> {
+            JITO_TIP_ACCOUNTS
+                .choose(&mut rand::rng())
+                .map(|account| account.to_string())
+                .ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))
+        }
+
+        fn get_swqos_type(&self) -> SwqosType {
+            SwqosType::Jito
+        }
+    }
+}
+
+#[cfg(feature = "nextblock")]
+pub use nextblock::NextBlockGrpcClient;
+
+#[cfg(feature = "nextblock")]
+mod nextblock {
+    use super::*;
+    use crate::constants::swqos::NEXTBLOCK_TIP_ACCOUNTS;
+    use crate::protos::nextblock_grpc::{
+        api_client::ApiClient, PostSubmitRequest, TransactionMessage,
+    };
+    use crate::swqos::common::serialize_transaction_and_encode;
+    use solana_transaction_status::UiTransactionEncoding;
+
+    /// NextBlock over gRPC
+    #[derive(Clone)]
+    pub struct NextBlockGrpcClient {
+        pub endpoint: String,
+        pub rpc_client: Arc<SolanaRpcClient>,
+        client: ApiClient<Channel>,
+        auth: Option<MetadataValue<Ascii>>,
+    }
+
+    impl NextBlockGrpcClient {
+        pub fn new(rpc: Arc<SolanaRpcClient>, endpoint: String, auth_token: &str) -> Result<Self> {
+            let client = ApiClient::new(lazy_channel(&endpoint)?);
+            Ok(Self { endpoint, rpc_client: rpc, client, auth: auth_value(auth_token)? })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SwqosClientTrait for NextBlockGrpcClient {
+        async fn send_transaction(
+            &self,
+            trade_type: TradeType,
+            transaction: &VersionedTransaction,
+        ) -> Result<()> {
+            let start_time = Instant::now();
+            let (content, signature) =
+                serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)
+                    .await?;
+            let request = PostSubmitRequest {
+                transaction: Some(TransactionMessage { content, is_cleanup: false }),
+                skip_pre_flight: true,
+                front_running_protection: Some(false),
+                experimental_front_running_protection: None,
+                snipe_transaction: None,
+            };
+            self.client
+                .clone()
+                .post_submit_v2(with_auth(request, "authorization", &self.auth))
+                .await?;
+            tracing::info!(provider = "NextBlock", transport = "grpc", %trade_type, elapsed = ?start_time.elapsed(), "transaction submitted");
+
+            let start_time = Instant::now();
+            poll_transaction_confirmation(&self.rpc_client, signature).await.inspect_err(|_| {
+                tracing::warn!(provider = "NextBlock", transport = "grpc", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmation failed");
+            })?;
+            tracing::info!(provider = "NextBlock", transport = "grpc", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmed");
+            Ok(())
+        }
+
+        async fn send_transactions(
+            &self,
+            trade_type: TradeType,
+            transactions: &Vec<VersionedTransaction>,
+        ) -> Result<()> {
+            for transaction in transactions {
+                self.send_transaction(trade_type, transaction).await?;
+            }
+            Ok(())
+        }
+
+        fn get_tip_account(&self) -> Result<String> {
+            NEXTBLOCK_TIP_ACCOUNTS
+                .choose(&mut rand::rng())
+                .map(|account| account.to_string())
+                .ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))
+        }
+
+        fn get_swqos_type(&self) -> SwqosType {
+            SwqosType::NextBlock
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Channels need a crypto provider, installed by `SolanaTrade::new` outside of tests
+    fn install_crypto_provider() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+
+    #[tokio::test]
+    async fn test_auth_header() {
+        install_crypto_provider();
+        assert!(auth_value("").unwrap().is_none());
+        let auth = auth_value("token").unwrap();
+        let request = with_auth((), "authorization", &auth);
+        assert_eq!(request.metadata().get("authorization").unwrap(), "token");
+        // Channels connect lazily, so clients can be built without a reachable endpoint
+        assert!(lazy_channel("https://localhost:1").is_ok());
+    }
+
+    #[cfg(feature = "jito")]
+    #[tokio::test]
+    async fn test_invalid_auth_token_is_an_error() {
+        install_crypto_provider();
+        use crate::common::SolanaRpcClient;
+        use crate::swqos::{SwqosConfig, SwqosRegion};
+        use solana_sdk::commitment_config::CommitmentConfig;
+
+        let rpc = Arc::new(SolanaRpcClient::new("http://localhost:8899".to_string()));
+        let config = SwqosConfig::JitoGrpc("tökén".to_string(), SwqosRegion::Frankfurt);
+        let result =
+            SwqosConfig::get_swqos_client_with_rpc(rpc, CommitmentConfig::confirmed(), config);
+        assert!(result.is_err());
+    }
+}
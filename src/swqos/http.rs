@@ -0,0 +1,78 @@
+//! HTTP client shared by the swqos providers
+//!
+//! Every HTTP based provider builds its own `reqwest::Client` with its own pool and timeout
+//! settings. Passing one client through `TradeConfigBuilder::with_swqos_http_client`, or
+//! settings through `with_swqos_http_config`, makes all providers share a single connection
+//! pool configured in one place.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+
+use crate::common::proxy::ProxyConfig;
+
+/// Settings of the shared swqos HTTP client
+///
+/// The defaults are the settings most providers use on their own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwqosHttpConfig {
+    pub pool_idle_timeout: Duration,
+    pub pool_max_idle_per_host: usize,
+    pub tcp_keepalive: Option<Duration>,
+    pub http2_keep_alive_interval: Option<Duration>,
+    /// Timeout of a whole request
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    /// Proxy the providers are reached through, see `common::proxy`
+    pub proxy: Option<ProxyConfig>,
+}
+
+impl Default for SwqosHttpConfig {
+    fn default() -> Self {
+        Self {
+            pool_idle_timeout: Duration::from_secs(60),
+            pool_max_idle_per_host: 64,
+            tcp_keepalive: Some(Duration::from_secs(1200)),
+            http2_keep_alive_interval: Some(Duration::from_secs(15)),
+            timeout: Duration::from_secs(10),
+            connect_timeout: Duration::from_secs(5),
+            proxy: None,
+        }
+    }
+}
+
+impl SwqosHttpConfig {
+    pub fn build(&self) -> Result<Client> {
+        let mut builder = Client::builder()
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .tcp_keepalive(self.tcp_keepalive)
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout);
+        if let Some(interval) = self.http2_keep_alive_interval {
+            builder = builder.http2_keep_alive_interval(interval);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = proxy.apply(builder)?;
+        }
+        builder.build().map_err(|e| anyhow!("Failed to build swqos HTTP client: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_http_client() {
+        assert!(SwqosHttpConfig::default().build().is_ok());
+        let config = SwqosHttpConfig {
+            tcp_keepalive: None,
+            http2_keep_alive_interval: None,
+            proxy: Some(ProxyConfig::new("socks5://127.0.0.1:1080")),
+            ..Default::default()
+        };
+        assert!(config.build().is_ok());
+    }
+}
@@ -3,24 +3,159 @@ use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
-use std::{sync::Arc, time::Instant};
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex, Weak};
+use tokio::task::JoinHandle;
+use std::time::Instant;
 
 use std::time::Duration;
 use solana_transaction_status::UiTransactionEncoding;
 
 use anyhow::Result;
-use solana_sdk::transaction::VersionedTransaction;
-use crate::swqos::{SwqosType, TradeType};
+use solana_sdk::{signature::Signature, transaction::VersionedTransaction};
+use solana_transaction_status::TransactionConfirmationStatus;
+use crate::common::{task_manager::TaskManager, TradeError};
+use crate::swqos::{SwqosRegion, SwqosType, TradeType};
 use crate::swqos::SwqosClientTrait;
 
-use crate::{common::SolanaRpcClient, constants::swqos::JITO_TIP_ACCOUNTS};
+use crate::{common::SolanaRpcClient, constants::swqos::{JITO_TIP_ACCOUNTS, SWQOS_ENDPOINTS_JITO}};
 
 
+/// How long a block engine that failed a request is skipped before being tried again
+pub const JITO_ENDPOINT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// How often the block engines of a failover client are probed in the background
+pub const JITO_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+struct EndpointHealth {
+    url: String,
+    unhealthy_until: Option<Instant>,
+}
+
+impl EndpointHealth {
+    fn is_healthy(&self, now: Instant) -> bool {
+        match self.unhealthy_until {
+            Some(until) => until <= now,
+            None => true,
+        }
+    }
+}
+
+fn mark_endpoint(endpoints: &Mutex<Vec<EndpointHealth>>, url: &str, healthy: bool) {
+    let mut endpoints = endpoints.lock().unwrap();
+    if let Some(e) = endpoints.iter_mut().find(|e| e.url == url) {
+        e.unhealthy_until = if healthy { None } else { Some(Instant::now() + JITO_ENDPOINT_COOLDOWN) };
+    }
+}
+
+/// Call `getTipAccounts` on every block engine and update its health
+async fn probe_endpoints(http_client: &Client, endpoints: &Mutex<Vec<EndpointHealth>>) -> Vec<String> {
+    let urls: Vec<String> = endpoints.lock().unwrap().iter().map(|e| e.url.clone()).collect();
+    let body = json!({
+        "jsonrpc": "2.0",
+        "method": "getTipAccounts",
+        "params": [],
+        "id": 1,
+    })
+    .to_string();
+    let probes = urls.iter().map(|url| {
+        let request = http_client
+            .post(format!("{}/api/v1/bundles", url))
+            .header("Content-Type", "application/json")
+            .body(body.clone())
+            .timeout(Duration::from_secs(2));
+        async move { request.send().await.map(|r| r.status().is_success()).unwrap_or(false) }
+    });
+    let results = futures::future::join_all(probes).await;
+
+    let mut healthy = Vec::new();
+    for (url, ok) in urls.into_iter().zip(results) {
+        mark_endpoint(endpoints, &url, ok);
+        if ok {
+            healthy.push(url);
+        }
+    }
+    healthy
+}
+
+/// Outcome of a single transaction in a simulated bundle
+#[derive(Debug, Clone)]
+pub struct BundleTransactionResult {
+    pub err: Option<serde_json::Value>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+/// Result of `simulateBundle`
+#[derive(Debug, Clone)]
+pub struct BundleSimulation {
+    pub succeeded: bool,
+    /// Bundle level error reported by the block engine
+    pub error: Option<serde_json::Value>,
+    /// Signature of the transaction that failed the bundle
+    pub failed_signature: Option<String>,
+    /// Per-transaction results, in bundle order, up to and including the failing one
+    pub transaction_results: Vec<BundleTransactionResult>,
+}
+
+/// Escalating tip schedule for `send_bundle_with_retry`
+#[derive(Debug, Clone)]
+pub struct BundleRetryConfig {
+    /// Tip of the first attempt, in SOL
+    pub initial_tip: f64,
+    /// Factor the tip is multiplied by after each attempt that did not land
+    pub tip_multiplier: f64,
+    /// Upper bound of the tip, in SOL
+    pub max_tip: f64,
+    pub max_attempts: u32,
+    /// How long an attempt is given to land before the bundle is resubmitted
+    pub attempt_timeout: Duration,
+}
+
+impl Default for BundleRetryConfig {
+    fn default() -> Self {
+        Self {
+            initial_tip: 0.001,
+            tip_multiplier: 2.0,
+            max_tip: 0.01,
+            max_attempts: 4,
+            attempt_timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+impl BundleRetryConfig {
+    /// Tip in SOL of the zero-based `attempt`
+    pub fn tip_for_attempt(&self, attempt: u32) -> f64 {
+        let tip = self.initial_tip * self.tip_multiplier.max(1.0).powi(attempt as i32);
+        tip.min(self.max_tip.max(self.initial_tip))
+    }
+}
+
+/// A submission made by `send_bundle_with_retry`
+#[derive(Debug, Clone)]
+pub struct BundleAttempt {
+    /// Zero-based attempt number
+    pub attempt: u32,
+    /// Tip paid by this attempt, in SOL
+    pub tip: f64,
+    /// Bundle id returned by the block engine
+    pub bundle_id: Option<String>,
+    /// Signatures of the bundle's transactions, in bundle order
+    pub signatures: Vec<Signature>,
+    /// Why the submission was rejected
+    pub error: Option<String>,
+}
+
 pub struct JitoClient {
+    /// Primary block engine
     pub endpoint: String,
     pub auth_token: String,
     pub rpc_client: Arc<SolanaRpcClient>,
     pub http_client: Client,
+    /// Primary followed by fallback block engines, in priority order
+    endpoints: Arc<Mutex<Vec<EndpointHealth>>>,
+    /// Simulate multi-transaction bundles before sending them
+    simulate_bundles: AtomicBool,
 }
 
 #[async_trait::async_trait]
@@ -47,24 +182,241 @@ impl SwqosClientTrait for JitoClient {
 }
 
 impl JitoClient {
-    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
-        let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = Client::builder()
-            .pool_idle_timeout(Duration::from_secs(60))
-            .pool_max_idle_per_host(64)
-            .tcp_keepalive(Some(Duration::from_secs(1200)))
-            .http2_keep_alive_interval(Duration::from_secs(15))
-            .timeout(Duration::from_secs(10))
-            .connect_timeout(Duration::from_secs(5))
-            .build()
-            .unwrap();
-        Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client }
+    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Result<Self> {
+        Self::new_with_failover(rpc_url, vec![endpoint], auth_token)
+    }
+
+    /// Create a client that fails over between several block engines.
+    ///
+    /// Requests go to the first healthy endpoint in order. An endpoint that times out,
+    /// refuses the connection or answers 429/5xx is skipped for `JITO_ENDPOINT_COOLDOWN`
+    /// and the request is retried on the next one, so a regional outage doesn't stop
+    /// bundles and tips from being submitted.
+    pub fn new_with_failover(rpc_url: String, endpoints: Vec<String>, auth_token: String) -> Result<Self> {
+        Self::with_shared_clients(Arc::new(SolanaRpcClient::new(rpc_url)), endpoints, auth_token, None)
+    }
+
+    /// Like `new_with_failover`, using `rpc_client` and, when set, `http_client` instead of
+    /// building its own
+    pub fn with_shared_clients(
+        rpc_client: Arc<SolanaRpcClient>,
+        mut endpoints: Vec<String>,
+        auth_token: String,
+        http_client: Option<Client>,
+    ) -> Result<Self> {
+        // Several regions share a block engine, keep the first occurrence only
+        let mut seen = std::collections::HashSet::new();
+        endpoints.retain(|url| seen.insert(url.clone()));
+        if endpoints.is_empty() {
+            endpoints.push(SWQOS_ENDPOINTS_JITO[SwqosRegion::Default as usize].to_string());
+        }
+        let http_client = match http_client {
+            Some(http_client) => http_client,
+            None => Client::builder()
+                .pool_idle_timeout(Duration::from_secs(60))
+                .pool_max_idle_per_host(64)
+                .tcp_keepalive(Some(Duration::from_secs(1200)))
+                .http2_keep_alive_interval(Duration::from_secs(15))
+                .timeout(Duration::from_secs(10))
+                .connect_timeout(Duration::from_secs(5))
+                .build()?,
+        };
+        let endpoint = endpoints[0].clone();
+        let endpoints = endpoints
+            .into_iter()
+            .map(|url| EndpointHealth { url, unhealthy_until: None })
+            .collect();
+        Ok(Self {
+            rpc_client,
+            endpoint,
+            auth_token,
+            http_client,
+            endpoints: Arc::new(Mutex::new(endpoints)),
+            simulate_bundles: AtomicBool::new(false),
+        })
+    }
+
+    /// Block engines ordered for the next request: healthy ones first, then those
+    /// still cooling down so a request is attempted even if every region failed recently
+    fn ordered_endpoints(&self) -> Vec<String> {
+        let now = Instant::now();
+        let endpoints = self.endpoints.lock().unwrap();
+        let (healthy, cooling): (Vec<_>, Vec<_>) = endpoints
+            .iter()
+            .partition(|e| e.is_healthy(now));
+        healthy.into_iter().chain(cooling).map(|e| e.url.clone()).collect()
+    }
+
+    fn mark_endpoint(&self, url: &str, healthy: bool) {
+        mark_endpoint(&self.endpoints, url, healthy);
+    }
+
+    /// Simulate bundles with more than one transaction before sending and abort
+    /// (without paying the tip) when the simulation fails
+    pub fn with_simulate_bundles(self, enabled: bool) -> Self {
+        self.set_simulate_bundles(enabled);
+        self
+    }
+
+    /// Like `with_simulate_bundles`, for a client that is already shared
+    pub fn set_simulate_bundles(&self, enabled: bool) {
+        self.simulate_bundles.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Simulate a bundle with `simulateBundle`.
+    ///
+    /// The transactions are executed in order against the current bank without landing,
+    /// so tips are not paid. An `Err` means the simulation request itself failed;
+    /// a failing bundle is reported through `BundleSimulation::succeeded`.
+    pub async fn simulate_bundle(&self, transactions: &[VersionedTransaction]) -> Result<BundleSimulation> {
+        let txs_base64 = transactions.iter().map(|tx| tx.to_base64_string()).collect::<Vec<String>>();
+        let account_configs = vec![serde_json::Value::Null; transactions.len()];
+        let body = json!({
+            "jsonrpc": "2.0",
+            "method": "simulateBundle",
+            "params": [
+                { "encodedTransactions": txs_base64 },
+                {
+                    "transactionEncoding": "base64",
+                    "skipSigVerify": false,
+                    "replaceRecentBlockhash": false,
+                    "preExecutionAccountsConfigs": account_configs,
+                    "postExecutionAccountsConfigs": account_configs,
+                }
+            ],
+            "id": 1,
+        });
+
+        let response_text = self.post_with_failover("/api/v1/bundles", body.to_string()).await?;
+        let response_json: serde_json::Value = serde_json::from_str(&response_text)
+            .map_err(|_| anyhow::anyhow!("invalid simulateBundle response: {}", response_text))?;
+        if let Some(error) = response_json.get("error") {
+            return Err(anyhow::anyhow!("simulateBundle failed: {}", error));
+        }
+        let value = response_json
+            .pointer("/result/value")
+            .ok_or_else(|| anyhow::anyhow!("invalid simulateBundle response: {}", response_text))?;
+
+        let summary = value.get("summary");
+        let failed = summary.and_then(|s| s.get("failed"));
+        let transaction_results = value
+            .get("transactionResults")
+            .and_then(|r| r.as_array())
+            .map(|results| {
+                results
+                    .iter()
+                    .map(|r| BundleTransactionResult {
+                        err: r.get("err").filter(|e| !e.is_null()).cloned(),
+                        logs: r
+                            .get("logs")
+                            .and_then(|l| l.as_array())
+                            .map(|l| l.iter().filter_map(|s| s.as_str().map(String::from)).collect())
+                            .unwrap_or_default(),
+                        units_consumed: r.get("unitsConsumed").and_then(|u| u.as_u64()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(BundleSimulation {
+            succeeded: failed.is_none() && summary.and_then(|s| s.as_str()) == Some("succeeded"),
+            error: failed.and_then(|f| f.get("error")).cloned(),
+            failed_signature: failed
+                .and_then(|f| f.get("tx_signature"))
+                .and_then(|s| s.as_str())
+                .map(String::from),
+            transaction_results,
+        })
+    }
+
+    /// Block engines that are currently considered healthy
+    pub fn healthy_endpoints(&self) -> Vec<String> {
+        let now = Instant::now();
+        self.endpoints
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.is_healthy(now))
+            .map(|e| e.url.clone())
+            .collect()
+    }
+
+    /// Probe every block engine with `getTipAccounts` and update its health.
+    ///
+    /// Returns the endpoints that answered successfully.
+    pub async fn probe_endpoints(&self) -> Vec<String> {
+        probe_endpoints(&self.http_client, &self.endpoints).await
+    }
+
+    /// Number of block engines the client fails over between
+    pub fn endpoint_count(&self) -> usize {
+        self.endpoints.lock().unwrap().len()
+    }
+
+    /// Run `probe_endpoints` every `interval` until the client is dropped, so a block engine
+    /// that recovered is preferred again without waiting for a request to fail over to it
+    pub fn spawn_probe_task(&self, interval: Duration) -> JoinHandle<()> {
+        let http_client = self.http_client.clone();
+        let endpoints: Weak<Mutex<Vec<EndpointHealth>>> = Arc::downgrade(&self.endpoints);
+        TaskManager::get_instance().spawn("jito_probe", async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match endpoints.upgrade() {
+                    Some(endpoints) => {
+                        probe_endpoints(&http_client, &endpoints).await;
+                    }
+                    None => break,
+                }
+            }
+        })
+    }
+
+    /// POST a JSON-RPC body to `path`, failing over to the next block engine on
+    /// connection errors, rate limiting or server errors
+    async fn post_with_failover(&self, path: &str, body: String) -> Result<String> {
+        let mut last_error = None;
+        for url in self.ordered_endpoints() {
+            let endpoint = if self.auth_token.is_empty() {
+                format!("{}{}", url, path)
+            } else {
+                format!("{}{}?uuid={}", url, path, self.auth_token)
+            };
+            let request = if self.auth_token.is_empty() {
+                self.http_client.post(&endpoint)
+            } else {
+                self.http_client.post(&endpoint)
+                    .header("x-jito-auth", &self.auth_token)
+            };
+            let response = request
+                .body(body.clone())
+                .header("Content-Type", "application/json")
+                .send()
+                .await;
+            match response {
+                Ok(response) if response.status().is_server_error() || response.status().as_u16() == 429 => {
+                    tracing::warn!(provider = "Jito", %url, status = %response.status(), "block engine error, failing over");
+                    self.mark_endpoint(&url, false);
+                    last_error = Some(anyhow::anyhow!("jito block engine {} returned {}", url, response.status()));
+                }
+                Ok(response) => {
+                    self.mark_endpoint(&url, true);
+                    return Ok(response.text().await?);
+                }
+                Err(e) => {
+                    tracing::warn!(provider = "Jito", %url, error = %e, "block engine unreachable, failing over");
+                    self.mark_endpoint(&url, false);
+                    last_error = Some(e.into());
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("no jito endpoints configured")))
     }
 
     pub async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
         let start_time = Instant::now();
         let (content, signature) = serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
-        println!(" Transaction encoded to base64: {:?}", start_time.elapsed());
+        tracing::debug!(provider = "Jito", elapsed = ?start_time.elapsed(), "transaction encoded");
 
         let request_body = serde_json::to_string(&json!({
             "id": 1,
@@ -78,50 +430,55 @@ impl JitoClient {
             ]
         }))?;
 
-        let endpoint = if self.auth_token.is_empty() {
-            format!("{}/api/v1/transactions", self.endpoint)
-        } else {
-            format!("{}/api/v1/transactions?uuid={}", self.endpoint, self.auth_token)
-        };
-        let response = if self.auth_token.is_empty() {
-            self.http_client.post(&endpoint)
-        } else {
-            self.http_client.post(&endpoint)
-                .header("x-jito-auth", &self.auth_token)
-        };
-        let response_text = response
-            .body(request_body)
-            .header("Content-Type", "application/json")
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = self.post_with_failover("/api/v1/transactions", request_body).await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
-                println!(" jito {} submitted: {:?}", trade_type, start_time.elapsed());
+                tracing::info!(provider = "Jito", %trade_type, elapsed = ?start_time.elapsed(), "transaction submitted");
             } else if let Some(_error) = response_json.get("error") {
-                eprintln!(" jito {} submission failed: {:?}", trade_type, _error);
+                tracing::warn!(provider = "Jito", %trade_type, error = ?_error, "transaction submission failed");
             }
         } else {
-            eprintln!(" jito {} submission failed: {:?}", trade_type, response_text);
+            tracing::warn!(provider = "Jito", %trade_type, error = ?response_text, "transaction submission failed");
         }
 
         let start_time: Instant = Instant::now();
         match poll_transaction_confirmation(&self.rpc_client, signature).await {
             Ok(_) => (),
             Err(e) => {
-                println!(" jito {} confirmation failed: {:?}", trade_type, start_time.elapsed());
+                tracing::warn!(provider = "Jito", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmation failed");
                 return Err(e);
             },
         }
 
-        println!(" jito {} confirmed: {:?}", trade_type, start_time.elapsed());
+        tracing::info!(provider = "Jito", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmed");
 
         Ok(())
     }
 
     pub async fn send_transactions(&self, trade_type: TradeType, transactions: &Vec<VersionedTransaction>) -> Result<()> {
+        if transactions.len() > 1 && self.simulate_bundles.load(Ordering::Relaxed) {
+            let start_time = Instant::now();
+            match self.simulate_bundle(transactions).await {
+                Ok(simulation) if !simulation.succeeded => {
+                    for (i, result) in simulation.transaction_results.iter().enumerate() {
+                        if let Some(err) = &result.err {
+                            tracing::warn!(provider = "Jito", %trade_type, index = i, error = %err, "bundle transaction failed simulation");
+                        }
+                    }
+                    return Err(anyhow::anyhow!(
+                        "jito {} bundle simulation failed (tx {:?}): {:?}",
+                        trade_type,
+                        simulation.failed_signature,
+                        simulation.error
+                    ));
+                }
+                Ok(_) => tracing::debug!(provider = "Jito", %trade_type, elapsed = ?start_time.elapsed(), "bundle simulated"),
+                // Not every block engine serves simulateBundle, don't block the send on it
+                Err(e) => tracing::debug!(provider = "Jito", %trade_type, error = %e, "bundle simulation unavailable"),
+            }
+        }
+
         let start_time = Instant::now();
         let txs_base64 = transactions.iter().map(|tx| tx.to_base64_string()).collect::<Vec<String>>();
         let body = serde_json::json!({
@@ -134,33 +491,170 @@ impl JitoClient {
             "id": 1,
         });
 
-        let endpoint = if self.auth_token.is_empty() {
-            format!("{}/api/v1/bundles", self.endpoint)
-        } else {
-            format!("{}/api/v1/bundles?uuid={}", self.endpoint, self.auth_token)
-        };
-        let response = if self.auth_token.is_empty() {
-            self.http_client.post(&endpoint)
-        } else {
-            self.http_client.post(&endpoint)
-                .header("x-jito-auth", &self.auth_token)
-        };
-        let response_text = response
-            .body(body.to_string())
-            .header("Content-Type", "application/json")
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = self.post_with_failover("/api/v1/bundles", body.to_string()).await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
-                println!(" jito {} submitted: {:?}", trade_type, start_time.elapsed());
+                tracing::info!(provider = "Jito", %trade_type, elapsed = ?start_time.elapsed(), "transaction submitted");
             } else if let Some(_error) = response_json.get("error") {
-                eprintln!(" jito {} submission failed: {:?}", trade_type, _error);
+                tracing::warn!(provider = "Jito", %trade_type, error = ?_error, "transaction submission failed");
             }
         }
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Submit a bundle with `sendBundle` and return its bundle id
+    pub async fn send_bundle(&self, transactions: &[VersionedTransaction]) -> Result<String> {
+        let txs_base64 = transactions.iter().map(|tx| tx.to_base64_string()).collect::<Vec<String>>();
+        let body = json!({
+            "jsonrpc": "2.0",
+            "method": "sendBundle",
+            "params": [
+                txs_base64,
+                { "encoding": "base64" }
+            ],
+            "id": 1,
+        });
+
+        let response_text = self.post_with_failover("/api/v1/bundles", body.to_string()).await?;
+        let response_json: serde_json::Value = serde_json::from_str(&response_text)
+            .map_err(|_| anyhow::anyhow!("invalid sendBundle response: {}", response_text))?;
+        if let Some(error) = response_json.get("error") {
+            return Err(anyhow::anyhow!("sendBundle failed: {}", error));
+        }
+        response_json
+            .get("result")
+            .and_then(|r| r.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("invalid sendBundle response: {}", response_text))
+    }
+
+    /// Send a bundle and resubmit it with an escalating tip until it lands.
+    ///
+    /// `build` is called with the tip in SOL of each attempt and returns the signed bundle
+    /// paying that tip. Each attempt is given `config.attempt_timeout` to land, checked
+    /// against the first transaction of every attempt made so far, and is reported to
+    /// `on_attempt` once submitted. Returns the attempt that landed, or `TradeError::Timeout`
+    /// when none did within `config.max_attempts`.
+    ///
+    /// Earlier attempts can still land after a resubmission; sign every attempt against
+    /// the same durable nonce so at most one of them executes.
+    pub async fn send_bundle_with_retry<B, Fut, R>(
+        &self,
+        config: &BundleRetryConfig,
+        build: B,
+        on_attempt: R,
+    ) -> Result<BundleAttempt>
+    where
+        B: Fn(f64) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<VersionedTransaction>>>,
+        R: Fn(&BundleAttempt),
+    {
+        let mut attempts: Vec<BundleAttempt> = Vec::new();
+        for attempt in 0..config.max_attempts {
+            let tip = config.tip_for_attempt(attempt);
+            let transactions = build(tip).await?;
+            let signatures: Vec<Signature> =
+                transactions.iter().filter_map(|tx| tx.signatures.first().copied()).collect();
+            if signatures.is_empty() {
+                return Err(anyhow::anyhow!("bundle has no signed transactions"));
+            }
+            let (bundle_id, error) = match self.send_bundle(&transactions).await {
+                Ok(id) => (Some(id), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            let current = BundleAttempt { attempt, tip, bundle_id, signatures, error };
+            tracing::info!(
+                provider = "Jito",
+                attempt,
+                tip,
+                bundle_id = ?current.bundle_id,
+                error = ?current.error,
+                "bundle submitted"
+            );
+            on_attempt(&current);
+            attempts.push(current);
+
+            if let Some(landed) = self.wait_for_bundle(&attempts, config.attempt_timeout).await? {
+                tracing::info!(provider = "Jito", attempt = landed, "bundle landed");
+                return Ok(attempts.swap_remove(landed));
+            }
+        }
+        let message = format!("bundle did not land after {} attempts", attempts.len());
+        Err(TradeError::Timeout(message).into())
+    }
+
+    /// Index of the attempt whose bundle landed within `timeout`
+    async fn wait_for_bundle(
+        &self,
+        attempts: &[BundleAttempt],
+        timeout: Duration,
+    ) -> Result<Option<usize>> {
+        let first_signatures: Vec<Signature> = attempts.iter().map(|a| a.signatures[0]).collect();
+        let start = Instant::now();
+        loop {
+            let statuses =
+                self.rpc_client.get_signature_statuses(&first_signatures).await.inspect_err(|_| {
+                    crate::metrics::record_rpc_error("get_signature_statuses");
+                })?;
+            for (index, status) in statuses.value.into_iter().enumerate() {
+                let Some(status) = status else { continue };
+                if let Some(err) = status.err {
+                    return Err(TradeError::TransactionFailed(err).into());
+                }
+                if matches!(
+                    status.confirmation_status,
+                    Some(
+                        TransactionConfirmationStatus::Confirmed
+                            | TransactionConfirmationStatus::Finalized
+                    )
+                ) {
+                    return Ok(Some(index));
+                }
+            }
+            if start.elapsed() >= timeout {
+                return Ok(None);
+            }
+            tokio::time::sleep(Duration::from_millis(400)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_tip_schedule() {
+        let config = BundleRetryConfig {
+            initial_tip: 0.001,
+            tip_multiplier: 2.0,
+            max_tip: 0.005,
+            ..Default::default()
+        };
+        let tips: Vec<f64> = (0..5).map(|attempt| config.tip_for_attempt(attempt)).collect();
+        assert_eq!(tips, vec![0.001, 0.002, 0.004, 0.005, 0.005]);
+    }
+
+    #[tokio::test]
+    async fn test_probe_task_stops_with_the_client() {
+        // Nothing listens on these ports, the probes fail right away
+        let endpoints = vec!["http://127.0.0.1:1".to_string(), "http://127.0.0.1:2".to_string()];
+        let client =
+            JitoClient::new_with_failover("http://127.0.0.1:1".to_string(), endpoints, String::new())
+                .unwrap();
+        assert_eq!(client.endpoint_count(), 2);
+        let task = client.spawn_probe_task(Duration::from_millis(10));
+
+        let probed = async {
+            while !client.healthy_endpoints().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), probed).await.unwrap();
+
+        drop(client);
+        tokio::time::timeout(Duration::from_secs(5), task).await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,86 @@
+//! Confirmation latency per provider
+//!
+//! Every transaction a provider lands updates a moving average of its submission to
+//! confirmation latency, so latency critical paths can pick the provider that has been
+//! fastest lately (see `SolanaTrade::fastest_swqos_client`).
+
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::swqos::SwqosType;
+
+/// Weight of a new sample in the moving average
+const SMOOTHING: f64 = 0.2;
+
+static INSTANCE: OnceLock<Arc<SwqosLatency>> = OnceLock::new();
+
+/// Moving average confirmation latency of the providers
+#[derive(Debug, Default)]
+pub struct SwqosLatency {
+    latencies: Mutex<Vec<(SwqosType, Duration)>>,
+}
+
+impl SwqosLatency {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The latencies recorded by the SDK's submissions
+    pub fn get_instance() -> Arc<Self> {
+        INSTANCE.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+
+    /// Record that `provider` confirmed a transaction `latency` after submission
+    pub fn record(&self, provider: SwqosType, latency: Duration) {
+        let mut latencies = self.latencies.lock().unwrap();
+        match latencies.iter_mut().find(|(existing, _)| *existing == provider) {
+            Some((_, average)) => {
+                *average = average.mul_f64(1.0 - SMOOTHING) + latency.mul_f64(SMOOTHING)
+            }
+            None => latencies.push((provider, latency)),
+        }
+    }
+
+    /// Average latency of `provider`, `None` before it landed anything
+    pub fn latency(&self, provider: &SwqosType) -> Option<Duration> {
+        let latencies = self.latencies.lock().unwrap();
+        latencies.iter().find(|(existing, _)| existing == provider).map(|(_, latency)| *latency)
+    }
+
+    /// The provider of `providers` with the lowest average latency, `None` when none of them
+    /// has landed anything yet
+    pub fn fastest<'a>(
+        &self,
+        providers: impl IntoIterator<Item = &'a SwqosType>,
+    ) -> Option<&'a SwqosType> {
+        providers
+            .into_iter()
+            .filter_map(|provider| self.latency(provider).map(|latency| (provider, latency)))
+            .min_by_key(|(_, latency)| *latency)
+            .map(|(provider, _)| provider)
+    }
+
+    pub fn clear(&self) {
+        self.latencies.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fastest_provider() {
+        let latency = SwqosLatency::new();
+        latency.record(SwqosType::Jito, Duration::from_millis(500));
+        latency.record(SwqosType::Default, Duration::from_millis(300));
+        latency.record(SwqosType::Default, Duration::from_millis(800));
+        let average = latency.latency(&SwqosType::Default).unwrap();
+        assert!(average > Duration::from_millis(390) && average < Duration::from_millis(410));
+        assert_eq!(latency.latency(&SwqosType::NextBlock), None);
+
+        let providers = [SwqosType::NextBlock, SwqosType::Default, SwqosType::Jito];
+        assert_eq!(latency.fastest(&providers), Some(&SwqosType::Default));
+        assert_eq!(latency.fastest(&providers[..1]), None);
+    }
+}
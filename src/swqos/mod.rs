@@ -1,13 +1,28 @@
+pub mod attribution;
 pub mod common;
+pub mod http;
+pub mod latency;
 pub mod solana_rpc;
+#[cfg(feature = "jito")]
 pub mod jito;
+#[cfg(feature = "nextblock")]
 pub mod nextblock;
+#[cfg(feature = "zeroslot")]
 pub mod zeroslot;
+#[cfg(feature = "temporal")]
 pub mod temporal;
+#[cfg(feature = "bloxroute")]
 pub mod bloxroute;
+#[cfg(feature = "node1")]
 pub mod node1;
+#[cfg(feature = "flashblock")]
 pub mod flashblock;
+#[cfg(feature = "blockrazor")]
+pub mod blockrazor;
+#[cfg(all(feature = "grpc", any(feature = "jito", feature = "nextblock")))]
+pub mod grpc;
 
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use solana_sdk::{commitment_config::CommitmentConfig, transaction::VersionedTransaction};
@@ -16,33 +31,45 @@ use tokio::sync::RwLock;
 use anyhow::Result;
 
 use crate::{
-    common::SolanaRpcClient, 
+    common::SolanaRpcClient,
     constants::swqos::{
-        SWQOS_ENDPOINTS_BLOX, 
-        SWQOS_ENDPOINTS_JITO, 
-        SWQOS_ENDPOINTS_NEXTBLOCK, 
-        SWQOS_ENDPOINTS_TEMPORAL, 
-        SWQOS_ENDPOINTS_ZERO_SLOT, 
-        SWQOS_ENDPOINTS_NODE1, 
-        SWQOS_ENDPOINTS_FLASHBLOCK
-    }, 
-    swqos::{
-        bloxroute::BloxrouteClient, 
-        jito::JitoClient, 
-        nextblock::NextBlockClient, 
-        solana_rpc::SolRpcClient, 
-        temporal::TemporalClient, 
-        zeroslot::ZeroSlotClient, 
-        node1::Node1Client, 
-        flashblock::FlashBlockClient
-    }
+        SWQOS_ENDPOINTS_BLOX,
+        SWQOS_ENDPOINTS_JITO,
+        SWQOS_ENDPOINTS_NEXTBLOCK,
+        SWQOS_ENDPOINTS_TEMPORAL,
+        SWQOS_ENDPOINTS_ZERO_SLOT,
+        SWQOS_ENDPOINTS_NODE1,
+        SWQOS_ENDPOINTS_FLASHBLOCK,
+        SWQOS_ENDPOINTS_BLOCKRAZOR
+    },
+    swqos::solana_rpc::SolRpcClient,
 };
+#[cfg(feature = "bloxroute")]
+use crate::swqos::bloxroute::BloxrouteClient;
+#[cfg(feature = "jito")]
+use crate::swqos::jito::{JitoClient, JITO_PROBE_INTERVAL};
+#[cfg(feature = "nextblock")]
+use crate::swqos::nextblock::NextBlockClient;
+#[cfg(feature = "temporal")]
+use crate::swqos::temporal::TemporalClient;
+#[cfg(feature = "zeroslot")]
+use crate::swqos::zeroslot::ZeroSlotClient;
+#[cfg(feature = "node1")]
+use crate::swqos::node1::Node1Client;
+#[cfg(feature = "flashblock")]
+use crate::swqos::flashblock::FlashBlockClient;
+#[cfg(feature = "blockrazor")]
+use crate::swqos::blockrazor::BlockRazorClient;
+#[cfg(all(feature = "grpc", feature = "jito"))]
+use crate::swqos::grpc::JitoGrpcClient;
+#[cfg(all(feature = "grpc", feature = "nextblock"))]
+use crate::swqos::grpc::NextBlockGrpcClient;
 
 lazy_static::lazy_static! {
     static ref TIP_ACCOUNT_CACHE: RwLock<Vec<String>> = RwLock::new(Vec::new());
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum TradeType {
     Create,
     CreateAndBuy,
@@ -62,7 +89,7 @@ impl std::fmt::Display for TradeType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SwqosType {
     Jito,
     NextBlock,
@@ -71,6 +98,7 @@ pub enum SwqosType {
     Bloxroute,
     Node1,
     FlashBlock,
+    BlockRazor,
     Default,
 }
 
@@ -96,19 +124,82 @@ pub enum SwqosRegion {
     Default,
 }
 
+impl std::str::FromStr for SwqosRegion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().replace(['_', '-', ' '], "").as_str() {
+            "newyork" | "ny" => Ok(SwqosRegion::NewYork),
+            "frankfurt" => Ok(SwqosRegion::Frankfurt),
+            "amsterdam" => Ok(SwqosRegion::Amsterdam),
+            "slc" => Ok(SwqosRegion::SLC),
+            "tokyo" => Ok(SwqosRegion::Tokyo),
+            "london" => Ok(SwqosRegion::London),
+            "losangeles" | "la" => Ok(SwqosRegion::LosAngeles),
+            "default" => Ok(SwqosRegion::Default),
+            _ => Err(anyhow::anyhow!("Unknown swqos region: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SwqosConfig {
     Default(String),
+    #[cfg(feature = "jito")]
     Jito(String, SwqosRegion),
+    /// Jito with regional failover: auth token and block engine regions in priority order
+    #[cfg(feature = "jito")]
+    JitoFailover(String, Vec<SwqosRegion>),
+    /// Jito over gRPC: UUID and block engine region
+    #[cfg(all(feature = "grpc", feature = "jito"))]
+    JitoGrpc(String, SwqosRegion),
+    #[cfg(feature = "nextblock")]
     NextBlock(String, SwqosRegion),
+    /// NextBlock over gRPC: API token and region
+    #[cfg(all(feature = "grpc", feature = "nextblock"))]
+    NextBlockGrpc(String, SwqosRegion),
+    #[cfg(feature = "bloxroute")]
     Bloxroute(String, SwqosRegion),
+    #[cfg(feature = "temporal")]
     Temporal(String, SwqosRegion),
+    #[cfg(feature = "zeroslot")]
     ZeroSlot(String, SwqosRegion),
+    #[cfg(feature = "node1")]
     Node1(String, SwqosRegion),
+    #[cfg(feature = "flashblock")]
     FlashBlock(String, SwqosRegion),
+    #[cfg(feature = "blockrazor")]
+    BlockRazor(String, SwqosRegion),
 }
 
 impl SwqosConfig {
+    /// Provider the configuration submits through
+    pub fn swqos_type(&self) -> SwqosType {
+        match self {
+            SwqosConfig::Default(_) => SwqosType::Default,
+            #[cfg(feature = "jito")]
+            SwqosConfig::Jito(..) | SwqosConfig::JitoFailover(..) => SwqosType::Jito,
+            #[cfg(all(feature = "grpc", feature = "jito"))]
+            SwqosConfig::JitoGrpc(..) => SwqosType::Jito,
+            #[cfg(feature = "nextblock")]
+            SwqosConfig::NextBlock(..) => SwqosType::NextBlock,
+            #[cfg(all(feature = "grpc", feature = "nextblock"))]
+            SwqosConfig::NextBlockGrpc(..) => SwqosType::NextBlock,
+            #[cfg(feature = "bloxroute")]
+            SwqosConfig::Bloxroute(..) => SwqosType::Bloxroute,
+            #[cfg(feature = "temporal")]
+            SwqosConfig::Temporal(..) => SwqosType::Temporal,
+            #[cfg(feature = "zeroslot")]
+            SwqosConfig::ZeroSlot(..) => SwqosType::ZeroSlot,
+            #[cfg(feature = "node1")]
+            SwqosConfig::Node1(..) => SwqosType::Node1,
+            #[cfg(feature = "flashblock")]
+            SwqosConfig::FlashBlock(..) => SwqosType::FlashBlock,
+            #[cfg(feature = "blockrazor")]
+            SwqosConfig::BlockRazor(..) => SwqosType::BlockRazor,
+        }
+    }
+
     pub fn get_endpoint(swqos_type: SwqosType, region: SwqosRegion) -> String {
         match swqos_type {
             SwqosType::Jito => SWQOS_ENDPOINTS_JITO[region as usize].to_string(),
@@ -118,75 +209,196 @@ impl SwqosConfig {
             SwqosType::Bloxroute => SWQOS_ENDPOINTS_BLOX[region as usize].to_string(),
             SwqosType::Node1 => SWQOS_ENDPOINTS_NODE1[region as usize].to_string(),
             SwqosType::FlashBlock => SWQOS_ENDPOINTS_FLASHBLOCK[region as usize].to_string(),
+            SwqosType::BlockRazor => SWQOS_ENDPOINTS_BLOCKRAZOR[region as usize].to_string(),
             SwqosType::Default => "".to_string(),
         }
     }
 
-    pub fn get_swqos_client(rpc_url: String, commitment: CommitmentConfig, swqos_config: SwqosConfig) -> Arc<SwqosClient> {
-        match swqos_config {
+    /// Create the client of `swqos_config`, failing when its auth token or endpoint is invalid
+    pub fn get_swqos_client(rpc_url: String, commitment: CommitmentConfig, swqos_config: SwqosConfig) -> Result<Arc<SwqosClient>> {
+        let rpc = Arc::new(SolanaRpcClient::new_with_commitment(rpc_url, commitment));
+        SwqosConfig::get_swqos_client_with_rpc(rpc, commitment, swqos_config)
+    }
+
+    /// Like `get_swqos_client`, but confirmation polling goes through `rpc`, e.g. an `RpcPool` client
+    pub fn get_swqos_client_with_rpc(rpc: Arc<SolanaRpcClient>, commitment: CommitmentConfig, swqos_config: SwqosConfig) -> Result<Arc<SwqosClient>> {
+        SwqosConfig::get_swqos_client_with_http(rpc, commitment, swqos_config, None)
+    }
+
+    /// Like `get_swqos_client_with_rpc`; HTTP based providers submit through `http_client`
+    /// instead of a client of their own when it is set, see `swqos::http`
+    pub fn get_swqos_client_with_http(
+        rpc: Arc<SolanaRpcClient>,
+        commitment: CommitmentConfig,
+        swqos_config: SwqosConfig,
+        http_client: Option<reqwest::Client>,
+    ) -> Result<Arc<SwqosClient>> {
+        SwqosConfig::build_swqos_client(rpc, commitment, swqos_config, http_client, false)
+    }
+
+    /// Like `get_swqos_client_with_http`; Jito HTTP clients simulate multi-transaction
+    /// bundles before sending them when `jito_simulate_bundles` is set
+    #[cfg_attr(
+        not(any(
+            feature = "jito",
+            feature = "nextblock",
+            feature = "bloxroute",
+            feature = "temporal",
+            feature = "zeroslot",
+            feature = "node1",
+            feature = "flashblock",
+            feature = "blockrazor"
+        )),
+        allow(unused_variables)
+    )]
+    pub(crate) fn build_swqos_client(
+        rpc: Arc<SolanaRpcClient>,
+        commitment: CommitmentConfig,
+        swqos_config: SwqosConfig,
+        http_client: Option<reqwest::Client>,
+        jito_simulate_bundles: bool,
+    ) -> Result<Arc<SwqosClient>> {
+        #[cfg(not(feature = "jito"))]
+        let _ = jito_simulate_bundles;
+        let client: Arc<SwqosClient> = match swqos_config {
+            #[cfg(feature = "jito")]
             SwqosConfig::Jito(auth_token, region) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::Jito, region);
-                let jito_client = JitoClient::new(
-                    rpc_url.clone(),
-                    endpoint,
-                    auth_token
-                );
+                let jito_client =
+                    JitoClient::with_shared_clients(rpc, vec![endpoint], auth_token, http_client)?
+                        .with_simulate_bundles(jito_simulate_bundles);
                 Arc::new(jito_client)
             }
+            #[cfg(feature = "jito")]
+            SwqosConfig::JitoFailover(auth_token, regions) => {
+                let endpoints = regions
+                    .into_iter()
+                    .map(|region| SwqosConfig::get_endpoint(SwqosType::Jito, region))
+                    .collect();
+                let jito_client =
+                    JitoClient::with_shared_clients(rpc, endpoints, auth_token, http_client)?
+                        .with_simulate_bundles(jito_simulate_bundles);
+                // Keep the health of the fallback regions current between requests
+                if jito_client.endpoint_count() > 1 {
+                    jito_client.spawn_probe_task(JITO_PROBE_INTERVAL);
+                }
+                Arc::new(jito_client)
+            }
+            #[cfg(all(feature = "grpc", feature = "jito"))]
+            SwqosConfig::JitoGrpc(auth_token, region) => {
+                let endpoint = SwqosConfig::get_endpoint(SwqosType::Jito, region);
+                Arc::new(
+                    JitoGrpcClient::new(rpc, endpoint, &auth_token)
+                        .map_err(|e| anyhow::anyhow!("invalid Jito gRPC config: {}", e))?,
+                )
+            }
+            #[cfg(all(feature = "grpc", feature = "nextblock"))]
+            SwqosConfig::NextBlockGrpc(auth_token, region) => {
+                use crate::constants::swqos::SWQOS_GRPC_ENDPOINTS_NEXTBLOCK;
+                let endpoint = SWQOS_GRPC_ENDPOINTS_NEXTBLOCK[region as usize].to_string();
+                Arc::new(
+                    NextBlockGrpcClient::new(rpc, endpoint, &auth_token)
+                        .map_err(|e| anyhow::anyhow!("invalid NextBlock gRPC config: {}", e))?,
+                )
+            }
+            #[cfg(feature = "nextblock")]
             SwqosConfig::NextBlock(auth_token, region) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::NextBlock, region);
-                let nextblock_client = NextBlockClient::new(
-                    rpc_url.clone(),
+                let mut nextblock_client = NextBlockClient::new(
+                    rpc.url(),
                     endpoint.to_string(),
                     auth_token
                 );
+                nextblock_client.rpc_client = rpc;
+                if let Some(http_client) = http_client {
+                    nextblock_client.http_client = http_client;
+                }
                 Arc::new(nextblock_client)
             },
+            #[cfg(feature = "zeroslot")]
             SwqosConfig::ZeroSlot(auth_token, region) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::ZeroSlot, region);
-                let zeroslot_client = ZeroSlotClient::new(
-                    rpc_url.clone(),
+                let mut zeroslot_client = ZeroSlotClient::new(
+                    rpc.url(),
                     endpoint.to_string(),
                     auth_token
                 );
+                zeroslot_client.rpc_client = rpc;
+                if let Some(http_client) = http_client {
+                    zeroslot_client.http_client = http_client;
+                }
                 Arc::new(zeroslot_client)
             },
+            #[cfg(feature = "temporal")]
             SwqosConfig::Temporal(auth_token, region) => {  
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::Temporal, region);
-                let temporal_client = TemporalClient::new(
-                    rpc_url.clone(),
+                let mut temporal_client = TemporalClient::new(
+                    rpc.url(),
                     endpoint.to_string(),
                     auth_token
                 );
+                temporal_client.rpc_client = rpc;
+                if let Some(http_client) = http_client {
+                    temporal_client.http_client = http_client;
+                }
                 Arc::new(temporal_client)
             },
+            #[cfg(feature = "bloxroute")]
             SwqosConfig::Bloxroute(auth_token, region) => { 
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::Bloxroute, region);
-                let bloxroute_client = BloxrouteClient::new(
-                    rpc_url.clone(),
+                let mut bloxroute_client = BloxrouteClient::new(
+                    rpc.url(),
                     endpoint.to_string(),
                     auth_token
                 );
+                bloxroute_client.rpc_client = rpc;
+                if let Some(http_client) = http_client {
+                    bloxroute_client.http_client = http_client;
+                }
                 Arc::new(bloxroute_client)
             },
+            #[cfg(feature = "node1")]
             SwqosConfig::Node1(auth_token, region) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::Node1, region);
-                let node1_client = Node1Client::new(
-                    rpc_url.clone(),
+                let mut node1_client = Node1Client::new(
+                    rpc.url(),
                     endpoint.to_string(),
                     auth_token
                 );
+                node1_client.rpc_client = rpc;
+                if let Some(http_client) = http_client {
+                    node1_client = node1_client.with_http_client(http_client);
+                }
                 Arc::new(node1_client)
             },
+            #[cfg(feature = "flashblock")]
             SwqosConfig::FlashBlock(auth_token, region) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::FlashBlock, region);
-                let flashblock_client = FlashBlockClient::new(
-                    rpc_url.clone(),
+                let mut flashblock_client = FlashBlockClient::new(
+                    rpc.url(),
                     endpoint.to_string(),
                     auth_token
                 );
+                flashblock_client.rpc_client = rpc;
+                if let Some(http_client) = http_client {
+                    flashblock_client.http_client = http_client;
+                }
                 Arc::new(flashblock_client)
             },
+            #[cfg(feature = "blockrazor")]
+            SwqosConfig::BlockRazor(auth_token, region) => {
+                let endpoint = SwqosConfig::get_endpoint(SwqosType::BlockRazor, region);
+                let mut blockrazor_client = BlockRazorClient::new(
+                    rpc.url(),
+                    endpoint.to_string(),
+                    auth_token
+                );
+                blockrazor_client.rpc_client = rpc;
+                if let Some(http_client) = http_client {
+                    blockrazor_client.http_client = http_client;
+                }
+                Arc::new(blockrazor_client)
+            },
             SwqosConfig::Default(endpoint) => {
                 let rpc = SolanaRpcClient::new_with_commitment(
                     endpoint,
@@ -195,6 +407,7 @@ impl SwqosConfig {
                 let rpc_client = SolRpcClient::new(Arc::new(rpc));
                 Arc::new(rpc_client)
             }
-        }
+        };
+        Ok(client)
     }
 }
\ No newline at end of file
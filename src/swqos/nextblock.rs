@@ -33,7 +33,7 @@ impl SwqosClientTrait for NextBlockClient {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        let tip_account = *NEXTBLOCK_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| NEXTBLOCK_TIP_ACCOUNTS.first()).unwrap();
+        let tip_account = *NEXTBLOCK_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| NEXTBLOCK_TIP_ACCOUNTS.first()).ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
         Ok(tip_account.to_string())
     }
 
@@ -60,7 +60,7 @@ impl NextBlockClient {
     pub async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
         let start_time = Instant::now();
         let (content, signature) = serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
-        println!(" Transaction encoded to base64: {:?}", start_time.elapsed());
+        tracing::debug!(provider = "NextBlock", elapsed = ?start_time.elapsed(), "transaction encoded");
 
         let request_body = serde_json::to_string(&json!({
             "transaction": {
@@ -80,24 +80,24 @@ impl NextBlockClient {
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
-                println!(" nextblock {} submitted: {:?}", trade_type, start_time.elapsed());
+                tracing::info!(provider = "NextBlock", %trade_type, elapsed = ?start_time.elapsed(), "transaction submitted");
             } else if let Some(_error) = response_json.get("error") {
-                eprintln!(" nextblock {} submission failed: {:?}", trade_type, _error);
+                tracing::warn!(provider = "NextBlock", %trade_type, error = ?_error, "transaction submission failed");
             }
         } else {
-            eprintln!(" nextblock {} submission failed: {:?}", trade_type, response_text);
+            tracing::warn!(provider = "NextBlock", %trade_type, error = ?response_text, "transaction submission failed");
         }
 
         let start_time: Instant = Instant::now();
         match poll_transaction_confirmation(&self.rpc_client, signature).await {
             Ok(_) => (),
             Err(e) => {
-                println!(" nextblock {} confirmation failed: {:?}", trade_type, start_time.elapsed());
+                tracing::warn!(provider = "NextBlock", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmation failed");
                 return Err(e);
             },
         }
 
-        println!(" nextblock {} confirmed: {:?}", trade_type, start_time.elapsed());
+        tracing::info!(provider = "NextBlock", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmed");
 
         Ok(())
     }
@@ -14,8 +14,17 @@ use crate::swqos::SwqosClientTrait;
 
 use crate::{common::SolanaRpcClient, constants::swqos::NODE1_TIP_ACCOUNTS};
 
-use tokio::task::JoinHandle;
-use std::sync::atomic::{AtomicBool, Ordering};
+use crate::common::task_manager::TaskManager;
+use tokio::task::AbortHandle;
+
+/// Keep-alive ping task, stopped when the last client clone is dropped
+struct PingTask(AbortHandle);
+
+impl Drop for PingTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
 
 #[derive(Clone)]
 pub struct Node1Client {
@@ -23,8 +32,7 @@ pub struct Node1Client {
     pub auth_token: String,
     pub rpc_client: Arc<SolanaRpcClient>,
     pub http_client: Client,
-    pub ping_handle: Arc<tokio::sync::Mutex<Option<JoinHandle<()>>>>,
-    pub stop_ping: Arc<AtomicBool>,
+    _ping_task: Arc<PingTask>,
 }
 
 #[async_trait::async_trait]
@@ -38,7 +46,7 @@ impl SwqosClientTrait for Node1Client {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        let tip_account = *NODE1_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| NODE1_TIP_ACCOUNTS.first()).unwrap();
+        let tip_account = *NODE1_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| NODE1_TIP_ACCOUNTS.first()).ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
         Ok(tip_account.to_string())
     }
 
@@ -64,60 +72,45 @@ impl Node1Client {
             .build()
             .unwrap();
         
-        let client = Self { 
-            rpc_client: Arc::new(rpc_client), 
-            endpoint, 
-            auth_token, 
-            http_client,
-            ping_handle: Arc::new(tokio::sync::Mutex::new(None)),
-            stop_ping: Arc::new(AtomicBool::new(false)),
-        };
-        
         // Start ping task
-        let client_clone = client.clone();
-        tokio::spawn(async move {
-            client_clone.start_ping_task().await;
-        });
-        
-        client
+        let ping_task = Self::spawn_ping_task(http_client.clone(), endpoint.clone());
+
+        Self {
+            rpc_client: Arc::new(rpc_client),
+            endpoint,
+            auth_token,
+            http_client,
+            _ping_task: Arc::new(PingTask(ping_task)),
+        }
+    }
+
+    /// Submit and ping through `http_client`, e.g. one shared with the other providers
+    pub fn with_http_client(mut self, http_client: Client) -> Self {
+        let ping_task = Self::spawn_ping_task(http_client.clone(), self.endpoint.clone());
+        self._ping_task = Arc::new(PingTask(ping_task));
+        self.http_client = http_client;
+        self
     }
 
     /// Start periodic ping task to keep connections active
-    async fn start_ping_task(&self) {
-        let endpoint = self.endpoint.clone();
-        let auth_token = self.auth_token.clone();
-        let http_client = self.http_client.clone();
-        let stop_ping = self.stop_ping.clone();
-        
-        let handle = tokio::spawn(async move {
+    fn spawn_ping_task(http_client: Client, endpoint: String) -> AbortHandle {
+        let task = TaskManager::get_instance().spawn("node1_ping", async move {
             let mut interval = tokio::time::interval(Duration::from_secs(60)); // Ping every 60 seconds
-            
+
             loop {
                 interval.tick().await;
-                
-                if stop_ping.load(Ordering::Relaxed) {
-                    break;
-                }
-                
+
                 // Send ping request
-                if let Err(e) = Self::send_ping_request(&http_client, &endpoint, &auth_token).await {
-                    eprintln!("Node1 ping request failed: {}", e);
+                if let Err(e) = Self::send_ping_request(&http_client, &endpoint).await {
+                    tracing::warn!(provider = "Node1", error = %e, "ping request failed");
                 }
             }
         });
-        
-        // Update ping_handle - use Mutex to safely update
-        {
-            let mut ping_guard = self.ping_handle.lock().await;
-            if let Some(old_handle) = ping_guard.as_ref() {
-                old_handle.abort();
-            }
-            *ping_guard = Some(handle);
-        }
+        task.abort_handle()
     }
 
     /// Send ping request to /ping endpoint
-    async fn send_ping_request(http_client: &Client, endpoint: &str, _auth_token: &str) -> Result<()> {
+    async fn send_ping_request(http_client: &Client, endpoint: &str) -> Result<()> {
         // Build ping URL
         let ping_url = if endpoint.ends_with('/') {
             format!("{}ping", endpoint)
@@ -134,7 +127,7 @@ impl Node1Client {
             // ping successful, connection remains active
             // Can optionally log, but to reduce noise, not printing here
         } else {
-            eprintln!("Node1 ping request returned non-success status: {}", response.status());
+            tracing::warn!(provider = "Node1", status = %response.status(), "ping request returned non-success status");
         }
         
         Ok(())
@@ -143,7 +136,7 @@ impl Node1Client {
     pub async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
         let start_time = Instant::now();
         let (content, signature) = serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
-        println!(" Transaction encoded to base64: {:?}", start_time.elapsed());
+        tracing::debug!(provider = "Node1", elapsed = ?start_time.elapsed(), "transaction encoded");
 
         let request_body = serde_json::to_string(&json!({
             "jsonrpc": "2.0",
@@ -168,24 +161,24 @@ impl Node1Client {
         // Parse JSON response
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
-                println!(" node1 {} submitted: {:?}", trade_type, start_time.elapsed());
+                tracing::info!(provider = "Node1", %trade_type, elapsed = ?start_time.elapsed(), "transaction submitted");
             } else if let Some(_error) = response_json.get("error") {
-                eprintln!(" node1 {} submission failed: {:?}", trade_type, _error);
+                tracing::warn!(provider = "Node1", %trade_type, error = ?_error, "transaction submission failed");
             }
         } else {
-            eprintln!(" node1 {} submission failed: {:?}", trade_type, response_text);
+            tracing::warn!(provider = "Node1", %trade_type, error = ?response_text, "transaction submission failed");
         }
 
         let start_time: Instant = Instant::now();
         match poll_transaction_confirmation(&self.rpc_client, signature).await {
             Ok(_) => (),
             Err(e) => {
-                println!(" node1 {} confirmation failed: {:?}", trade_type, start_time.elapsed());
+                tracing::warn!(provider = "Node1", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmation failed");
                 return Err(e);
             },
         }
 
-        println!(" node1 {} confirmed: {:?}", trade_type, start_time.elapsed());
+        tracing::info!(provider = "Node1", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmed");
 
         Ok(())
     }
@@ -197,21 +190,3 @@ impl Node1Client {
         Ok(())
     }
 }
-
-impl Drop for Node1Client {
-    fn drop(&mut self) {
-        // Ensure ping task stops when client is destroyed
-        self.stop_ping.store(true, Ordering::Relaxed);
-        
-        // Try to stop ping task immediately
-        // Use tokio::spawn to avoid blocking Drop
-        let ping_handle = self.ping_handle.clone();
-        tokio::spawn(async move {
-            let mut ping_guard = ping_handle.lock().await;
-            if let Some(handle) = ping_guard.as_ref() {
-                handle.abort();
-            }
-            *ping_guard = None;
-        });
-    }
-}
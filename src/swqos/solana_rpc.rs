@@ -41,12 +41,11 @@ impl SwqosClientTrait for SolRpcClient {
         match poll_transaction_confirmation(&self.rpc_client, signature).await {
             Ok(_) => (),
             Err(e) => {
-                println!(" rpc{} confirmation failed: {:?}", trade_type, start_time.elapsed());
+                tracing::warn!(provider = "Default", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmation failed");
                 return Err(e);
             }
         }
-        println!(" signature: {:?}", signature);
-        println!(" rpc{} confirmed: {:?}", trade_type, start_time.elapsed());
+        tracing::info!(provider = "Default", %trade_type, %signature, elapsed = ?start_time.elapsed(), "transaction confirmed");
 
         Ok(())
     }
@@ -34,7 +34,7 @@ impl SwqosClientTrait for ZeroSlotClient {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        let tip_account = *ZEROSLOT_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| ZEROSLOT_TIP_ACCOUNTS.first()).unwrap();
+        let tip_account = *ZEROSLOT_TIP_ACCOUNTS.choose(&mut rand::rng()).or_else(|| ZEROSLOT_TIP_ACCOUNTS.first()).ok_or_else(|| anyhow::anyhow!("no valid tip accounts found"))?;
         Ok(tip_account.to_string())
     }
 
@@ -61,7 +61,7 @@ impl ZeroSlotClient {
     pub async fn send_transaction(&self, trade_type: TradeType, transaction: &VersionedTransaction) -> Result<()> {
         let start_time = Instant::now();
         let (content, signature) = serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
-        println!(" Transaction encoded to base64: {:?}", start_time.elapsed());
+        tracing::debug!(provider = "ZeroSlot", elapsed = ?start_time.elapsed(), "transaction encoded");
 
         let request_body = serde_json::to_string(&json!({
             "jsonrpc": "2.0",
@@ -90,24 +90,24 @@ impl ZeroSlotClient {
         // 5. Use `serde_json::from_str()` to parse JSON, reducing extra wait from `.json().await?`
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
-                println!(" 0slot {} submitted: {:?}", trade_type, start_time.elapsed());
+                tracing::info!(provider = "ZeroSlot", %trade_type, elapsed = ?start_time.elapsed(), "transaction submitted");
             } else if let Some(_error) = response_json.get("error") {
-                eprintln!(" 0slot {} submission failed: {:?}", trade_type, _error);
+                tracing::warn!(provider = "ZeroSlot", %trade_type, error = ?_error, "transaction submission failed");
             }
         } else {
-            eprintln!(" 0slot {} submission failed: {:?}", trade_type, response_text);
+            tracing::warn!(provider = "ZeroSlot", %trade_type, error = ?response_text, "transaction submission failed");
         }
 
         let start_time: Instant = Instant::now();
         match poll_transaction_confirmation(&self.rpc_client, signature).await {
             Ok(_) => (),
             Err(e) => {
-                println!(" 0slot {} confirmation failed: {:?}", trade_type, start_time.elapsed());
+                tracing::warn!(provider = "ZeroSlot", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmation failed");
                 return Err(e);
             },
         }
 
-        println!(" 0slot {} confirmed: {:?}", trade_type, start_time.elapsed());
+        tracing::info!(provider = "ZeroSlot", %trade_type, elapsed = ?start_time.elapsed(), "transaction confirmed");
 
         Ok(())
     }
@@ -0,0 +1,310 @@
+//! Test doubles for unit testing strategies and trade execution without a network
+//!
+//! `MockRpc` builds a regular `SolanaRpcClient` whose transport answers from memory, so
+//! everything taking the RPC client works unchanged. `MockSwqos` stands in for a swqos
+//! provider and records what it was sent. Both can inject failures and latency. Enabled by
+//! the `testing` feature:
+//!
+//! ```ignore
+//! let rpc = MockRpc::new()
+//!     .with_account(bonding_curve, account)
+//!     .with_latency(Duration::from_millis(50));
+//! let jito = Arc::new(MockSwqos::new(SwqosType::Jito).with_failure("bundle dropped"));
+//! let client = rpc.client();
+//! // ... run the code under test with `client` and `jito`
+//! assert_eq!(rpc.request_count(RpcRequest::GetLatestBlockhash), 1);
+//! assert!(jito.sent().is_empty());
+//! ```
+//!
+//! For end-to-end tests against real programs, `validator::LocalValidator` runs a local
+//! `solana-test-validator`.
+
+#[cfg(feature = "grpc")]
+pub mod parser_fixtures;
+pub mod validator;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use solana_account_decoder::{encode_ui_account, UiAccountEncoding};
+use solana_client::{
+    client_error::{ClientErrorKind, Result as ClientResult},
+    rpc_client::RpcClientConfig,
+    rpc_request::RpcRequest,
+    rpc_response::{Response, RpcResponseContext},
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use solana_rpc_client::mock_sender::MockSender;
+use solana_sdk::{
+    account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::common::SolanaRpcClient;
+use crate::swqos::{SwqosClientTrait, SwqosType, TradeType};
+
+#[derive(Default)]
+struct MockRpcState {
+    responses: HashMap<RpcRequest, Value>,
+    errors: HashMap<RpcRequest, String>,
+    accounts: HashMap<Pubkey, Account>,
+    latency: Duration,
+    requests: Vec<RpcRequest>,
+}
+
+/// In-memory RPC backend
+///
+/// `getAccountInfo` and `getMultipleAccounts` are served from the accounts added with
+/// `with_account`, unknown accounts do not exist. Other requests return the response set
+/// with `with_response`, or else a synthetic default (e.g. a fixed blockhash, successful
+/// `sendTransaction` and confirmed signature statuses). Clones share their state, so
+/// failures can be toggled while the code under test runs.
+#[derive(Clone, Default)]
+pub struct MockRpc {
+    state: Arc<Mutex<MockRpcState>>,
+}
+
+impl MockRpc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer `request` with `result`, the content of the JSON-RPC `result` field
+    pub fn with_response(self, request: RpcRequest, result: Value) -> Self {
+        self.state.lock().unwrap().responses.insert(request, result);
+        self
+    }
+
+    /// Fail every `request` with `message`
+    pub fn with_error(self, request: RpcRequest, message: impl Into<String>) -> Self {
+        self.fail(request, message);
+        self
+    }
+
+    pub fn with_account(self, pubkey: Pubkey, account: Account) -> Self {
+        self.set_account(pubkey, account);
+        self
+    }
+
+    /// Delay every response by `latency`
+    pub fn with_latency(self, latency: Duration) -> Self {
+        self.state.lock().unwrap().latency = latency;
+        self
+    }
+
+    /// Start failing `request` with `message`
+    pub fn fail(&self, request: RpcRequest, message: impl Into<String>) {
+        self.state.lock().unwrap().errors.insert(request, message.into());
+    }
+
+    /// Stop failing `request`
+    pub fn recover(&self, request: RpcRequest) {
+        self.state.lock().unwrap().errors.remove(&request);
+    }
+
+    pub fn set_account(&self, pubkey: Pubkey, account: Account) {
+        self.state.lock().unwrap().accounts.insert(pubkey, account);
+    }
+
+    /// Requests received so far, in order
+    pub fn requests(&self) -> Vec<RpcRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn request_count(&self, request: RpcRequest) -> usize {
+        self.state.lock().unwrap().requests.iter().filter(|r| **r == request).count()
+    }
+
+    /// An RPC client backed by this mock
+    pub fn client(&self) -> Arc<SolanaRpcClient> {
+        let sender = MockRpcSender { rpc: self.clone(), fallback: MockSender::new("succeeds") };
+        let config = RpcClientConfig::with_commitment(CommitmentConfig::confirmed());
+        Arc::new(SolanaRpcClient::new_sender(sender, config))
+    }
+
+    fn respond(&self, request: RpcRequest, params: &Value) -> Option<ClientResult<Value>> {
+        let state = self.state.lock().unwrap();
+        if let Some(message) = state.errors.get(&request) {
+            return Some(Err(ClientErrorKind::Custom(message.clone()).into()));
+        }
+        if let Some(result) = state.responses.get(&request) {
+            return Some(Ok(result.clone()));
+        }
+        let account = |key: &Value| {
+            let pubkey = Pubkey::from_str(key.as_str()?).ok()?;
+            let account = state.accounts.get(&pubkey)?;
+            Some(encode_ui_account(&pubkey, account, UiAccountEncoding::Base64, None, None))
+        };
+        let value = match request {
+            RpcRequest::GetAccountInfo => serde_json::to_value(account(&params[0])),
+            RpcRequest::GetMultipleAccounts => serde_json::to_value(
+                params[0].as_array().into_iter().flatten().map(account).collect::<Vec<_>>(),
+            ),
+            _ => return None,
+        };
+        let context = RpcResponseContext { slot: 1, api_version: None };
+        Some(
+            value
+                .and_then(|value| serde_json::to_value(Response { context, value }))
+                .map_err(|e| ClientErrorKind::SerdeJson(e).into()),
+        )
+    }
+}
+
+struct MockRpcSender {
+    rpc: MockRpc,
+    fallback: MockSender,
+}
+
+#[async_trait::async_trait]
+impl RpcSender for MockRpcSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let latency = {
+            let mut state = self.rpc.state.lock().unwrap();
+            state.requests.push(request);
+            state.latency
+        };
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        match self.rpc.respond(request, &params) {
+            Some(result) => result,
+            None => self.fallback.send(request, params).await,
+        }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "mock".to_string()
+    }
+}
+
+/// Swqos provider that records the transactions it is sent instead of submitting them
+///
+/// Clones share the recorded transactions and the failure, so the mock can be inspected
+/// after handing an `Arc` of it to the code under test.
+#[derive(Clone)]
+pub struct MockSwqos {
+    swqos_type: SwqosType,
+    tip_account: Pubkey,
+    latency: Duration,
+    failure: Arc<Mutex<Option<String>>>,
+    sent: Arc<Mutex<Vec<VersionedTransaction>>>,
+}
+
+impl MockSwqos {
+    pub fn new(swqos_type: SwqosType) -> Self {
+        Self {
+            swqos_type,
+            tip_account: Pubkey::new_unique(),
+            latency: Duration::ZERO,
+            failure: Arc::new(Mutex::new(None)),
+            sent: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn with_tip_account(mut self, tip_account: Pubkey) -> Self {
+        self.tip_account = tip_account;
+        self
+    }
+
+    /// Delay every submission by `latency`
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Fail every submission with `message`
+    pub fn with_failure(self, message: impl Into<String>) -> Self {
+        self.set_failure(Some(message.into()));
+        self
+    }
+
+    /// Start failing submissions with `message`, or stop failing with `None`
+    pub fn set_failure(&self, message: Option<String>) {
+        *self.failure.lock().unwrap() = message;
+    }
+
+    /// Transactions accepted so far, in order
+    pub fn sent(&self) -> Vec<VersionedTransaction> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    async fn submit(&self, transactions: &[VersionedTransaction]) -> Result<()> {
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+        if let Some(message) = self.failure.lock().unwrap().clone() {
+            return Err(anyhow!(message));
+        }
+        self.sent.lock().unwrap().extend_from_slice(transactions);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for MockSwqos {
+    async fn send_transaction(
+        &self,
+        _trade_type: TradeType,
+        transaction: &VersionedTransaction,
+    ) -> Result<()> {
+        self.submit(std::slice::from_ref(transaction)).await
+    }
+
+    async fn send_transactions(
+        &self,
+        _trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+    ) -> Result<()> {
+        self.submit(transactions).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        Ok(self.tip_account.to_string())
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        self.swqos_type.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_rpc_and_swqos() {
+        let pubkey = Pubkey::new_unique();
+        let account = Account { lamports: 42, data: vec![1, 2, 3], ..Default::default() };
+        let rpc = MockRpc::new()
+            .with_account(pubkey, account.clone())
+            .with_error(RpcRequest::GetSlot, "node is behind");
+        let client = rpc.client();
+
+        assert_eq!(client.get_account(&pubkey).await.unwrap(), account);
+        assert!(client.get_account(&Pubkey::new_unique()).await.is_err());
+        let accounts = client.get_multiple_accounts(&[Pubkey::new_unique(), pubkey]).await;
+        assert_eq!(accounts.unwrap(), vec![None, Some(account)]);
+        assert!(client.get_slot().await.is_err());
+        rpc.recover(RpcRequest::GetSlot);
+        assert!(client.get_slot().await.is_ok());
+        assert!(client.get_latest_blockhash().await.is_ok());
+        assert_eq!(rpc.request_count(RpcRequest::GetSlot), 2);
+
+        let swqos = MockSwqos::new(SwqosType::Jito).with_failure("bundle dropped");
+        let transaction = VersionedTransaction::default();
+        assert!(swqos.send_transaction(TradeType::Buy, &transaction).await.is_err());
+        swqos.set_failure(None);
+        swqos.send_transaction(TradeType::Buy, &transaction).await.unwrap();
+        assert_eq!(swqos.sent(), vec![transaction]);
+    }
+}
@@ -0,0 +1,249 @@
+//! Local `solana-test-validator` for end-to-end tests
+//!
+//! Starts a validator with the PumpFun and PumpSwap programs and their global accounts
+//! cloned from a live cluster, then funds fresh keypairs through its faucet, so buy and sell
+//! transactions can be built and executed for real. Needs the Solana CLI on `PATH` and
+//! access to the clone source when the validator starts.
+//!
+//! ```ignore
+//! let validator = LocalValidator::start(LocalValidatorConfig::default()).await?;
+//! let payer = validator.payer();
+//! let config = TradeConfigBuilder::new(validator.rpc_url()).build()?;
+//! let client = SolanaTrade::new(payer, config).await?;
+//! ```
+
+use anyhow::{anyhow, Result};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::process::{Child, Command};
+
+use crate::common::SolanaRpcClient;
+
+/// Cluster programs and accounts are cloned from by default
+pub const DEFAULT_CLONE_URL: &str = "https://api.mainnet-beta.solana.com";
+
+#[derive(Debug, Clone)]
+pub struct LocalValidatorConfig {
+    /// Cluster the programs and accounts are cloned from
+    pub clone_url: String,
+    /// Upgradeable programs to clone
+    pub programs: Vec<Pubkey>,
+    /// Accounts to clone
+    pub accounts: Vec<Pubkey>,
+    pub rpc_port: u16,
+    /// Number of keypairs funded at startup
+    pub funded_keypairs: usize,
+    /// Lamports airdropped to each funded keypair
+    pub funding_lamports: u64,
+    pub startup_timeout: Duration,
+    /// Ledger directory; a fresh one under the system temp dir, removed on drop, when `None`
+    pub ledger_dir: Option<PathBuf>,
+}
+
+impl Default for LocalValidatorConfig {
+    /// Clones the programs and global accounts of the enabled protocols
+    fn default() -> Self {
+        #[allow(unused_mut)]
+        let mut programs = vec![crate::constants::pumpfun::accounts::MPL_TOKEN_METADATA];
+        #[allow(unused_mut)]
+        let mut accounts = Vec::new();
+        #[cfg(feature = "pumpfun")]
+        {
+            use crate::constants::pumpfun::{
+                accounts::{FEE_PROGRAM, PUMPFUN},
+                global_constants::GLOBAL_ACCOUNT,
+            };
+            use crate::trading::pumpfun::common::{
+                get_fee_config_pda, get_global_volume_accumulator_pda,
+            };
+            programs.extend([PUMPFUN, FEE_PROGRAM]);
+            accounts.push(GLOBAL_ACCOUNT);
+            accounts.extend(get_global_volume_accumulator_pda());
+            accounts.extend(get_fee_config_pda());
+        }
+        #[cfg(feature = "pumpswap")]
+        {
+            use crate::constants::pumpswap::accounts::{AMM_PROGRAM, GLOBAL_ACCOUNT};
+            use crate::trading::pumpswap::common::get_global_volume_accumulator_pda;
+            programs.push(AMM_PROGRAM);
+            accounts.push(GLOBAL_ACCOUNT);
+            accounts.extend(get_global_volume_accumulator_pda());
+        }
+        Self {
+            clone_url: DEFAULT_CLONE_URL.to_string(),
+            programs,
+            accounts,
+            rpc_port: 8899,
+            funded_keypairs: 2,
+            funding_lamports: 10_000_000_000,
+            startup_timeout: Duration::from_secs(60),
+            ledger_dir: None,
+        }
+    }
+}
+
+impl LocalValidatorConfig {
+    /// Also clone the upgradeable program `program`
+    pub fn with_program(mut self, program: Pubkey) -> Self {
+        self.programs.push(program);
+        self
+    }
+
+    /// Also clone `account`, e.g. the bonding curve or pool of a token under test
+    pub fn with_account(mut self, account: Pubkey) -> Self {
+        self.accounts.push(account);
+        self
+    }
+
+    fn args(&self, ledger_dir: &Path) -> Vec<String> {
+        let mut args = vec![
+            "--reset".to_string(),
+            "--quiet".to_string(),
+            "--ledger".to_string(),
+            ledger_dir.display().to_string(),
+            "--rpc-port".to_string(),
+            self.rpc_port.to_string(),
+        ];
+        if !self.programs.is_empty() || !self.accounts.is_empty() {
+            args.extend(["--url".to_string(), self.clone_url.clone()]);
+        }
+        for program in &self.programs {
+            args.extend(["--clone-upgradeable-program".to_string(), program.to_string()]);
+        }
+        for account in &self.accounts {
+            args.extend(["--clone".to_string(), account.to_string()]);
+        }
+        args
+    }
+}
+
+/// A running `solana-test-validator`, stopped when dropped
+pub struct LocalValidator {
+    process: Child,
+    rpc_url: String,
+    rpc: Arc<SolanaRpcClient>,
+    keypairs: Vec<Arc<Keypair>>,
+    ledger_dir: PathBuf,
+    remove_ledger: bool,
+}
+
+impl LocalValidator {
+    /// Start the validator, wait until it serves RPC and fund the configured keypairs
+    pub async fn start(config: LocalValidatorConfig) -> Result<Self> {
+        let remove_ledger = config.ledger_dir.is_none();
+        let ledger_dir = config.ledger_dir.clone().unwrap_or_else(|| {
+            std::env::temp_dir().join(format!(
+                "sol-trade-sdk-validator-{}-{}",
+                std::process::id(),
+                config.rpc_port
+            ))
+        });
+        let process = Command::new("solana-test-validator")
+            .args(config.args(&ledger_dir))
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow!("Failed to start solana-test-validator: {}", e))?;
+        let rpc_url = format!("http://127.0.0.1:{}", config.rpc_port);
+        let rpc = Arc::new(SolanaRpcClient::new(rpc_url.clone()));
+        let mut validator =
+            Self { process, rpc_url, rpc, keypairs: Vec::new(), ledger_dir, remove_ledger };
+
+        validator.wait_until_ready(config.startup_timeout).await?;
+        for _ in 0..config.funded_keypairs {
+            let keypair = Arc::new(Keypair::new());
+            validator.fund(&keypair.pubkey(), config.funding_lamports).await?;
+            validator.keypairs.push(keypair);
+        }
+        Ok(validator)
+    }
+
+    async fn wait_until_ready(&mut self, timeout: Duration) -> Result<()> {
+        let start = Instant::now();
+        loop {
+            if let Some(status) = self.process.try_wait()? {
+                return Err(anyhow!("solana-test-validator exited during startup: {}", status));
+            }
+            if self.rpc.get_health().await.is_ok() {
+                return Ok(());
+            }
+            if start.elapsed() > timeout {
+                return Err(anyhow!("solana-test-validator not ready after {:?}", timeout));
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    pub fn rpc(&self) -> Arc<SolanaRpcClient> {
+        self.rpc.clone()
+    }
+
+    /// Keypairs funded at startup
+    pub fn keypairs(&self) -> &[Arc<Keypair>] {
+        &self.keypairs
+    }
+
+    /// The first funded keypair
+    pub fn payer(&self) -> Arc<Keypair> {
+        self.keypairs.first().cloned().expect("no funded keypairs configured")
+    }
+
+    /// Airdrop `lamports` to `pubkey` and wait for the airdrop to confirm
+    pub async fn fund(&self, pubkey: &Pubkey, lamports: u64) -> Result<()> {
+        let signature = self.rpc.request_airdrop(pubkey, lamports).await?;
+        let start = Instant::now();
+        while !self.rpc.confirm_transaction(&signature).await? {
+            if start.elapsed() > Duration::from_secs(30) {
+                return Err(anyhow!("Airdrop to {} not confirmed", pubkey));
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for LocalValidator {
+    fn drop(&mut self) {
+        let _ = self.process.start_kill();
+        if self.remove_ledger {
+            let _ = std::fs::remove_dir_all(&self.ledger_dir);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validator_args() {
+        let mint = Pubkey::new_unique();
+        let config = LocalValidatorConfig::default().with_account(mint);
+        let args = config.args(Path::new("/tmp/ledger"));
+        assert_eq!(args[..4], ["--reset", "--quiet", "--ledger", "/tmp/ledger"]);
+        let position = |flag: &str, value: &Pubkey| {
+            args.windows(2).position(|pair| pair == [flag.to_string(), value.to_string()])
+        };
+        assert!(position("--clone", &mint).is_some());
+        assert!(position(
+            "--clone-upgradeable-program",
+            &crate::constants::pumpfun::accounts::PUMPFUN
+        )
+        .is_some());
+        let url = args.iter().position(|arg| arg == "--url").unwrap();
+        assert_eq!(args[url + 1], DEFAULT_CLONE_URL);
+    }
+}
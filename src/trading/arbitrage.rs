@@ -0,0 +1,662 @@
+//! PumpSwap / Raydium price divergence watcher
+//!
+//! `ArbitrageWatcher` keeps the latest reserves of every SOL quoted pool of a token on
+//! PumpSwap, Raydium CPMM and Raydium AMM V4. Each update compares the venues; when buying
+//! on the cheaper pool and selling on the dearer one clears the configured spread and
+//! profit after fees and price impact, an `ArbitrageOpportunity` is passed to the callback.
+//!
+//! PumpSwap trade events carry the pool reserves, so PumpSwap quotes follow the event
+//! stream directly. Raydium swap events do not, the Raydium quotes are refreshed over RPC
+//! when a swap on the pool is seen (see `streaming::watch_arbitrage`).
+//!
+//! `SolanaTrade::execute_arbitrage` sends both legs as one Jito bundle, so either both land
+//! or neither does.
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{hash::Hash, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use solana_streamer_sdk::streaming::event_parser::protocols::pumpswap::{
+    PumpSwapBuyEvent, PumpSwapSellEvent,
+};
+
+use crate::{
+    common::{SolanaRpcClient, TradeError, TradeResult},
+    constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT,
+    swqos::{SwqosType, TradeType},
+    trading::{
+        common::{
+            build_rpc_transaction, build_tip_transaction, get_multi_token_balances,
+            is_using_nonce,
+        },
+        core::{
+            executor::MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT,
+            params::{PumpSwapParams, RaydiumAmmV4Params, RaydiumCpmmParams},
+            traits::ProtocolParams,
+        },
+        factory::DexType,
+        raydium_amm_v4::common::fetch_amm_info,
+        raydium_cpmm::common::{fetch_pool_state, get_pool_token_balances},
+        BuyParams, SellParams, TradeFactory,
+    },
+    utils::calc::{
+        common::calculate_with_slippage_sell,
+        pumpswap::{buy_quote_input_internal, sell_base_input_internal},
+        raydium_amm_v4, raydium_cpmm,
+    },
+    SolanaTrade,
+};
+
+/// Venue a quote comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Venue {
+    PumpSwap,
+    RaydiumCpmm,
+    RaydiumAmmV4,
+}
+
+impl Venue {
+    pub fn dex_type(&self) -> DexType {
+        match self {
+            Venue::PumpSwap => DexType::PumpSwap,
+            Venue::RaydiumCpmm => DexType::RaydiumCpmm,
+            Venue::RaydiumAmmV4 => DexType::RaydiumAmmV4,
+        }
+    }
+}
+
+/// Reserves of one SOL quoted pool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueQuote {
+    pub venue: Venue,
+    pub pool: Pubkey,
+    /// Token reserve in base units
+    pub token_reserve: u64,
+    /// SOL reserve in lamports
+    pub sol_reserve: u64,
+    /// PumpSwap coin creator, affects the fee; default for Raydium pools
+    pub coin_creator: Pubkey,
+    #[serde(skip, default = "Instant::now")]
+    pub updated_at: Instant,
+}
+
+impl VenueQuote {
+    /// Marginal price in lamports per token base unit
+    pub fn price(&self) -> f64 {
+        if self.token_reserve == 0 {
+            return 0.0;
+        }
+        self.sol_reserve as f64 / self.token_reserve as f64
+    }
+
+    /// Tokens received for `sol_in` lamports, after fees
+    ///
+    /// `None` when the pool is empty or cannot be quoted for `sol_in`.
+    pub fn buy_out(&self, sol_in: u64) -> Option<u64> {
+        if self.token_reserve == 0 || self.sol_reserve == 0 {
+            return None;
+        }
+        if sol_in == 0 {
+            return Some(0);
+        }
+        match self.venue {
+            Venue::PumpSwap => buy_quote_input_internal(
+                sol_in,
+                0,
+                self.token_reserve,
+                self.sol_reserve,
+                &self.coin_creator,
+            )
+            .map(|result| result.base)
+            .ok(),
+            Venue::RaydiumCpmm => Some(
+                raydium_cpmm::compute_swap_amount(
+                    self.token_reserve,
+                    self.sol_reserve,
+                    false,
+                    sol_in,
+                    0,
+                )
+                .amount_out,
+            ),
+            Venue::RaydiumAmmV4 => Some(
+                raydium_amm_v4::compute_swap_amount(
+                    self.token_reserve,
+                    self.sol_reserve,
+                    false,
+                    sol_in,
+                    0,
+                )
+                .amount_out,
+            ),
+        }
+    }
+
+    /// Lamports received for `token_in` tokens, after fees
+    ///
+    /// `None` when the pool is empty or cannot be quoted for `token_in`.
+    pub fn sell_out(&self, token_in: u64) -> Option<u64> {
+        if self.token_reserve == 0 || self.sol_reserve == 0 {
+            return None;
+        }
+        if token_in == 0 {
+            return Some(0);
+        }
+        match self.venue {
+            Venue::PumpSwap => sell_base_input_internal(
+                token_in,
+                0,
+                self.token_reserve,
+                self.sol_reserve,
+                &self.coin_creator,
+            )
+            .map(|result| result.ui_quote)
+            .ok(),
+            Venue::RaydiumCpmm => Some(
+                raydium_cpmm::compute_swap_amount(
+                    self.token_reserve,
+                    self.sol_reserve,
+                    true,
+                    token_in,
+                    0,
+                )
+                .amount_out,
+            ),
+            Venue::RaydiumAmmV4 => Some(
+                raydium_amm_v4::compute_swap_amount(
+                    self.token_reserve,
+                    self.sol_reserve,
+                    true,
+                    token_in,
+                    0,
+                )
+                .amount_out,
+            ),
+        }
+    }
+}
+
+/// Thresholds and sizing limits of an `ArbitrageWatcher`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbitrageConfig {
+    /// Minimum divergence of the marginal prices, in basis points
+    pub min_spread_bps: u64,
+    /// Minimum expected profit in lamports, after `tx_cost_lamports`
+    pub min_profit_lamports: u64,
+    /// Largest buy leg in lamports
+    pub max_input_lamports: u64,
+    /// Fees and tip of executing both legs, subtracted from the profit
+    pub tx_cost_lamports: u64,
+    /// Quotes older than this are ignored
+    pub max_quote_age: Duration,
+}
+
+impl Default for ArbitrageConfig {
+    fn default() -> Self {
+        Self {
+            min_spread_bps: 50,
+            min_profit_lamports: 1_000_000,
+            max_input_lamports: 1_000_000_000,
+            tx_cost_lamports: 200_000,
+            max_quote_age: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Buy on `buy`, sell the tokens on `sell`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArbitrageOpportunity {
+    pub mint: Pubkey,
+    pub buy: VenueQuote,
+    pub sell: VenueQuote,
+    /// Marginal price divergence in basis points
+    pub spread_bps: u64,
+    /// Lamports spent on the buy leg
+    pub sol_in: u64,
+    /// Tokens bought and sold
+    pub token_amount: u64,
+    /// Lamports received from the sell leg
+    pub sol_out: u64,
+    /// `sol_out - sol_in - tx_cost_lamports`
+    pub expected_profit: i64,
+}
+
+type OpportunityCallback = Arc<dyn Fn(ArbitrageOpportunity) + Send + Sync>;
+
+/// Tracks pool reserves per token and reports cross-venue opportunities
+pub struct ArbitrageWatcher {
+    config: ArbitrageConfig,
+    callback: OpportunityCallback,
+    /// mint -> pool -> quote
+    quotes: Mutex<HashMap<Pubkey, HashMap<Pubkey, VenueQuote>>>,
+    /// pool -> mint, for swap events that don't name the mint
+    pool_mints: Mutex<HashMap<Pubkey, Pubkey>>,
+}
+
+impl ArbitrageWatcher {
+    pub fn new<F>(config: ArbitrageConfig, callback: F) -> Self
+    where
+        F: Fn(ArbitrageOpportunity) + Send + Sync + 'static,
+    {
+        Self {
+            config,
+            callback: Arc::new(callback),
+            quotes: Mutex::new(HashMap::new()),
+            pool_mints: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &ArbitrageConfig {
+        &self.config
+    }
+
+    /// Store a quote and report the opportunity it opens, if any
+    pub fn update_quote(&self, mint: Pubkey, quote: VenueQuote) -> Option<ArbitrageOpportunity> {
+        self.pool_mints.lock().unwrap().insert(quote.pool, mint);
+        self.quotes.lock().unwrap().entry(mint).or_default().insert(quote.pool, quote);
+        let opportunity = self.evaluate(&mint)?;
+        (self.callback)(opportunity.clone());
+        Some(opportunity)
+    }
+
+    /// Token traded on a tracked pool
+    pub fn pool_mint(&self, pool: &Pubkey) -> Option<Pubkey> {
+        self.pool_mints.lock().unwrap().get(pool).copied()
+    }
+
+    /// Pools seen so far
+    pub fn pools(&self) -> Vec<Pubkey> {
+        self.pool_mints.lock().unwrap().keys().copied().collect()
+    }
+
+    /// Current quotes of `mint`
+    pub fn quotes(&self, mint: &Pubkey) -> Vec<VenueQuote> {
+        self.quotes
+            .lock()
+            .unwrap()
+            .get(mint)
+            .map(|quotes| quotes.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Update from a PumpSwap buy (the event reserves are pre-trade)
+    pub fn on_pumpswap_buy(&self, event: &PumpSwapBuyEvent) -> Option<ArbitrageOpportunity> {
+        if event.quote_mint != WSOL_TOKEN_ACCOUNT {
+            return None;
+        }
+        self.update_quote(
+            event.base_mint,
+            VenueQuote {
+                venue: Venue::PumpSwap,
+                pool: event.pool,
+                token_reserve: event.pool_base_token_reserves.saturating_sub(event.base_amount_out),
+                sol_reserve: event
+                    .pool_quote_token_reserves
+                    .saturating_add(event.quote_amount_in_with_lp_fee),
+                coin_creator: event.coin_creator,
+                updated_at: Instant::now(),
+            },
+        )
+    }
+
+    /// Update from a PumpSwap sell (the event reserves are pre-trade)
+    pub fn on_pumpswap_sell(&self, event: &PumpSwapSellEvent) -> Option<ArbitrageOpportunity> {
+        if event.quote_mint != WSOL_TOKEN_ACCOUNT {
+            return None;
+        }
+        self.update_quote(
+            event.base_mint,
+            VenueQuote {
+                venue: Venue::PumpSwap,
+                pool: event.pool,
+                token_reserve: event.pool_base_token_reserves.saturating_add(event.base_amount_in),
+                sol_reserve: event
+                    .pool_quote_token_reserves
+                    .saturating_sub(event.quote_amount_out_without_lp_fee),
+                coin_creator: event.coin_creator,
+                updated_at: Instant::now(),
+            },
+        )
+    }
+
+    /// Load the reserves of a Raydium CPMM pool over RPC
+    pub async fn refresh_raydium_cpmm(
+        &self,
+        rpc: &SolanaRpcClient,
+        pool: &Pubkey,
+    ) -> Result<Option<ArbitrageOpportunity>, anyhow::Error> {
+        let state = fetch_pool_state(rpc, pool).await?;
+        let (token0_balance, token1_balance) =
+            get_pool_token_balances(rpc, pool, &state.token0_mint, &state.token1_mint).await?;
+        let (mint, token_reserve, sol_reserve) = if state.token1_mint == WSOL_TOKEN_ACCOUNT {
+            (state.token0_mint, token0_balance, token1_balance)
+        } else if state.token0_mint == WSOL_TOKEN_ACCOUNT {
+            (state.token1_mint, token1_balance, token0_balance)
+        } else {
+            return Err(anyhow!("Raydium CPMM pool {} is not quoted in SOL", pool));
+        };
+        Ok(self.update_quote(
+            mint,
+            VenueQuote {
+                venue: Venue::RaydiumCpmm,
+                pool: *pool,
+                token_reserve,
+                sol_reserve,
+                coin_creator: Pubkey::default(),
+                updated_at: Instant::now(),
+            },
+        ))
+    }
+
+    /// Load the reserves of a Raydium AMM V4 pool over RPC
+    pub async fn refresh_raydium_amm_v4(
+        &self,
+        rpc: &SolanaRpcClient,
+        amm: &Pubkey,
+    ) -> Result<Option<ArbitrageOpportunity>, anyhow::Error> {
+        let amm_info = fetch_amm_info(rpc, *amm).await?;
+        let (coin_reserve, pc_reserve) =
+            get_multi_token_balances(rpc, &amm_info.token_coin, &amm_info.token_pc).await?;
+        let (mint, token_reserve, sol_reserve) = if amm_info.pc_mint == WSOL_TOKEN_ACCOUNT {
+            (amm_info.coin_mint, coin_reserve, pc_reserve)
+        } else if amm_info.coin_mint == WSOL_TOKEN_ACCOUNT {
+            (amm_info.pc_mint, pc_reserve, coin_reserve)
+        } else {
+            return Err(anyhow!("Raydium AMM V4 pool {} is not quoted in SOL", amm));
+        };
+        Ok(self.update_quote(
+            mint,
+            VenueQuote {
+                venue: Venue::RaydiumAmmV4,
+                pool: *amm,
+                token_reserve,
+                sol_reserve,
+                coin_creator: Pubkey::default(),
+                updated_at: Instant::now(),
+            },
+        ))
+    }
+
+    /// Best opportunity across the fresh quotes of `mint`
+    pub fn evaluate(&self, mint: &Pubkey) -> Option<ArbitrageOpportunity> {
+        let quotes: Vec<VenueQuote> = self
+            .quotes(mint)
+            .into_iter()
+            .filter(|quote| quote.updated_at.elapsed() <= self.config.max_quote_age)
+            .filter(|quote| quote.price() > 0.0)
+            .collect();
+        let mut best: Option<ArbitrageOpportunity> = None;
+        for buy in &quotes {
+            for sell in &quotes {
+                if buy.venue == sell.venue || sell.price() <= buy.price() {
+                    continue;
+                }
+                let spread_bps = ((sell.price() / buy.price() - 1.0) * 10_000.0) as u64;
+                if spread_bps < self.config.min_spread_bps {
+                    continue;
+                }
+                // A pool that cannot be quoted over the range is skipped
+                let Some((sol_in, token_amount, sol_out)) =
+                    best_size(buy, sell, self.config.max_input_lamports)
+                else {
+                    continue;
+                };
+                let expected_profit =
+                    sol_out as i64 - sol_in as i64 - self.config.tx_cost_lamports as i64;
+                if expected_profit < self.config.min_profit_lamports as i64 {
+                    continue;
+                }
+                if best.as_ref().is_some_and(|best| best.expected_profit >= expected_profit) {
+                    continue;
+                }
+                best = Some(ArbitrageOpportunity {
+                    mint: *mint,
+                    buy: buy.clone(),
+                    sell: sell.clone(),
+                    spread_bps,
+                    sol_in,
+                    token_amount,
+                    sol_out,
+                    expected_profit,
+                });
+            }
+        }
+        best
+    }
+}
+
+/// Buy size in `[0, max_input]` maximising the round trip profit
+///
+/// The profit of buying on one constant product pool and selling on another is concave in
+/// the input, so a ternary search finds the optimum. `None` when either pool fails to quote
+/// a size on the way.
+fn best_size(buy: &VenueQuote, sell: &VenueQuote, max_input: u64) -> Option<(u64, u64, u64)> {
+    let profit = |sol_in: u64| Some(sell.sell_out(buy.buy_out(sol_in)?)? as i128 - sol_in as i128);
+    let (mut lo, mut hi) = (0u64, max_input);
+    while hi - lo > 1_000 {
+        let m1 = lo + (hi - lo) / 3;
+        let m2 = hi - (hi - lo) / 3;
+        if profit(m1)? < profit(m2)? {
+            lo = m1;
+        } else {
+            hi = m2;
+        }
+    }
+    let sol_in = (lo + hi) / 2;
+    let token_amount = buy.buy_out(sol_in)?;
+    Some((sol_in, token_amount, sell.sell_out(token_amount)?))
+}
+
+impl SolanaTrade {
+    /// Execute both legs of `opportunity` as one Jito bundle
+    ///
+    /// The pools are reloaded for the trade parameters. `slippage_basis_points` applies to
+    /// both legs; the sell leg sells the minimum the buy leg can return, so dust may remain.
+    /// Requires a Jito swqos client and a non-zero `buy_tip_fee` in the priority fee config;
+    /// the buy tip fee is paid once for the bundle, on the sell transaction. Durable nonces
+    /// are rejected, both legs would advance the same nonce.
+    pub async fn execute_arbitrage(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+    ) -> TradeResult<()> {
+        if is_using_nonce() {
+            return Err(TradeError::InvalidParams(
+                "Arbitrage bundles cannot use a durable nonce".to_string(),
+            ));
+        }
+        let priority_fee = self.trade_config.priority_fee.clone();
+        // Jito drops bundles without a tip, fail before building anything
+        if !(priority_fee.buy_tip_fee.is_finite() && priority_fee.buy_tip_fee > 0.0) {
+            return Err(TradeError::InvalidParams(format!(
+                "Arbitrage bundles need a positive buy_tip_fee for the Jito tip, got {}",
+                priority_fee.buy_tip_fee
+            )));
+        }
+        let jito = self
+            .swqos_clients
+            .iter()
+            .find(|client| client.get_swqos_type() == SwqosType::Jito)
+            .ok_or_else(|| {
+                TradeError::InvalidParams("Arbitrage execution needs a Jito client".to_string())
+            })?;
+        let tip_account: Pubkey = jito
+            .get_tip_account()
+            .and_then(|account| account.parse().map_err(anyhow::Error::from))
+            .map_err(TradeError::from)?;
+        let lookup_table_key = self.trade_config.lookup_table_key;
+        let buy_dex = opportunity.buy.venue.dex_type();
+        let sell_dex = opportunity.sell.venue.dex_type();
+
+        let buy_params = BuyParams {
+            rpc: Some(self.rpc.clone()),
+            payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
+            mint: opportunity.mint,
+            sol_amount: opportunity.sol_in,
+            slippage_basis_points,
+            priority_fee: priority_fee.clone(),
+            lookup_table_key,
+            recent_blockhash,
+            data_size_limit: MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT,
+            wait_transaction_confirmed: false,
+            protocol_params: self.venue_params(&opportunity.buy).await?,
+            lifecycle: None,
+        };
+        let token_amount = calculate_with_slippage_sell(
+            opportunity.token_amount,
+            slippage_basis_points.unwrap_or(0),
+        )
+        .map_err(|e| anyhow!(e))?;
+        let sell_params = SellParams {
+            rpc: Some(self.rpc.clone()),
+            payer: self.payer.clone(),
+            fee_payer: self.fee_payer.clone(),
+            mint: opportunity.mint,
+            token_amount: Some(token_amount),
+            slippage_basis_points,
+            priority_fee: priority_fee.clone(),
+            lookup_table_key,
+            recent_blockhash,
+            wait_transaction_confirmed: false,
+            protocol_params: self.venue_params(&opportunity.sell).await?,
+            lifecycle: None,
+        };
+
+        let buy_instructions = TradeFactory::create_instruction_builder(buy_dex.clone())
+            .build_buy_instructions(&buy_params)
+            .await?;
+        let sell_instructions = TradeFactory::create_instruction_builder(sell_dex.clone())
+            .build_sell_instructions(&sell_params)
+            .await?;
+        let buy_transaction = build_rpc_transaction(
+            self.payer.clone(),
+            self.fee_payer.clone(),
+            &priority_fee,
+            buy_instructions,
+            lookup_table_key,
+            recent_blockhash,
+            MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT,
+            self.middleware_manager.clone(),
+            buy_dex,
+            true,
+        )
+        .await?;
+        let sell_transaction = build_tip_transaction(
+            self.payer.clone(),
+            self.fee_payer.clone(),
+            &priority_fee,
+            sell_instructions,
+            &tip_account,
+            priority_fee.buy_tip_fee,
+            lookup_table_key,
+            recent_blockhash,
+            MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT,
+            self.middleware_manager.clone(),
+            sell_dex,
+            false,
+        )
+        .await?;
+
+        jito.send_transactions(TradeType::Buy, &vec![buy_transaction, sell_transaction])
+            .await
+            .map_err(|e| TradeError::from_swqos("Jito", e))
+    }
+
+    async fn venue_params(&self, quote: &VenueQuote) -> TradeResult<Box<dyn ProtocolParams>> {
+        let params: Box<dyn ProtocolParams> = match quote.venue {
+            Venue::PumpSwap => {
+                Box::new(PumpSwapParams::from_pool_address_by_rpc(&self.rpc, &quote.pool).await?)
+            }
+            Venue::RaydiumCpmm => {
+                Box::new(RaydiumCpmmParams::from_pool_address_by_rpc(&self.rpc, &quote.pool).await?)
+            }
+            Venue::RaydiumAmmV4 => {
+                Box::new(RaydiumAmmV4Params::from_amm_address_by_rpc(&self.rpc, quote.pool).await?)
+            }
+        };
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(venue: Venue, token_reserve: u64, sol_reserve: u64) -> VenueQuote {
+        VenueQuote {
+            venue,
+            pool: Pubkey::new_unique(),
+            token_reserve,
+            sol_reserve,
+            coin_creator: Pubkey::default(),
+            updated_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_finds_cheaper_venue() {
+        let watcher = ArbitrageWatcher::new(ArbitrageConfig::default(), |_| {});
+        let mint = Pubkey::new_unique();
+        // 100 SOL / 1e15 tokens on CPMM vs 110 SOL / 1e15 tokens on AMM V4: ~10% spread
+        assert!(watcher
+            .update_quote(mint, quote(Venue::RaydiumCpmm, 1_000_000_000_000_000, 100_000_000_000))
+            .is_none());
+        let opportunity = watcher
+            .update_quote(mint, quote(Venue::RaydiumAmmV4, 1_000_000_000_000_000, 110_000_000_000))
+            .expect("opportunity");
+        assert_eq!(opportunity.buy.venue, Venue::RaydiumCpmm);
+        assert_eq!(opportunity.sell.venue, Venue::RaydiumAmmV4);
+        assert!(opportunity.spread_bps >= 900);
+        assert!(opportunity.sol_in <= ArbitrageConfig::default().max_input_lamports);
+        assert!(opportunity.expected_profit > 0);
+        assert_eq!(
+            opportunity.sol_out as i64
+                - opportunity.sol_in as i64
+                - ArbitrageConfig::default().tx_cost_lamports as i64,
+            opportunity.expected_profit
+        );
+    }
+
+    #[test]
+    fn test_empty_pool_has_no_quote() {
+        let empty = quote(Venue::RaydiumCpmm, 1_000_000_000_000_000, 0);
+        assert_eq!(empty.buy_out(1_000_000), None);
+        assert_eq!(empty.sell_out(1_000_000), None);
+        let pool = quote(Venue::RaydiumAmmV4, 1_000_000_000_000_000, 100_000_000_000);
+        assert_eq!(pool.buy_out(0), Some(0));
+        assert!(best_size(&empty, &pool, 1_000_000_000).is_none());
+        assert!(best_size(&pool, &empty, 1_000_000_000).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_arbitrage_needs_a_tip() {
+        use solana_sdk::{commitment_config::CommitmentConfig, signature::Keypair};
+
+        let priority_fee = crate::common::PriorityFee { buy_tip_fee: 0.0, ..Default::default() };
+        let config = crate::common::TradeConfig::new(
+            "http://127.0.0.1:1".to_string(),
+            vec![],
+            priority_fee,
+            CommitmentConfig::confirmed(),
+            None,
+        );
+        let client = SolanaTrade::new(Arc::new(Keypair::new()), config).await.unwrap();
+        let opportunity = ArbitrageOpportunity {
+            mint: Pubkey::new_unique(),
+            buy: quote(Venue::RaydiumCpmm, 1_000_000_000_000_000, 100_000_000_000),
+            sell: quote(Venue::RaydiumAmmV4, 1_000_000_000_000_000, 110_000_000_000),
+            spread_bps: 1_000,
+            sol_in: 1_000_000,
+            token_amount: 9_000_000_000,
+            sol_out: 1_090_000,
+            expected_profit: 0,
+        };
+        let result = client.execute_arbitrage(&opportunity, None, Hash::default()).await;
+        assert!(matches!(result, Err(TradeError::InvalidParams(_))));
+    }
+}
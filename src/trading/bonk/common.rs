@@ -1,11 +1,13 @@
 use crate::{
     common::SolanaRpcClient,
     constants::{self, bonk::accounts},
+    trading::common::batch_fetcher::get_multiple_accounts,
 };
 use anyhow::anyhow;
 use solana_sdk::pubkey::Pubkey;
 use solana_streamer_sdk::streaming::event_parser::protocols::bonk::{
-    pool_state_decode, types::PoolState,
+    pool_state_decode,
+    types::{global_config_decode, PoolState},
 };
 
 pub async fn fetch_pool_state(
@@ -21,23 +23,40 @@ pub async fn fetch_pool_state(
     Ok(pool_state)
 }
 
+/// Input left after the protocol, platform and share fees (rates in basis points)
 pub fn get_amount_in_net(
     amount_in: u64,
     protocol_fee_rate: u128,
     platform_fee_rate: u128,
     share_fee_rate: u128,
-) -> u64 {
+) -> Result<u64, String> {
     let amount_in_u128 = amount_in as u128;
-    let protocol_fee = (amount_in_u128 * protocol_fee_rate / 10000) as u128;
-    let platform_fee = (amount_in_u128 * platform_fee_rate / 10000) as u128;
-    let share_fee = (amount_in_u128 * share_fee_rate / 10000) as u128;
-    amount_in_u128
-        .checked_sub(protocol_fee)
-        .unwrap()
-        .checked_sub(platform_fee)
-        .unwrap()
-        .checked_sub(share_fee)
-        .unwrap() as u64
+    let fees = [protocol_fee_rate, platform_fee_rate, share_fee_rate]
+        .into_iter()
+        .try_fold(0u128, |fees, rate| {
+            amount_in_u128.checked_mul(rate).and_then(|fee| fees.checked_add(fee / 10000))
+        })
+        .ok_or_else(|| "Fee calculation overflowed".to_string())?;
+    let amount_in_net = amount_in_u128
+        .checked_sub(fees)
+        .ok_or_else(|| format!("Fees {} exceed the input amount {}", fees, amount_in))?;
+    Ok(amount_in_net as u64)
+}
+
+/// Reserves the LaunchLab curve trades against: (quote, base)
+fn curve_reserves(
+    virtual_base: u128,
+    virtual_quote: u128,
+    real_base: u128,
+    real_quote: u128,
+) -> Result<(u128, u128), String> {
+    let quote_reserve = virtual_quote
+        .checked_add(real_quote)
+        .ok_or_else(|| "Quote reserves overflowed".to_string())?;
+    let base_reserve = virtual_base.checked_sub(real_base).ok_or_else(|| {
+        format!("Real base reserves {} exceed virtual reserves {}", real_base, virtual_base)
+    })?;
+    Ok((quote_reserve, base_reserve))
 }
 
 pub fn get_amount_in(
@@ -50,26 +69,41 @@ pub fn get_amount_in(
     real_base: u128,
     real_quote: u128,
     slippage_basis_points: u128,
-) -> u64 {
+) -> Result<u64, String> {
+    if slippage_basis_points >= 10000 {
+        return Err(format!("Slippage {} bps must be below 10000", slippage_basis_points));
+    }
+    let total_fee_rate = protocol_fee_rate
+        .checked_add(platform_fee_rate)
+        .and_then(|rate| rate.checked_add(share_fee_rate))
+        .filter(|rate| *rate < 10000)
+        .ok_or_else(|| "Total fee rate must be below 10000 bps".to_string())?;
     let amount_out_u128 = amount_out as u128;
 
     // 考虑滑点，实际需要的输出金额更高
     let amount_out_with_slippage = amount_out_u128 * 10000 / (10000 - slippage_basis_points);
 
-    let input_reserve = virtual_quote.checked_add(real_quote).unwrap();
-    let output_reserve = virtual_base.checked_sub(real_base).unwrap();
+    let (input_reserve, output_reserve) =
+        curve_reserves(virtual_base, virtual_quote, real_base, real_quote)?;
 
     // 根据 AMM 公式反推: amount_in_net = (amount_out * input_reserve) / (output_reserve - amount_out)
-    let numerator = amount_out_with_slippage.checked_mul(input_reserve).unwrap();
-    let denominator = output_reserve.checked_sub(amount_out_with_slippage).unwrap();
-    let amount_in_net = numerator.checked_div(denominator).unwrap();
+    let numerator = amount_out_with_slippage
+        .checked_mul(input_reserve)
+        .ok_or_else(|| "Amount in calculation overflowed".to_string())?;
+    let denominator = output_reserve
+        .checked_sub(amount_out_with_slippage)
+        .filter(|denominator| *denominator > 0)
+        .ok_or_else(|| {
+            format!("Cannot buy {} tokens from reserves of {}", amount_out, output_reserve)
+        })?;
+    let amount_in_net = numerator / denominator;
 
     // 计算总费用率
-    let total_fee_rate = protocol_fee_rate + platform_fee_rate + share_fee_rate;
-
-    let amount_in = amount_in_net * 10000 / (10000 - total_fee_rate);
-
-    amount_in as u64
+    let amount_in = amount_in_net
+        .checked_mul(10000)
+        .map(|amount| amount / (10000 - total_fee_rate))
+        .ok_or_else(|| "Amount in calculation overflowed".to_string())?;
+    u64::try_from(amount_in).map_err(|_| format!("Amount in {} exceeds u64", amount_in))
 }
 
 pub fn get_amount_out(
@@ -82,26 +116,26 @@ pub fn get_amount_out(
     real_base: u128,
     real_quote: u128,
     slippage_basis_points: u128,
-) -> u64 {
-    let amount_in_u128 = amount_in as u128;
-    let protocol_fee = (amount_in_u128 * protocol_fee_rate / 10000) as u128;
-    let platform_fee = (amount_in_u128 * platform_fee_rate / 10000) as u128;
-    let share_fee = (amount_in_u128 * share_fee_rate / 10000) as u128;
-    let amount_in_net = amount_in_u128
-        .checked_sub(protocol_fee)
-        .unwrap()
-        .checked_sub(platform_fee)
-        .unwrap()
-        .checked_sub(share_fee)
-        .unwrap();
-    let input_reserve = virtual_quote.checked_add(real_quote).unwrap();
-    let output_reserve = virtual_base.checked_sub(real_base).unwrap();
-    let numerator = amount_in_net.checked_mul(output_reserve).unwrap();
-    let denominator = input_reserve.checked_add(amount_in_net).unwrap();
-    let mut amount_out = numerator.checked_div(denominator).unwrap();
-
-    amount_out = amount_out - (amount_out * slippage_basis_points) / 10000;
-    amount_out as u64
+) -> Result<u64, String> {
+    if slippage_basis_points > 10000 {
+        return Err(format!("Slippage {} bps exceeds 10000", slippage_basis_points));
+    }
+    let amount_in_net =
+        get_amount_in_net(amount_in, protocol_fee_rate, platform_fee_rate, share_fee_rate)? as u128;
+    let (input_reserve, output_reserve) =
+        curve_reserves(virtual_base, virtual_quote, real_base, real_quote)?;
+    let numerator = amount_in_net
+        .checked_mul(output_reserve)
+        .ok_or_else(|| "Amount out calculation overflowed".to_string())?;
+    let denominator = input_reserve
+        .checked_add(amount_in_net)
+        .filter(|denominator| *denominator > 0)
+        .ok_or_else(|| "Quote reserves are empty".to_string())?;
+    // Below output_reserve, which may still exceed u64 for a corrupt pool
+    let amount_out = u64::try_from(numerator / denominator)
+        .map_err(|_| "Amount out exceeds u64".to_string())? as u128;
+
+    Ok((amount_out - (amount_out * slippage_basis_points) / 10000) as u64)
 }
 
 pub fn get_pool_pda(base_mint: &Pubkey, quote_mint: &Pubkey) -> Option<Pubkey> {
@@ -134,6 +168,110 @@ pub fn get_creator_associated_account(creator: &Pubkey) -> Option<Pubkey> {
     pda.map(|pubkey| pubkey.0)
 }
 
+/// LaunchLab 费率单位（百万分之一），SDK 内部使用基点
+const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+/// PlatformConfig 账户中 fee_rate 字段的偏移（含 8 字节 discriminator）
+const PLATFORM_FEE_RATE_OFFSET: usize = 8 + 8 + 32 * 2 + 8 * 3;
+
+/// 解码后的 LaunchLab 池子，附带报价所需的费率（基点）
+#[derive(Debug, Clone)]
+pub struct BonkPoolInfo {
+    pub pool_address: Pubkey,
+    pub pool_state: PoolState,
+    pub protocol_fee_rate: u128,
+    pub platform_fee_rate: u128,
+    pub share_fee_rate: u128,
+}
+
+impl BonkPoolInfo {
+    /// 池子仍在募资阶段，可在 bonding curve 上交易
+    #[inline]
+    pub fn is_tradable(&self) -> bool {
+        self.pool_state.status == 0
+    }
+
+    /// 池子已完成募资，正在迁移或已迁移
+    #[inline]
+    pub fn is_migrated(&self) -> bool {
+        self.pool_state.status != 0
+    }
+
+    /// 按当前储备和费率计算买入可得的代币数量
+    pub fn get_amount_out(
+        &self,
+        amount_in: u64,
+        slippage_basis_points: u128,
+    ) -> Result<u64, String> {
+        get_amount_out(
+            amount_in,
+            self.protocol_fee_rate,
+            self.platform_fee_rate,
+            self.share_fee_rate,
+            self.pool_state.virtual_base as u128,
+            self.pool_state.virtual_quote as u128,
+            self.pool_state.real_base as u128,
+            self.pool_state.real_quote as u128,
+            slippage_basis_points,
+        )
+    }
+
+    /// 按当前储备和费率计算买到 `amount_out` 所需的 quote 数量
+    pub fn get_amount_in(
+        &self,
+        amount_out: u64,
+        slippage_basis_points: u128,
+    ) -> Result<u64, String> {
+        get_amount_in(
+            amount_out,
+            self.protocol_fee_rate,
+            self.platform_fee_rate,
+            self.share_fee_rate,
+            self.pool_state.virtual_base as u128,
+            self.pool_state.virtual_quote as u128,
+            self.pool_state.real_base as u128,
+            self.pool_state.real_quote as u128,
+            slippage_basis_points,
+        )
+    }
+}
+
+/// 根据 base mint 获取 LaunchLab 池子状态，并从全局配置和平台配置读取费率
+///
+/// 配置账户读取失败时回退到常量中的默认费率
+pub async fn get_pool_state(
+    rpc: &SolanaRpcClient,
+    base_mint: &Pubkey,
+) -> Result<BonkPoolInfo, anyhow::Error> {
+    let pool_address = get_pool_pda(base_mint, &accounts::WSOL_TOKEN_ACCOUNT)
+        .ok_or_else(|| anyhow!("Failed to derive pool address"))?;
+    let pool_state = fetch_pool_state(rpc, &pool_address).await?;
+
+    let configs =
+        get_multiple_accounts(rpc, &[pool_state.global_config, pool_state.platform_config]).await?;
+    let protocol_fee_rate = configs[0]
+        .as_ref()
+        .and_then(|account| account.data.get(8..))
+        .and_then(global_config_decode)
+        .map(|config| (config.trade_fee_rate * 10_000 / FEE_RATE_DENOMINATOR) as u128)
+        .unwrap_or(accounts::PROTOCOL_FEE_RATE);
+    let platform_fee_rate = configs[1]
+        .as_ref()
+        .and_then(|account| {
+            account.data.get(PLATFORM_FEE_RATE_OFFSET..PLATFORM_FEE_RATE_OFFSET + 8)
+        })
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(|bytes| (u64::from_le_bytes(bytes) * 10_000 / FEE_RATE_DENOMINATOR) as u128)
+        .unwrap_or(accounts::PLATFORM_FEE_RATE);
+
+    Ok(BonkPoolInfo {
+        pool_address,
+        pool_state,
+        protocol_fee_rate,
+        platform_fee_rate,
+        share_fee_rate: accounts::SHARE_FEE_RATE,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::constants::bonk::accounts::{PLATFORM_FEE_RATE, PROTOCOL_FEE_RATE, SHARE_FEE_RATE};
@@ -164,7 +302,8 @@ mod tests {
             real_base,
             real_quote,
             slippage_basis_points,
-        );
+        )
+        .unwrap();
 
         let amount_out = 25959582643397;
         let get_amount_in_result = get_amount_in(
@@ -177,7 +316,8 @@ mod tests {
             real_base,
             real_quote,
             slippage_basis_points,
-        );
+        )
+        .unwrap();
 
         println!("Original amount_in: {}", original_amount_in);
         println!("Amount_out: {}", geet_amount_out_result);
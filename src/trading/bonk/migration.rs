@@ -0,0 +1,249 @@
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+use solana_streamer_sdk::streaming::event_parser::protocols::bonk::{
+    types::PoolStatus, BonkMigrateToAmmEvent, BonkMigrateToCpswapEvent, BonkTradeEvent,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::{
+    common::SolanaRpcClient,
+    constants::bonk::accounts,
+    trading::{
+        bonk::common::{fetch_pool_state, get_pool_pda, get_pool_state},
+        core::{
+            params::{BonkParams, RaydiumAmmV4Params, RaydiumCpmmParams},
+            traits::ProtocolParams,
+        },
+        factory::DexType,
+        raydium::{find_pools, RaydiumPool},
+    },
+};
+
+/// Bonk 池子毕业后迁移到的 Raydium 池子
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BonkMigrationTarget {
+    RaydiumAmmV4 { pool: Pubkey },
+    RaydiumCpmm { pool: Pubkey },
+}
+
+impl BonkMigrationTarget {
+    /// 迁移后对应的交易协议
+    pub fn dex_type(&self) -> DexType {
+        match self {
+            BonkMigrationTarget::RaydiumAmmV4 { .. } => DexType::RaydiumAmmV4,
+            BonkMigrationTarget::RaydiumCpmm { .. } => DexType::RaydiumCpmm,
+        }
+    }
+}
+
+/// 检测到 Bonk 代币迁移时发出的事件
+#[derive(Debug, Clone)]
+pub struct BonkMigratedEvent {
+    pub mint: Pubkey,
+    pub target: BonkMigrationTarget,
+}
+
+type MigrationListener = Arc<dyn Fn(&BonkMigratedEvent) + Send + Sync>;
+
+/// BonkMigrationCache 单例，记录已毕业的 Bonk 代币及其 Raydium 池子
+///
+/// 迁移可由事件流（MigrateToAmm / MigrateToCpswap）或 `resolve_route` 的 RPC 查询发现，
+/// 每个 mint 首次记录时通知所有监听者。`SolanaTrade` 的 Bonk 买卖会据此自动改走 Raydium。
+pub struct BonkMigrationCache {
+    targets: Mutex<HashMap<Pubkey, BonkMigrationTarget>>,
+    pending: Mutex<HashSet<Pubkey>>,
+    listeners: Mutex<Vec<MigrationListener>>,
+}
+
+static BONK_MIGRATION_CACHE: OnceLock<Arc<BonkMigrationCache>> = OnceLock::new();
+
+impl BonkMigrationCache {
+    /// 获取 BonkMigrationCache 单例实例
+    pub fn get_instance() -> Arc<BonkMigrationCache> {
+        BONK_MIGRATION_CACHE
+            .get_or_init(|| {
+                Arc::new(BonkMigrationCache {
+                    targets: Mutex::new(HashMap::new()),
+                    pending: Mutex::new(HashSet::new()),
+                    listeners: Mutex::new(Vec::new()),
+                })
+            })
+            .clone()
+    }
+
+    /// 注册迁移事件监听
+    pub fn subscribe<F>(&self, listener: F)
+    where
+        F: Fn(&BonkMigratedEvent) + Send + Sync + 'static,
+    {
+        self.listeners.lock().unwrap().push(Arc::new(listener));
+    }
+
+    /// 获取 mint 的迁移目标
+    pub fn get(&self, mint: &Pubkey) -> Option<BonkMigrationTarget> {
+        self.targets.lock().unwrap().get(mint).copied()
+    }
+
+    /// 池子已进入迁移状态但目标池子未知
+    pub fn is_pending(&self, mint: &Pubkey) -> bool {
+        self.pending.lock().unwrap().contains(mint)
+    }
+
+    /// 记录迁移目标，首次记录时通知监听者
+    pub fn record(&self, mint: Pubkey, target: BonkMigrationTarget) {
+        self.pending.lock().unwrap().remove(&mint);
+        let previous = self.targets.lock().unwrap().insert(mint, target);
+        if previous == Some(target) {
+            return;
+        }
+        let event = BonkMigratedEvent { mint, target };
+        let listeners = self.listeners.lock().unwrap().clone();
+        for listener in listeners {
+            listener(&event);
+        }
+    }
+
+    /// 使用迁移到 AMM V4 的事件更新
+    pub fn update_from_migrate_to_amm(&self, event: &BonkMigrateToAmmEvent) {
+        self.record(event.base_mint, BonkMigrationTarget::RaydiumAmmV4 { pool: event.amm_pool });
+    }
+
+    /// 使用迁移到 CPMM 的事件更新
+    pub fn update_from_migrate_to_cpswap(&self, event: &BonkMigrateToCpswapEvent) {
+        self.record(event.base_mint, BonkMigrationTarget::RaydiumCpmm { pool: event.cpswap_pool });
+    }
+
+    /// 交易事件显示募资结束时标记为待迁移
+    pub fn update_from_trade(&self, event: &BonkTradeEvent) {
+        if event.pool_status != PoolStatus::Fund && self.get(&event.base_token_mint).is_none() {
+            self.pending.lock().unwrap().insert(event.base_token_mint);
+        }
+    }
+
+    /// 清空缓存
+    pub fn clear(&self) {
+        self.targets.lock().unwrap().clear();
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+/// 解析 Bonk 代币当前应使用的协议和参数
+///
+/// 已知迁移的代币直接走 Raydium；否则查询 LaunchLab 池子，仍在募资阶段时返回 Bonk 参数，
+/// 已毕业时查找对应的 Raydium 池子并记录迁移。
+pub async fn resolve_route(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<(DexType, Box<dyn ProtocolParams>), anyhow::Error> {
+    let cache = BonkMigrationCache::get_instance();
+    if let Some(target) = cache.get(mint) {
+        return raydium_route(rpc, target).await;
+    }
+    let pool_info = get_pool_state(rpc, mint).await?;
+    if pool_info.is_tradable() {
+        return Ok((DexType::Bonk, Box::new(BonkParams::from_mint_by_rpc(rpc, mint).await?)));
+    }
+
+    // migrate_type: 0 = AMM V4, 1 = CPMM
+    let prefer_cpmm = pool_info.pool_state.migrate_type == 1;
+    let pools = find_pools(rpc, mint).await?;
+    let target = pools
+        .iter()
+        .filter(|pool| {
+            let (mint0, mint1) = pool.mints();
+            mint0 == accounts::WSOL_TOKEN_ACCOUNT || mint1 == accounts::WSOL_TOKEN_ACCOUNT
+        })
+        .filter_map(|pool| match pool {
+            RaydiumPool::AmmV4 { address, .. } => {
+                Some(BonkMigrationTarget::RaydiumAmmV4 { pool: *address })
+            }
+            RaydiumPool::Cpmm { address, .. } => {
+                Some(BonkMigrationTarget::RaydiumCpmm { pool: *address })
+            }
+            RaydiumPool::Clmm { .. } => None,
+        })
+        .max_by_key(|target| {
+            matches!(target, BonkMigrationTarget::RaydiumCpmm { .. }) == prefer_cpmm
+        })
+        .ok_or_else(|| anyhow!("Bonk pool for {} has migrated but no Raydium pool found", mint))?;
+    cache.record(*mint, target);
+    raydium_route(rpc, target).await
+}
+
+/// 若 Bonk 交易的代币已迁移，改写为对应的 Raydium 协议和参数
+///
+/// 缓存未命中时读取 LaunchLab 池子账户确认状态，未订阅迁移事件也不会在已毕业的池子上交易。
+pub(crate) async fn reroute_if_migrated(
+    rpc: &SolanaRpcClient,
+    dex_type: DexType,
+    mint: &Pubkey,
+    protocol_params: Box<dyn ProtocolParams>,
+) -> Result<(DexType, Box<dyn ProtocolParams>), anyhow::Error> {
+    if dex_type != DexType::Bonk {
+        return Ok((dex_type, protocol_params));
+    }
+    let cache = BonkMigrationCache::get_instance();
+    if let Some(target) = cache.get(mint) {
+        return raydium_route(rpc, target).await;
+    }
+    if cache.is_pending(mint) {
+        return resolve_route(rpc, mint).await;
+    }
+    let pool_address = get_pool_pda(mint, &accounts::WSOL_TOKEN_ACCOUNT)
+        .ok_or_else(|| anyhow!("Failed to derive pool address"))?;
+    if fetch_pool_state(rpc, &pool_address).await?.status != 0 {
+        return resolve_route(rpc, mint).await;
+    }
+    Ok((dex_type, protocol_params))
+}
+
+async fn raydium_route(
+    rpc: &SolanaRpcClient,
+    target: BonkMigrationTarget,
+) -> Result<(DexType, Box<dyn ProtocolParams>), anyhow::Error> {
+    match target {
+        BonkMigrationTarget::RaydiumAmmV4 { pool } => Ok((
+            DexType::RaydiumAmmV4,
+            Box::new(RaydiumAmmV4Params::from_amm_address_by_rpc(rpc, pool).await?),
+        )),
+        BonkMigrationTarget::RaydiumCpmm { pool } => Ok((
+            DexType::RaydiumCpmm,
+            Box::new(RaydiumCpmmParams::from_pool_address_by_rpc(rpc, &pool).await?),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockRpc;
+    use solana_sdk::account::Account;
+    use solana_streamer_sdk::streaming::event_parser::protocols::bonk::types::POOL_STATE_SIZE;
+
+    fn pool_account(status: u8) -> Account {
+        let mut data = vec![0u8; 8 + POOL_STATE_SIZE];
+        // discriminator, epoch, auth_bump
+        data[8 + 8 + 1] = status;
+        Account { lamports: 1, data, owner: accounts::BONK, executable: false, rent_epoch: 0 }
+    }
+
+    #[tokio::test]
+    async fn test_reroute_checks_pool_on_cache_miss() {
+        let mint = Pubkey::new_unique();
+        let pool = get_pool_pda(&mint, &accounts::WSOL_TOKEN_ACCOUNT).unwrap();
+
+        let rpc = MockRpc::new().with_account(pool, pool_account(0)).client();
+        let (dex_type, _) =
+            reroute_if_migrated(&rpc, DexType::Bonk, &mint, Box::new(BonkParams::default()))
+                .await
+                .unwrap();
+        assert_eq!(dex_type, DexType::Bonk);
+
+        // Fundraising ended: routed to Raydium, which has no pool here
+        let rpc = MockRpc::new().with_account(pool, pool_account(1)).client();
+        let route =
+            reroute_if_migrated(&rpc, DexType::Bonk, &mint, Box::new(BonkParams::default())).await;
+        assert!(route.is_err());
+    }
+}
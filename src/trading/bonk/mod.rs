@@ -1 +1,3 @@
 pub mod common;
+#[cfg(feature = "raydium")]
+pub mod migration;
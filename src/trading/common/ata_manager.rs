@@ -0,0 +1,71 @@
+use solana_sdk::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_system_interface::instruction::create_account_with_seed;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::common::ata_cache::AtaCache;
+
+/// 按 AtaCache 策略添加用户 ATA 的幂等创建指令
+///
+/// 在 `SkipKnownExisting` 模式下，已知存在的 ATA 不会再添加创建指令
+pub fn add_create_ata_instruction(
+    instructions: &mut Vec<Instruction>,
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) {
+    if AtaCache::get_instance().needs_create(owner, mint) {
+        instructions.push(create_associated_token_account_idempotent(
+            payer,
+            owner,
+            mint,
+            token_program,
+        ));
+    }
+}
+
+/// 创建以 `create_account_with_seed` 派生的临时 WSOL 账户，存入 `lamports` 后可直接用于交易
+///
+/// 每次调用使用随机种子，同一钱包的并行交易各自使用独立账户，不会争用同一个 WSOL ATA。
+/// 返回账户地址及创建、初始化指令，使用后需由调用方关闭账户以取回 SOL 和租金。
+pub fn create_seeded_wsol_account(owner: &Pubkey, lamports: u64) -> (Pubkey, Vec<Instruction>) {
+    let seed = format!("wsol{:016x}", rand::random::<u64>());
+    let account = Pubkey::create_with_seed(owner, &seed, &spl_token::ID)
+        .expect("seed is shorter than the maximum length");
+    let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let instructions = vec![
+        create_account_with_seed(
+            owner,
+            &account,
+            owner,
+            &seed,
+            rent + lamports,
+            spl_token::state::Account::LEN as u64,
+            &spl_token::ID,
+        ),
+        // 原生 mint 的账户初始化时，超出租金的 lamports 即为 WSOL 余额
+        spl_token::instruction::initialize_account3(
+            &spl_token::ID,
+            &account,
+            &spl_token::native_mint::ID,
+            owner,
+        )
+        .expect("token program id is valid"),
+    ];
+    (account, instructions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_wsol_accounts_are_distinct() {
+        let owner = Pubkey::new_unique();
+        let (first, instructions) = create_seeded_wsol_account(&owner, 1_000);
+        let (second, _) = create_seeded_wsol_account(&owner, 1_000);
+        assert_ne!(first, second);
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[1].accounts[0].pubkey, first);
+    }
+}
@@ -0,0 +1,159 @@
+use anyhow::anyhow;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+#[cfg(feature = "pumpfun")]
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+#[cfg(feature = "pumpfun")]
+use std::sync::Arc;
+
+use crate::common::SolanaRpcClient;
+#[cfg(feature = "pumpfun")]
+use crate::{
+    common::{bonding_curve::BondingCurveAccount, bonding_curve_cache::BondingCurveCache},
+    solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::types::bonding_curve_decode,
+    trading::pumpfun::common::get_bonding_curve_pda,
+};
+
+/// getMultipleAccounts 单次请求的账户上限
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Fetch many accounts with as few `getMultipleAccounts` round trips as possible
+///
+/// The result has the same order and length as `pubkeys`; missing accounts are `None`.
+pub async fn get_multiple_accounts(
+    rpc: &SolanaRpcClient,
+    pubkeys: &[Pubkey],
+) -> Result<Vec<Option<Account>>, anyhow::Error> {
+    let mut accounts = Vec::with_capacity(pubkeys.len());
+    for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        accounts.extend(rpc.get_multiple_accounts(chunk).await?);
+    }
+    Ok(accounts)
+}
+
+/// Read the `amount` field of an SPL Token / Token-2022 account
+#[inline]
+pub fn parse_token_account_amount(account: &Account) -> Option<u64> {
+    let bytes = account.data.get(64..72)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Fetch the amounts of several token accounts in a single round trip
+pub async fn get_token_account_amounts(
+    rpc: &SolanaRpcClient,
+    token_accounts: &[Pubkey],
+) -> Result<Vec<u64>, anyhow::Error> {
+    let accounts = get_multiple_accounts(rpc, token_accounts).await?;
+    accounts
+        .iter()
+        .zip(token_accounts)
+        .map(|(account, pubkey)| {
+            account
+                .as_ref()
+                .and_then(parse_token_account_amount)
+                .ok_or_else(|| anyhow!("Token account {} not found or invalid", pubkey))
+        })
+        .collect()
+}
+
+/// Accounts needed to trade a PumpFun token, fetched together
+#[cfg(feature = "pumpfun")]
+pub struct PumpFunTradeAccounts {
+    pub bonding_curve: Arc<BondingCurveAccount>,
+    /// Token program owning the mint
+    pub token_program: Pubkey,
+    /// Balance of the user's associated token account, `None` if it does not exist
+    pub user_token_balance: Option<u64>,
+    /// User SOL balance in lamports
+    pub user_sol_balance: u64,
+}
+
+/// Fetch bonding curve, mint, user ATA and user wallet in one `getMultipleAccounts` call
+///
+/// Both the SPL Token and Token-2022 ATAs are requested so the token program does not
+/// have to be known up front.
+#[cfg(feature = "pumpfun")]
+pub async fn fetch_pumpfun_trade_accounts(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    user: &Pubkey,
+) -> Result<PumpFunTradeAccounts, anyhow::Error> {
+    let bonding_curve_pda =
+        get_bonding_curve_pda(mint).ok_or_else(|| anyhow!("Bonding curve not found"))?;
+    let user_ata = get_associated_token_address_with_program_id(user, mint, &spl_token::ID);
+    let user_ata_2022 =
+        get_associated_token_address_with_program_id(user, mint, &spl_token_2022::ID);
+    let accounts =
+        get_multiple_accounts(rpc, &[bonding_curve_pda, *mint, *user, user_ata, user_ata_2022])
+            .await?;
+
+    let bonding_curve_account =
+        accounts[0].as_ref().ok_or_else(|| anyhow!("Bonding curve not found"))?;
+    let bonding_curve = bonding_curve_account
+        .data
+        .get(8..)
+        .and_then(bonding_curve_decode)
+        .ok_or_else(|| anyhow!("Failed to deserialize bonding curve account"))?;
+    let mint_account = accounts[1].as_ref().ok_or_else(|| anyhow!("Mint {} not found", mint))?;
+    let token_program = mint_account.owner;
+    let user_sol_balance = accounts[2].as_ref().map(|account| account.lamports).unwrap_or(0);
+    let user_token_account =
+        if token_program == spl_token_2022::ID { &accounts[4] } else { &accounts[3] };
+    let user_token_balance = user_token_account.as_ref().and_then(parse_token_account_amount);
+
+    let bonding_curve = Arc::new(BondingCurveAccount::from_bonding_curve(mint, &bonding_curve));
+    BondingCurveCache::get_instance().insert(*mint, bonding_curve.clone());
+
+    Ok(PumpFunTradeAccounts { bonding_curve, token_program, user_token_balance, user_sol_balance })
+}
+
+#[cfg(all(test, feature = "pumpfun"))]
+mod tests {
+    use super::*;
+    use crate::testing::MockRpc;
+    use crate::trading::core::params::PumpFunParams;
+    use solana_client::rpc_request::RpcRequest;
+
+    fn account(owner: Pubkey, lamports: u64, data: Vec<u8>) -> Account {
+        Account { lamports, data, owner, executable: false, rent_epoch: 0 }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pumpfun_trade_accounts() {
+        let mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+
+        let mut curve = vec![0u8; 8];
+        for reserve in [1_000_000u64, 30_000_000_000, 800_000, 0, 1_000_000_000] {
+            curve.extend_from_slice(&reserve.to_le_bytes());
+        }
+        curve.push(0);
+        curve.extend_from_slice(creator.as_ref());
+        let mut user_ata = vec![0u8; 165];
+        user_ata[64..72].copy_from_slice(&500u64.to_le_bytes());
+        let user_ata_address =
+            get_associated_token_address_with_program_id(&user, &mint, &spl_token_2022::ID);
+
+        let mock = MockRpc::new()
+            .with_account(
+                get_bonding_curve_pda(&mint).unwrap(),
+                account(Pubkey::new_unique(), 1, curve),
+            )
+            .with_account(mint, account(spl_token_2022::ID, 1, vec![0u8; 82]))
+            .with_account(user, account(Pubkey::default(), 2_000_000, vec![]))
+            .with_account(user_ata_address, account(spl_token_2022::ID, 1, user_ata));
+        let rpc = mock.client();
+
+        let accounts = fetch_pumpfun_trade_accounts(&rpc, &mint, &user).await.unwrap();
+        assert_eq!(mock.request_count(RpcRequest::GetMultipleAccounts), 1);
+        assert_eq!(accounts.token_program, spl_token_2022::ID);
+        assert_eq!(accounts.user_token_balance, Some(500));
+        assert_eq!(accounts.user_sol_balance, 2_000_000);
+        assert_eq!(accounts.bonding_curve.virtual_sol_reserves, 30_000_000_000);
+        assert_eq!(accounts.bonding_curve.creator, creator);
+
+        let params = PumpFunParams::from_trade_accounts(&accounts, Some(true));
+        assert_eq!(params.creator_vault, accounts.bonding_curve.get_creator_vault_pda());
+        assert_eq!(params.close_token_account_when_sell, Some(true));
+    }
+}
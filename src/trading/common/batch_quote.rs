@@ -0,0 +1,197 @@
+//! Prices of many mints with batched RPC calls
+//!
+//! Quoting a portfolio or a list of candidates one mint at a time costs a round trip per
+//! account. [`quote_many`] instead reads every bonding curve with `getMultipleAccounts`, and
+//! for the mints that graduated (or never had a curve) reads their PumpSwap pools, the missing
+//! mint accounts and the pool vaults in two more batched requests.
+
+use anyhow::anyhow;
+use futures::future::join_all;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::common::{
+    bonding_curve::BondingCurveAccount,
+    bonding_curve_cache::BondingCurveCache,
+    mint_info_cache::{MintInfo, MintInfoCache},
+    SolanaRpcClient,
+};
+use crate::constants::pumpswap::accounts::{AMM_PROGRAM, WSOL_TOKEN_ACCOUNT};
+use crate::solana_streamer_sdk::streaming::event_parser::protocols::{
+    pumpfun::types::bonding_curve_decode,
+    pumpswap::types::{pool_decode, Pool},
+};
+use crate::trading::common::batch_fetcher::{get_multiple_accounts, parse_token_account_amount};
+use crate::trading::factory::DexType;
+use crate::trading::pumpfun::common::get_bonding_curve_pda;
+use crate::trading::pumpswap::{common::find_pool, reserves_cache::PoolReservesCache};
+use crate::utils::price;
+
+const SOL_DECIMALS: u8 = 9;
+
+/// Price of a mint on the venue it currently trades on
+#[derive(Debug, Clone, PartialEq)]
+pub struct MintQuote {
+    pub dex_type: DexType,
+    /// Bonding curve or pool the price comes from
+    pub market: Pubkey,
+    /// Price of one whole token in SOL
+    pub price: f64,
+    /// SOL liquidity in lamports, the real reserves on the bonding curve
+    pub sol_reserves: u64,
+}
+
+/// Quote `mints` on their bonding curve, or on their PumpSwap pool once graduated
+///
+/// Mints that have neither, or whose pool is not paired with SOL, are missing from the
+/// result. Pools are resolved through `PoolCache`; a mint missing from it costs one
+/// `getProgramAccounts` scan, run concurrently with the others. The fetched curves, pools,
+/// reserves and mint infos refresh the SDK caches.
+pub async fn quote_many(
+    rpc: &SolanaRpcClient,
+    mints: &[Pubkey],
+) -> Result<HashMap<Pubkey, MintQuote>, anyhow::Error> {
+    let mut quotes = HashMap::with_capacity(mints.len());
+
+    let curves: Vec<(Pubkey, Pubkey)> =
+        mints.iter().filter_map(|mint| Some((*mint, get_bonding_curve_pda(mint)?))).collect();
+    let curve_addresses: Vec<Pubkey> = curves.iter().map(|(_, curve)| *curve).collect();
+    let curve_accounts = get_multiple_accounts(rpc, &curve_addresses).await?;
+    let bonding_curve_cache = BondingCurveCache::get_instance();
+    for ((mint, curve_address), account) in curves.iter().zip(curve_accounts) {
+        let Some(curve) = account.and_then(|account| bonding_curve_decode(account.data.get(8..)?))
+        else {
+            continue;
+        };
+        if curve.complete {
+            continue;
+        }
+        bonding_curve_cache
+            .insert(*mint, Arc::new(BondingCurveAccount::from_bonding_curve(mint, &curve)));
+        let quote = MintQuote {
+            dex_type: DexType::PumpFun,
+            market: *curve_address,
+            price: price::pumpfun::price_token_in_sol(
+                curve.virtual_sol_reserves,
+                curve.virtual_token_reserves,
+            ),
+            sol_reserves: curve.real_sol_reserves,
+        };
+        quotes.insert(*mint, quote);
+    }
+
+    let graduated: Vec<Pubkey> =
+        mints.iter().filter(|mint| !quotes.contains_key(*mint)).copied().collect();
+    if graduated.is_empty() {
+        return Ok(quotes);
+    }
+    let pool_addresses = join_all(graduated.iter().map(|mint| find_pool(rpc, mint))).await;
+    let graduated: Vec<(Pubkey, Pubkey)> = graduated
+        .into_iter()
+        .zip(pool_addresses)
+        .filter_map(|(mint, pool_address)| Some((mint, pool_address.ok()?)))
+        .collect();
+
+    // Pools and mint infos the caches do not have yet, in one request
+    let reserves_cache = PoolReservesCache::get_instance();
+    let mint_info_cache = MintInfoCache::get_instance();
+    let mut missing: Vec<Pubkey> = graduated
+        .iter()
+        .filter(|(_, pool_address)| reserves_cache.get_pool(pool_address).is_none())
+        .map(|(_, pool_address)| *pool_address)
+        .collect();
+    missing.extend(
+        graduated.iter().map(|(mint, _)| *mint).filter(|mint| mint_info_cache.get(mint).is_none()),
+    );
+    let missing_accounts = get_multiple_accounts(rpc, &missing).await?;
+    for (address, account) in missing.iter().zip(missing_accounts) {
+        let Some(account) = account else { continue };
+        if account.owner == AMM_PROGRAM {
+            if let Some(pool) = account.data.get(8..).and_then(pool_decode) {
+                reserves_cache.insert_pool(*address, pool);
+            }
+        } else if let Ok(info) = MintInfo::from_account(&account) {
+            mint_info_cache.insert(*address, info);
+        }
+    }
+
+    let pools: Vec<(Pubkey, Pubkey, Pool)> = graduated
+        .into_iter()
+        .filter_map(|(mint, pool_address)| {
+            Some((mint, pool_address, reserves_cache.get_pool(&pool_address)?))
+        })
+        .collect();
+    let vaults: Vec<Pubkey> = pools
+        .iter()
+        .flat_map(|(_, _, pool)| [pool.pool_base_token_account, pool.pool_quote_token_account])
+        .collect();
+    let vault_accounts = get_multiple_accounts(rpc, &vaults).await?;
+    for ((mint, pool_address, pool), vaults) in pools.iter().zip(vault_accounts.chunks(2)) {
+        let reserve = |index: usize| {
+            vaults[index].as_ref().and_then(parse_token_account_amount).ok_or_else(|| {
+                anyhow!("Vault of PumpSwap pool {} not found or invalid", pool_address)
+            })
+        };
+        let (base_reserve, quote_reserve) = (reserve(0)?, reserve(1)?);
+        reserves_cache.insert_reserves(*pool_address, base_reserve, quote_reserve);
+        let Some(info) = mint_info_cache.get(mint) else { continue };
+        let (price, sol_reserves) = if pool.quote_mint == WSOL_TOKEN_ACCOUNT {
+            let price = price::pumpswap::price_base_in_quote(
+                base_reserve,
+                quote_reserve,
+                info.decimals,
+                SOL_DECIMALS,
+            );
+            (price, quote_reserve)
+        } else if pool.base_mint == WSOL_TOKEN_ACCOUNT {
+            let price = price::pumpswap::price_quote_in_base(
+                base_reserve,
+                quote_reserve,
+                SOL_DECIMALS,
+                info.decimals,
+            );
+            (price, base_reserve)
+        } else {
+            continue;
+        };
+        let quote =
+            MintQuote { dex_type: DexType::PumpSwap, market: *pool_address, price, sol_reserves };
+        quotes.insert(*mint, quote);
+    }
+    Ok(quotes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockRpc;
+    use solana_sdk::account::Account;
+
+    #[tokio::test]
+    async fn test_quote_bonding_curve() {
+        let mint = Pubkey::new_unique();
+        let mut data = vec![0u8; 8];
+        for reserve in [1_000_000_000_000u64, 30_000_000_000, 800_000_000_000, 2_000_000_000] {
+            data.extend_from_slice(&reserve.to_le_bytes());
+        }
+        data.extend_from_slice(&1_000_000_000_000_000u64.to_le_bytes());
+        data.push(0);
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        let curve = Account {
+            lamports: 1,
+            data,
+            owner: crate::constants::pumpfun::accounts::PUMPFUN,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let curve_address = get_bonding_curve_pda(&mint).unwrap();
+        let rpc = MockRpc::new().with_account(curve_address, curve).client();
+
+        let quotes = quote_many(&rpc, &[mint]).await.unwrap();
+        let quote = &quotes[&mint];
+        assert_eq!((quote.dex_type.clone(), quote.market), (DexType::PumpFun, curve_address));
+        assert_eq!(quote.sol_reserves, 2_000_000_000);
+        assert!((quote.price - 0.00003).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,137 @@
+//! Priority fees from the fee market of the trade's own accounts
+//!
+//! The global fee market says little about what it takes to land on a hot bonding curve or
+//! pool: the scheduler orders transactions per write-locked account, so what counts is what
+//! other transactions locking the same accounts pay. With a `ContentionFeeConfig` set on
+//! `SolanaTrade`, the executors ask `getRecentPrioritizationFees` for the writable accounts
+//! of the built instructions and raise the compute unit price to a percentile of the answer.
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::common::{PriorityFee, SolanaRpcClient};
+
+/// Most accounts `getRecentPrioritizationFees` takes
+const MAX_FEE_ACCOUNTS: usize = 128;
+
+/// How the compute unit price follows the fees paid on the trade's accounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentionFeeConfig {
+    /// Percentile of the recent per-slot fees to pay, 0-100
+    pub percentile: u8,
+    /// Micro-lamports per compute unit never exceeded
+    pub max_unit_price: u64,
+}
+
+impl Default for ContentionFeeConfig {
+    fn default() -> Self {
+        Self { percentile: 75, max_unit_price: 1_000_000 }
+    }
+}
+
+impl ContentionFeeConfig {
+    pub fn new(percentile: u8, max_unit_price: u64) -> Self {
+        Self { percentile: percentile.min(100), max_unit_price }
+    }
+}
+
+/// Writable accounts of `instructions` that are not signers, the accounts other trades
+/// contend for
+pub fn contended_accounts(instructions: &[Instruction]) -> Vec<Pubkey> {
+    let mut accounts: Vec<Pubkey> = vec![];
+    for meta in instructions.iter().flat_map(|instruction| &instruction.accounts) {
+        if meta.is_writable && !meta.is_signer && !accounts.contains(&meta.pubkey) {
+            accounts.push(meta.pubkey);
+        }
+    }
+    accounts.truncate(MAX_FEE_ACCOUNTS);
+    accounts
+}
+
+/// Compute unit price paid in `percentile` of the recent slots by transactions locking
+/// `accounts`, in micro-lamports
+pub async fn contention_unit_price(
+    rpc: &SolanaRpcClient,
+    accounts: &[Pubkey],
+    percentile: u8,
+) -> Result<u64, anyhow::Error> {
+    let fees = rpc.get_recent_prioritization_fees(accounts).await?;
+    Ok(fee_percentile(fees.into_iter().map(|fee| fee.prioritization_fee).collect(), percentile))
+}
+
+/// Raise the unit prices of `priority_fee` to what the accounts of `instructions` take
+///
+/// The configured prices stay the floor. Without an answer from `rpc` they are kept as
+/// they are, a failed lookup must not fail the trade.
+pub async fn apply_contention_fee(
+    rpc: &SolanaRpcClient,
+    instructions: &[Instruction],
+    config: &ContentionFeeConfig,
+    priority_fee: &mut PriorityFee,
+) {
+    let accounts = contended_accounts(instructions);
+    if accounts.is_empty() {
+        return;
+    }
+    let unit_price = match contention_unit_price(rpc, &accounts, config.percentile).await {
+        Ok(unit_price) => unit_price.min(config.max_unit_price),
+        Err(e) => {
+            tracing::warn!(error = %e, "contention fee lookup failed, using configured fees");
+            return;
+        }
+    };
+    tracing::debug!(unit_price, accounts = accounts.len(), "contention unit price");
+    priority_fee.tip_unit_price = priority_fee.tip_unit_price.max(unit_price);
+    priority_fee.rpc_unit_price = priority_fee.rpc_unit_price.max(unit_price);
+}
+
+/// Nearest-rank percentile, 0 without fees
+fn fee_percentile(mut fees: Vec<u64>, percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+    let rank = (fees.len() * percentile.min(100) as usize).div_ceil(100);
+    fees[rank.saturating_sub(1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockRpc;
+    use serde_json::json;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_sdk::instruction::AccountMeta;
+
+    #[tokio::test]
+    async fn test_apply_contention_fee() {
+        let (payer, curve) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(curve, false),
+                AccountMeta::new_readonly(Pubkey::new_unique(), false),
+                AccountMeta::new(curve, false),
+            ],
+        );
+
+        let fees: Vec<_> = [0u64, 5_000, 1_000, 20_000]
+            .iter()
+            .enumerate()
+            .map(|(slot, fee)| json!({ "slot": slot, "prioritizationFee": fee }))
+            .collect();
+        let rpc = MockRpc::new()
+            .with_response(RpcRequest::GetRecentPrioritizationFees, json!(fees))
+            .client();
+        let mut priority_fee =
+            PriorityFee { tip_unit_price: 2_000, rpc_unit_price: 10_000, ..Default::default() };
+        let config = ContentionFeeConfig::new(75, 15_000);
+        let instructions = [instruction];
+        assert_eq!(contended_accounts(&instructions), vec![curve]);
+        apply_contention_fee(&rpc, &instructions, &config, &mut priority_fee).await;
+        assert_eq!((priority_fee.tip_unit_price, priority_fee.rpc_unit_price), (5_000, 10_000));
+        assert_eq!(fee_percentile(vec![0, 5_000, 1_000, 20_000], 100), 20_000);
+        assert_eq!(fee_percentile(vec![], 50), 0);
+    }
+}
@@ -0,0 +1,216 @@
+//! Loaded accounts data size limit sized to the trade
+//!
+//! Buys request a fixed 256KB loaded accounts data size limit. The scheduler prices
+//! transactions by the requested limit, so a limit close to what the transaction actually
+//! loads costs less and is scheduled earlier. The accounts a trade loads are known once its
+//! instructions are built; [`loaded_data_size`] adds up their sizes, remembering the sizes it
+//! fetched, and [`auto_data_size_limit`] adds headroom for what the instructions do not show.
+
+use solana_sdk::{
+    account::Account, bpf_loader_upgradeable::UpgradeableLoaderState, instruction::Instruction,
+    pubkey::Pubkey,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use super::batch_fetcher::get_multiple_accounts;
+use crate::common::SolanaRpcClient;
+
+/// Bytes every loaded account counts for on top of its data
+pub const TRANSACTION_ACCOUNT_BASE_SIZE: u32 = 64;
+
+/// Largest loaded accounts data size limit a transaction can request
+pub const MAX_LOADED_ACCOUNTS_DATA_SIZE_BYTES: u32 = 64 * 1024 * 1024;
+
+/// Default headroom of `auto_data_size_limit`, in basis points of the loaded size
+pub const DEFAULT_DATA_SIZE_HEADROOM_BPS: u64 = 2_000;
+
+static INSTANCE: OnceLock<Arc<AccountSizeCache>> = OnceLock::new();
+
+/// Loaded accounts data size limit of a buy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataSizeLimit {
+    /// Bytes to request, 0 for the 256KB default
+    Fixed(u32),
+    /// The bytes the trade loads plus `headroom_bps` of them, see [`auto_data_size_limit`]
+    Auto { headroom_bps: u64 },
+}
+
+impl Default for DataSizeLimit {
+    fn default() -> Self {
+        DataSizeLimit::Fixed(0)
+    }
+}
+
+impl DataSizeLimit {
+    /// `Auto` with `DEFAULT_DATA_SIZE_HEADROOM_BPS`
+    pub fn auto() -> Self {
+        DataSizeLimit::Auto { headroom_bps: DEFAULT_DATA_SIZE_HEADROOM_BPS }
+    }
+
+    /// The fixed limit, 0 when the executor decides
+    pub fn fixed_limit(&self) -> u32 {
+        match self {
+            DataSizeLimit::Fixed(limit) => *limit,
+            DataSizeLimit::Auto { .. } => 0,
+        }
+    }
+}
+
+/// Data sizes of accounts, with the program data size for upgradeable programs
+///
+/// Only accounts that exist are cached; an account a trade creates is fetched again until
+/// it exists.
+#[derive(Debug, Default)]
+pub struct AccountSizeCache {
+    sizes: Mutex<HashMap<Pubkey, u32>>,
+}
+
+impl AccountSizeCache {
+    pub fn get_instance() -> Arc<Self> {
+        INSTANCE.get_or_init(|| Arc::new(Self::default())).clone()
+    }
+
+    pub fn get(&self, account: &Pubkey) -> Option<u32> {
+        self.sizes.lock().unwrap().get(account).copied()
+    }
+
+    pub fn insert(&self, account: Pubkey, size: u32) {
+        self.sizes.lock().unwrap().insert(account, size);
+    }
+
+    pub fn invalidate(&self, account: &Pubkey) {
+        self.sizes.lock().unwrap().remove(account);
+    }
+
+    pub fn clear(&self) {
+        self.sizes.lock().unwrap().clear();
+    }
+}
+
+/// Bytes a transaction of `instructions` loads, counting `extra_accounts` (the fee payer,
+/// lookup tables, ...) as well
+///
+/// Accounts missing from `AccountSizeCache` are fetched in one batch. An upgradeable
+/// program counts with its program data account, which the runtime loads along with it.
+pub async fn loaded_data_size(
+    rpc: &SolanaRpcClient,
+    instructions: &[Instruction],
+    extra_accounts: &[Pubkey],
+) -> Result<u32, anyhow::Error> {
+    let mut accounts: Vec<Pubkey> = extra_accounts.to_vec();
+    for instruction in instructions {
+        let keys = instruction.accounts.iter().map(|meta| meta.pubkey);
+        for key in std::iter::once(instruction.program_id).chain(keys) {
+            if !accounts.contains(&key) {
+                accounts.push(key);
+            }
+        }
+    }
+
+    let cache = AccountSizeCache::get_instance();
+    let missing: Vec<Pubkey> =
+        accounts.iter().filter(|account| cache.get(account).is_none()).copied().collect();
+    if !missing.is_empty() {
+        let fetched = get_multiple_accounts(rpc, &missing).await?;
+        let programs: Vec<(Pubkey, Account, Pubkey)> = missing
+            .iter()
+            .zip(&fetched)
+            .filter_map(|(address, account)| {
+                let account = account.as_ref()?;
+                Some((*address, account.clone(), program_data_address(account)?))
+            })
+            .collect();
+        let program_data_addresses: Vec<Pubkey> =
+            programs.iter().map(|(_, _, program_data)| *program_data).collect();
+        let program_data = get_multiple_accounts(rpc, &program_data_addresses).await?;
+        for (address, account) in missing.iter().zip(&fetched) {
+            if let Some(account) = account {
+                cache.insert(*address, account.data.len() as u32);
+            }
+        }
+        for ((address, account, _), data) in programs.iter().zip(program_data) {
+            let data_size = data.map(|data| data.data.len() as u32).unwrap_or(0);
+            let size = account.data.len() as u32 + TRANSACTION_ACCOUNT_BASE_SIZE + data_size;
+            cache.insert(*address, size);
+        }
+    }
+
+    Ok(accounts
+        .iter()
+        .map(|account| cache.get(account).unwrap_or(0) + TRANSACTION_ACCOUNT_BASE_SIZE)
+        .sum())
+}
+
+/// Loaded accounts data size limit for `instructions`: their loaded size plus
+/// `headroom_bps`, capped at `MAX_LOADED_ACCOUNTS_DATA_SIZE_BYTES`
+pub async fn auto_data_size_limit(
+    rpc: &SolanaRpcClient,
+    instructions: &[Instruction],
+    extra_accounts: &[Pubkey],
+    headroom_bps: u64,
+) -> Result<u32, anyhow::Error> {
+    let size = loaded_data_size(rpc, instructions, extra_accounts).await? as u64;
+    let limit = size * (10_000 + headroom_bps) / 10_000;
+    Ok(limit.min(MAX_LOADED_ACCOUNTS_DATA_SIZE_BYTES as u64) as u32)
+}
+
+/// Program data account of an upgradeable program account
+fn program_data_address(account: &Account) -> Option<Pubkey> {
+    if !account.executable || account.owner != solana_sdk::bpf_loader_upgradeable::id() {
+        return None;
+    }
+    match bincode::deserialize(&account.data).ok()? {
+        UpgradeableLoaderState::Program { programdata_address } => Some(programdata_address),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockRpc;
+    use solana_sdk::instruction::AccountMeta;
+
+    #[tokio::test]
+    async fn test_loaded_data_size() {
+        let (program, program_data, curve) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let program_account = Account {
+            lamports: 1,
+            data: bincode::serialize(&UpgradeableLoaderState::Program {
+                programdata_address: program_data,
+            })
+            .unwrap(),
+            owner: solana_sdk::bpf_loader_upgradeable::id(),
+            executable: true,
+            rent_epoch: 0,
+        };
+        let account = |size| Account {
+            lamports: 1,
+            data: vec![0; size],
+            owner: program,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let rpc = MockRpc::new()
+            .with_account(program, program_account)
+            .with_account(program_data, account(1_000))
+            .with_account(curve, account(150))
+            .client();
+        let created = Pubkey::new_unique();
+        let instructions = [Instruction::new_with_bytes(
+            program,
+            &[],
+            vec![AccountMeta::new(curve, false), AccountMeta::new(created, false)],
+        )];
+
+        // 36 byte program account with its program data, the curve and the missing account
+        let expected = (36 + 64 + 1_000) + 150 + 3 * 64;
+        let size = loaded_data_size(&rpc, &instructions, &[]).await.unwrap();
+        assert_eq!(size, expected);
+        assert_eq!(AccountSizeCache::get_instance().get(&created), None);
+        let limit = auto_data_size_limit(&rpc, &instructions, &[], 1_000).await.unwrap();
+        assert_eq!(limit, expected * 11 / 10);
+    }
+}
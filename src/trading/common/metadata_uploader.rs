@@ -0,0 +1,352 @@
+use crate::common::proxy::ProxyConfig;
+use anyhow::anyhow;
+use reqwest::{multipart, Client, ClientBuilder};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha384};
+use solana_sdk::{signature::Keypair, signer::Signer};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const PINATA_API_URL: &str = "https://api.pinata.cloud";
+pub const PINATA_GATEWAY_URL: &str = "https://gateway.pinata.cloud";
+pub const NFT_STORAGE_API_URL: &str = "https://api.nft.storage";
+pub const NFT_STORAGE_GATEWAY_URL: &str = "https://nftstorage.link";
+pub const IRYS_NODE_URL: &str = "https://node1.irys.xyz";
+pub const IRYS_GATEWAY_URL: &str = "https://gateway.irys.xyz";
+
+/// Off-chain token metadata, uploaded as the JSON the token's `uri` points to
+#[derive(Debug, Clone, Default)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    /// Path of the token image on disk
+    pub image_path: String,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+    pub website: Option<String>,
+}
+
+/// Storage backend for token images and metadata JSON
+#[async_trait::async_trait]
+pub trait MetadataUploader: Send + Sync {
+    /// Upload raw bytes and return their public URL
+    async fn upload_bytes(
+        &self,
+        file_name: &str,
+        content_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<String, anyhow::Error>;
+
+    /// Upload a JSON document and return its public URL
+    async fn upload_json(&self, value: &Value) -> Result<String, anyhow::Error> {
+        self.upload_bytes("metadata.json", "application/json", serde_json::to_vec(value)?).await
+    }
+
+    /// Upload the image, then the metadata JSON referencing it, and return the metadata URI
+    async fn upload_metadata(&self, metadata: &TokenMetadata) -> Result<String, anyhow::Error> {
+        let bytes = tokio::fs::read(&metadata.image_path).await?;
+        let path = Path::new(&metadata.image_path);
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("image");
+        let image = self.upload_bytes(file_name, image_content_type(path), bytes).await?;
+
+        let mut json = json!({
+            "name": metadata.name,
+            "symbol": metadata.symbol,
+            "description": metadata.description,
+            "image": image,
+            "showName": true,
+        });
+        for (key, value) in [
+            ("twitter", &metadata.twitter),
+            ("telegram", &metadata.telegram),
+            ("website", &metadata.website),
+        ] {
+            if let Some(value) = value {
+                json[key] = json!(value);
+            }
+        }
+        self.upload_json(&json).await
+    }
+}
+
+/// Backend selection for `SolanaTrade::with_metadata_uploader`
+#[derive(Debug, Clone)]
+pub enum MetadataUploaderConfig {
+    /// Pinata IPFS pinning, authenticated with a JWT; `gateway` defaults to the public Pinata gateway
+    Pinata { jwt: String, gateway: Option<String> },
+    /// NFT.Storage, authenticated with an API key
+    NftStorage { api_key: String },
+    /// Irys (Arweave), uploads are signed and paid for by `keypair`; `node` defaults to node1
+    Irys { keypair: Arc<Keypair>, node: Option<String> },
+}
+
+impl MetadataUploaderConfig {
+    pub fn build(&self) -> Arc<dyn MetadataUploader> {
+        self.build_with_client(http_client())
+    }
+
+    /// Like `build`, uploading through `proxy`
+    pub fn build_with_proxy(
+        &self,
+        proxy: &ProxyConfig,
+    ) -> Result<Arc<dyn MetadataUploader>, anyhow::Error> {
+        let client = proxy
+            .apply(http_client_builder())?
+            .build()
+            .map_err(|e| anyhow!("Failed to build upload client: {}", e))?;
+        Ok(self.build_with_client(client))
+    }
+
+    fn build_with_client(&self, http_client: Client) -> Arc<dyn MetadataUploader> {
+        match self {
+            MetadataUploaderConfig::Pinata { jwt, gateway } => Arc::new(PinataUploader {
+                jwt: jwt.clone(),
+                gateway: gateway
+                    .as_deref()
+                    .unwrap_or(PINATA_GATEWAY_URL)
+                    .trim_end_matches('/')
+                    .to_string(),
+                http_client,
+            }),
+            MetadataUploaderConfig::NftStorage { api_key } => {
+                Arc::new(NftStorageUploader { api_key: api_key.clone(), http_client })
+            }
+            MetadataUploaderConfig::Irys { keypair, node } => Arc::new(IrysUploader {
+                keypair: keypair.clone(),
+                node: node.as_deref().unwrap_or(IRYS_NODE_URL).trim_end_matches('/').to_string(),
+                http_client,
+            }),
+        }
+    }
+}
+
+fn http_client_builder() -> ClientBuilder {
+    Client::builder().timeout(Duration::from_secs(60))
+}
+
+fn http_client() -> Client {
+    http_client_builder().build().unwrap_or_default()
+}
+
+fn image_content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+async fn read_json_response(response: reqwest::Response) -> Result<Value, anyhow::Error> {
+    let status = response.status();
+    let text = response.text().await?;
+    if !status.is_success() {
+        return Err(anyhow!("Upload failed with status {}: {}", status, text));
+    }
+    serde_json::from_str(&text).map_err(|_| anyhow!("Invalid upload response: {}", text))
+}
+
+/// Pinata IPFS pinning service
+pub struct PinataUploader {
+    jwt: String,
+    gateway: String,
+    http_client: Client,
+}
+
+impl PinataUploader {
+    pub fn new(jwt: String, gateway: String) -> Self {
+        Self { jwt, gateway: gateway.trim_end_matches('/').to_string(), http_client: http_client() }
+    }
+}
+
+#[async_trait::async_trait]
+impl MetadataUploader for PinataUploader {
+    async fn upload_bytes(
+        &self,
+        file_name: &str,
+        content_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<String, anyhow::Error> {
+        let part = multipart::Part::bytes(bytes)
+            .file_name(file_name.to_string())
+            .mime_str(content_type)?;
+        let response = self
+            .http_client
+            .post(format!("{}/pinning/pinFileToIPFS", PINATA_API_URL))
+            .bearer_auth(&self.jwt)
+            .multipart(multipart::Form::new().part("file", part))
+            .send()
+            .await?;
+        let json = read_json_response(response).await?;
+        let hash =
+            json["IpfsHash"].as_str().ok_or_else(|| anyhow!("Missing IpfsHash: {}", json))?;
+        Ok(format!("{}/ipfs/{}", self.gateway, hash))
+    }
+
+    async fn upload_json(&self, value: &Value) -> Result<String, anyhow::Error> {
+        let response = self
+            .http_client
+            .post(format!("{}/pinning/pinJSONToIPFS", PINATA_API_URL))
+            .bearer_auth(&self.jwt)
+            .json(&json!({ "pinataContent": value }))
+            .send()
+            .await?;
+        let json = read_json_response(response).await?;
+        let hash =
+            json["IpfsHash"].as_str().ok_or_else(|| anyhow!("Missing IpfsHash: {}", json))?;
+        Ok(format!("{}/ipfs/{}", self.gateway, hash))
+    }
+}
+
+/// NFT.Storage IPFS service
+pub struct NftStorageUploader {
+    api_key: String,
+    http_client: Client,
+}
+
+impl NftStorageUploader {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key, http_client: http_client() }
+    }
+}
+
+#[async_trait::async_trait]
+impl MetadataUploader for NftStorageUploader {
+    async fn upload_bytes(
+        &self,
+        _file_name: &str,
+        content_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<String, anyhow::Error> {
+        let response = self
+            .http_client
+            .post(format!("{}/upload", NFT_STORAGE_API_URL))
+            .bearer_auth(&self.api_key)
+            .header("Content-Type", content_type)
+            .body(bytes)
+            .send()
+            .await?;
+        let json = read_json_response(response).await?;
+        let cid = json["value"]["cid"].as_str().ok_or_else(|| anyhow!("Missing cid: {}", json))?;
+        Ok(format!("{}/ipfs/{}", NFT_STORAGE_GATEWAY_URL, cid))
+    }
+}
+
+/// Irys (Arweave) uploader
+///
+/// Data is posted as ANS-104 data items signed with the Solana keypair, which must hold
+/// an Irys balance for uploads above the free size limit.
+pub struct IrysUploader {
+    keypair: Arc<Keypair>,
+    node: String,
+    http_client: Client,
+}
+
+/// ANS-104 signature type for ed25519 (Solana) signers
+const ED25519_SIGNATURE_TYPE: u16 = 2;
+
+impl IrysUploader {
+    pub fn new(keypair: Arc<Keypair>, node: String) -> Self {
+        Self { keypair, node: node.trim_end_matches('/').to_string(), http_client: http_client() }
+    }
+
+    /// Build a signed ANS-104 data item without target or anchor
+    fn create_data_item(&self, tags: &[(&str, &str)], data: &[u8]) -> Vec<u8> {
+        let owner = self.keypair.pubkey().to_bytes();
+        let tag_bytes = encode_avro_tags(tags);
+        let message = deep_hash_list(&[
+            b"dataitem",
+            b"1",
+            ED25519_SIGNATURE_TYPE.to_string().as_bytes(),
+            &owner,
+            &[],
+            &[],
+            &tag_bytes,
+            data,
+        ]);
+        let signature = self.keypair.sign_message(&message);
+
+        let mut item = Vec::with_capacity(2 + 64 + 32 + 2 + 16 + tag_bytes.len() + data.len());
+        item.extend_from_slice(&ED25519_SIGNATURE_TYPE.to_le_bytes());
+        item.extend_from_slice(signature.as_ref());
+        item.extend_from_slice(&owner);
+        item.push(0); // no target
+        item.push(0); // no anchor
+        item.extend_from_slice(&(tags.len() as u64).to_le_bytes());
+        item.extend_from_slice(&(tag_bytes.len() as u64).to_le_bytes());
+        item.extend_from_slice(&tag_bytes);
+        item.extend_from_slice(data);
+        item
+    }
+}
+
+#[async_trait::async_trait]
+impl MetadataUploader for IrysUploader {
+    async fn upload_bytes(
+        &self,
+        _file_name: &str,
+        content_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<String, anyhow::Error> {
+        let item = self.create_data_item(&[("Content-Type", content_type)], &bytes);
+        let response = self
+            .http_client
+            .post(format!("{}/tx/solana", self.node))
+            .header("Content-Type", "application/octet-stream")
+            .body(item)
+            .send()
+            .await?;
+        let json = read_json_response(response).await?;
+        let id = json["id"].as_str().ok_or_else(|| anyhow!("Missing id: {}", json))?;
+        Ok(format!("{}/{}", IRYS_GATEWAY_URL, id))
+    }
+}
+
+/// Avro encoding of the ANS-104 tag array (`[{ name: bytes, value: bytes }]`)
+fn encode_avro_tags(tags: &[(&str, &str)]) -> Vec<u8> {
+    if tags.is_empty() {
+        return Vec::new();
+    }
+    let mut out = Vec::new();
+    encode_avro_long(&mut out, tags.len() as i64);
+    for (name, value) in tags {
+        encode_avro_long(&mut out, name.len() as i64);
+        out.extend_from_slice(name.as_bytes());
+        encode_avro_long(&mut out, value.len() as i64);
+        out.extend_from_slice(value.as_bytes());
+    }
+    out.push(0);
+    out
+}
+
+fn encode_avro_long(out: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n & !0x7f != 0 {
+        out.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+/// Arweave deep hash (SHA-384) of a list of byte blobs
+fn deep_hash_list(chunks: &[&[u8]]) -> [u8; 48] {
+    let mut acc: [u8; 48] = Sha384::digest(format!("list{}", chunks.len())).into();
+    for chunk in chunks {
+        let mut hasher = Sha384::new();
+        hasher.update(acc);
+        hasher.update(deep_hash_blob(chunk));
+        acc = hasher.finalize().into();
+    }
+    acc
+}
+
+fn deep_hash_blob(data: &[u8]) -> [u8; 48] {
+    let mut hasher = Sha384::new();
+    hasher.update(Sha384::digest(format!("blob{}", data.len())));
+    hasher.update(Sha384::digest(data));
+    hasher.finalize().into()
+}
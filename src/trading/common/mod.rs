@@ -1,12 +1,32 @@
 pub mod nonce_manager;
 pub mod transaction_builder;
+pub mod transaction_template;
 pub mod compute_budget_manager;
+pub mod contention_fee;
+pub mod data_size_limit;
 pub mod address_lookup_manager;
 pub mod utils;
+pub mod batch_fetcher;
+#[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+pub mod batch_quote;
+pub mod ata_manager;
+pub mod metadata_uploader;
+pub mod offline_signing;
+pub mod token_metadata;
 
 // Re-export commonly used functions
 pub use nonce_manager::*;
 pub use transaction_builder::*;
+pub use transaction_template::*;
 pub use compute_budget_manager::*;
+pub use contention_fee::*;
+pub use data_size_limit::*;
 pub use address_lookup_manager::*;
-pub use utils::*;
\ No newline at end of file
+pub use utils::*;
+pub use batch_fetcher::*;
+#[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+pub use batch_quote::*;
+pub use ata_manager::*;
+pub use metadata_uploader::*;
+pub use offline_signing::*;
+pub use token_metadata::*;
\ No newline at end of file
@@ -1,9 +1,9 @@
 use anyhow::anyhow;
 use solana_hash::Hash;
-use solana_sdk::{instruction::Instruction, signature::Keypair, signer::Signer};
+use solana_sdk::instruction::Instruction;
 use solana_system_interface::instruction::advance_nonce_account;
 
-use crate::common::nonce_cache::NonceCache;
+use crate::common::{nonce_cache::NonceCache, TradeSigner};
 
 /// 添加nonce消费指令到指令集合中
 ///
@@ -12,7 +12,7 @@ use crate::common::nonce_cache::NonceCache;
 /// 成功时会锁定并标记nonce为已使用
 pub fn add_nonce_instruction(
     instructions: &mut Vec<Instruction>,
-    payer: &Keypair,
+    payer: &dyn TradeSigner,
 ) -> Result<(), anyhow::Error> {
     let nonce_cache = NonceCache::get_instance();
     let nonce_info = nonce_cache.get_nonce_info();
@@ -0,0 +1,141 @@
+//! Offline signing with durable nonces
+//!
+//! For setups where keys never touch the trading box: the box runs `SolanaTrade` with a
+//! `NullSigner` of the wallet's pubkey and builds the transactions with
+//! `SolanaTrade::prepare_offline`. A separate signer service signs them with
+//! [`sign_serialized`] and the box submits the signed transactions with
+//! `SolanaTrade::submit_offline`.
+//!
+//! The transactions use a durable nonce instead of a recent blockhash, so they stay valid
+//! however long the signer takes. There is one transaction per swqos provider, each tipping
+//! its provider. All of them advance the same nonce, so at most one can land.
+
+use anyhow::anyhow;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_sdk::{pubkey::Pubkey, signer::Signer, transaction::VersionedTransaction};
+
+use crate::swqos::SwqosType;
+
+/// Transaction built for an offline signer, to be submitted through `swqos_type`
+#[derive(Debug, Clone)]
+pub struct OfflineTransaction {
+    pub swqos_type: SwqosType,
+    /// Signature slots that were not signed yet hold the default signature
+    pub transaction: VersionedTransaction,
+}
+
+impl OfflineTransaction {
+    pub fn new(swqos_type: SwqosType, transaction: VersionedTransaction) -> Self {
+        Self { swqos_type, transaction }
+    }
+
+    /// The transaction as base64 encoded bincode, the format `sign_serialized` takes
+    pub fn serialize(&self) -> Result<String, anyhow::Error> {
+        Ok(STANDARD.encode(bincode::serialize(&self.transaction)?))
+    }
+
+    /// Take over the signatures of `signed`, the output of `sign_serialized`
+    ///
+    /// Fails when the signer changed the message instead of only signing it.
+    pub fn apply_signed(&mut self, signed: &str) -> Result<(), anyhow::Error> {
+        let signed = deserialize(signed)?;
+        if signed.message != self.transaction.message {
+            return Err(anyhow!("Signed transaction has a different message"));
+        }
+        self.transaction = signed;
+        Ok(())
+    }
+
+    /// Sign in place as `signer`, which must be one of the required signers
+    pub fn sign(&mut self, signer: &dyn Signer) -> Result<(), anyhow::Error> {
+        sign_transaction(&mut self.transaction, signer)
+    }
+
+    /// Required signers without a valid signature yet
+    pub fn missing_signers(&self) -> Vec<Pubkey> {
+        let keys = self.transaction.message.static_account_keys();
+        self.transaction
+            .verify_with_results()
+            .into_iter()
+            .zip(keys)
+            .filter(|(valid, _)| !valid)
+            .map(|(_, key)| *key)
+            .collect()
+    }
+}
+
+/// Sign a transaction serialized by `OfflineTransaction::serialize` as each of `signers`
+///
+/// Meant for the signer service; other signature slots are left as they are, so a
+/// transaction can be passed through several signers.
+pub fn sign_serialized(
+    transaction: &str,
+    signers: &[&dyn Signer],
+) -> Result<String, anyhow::Error> {
+    let mut transaction = deserialize(transaction)?;
+    for signer in signers {
+        sign_transaction(&mut transaction, *signer)?;
+    }
+    Ok(STANDARD.encode(bincode::serialize(&transaction)?))
+}
+
+fn deserialize(transaction: &str) -> Result<VersionedTransaction, anyhow::Error> {
+    Ok(bincode::deserialize(&STANDARD.decode(transaction)?)?)
+}
+
+fn sign_transaction(
+    transaction: &mut VersionedTransaction,
+    signer: &dyn Signer,
+) -> Result<(), anyhow::Error> {
+    let pubkey = signer.pubkey();
+    let message = &transaction.message;
+    let signers =
+        &message.static_account_keys()[..message.header().num_required_signatures as usize];
+    let index = signers
+        .iter()
+        .position(|key| *key == pubkey)
+        .ok_or_else(|| anyhow!("{} is not a signer of the transaction", pubkey))?;
+    let signature = signer.try_sign_message(&message.serialize())?;
+    transaction.signatures[index] = signature;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_hash::Hash;
+    use solana_sdk::{
+        message::{v0, VersionedMessage},
+        signature::Keypair,
+        signer::null_signer::NullSigner,
+    };
+    use solana_system_interface::instruction::transfer;
+
+    #[test]
+    fn test_sign_serialized() {
+        let payer = Keypair::new();
+        let authority = Keypair::new();
+        let instructions = [
+            solana_system_interface::instruction::advance_nonce_account(
+                &Pubkey::new_unique(),
+                &authority.pubkey(),
+            ),
+            transfer(&payer.pubkey(), &Pubkey::new_unique(), 1),
+        ];
+        let message =
+            v0::Message::try_compile(&payer.pubkey(), &instructions, &[], Hash::new_unique())
+                .unwrap();
+        let null_signers = [NullSigner::new(&payer.pubkey()), NullSigner::new(&authority.pubkey())];
+        let transaction =
+            VersionedTransaction::try_new(VersionedMessage::V0(message), &null_signers).unwrap();
+        let mut offline = OfflineTransaction::new(SwqosType::Default, transaction);
+        assert_eq!(offline.missing_signers(), vec![payer.pubkey(), authority.pubkey()]);
+
+        let signed = sign_serialized(&offline.serialize().unwrap(), &[&payer]).unwrap();
+        offline.apply_signed(&signed).unwrap();
+        assert_eq!(offline.missing_signers(), vec![authority.pubkey()]);
+        offline.sign(&authority).unwrap();
+        assert!(offline.missing_signers().is_empty());
+        assert!(offline.sign(&Keypair::new()).is_err());
+    }
+}
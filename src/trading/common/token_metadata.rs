@@ -0,0 +1,178 @@
+use anyhow::anyhow;
+use reqwest::Client;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::time::Duration;
+
+use crate::{
+    common::SolanaRpcClient,
+    constants::pumpfun::{accounts::MPL_TOKEN_METADATA, seeds::METADATA_SEED},
+};
+
+/// Default timeout for downloading the off-chain metadata JSON
+pub const DEFAULT_METADATA_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Name, symbol and URI stored in the Metaplex metadata account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnChainMetadata {
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+/// Off-chain metadata JSON the URI points to
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OffChainMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub twitter: Option<String>,
+    #[serde(default)]
+    pub telegram: Option<String>,
+    #[serde(default)]
+    pub website: Option<String>,
+}
+
+impl OffChainMetadata {
+    pub fn has_image(&self) -> bool {
+        self.image.as_deref().is_some_and(|image| !image.is_empty())
+    }
+
+    /// At least one of twitter / telegram / website is set
+    pub fn has_socials(&self) -> bool {
+        [&self.twitter, &self.telegram, &self.website]
+            .iter()
+            .any(|value| value.as_deref().is_some_and(|value| !value.is_empty()))
+    }
+}
+
+/// On-chain metadata plus the downloaded off-chain JSON
+#[derive(Debug, Clone)]
+pub struct FetchedTokenMetadata {
+    pub on_chain: OnChainMetadata,
+    pub off_chain: OffChainMetadata,
+}
+
+/// Decode a Metaplex metadata account up to the URI
+///
+/// Layout: key (1), update authority (32), mint (32), then borsh strings name, symbol
+/// and uri, padded with `\0` by the metadata program.
+pub fn decode_metadata_account(data: &[u8]) -> Option<OnChainMetadata> {
+    let update_authority = Pubkey::try_from(data.get(1..33)?).ok()?;
+    let mint = Pubkey::try_from(data.get(33..65)?).ok()?;
+    let mut offset = 65;
+    let mut read_string = || -> Option<String> {
+        let len = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?) as usize;
+        let bytes = data.get(offset + 4..offset + 4 + len)?;
+        offset += 4 + len;
+        Some(String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string())
+    };
+    let name = read_string()?;
+    let symbol = read_string()?;
+    let uri = read_string()?;
+    Some(OnChainMetadata { update_authority, mint, name, symbol, uri })
+}
+
+fn get_metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[METADATA_SEED, MPL_TOKEN_METADATA.as_ref(), mint.as_ref()],
+        &MPL_TOKEN_METADATA,
+    )
+    .0
+}
+
+/// Read the Metaplex metadata account of a mint
+pub async fn fetch_on_chain_metadata(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<OnChainMetadata, anyhow::Error> {
+    let account = rpc.get_account(&get_metadata_pda(mint)).await?;
+    if account.owner != MPL_TOKEN_METADATA {
+        return Err(anyhow!("Metadata account of {} is not owned by the metadata program", mint));
+    }
+    decode_metadata_account(&account.data)
+        .ok_or_else(|| anyhow!("Failed to decode metadata account of {}", mint))
+}
+
+/// Resolve a token's metadata: read the URI from the metadata PDA and download the JSON
+///
+/// Fails if the URI is empty, the download exceeds `timeout`
+/// (`DEFAULT_METADATA_FETCH_TIMEOUT` when `None`) or the body is not valid metadata JSON.
+pub async fn fetch_token_metadata(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    timeout: Option<Duration>,
+) -> Result<FetchedTokenMetadata, anyhow::Error> {
+    let on_chain = fetch_on_chain_metadata(rpc, mint).await?;
+    if on_chain.uri.is_empty() {
+        return Err(anyhow!("Token {} has no metadata URI", mint));
+    }
+    let client =
+        Client::builder().timeout(timeout.unwrap_or(DEFAULT_METADATA_FETCH_TIMEOUT)).build()?;
+    let response = client.get(&on_chain.uri).send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("Metadata request to {} failed: {}", on_chain.uri, response.status()));
+    }
+    let off_chain = response.json::<OffChainMetadata>().await?;
+    Ok(FetchedTokenMetadata { on_chain, off_chain })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Metadata account as the metadata program writes it, strings padded with `\0` to
+    /// their maximum length, followed by the fields after the URI
+    fn metadata_account(update_authority: &Pubkey, mint: &Pubkey) -> Vec<u8> {
+        let mut data = vec![4u8];
+        data.extend_from_slice(update_authority.as_ref());
+        data.extend_from_slice(mint.as_ref());
+        for (value, max_len) in
+            [("Test Token", 32), ("TEST", 10), ("https://example.com/t.json", 200)]
+        {
+            let mut bytes = value.as_bytes().to_vec();
+            bytes.resize(max_len, 0);
+            data.extend_from_slice(&(max_len as u32).to_le_bytes());
+            data.extend_from_slice(&bytes);
+        }
+        // seller fee basis points, creators, primary sale, is mutable
+        data.extend_from_slice(&[0xf4, 0x01, 0, 0, 1]);
+        data
+    }
+
+    #[test]
+    fn test_decode_metadata_account() {
+        let (update_authority, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let metadata =
+            decode_metadata_account(&metadata_account(&update_authority, &mint)).unwrap();
+        assert_eq!(
+            metadata,
+            OnChainMetadata {
+                update_authority,
+                mint,
+                name: "Test Token".to_string(),
+                symbol: "TEST".to_string(),
+                uri: "https://example.com/t.json".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_truncated_metadata_account() {
+        let data = metadata_account(&Pubkey::new_unique(), &Pubkey::new_unique());
+        // Cut inside the header, the length prefixes and the strings
+        let uri_end = 65 + 4 + 32 + 4 + 10 + 4 + 200;
+        for len in [0, 32, 64, 68, 100, 114, 118, uri_end - 1] {
+            assert_eq!(decode_metadata_account(&data[..len]), None, "len {}", len);
+        }
+        assert!(decode_metadata_account(&data[..uri_end]).is_some());
+    }
+}
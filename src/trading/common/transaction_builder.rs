@@ -1,14 +1,13 @@
 use solana_hash::Hash;
 use solana_sdk::{
     instruction::Instruction,
-    message::{v0, VersionedMessage},
+    message::{v0, AddressLookupTableAccount, VersionedMessage},
     native_token::sol_str_to_lamports,
     pubkey::Pubkey,
-    signature::Keypair,
     signer::Signer,
     transaction::VersionedTransaction,
 };
-use solana_system_interface::instruction::transfer;
+use solana_system_interface::instruction::{advance_nonce_account, transfer};
 use std::sync::Arc;
 
 use super::{
@@ -19,26 +18,39 @@ use super::{
     nonce_manager::{add_nonce_instruction, get_transaction_blockhash},
 };
 use crate::{
-    common::PriorityFee,
+    common::{PriorityFee, TradeSigner},
     trading::{
         common::{add_sell_compute_budget_instructions, add_sell_tip_compute_budget_instructions},
+        factory::DexType,
         MiddlewareManager,
     },
 };
 
+/// nonce、最多三条计算预算指令及小费转账
+const MAX_ADDED_INSTRUCTIONS: usize = 5;
+
+/// 预分配可容纳业务指令及 SDK 添加的指令的缓冲区，追加时无需重新分配
+fn instruction_buffer(
+    business_instructions: &impl Iterator<Item = Instruction>,
+) -> Vec<Instruction> {
+    Vec::with_capacity(MAX_ADDED_INSTRUCTIONS + business_instructions.size_hint().0)
+}
+
 /// 构建标准的RPC交易
 pub async fn build_rpc_transaction(
-    payer: Arc<Keypair>,
+    payer: Arc<dyn TradeSigner>,
+    fee_payer: Option<Arc<dyn TradeSigner>>,
     priority_fee: &PriorityFee,
-    business_instructions: Vec<Instruction>,
+    business_instructions: impl IntoIterator<Item = Instruction>,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     data_size_limit: u32,
     middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: String,
+    dex_type: DexType,
     is_buy: bool,
 ) -> Result<VersionedTransaction, anyhow::Error> {
-    let mut instructions = vec![];
+    let business_instructions = business_instructions.into_iter();
+    let mut instructions = instruction_buffer(&business_instructions);
 
     // 添加nonce指令
     if let Err(e) = add_nonce_instruction(&mut instructions, payer.as_ref()) {
@@ -60,11 +72,12 @@ pub async fn build_rpc_transaction(
     // 构建交易
     build_versioned_transaction(
         payer,
+        fee_payer,
         instructions,
         address_lookup_table_accounts,
         blockhash,
         middleware_manager,
-        protocol_name,
+        dex_type,
         is_buy,
     )
     .await
@@ -72,19 +85,21 @@ pub async fn build_rpc_transaction(
 
 /// 构建带小费的交易
 pub async fn build_tip_transaction(
-    payer: Arc<Keypair>,
+    payer: Arc<dyn TradeSigner>,
+    fee_payer: Option<Arc<dyn TradeSigner>>,
     priority_fee: &PriorityFee,
-    business_instructions: Vec<Instruction>,
+    business_instructions: impl IntoIterator<Item = Instruction>,
     tip_account: &Pubkey,
     tip_amount: f64,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     data_size_limit: u32,
     middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: String,
+    dex_type: DexType,
     is_buy: bool,
 ) -> Result<VersionedTransaction, anyhow::Error> {
-    let mut instructions = vec![];
+    let business_instructions = business_instructions.into_iter();
+    let mut instructions = instruction_buffer(&business_instructions);
 
     // 添加nonce指令
     if let Err(e) = add_nonce_instruction(&mut instructions, payer.as_ref()) {
@@ -97,9 +112,10 @@ pub async fn build_tip_transaction(
     // 添加业务指令
     instructions.extend(business_instructions);
 
-    // 添加小费转账指令
+    // 添加小费转账指令，由手续费支付者支付
+    let tip_payer = fee_payer.as_ref().unwrap_or(&payer).pubkey();
     instructions.push(transfer(
-        &payer.pubkey(),
+        &tip_payer,
         tip_account,
         sol_str_to_lamports(tip_amount.to_string().as_str()).unwrap_or(0),
     ));
@@ -113,11 +129,12 @@ pub async fn build_tip_transaction(
     // 构建交易
     build_versioned_transaction(
         payer,
+        fee_payer,
         instructions,
         address_lookup_table_accounts,
         blockhash,
         middleware_manager,
-        protocol_name,
+        dex_type,
         is_buy,
     )
     .await
@@ -125,47 +142,256 @@ pub async fn build_tip_transaction(
 
 /// 构建版本化交易的底层函数
 async fn build_versioned_transaction(
-    payer: Arc<Keypair>,
+    payer: Arc<dyn TradeSigner>,
+    fee_payer: Option<Arc<dyn TradeSigner>>,
     instructions: Vec<Instruction>,
     address_lookup_table_accounts: Vec<solana_sdk::message::AddressLookupTableAccount>,
     blockhash: Hash,
     middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: String,
+    dex_type: DexType,
     is_buy: bool,
 ) -> Result<VersionedTransaction, anyhow::Error> {
-    let full_instructions = match middleware_manager {
-        Some(middleware_manager) => middleware_manager
-            .apply_middlewares_process_full_instructions(instructions, protocol_name, is_buy)?,
+    let full_instructions = match &middleware_manager {
+        Some(middleware_manager) => middleware_manager.apply_middlewares_process_full_instructions(
+            instructions,
+            dex_type.clone(),
+            is_buy,
+        )?,
         None => instructions,
     };
-    let v0_message: v0::Message = v0::Message::try_compile(
-        &payer.pubkey(),
+    let transaction = compile_and_sign(
+        payer.as_ref(),
+        fee_payer.as_deref(),
         &full_instructions,
         &address_lookup_table_accounts,
         blockhash,
     )?;
 
-    let versioned_message: VersionedMessage = VersionedMessage::V0(v0_message.clone());
-    let transaction = VersionedTransaction::try_new(versioned_message, &[payer.as_ref()])?;
+    if let Some(middleware_manager) = middleware_manager {
+        middleware_manager
+            .apply_middlewares_process_transaction(&transaction, dex_type, is_buy)
+            .await?;
+    }
 
     Ok(transaction)
 }
 
+/// 编译 v0 消息并签名，设置了手续费支付者时由其作为消息的第一个签名账户
+fn compile_and_sign(
+    payer: &dyn TradeSigner,
+    fee_payer: Option<&dyn TradeSigner>,
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    blockhash: Hash,
+) -> Result<VersionedTransaction, anyhow::Error> {
+    let fee_payer = fee_payer.filter(|fee_payer| fee_payer.pubkey() != payer.pubkey());
+    let message_payer = fee_payer.unwrap_or(payer).pubkey();
+    let v0_message: v0::Message = v0::Message::try_compile(
+        &message_payer,
+        instructions,
+        address_lookup_table_accounts,
+        blockhash,
+    )?;
+
+    let versioned_message: VersionedMessage = VersionedMessage::V0(v0_message);
+    let transaction = match fee_payer {
+        // try_new 会按消息中的签名账户顺序排列签名
+        Some(fee_payer) => VersionedTransaction::try_new(versioned_message, &[fee_payer, payer])?,
+        None => payer.sign_transaction(versioned_message)?,
+    };
+    Ok(transaction)
+}
+
+/// 可组合的交易构建器
+///
+/// 接受任意用户指令以及 SDK 生成的指令（如 `build_buy_instructions` 的结果），
+/// 按买卖路径相同的方式添加 nonce、计算预算和小费指令，解析地址查找表并签名，
+/// 生成 v0 交易：
+///
+/// ```ignore
+/// let transaction = TransactionBuilder::new(payer.clone(), priority_fee)
+///     .with_instructions(buy_instructions)
+///     .with_instruction(transfer(&payer.pubkey(), &partner, fee_lamports))
+///     .with_tip(tip_account, 0.001)
+///     .with_lookup_table_key(lookup_table_key)
+///     .build(recent_blockhash)
+///     .await?;
+/// ```
+#[derive(Clone)]
+pub struct TransactionBuilder {
+    payer: Arc<dyn TradeSigner>,
+    fee_payer: Option<Arc<dyn TradeSigner>>,
+    priority_fee: PriorityFee,
+    instructions: Vec<Instruction>,
+    /// 小费账户及金额（SOL）
+    tip: Option<(Pubkey, f64)>,
+    lookup_table_key: Option<Pubkey>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    /// 账户数据加载上限，0 表示不设置
+    data_size_limit: u32,
+    use_nonce: bool,
+    /// 指定的 durable nonce：nonce 账户、授权账户及当前 nonce 值
+    durable_nonce: Option<(Pubkey, Pubkey, Hash)>,
+}
+
+impl TransactionBuilder {
+    pub fn new(payer: Arc<dyn TradeSigner>, priority_fee: PriorityFee) -> Self {
+        Self {
+            payer,
+            fee_payer: None,
+            priority_fee,
+            instructions: vec![],
+            tip: None,
+            lookup_table_key: None,
+            lookup_tables: vec![],
+            data_size_limit: 0,
+            use_nonce: false,
+            durable_nonce: None,
+        }
+    }
+
+    /// 由手续费支付者支付交易费和小费
+    pub fn with_fee_payer(mut self, fee_payer: Option<Arc<dyn TradeSigner>>) -> Self {
+        self.fee_payer = fee_payer;
+        self
+    }
+
+    /// 追加一条指令
+    pub fn with_instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// 按顺序追加多条指令
+    pub fn with_instructions(
+        mut self,
+        instructions: impl IntoIterator<Item = Instruction>,
+    ) -> Self {
+        self.instructions.extend(instructions);
+        self
+    }
+
+    /// 向 `tip_account` 支付 `tip_amount` SOL 小费，并使用小费路径的计算预算
+    pub fn with_tip(mut self, tip_account: Pubkey, tip_amount: f64) -> Self {
+        self.tip = Some((tip_account, tip_amount));
+        self
+    }
+
+    /// 从 `AddressLookupTableCache` 解析的地址查找表
+    pub fn with_lookup_table_key(mut self, lookup_table_key: Option<Pubkey>) -> Self {
+        self.lookup_table_key = lookup_table_key;
+        self
+    }
+
+    /// 直接提供的地址查找表
+    pub fn with_lookup_tables(mut self, lookup_tables: Vec<AddressLookupTableAccount>) -> Self {
+        self.lookup_tables = lookup_tables;
+        self
+    }
+
+    pub fn with_data_size_limit(mut self, data_size_limit: u32) -> Self {
+        self.data_size_limit = data_size_limit;
+        self
+    }
+
+    /// 使用 NonceCache 中的 durable nonce 代替 recent blockhash
+    pub fn with_nonce(mut self, use_nonce: bool) -> Self {
+        self.use_nonce = use_nonce;
+        self
+    }
+
+    /// 使用指定 nonce 账户的 durable nonce，优先于 `with_nonce`
+    ///
+    /// `nonce` 为账户中当前存储的值，`authority` 需要签名交易。
+    pub fn with_durable_nonce(
+        mut self,
+        nonce_account: Pubkey,
+        authority: Pubkey,
+        nonce: Hash,
+    ) -> Self {
+        self.durable_nonce = Some((nonce_account, authority, nonce));
+        self
+    }
+
+    /// 组装全部指令，不签名
+    pub fn instructions(&self) -> Result<Vec<Instruction>, anyhow::Error> {
+        let mut instructions =
+            Vec::with_capacity(MAX_ADDED_INSTRUCTIONS + self.instructions.len());
+        if let Some((nonce_account, authority, _)) = &self.durable_nonce {
+            instructions.push(advance_nonce_account(nonce_account, authority));
+        } else if self.use_nonce {
+            add_nonce_instruction(&mut instructions, self.payer.as_ref())?;
+        }
+        match (self.tip.is_some(), self.data_size_limit) {
+            (false, 0) => {
+                add_sell_compute_budget_instructions(&mut instructions, &self.priority_fee)
+            }
+            (false, limit) => {
+                add_rpc_compute_budget_instructions(&mut instructions, &self.priority_fee, limit)
+            }
+            (true, 0) => {
+                add_sell_tip_compute_budget_instructions(&mut instructions, &self.priority_fee)
+            }
+            (true, limit) => {
+                add_tip_compute_budget_instructions(&mut instructions, &self.priority_fee, limit)
+            }
+        }
+        instructions.extend(self.instructions.iter().cloned());
+        if let Some((tip_account, tip_amount)) = &self.tip {
+            let tip_payer = self.fee_payer.as_ref().unwrap_or(&self.payer).pubkey();
+            instructions.push(transfer(
+                &tip_payer,
+                tip_account,
+                sol_str_to_lamports(tip_amount.to_string().as_str()).unwrap_or(0),
+            ));
+        }
+        Ok(instructions)
+    }
+
+    /// 编译并签名 v0 交易；使用 nonce 时忽略 `recent_blockhash`
+    pub async fn build(
+        &self,
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction, anyhow::Error> {
+        let instructions = self.instructions()?;
+        let blockhash = if let Some((_, _, nonce)) = self.durable_nonce {
+            nonce
+        } else if self.use_nonce {
+            get_transaction_blockhash(recent_blockhash)
+        } else {
+            recent_blockhash
+        };
+        let mut lookup_tables = self.lookup_tables.clone();
+        if self.lookup_table_key.is_some() {
+            lookup_tables.extend(get_address_lookup_table_accounts(self.lookup_table_key).await);
+        }
+        compile_and_sign(
+            self.payer.as_ref(),
+            self.fee_payer.as_deref(),
+            &instructions,
+            &lookup_tables,
+            blockhash,
+        )
+    }
+}
+
 /// 构建带小费的交易（使用PriorityFee中的tip_fee）
 pub async fn build_tip_transaction_with_priority_fee(
-    payer: Arc<Keypair>,
+    payer: Arc<dyn TradeSigner>,
+    fee_payer: Option<Arc<dyn TradeSigner>>,
     priority_fee: &PriorityFee,
-    business_instructions: Vec<Instruction>,
+    business_instructions: impl IntoIterator<Item = Instruction>,
     tip_account: &Pubkey,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     data_size_limit: u32,
     middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: String,
+    dex_type: DexType,
     is_buy: bool,
 ) -> Result<VersionedTransaction, anyhow::Error> {
     build_tip_transaction(
         payer,
+        fee_payer,
         priority_fee,
         business_instructions,
         tip_account,
@@ -174,7 +400,7 @@ pub async fn build_tip_transaction_with_priority_fee(
         recent_blockhash,
         data_size_limit,
         middleware_manager,
-        protocol_name,
+        dex_type,
         is_buy,
     )
     .await
@@ -182,16 +408,18 @@ pub async fn build_tip_transaction_with_priority_fee(
 
 /// 构建标准的RPC交易
 pub async fn build_sell_transaction(
-    payer: Arc<Keypair>,
+    payer: Arc<dyn TradeSigner>,
+    fee_payer: Option<Arc<dyn TradeSigner>>,
     priority_fee: &PriorityFee,
-    business_instructions: Vec<Instruction>,
+    business_instructions: impl IntoIterator<Item = Instruction>,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: String,
+    dex_type: DexType,
     is_buy: bool,
 ) -> Result<VersionedTransaction, anyhow::Error> {
-    let mut instructions = vec![];
+    let business_instructions = business_instructions.into_iter();
+    let mut instructions = instruction_buffer(&business_instructions);
 
     // 添加计算预算指令
     add_sell_compute_budget_instructions(&mut instructions, priority_fee);
@@ -205,29 +433,32 @@ pub async fn build_sell_transaction(
     // 构建交易
     build_versioned_transaction(
         payer,
+        fee_payer,
         instructions,
         address_lookup_table_accounts,
         recent_blockhash,
         middleware_manager,
-        protocol_name,
+        dex_type,
         is_buy,
     )
     .await
 }
 
 pub async fn build_sell_tip_transaction(
-    payer: Arc<Keypair>,
+    payer: Arc<dyn TradeSigner>,
+    fee_payer: Option<Arc<dyn TradeSigner>>,
     priority_fee: &PriorityFee,
-    business_instructions: Vec<Instruction>,
+    business_instructions: impl IntoIterator<Item = Instruction>,
     tip_account: &Pubkey,
     tip_amount: f64,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: String,
+    dex_type: DexType,
     is_buy: bool,
 ) -> Result<VersionedTransaction, anyhow::Error> {
-    let mut instructions = vec![];
+    let business_instructions = business_instructions.into_iter();
+    let mut instructions = instruction_buffer(&business_instructions);
 
     // 添加计算预算指令
     add_sell_tip_compute_budget_instructions(&mut instructions, priority_fee);
@@ -235,9 +466,10 @@ pub async fn build_sell_tip_transaction(
     // 添加业务指令
     instructions.extend(business_instructions);
 
-    // 添加小费转账指令
+    // 添加小费转账指令，由手续费支付者支付
+    let tip_payer = fee_payer.as_ref().unwrap_or(&payer).pubkey();
     instructions.push(transfer(
-        &payer.pubkey(),
+        &tip_payer,
         tip_account,
         sol_str_to_lamports(tip_amount.to_string().as_str()).unwrap_or(0),
     ));
@@ -248,29 +480,32 @@ pub async fn build_sell_tip_transaction(
     // 构建交易
     build_versioned_transaction(
         payer,
+        fee_payer,
         instructions,
         address_lookup_table_accounts,
         recent_blockhash,
         middleware_manager,
-        protocol_name,
+        dex_type,
         is_buy,
     )
     .await
 }
 
 pub async fn build_sell_tip_transaction_with_priority_fee(
-    payer: Arc<Keypair>,
+    payer: Arc<dyn TradeSigner>,
+    fee_payer: Option<Arc<dyn TradeSigner>>,
     priority_fee: &PriorityFee,
-    business_instructions: Vec<Instruction>,
+    business_instructions: impl IntoIterator<Item = Instruction>,
     tip_account: &Pubkey,
     lookup_table_key: Option<Pubkey>,
     recent_blockhash: Hash,
     middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: String,
+    dex_type: DexType,
     is_buy: bool,
 ) -> Result<VersionedTransaction, anyhow::Error> {
     build_sell_tip_transaction(
         payer,
+        fee_payer,
         priority_fee,
         business_instructions,
         tip_account,
@@ -278,8 +513,34 @@ pub async fn build_sell_tip_transaction_with_priority_fee(
         lookup_table_key,
         recent_blockhash,
         middleware_manager,
-        protocol_name,
+        dex_type,
         is_buy,
     )
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Keypair;
+
+    #[tokio::test]
+    async fn test_transaction_builder_fee_payer_and_tip() {
+        let payer: Arc<dyn TradeSigner> = Arc::new(Keypair::new());
+        let fee_payer: Arc<dyn TradeSigner> = Arc::new(Keypair::new());
+        let tip_account = Pubkey::new_unique();
+        let builder = TransactionBuilder::new(payer.clone(), PriorityFee::default())
+            .with_fee_payer(Some(fee_payer.clone()))
+            .with_instruction(transfer(&payer.pubkey(), &Pubkey::new_unique(), 1))
+            .with_tip(tip_account, 0.001);
+
+        // 计算单元价格、计算单元上限、用户指令、小费
+        let instructions = builder.instructions().unwrap();
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(instructions[3], transfer(&fee_payer.pubkey(), &tip_account, 1_000_000));
+
+        let transaction = builder.build(Hash::new_unique()).await.unwrap();
+        assert_eq!(transaction.message.static_account_keys()[0], fee_payer.pubkey());
+        assert!(transaction.verify_with_results().iter().all(|ok| *ok));
+    }
+}
@@ -0,0 +1,326 @@
+//! Transaction templates
+//!
+//! A [`TransactionTemplate`] is a v0 message compiled ahead of time with placeholder
+//! accounts and zero amounts. When the trade has to go out, only the placeholders, the
+//! amounts and the blockhash are patched into a copy of the compiled message before signing;
+//! instruction assembly, PDA lookups for fixed accounts and message compilation are already
+//! done. [`PumpFunBuyTemplate`] prepares the buy of a freshly created PumpFun token this way,
+//! so a sniper reacting to a create event only derives the mint specific accounts.
+
+use anyhow::{anyhow, Result};
+use solana_hash::Hash;
+use solana_sdk::{
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount, VersionedMessage},
+    pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+use std::collections::HashSet;
+
+use crate::common::TradeSigner;
+
+/// Compiled message with patchable accounts and amounts
+#[derive(Debug, Clone)]
+pub struct TransactionTemplate {
+    message: v0::Message,
+    /// Index in `static_account_keys` of each placeholder
+    account_slots: Vec<usize>,
+    /// Instruction index and data offset of each little endian `u64` amount
+    amount_slots: Vec<(usize, usize)>,
+    /// Addresses loaded from the lookup tables, patched accounts must not repeat them
+    loaded_addresses: Vec<Pubkey>,
+}
+
+impl TransactionTemplate {
+    /// Compile `instructions` into a template
+    ///
+    /// `placeholders` are accounts replaced on every use, each must be unique and appear in
+    /// the instructions; they are never resolved through the lookup tables. `amounts` locates
+    /// each patched `u64` by instruction index and byte offset in its data.
+    pub fn compile(
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        placeholders: &[Pubkey],
+        amounts: &[(usize, usize)],
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<Self> {
+        // Placeholders are unknown to the lookup tables, they always end up static
+        let message =
+            v0::Message::try_compile(payer, instructions, lookup_tables, Hash::default())?;
+        let account_slots = placeholders
+            .iter()
+            .map(|placeholder| {
+                message
+                    .account_keys
+                    .iter()
+                    .position(|key| key == placeholder)
+                    .ok_or_else(|| anyhow!("Placeholder {} is not used", placeholder))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for &(instruction, offset) in amounts {
+            let data_len = message
+                .instructions
+                .get(instruction)
+                .map(|instruction| instruction.data.len())
+                .ok_or_else(|| anyhow!("No instruction {}", instruction))?;
+            if offset + 8 > data_len {
+                return Err(anyhow!("Amount at {} is outside instruction {}", offset, instruction));
+            }
+        }
+        let loaded_addresses = message
+            .address_table_lookups
+            .iter()
+            .flat_map(|lookup| {
+                let table = lookup_tables.iter().find(|table| table.key == lookup.account_key);
+                lookup
+                    .writable_indexes
+                    .iter()
+                    .chain(&lookup.readonly_indexes)
+                    .filter_map(move |&index| table?.addresses.get(index as usize).copied())
+            })
+            .collect();
+        Ok(Self { message, account_slots, amount_slots: amounts.to_vec(), loaded_addresses })
+    }
+
+    /// Message with `accounts` and `amounts` patched in, in the order given to `compile`
+    pub fn instantiate(
+        &self,
+        accounts: &[Pubkey],
+        amounts: &[u64],
+        recent_blockhash: Hash,
+    ) -> Result<VersionedMessage> {
+        if accounts.len() != self.account_slots.len() || amounts.len() != self.amount_slots.len() {
+            return Err(anyhow!(
+                "Template expects {} accounts and {} amounts",
+                self.account_slots.len(),
+                self.amount_slots.len()
+            ));
+        }
+        let mut message = self.message.clone();
+        for (&slot, account) in self.account_slots.iter().zip(accounts) {
+            message.account_keys[slot] = *account;
+        }
+        // An account listed twice makes the transaction invalid
+        let mut seen = HashSet::with_capacity(message.account_keys.len());
+        if let Some(duplicate) = message
+            .account_keys
+            .iter()
+            .chain(&self.loaded_addresses)
+            .find(|key| !seen.insert(**key))
+        {
+            return Err(anyhow!("Account {} is already part of the template", duplicate));
+        }
+        for (&(instruction, offset), amount) in self.amount_slots.iter().zip(amounts) {
+            message.instructions[instruction].data[offset..offset + 8]
+                .copy_from_slice(&amount.to_le_bytes());
+        }
+        message.recent_blockhash = recent_blockhash;
+        Ok(VersionedMessage::V0(message))
+    }
+
+    /// Patch and sign, `fee_payer` must be the payer the template was compiled with if set
+    pub fn sign(
+        &self,
+        payer: &dyn TradeSigner,
+        fee_payer: Option<&dyn TradeSigner>,
+        accounts: &[Pubkey],
+        amounts: &[u64],
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction> {
+        let message = self.instantiate(accounts, amounts, recent_blockhash)?;
+        let transaction = match fee_payer.filter(|fee_payer| fee_payer.pubkey() != payer.pubkey()) {
+            Some(fee_payer) => VersionedTransaction::try_new(message, &[fee_payer, payer])?,
+            None => payer.sign_transaction(message)?,
+        };
+        Ok(transaction)
+    }
+}
+
+#[cfg(feature = "pumpfun")]
+pub use pumpfun::PumpFunBuyTemplate;
+
+#[cfg(feature = "pumpfun")]
+mod pumpfun {
+    use solana_sdk::{native_token::sol_str_to_lamports, signature::Keypair, signer::Signer};
+    use solana_system_interface::instruction::transfer;
+    use spl_associated_token_account::{
+        get_associated_token_address, instruction::create_associated_token_account_idempotent,
+    };
+
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        common::PriorityFee,
+        constants::pumpfun::{accounts::TOKEN_PROGRAM, global_constants::FEE_RECIPIENT},
+        instruction::pumpfun::{buy, Buy},
+        trading::{
+            common::{add_tip_compute_budget_instructions, get_address_lookup_table_accounts},
+            pumpfun::common::{get_bonding_curve_pda, get_creator_vault_pda},
+        },
+    };
+
+    /// Buy of a PumpFun token prepared before the token exists
+    ///
+    /// Holds the compute budget, ATA creation, buy and optional tip instructions of the
+    /// payer; `sign` fills in the mint, its creator, the amounts and the blockhash.
+    #[derive(Clone)]
+    pub struct PumpFunBuyTemplate {
+        template: TransactionTemplate,
+        payer: Arc<dyn TradeSigner>,
+        fee_payer: Option<Arc<dyn TradeSigner>>,
+    }
+
+    impl PumpFunBuyTemplate {
+        /// Prepare the buy for `payer`, tipping `tip_fee` SOL to `tip_account` when set
+        pub async fn new(
+            payer: Arc<dyn TradeSigner>,
+            fee_payer: Option<Arc<dyn TradeSigner>>,
+            priority_fee: &PriorityFee,
+            tip: Option<(Pubkey, f64)>,
+            lookup_table_key: Option<Pubkey>,
+        ) -> Result<Self> {
+            let owner = payer.pubkey();
+            // Random keys, never equal to an account of the real trade
+            let placeholder_mint = Keypair::new().pubkey();
+            let placeholder_creator = Keypair::new().pubkey();
+            let accounts = MintAccounts::derive(&owner, &placeholder_mint, &placeholder_creator)?;
+
+            let mut instructions = vec![];
+            add_tip_compute_budget_instructions(&mut instructions, priority_fee, 0);
+            instructions.push(create_associated_token_account_idempotent(
+                &owner,
+                &owner,
+                &placeholder_mint,
+                &TOKEN_PROGRAM,
+            ));
+            let buy_index = instructions.len();
+            instructions.push(buy(
+                payer.as_ref(),
+                &placeholder_mint,
+                &accounts.bonding_curve,
+                &accounts.creator_vault,
+                &FEE_RECIPIENT,
+                Buy { _amount: 0, _max_sol_cost: 0 },
+            ));
+            let message_payer = fee_payer.as_ref().map_or(owner, |fee_payer| fee_payer.pubkey());
+            if let Some((tip_account, tip_fee)) = tip {
+                instructions.push(transfer(
+                    &message_payer,
+                    &tip_account,
+                    sol_str_to_lamports(tip_fee.to_string().as_str()).unwrap_or(0),
+                ));
+            }
+
+            let lookup_tables = get_address_lookup_table_accounts(lookup_table_key).await;
+            let template = TransactionTemplate::compile(
+                &message_payer,
+                &instructions,
+                &accounts.keys(&placeholder_mint),
+                // Discriminator, then token amount and max SOL cost
+                &[(buy_index, 8), (buy_index, 16)],
+                &lookup_tables,
+            )?;
+            Ok(Self { template, payer, fee_payer })
+        }
+
+        /// Buy `token_amount` of `mint` created by `creator`, paying at most `max_sol_cost`
+        ///
+        /// Compute the amounts from the create event reserves, e.g. with
+        /// `get_buy_token_amount_from_sol_amount` and `calculate_with_slippage_buy`.
+        pub fn sign(
+            &self,
+            mint: &Pubkey,
+            creator: &Pubkey,
+            token_amount: u64,
+            max_sol_cost: u64,
+            recent_blockhash: Hash,
+        ) -> Result<VersionedTransaction> {
+            let accounts = MintAccounts::derive(&self.payer.pubkey(), mint, creator)?;
+            self.template.sign(
+                self.payer.as_ref(),
+                self.fee_payer.as_deref(),
+                &accounts.keys(mint),
+                &[token_amount, max_sol_cost],
+                recent_blockhash,
+            )
+        }
+    }
+
+    /// Accounts of the buy that depend on the mint
+    struct MintAccounts {
+        bonding_curve: Pubkey,
+        associated_bonding_curve: Pubkey,
+        user_token_account: Pubkey,
+        creator_vault: Pubkey,
+    }
+
+    impl MintAccounts {
+        fn derive(owner: &Pubkey, mint: &Pubkey, creator: &Pubkey) -> Result<Self> {
+            let bonding_curve =
+                get_bonding_curve_pda(mint).ok_or_else(|| anyhow!("No bonding curve PDA"))?;
+            Ok(Self {
+                bonding_curve,
+                associated_bonding_curve: get_associated_token_address(&bonding_curve, mint),
+                user_token_account: get_associated_token_address(owner, mint),
+                creator_vault: get_creator_vault_pda(creator)
+                    .ok_or_else(|| anyhow!("No creator vault PDA"))?,
+            })
+        }
+
+        fn keys(&self, mint: &Pubkey) -> [Pubkey; 5] {
+            [
+                *mint,
+                self.bonding_curve,
+                self.associated_bonding_curve,
+                self.user_token_account,
+                self.creator_vault,
+            ]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        instruction::AccountMeta, signature::Keypair, signer::Signer,
+        transaction::VersionedTransaction,
+    };
+
+    #[test]
+    fn test_instantiate_matches_direct_compile() {
+        let payer = Keypair::new();
+        let program = Pubkey::new_unique();
+        let instruction = |account: Pubkey, amount: u64| {
+            let mut data = vec![7u8; 4];
+            data.extend_from_slice(&amount.to_le_bytes());
+            Instruction::new_with_bytes(
+                program,
+                &data,
+                vec![AccountMeta::new(payer.pubkey(), true), AccountMeta::new(account, false)],
+            )
+        };
+        let placeholder = Pubkey::new_unique();
+        let template = TransactionTemplate::compile(
+            &payer.pubkey(),
+            &[instruction(placeholder, 0)],
+            &[placeholder],
+            &[(0, 4)],
+            &[],
+        )
+        .unwrap();
+
+        let account = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let patched = template.sign(&payer, None, &[account], &[42], blockhash).unwrap();
+        let direct =
+            v0::Message::try_compile(&payer.pubkey(), &[instruction(account, 42)], &[], blockhash)
+                .unwrap();
+        let direct =
+            VersionedTransaction::try_new(VersionedMessage::V0(direct), &[&payer]).unwrap();
+        assert_eq!(patched.message, direct.message);
+        assert!(patched.verify_with_results().iter().all(|ok| *ok));
+        assert!(template.instantiate(&[payer.pubkey()], &[1], blockhash).is_err());
+    }
+}
@@ -1,13 +1,19 @@
-use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction};
+use solana_account_decoder::UiAccountData;
+use solana_client::{rpc_request::TokenAccountsFilter, rpc_response::RpcKeyedAccount};
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::Transaction};
 use solana_system_interface::instruction::transfer;
 use spl_associated_token_account::get_associated_token_address;
 use spl_token::instruction::close_account;
 
-use crate::common::SolanaRpcClient;
-use anyhow::anyhow;
+use super::batch_fetcher::get_token_account_amounts;
+use crate::common::{ata_cache::AtaCache, SolanaRpcClient, TradeSigner};
+use anyhow::{anyhow, Context};
+use solana_client::client_error::ClientError;
 
 /// Get the balances of two tokens in the pool
 ///
+/// Both vaults are fetched in a single `getMultipleAccounts` round trip.
+///
 /// # Returns
 /// Returns token0_balance, token1_balance
 pub async fn get_multi_token_balances(
@@ -15,14 +21,8 @@ pub async fn get_multi_token_balances(
     token0_vault: &Pubkey,
     token1_vault: &Pubkey,
 ) -> Result<(u64, u64), anyhow::Error> {
-    let token0_balance = rpc.get_token_account_balance(&token0_vault).await?;
-    let token1_balance = rpc.get_token_account_balance(&token1_vault).await?;
-    // Parse balance string to u64
-    let token0_amount =
-        token0_balance.amount.parse::<u64>().map_err(|e| anyhow!("Failed to parse token0 balance: {}", e))?;
-    let token1_amount =
-        token1_balance.amount.parse::<u64>().map_err(|e| anyhow!("Failed to parse token1 balance: {}", e))?;
-    Ok((token0_amount, token1_amount))
+    let amounts = get_token_account_amounts(rpc, &[*token0_vault, *token1_vault]).await?;
+    Ok((amounts[0], amounts[1]))
 }
 
 #[inline]
@@ -32,10 +32,44 @@ pub async fn get_token_balance(
     mint: &Pubkey,
 ) -> Result<u64, anyhow::Error> {
     let ata = get_associated_token_address(payer, mint);
-    let balance = rpc.get_token_account_balance(&ata).await?;
-    let balance_u64 =
-        balance.amount.parse::<u64>().map_err(|_| anyhow!("Failed to parse token balance"))?;
-    Ok(balance_u64)
+    get_token_account_balance(rpc, &ata, mint).await
+}
+
+/// Read the balance of a token account, with the account and mint in the error
+///
+/// A missing account is reported as such instead of the raw RPC error.
+pub async fn get_token_account_balance(
+    rpc: &SolanaRpcClient,
+    token_account: &Pubkey,
+    mint: &Pubkey,
+) -> Result<u64, anyhow::Error> {
+    let balance = match rpc.get_token_account_balance(token_account).await {
+        Ok(balance) => balance,
+        Err(e) if rpc_account_not_found(&e) => {
+            return Err(anyhow!(
+                "Token account {} for mint {} does not exist",
+                token_account,
+                mint
+            ));
+        }
+        Err(e) => {
+            return Err(anyhow::Error::new(e)
+                .context(format!("Failed to get balance of token account {}", token_account)));
+        }
+    };
+    balance.amount.parse::<u64>().map_err(|e| {
+        anyhow!(
+            "Failed to parse balance {:?} of token account {}: {}",
+            balance.amount,
+            token_account,
+            e
+        )
+    })
+}
+
+/// `getTokenAccountBalance` answers a missing account with "could not find account"
+fn rpc_account_not_found(err: &ClientError) -> bool {
+    err.to_string().contains("could not find account")
 }
 
 #[inline]
@@ -43,13 +77,14 @@ pub async fn get_sol_balance(
     rpc: &SolanaRpcClient,
     account: &Pubkey,
 ) -> Result<u64, anyhow::Error> {
-    let balance = rpc.get_balance(account).await?;
-    Ok(balance)
+    rpc.get_balance(account)
+        .await
+        .with_context(|| format!("Failed to get SOL balance of {}", account))
 }
 
 pub async fn transfer_sol(
     rpc: &SolanaRpcClient,
-    payer: &Keypair,
+    payer: &dyn TradeSigner,
     receive_wallet: &Pubkey,
     amount: u64,
 ) -> Result<(), anyhow::Error> {
@@ -94,7 +129,7 @@ pub async fn transfer_sol(
 /// Returns a Result, success returns (), failure returns error
 pub async fn close_token_account(
     rpc: &SolanaRpcClient,
-    payer: &Keypair,
+    payer: &dyn TradeSigner,
     mint: &Pubkey,
 ) -> Result<(), anyhow::Error> {
     // Get associated token account address
@@ -124,3 +159,165 @@ pub async fn close_token_account(
 
     Ok(())
 }
+
+/// Rent-exempt minimum of an SPL token account (165 bytes)
+pub const TOKEN_ACCOUNT_RENT: u64 = 2_039_280;
+
+/// Lamports per transaction signature
+pub const SIGNATURE_FEE: u64 = 5_000;
+
+/// `close_account` instructions per sweep transaction, well within the transaction size limit
+pub const DEFAULT_SWEEP_BATCH_SIZE: usize = 20;
+
+/// Outcome of `sweep_empty_token_accounts`
+#[derive(Debug, Clone, Default)]
+pub struct SweepSummary {
+    /// Closed token accounts
+    pub closed: Vec<Pubkey>,
+    /// Empty accounts whose close transaction failed
+    pub failed: Vec<Pubkey>,
+    /// Rent returned to the owner, in lamports
+    pub rent_reclaimed: u64,
+    pub signatures: Vec<Signature>,
+}
+
+/// Empty token account of the owner that it may close
+#[derive(Debug, Clone, PartialEq)]
+struct EmptyTokenAccount {
+    address: Pubkey,
+    mint: Pubkey,
+    program_id: Pubkey,
+    lamports: u64,
+}
+
+/// The account when it holds no tokens and `owner` can close it
+///
+/// Frozen accounts and accounts with another close authority are left alone.
+fn empty_token_account(
+    keyed: &RpcKeyedAccount,
+    owner: &Pubkey,
+    program_id: &Pubkey,
+) -> Option<EmptyTokenAccount> {
+    let UiAccountData::Json(parsed) = &keyed.account.data else {
+        return None;
+    };
+    let info = parsed.parsed.get("info")?;
+    if info["tokenAmount"]["amount"].as_str()? != "0" || info["state"].as_str()? == "frozen" {
+        return None;
+    }
+    if let Some(close_authority) = info.get("closeAuthority").and_then(|value| value.as_str()) {
+        if close_authority != owner.to_string() {
+            return None;
+        }
+    }
+    Some(EmptyTokenAccount {
+        address: keyed.pubkey.parse().ok()?,
+        mint: info["mint"].as_str()?.parse().ok()?,
+        program_id: *program_id,
+        lamports: keyed.account.lamports,
+    })
+}
+
+/// Close every empty token account of the payer to reclaim its rent
+///
+/// Covers SPL Token and Token-2022 accounts, `batch_size` closes per transaction. A failed
+/// batch is logged and reported in `SweepSummary::failed`, the remaining batches still run.
+pub async fn sweep_empty_token_accounts(
+    rpc: &SolanaRpcClient,
+    payer: &dyn TradeSigner,
+    batch_size: usize,
+) -> Result<SweepSummary, anyhow::Error> {
+    let owner = payer.pubkey();
+    let mut empty = vec![];
+    for program_id in [spl_token::ID, spl_token_2022::ID] {
+        let accounts = rpc
+            .get_token_accounts_by_owner(&owner, TokenAccountsFilter::ProgramId(program_id))
+            .await
+            .with_context(|| format!("Failed to list token accounts of {}", owner))?;
+        empty.extend(
+            accounts.iter().filter_map(|keyed| empty_token_account(keyed, &owner, &program_id)),
+        );
+    }
+
+    let mut summary = SweepSummary::default();
+    for batch in empty.chunks(batch_size.max(1)) {
+        let instructions = batch
+            .iter()
+            .map(|account| {
+                spl_token_2022::instruction::close_account(
+                    &account.program_id,
+                    &account.address,
+                    &owner,
+                    &owner,
+                    &[],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let recent_blockhash = rpc.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&owner),
+            &[payer],
+            recent_blockhash,
+        );
+        match rpc.send_and_confirm_transaction(&transaction).await {
+            Ok(signature) => {
+                for account in batch {
+                    AtaCache::get_instance().remove(&owner, &account.mint);
+                    summary.closed.push(account.address);
+                    summary.rent_reclaimed += account.lamports;
+                }
+                summary.signatures.push(signature);
+            }
+            Err(e) => {
+                tracing::warn!(accounts = batch.len(), error = %e, "token account sweep failed");
+                summary.failed.extend(batch.iter().map(|account| account.address));
+            }
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_account_decoder::{parse_account_data::ParsedAccount, UiAccount};
+
+    fn keyed(amount: &str, state: &str, close_authority: Option<Pubkey>) -> RpcKeyedAccount {
+        let mut info = serde_json::json!({
+            "mint": Pubkey::new_unique().to_string(),
+            "state": state,
+            "tokenAmount": { "amount": amount },
+        });
+        if let Some(close_authority) = close_authority {
+            info["closeAuthority"] = close_authority.to_string().into();
+        }
+        RpcKeyedAccount {
+            pubkey: Pubkey::new_unique().to_string(),
+            account: UiAccount {
+                lamports: 2_039_280,
+                data: UiAccountData::Json(ParsedAccount {
+                    program: "spl-token".to_string(),
+                    parsed: serde_json::json!({ "type": "account", "info": info }),
+                    space: 165,
+                }),
+                owner: spl_token::ID.to_string(),
+                executable: false,
+                rent_epoch: 0,
+                space: Some(165),
+            },
+        }
+    }
+
+    #[test]
+    fn test_empty_token_account_filter() {
+        let owner = Pubkey::new_unique();
+        let empty = empty_token_account(&keyed("0", "initialized", None), &owner, &spl_token::ID);
+        assert_eq!(empty.map(|account| account.lamports), Some(2_039_280));
+        let filter = |account| empty_token_account(&account, &owner, &spl_token::ID);
+        assert!(filter(keyed("0", "initialized", Some(owner))).is_some());
+        assert!(filter(keyed("5", "initialized", None)).is_none());
+        assert!(filter(keyed("0", "frozen", None)).is_none());
+        assert!(filter(keyed("0", "initialized", Some(Pubkey::new_unique()))).is_none());
+    }
+}
@@ -1,34 +1,51 @@
 use anyhow::{anyhow, Result};
+use solana_sdk::{signature::Signature, signer::Signer};
 use std::sync::Arc;
 
 use super::{
-    parallel::parallel_execute_with_tips,
+    lifecycle::{TradeLifecycle, TradeStage},
+    parallel::{parallel_execute_with_tips, ParallelExecuteParams},
     params::{BuyParams, BuyWithTipParams, SellParams, SellWithTipParams},
-    timer::TradeTimer,
+    timer::{stage, TradeTimer},
     traits::{InstructionBuilder, TradeExecutor},
 };
 use crate::{
-    swqos::TradeType,
+    common::{ata_cache::AtaCache, SolanaRpcClient},
+    swqos::{SwqosType, TradeType},
     trading::{
-        common::{build_rpc_transaction, build_sell_transaction},
+        common::{
+            apply_contention_fee, auto_data_size_limit, build_rpc_transaction,
+            build_sell_transaction,
+        },
+        factory::DexType,
         middleware::MiddlewareManager,
     },
 };
 
-const MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT: u32 = 256 * 1024;
+pub(crate) const MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT: u32 = 256 * 1024;
+
+/// Report the landing of a plain RPC submission without waiting for the slot lookup
+fn report_landed(lifecycle: &TradeLifecycle, signature: Signature, rpc: &Arc<SolanaRpcClient>) {
+    if lifecycle.is_observed() {
+        let (lifecycle, rpc) = (lifecycle.clone(), rpc.clone());
+        tokio::spawn(async move {
+            lifecycle.landed(SwqosType::Default, signature, Some(&rpc)).await;
+        });
+    }
+}
 
 /// Generic trade executor implementation
 pub struct GenericTradeExecutor {
     instruction_builder: Arc<dyn InstructionBuilder>,
-    protocol_name: &'static str,
+    dex_type: DexType,
 }
 
 impl GenericTradeExecutor {
     pub fn new(
         instruction_builder: Arc<dyn InstructionBuilder>,
-        protocol_name: &'static str,
+        dex_type: DexType,
     ) -> Self {
-        Self { instruction_builder, protocol_name }
+        Self { instruction_builder, dex_type }
     }
 }
 
@@ -42,108 +59,177 @@ impl TradeExecutor for GenericTradeExecutor {
         if params.data_size_limit == 0 {
             params.data_size_limit = MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT;
         }
-        if params.rpc.is_none() {
-            return Err(anyhow!("RPC is not set"));
-        }
-        let rpc = params.rpc.as_ref().unwrap().clone();
-        let mut timer = TradeTimer::new("Building buy transaction instructions");
-        // Build instructions
-        let instructions = self.instruction_builder.build_buy_instructions(&params).await?;
-        let final_instructions = match middleware_manager.clone() {
-            Some(middleware_manager) => middleware_manager
-                .apply_middlewares_process_protocol_instructions(
-                    instructions,
-                    self.protocol_name.to_string(),
-                    true,
-                )?,
-            None => instructions,
-        };
-        timer.stage("Building RPC transaction instructions");
+        let rpc = params.rpc.clone().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let lifecycle = params.lifecycle.clone().unwrap_or_else(|| {
+            TradeLifecycle::new(self.dex_type.clone(), params.mint, TradeType::Buy)
+        });
+        lifecycle
+            .track(async {
+                let mut timer = TradeTimer::new(stage::BUILD).with_lifecycle(lifecycle.clone());
+                // Build instructions
+                let instructions = self.instruction_builder.build_buy_instructions(&params).await?;
+                let final_instructions = match middleware_manager.clone() {
+                    Some(middleware_manager) => middleware_manager
+                        .apply_middlewares_process_protocol_instructions(
+                            instructions,
+                            self.dex_type.clone(),
+                            true,
+                        )?,
+                    None => instructions,
+                };
+                lifecycle.emit(TradeStage::InstructionsBuilt {
+                    instructions: final_instructions.len(),
+                });
+                timer.stage(stage::SIGN);
 
-        // Build transaction
-        let transaction = build_rpc_transaction(
-            params.payer.clone(),
-            &params.priority_fee,
-            final_instructions,
-            params.lookup_table_key,
-            params.recent_blockhash,
-            params.data_size_limit,
-            middleware_manager,
-            self.protocol_name.to_string(),
-            true,
-        )
-        .await?;
-        timer.stage("RPC submission confirmation");
+                // Build transaction
+                let transaction = build_rpc_transaction(
+                    params.payer.clone(),
+                    params.fee_payer.clone(),
+                    &params.priority_fee,
+                    final_instructions,
+                    params.lookup_table_key,
+                    params.recent_blockhash,
+                    params.data_size_limit,
+                    middleware_manager,
+                    self.dex_type.clone(),
+                    true,
+                )
+                .await?;
+                let signature = transaction.signatures[0];
+                lifecycle.emit(TradeStage::Signed { signature });
+                let submit_stage =
+                    if params.wait_transaction_confirmed { stage::CONFIRM } else { stage::SUBMIT };
+                timer.stage(submit_stage);
 
-        // Send transaction
-        if params.wait_transaction_confirmed {
-            rpc.send_and_confirm_transaction(&transaction).await?;
-        } else {
-            // Send transaction asynchronously
-            rpc.send_transaction(&transaction).await?;
-        }
-        timer.finish();
+                // Send transaction
+                lifecycle.emit(TradeStage::Submitted { provider: SwqosType::Default, signature });
+                if params.wait_transaction_confirmed {
+                    rpc.send_and_confirm_transaction(&transaction).await?;
+                    report_landed(&lifecycle, signature, &rpc);
+                    // The buy created the user's ATA
+                    AtaCache::get_instance().mark_existing(&params.payer.pubkey(), &params.mint);
+                } else {
+                    // Send transaction asynchronously
+                    rpc.send_transaction(&transaction).await?;
+                }
+                timer.finish();
 
-        Ok(())
+                Ok(())
+            })
+            .await
     }
 
     async fn buy_with_tip(
         &self,
-        mut params: BuyWithTipParams,
+        params: BuyWithTipParams,
         middleware_manager: Option<Arc<MiddlewareManager>>,
     ) -> Result<()> {
-        if params.data_size_limit == 0 {
-            params.data_size_limit = MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT;
-        }
-        let timer = TradeTimer::new("Building buy transaction instructions");
+        let lifecycle = params.lifecycle.clone().unwrap_or_else(|| {
+            TradeLifecycle::new(self.dex_type.clone(), params.mint, TradeType::Buy)
+        });
+        lifecycle
+            .track(async {
+                let timer = TradeTimer::new(stage::BUILD).with_lifecycle(lifecycle.clone());
 
-        // Validate parameters - convert to BuyParams for validation
-        let buy_params = BuyParams {
-            rpc: params.rpc,
-            payer: params.payer.clone(),
-            mint: params.mint,
-            sol_amount: params.sol_amount,
-            slippage_basis_points: params.slippage_basis_points,
-            priority_fee: params.priority_fee.clone(),
-            lookup_table_key: params.lookup_table_key,
-            recent_blockhash: params.recent_blockhash,
-            data_size_limit: params.data_size_limit,
-            wait_transaction_confirmed: params.wait_transaction_confirmed,
-            protocol_params: params.protocol_params.clone(),
-        };
+                // Validate parameters - convert to BuyParams for validation
+                let buy_params = BuyParams {
+                    rpc: params.rpc,
+                    payer: params.payer.clone(),
+                    fee_payer: params.fee_payer.clone(),
+                    mint: params.mint,
+                    sol_amount: params.sol_amount,
+                    slippage_basis_points: params.slippage_basis_points,
+                    priority_fee: params.priority_fee.clone(),
+                    lookup_table_key: params.lookup_table_key,
+                    recent_blockhash: params.recent_blockhash,
+                    data_size_limit: params.data_size_limit,
+                    wait_transaction_confirmed: params.wait_transaction_confirmed,
+                    protocol_params: params.protocol_params.clone(),
+                    lifecycle: Some(lifecycle.clone()),
+                };
 
-        // Build instructions
-        let instructions = self.instruction_builder.build_buy_instructions(&buy_params).await?;
-        let final_instructions = match middleware_manager.clone() {
-            Some(middleware_manager) => middleware_manager
-                .apply_middlewares_process_protocol_instructions(
-                    instructions,
-                    self.protocol_name.to_string(),
-                    true,
-                )?,
-            None => instructions,
-        };
+                // Build instructions
+                let instructions =
+                    self.instruction_builder.build_buy_instructions(&buy_params).await?;
+                let final_instructions = match middleware_manager.clone() {
+                    Some(middleware_manager) => middleware_manager
+                        .apply_middlewares_process_protocol_instructions(
+                            instructions,
+                            self.dex_type.clone(),
+                            true,
+                        )?,
+                    None => instructions,
+                };
+                lifecycle.emit(TradeStage::InstructionsBuilt {
+                    instructions: final_instructions.len(),
+                });
+                let mut priority_fee = params.priority_fee;
+                if let (Some(config), Some(rpc)) = (&params.contention_fee, &buy_params.rpc) {
+                    apply_contention_fee(rpc, &final_instructions, config, &mut priority_fee)
+                        .await;
+                }
+                let data_size_limit = match (
+                    params.data_size_limit,
+                    params.data_size_headroom_bps,
+                    &buy_params.rpc,
+                ) {
+                    (0, Some(headroom_bps), Some(rpc)) => {
+                        // Accounts the transaction builder adds to the instructions
+                        let mut extra_accounts = vec![
+                            params.payer.pubkey(),
+                            solana_sdk::compute_budget::id(),
+                            solana_sdk::system_program::id(),
+                        ];
+                        extra_accounts.extend(params.fee_payer.as_ref().map(|p| p.pubkey()));
+                        extra_accounts.extend(params.lookup_table_key);
+                        auto_data_size_limit(
+                            rpc,
+                            &final_instructions,
+                            &extra_accounts,
+                            headroom_bps,
+                        )
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::warn!(error = %e, "sizing the data size limit failed");
+                            MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT
+                        })
+                    }
+                    (0, ..) => MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT,
+                    (data_size_limit, ..) => data_size_limit,
+                };
 
-        timer.finish();
+                timer.finish();
 
-        // Execute transactions in parallel
-        parallel_execute_with_tips(
-            params.swqos_clients,
-            params.payer,
-            final_instructions,
-            params.priority_fee,
-            params.lookup_table_key,
-            params.recent_blockhash,
-            params.data_size_limit,
-            TradeType::Buy,
-            middleware_manager,
-            self.protocol_name.to_string(),
-            true,
-            params.wait_transaction_confirmed,
-        )
-        .await?;
+                // Execute transactions in parallel
+                parallel_execute_with_tips(ParallelExecuteParams {
+                    swqos_clients: params.swqos_clients,
+                    rpc: buy_params.rpc.clone(),
+                    payer: params.payer,
+                    fee_payer: params.fee_payer,
+                    instructions: final_instructions,
+                    priority_fee,
+                    lookup_table_key: params.lookup_table_key,
+                    recent_blockhash: params.recent_blockhash,
+                    data_size_limit,
+                    trade_type: TradeType::Buy,
+                    middleware_manager,
+                    dex_type: self.dex_type.clone(),
+                    is_buy: true,
+                    wait_transaction_confirmed: params.wait_transaction_confirmed,
+                    single_signature: params.single_signature,
+                    simulate_race: params.simulate_race,
+                    lifecycle: lifecycle.clone(),
+                })
+                .await?;
+                if params.wait_transaction_confirmed {
+                    AtaCache::get_instance()
+                        .mark_existing(&buy_params.payer.pubkey(), &buy_params.mint);
+                }
 
-        Ok(())
+                Ok(())
+            })
+            .await
     }
 
     async fn sell(
@@ -151,48 +237,63 @@ impl TradeExecutor for GenericTradeExecutor {
         params: SellParams,
         middleware_manager: Option<Arc<MiddlewareManager>>,
     ) -> Result<()> {
-        if params.rpc.is_none() {
-            return Err(anyhow!("RPC is not set"));
-        }
-        let rpc = params.rpc.as_ref().unwrap().clone();
-        let mut timer = TradeTimer::new("Building sell transaction instructions");
+        let rpc = params.rpc.clone().ok_or_else(|| anyhow!("RPC is not set"))?;
+        let lifecycle = params.lifecycle.clone().unwrap_or_else(|| {
+            TradeLifecycle::new(self.dex_type.clone(), params.mint, TradeType::Sell)
+        });
+        lifecycle
+            .track(async {
+                let mut timer = TradeTimer::new(stage::BUILD).with_lifecycle(lifecycle.clone());
 
-        // Build instructions
-        let instructions = self.instruction_builder.build_sell_instructions(&params).await?;
-        let final_instructions = match middleware_manager.clone() {
-            Some(middleware_manager) => middleware_manager
-                .apply_middlewares_process_protocol_instructions(
-                    instructions,
-                    self.protocol_name.to_string(),
-                    false,
-                )?,
-            None => instructions,
-        };
-        timer.stage("Sell transaction instructions");
+                // Build instructions
+                let instructions =
+                    self.instruction_builder.build_sell_instructions(&params).await?;
+                let final_instructions = match middleware_manager.clone() {
+                    Some(middleware_manager) => middleware_manager
+                        .apply_middlewares_process_protocol_instructions(
+                            instructions,
+                            self.dex_type.clone(),
+                            false,
+                        )?,
+                    None => instructions,
+                };
+                lifecycle.emit(TradeStage::InstructionsBuilt {
+                    instructions: final_instructions.len(),
+                });
+                timer.stage(stage::SIGN);
 
-        // Build transaction
-        let transaction = build_sell_transaction(
-            params.payer.clone(),
-            &params.priority_fee,
-            final_instructions,
-            params.lookup_table_key,
-            params.recent_blockhash,
-            middleware_manager,
-            self.protocol_name.to_string(),
-            false,
-        )
-        .await?;
-        timer.stage("Sell transaction signing");
+                // Build transaction
+                let transaction = build_sell_transaction(
+                    params.payer.clone(),
+                    params.fee_payer.clone(),
+                    &params.priority_fee,
+                    final_instructions,
+                    params.lookup_table_key,
+                    params.recent_blockhash,
+                    middleware_manager,
+                    self.dex_type.clone(),
+                    false,
+                )
+                .await?;
+                let signature = transaction.signatures[0];
+                lifecycle.emit(TradeStage::Signed { signature });
+                let submit_stage =
+                    if params.wait_transaction_confirmed { stage::CONFIRM } else { stage::SUBMIT };
+                timer.stage(submit_stage);
 
-        // Send transaction
-        if params.wait_transaction_confirmed {
-            rpc.send_and_confirm_transaction(&transaction).await?;
-        } else {
-            rpc.send_transaction(&transaction).await?;
-        }
-        timer.finish();
+                // Send transaction
+                lifecycle.emit(TradeStage::Submitted { provider: SwqosType::Default, signature });
+                if params.wait_transaction_confirmed {
+                    rpc.send_and_confirm_transaction(&transaction).await?;
+                    report_landed(&lifecycle, signature, &rpc);
+                } else {
+                    rpc.send_transaction(&transaction).await?;
+                }
+                timer.finish();
 
-        Ok(())
+                Ok(())
+            })
+            .await
     }
 
     async fn sell_with_tip(
@@ -200,57 +301,80 @@ impl TradeExecutor for GenericTradeExecutor {
         params: SellWithTipParams,
         middleware_manager: Option<Arc<MiddlewareManager>>,
     ) -> Result<()> {
-        let timer = TradeTimer::new("Building sell transaction instructions");
+        let lifecycle = params.lifecycle.clone().unwrap_or_else(|| {
+            TradeLifecycle::new(self.dex_type.clone(), params.mint, TradeType::Sell)
+        });
+        lifecycle
+            .track(async {
+                let timer = TradeTimer::new(stage::BUILD).with_lifecycle(lifecycle.clone());
 
-        // Convert to SellParams for instruction building
-        let sell_params = SellParams {
-            rpc: params.rpc,
-            payer: params.payer.clone(),
-            mint: params.mint,
-            token_amount: params.token_amount,
-            slippage_basis_points: params.slippage_basis_points,
-            priority_fee: params.priority_fee.clone(),
-            lookup_table_key: params.lookup_table_key,
-            recent_blockhash: params.recent_blockhash,
-            wait_transaction_confirmed: params.wait_transaction_confirmed,
-            protocol_params: params.protocol_params.clone(),
-        };
+                // Convert to SellParams for instruction building
+                let sell_params = SellParams {
+                    rpc: params.rpc,
+                    payer: params.payer.clone(),
+                    fee_payer: params.fee_payer.clone(),
+                    mint: params.mint,
+                    token_amount: params.token_amount,
+                    slippage_basis_points: params.slippage_basis_points,
+                    priority_fee: params.priority_fee.clone(),
+                    lookup_table_key: params.lookup_table_key,
+                    recent_blockhash: params.recent_blockhash,
+                    wait_transaction_confirmed: params.wait_transaction_confirmed,
+                    protocol_params: params.protocol_params.clone(),
+                    lifecycle: Some(lifecycle.clone()),
+                };
 
-        // Build instructions
-        let instructions = self.instruction_builder.build_sell_instructions(&sell_params).await?;
-        let final_instructions = match middleware_manager.clone() {
-            Some(middleware_manager) => middleware_manager
-                .apply_middlewares_process_protocol_instructions(
-                    instructions,
-                    self.protocol_name.to_string(),
-                    false,
-                )?,
-            None => instructions,
-        };
+                // Build instructions
+                let instructions =
+                    self.instruction_builder.build_sell_instructions(&sell_params).await?;
+                let final_instructions = match middleware_manager.clone() {
+                    Some(middleware_manager) => middleware_manager
+                        .apply_middlewares_process_protocol_instructions(
+                            instructions,
+                            self.dex_type.clone(),
+                            false,
+                        )?,
+                    None => instructions,
+                };
+                lifecycle.emit(TradeStage::InstructionsBuilt {
+                    instructions: final_instructions.len(),
+                });
+                let mut priority_fee = params.priority_fee;
+                if let (Some(config), Some(rpc)) = (&params.contention_fee, &sell_params.rpc) {
+                    apply_contention_fee(rpc, &final_instructions, config, &mut priority_fee)
+                        .await;
+                }
 
-        timer.finish();
+                timer.finish();
 
-        // Execute transactions in parallel
-        parallel_execute_with_tips(
-            params.swqos_clients,
-            params.payer,
-            final_instructions,
-            params.priority_fee,
-            params.lookup_table_key,
-            params.recent_blockhash,
-            0,
-            TradeType::Sell,
-            middleware_manager,
-            self.protocol_name.to_string(),
-            false,
-            params.wait_transaction_confirmed,
-        )
-        .await?;
+                // Execute transactions in parallel
+                parallel_execute_with_tips(ParallelExecuteParams {
+                    swqos_clients: params.swqos_clients,
+                    rpc: sell_params.rpc.clone(),
+                    payer: params.payer,
+                    fee_payer: params.fee_payer,
+                    instructions: final_instructions,
+                    priority_fee,
+                    lookup_table_key: params.lookup_table_key,
+                    recent_blockhash: params.recent_blockhash,
+                    data_size_limit: 0,
+                    trade_type: TradeType::Sell,
+                    middleware_manager,
+                    dex_type: self.dex_type.clone(),
+                    is_buy: false,
+                    wait_transaction_confirmed: params.wait_transaction_confirmed,
+                    single_signature: params.single_signature,
+                    simulate_race: params.simulate_race,
+                    lifecycle: lifecycle.clone(),
+                })
+                .await?;
 
-        Ok(())
+                Ok(())
+            })
+            .await
     }
 
-    fn protocol_name(&self) -> &'static str {
-        self.protocol_name
+    fn dex_type(&self) -> DexType {
+        self.dex_type.clone()
     }
 }
@@ -0,0 +1,237 @@
+//! Trade lifecycle events
+//!
+//! The executors report every stage a trade goes through on a process wide broadcast
+//! channel, so dashboards and notifiers can follow all trades without wrapping each call:
+//!
+//! ```ignore
+//! let mut events = SolanaTrade::subscribe_trade_events();
+//! tokio::spawn(async move {
+//!     while let Ok(event) = events.recv().await {
+//!         println!("trade {} {:?} after {:?}", event.trade_id, event.stage, event.elapsed);
+//!     }
+//! });
+//! ```
+//!
+//! Events are only built while someone is subscribed. A receiver that falls more than
+//! `TRADE_EVENT_CAPACITY` events behind skips the oldest ones and gets `RecvError::Lagged`.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use tokio::sync::broadcast;
+
+use crate::common::SolanaRpcClient;
+use crate::swqos::{SwqosType, TradeType};
+use crate::trading::factory::DexType;
+
+/// Events buffered per receiver
+pub const TRADE_EVENT_CAPACITY: usize = 1024;
+
+static SENDER: OnceLock<broadcast::Sender<TradeLifecycleEvent>> = OnceLock::new();
+static NEXT_TRADE_ID: AtomicU64 = AtomicU64::new(1);
+
+fn sender() -> &'static broadcast::Sender<TradeLifecycleEvent> {
+    SENDER.get_or_init(|| broadcast::channel(TRADE_EVENT_CAPACITY).0)
+}
+
+/// Receive the lifecycle events of all trades executed from now on
+pub fn subscribe() -> broadcast::Receiver<TradeLifecycleEvent> {
+    sender().subscribe()
+}
+
+/// Stage a trade reached
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TradeStage {
+    /// Protocol instructions built and passed through the middlewares
+    InstructionsBuilt { instructions: usize },
+    /// A transaction was built and signed; parallel submissions sign one per provider group
+    Signed { signature: Signature },
+    /// The transaction was handed to `provider`, `SwqosType::Default` for plain RPC
+    Submitted { provider: SwqosType, signature: Signature },
+    /// The transaction confirmed; reported once per trade by the first provider to confirm
+    Landed { provider: SwqosType, signature: Signature, slot: Option<u64> },
+    /// A stage measured by the trade's `TradeTimer` finished, see `timer::stage` for the
+    /// names the built-in executors use
+    StageTimed { stage: String, provider: Option<SwqosType>, elapsed: Duration },
+    /// The simulation raced against the submission failed with a program error before the
+    /// transaction landed; `Failed` follows
+    PredictedFailed { signature: Signature, reason: String },
+    /// The trade failed
+    Failed { reason: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeLifecycleEvent {
+    /// Identifies the trade across its events
+    pub trade_id: u64,
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    pub trade_type: TradeType,
+    pub stage: TradeStage,
+    /// Time since the executor started the trade
+    pub elapsed: Duration,
+}
+
+/// Emits the lifecycle events of one trade
+///
+/// Cheap to clone; clones report for the same trade. Custom `TradeExecutor`s can use it to
+/// report their trades like the built-in executors do.
+#[derive(Debug, Clone)]
+pub struct TradeLifecycle {
+    trade_id: u64,
+    dex_type: DexType,
+    mint: Pubkey,
+    trade_type: TradeType,
+    start: Instant,
+    landed: Arc<AtomicBool>,
+}
+
+impl TradeLifecycle {
+    pub fn new(dex_type: DexType, mint: Pubkey, trade_type: TradeType) -> Self {
+        Self {
+            trade_id: NEXT_TRADE_ID.fetch_add(1, Ordering::Relaxed),
+            dex_type,
+            mint,
+            trade_type,
+            start: Instant::now(),
+            landed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn trade_id(&self) -> u64 {
+        self.trade_id
+    }
+
+    /// Whether anyone receives the events
+    pub fn is_observed(&self) -> bool {
+        sender().receiver_count() > 0
+    }
+
+    pub fn emit(&self, stage: TradeStage) {
+        if !self.is_observed() {
+            return;
+        }
+        let _ = sender().send(TradeLifecycleEvent {
+            trade_id: self.trade_id,
+            dex_type: self.dex_type.clone(),
+            mint: self.mint,
+            trade_type: self.trade_type,
+            stage,
+            elapsed: self.start.elapsed(),
+        });
+    }
+
+    /// Emit `Landed` unless the trade already landed, looking up the slot with `rpc`
+    pub async fn landed(
+        &self,
+        provider: SwqosType,
+        signature: Signature,
+        rpc: Option<&SolanaRpcClient>,
+    ) {
+        if self.landed.swap(true, Ordering::Relaxed) || !self.is_observed() {
+            return;
+        }
+        let slot = match rpc {
+            Some(rpc) => rpc
+                .get_signature_statuses(&[signature])
+                .await
+                .ok()
+                .and_then(|statuses| statuses.value.into_iter().next().flatten())
+                .map(|status| status.slot),
+            None => None,
+        };
+        self.emit(TradeStage::Landed { provider, signature, slot });
+    }
+
+    /// Run `trade`, emitting `Failed` when it returns an error
+    pub async fn track<T>(&self, trade: impl Future<Output = Result<T>>) -> Result<T> {
+        trade.await.inspect_err(|e| self.emit(TradeStage::Failed { reason: e.to_string() }))
+    }
+}
+
+#[cfg(all(test, feature = "pumpfun"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lifecycle_events() {
+        let mint = Pubkey::new_unique();
+        let lifecycle = TradeLifecycle::new(DexType::PumpFun, mint, TradeType::Buy);
+        // Nothing is sent without subscribers
+        lifecycle.emit(TradeStage::InstructionsBuilt { instructions: 1 });
+
+        let mut events = subscribe();
+        let signature = Signature::new_unique();
+        lifecycle.emit(TradeStage::Signed { signature });
+        lifecycle.landed(SwqosType::Jito, signature, None).await;
+        lifecycle.clone().landed(SwqosType::NextBlock, signature, None).await;
+        let result = lifecycle.track(async { Err::<(), _>(anyhow::anyhow!("expired")) }).await;
+        assert!(result.is_err());
+
+        // Other tests may trade concurrently
+        let mut stages = vec![];
+        while let Ok(event) = events.try_recv() {
+            if event.trade_id == lifecycle.trade_id() {
+                assert_eq!(event.mint, mint);
+                stages.push(event.stage);
+            }
+        }
+        assert_eq!(
+            stages,
+            vec![
+                TradeStage::Signed { signature },
+                TradeStage::Landed { provider: SwqosType::Jito, signature, slot: None },
+                TradeStage::Failed { reason: "expired".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_json_field_names() {
+        let mint = Pubkey::new_unique();
+        let signature = Signature::new_unique();
+        let event = TradeLifecycleEvent {
+            trade_id: 7,
+            dex_type: DexType::PumpFun,
+            mint,
+            trade_type: TradeType::Buy,
+            stage: TradeStage::Landed { provider: SwqosType::Jito, signature, slot: Some(42) },
+            elapsed: Duration::from_millis(350),
+        };
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "trade_id": 7,
+                "dex_type": "PumpFun",
+                "mint": serde_json::to_value(mint).unwrap(),
+                "trade_type": "Buy",
+                "stage": {
+                    "kind": "landed",
+                    "provider": "Jito",
+                    "signature": serde_json::to_value(signature).unwrap(),
+                    "slot": 42,
+                },
+                "elapsed": { "secs": 0, "nanos": 350_000_000 },
+            })
+        );
+        let decoded: TradeLifecycleEvent = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.stage, event.stage);
+        assert_eq!(decoded.elapsed, event.elapsed);
+
+        let timed = TradeStage::StageTimed {
+            stage: "sign".to_string(),
+            provider: None,
+            elapsed: Duration::from_micros(80),
+        };
+        let value = serde_json::to_value(&timed).unwrap();
+        assert_eq!(value["kind"], "stage_timed");
+        assert_eq!(value["stage"], "sign");
+    }
+}
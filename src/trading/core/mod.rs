@@ -1,5 +1,6 @@
 pub mod params;
 pub mod traits;
 pub mod executor;
+pub mod lifecycle;
 pub mod parallel;
 pub mod timer; 
\ No newline at end of file
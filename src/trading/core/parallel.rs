@@ -1,147 +1,394 @@
 use anyhow::{anyhow, Result};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
 use solana_hash::Hash;
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Keypair};
-use std::{str::FromStr, sync::Arc};
-use tokio::sync::mpsc;
-use tokio::task::JoinHandle;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    native_token::sol_str_to_lamports,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{TransactionError, VersionedTransaction},
+};
+use solana_system_interface::instruction::transfer;
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::AbortHandle,
+};
+use tracing::Instrument;
 
 use crate::{
-    common::PriorityFee,
-    swqos::{SwqosClient, SwqosType, TradeType},
+    common::{runtime, PriorityFee, SolanaRpcClient, TradeError, TradeSigner},
+    metrics,
+    swqos::{attribution, latency::SwqosLatency, SwqosClient, SwqosType, TradeType},
     trading::{
         common::{
             build_rpc_transaction, build_sell_tip_transaction_with_priority_fee,
             build_sell_transaction, build_tip_transaction_with_priority_fee,
         },
-        core::timer::TradeTimer,
+        core::{
+            lifecycle::{TradeLifecycle, TradeStage},
+            timer::{stage, TradeTimer},
+        },
+        factory::DexType,
         MiddlewareManager,
     },
 };
 
+/// Clients that can submit the same signed transaction
+///
+/// Clients of one provider type share their tip accounts, so with the same tip amount a
+/// single transaction, tipping the account picked from the first client, is valid for all
+/// of them. It is built and signed once per group instead of once per client.
+struct SubmissionGroup {
+    swqos_type: SwqosType,
+    tip_fee: f64,
+    clients: Vec<Arc<SwqosClient>>,
+    /// Tips of other providers the transaction pays as well, each with a client to pick the
+    /// tip account from
+    extra_tips: Vec<(Arc<SwqosClient>, f64)>,
+}
+
+/// Group `swqos_clients` by provider type and tip, keeping the configured order
+///
+/// Client `i` tips `tip_fees[i % len]`, or `default_tip_fee` when `tip_fees` is empty.
+fn group_clients(
+    swqos_clients: &[Arc<SwqosClient>],
+    tip_fees: &[f64],
+    default_tip_fee: f64,
+) -> Vec<SubmissionGroup> {
+    let mut groups: Vec<SubmissionGroup> = vec![];
+    for (i, client) in swqos_clients.iter().enumerate() {
+        let swqos_type = client.get_swqos_type();
+        let tip_fee =
+            if tip_fees.is_empty() { default_tip_fee } else { tip_fees[i % tip_fees.len()] };
+        match groups
+            .iter_mut()
+            .find(|group| group.swqos_type == swqos_type && group.tip_fee == tip_fee)
+        {
+            Some(group) => group.clients.push(client.clone()),
+            None => groups.push(SubmissionGroup {
+                swqos_type,
+                tip_fee,
+                clients: vec![client.clone()],
+                extra_tips: vec![],
+            }),
+        }
+    }
+    groups
+}
+
+/// Merge `groups` into one group whose single transaction pays the tip of every provider
+///
+/// The first tipping group builds the transaction and the tips of the other tipping groups
+/// are added to it. Every client then submits the same signature, so the race can land at
+/// most once however the providers rebuild or retry it.
+fn merge_groups(groups: Vec<SubmissionGroup>) -> Vec<SubmissionGroup> {
+    let Some(primary) = groups
+        .iter()
+        .position(|group| group.swqos_type != SwqosType::Default)
+        .or((!groups.is_empty()).then_some(0))
+    else {
+        return groups;
+    };
+    let mut groups = groups;
+    let mut merged = groups.remove(primary);
+    for group in groups {
+        if group.swqos_type != SwqosType::Default {
+            merged.extra_tips.push((group.clients[0].clone(), group.tip_fee));
+        }
+        merged.clients.extend(group.clients);
+    }
+    vec![merged]
+}
+
+/// Largest tip a trade raced through `swqos_clients` pays, or with `single_signature` the
+/// sum of the tips its one transaction pays
+pub(crate) fn racing_tip(
+    swqos_clients: &[Arc<SwqosClient>],
+    tip_fees: &[f64],
+    default_tip_fee: f64,
+    single_signature: bool,
+) -> f64 {
+    let tips = group_clients(swqos_clients, tip_fees, default_tip_fee)
+        .into_iter()
+        .filter(|group| group.swqos_type != SwqosType::Default)
+        .map(|group| group.tip_fee);
+    if single_signature {
+        tips.sum()
+    } else {
+        tips.fold(0.0, f64::max)
+    }
+}
+
+/// Trade submitted by `parallel_execute_with_tips`
+pub struct ParallelExecuteParams {
+    pub swqos_clients: Vec<Arc<SwqosClient>>,
+    /// Attributes the landed transaction and runs the raced simulation
+    pub rpc: Option<Arc<SolanaRpcClient>>,
+    pub payer: Arc<dyn TradeSigner>,
+    pub fee_payer: Option<Arc<dyn TradeSigner>>,
+    pub instructions: Vec<Instruction>,
+    pub priority_fee: PriorityFee,
+    pub lookup_table_key: Option<Pubkey>,
+    pub recent_blockhash: Hash,
+    /// Loaded accounts data size limit of buy transactions
+    pub data_size_limit: u32,
+    pub trade_type: TradeType,
+    pub middleware_manager: Option<Arc<MiddlewareManager>>,
+    pub dex_type: DexType,
+    pub is_buy: bool,
+    pub wait_transaction_confirmed: bool,
+    /// Submit one transaction paying every provider's tip
+    pub single_signature: bool,
+    /// Simulate the first transaction while it is being submitted
+    pub simulate_race: bool,
+    pub lifecycle: TradeLifecycle,
+}
+
 /// Generic function for parallel transaction execution
-pub async fn parallel_execute_with_tips(
-    swqos_clients: Vec<Arc<SwqosClient>>,
-    payer: Arc<Keypair>,
-    instructions: Vec<Instruction>,
-    priority_fee: PriorityFee,
-    lookup_table_key: Option<Pubkey>,
-    recent_blockhash: Hash,
-    data_size_limit: u32,
-    trade_type: TradeType,
-    middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: String,
-    is_buy: bool,
-    wait_transaction_confirmed: bool,
-) -> Result<()> {
-    let cores = core_affinity::get_core_ids().unwrap();
-    let mut handles: Vec<JoinHandle<Result<()>>> = vec![];
-
-    for i in 0..swqos_clients.len() {
-        let swqos_client = swqos_clients[i].clone();
+///
+/// The transaction is built and signed once per `SubmissionGroup` and the same bytes are
+/// submitted through every client of the group. When waiting for confirmation and `rpc` is
+/// set, the landed transaction is attributed to the provider whose tip it paid in the
+/// background, see `swqos::attribution`.
+///
+/// With `single_signature` all clients submit one transaction paying every provider's tip,
+/// see `merge_groups`, instead of one transaction per group that could each land. Its tips
+/// don't tell the providers apart, the landing is credited to the first client whose
+/// submission confirmed.
+///
+/// With `simulate_race`, `rpc` set and waiting for confirmation, the first transaction is
+/// simulated while it is being submitted. If the simulation fails with a program error
+/// before anything landed, the submissions still in flight are aborted and the trade fails
+/// right away with `TradeError::PredictedFailure`. Providers that already accepted the
+/// transaction can still land it.
+///
+/// `lifecycle` reports the signed transactions, every submission and the first landing.
+pub async fn parallel_execute_with_tips(params: ParallelExecuteParams) -> Result<()> {
+    let ParallelExecuteParams {
+        swqos_clients,
+        rpc,
+        payer,
+        fee_payer,
+        instructions,
+        priority_fee,
+        lookup_table_key,
+        recent_blockhash,
+        data_size_limit,
+        trade_type,
+        middleware_manager,
+        dex_type,
+        is_buy,
+        wait_transaction_confirmed,
+        single_signature,
+        simulate_race,
+        lifecycle,
+    } = params;
+    let cores = runtime::pinned_cores();
+    // Sells tip `sell_tip_fee` through every client
+    let groups = match trade_type {
+        TradeType::Sell => group_clients(&swqos_clients, &[], priority_fee.sell_tip_fee),
+        _ => group_clients(&swqos_clients, &priority_fee.buy_tip_fees, priority_fee.buy_tip_fee),
+    };
+    let groups = if single_signature { merge_groups(groups) } else { groups };
+    // One result per submission, or per group when its transaction could not be built
+    let (tx, mut rx) = mpsc::channel::<Result<(SwqosType, Signature)>>(swqos_clients.len());
+    // The program error of the raced simulation, with the simulated signature
+    let simulating = simulate_race && wait_transaction_confirmed && rpc.is_some();
+    let (simulation_tx, mut simulation_rx) = oneshot::channel::<(Signature, TransactionError)>();
+    let mut simulation_tx = simulating.then_some(simulation_tx);
+    // Every spawned task, to stop the submissions once the simulation predicted a failure
+    let tasks: Arc<Mutex<Vec<AbortHandle>>> = Arc::default();
+    // Shared by the groups, each clones them once into its own transaction
+    let instructions: Arc<[Instruction]> = instructions.into();
+
+    for (i, group) in groups.into_iter().enumerate() {
+        let simulation_tx = simulation_tx.take();
+        let spawned = tasks.clone();
         let payer = payer.clone();
+        let fee_payer = fee_payer.clone();
         let instructions = instructions.clone();
         let mut priority_fee = priority_fee.clone();
-        let core_id = cores[i % cores.len()];
+        let core_id = cores.as_ref().map(|cores| cores[i % cores.len()]);
 
         let middleware_manager = middleware_manager.clone();
-        let protocol_name = protocol_name.clone();
-
-        let handle = tokio::spawn(async move {
-            core_affinity::set_for_current(core_id);
-
-            let mut timer = TradeTimer::new(format!(
-                "Building transaction instructions: {:?}",
-                swqos_client.get_swqos_type()
-            ));
-
-            let transaction = if matches!(trade_type, TradeType::Sell)
-                && swqos_client.get_swqos_type() == SwqosType::Default
-            {
-                build_sell_transaction(
-                    payer,
-                    &priority_fee,
-                    instructions,
-                    lookup_table_key,
-                    recent_blockhash,
-                    middleware_manager,
-                    protocol_name,
-                    is_buy,
-                )
-                .await?
-            } else if matches!(trade_type, TradeType::Sell)
-                && swqos_client.get_swqos_type() != SwqosType::Default
-            {
-                let tip_account = swqos_client.get_tip_account()?;
-                let tip_account = Arc::new(Pubkey::from_str(&tip_account).map_err(|e| anyhow!(e))?);
-                build_sell_tip_transaction_with_priority_fee(
-                    payer,
-                    &priority_fee,
-                    instructions,
-                    &tip_account,
-                    lookup_table_key,
-                    recent_blockhash,
-                    middleware_manager,
-                    protocol_name,
-                    is_buy,
-                )
-                .await?
-            } else if swqos_client.get_swqos_type() == SwqosType::Default {
-                build_rpc_transaction(
-                    payer,
-                    &priority_fee,
-                    instructions,
-                    lookup_table_key,
-                    recent_blockhash,
-                    data_size_limit,
-                    middleware_manager,
-                    protocol_name,
-                    is_buy,
-                )
-                .await?
-            } else {
-                let tip_account = swqos_client.get_tip_account()?;
-                let tip_account = Arc::new(Pubkey::from_str(&tip_account).map_err(|e| anyhow!(e))?);
-                priority_fee.buy_tip_fee =
-                    priority_fee.buy_tip_fees[i % priority_fee.buy_tip_fees.len()];
-
-                build_tip_transaction_with_priority_fee(
-                    payer,
-                    &priority_fee,
-                    instructions,
-                    &tip_account,
-                    lookup_table_key,
-                    recent_blockhash,
-                    data_size_limit,
-                    middleware_manager,
-                    protocol_name,
-                    is_buy,
-                )
-                .await?
-            };
-
-            timer.stage(format!(
-                "Submitting transaction instructions: {:?}",
-                swqos_client.get_swqos_type()
-            ));
-
-            swqos_client.send_transaction(trade_type, &transaction).await?;
-
-            timer.finish();
-            Ok::<(), anyhow::Error>(())
-        });
-
-        handles.push(handle);
-    }
-    // Return as soon as any one succeeds
-    let (tx, mut rx) = mpsc::channel(swqos_clients.len());
-
-    // Start monitoring tasks
-    for handle in handles {
+        let dex_type = dex_type.clone();
         let tx = tx.clone();
-        tokio::spawn(async move {
-            let result = handle.await;
-            let _ = tx.send(result).await;
-        });
+        let rpc = rpc.clone();
+        let lifecycle = lifecycle.clone();
+        // Spawned tasks don't inherit the caller's span, attach it explicitly
+        let span = tracing::info_span!(
+            "swqos",
+            provider = ?group.swqos_type,
+            clients = group.clients.len()
+        );
+
+        let task = tokio::spawn(
+            async move {
+                if let Some(core_id) = core_id {
+                    core_affinity::set_for_current(core_id);
+                }
+
+                let mut timer = TradeTimer::new(stage::SIGN).with_lifecycle(lifecycle.clone());
+
+                let build = async {
+                    // Paid by the fee payer like the tip the builders add
+                    let tip_payer = fee_payer.as_ref().unwrap_or(&payer).pubkey();
+                    let mut extra_tips = Vec::with_capacity(group.extra_tips.len());
+                    for (client, tip_fee) in &group.extra_tips {
+                        let tip_account =
+                            Pubkey::from_str(&client.get_tip_account()?).map_err(|e| anyhow!(e))?;
+                        let lamports =
+                            sol_str_to_lamports(tip_fee.to_string().as_str()).unwrap_or(0);
+                        extra_tips.push(transfer(&tip_payer, &tip_account, lamports));
+                    }
+                    let instructions = instructions.iter().cloned().chain(extra_tips);
+                    let transaction = if matches!(trade_type, TradeType::Sell)
+                        && group.swqos_type == SwqosType::Default
+                    {
+                        build_sell_transaction(
+                            payer,
+                            fee_payer,
+                            &priority_fee,
+                            instructions,
+                            lookup_table_key,
+                            recent_blockhash,
+                            middleware_manager,
+                            dex_type,
+                            is_buy,
+                        )
+                        .await?
+                    } else if matches!(trade_type, TradeType::Sell) {
+                        let tip_account = group.clients[0].get_tip_account()?;
+                        let tip_account =
+                            Arc::new(Pubkey::from_str(&tip_account).map_err(|e| anyhow!(e))?);
+                        build_sell_tip_transaction_with_priority_fee(
+                            payer,
+                            fee_payer,
+                            &priority_fee,
+                            instructions,
+                            &tip_account,
+                            lookup_table_key,
+                            recent_blockhash,
+                            middleware_manager,
+                            dex_type,
+                            is_buy,
+                        )
+                        .await?
+                    } else if group.swqos_type == SwqosType::Default {
+                        build_rpc_transaction(
+                            payer,
+                            fee_payer,
+                            &priority_fee,
+                            instructions,
+                            lookup_table_key,
+                            recent_blockhash,
+                            data_size_limit,
+                            middleware_manager,
+                            dex_type,
+                            is_buy,
+                        )
+                        .await?
+                    } else {
+                        let tip_account = group.clients[0].get_tip_account()?;
+                        let tip_account =
+                            Arc::new(Pubkey::from_str(&tip_account).map_err(|e| anyhow!(e))?);
+                        priority_fee.buy_tip_fee = group.tip_fee;
+
+                        build_tip_transaction_with_priority_fee(
+                            payer,
+                            fee_payer,
+                            &priority_fee,
+                            instructions,
+                            &tip_account,
+                            lookup_table_key,
+                            recent_blockhash,
+                            data_size_limit,
+                            middleware_manager,
+                            dex_type,
+                            is_buy,
+                        )
+                        .await?
+                    };
+                    Ok::<_, anyhow::Error>(transaction)
+                };
+                let transaction = match build.await {
+                    Ok(transaction) => {
+                        lifecycle.emit(TradeStage::Signed { signature: transaction.signatures[0] });
+                        Arc::new(transaction)
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(e)).await;
+                        return;
+                    }
+                };
+
+                timer.stage(stage::CONFIRM);
+
+                if let (Some(simulation_tx), Some(rpc)) = (simulation_tx, rpc.clone()) {
+                    let transaction = transaction.clone();
+                    let task = tokio::spawn(async move {
+                        if let Some(err) = simulate_program_error(&rpc, &transaction).await {
+                            let _ = simulation_tx.send((transaction.signatures[0], err));
+                        }
+                    });
+                    spawned.lock().unwrap().push(task.abort_handle());
+                }
+
+                let timers = vec![timer; group.clients.len()];
+                for (swqos_client, timer) in group.clients.into_iter().zip(timers) {
+                    let transaction = transaction.clone();
+                    let tx = tx.clone();
+                    let rpc = rpc.clone();
+                    let lifecycle = lifecycle.clone();
+                    let span =
+                        tracing::info_span!("submit", provider = ?swqos_client.get_swqos_type());
+                    let task = tokio::spawn(
+                        async move {
+                            let swqos_type = swqos_client.get_swqos_type();
+                            let timer = timer.with_provider(swqos_type.clone());
+                            let provider = format!("{:?}", swqos_type);
+                            let signature = transaction.signatures[0];
+                            lifecycle.emit(TradeStage::Submitted {
+                                provider: swqos_type.clone(),
+                                signature,
+                            });
+                            let start_time = Instant::now();
+                            let result = swqos_client
+                                .send_transaction(trade_type, &transaction)
+                                .await
+                                .map(|_| (swqos_type.clone(), signature))
+                                .map_err(|e| TradeError::from_swqos(&provider, e));
+                            metrics::record_submission(
+                                &provider,
+                                start_time.elapsed(),
+                                result.as_ref().err(),
+                            );
+                            if result.is_ok() {
+                                SwqosLatency::get_instance()
+                                    .record(swqos_type.clone(), start_time.elapsed());
+                            }
+                            timer.finish();
+                            let landed = result.is_ok();
+                            let _ = tx.send(result.map_err(anyhow::Error::from)).await;
+                            // After reporting the result so the slot lookup cannot delay the trade
+                            if landed {
+                                lifecycle.landed(swqos_type, signature, rpc.as_deref()).await;
+                            }
+                        }
+                        .instrument(span),
+                    );
+                    spawned.lock().unwrap().push(task.abort_handle());
+                }
+            }
+            .instrument(span),
+        );
+        tasks.lock().unwrap().push(task.abort_handle());
     }
     drop(tx); // Close the sender
 
@@ -152,16 +399,148 @@ pub async fn parallel_execute_with_tips(
         return Ok(());
     }
 
-    while let Some(result) = rx.recv().await {
+    let mut simulating = simulating;
+    loop {
+        let result = tokio::select! {
+            result = rx.recv() => match result {
+                Some(result) => result,
+                None => break,
+            },
+            predicted = &mut simulation_rx, if simulating => {
+                simulating = false;
+                // The sender is dropped when the simulation found no program error
+                let Ok((signature, err)) = predicted else { continue };
+                for task in tasks.lock().unwrap().drain(..) {
+                    task.abort();
+                }
+                tracing::info!(%signature, error = %err, "simulation predicts the trade fails");
+                lifecycle.emit(TradeStage::PredictedFailed { signature, reason: err.to_string() });
+                return Err(TradeError::PredictedFailure(err).into());
+            }
+        };
         match result {
-            Ok(Ok(_)) => {
+            Ok((provider, signature)) => {
+                if single_signature {
+                    metrics::record_landing(&format!("{:?}", provider));
+                } else if let Some(rpc) = rpc {
+                    tokio::spawn(async move {
+                        if let Err(e) = attribution::attribute_landed(&rpc, &signature).await {
+                            tracing::debug!(%signature, error = %e, "landing attribution failed");
+                        }
+                    });
+                }
                 return Ok(());
             }
-            Ok(Err(e)) => errors.push(format!("Task error: {}", e)),
-            Err(e) => errors.push(format!("Join error: {}", e)),
+            Err(e) => errors.push(TradeError::from(e)),
         }
     }
 
     // If no success, return error
-    return Err(anyhow!("All transactions failed: {:?}", errors));
+    Err(TradeError::from_submissions(errors).into())
+}
+
+/// Program error the simulation of `transaction` fails with, `None` when it succeeds, fails
+/// for another reason or cannot be run
+async fn simulate_program_error(
+    rpc: &SolanaRpcClient,
+    transaction: &VersionedTransaction,
+) -> Option<TransactionError> {
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        commitment: Some(CommitmentConfig::processed()),
+        ..Default::default()
+    };
+    match rpc.simulate_transaction_with_config(transaction, config).await {
+        Ok(response) => {
+            response.value.err.filter(|err| matches!(err, TransactionError::InstructionError(..)))
+        }
+        Err(e) => {
+            tracing::debug!(error = %e, "raced simulation failed");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockRpc, MockSwqos};
+    use serde_json::json;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_sdk::{
+        instruction::InstructionError, message::VersionedMessage, signature::Keypair,
+        signer::Signer,
+    };
+
+    #[test]
+    fn test_group_clients_by_type_and_tip() {
+        let clients: Vec<Arc<SwqosClient>> = vec![
+            Arc::new(MockSwqos::new(SwqosType::Jito)),
+            Arc::new(MockSwqos::new(SwqosType::Jito)),
+            Arc::new(MockSwqos::new(SwqosType::NextBlock)),
+            Arc::new(MockSwqos::new(SwqosType::Jito)),
+        ];
+        let groups = group_clients(&clients, &[0.001, 0.001, 0.001, 0.002], 0.0);
+        let sizes: Vec<_> =
+            groups.iter().map(|g| (g.swqos_type.clone(), g.tip_fee, g.clients.len())).collect();
+        assert_eq!(
+            sizes,
+            vec![
+                (SwqosType::Jito, 0.001, 2),
+                (SwqosType::NextBlock, 0.001, 1),
+                (SwqosType::Jito, 0.002, 1)
+            ]
+        );
+        assert_eq!(group_clients(&clients, &[], 0.001).len(), 2);
+    }
+
+    #[test]
+    fn test_merge_groups_into_one_transaction() {
+        let clients: Vec<Arc<SwqosClient>> = vec![
+            Arc::new(MockSwqos::new(SwqosType::Default)),
+            Arc::new(MockSwqos::new(SwqosType::Jito)),
+            Arc::new(MockSwqos::new(SwqosType::NextBlock)),
+        ];
+        let merged = merge_groups(group_clients(&clients, &[0.001, 0.001, 0.002], 0.0));
+        assert_eq!(merged.len(), 1);
+        assert_eq!((merged[0].swqos_type.clone(), merged[0].tip_fee), (SwqosType::Jito, 0.001));
+        assert_eq!(merged[0].clients.len(), 3);
+        let extra: Vec<_> =
+            merged[0].extra_tips.iter().map(|(c, tip)| (c.get_swqos_type(), *tip)).collect();
+        assert_eq!(extra, vec![(SwqosType::NextBlock, 0.002)]);
+
+        assert!((racing_tip(&clients, &[0.001, 0.001, 0.002], 0.0, true) - 0.003).abs() < 1e-12);
+        assert_eq!(racing_tip(&clients, &[0.001, 0.001, 0.002], 0.0, false), 0.002);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_program_error() {
+        let payer = Keypair::new();
+        let message = solana_sdk::message::v0::Message::try_compile(
+            &payer.pubkey(),
+            &[transfer(&payer.pubkey(), &Pubkey::new_unique(), 1)],
+            &[],
+            Hash::new_unique(),
+        )
+        .unwrap();
+        let transaction =
+            VersionedTransaction::try_new(VersionedMessage::V0(message), &[&payer]).unwrap();
+        let simulation = |err| json!({ "context": { "slot": 1 }, "value": { "err": err } });
+
+        let rpc = MockRpc::new()
+            .with_response(
+                RpcRequest::SimulateTransaction,
+                simulation(json!({ "InstructionError": [2, { "Custom": 6002 }] })),
+            )
+            .client();
+        assert_eq!(
+            simulate_program_error(&rpc, &transaction).await,
+            Some(TransactionError::InstructionError(2, InstructionError::Custom(6002)))
+        );
+
+        let rpc = MockRpc::new()
+            .with_response(RpcRequest::SimulateTransaction, simulation(json!("BlockhashNotFound")))
+            .client();
+        assert_eq!(simulate_program_error(&rpc, &transaction).await, None);
+    }
 }
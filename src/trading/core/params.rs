@@ -1,29 +1,46 @@
 use solana_hash::Hash;
-use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use solana_sdk::pubkey::Pubkey;
+#[cfg(feature = "pumpfun")]
 use solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunTradeEvent;
+#[cfg(feature = "pumpswap")]
 use solana_streamer_sdk::streaming::event_parser::protocols::pumpswap::{
-    PumpSwapBuyEvent, PumpSwapSellEvent,
+    types::Pool, PumpSwapBuyEvent, PumpSwapSellEvent,
 };
+#[cfg(feature = "raydium")]
 use solana_streamer_sdk::streaming::event_parser::protocols::raydium_amm_v4::types::AmmInfo;
+#[cfg(feature = "raydium")]
+use solana_streamer_sdk::streaming::event_parser::protocols::raydium_cpmm::types::PoolState;
 use std::sync::Arc;
 
 use super::traits::ProtocolParams;
+#[cfg(feature = "pumpfun")]
 use crate::common::bonding_curve::BondingCurveAccount;
-use crate::common::{PriorityFee, SolanaRpcClient};
-use crate::constants::bonk::accounts::{
-    self, PLATFORM_FEE_RATE, PROTOCOL_FEE_RATE, SHARE_FEE_RATE,
-};
+use crate::common::{PriorityFee, SolanaRpcClient, TradeSigner};
+#[cfg(any(feature = "bonk", feature = "pumpswap"))]
+use crate::constants::bonk::accounts;
+#[cfg(feature = "bonk")]
+use crate::constants::bonk::accounts::{PLATFORM_FEE_RATE, PROTOCOL_FEE_RATE, SHARE_FEE_RATE};
+#[cfg(feature = "bonk")]
 use crate::solana_streamer_sdk::streaming::event_parser::common::EventType;
+#[cfg(feature = "bonk")]
 use crate::solana_streamer_sdk::streaming::event_parser::protocols::bonk::BonkTradeEvent;
 use crate::swqos::SwqosClient;
+#[cfg(feature = "bonk")]
 use crate::trading::bonk::common::{
     get_amount_in, get_amount_in_net, get_amount_out, get_creator_associated_account,
     get_platform_associated_account,
 };
+#[cfg(feature = "raydium")]
 use crate::trading::common::get_multi_token_balances;
-use crate::trading::pumpswap::common::{
-    coin_creator_vault_ata, coin_creator_vault_authority, get_token_balances,
-};
+use crate::trading::common::ContentionFeeConfig;
+use crate::trading::core::lifecycle::TradeLifecycle;
+#[cfg(feature = "pumpswap")]
+use crate::trading::pumpswap::common::{coin_creator_vault_ata, coin_creator_vault_authority};
+#[cfg(feature = "pumpswap")]
+use crate::trading::pumpswap::fee_config::FeeConfigCache;
+#[cfg(feature = "pumpswap")]
+use crate::trading::pumpswap::reserves_cache::PoolReservesCache;
+#[cfg(feature = "raydium")]
 use crate::trading::raydium_cpmm::common::get_pool_token_balances;
 
 /// Common buy parameters
@@ -31,7 +48,9 @@ use crate::trading::raydium_cpmm::common::get_pool_token_balances;
 #[derive(Clone)]
 pub struct BuyParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
-    pub payer: Arc<Keypair>,
+    pub payer: Arc<dyn TradeSigner>,
+    /// Pays transaction fees and tips when set; token accounts stay owned by `payer`
+    pub fee_payer: Option<Arc<dyn TradeSigner>>,
     pub mint: Pubkey,
     pub sol_amount: u64,
     pub slippage_basis_points: Option<u64>,
@@ -41,6 +60,8 @@ pub struct BuyParams {
     pub data_size_limit: u32,
     pub wait_transaction_confirmed: bool,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// Lifecycle the executor reports on, a new trade is started when `None`
+    pub lifecycle: Option<TradeLifecycle>,
 }
 
 /// Buy parameters with MEV service support
@@ -49,7 +70,9 @@ pub struct BuyParams {
 pub struct BuyWithTipParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
     pub swqos_clients: Vec<Arc<SwqosClient>>,
-    pub payer: Arc<Keypair>,
+    pub payer: Arc<dyn TradeSigner>,
+    /// Pays transaction fees and tips when set; token accounts stay owned by `payer`
+    pub fee_payer: Option<Arc<dyn TradeSigner>>,
     pub mint: Pubkey,
     pub sol_amount: u64,
     pub slippage_basis_points: Option<u64>,
@@ -59,6 +82,17 @@ pub struct BuyWithTipParams {
     pub data_size_limit: u32,
     pub wait_transaction_confirmed: bool,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// Lifecycle the executor reports on, a new trade is started when `None`
+    pub lifecycle: Option<TradeLifecycle>,
+    /// Race one transaction paying every provider's tip instead of one per provider
+    pub single_signature: bool,
+    /// Simulate the transaction while submitting it and fail early on a program error
+    pub simulate_race: bool,
+    /// Price compute units by the fees paid on the trade's writable accounts
+    pub contention_fee: Option<ContentionFeeConfig>,
+    /// With `data_size_limit` 0, size the limit to the accounts the trade loads plus this
+    /// headroom instead of using the fixed default
+    pub data_size_headroom_bps: Option<u64>,
 }
 
 /// Common sell parameters
@@ -66,7 +100,9 @@ pub struct BuyWithTipParams {
 #[derive(Clone)]
 pub struct SellParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
-    pub payer: Arc<Keypair>,
+    pub payer: Arc<dyn TradeSigner>,
+    /// Pays transaction fees and tips when set; token accounts stay owned by `payer`
+    pub fee_payer: Option<Arc<dyn TradeSigner>>,
     pub mint: Pubkey,
     pub token_amount: Option<u64>,
     pub slippage_basis_points: Option<u64>,
@@ -75,6 +111,8 @@ pub struct SellParams {
     pub recent_blockhash: Hash,
     pub wait_transaction_confirmed: bool,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// Lifecycle the executor reports on, a new trade is started when `None`
+    pub lifecycle: Option<TradeLifecycle>,
 }
 
 /// Sell parameters with MEV service support
@@ -83,7 +121,9 @@ pub struct SellParams {
 pub struct SellWithTipParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
     pub swqos_clients: Vec<Arc<SwqosClient>>,
-    pub payer: Arc<Keypair>,
+    pub payer: Arc<dyn TradeSigner>,
+    /// Pays transaction fees and tips when set; token accounts stay owned by `payer`
+    pub fee_payer: Option<Arc<dyn TradeSigner>>,
     pub mint: Pubkey,
     pub token_amount: Option<u64>,
     pub slippage_basis_points: Option<u64>,
@@ -92,10 +132,19 @@ pub struct SellWithTipParams {
     pub recent_blockhash: Hash,
     pub wait_transaction_confirmed: bool,
     pub protocol_params: Box<dyn ProtocolParams>,
+    /// Lifecycle the executor reports on, a new trade is started when `None`
+    pub lifecycle: Option<TradeLifecycle>,
+    /// Race one transaction paying every provider's tip instead of one per provider
+    pub single_signature: bool,
+    /// Simulate the transaction while submitting it and fail early on a program error
+    pub simulate_race: bool,
+    /// Price compute units by the fees paid on the trade's writable accounts
+    pub contention_fee: Option<ContentionFeeConfig>,
 }
 
 /// PumpFun protocol specific parameters
 /// Configuration parameters specific to PumpFun trading protocol
+#[cfg(feature = "pumpfun")]
 #[derive(Clone)]
 pub struct PumpFunParams {
     pub bonding_curve: Arc<BondingCurveAccount>,
@@ -104,6 +153,7 @@ pub struct PumpFunParams {
     pub close_token_account_when_sell: Option<bool>,
 }
 
+#[cfg(feature = "pumpfun")]
 impl PumpFunParams {
     pub fn immediate_sell(creator_vault: Pubkey, close_token_account_when_sell: bool) -> Self {
         Self {
@@ -141,8 +191,37 @@ impl PumpFunParams {
             close_token_account_when_sell: close_token_account_when_sell,
         }
     }
+
+    /// Build params from the bonding curve cache, fetching from RPC only on a miss
+    pub async fn from_mint_by_rpc(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+        close_token_account_when_sell: Option<bool>,
+    ) -> Result<Self, anyhow::Error> {
+        let bonding_curve =
+            crate::trading::pumpfun::common::get_bonding_curve_account(rpc, mint).await?;
+        Ok(Self {
+            creator_vault: bonding_curve.get_creator_vault_pda(),
+            bonding_curve,
+            close_token_account_when_sell,
+        })
+    }
+
+    /// Build params from `fetch_pumpfun_trade_accounts`, which reads the user's balances in
+    /// the same round trip as the bonding curve
+    pub fn from_trade_accounts(
+        accounts: &crate::trading::common::PumpFunTradeAccounts,
+        close_token_account_when_sell: Option<bool>,
+    ) -> Self {
+        Self {
+            creator_vault: accounts.bonding_curve.get_creator_vault_pda(),
+            bonding_curve: accounts.bonding_curve.clone(),
+            close_token_account_when_sell,
+        }
+    }
 }
 
+#[cfg(feature = "pumpfun")]
 impl ProtocolParams for PumpFunParams {
     fn as_any(&self) -> &dyn std::any::Any {
         self
@@ -161,6 +240,7 @@ impl ProtocolParams for PumpFunParams {
 /// **Performance Note**: If these parameters are not provided, the system will attempt to
 /// retrieve the relevant information from RPC, which will increase transaction time.
 /// For optimal performance, it is recommended to provide all necessary parameters in advance.
+#[cfg(feature = "pumpswap")]
 #[derive(Clone)]
 pub struct PumpSwapParams {
     /// Liquidity pool address
@@ -185,9 +265,11 @@ pub struct PumpSwapParams {
     pub quote_token_program: Pubkey,
     /// Automatically handle WSOL wrapping
     /// When true, automatically handles wrapping and unwrapping operations between SOL and WSOL
+    /// Has no effect on pools quoted in another token (e.g. USDC), where amounts are in quote token units
     pub auto_handle_wsol: bool,
 }
 
+#[cfg(feature = "pumpswap")]
 impl PumpSwapParams {
     pub fn from_buy_trade(event: &PumpSwapBuyEvent) -> Self {
         Self {
@@ -219,13 +301,31 @@ impl PumpSwapParams {
         }
     }
 
+    /// Build params from the pool account and reserves, served from `PoolReservesCache`
+    /// and falling back to RPC on a miss
     pub async fn from_pool_address_by_rpc(
         rpc: &SolanaRpcClient,
         pool_address: &Pubkey,
     ) -> Result<Self, anyhow::Error> {
-        let pool_data = crate::trading::pumpswap::common::fetch_pool(rpc, pool_address).await?;
-        let (pool_base_token_reserves, pool_quote_token_reserves) =
-            get_token_balances(&pool_data, rpc).await?;
+        let (pool_data, pool_base_token_reserves, pool_quote_token_reserves) =
+            PoolReservesCache::get_instance().get_or_fetch(rpc, pool_address).await?;
+        // Keep the fee rates used by the quote math in sync with the program
+        FeeConfigCache::get_instance().refresh_if_stale(rpc).await;
+        Ok(Self::from_pool_with_reserves(
+            pool_address,
+            &pool_data,
+            pool_base_token_reserves,
+            pool_quote_token_reserves,
+        ))
+    }
+
+    /// Build params from caller-supplied pool account and reserves without any RPC request
+    pub fn from_pool_with_reserves(
+        pool_address: &Pubkey,
+        pool_data: &Pool,
+        pool_base_token_reserves: u64,
+        pool_quote_token_reserves: u64,
+    ) -> Self {
         let creator = pool_data.creator;
         let coin_creator_vault_ata = coin_creator_vault_ata(creator, pool_data.quote_mint);
         let coin_creator_vault_authority = coin_creator_vault_authority(creator);
@@ -243,7 +343,7 @@ impl PumpSwapParams {
                 &accounts::TOKEN_PROGRAM,
             );
 
-        Ok(Self {
+        Self {
             pool: pool_address.clone(),
             base_mint: pool_data.base_mint,
             quote_mint: pool_data.quote_mint,
@@ -262,10 +362,11 @@ impl PumpSwapParams {
                 spl_token_2022::ID
             },
             auto_handle_wsol: true,
-        })
+        }
     }
 }
 
+#[cfg(feature = "pumpswap")]
 impl ProtocolParams for PumpSwapParams {
     fn as_any(&self) -> &dyn std::any::Any {
         self
@@ -278,6 +379,7 @@ impl ProtocolParams for PumpSwapParams {
 
 /// Bonk protocol specific parameters
 /// Configuration parameters specific to Bonk trading protocol
+#[cfg(feature = "bonk")]
 #[derive(Clone, Default)]
 pub struct BonkParams {
     pub virtual_base: u128,
@@ -293,6 +395,7 @@ pub struct BonkParams {
     pub auto_handle_wsol: bool,
 }
 
+#[cfg(feature = "bonk")]
 impl BonkParams {
     pub fn immediate_sell(
         mint_token_program: Pubkey,
@@ -340,10 +443,13 @@ impl BonkParams {
                 0,
                 0,
             )
+            // Only an amount beyond the fresh curve fails, which a real dev buy cannot be
+            .unwrap_or(trade_info.amount_in)
         };
+        // Neither fails with the default fee rates and reserves of a fresh curve
         let real_quote =
             get_amount_in_net(amount_in, PROTOCOL_FEE_RATE, PLATFORM_FEE_RATE, SHARE_FEE_RATE)
-                as u128;
+                .unwrap_or_default() as u128;
         let amount_out = if trade_info.metadata.event_type == EventType::BonkBuyExactIn {
             get_amount_out(
                 trade_info.amount_in,
@@ -355,7 +461,8 @@ impl BonkParams {
                 0,
                 0,
                 0,
-            ) as u128
+            )
+            .unwrap_or_default() as u128
         } else {
             trade_info.amount_out as u128
         };
@@ -381,7 +488,9 @@ impl BonkParams {
             crate::trading::bonk::common::get_pool_pda(mint, &accounts::WSOL_TOKEN_ACCOUNT)
                 .unwrap();
         let pool_data = crate::trading::bonk::common::fetch_pool_state(rpc, &pool_address).await?;
-        let token_account = rpc.get_account(&pool_data.base_mint).await?;
+        let mint_info = crate::common::mint_info_cache::MintInfoCache::get_instance()
+            .get_or_fetch(rpc, &pool_data.base_mint)
+            .await?;
         let platform_associated_account =
             get_platform_associated_account(&pool_data.platform_config);
         let creator_associated_account = get_creator_associated_account(&pool_data.creator);
@@ -392,7 +501,7 @@ impl BonkParams {
             virtual_quote: pool_data.virtual_quote as u128,
             real_base: pool_data.real_base as u128,
             real_quote: pool_data.real_quote as u128,
-            mint_token_program: token_account.owner,
+            mint_token_program: mint_info.token_program,
             platform_config: pool_data.platform_config,
             platform_associated_account,
             creator_associated_account,
@@ -401,6 +510,7 @@ impl BonkParams {
     }
 }
 
+#[cfg(feature = "bonk")]
 impl ProtocolParams for BonkParams {
     fn as_any(&self) -> &dyn std::any::Any {
         self
@@ -413,8 +523,13 @@ impl ProtocolParams for BonkParams {
 
 /// RaydiumCpmm protocol specific parameters
 /// Configuration parameters specific to Raydium CPMM trading protocol
+#[cfg(feature = "raydium")]
 #[derive(Clone)]
 pub struct RaydiumCpmmParams {
+    /// Pool state address, derived from `amm_config` and the mints when left as default
+    pub pool_state: Pubkey,
+    /// AMM config of the pool (fee tier), the default config is used when left as default
+    pub amm_config: Pubkey,
     /// Base token mint address
     pub base_mint: Pubkey,
     /// Quote token mint address
@@ -431,6 +546,7 @@ pub struct RaydiumCpmmParams {
     pub auto_handle_wsol: bool,
 }
 
+#[cfg(feature = "raydium")]
 impl RaydiumCpmmParams {
     pub async fn from_pool_address_by_rpc(
         rpc: &SolanaRpcClient,
@@ -441,7 +557,19 @@ impl RaydiumCpmmParams {
         let (token0_balance, token1_balance) =
             get_pool_token_balances(rpc, pool_address, &pool.token0_mint, &pool.token1_mint)
                 .await?;
-        Ok(Self {
+        Ok(Self::from_pool_state(pool_address, &pool, token0_balance, token1_balance))
+    }
+
+    /// Build params from a decoded pool state and its vault balances without any RPC request
+    pub fn from_pool_state(
+        pool_address: &Pubkey,
+        pool: &PoolState,
+        token0_balance: u64,
+        token1_balance: u64,
+    ) -> Self {
+        Self {
+            pool_state: *pool_address,
+            amm_config: pool.amm_config,
             base_mint: pool.token0_mint,
             quote_mint: pool.token1_mint,
             base_reserve: token0_balance,
@@ -449,10 +577,30 @@ impl RaydiumCpmmParams {
             base_token_program: pool.token0_program,
             quote_token_program: pool.token1_program,
             auto_handle_wsol: true,
-        })
+        }
+    }
+
+    /// Resolve the pool state and AMM config addresses used by the swap instruction
+    pub fn pool_keys(&self) -> Option<(Pubkey, Pubkey)> {
+        let amm_config = if self.amm_config == Pubkey::default() {
+            crate::constants::raydium_cpmm::accounts::AMM_CONFIG
+        } else {
+            self.amm_config
+        };
+        let pool_state = if self.pool_state == Pubkey::default() {
+            crate::trading::raydium_cpmm::common::get_pool_pda(
+                &amm_config,
+                &self.base_mint,
+                &self.quote_mint,
+            )?
+        } else {
+            self.pool_state
+        };
+        Some((pool_state, amm_config))
     }
 }
 
+#[cfg(feature = "raydium")]
 impl ProtocolParams for RaydiumCpmmParams {
     fn as_any(&self) -> &dyn std::any::Any {
         self
@@ -465,6 +613,7 @@ impl ProtocolParams for RaydiumCpmmParams {
 
 /// RaydiumCpmm protocol specific parameters
 /// Configuration parameters specific to Raydium CPMM trading protocol
+#[cfg(feature = "raydium")]
 #[derive(Clone)]
 pub struct RaydiumAmmV4Params {
     /// AMM pool address
@@ -485,6 +634,7 @@ pub struct RaydiumAmmV4Params {
     pub auto_handle_wsol: bool,
 }
 
+#[cfg(feature = "raydium")]
 impl RaydiumAmmV4Params {
     pub fn from_amm_info_and_reserves(
         amm: Pubkey,
@@ -523,6 +673,7 @@ impl RaydiumAmmV4Params {
     }
 }
 
+#[cfg(feature = "raydium")]
 impl ProtocolParams for RaydiumAmmV4Params {
     fn as_any(&self) -> &dyn std::any::Any {
         self
@@ -541,6 +692,7 @@ impl BuyParams {
             rpc: self.rpc,
             swqos_clients,
             payer: self.payer,
+            fee_payer: self.fee_payer,
             mint: self.mint,
             sol_amount: self.sol_amount,
             slippage_basis_points: self.slippage_basis_points,
@@ -550,6 +702,11 @@ impl BuyParams {
             data_size_limit: self.data_size_limit,
             wait_transaction_confirmed: self.wait_transaction_confirmed,
             protocol_params: self.protocol_params,
+            lifecycle: self.lifecycle,
+            single_signature: false,
+            simulate_race: false,
+            contention_fee: None,
+            data_size_headroom_bps: None,
         }
     }
 }
@@ -562,6 +719,7 @@ impl SellParams {
             rpc: self.rpc,
             swqos_clients,
             payer: self.payer,
+            fee_payer: self.fee_payer,
             mint: self.mint,
             token_amount: self.token_amount,
             slippage_basis_points: self.slippage_basis_points,
@@ -570,6 +728,10 @@ impl SellParams {
             recent_blockhash: self.recent_blockhash,
             wait_transaction_confirmed: self.wait_transaction_confirmed,
             protocol_params: self.protocol_params,
+            lifecycle: self.lifecycle,
+            single_signature: false,
+            simulate_race: false,
+            contention_fee: None,
         }
     }
 }
@@ -1,45 +1,140 @@
-use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use super::lifecycle::{TradeLifecycle, TradeStage};
+use crate::swqos::SwqosType;
+
+/// Stage names used by the built-in executors
+pub mod stage {
+    /// Building the protocol instructions and applying the middlewares
+    pub const BUILD: &str = "build";
+    /// Building and signing the transaction
+    pub const SIGN: &str = "sign";
+    /// Sending the transaction without waiting for it to land
+    pub const SUBMIT: &str = "submit";
+    /// Sending the transaction until it confirmed; swqos clients always wait for it
+    pub const CONFIRM: &str = "confirm";
+}
+
+/// Duration of one finished stage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageTiming {
+    pub stage: String,
+    /// Provider the stage ran against, for the per provider submission stages
+    pub provider: Option<SwqosType>,
+    pub elapsed: Duration,
+}
 
 /// Trade time measurement tool
+///
+/// Finished stages are kept as `StageTiming`s, logged at debug level and, with a
+/// lifecycle, reported as `TradeStage::StageTimed`. Each stage also gets a `trade_stage`
+/// debug span, so tracing exporters see the stage durations within the trade span.
 #[derive(Clone)]
 pub struct TradeTimer {
     start_time: Instant,
     stage: String,
+    provider: Option<SwqosType>,
+    stages: Vec<StageTiming>,
+    lifecycle: Option<TradeLifecycle>,
+    span: tracing::Span,
 }
 
 impl TradeTimer {
     /// Create a new timer
     pub fn new(stage: impl Into<String>) -> Self {
-        Self { start_time: Instant::now(), stage: stage.into() }
+        let stage = stage.into();
+        Self {
+            start_time: Instant::now(),
+            span: stage_span(&stage),
+            stage,
+            provider: None,
+            stages: Vec::new(),
+            lifecycle: None,
+        }
+    }
+
+    /// Report finished stages as lifecycle events of the trade
+    pub fn with_lifecycle(mut self, lifecycle: TradeLifecycle) -> Self {
+        self.lifecycle = Some(lifecycle);
+        self
+    }
+
+    /// Attribute the current and following stages to `provider`
+    pub fn with_provider(mut self, provider: SwqosType) -> Self {
+        self.provider = Some(provider);
+        self
     }
 
     /// Record current stage time and start a new stage
     pub fn stage(&mut self, new_stage: impl Into<String>) {
-        let elapsed = self.start_time.elapsed();
-        println!(" {} time cost: {:?}", self.stage, elapsed);
+        self.record();
 
         self.start_time = Instant::now();
         self.stage = new_stage.into();
+        self.span = stage_span(&self.stage);
     }
 
-    /// Complete timing and output final time cost
-    pub fn finish(mut self) {
-        let elapsed = self.start_time.elapsed();
-        println!(" {} time cost: {:?}", self.stage, elapsed);
-        self.stage.clear(); // Clear stage to avoid duplicate printing in Drop
+    /// Complete timing and return all stage durations
+    pub fn finish(mut self) -> Vec<StageTiming> {
+        self.record();
+        self.stage.clear(); // Clear stage to avoid duplicate recording in Drop
+        std::mem::take(&mut self.stages)
     }
 
     /// Get the elapsed time of current stage (without resetting the timer)
-    pub fn elapsed(&self) -> std::time::Duration {
+    pub fn elapsed(&self) -> Duration {
         self.start_time.elapsed()
     }
+
+    /// Stages finished so far
+    pub fn stages(&self) -> &[StageTiming] {
+        &self.stages
+    }
+
+    fn record(&mut self) {
+        let elapsed = self.start_time.elapsed();
+        tracing::debug!(stage = %self.stage, provider = ?self.provider, ?elapsed, "trade stage");
+        if let Some(lifecycle) = &self.lifecycle {
+            lifecycle.emit(TradeStage::StageTimed {
+                stage: self.stage.clone(),
+                provider: self.provider.clone(),
+                elapsed,
+            });
+        }
+        self.stages.push(StageTiming {
+            stage: self.stage.clone(),
+            provider: self.provider.clone(),
+            elapsed,
+        });
+    }
+}
+
+/// Span covering one stage; it is never entered, only its lifetime matters
+fn stage_span(stage: &str) -> tracing::Span {
+    tracing::debug_span!("trade_stage", stage)
 }
 
 impl Drop for TradeTimer {
     fn drop(&mut self) {
         if !self.stage.is_empty() {
-            let elapsed = self.start_time.elapsed();
-            println!(" {} time cost: {:?}", self.stage, elapsed);
+            self.record();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_timings() {
+        let mut timer = TradeTimer::new(stage::BUILD);
+        timer.stage(stage::SIGN);
+        let timer = timer.with_provider(SwqosType::Jito);
+        assert_eq!(timer.stages().len(), 1);
+        let stages = timer.finish();
+        let names: Vec<_> = stages.iter().map(|s| (s.stage.as_str(), s.provider.clone())).collect();
+        assert_eq!(names, vec![(stage::BUILD, None), (stage::SIGN, Some(SwqosType::Jito))]);
+    }
+}
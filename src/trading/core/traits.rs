@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use solana_sdk::instruction::Instruction;
-use crate::trading::MiddlewareManager;
+use crate::trading::{factory::DexType, MiddlewareManager};
 
 use super::params::{BuyParams, BuyWithTipParams, SellParams, SellWithTipParams};
 
@@ -21,8 +21,14 @@ pub trait TradeExecutor: Send + Sync {
     /// 使用MEV服务执行卖出交易
     async fn sell_with_tip(&self, params: SellWithTipParams, middleware_manager: Option<Arc<MiddlewareManager>>) -> Result<()>;
 
+    /// 获取交易协议
+    fn dex_type(&self) -> DexType;
+
     /// 获取协议名称
-    fn protocol_name(&self) -> &'static str;
+    #[deprecated(note = "use `dex_type()`; the name is `dex_type().as_str()`")]
+    fn protocol_name(&self) -> &'static str {
+        self.dex_type().as_str()
+    }
 }
 
 /// 指令构建器trait - 负责构建协议特定的交易指令
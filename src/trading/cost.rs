@@ -0,0 +1,202 @@
+//! Pre-trade cost estimate
+//!
+//! `SolanaTrade::estimate_trade_cost` breaks the lamports a trade moves into the swap itself,
+//! the protocol fee, the network fees, the swqos tip and the rent of the accounts the trade
+//! creates, from the same settings and params `buy` / `sell` would use. Budget guards and UIs
+//! can show or check it before anything is sent.
+//!
+//! `SolanaTrade::network_fees` is the one estimate of the signature fee, priority fee and tip
+//! of a trade; the balance check and paper fills use it as well.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{native_token::sol_str_to_lamports, pubkey::Pubkey};
+
+use super::{
+    common::utils::SIGNATURE_FEE,
+    core::{parallel::racing_tip, traits::ProtocolParams},
+    factory::DexType,
+    paper::{protocol_fee_basis_points, quote_sell, Side},
+};
+use crate::common::{PriorityFee, TradeResult};
+#[cfg(any(feature = "bonk", feature = "raydium"))]
+use crate::utils::calc::common::compute_fee;
+use crate::SolanaTrade;
+
+/// Expected lamports of a trade, by where they go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeCostEstimate {
+    /// SOL swapped against the pool: spent by a buy, or the gross proceeds of a sell
+    pub swap_lamports: u64,
+    /// Fee the protocol takes out of the SOL side
+    pub protocol_fee_lamports: u64,
+    pub signature_fee_lamports: u64,
+    /// Compute unit limit times the compute unit price
+    pub priority_fee_lamports: u64,
+    /// Tip of the swqos providers the trade is sent through
+    pub tip_lamports: u64,
+    /// Rent of the token accounts the trade creates, refunded when they are closed
+    pub rent_lamports: u64,
+}
+
+impl TradeCostEstimate {
+    /// Everything besides the swap
+    pub fn fees(&self) -> u64 {
+        self.protocol_fee_lamports
+            + self.signature_fee_lamports
+            + self.priority_fee_lamports
+            + self.tip_lamports
+            + self.rent_lamports
+    }
+
+    /// Lamports the trade moves in total
+    pub fn total(&self) -> u64 {
+        self.swap_lamports + self.fees()
+    }
+}
+
+impl SolanaTrade {
+    /// Estimate what a trade costs before executing it
+    ///
+    /// `amount` is the lamports a buy spends, protocol fee included, or the tokens a sell
+    /// sells. The priority fee and tip follow the configured `PriorityFee` the way `buy` and
+    /// `sell` apply it without a custom tip.
+    pub fn estimate_trade_cost(
+        &self,
+        dex_type: &DexType,
+        mint: &Pubkey,
+        side: Side,
+        amount: u64,
+        protocol_params: &dyn ProtocolParams,
+    ) -> TradeResult<TradeCostEstimate> {
+        let fee_bps = protocol_fee_basis_points(dex_type, protocol_params)?;
+        let (swap_lamports, protocol_fee_lamports) = match side {
+            Side::Buy => {
+                let fee = buy_fee(dex_type, amount, fee_bps);
+                (amount - fee, fee)
+            }
+            Side::Sell => {
+                let net = quote_sell(dex_type, protocol_params, amount)?;
+                let gross = (net as u128 * 10_000).div_ceil(10_000 - fee_bps.min(9_999) as u128);
+                (gross as u64, gross as u64 - net)
+            }
+        };
+
+        let network_fees =
+            self.network_fees(&self.trade_config.priority_fee, side, self.sends_with_tip());
+        Ok(TradeCostEstimate {
+            swap_lamports,
+            protocol_fee_lamports,
+            rent_lamports: self.rent_lamports(dex_type, mint, side == Side::Buy),
+            ..network_fees
+        })
+    }
+
+    /// Signature fee, priority fee and tip of a trade sent with `priority_fee`
+    ///
+    /// With `with_tip` the `tip_unit_*` settings apply and the tip is the one the trade races
+    /// through the swqos clients with (see `racing_tip`), otherwise the `rpc_unit_*` settings
+    /// apply and no tip is paid. Only the network fee fields of the estimate are set.
+    pub(crate) fn network_fees(
+        &self,
+        priority_fee: &PriorityFee,
+        side: Side,
+        with_tip: bool,
+    ) -> TradeCostEstimate {
+        let signers = match &self.fee_payer {
+            Some(fee_payer) if fee_payer.pubkey() != self.payer.pubkey() => 2,
+            _ => 1,
+        };
+        let tip = match (with_tip, side) {
+            (false, _) => 0.0,
+            (true, Side::Buy) => racing_tip(
+                &self.swqos_clients,
+                &priority_fee.buy_tip_fees,
+                priority_fee.buy_tip_fee,
+                self.single_signature_racing,
+            ),
+            (true, Side::Sell) => racing_tip(
+                &self.swqos_clients,
+                &[],
+                priority_fee.sell_tip_fee,
+                self.single_signature_racing,
+            ),
+        };
+        network_fees(priority_fee, signers, with_tip.then_some(tip))
+    }
+}
+
+/// Network fees of a transaction with `signers` signatures, tipping `tip` SOL when sent
+/// with a tip
+fn network_fees(priority_fee: &PriorityFee, signers: u64, tip: Option<f64>) -> TradeCostEstimate {
+    let (unit_price, unit_limit) = match tip {
+        Some(_) => (priority_fee.tip_unit_price, priority_fee.tip_unit_limit),
+        None => (priority_fee.rpc_unit_price, priority_fee.rpc_unit_limit),
+    };
+    // The unit price is in micro-lamports
+    let priority_fee_lamports = (unit_price as u128 * unit_limit as u128).div_ceil(1_000_000);
+    TradeCostEstimate {
+        signature_fee_lamports: SIGNATURE_FEE * signers,
+        priority_fee_lamports: u64::try_from(priority_fee_lamports).unwrap_or(u64::MAX),
+        tip_lamports: tip
+            .map_or(0, |tip| sol_str_to_lamports(tip.to_string().as_str()).unwrap_or(0)),
+        ..Default::default()
+    }
+}
+
+/// Protocol fee included in `amount` lamports a buy spends
+fn buy_fee(dex_type: &DexType, amount: u64, fee_bps: u64) -> u64 {
+    match dex_type {
+        // The fee comes on top of the swapped amount
+        #[cfg(feature = "pumpfun")]
+        DexType::PumpFun => amount - (amount as u128 * 10_000 / (10_000 + fee_bps) as u128) as u64,
+        #[cfg(feature = "pumpswap")]
+        DexType::PumpSwap => amount - (amount as u128 * 10_000 / (10_000 + fee_bps) as u128) as u64,
+        // The fee is taken out of the input
+        #[cfg(any(feature = "bonk", feature = "raydium"))]
+        _ => compute_fee(amount as u128, fee_bps as u128) as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buy_fee() {
+        #[cfg(feature = "pumpfun")]
+        assert_eq!(buy_fee(&DexType::PumpFun, 1_010_000, 100), 10_000);
+        #[cfg(feature = "raydium")]
+        assert_eq!(buy_fee(&DexType::RaydiumAmmV4, 1_000_000, 25), 2_500);
+
+        let estimate = TradeCostEstimate {
+            swap_lamports: 1_000_000,
+            protocol_fee_lamports: 10_000,
+            signature_fee_lamports: 5_000,
+            priority_fee_lamports: 100_000,
+            tip_lamports: 1_000_000,
+            rent_lamports: 2_039_280,
+        };
+        assert_eq!(estimate.fees(), 3_154_280);
+        assert_eq!(estimate.total(), 4_154_280);
+    }
+
+    #[test]
+    fn test_network_fees() {
+        let priority_fee = PriorityFee {
+            tip_unit_limit: 100_000,
+            tip_unit_price: 1_000_001,
+            rpc_unit_limit: 200_000,
+            rpc_unit_price: 500_000,
+            ..Default::default()
+        };
+        let fees = network_fees(&priority_fee, 1, Some(0.001));
+        // The compute fee rounds up like the runtime charges it
+        assert_eq!(
+            (fees.signature_fee_lamports, fees.priority_fee_lamports, fees.tip_lamports),
+            (5_000, 100_001, 1_000_000)
+        );
+        assert_eq!(fees.fees(), 1_105_001);
+        let fees = network_fees(&priority_fee, 2, None);
+        assert_eq!(fees.fees(), 10_000 + 100_000);
+    }
+}
@@ -1,45 +1,86 @@
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use crate::instruction::{
-    bonk::BonkInstructionBuilder, pumpfun::PumpFunInstructionBuilder,
-    pumpswap::PumpSwapInstructionBuilder, raydium_amm_v4::RaydiumAmmV4InstructionBuilder,
-    raydium_cpmm::RaydiumCpmmInstructionBuilder,
+#[cfg(feature = "bonk")]
+use crate::instruction::bonk::BonkInstructionBuilder;
+#[cfg(feature = "pumpfun")]
+use crate::instruction::pumpfun::PumpFunInstructionBuilder;
+#[cfg(feature = "pumpswap")]
+use crate::instruction::pumpswap::PumpSwapInstructionBuilder;
+#[cfg(feature = "raydium")]
+use crate::instruction::raydium_amm_v4::RaydiumAmmV4InstructionBuilder;
+#[cfg(feature = "raydium")]
+use crate::instruction::raydium_cpmm::RaydiumCpmmInstructionBuilder;
+
+use super::core::{
+    executor::GenericTradeExecutor,
+    traits::{InstructionBuilder, TradeExecutor},
 };
 
-use super::core::{executor::GenericTradeExecutor, traits::TradeExecutor};
+#[cfg(not(any(
+    feature = "pumpfun",
+    feature = "pumpswap",
+    feature = "bonk",
+    feature = "raydium"
+)))]
+compile_error!("enable at least one protocol feature: pumpfun, pumpswap, bonk or raydium");
 
 /// 支持的交易协议
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// 每个协议的变体仅在启用对应 feature 时存在
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DexType {
+    #[cfg(feature = "pumpfun")]
     PumpFun,
+    #[cfg(feature = "pumpswap")]
     PumpSwap,
+    #[cfg(feature = "bonk")]
     Bonk,
+    #[cfg(feature = "raydium")]
     RaydiumCpmm,
+    #[cfg(feature = "raydium")]
     RaydiumAmmV4,
 }
 
-impl std::fmt::Display for DexType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DexType {
+    /// 协议名称
+    pub fn as_str(&self) -> &'static str {
         match self {
-            DexType::PumpFun => write!(f, "PumpFun"),
-            DexType::PumpSwap => write!(f, "PumpSwap"),
-            DexType::Bonk => write!(f, "Bonk"),
-            DexType::RaydiumCpmm => write!(f, "RaydiumCpmm"),
-            DexType::RaydiumAmmV4 => write!(f, "RaydiumAmmV4"),
+            #[cfg(feature = "pumpfun")]
+            DexType::PumpFun => "PumpFun",
+            #[cfg(feature = "pumpswap")]
+            DexType::PumpSwap => "PumpSwap",
+            #[cfg(feature = "bonk")]
+            DexType::Bonk => "Bonk",
+            #[cfg(feature = "raydium")]
+            DexType::RaydiumCpmm => "RaydiumCpmm",
+            #[cfg(feature = "raydium")]
+            DexType::RaydiumAmmV4 => "RaydiumAmmV4",
         }
     }
 }
 
+impl std::fmt::Display for DexType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 impl std::str::FromStr for DexType {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
+        match s.to_lowercase().replace(['_', '-', ' '], "").as_str() {
+            #[cfg(feature = "pumpfun")]
             "pumpfun" => Ok(DexType::PumpFun),
+            #[cfg(feature = "pumpswap")]
             "pumpswap" => Ok(DexType::PumpSwap),
+            #[cfg(feature = "bonk")]
             "bonk" => Ok(DexType::Bonk),
+            #[cfg(feature = "raydium")]
             "raydiumcpmm" => Ok(DexType::RaydiumCpmm),
+            #[cfg(feature = "raydium")]
             "raydiumammv4" => Ok(DexType::RaydiumAmmV4),
             _ => Err(anyhow!("Unsupported protocol: {}", s)),
         }
@@ -52,33 +93,43 @@ pub struct TradeFactory;
 impl TradeFactory {
     /// 创建指定协议的交易执行器
     pub fn create_executor(dex_type: DexType) -> Arc<dyn TradeExecutor> {
+        let instruction_builder = Self::create_instruction_builder(dex_type.clone());
+        Arc::new(GenericTradeExecutor::new(instruction_builder, dex_type))
+    }
+
+    /// 创建协议的指令构建器，用于自行组装交易
+    pub fn create_instruction_builder(dex_type: DexType) -> Arc<dyn InstructionBuilder> {
         match dex_type {
-            DexType::PumpFun => {
-                let instruction_builder = Arc::new(PumpFunInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "PumpFun"))
-            }
-            DexType::PumpSwap => {
-                let instruction_builder = Arc::new(PumpSwapInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "PumpSwap"))
-            }
-            DexType::Bonk => {
-                let instruction_builder = Arc::new(BonkInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "Bonk"))
-            }
-            DexType::RaydiumCpmm => {
-                let instruction_builder = Arc::new(RaydiumCpmmInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "RaydiumCpmm"))
-            }
-            DexType::RaydiumAmmV4 => {
-                let instruction_builder = Arc::new(RaydiumAmmV4InstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "RaydiumAmmV4"))
-            }
+            #[cfg(feature = "pumpfun")]
+            DexType::PumpFun => Arc::new(PumpFunInstructionBuilder),
+            #[cfg(feature = "pumpswap")]
+            DexType::PumpSwap => Arc::new(PumpSwapInstructionBuilder),
+            #[cfg(feature = "bonk")]
+            DexType::Bonk => Arc::new(BonkInstructionBuilder),
+            #[cfg(feature = "raydium")]
+            DexType::RaydiumCpmm => Arc::new(RaydiumCpmmInstructionBuilder),
+            #[cfg(feature = "raydium")]
+            DexType::RaydiumAmmV4 => Arc::new(RaydiumAmmV4InstructionBuilder),
         }
     }
 
+    #[deprecated(note = "parse the name into a `DexType` and use `create_executor`")]
+    pub fn create_executor_by_name(name: &str) -> Result<Arc<dyn TradeExecutor>> {
+        Ok(Self::create_executor(name.parse()?))
+    }
+
     /// 获取所有支持的协议
     pub fn supported_dex_types() -> Vec<DexType> {
-        vec![DexType::PumpFun, DexType::PumpSwap, DexType::Bonk, DexType::RaydiumCpmm]
+        vec![
+            #[cfg(feature = "pumpfun")]
+            DexType::PumpFun,
+            #[cfg(feature = "pumpswap")]
+            DexType::PumpSwap,
+            #[cfg(feature = "bonk")]
+            DexType::Bonk,
+            #[cfg(feature = "raydium")]
+            DexType::RaydiumCpmm,
+        ]
     }
 
     /// 检查协议是否支持
@@ -0,0 +1,182 @@
+//! Fund a fresh wallet and buy with it in one Jito bundle
+//!
+//! Funding a wallet ahead of its first buy leaves a transfer on chain that announces the
+//! trade and links the wallet to its funder before the position exists.
+//! `SolanaTrade::buy_with_fresh_wallet` bundles the funding transfer, the creation of the
+//! wallet's token account and the wallet's buy, so nothing is visible until all of it lands
+//! in the same block, or none of it does.
+
+use solana_sdk::{hash::Hash, instruction::Instruction, pubkey::Pubkey, signature::Signature};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use std::sync::Arc;
+
+use crate::{
+    common::{mint_info_cache::MintInfoCache, TradeError, TradeResult, TradeSigner},
+    swqos::{SwqosType, TradeType},
+    trading::{
+        common::{build_rpc_transaction, build_tip_transaction, is_using_nonce},
+        core::{executor::MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT, traits::ProtocolParams},
+        factory::DexType,
+        BuyParams, TradeFactory,
+    },
+    SolanaTrade,
+};
+
+/// Instructions of the funding transaction: `lamports` from `funder` to `wallet` and the
+/// wallet's associated token account for `mint`, paid by `funder`
+pub fn fresh_wallet_funding_instructions(
+    funder: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    lamports: u64,
+) -> Vec<Instruction> {
+    vec![
+        transfer(funder, wallet, lamports),
+        create_associated_token_account_idempotent(funder, wallet, mint, token_program),
+    ]
+}
+
+impl SolanaTrade {
+    /// Fund `wallet` from the payer and execute its first buy in one Jito bundle
+    ///
+    /// The first transaction, signed by the payer (or fee payer), transfers
+    /// `funding_lamports` to `wallet`, creates its token account for `mint` and pays the
+    /// buy tip fee of the priority fee config. The second is the buy, signed and paid by
+    /// `wallet` alone. `funding_lamports` must cover `sol_amount`, the buy's network fees,
+    /// the temporary wrapped SOL account rent of pool based venues and the rent-exempt
+    /// minimum the wallet keeps. Requires a Jito swqos client and no durable nonce, since
+    /// both transactions would advance the same nonce. Returns the buy signature.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn buy_with_fresh_wallet(
+        &self,
+        wallet: Arc<dyn TradeSigner>,
+        funding_lamports: u64,
+        dex_type: DexType,
+        mint: Pubkey,
+        sol_amount: u64,
+        slippage_basis_points: Option<u64>,
+        recent_blockhash: Hash,
+        extension_params: Box<dyn ProtocolParams>,
+    ) -> TradeResult<Signature> {
+        if is_using_nonce() {
+            return Err(TradeError::InvalidParams(
+                "Fresh wallet buys cannot use a durable nonce".to_string(),
+            ));
+        }
+        let jito = self
+            .swqos_clients
+            .iter()
+            .find(|client| client.get_swqos_type() == SwqosType::Jito)
+            .ok_or_else(|| {
+                TradeError::InvalidParams("Fresh wallet buys need a Jito client".to_string())
+            })?;
+        let tip_account: Pubkey = jito
+            .get_tip_account()
+            .and_then(|account| account.parse().map_err(anyhow::Error::from))
+            .map_err(TradeError::from)?;
+        let rent_exempt = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(0)
+            .await
+            .map_err(anyhow::Error::from)?;
+        if funding_lamports < sol_amount.saturating_add(rent_exempt) {
+            return Err(TradeError::InvalidParams(format!(
+                "Funding of {} lamports does not cover the buy of {} lamports and the {} lamports rent-exempt minimum",
+                funding_lamports, sol_amount, rent_exempt
+            )));
+        }
+        let mint_info = MintInfoCache::get_instance().get_or_fetch(&self.rpc, &mint).await?;
+        let priority_fee = self.trade_config.priority_fee.clone();
+        let lookup_table_key = self.trade_config.lookup_table_key;
+
+        let funder = self.fee_payer.as_ref().unwrap_or(&self.payer).pubkey();
+        let funding_transaction = build_tip_transaction(
+            self.payer.clone(),
+            self.fee_payer.clone(),
+            &priority_fee,
+            fresh_wallet_funding_instructions(
+                &funder,
+                &wallet.pubkey(),
+                &mint,
+                &mint_info.token_program,
+                funding_lamports,
+            ),
+            &tip_account,
+            priority_fee.buy_tip_fee,
+            lookup_table_key,
+            recent_blockhash,
+            MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT,
+            self.middleware_manager.clone(),
+            dex_type.clone(),
+            true,
+        )
+        .await?;
+
+        let buy_params = BuyParams {
+            rpc: Some(self.rpc.clone()),
+            payer: wallet.clone(),
+            fee_payer: None,
+            mint,
+            sol_amount,
+            slippage_basis_points,
+            priority_fee: priority_fee.clone(),
+            lookup_table_key,
+            recent_blockhash,
+            data_size_limit: MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT,
+            wait_transaction_confirmed: false,
+            protocol_params: extension_params,
+            lifecycle: None,
+        };
+        let buy_instructions = TradeFactory::create_instruction_builder(dex_type.clone())
+            .build_buy_instructions(&buy_params)
+            .await?;
+        let buy_transaction = build_rpc_transaction(
+            wallet,
+            None,
+            &priority_fee,
+            buy_instructions,
+            lookup_table_key,
+            recent_blockhash,
+            MAX_LOADED_ACCOUNTS_DATA_SIZE_LIMIT,
+            self.middleware_manager.clone(),
+            dex_type,
+            true,
+        )
+        .await?;
+        let signature = buy_transaction.signatures[0];
+
+        jito.send_transactions(TradeType::Buy, &vec![funding_transaction, buy_transaction])
+            .await
+            .map_err(|e| TradeError::from_swqos("Jito", e))?;
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+    #[test]
+    fn test_fresh_wallet_funding_instructions() {
+        let (funder, wallet, mint) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let instructions =
+            fresh_wallet_funding_instructions(&funder, &wallet, &mint, &spl_token::ID, 50_000_000);
+
+        assert_eq!(
+            instructions,
+            vec![
+                transfer(&funder, &wallet, 50_000_000),
+                create_associated_token_account_idempotent(&funder, &wallet, &mint, &spl_token::ID),
+            ]
+        );
+        // The token account belongs to the wallet, the funder only pays its rent
+        let ata = get_associated_token_address_with_program_id(&wallet, &mint, &spl_token::ID);
+        assert_eq!(instructions[1].accounts[0].pubkey, funder);
+        assert_eq!(instructions[1].accounts[1].pubkey, ata);
+        assert_eq!(instructions[1].accounts[2].pubkey, wallet);
+    }
+}
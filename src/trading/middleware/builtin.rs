@@ -1,6 +1,17 @@
+use crate::common::SolanaRpcClient;
+use crate::swqos::attribution;
+use crate::trading::factory::DexType;
 use crate::trading::middleware::traits::InstructionMiddleware;
-use anyhow::Result;
-use solana_sdk::instruction::Instruction;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use solana_rpc_client_api::config::RpcSimulateTransactionConfig;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, instruction::Instruction, pubkey::Pubkey,
+    signature::Signature, transaction::VersionedTransaction,
+};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 
 /// Logging middleware - Records instruction information
 #[derive(Clone)]
@@ -14,36 +25,361 @@ impl InstructionMiddleware for LoggingMiddleware {
     fn process_protocol_instructions(
         &self,
         protocol_instructions: Vec<Instruction>,
-        protocol_name: String,
+        dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>> {
-        println!("-------------------[{}]-------------------", self.name());
-        println!("process_protocol_instructions");
-        println!("[{}] Instruction count: {}", self.name(), protocol_instructions.len());
-        println!("[{}] Protocol name: {}\n", self.name(), protocol_name);
-        println!("[{}] Is buy: {}", self.name(), is_buy);
-        for (i, instruction) in protocol_instructions.iter().enumerate() {
-            println!("Instruction {}:", i + 1);
-            println!("{:?}\n", instruction);
+        tracing::debug!(
+            middleware = self.name(),
+            dex_type = %dex_type,
+            is_buy,
+            instruction_count = protocol_instructions.len(),
+            "process_protocol_instructions"
+        );
+        tracing::trace!(middleware = self.name(), instructions = ?protocol_instructions);
+        Ok(protocol_instructions)
+    }
+
+    fn process_full_instructions(
+        &self,
+        full_instructions: Vec<Instruction>,
+        dex_type: DexType,
+        is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        tracing::debug!(
+            middleware = self.name(),
+            dex_type = %dex_type,
+            is_buy,
+            instruction_count = full_instructions.len(),
+            "process_full_instructions"
+        );
+        tracing::trace!(middleware = self.name(), instructions = ?full_instructions);
+        Ok(full_instructions)
+    }
+
+    fn clone_box(&self) -> Box<dyn InstructionMiddleware> {
+        Box::new(self.clone())
+    }
+}
+
+/// Fee cap middleware - Aborts the trade when the expected fee spend exceeds a cap
+///
+/// The expected cost is computed from the assembled transaction: compute unit price ×
+/// compute unit limit (priority fee) plus any transfer to a known swqos tip account.
+#[derive(Clone)]
+pub struct FeeCapMiddleware {
+    /// Maximum total fee in lamports (priority fee + tip)
+    pub max_total_fee_lamports: u64,
+}
+
+/// Fee breakdown of an assembled transaction, in lamports
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    pub compute_unit_price: u64,
+    pub compute_unit_limit: u32,
+    pub priority_fee_lamports: u64,
+    pub tip_lamports: u64,
+}
+
+impl FeeBreakdown {
+    pub fn total_lamports(&self) -> u64 {
+        self.priority_fee_lamports.saturating_add(self.tip_lamports)
+    }
+
+    /// Build the breakdown from a full instruction list
+    pub fn from_instructions(instructions: &[Instruction]) -> Self {
+        let mut breakdown = FeeBreakdown::default();
+        for instruction in instructions {
+            if instruction.program_id == solana_sdk::compute_budget::ID {
+                match instruction.data.first() {
+                    // SetComputeUnitLimit(u32)
+                    Some(2) if instruction.data.len() >= 5 => {
+                        let mut bytes = [0u8; 4];
+                        bytes.copy_from_slice(&instruction.data[1..5]);
+                        breakdown.compute_unit_limit = u32::from_le_bytes(bytes);
+                    }
+                    // SetComputeUnitPrice(u64), in micro-lamports
+                    Some(3) if instruction.data.len() >= 9 => {
+                        let mut bytes = [0u8; 8];
+                        bytes.copy_from_slice(&instruction.data[1..9]);
+                        breakdown.compute_unit_price = u64::from_le_bytes(bytes);
+                    }
+                    _ => {}
+                }
+            } else if instruction.program_id == solana_system_interface::program::ID
+                && instruction.data.len() == 12
+                && instruction.data[..4] == 2u32.to_le_bytes()
+                && instruction.accounts.get(1).is_some_and(|meta| is_tip_account(&meta.pubkey))
+            {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&instruction.data[4..12]);
+                breakdown.tip_lamports =
+                    breakdown.tip_lamports.saturating_add(u64::from_le_bytes(bytes));
+            }
+        }
+        let micro_lamports =
+            breakdown.compute_unit_price as u128 * breakdown.compute_unit_limit as u128;
+        breakdown.priority_fee_lamports =
+            micro_lamports.div_ceil(1_000_000).min(u64::MAX as u128) as u64;
+        breakdown
+    }
+}
+
+fn is_tip_account(pubkey: &Pubkey) -> bool {
+    attribution::provider_for_tip_account(pubkey).is_some()
+}
+
+impl FeeCapMiddleware {
+    pub fn new(max_total_fee_lamports: u64) -> Self {
+        Self { max_total_fee_lamports }
+    }
+}
+
+impl InstructionMiddleware for FeeCapMiddleware {
+    fn name(&self) -> &'static str {
+        "FeeCapMiddleware"
+    }
+
+    fn process_protocol_instructions(
+        &self,
+        protocol_instructions: Vec<Instruction>,
+        _dex_type: DexType,
+        _is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        Ok(protocol_instructions)
+    }
+
+    fn process_full_instructions(
+        &self,
+        full_instructions: Vec<Instruction>,
+        dex_type: DexType,
+        is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        let breakdown = FeeBreakdown::from_instructions(&full_instructions);
+        tracing::info!(
+            middleware = self.name(),
+            dex = %dex_type,
+            side = if is_buy { "buy" } else { "sell" },
+            unit_price = breakdown.compute_unit_price,
+            unit_limit = breakdown.compute_unit_limit,
+            priority_fee = breakdown.priority_fee_lamports,
+            tip = breakdown.tip_lamports,
+            total = breakdown.total_lamports(),
+            cap = self.max_total_fee_lamports,
+            "trade fee breakdown (lamports)"
+        );
+        if breakdown.total_lamports() > self.max_total_fee_lamports {
+            return Err(anyhow!(
+                "Fee cap exceeded: total {} lamports (priority {} + tip {}) > cap {} lamports",
+                breakdown.total_lamports(),
+                breakdown.priority_fee_lamports,
+                breakdown.tip_lamports,
+                self.max_total_fee_lamports
+            ));
         }
+        Ok(full_instructions)
+    }
+
+    fn clone_box(&self) -> Box<dyn InstructionMiddleware> {
+        Box::new(self.clone())
+    }
+}
+
+/// Result of simulating an assembled transaction
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub dex_type: DexType,
+    pub is_buy: bool,
+    /// Simulation error, `None` when the transaction would succeed
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+type SimulationVeto = Arc<dyn Fn(&SimulationReport) -> Result<()> + Send + Sync>;
+
+/// Simulation middleware - Simulates the signed transaction before submission
+///
+/// Recent reports are kept by transaction signature in a slot shared by all clones, so
+/// callers (or other middlewares holding a clone) can read logs and consumed units. A failed
+/// simulation aborts the trade unless `allow_failed_simulation` is set, and an optional veto
+/// callback can reject on any criteria.
+///
+/// Note: tip transactions are signed once per provider group (swqos clients of one provider
+/// type paying the same tip), so a trade runs one simulation per group, or a single one with
+/// single signature racing.
+#[derive(Clone)]
+pub struct SimulationMiddleware {
+    rpc: Arc<SolanaRpcClient>,
+    fail_on_error: bool,
+    veto: Option<SimulationVeto>,
+    reports: Arc<Mutex<VecDeque<(Signature, SimulationReport)>>>,
+}
+
+/// Reports kept by `SimulationMiddleware`, oldest dropped first
+const MAX_SIMULATION_REPORTS: usize = 64;
+
+impl SimulationMiddleware {
+    pub fn new(rpc: Arc<SolanaRpcClient>) -> Self {
+        Self { rpc, fail_on_error: true, veto: None, reports: Arc::default() }
+    }
+
+    /// Keep sending even if the simulation reports an error
+    pub fn allow_failed_simulation(mut self) -> Self {
+        self.fail_on_error = false;
+        self
+    }
+
+    /// Reject the trade when the callback returns an error
+    pub fn with_veto<F>(mut self, veto: F) -> Self
+    where
+        F: Fn(&SimulationReport) -> Result<()> + Send + Sync + 'static,
+    {
+        self.veto = Some(Arc::new(veto));
+        self
+    }
+
+    /// Report of the simulation of the transaction with `signature`
+    pub fn report(&self, signature: &Signature) -> Option<SimulationReport> {
+        let reports = self.reports.lock().unwrap();
+        reports.iter().find(|(sig, _)| sig == signature).map(|(_, report)| report.clone())
+    }
+
+    /// Report of the most recent simulation
+    ///
+    /// Clones, provider groups and concurrent trades share it, so it only describes a given
+    /// trade while a single one is in flight. Use `report` otherwise.
+    pub fn last_report(&self) -> Option<SimulationReport> {
+        self.reports.lock().unwrap().back().map(|(_, report)| report.clone())
+    }
+}
+
+#[async_trait::async_trait]
+impl InstructionMiddleware for SimulationMiddleware {
+    fn name(&self) -> &'static str {
+        "SimulationMiddleware"
+    }
+
+    fn process_protocol_instructions(
+        &self,
+        protocol_instructions: Vec<Instruction>,
+        _dex_type: DexType,
+        _is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
         Ok(protocol_instructions)
     }
 
     fn process_full_instructions(
         &self,
         full_instructions: Vec<Instruction>,
-        protocol_name: String,
+        _dex_type: DexType,
+        _is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        Ok(full_instructions)
+    }
+
+    async fn process_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        dex_type: DexType,
+        is_buy: bool,
+    ) -> Result<()> {
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            commitment: Some(CommitmentConfig::processed()),
+            ..Default::default()
+        };
+        let result = self.rpc.simulate_transaction_with_config(transaction, config).await?.value;
+        let report = SimulationReport {
+            dex_type,
+            is_buy,
+            err: result.err.map(|e| e.to_string()),
+            logs: result.logs.unwrap_or_default(),
+            units_consumed: result.units_consumed,
+        };
+        {
+            let mut reports = self.reports.lock().unwrap();
+            if reports.len() == MAX_SIMULATION_REPORTS {
+                reports.pop_front();
+            }
+            reports.push_back((transaction.signatures[0], report.clone()));
+        }
+
+        if let Some(err) = &report.err {
+            if self.fail_on_error {
+                return Err(anyhow!("Simulation failed: {}, logs: {:?}", err, report.logs));
+            }
+        }
+        if let Some(veto) = &self.veto {
+            veto(&report)?;
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn InstructionMiddleware> {
+        Box::new(self.clone())
+    }
+}
+
+/// Close token account middleware - Appends a `close_account` for the seller's token ATA
+///
+/// Only applies to sells. Reclaims the ATA rent in the same transaction once the full
+/// balance has been sold; skipped when the protocol builder already closes the account.
+#[derive(Clone)]
+pub struct CloseTokenAccountMiddleware {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl CloseTokenAccountMiddleware {
+    pub fn new(owner: Pubkey, mint: Pubkey, token_program: Pubkey) -> Self {
+        Self { owner, mint, token_program }
+    }
+
+    fn token_account(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(&self.owner, &self.mint, &self.token_program)
+    }
+}
+
+impl InstructionMiddleware for CloseTokenAccountMiddleware {
+    fn name(&self) -> &'static str {
+        "CloseTokenAccountMiddleware"
+    }
+
+    fn process_protocol_instructions(
+        &self,
+        mut protocol_instructions: Vec<Instruction>,
+        _dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>> {
-        println!("-------------------[{}]-------------------", self.name());
-        println!("process_full_instructions");
-        println!("[{}] Instruction count: {}", self.name(), full_instructions.len());
-        println!("[{}] Protocol name: {}\n", self.name(), protocol_name);
-        println!("[{}] Is buy: {}", self.name(), is_buy);
-        for (i, instruction) in full_instructions.iter().enumerate() {
-            println!("Instruction {}:", i + 1);
-            println!("{:?}\n", instruction);
+        if is_buy {
+            return Ok(protocol_instructions);
+        }
+        let token_account = self.token_account();
+        // CloseAccount instruction index is 9 for both token programs
+        let already_closed = protocol_instructions.iter().any(|instruction| {
+            instruction.program_id == self.token_program
+                && instruction.data.first() == Some(&9)
+                && instruction.accounts.first().map(|meta| meta.pubkey) == Some(token_account)
+        });
+        if !already_closed {
+            protocol_instructions.push(spl_token_2022::instruction::close_account(
+                &self.token_program,
+                &token_account,
+                &self.owner,
+                &self.owner,
+                &[],
+            )?);
         }
+        Ok(protocol_instructions)
+    }
+
+    fn process_full_instructions(
+        &self,
+        full_instructions: Vec<Instruction>,
+        _dex_type: DexType,
+        _is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
         Ok(full_instructions)
     }
 
@@ -51,3 +387,41 @@ impl InstructionMiddleware for LoggingMiddleware {
         Box::new(self.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::swqos::BLOCKRAZOR_TIP_ACCOUNTS;
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+    #[test]
+    fn test_fee_cap_counts_blockrazor_tip() {
+        let payer = Pubkey::new_unique();
+        let instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            ComputeBudgetInstruction::set_compute_unit_price(1_000_000),
+            solana_system_interface::instruction::transfer(
+                &payer,
+                &BLOCKRAZOR_TIP_ACCOUNTS[0],
+                1_000_000,
+            ),
+        ];
+
+        let breakdown = FeeBreakdown::from_instructions(&instructions);
+        assert_eq!(breakdown.priority_fee_lamports, 200_000);
+        assert_eq!(breakdown.tip_lamports, 1_000_000);
+
+        let capped = FeeCapMiddleware::new(1_000_000).process_full_instructions(
+            instructions.clone(),
+            DexType::PumpFun,
+            true,
+        );
+        assert!(capped.is_err());
+        let allowed = FeeCapMiddleware::new(1_200_000).process_full_instructions(
+            instructions,
+            DexType::PumpFun,
+            true,
+        );
+        assert!(allowed.is_ok());
+    }
+}
@@ -1,9 +1,12 @@
 use anyhow::Result;
-use solana_sdk::instruction::Instruction;
+use solana_sdk::{instruction::Instruction, transaction::VersionedTransaction};
+
+use crate::trading::factory::DexType;
 
 /// Instruction middleware trait
 ///
 /// Used to modify, add or remove protocol_instructions before transaction execution
+#[async_trait::async_trait]
 pub trait InstructionMiddleware: Send + Sync {
     /// Middleware name
     fn name(&self) -> &'static str;
@@ -12,33 +15,110 @@ pub trait InstructionMiddleware: Send + Sync {
     ///
     /// # Arguments
     /// * `protocol_instructions` - Current instruction list
-    /// * `protocol_name` - Protocol name
+    /// * `dex_type` - Trading protocol
     /// * `is_buy` - Whether the transaction is a buy transaction
     ///
     /// # Returns
     /// Returns modified instruction list
+    ///
+    /// The default implementation forwards to `process_protocol_instructions_by_name`.
     fn process_protocol_instructions(
         &self,
         protocol_instructions: Vec<Instruction>,
-        protocol_name: String,
+        dex_type: DexType,
         is_buy: bool,
-    ) -> Result<Vec<Instruction>>;
+    ) -> Result<Vec<Instruction>> {
+        #[allow(deprecated)]
+        self.process_protocol_instructions_by_name(
+            protocol_instructions,
+            dex_type.as_str().to_string(),
+            is_buy,
+        )
+    }
+
+    /// `process_protocol_instructions` with the protocol name, e.g. `"PumpFun"`
+    ///
+    /// Middleware written against protocol names keeps working by implementing this method
+    /// instead. The default implementation returns the instructions unchanged.
+    #[deprecated(note = "implement `process_protocol_instructions`, which takes a `DexType`")]
+    fn process_protocol_instructions_by_name(
+        &self,
+        protocol_instructions: Vec<Instruction>,
+        _protocol_name: String,
+        _is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        Ok(protocol_instructions)
+    }
 
     /// Core method for processing full_instructions
     ///
     /// # Arguments
     /// * `full_instructions` - Current instruction list
-    /// * `protocol_name` - Protocol name
+    /// * `dex_type` - Trading protocol
     /// * `is_buy` - Whether the transaction is a buy transaction
     ///
     /// # Returns
     /// Returns modified instruction list
+    ///
+    /// The default implementation forwards to `process_full_instructions_by_name`.
     fn process_full_instructions(
         &self,
         full_instructions: Vec<Instruction>,
-        protocol_name: String,
+        dex_type: DexType,
         is_buy: bool,
-    ) -> Result<Vec<Instruction>>;
+    ) -> Result<Vec<Instruction>> {
+        #[allow(deprecated)]
+        self.process_full_instructions_by_name(
+            full_instructions,
+            dex_type.as_str().to_string(),
+            is_buy,
+        )
+    }
+
+    /// `process_full_instructions` with the protocol name, e.g. `"PumpFun"`
+    ///
+    /// The default implementation returns the instructions unchanged.
+    #[deprecated(note = "implement `process_full_instructions`, which takes a `DexType`")]
+    fn process_full_instructions_by_name(
+        &self,
+        full_instructions: Vec<Instruction>,
+        _protocol_name: String,
+        _is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        Ok(full_instructions)
+    }
+
+    /// Inspect the assembled and signed transaction before it is submitted
+    ///
+    /// Returning an error vetoes the trade. The default implementation forwards to
+    /// `process_transaction_by_name`.
+    ///
+    /// # Arguments
+    /// * `transaction` - Signed transaction about to be sent
+    /// * `dex_type` - Trading protocol
+    /// * `is_buy` - Whether the transaction is a buy transaction
+    async fn process_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        dex_type: DexType,
+        is_buy: bool,
+    ) -> Result<()> {
+        #[allow(deprecated)]
+        self.process_transaction_by_name(transaction, dex_type.as_str().to_string(), is_buy).await
+    }
+
+    /// `process_transaction` with the protocol name, e.g. `"PumpFun"`
+    ///
+    /// The default implementation accepts every transaction.
+    #[deprecated(note = "implement `process_transaction`, which takes a `DexType`")]
+    async fn process_transaction_by_name(
+        &self,
+        _transaction: &VersionedTransaction,
+        _protocol_name: String,
+        _is_buy: bool,
+    ) -> Result<()> {
+        Ok(())
+    }
 
     /// Clone middleware
     fn clone_box(&self) -> Box<dyn InstructionMiddleware>;
@@ -72,13 +152,13 @@ impl MiddlewareManager {
     pub fn apply_middlewares_process_full_instructions(
         &self,
         mut full_instructions: Vec<Instruction>,
-        protocol_name: String,
+        dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>> {
         for middleware in &self.middlewares {
             full_instructions = middleware.process_full_instructions(
                 full_instructions,
-                protocol_name.clone(),
+                dex_type.clone(),
                 is_buy,
             )?;
             if full_instructions.is_empty() {
@@ -92,13 +172,13 @@ impl MiddlewareManager {
     pub fn apply_middlewares_process_protocol_instructions(
         &self,
         mut protocol_instructions: Vec<Instruction>,
-        protocol_name: String,
+        dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>> {
         for middleware in &self.middlewares {
             protocol_instructions = middleware.process_protocol_instructions(
                 protocol_instructions,
-                protocol_name.clone(),
+                dex_type.clone(),
                 is_buy,
             )?;
             if protocol_instructions.is_empty() {
@@ -108,8 +188,73 @@ impl MiddlewareManager {
         Ok(protocol_instructions)
     }
 
+    /// Apply all middlewares to inspect the signed transaction
+    pub async fn apply_middlewares_process_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        dex_type: DexType,
+        is_buy: bool,
+    ) -> Result<()> {
+        for middleware in &self.middlewares {
+            middleware.process_transaction(transaction, dex_type.clone(), is_buy).await?;
+        }
+        Ok(())
+    }
+
     /// Create manager with common middlewares
     pub fn with_common_middlewares() -> Self {
         Self::new().add_middleware(Box::new(crate::trading::middleware::builtin::LoggingMiddleware))
     }
 }
+
+#[cfg(all(test, feature = "pumpfun", feature = "pumpswap"))]
+mod tests {
+    use super::*;
+
+    /// Middleware still written against protocol names
+    #[derive(Clone)]
+    struct NamedMiddleware;
+
+    #[allow(deprecated)]
+    impl InstructionMiddleware for NamedMiddleware {
+        fn name(&self) -> &'static str {
+            "NamedMiddleware"
+        }
+
+        fn process_protocol_instructions_by_name(
+            &self,
+            protocol_instructions: Vec<Instruction>,
+            protocol_name: String,
+            _is_buy: bool,
+        ) -> Result<Vec<Instruction>> {
+            if protocol_name == "PumpSwap" {
+                return Ok(vec![]);
+            }
+            Ok(protocol_instructions)
+        }
+
+        fn clone_box(&self) -> Box<dyn InstructionMiddleware> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_protocol_name_middleware() {
+        use solana_sdk::pubkey::Pubkey;
+
+        let manager = MiddlewareManager::new().add_middleware(Box::new(NamedMiddleware));
+        let instructions = vec![Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![])];
+        let kept = manager
+            .apply_middlewares_process_protocol_instructions(
+                instructions.clone(),
+                DexType::PumpFun,
+                true,
+            )
+            .unwrap();
+        assert_eq!(kept, instructions);
+        let dropped = manager
+            .apply_middlewares_process_protocol_instructions(instructions, DexType::PumpSwap, true)
+            .unwrap();
+        assert!(dropped.is_empty());
+    }
+}
@@ -1,14 +1,35 @@
+#[cfg(all(feature = "pumpswap", feature = "raydium"))]
+pub mod arbitrage;
+#[cfg(feature = "bonk")]
 pub mod bonk;
 pub mod common;
 pub mod core;
+pub mod cost;
 pub mod factory;
+pub mod fresh_wallet;
 pub mod middleware;
+pub mod paper;
+pub mod position;
+#[cfg(feature = "pumpfun")]
 pub mod pumpfun;
+#[cfg(feature = "pumpswap")]
 pub mod pumpswap;
+#[cfg(feature = "raydium")]
+pub mod raydium;
+#[cfg(feature = "raydium")]
 pub mod raydium_amm_v4;
+#[cfg(feature = "raydium")]
 pub mod raydium_cpmm;
+pub mod safety;
+pub mod slippage_ladder;
 
+pub use cost::TradeCostEstimate;
+pub use core::lifecycle::{TradeLifecycleEvent, TradeStage};
 pub use core::params::{BuyParams, BuyWithTipParams, SellParams, SellWithTipParams};
 pub use core::traits::{InstructionBuilder, TradeExecutor};
 pub use factory::TradeFactory;
 pub use middleware::{InstructionMiddleware, MiddlewareManager};
+pub use paper::{PaperTrader, PaperTradingConfig};
+pub use position::{Position, PositionTracker};
+pub use safety::{SafetyReport, SellSimulation};
+pub use slippage_ladder::{LadderAttempt, LadderBuyResult, SlippageLadder};
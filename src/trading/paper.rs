@@ -0,0 +1,482 @@
+//! Paper trading
+//!
+//! With a `PaperTrader` set on `SolanaTrade`, `buy` and `sell` still build the transaction
+//! and (optionally) simulate it, but nothing is broadcast. Instead a virtual fill is priced
+//! against the reserves in the protocol params, the same numbers the instruction builders
+//! use for slippage, and booked in a `PositionTracker`. Strategies can run against live
+//! streams unchanged and without risk.
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, transaction::VersionedTransaction,
+};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+#[cfg(feature = "bonk")]
+use super::core::params::BonkParams;
+#[cfg(feature = "pumpfun")]
+use super::core::params::PumpFunParams;
+#[cfg(feature = "pumpswap")]
+use super::core::params::PumpSwapParams;
+#[cfg(feature = "raydium")]
+use super::core::params::{RaydiumAmmV4Params, RaydiumCpmmParams};
+use super::{
+    common::{build_rpc_transaction, build_sell_transaction},
+    core::{
+        params::{BuyParams, SellParams},
+        traits::ProtocolParams,
+    },
+    factory::{DexType, TradeFactory},
+    middleware::{builtin::SimulationReport, MiddlewareManager},
+    position::PositionTracker,
+};
+use crate::common::{AnyResult, SolanaRpcClient};
+#[cfg(any(feature = "pumpswap", feature = "raydium"))]
+use crate::constants::pumpswap::accounts::WSOL_TOKEN_ACCOUNT;
+
+/// Side of a fill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+/// A trade that was priced but not sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperFill {
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    pub side: Side,
+    /// Lamports spent (buy) or received (sell)
+    pub sol_amount: u64,
+    /// Tokens received (buy) or sold (sell)
+    pub token_amount: u64,
+    /// Estimated signature fee, priority fee and tip
+    pub fee_lamports: u64,
+    /// PnL realised by a sell, 0 for buys
+    pub realized_pnl_lamports: i64,
+    /// Simulation of the built transaction, when enabled
+    pub simulation: Option<SimulationReport>,
+    pub timestamp: SystemTime,
+}
+
+/// Settings of a `PaperTrader`
+#[derive(Debug, Clone)]
+pub struct PaperTradingConfig {
+    /// Simulate the built transaction over RPC
+    pub simulate: bool,
+    /// Reject the trade when the simulation fails; off by default since the wallet usually
+    /// does not hold the virtual position
+    pub fail_on_simulation_error: bool,
+}
+
+impl Default for PaperTradingConfig {
+    fn default() -> Self {
+        Self { simulate: true, fail_on_simulation_error: false }
+    }
+}
+
+/// Executes trades virtually and keeps the resulting positions
+pub struct PaperTrader {
+    config: PaperTradingConfig,
+    tracker: Arc<PositionTracker>,
+    fills: Mutex<Vec<PaperFill>>,
+}
+
+impl PaperTrader {
+    pub fn new(config: PaperTradingConfig) -> Self {
+        Self::with_tracker(config, Arc::new(PositionTracker::new()))
+    }
+
+    /// Book the fills in an existing tracker
+    pub fn with_tracker(config: PaperTradingConfig, tracker: Arc<PositionTracker>) -> Self {
+        Self { config, tracker, fills: Mutex::new(Vec::new()) }
+    }
+
+    pub fn config(&self) -> &PaperTradingConfig {
+        &self.config
+    }
+
+    pub fn tracker(&self) -> &Arc<PositionTracker> {
+        &self.tracker
+    }
+
+    /// All fills so far, oldest first
+    pub fn fills(&self) -> Vec<PaperFill> {
+        self.fills.lock().unwrap().clone()
+    }
+
+    pub(crate) async fn buy(
+        &self,
+        dex_type: DexType,
+        params: BuyParams,
+        fee_lamports: u64,
+        middleware_manager: Option<Arc<MiddlewareManager>>,
+    ) -> AnyResult<PaperFill> {
+        let token_amount =
+            quote_buy(&dex_type, params.protocol_params.as_ref(), params.sol_amount)?;
+        let simulation = if self.config.simulate {
+            let instructions = TradeFactory::create_instruction_builder(dex_type.clone())
+                .build_buy_instructions(&params)
+                .await?;
+            let transaction = build_rpc_transaction(
+                params.payer.clone(),
+                params.fee_payer.clone(),
+                &params.priority_fee,
+                instructions,
+                params.lookup_table_key,
+                params.recent_blockhash,
+                params.data_size_limit,
+                middleware_manager,
+                dex_type.clone(),
+                true,
+            )
+            .await?;
+            Some(self.simulate(params.rpc.as_ref(), &transaction, dex_type.clone(), true).await?)
+        } else {
+            None
+        };
+
+        self.tracker.record_buy(params.mint, params.sol_amount, token_amount, fee_lamports);
+        let fill = PaperFill {
+            dex_type,
+            mint: params.mint,
+            side: Side::Buy,
+            sol_amount: params.sol_amount,
+            token_amount,
+            fee_lamports,
+            realized_pnl_lamports: 0,
+            simulation,
+            timestamp: SystemTime::now(),
+        };
+        tracing::info!(
+            mint = %fill.mint,
+            sol = fill.sol_amount,
+            tokens = fill.token_amount,
+            "paper buy"
+        );
+        self.fills.lock().unwrap().push(fill.clone());
+        Ok(fill)
+    }
+
+    pub(crate) async fn sell(
+        &self,
+        dex_type: DexType,
+        params: SellParams,
+        fee_lamports: u64,
+        middleware_manager: Option<Arc<MiddlewareManager>>,
+    ) -> AnyResult<PaperFill> {
+        let token_amount = params.token_amount.ok_or_else(|| anyhow!("Token amount is not set"))?;
+        let sol_amount = quote_sell(&dex_type, params.protocol_params.as_ref(), token_amount)?;
+        let simulation = if self.config.simulate {
+            let instructions = TradeFactory::create_instruction_builder(dex_type.clone())
+                .build_sell_instructions(&params)
+                .await?;
+            let transaction = build_sell_transaction(
+                params.payer.clone(),
+                params.fee_payer.clone(),
+                &params.priority_fee,
+                instructions,
+                params.lookup_table_key,
+                params.recent_blockhash,
+                middleware_manager,
+                dex_type.clone(),
+                false,
+            )
+            .await?;
+            Some(self.simulate(params.rpc.as_ref(), &transaction, dex_type.clone(), false).await?)
+        } else {
+            None
+        };
+
+        let realized_pnl_lamports =
+            self.tracker.record_sell(params.mint, token_amount, sol_amount, fee_lamports);
+        let fill = PaperFill {
+            dex_type,
+            mint: params.mint,
+            side: Side::Sell,
+            sol_amount,
+            token_amount,
+            fee_lamports,
+            realized_pnl_lamports,
+            simulation,
+            timestamp: SystemTime::now(),
+        };
+        tracing::info!(
+            mint = %fill.mint,
+            sol = fill.sol_amount,
+            tokens = fill.token_amount,
+            pnl = realized_pnl_lamports,
+            "paper sell"
+        );
+        self.fills.lock().unwrap().push(fill.clone());
+        Ok(fill)
+    }
+
+    async fn simulate(
+        &self,
+        rpc: Option<&Arc<SolanaRpcClient>>,
+        transaction: &VersionedTransaction,
+        dex_type: DexType,
+        is_buy: bool,
+    ) -> AnyResult<SimulationReport> {
+        let rpc = rpc.ok_or_else(|| anyhow!("RPC is not set"))?;
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(CommitmentConfig::processed()),
+            ..Default::default()
+        };
+        let result = rpc.simulate_transaction_with_config(transaction, config).await?.value;
+        let report = SimulationReport {
+            dex_type,
+            is_buy,
+            err: result.err.map(|e| e.to_string()),
+            logs: result.logs.unwrap_or_default(),
+            units_consumed: result.units_consumed,
+        };
+        if let Some(err) = &report.err {
+            if self.config.fail_on_simulation_error {
+                return Err(anyhow!("Paper trade simulation failed: {}", err));
+            }
+            tracing::debug!(error = %err, "paper trade simulation failed");
+        }
+        Ok(report)
+    }
+}
+
+/// Tokens received for `sol_amount` lamports at the reserves in `params`, after protocol fees
+#[cfg_attr(
+    not(any(feature = "pumpfun", feature = "pumpswap", feature = "bonk", feature = "raydium")),
+    allow(unused_variables)
+)]
+pub fn quote_buy(
+    dex_type: &DexType,
+    params: &dyn ProtocolParams,
+    sol_amount: u64,
+) -> AnyResult<u64> {
+    let params = params.as_any();
+    let invalid = || anyhow!("Invalid protocol params for {}", dex_type);
+    match dex_type {
+        #[cfg(feature = "pumpfun")]
+        DexType::PumpFun => {
+            let curve = &params.downcast_ref::<PumpFunParams>().ok_or_else(invalid)?.bonding_curve;
+            Ok(crate::utils::calc::pumpfun::get_buy_token_amount_from_sol_amount(
+                curve.virtual_token_reserves as u128,
+                curve.virtual_sol_reserves as u128,
+                curve.real_token_reserves as u128,
+                curve.creator,
+                sol_amount,
+            ))
+        }
+        #[cfg(feature = "pumpswap")]
+        DexType::PumpSwap => {
+            let p = params.downcast_ref::<PumpSwapParams>().ok_or_else(invalid)?;
+            let creator = pumpswap_fee_creator(p);
+            let (base, quote) = (p.pool_base_token_reserves, p.pool_quote_token_reserves);
+            use crate::utils::calc::pumpswap::{
+                buy_quote_input_internal, sell_base_input_internal,
+            };
+            // Pools holding WSOL as base are traded the other way round
+            let out = if p.base_mint != WSOL_TOKEN_ACCOUNT {
+                buy_quote_input_internal(sol_amount, 0, base, quote, &creator).map(|r| r.base)
+            } else {
+                sell_base_input_internal(sol_amount, 0, base, quote, &creator).map(|r| r.ui_quote)
+            };
+            out.map_err(|e| anyhow!("PumpSwap quote failed: {}", e))
+        }
+        #[cfg(feature = "bonk")]
+        DexType::Bonk => {
+            let p = params.downcast_ref::<BonkParams>().ok_or_else(invalid)?;
+            crate::utils::calc::bonk::get_buy_token_amount_from_sol_amount(
+                sol_amount,
+                p.virtual_base,
+                p.virtual_quote,
+                p.real_base,
+                p.real_quote,
+                0,
+            )
+            .map_err(|e| anyhow!("Bonk quote failed: {}", e))
+        }
+        #[cfg(feature = "raydium")]
+        DexType::RaydiumCpmm => {
+            let p = params.downcast_ref::<RaydiumCpmmParams>().ok_or_else(invalid)?;
+            let is_base_in = p.base_mint == WSOL_TOKEN_ACCOUNT;
+            Ok(crate::utils::calc::raydium_cpmm::compute_swap_amount(
+                p.base_reserve,
+                p.quote_reserve,
+                is_base_in,
+                sol_amount,
+                0,
+            )
+            .amount_out)
+        }
+        #[cfg(feature = "raydium")]
+        DexType::RaydiumAmmV4 => {
+            let p = params.downcast_ref::<RaydiumAmmV4Params>().ok_or_else(invalid)?;
+            let is_base_in = p.coin_mint == WSOL_TOKEN_ACCOUNT;
+            Ok(crate::utils::calc::raydium_amm_v4::compute_swap_amount(
+                p.coin_reserve,
+                p.pc_reserve,
+                is_base_in,
+                sol_amount,
+                0,
+            )
+            .amount_out)
+        }
+    }
+}
+
+/// Lamports received for `token_amount` tokens at the reserves in `params`, after protocol fees
+#[cfg_attr(
+    not(any(feature = "pumpfun", feature = "pumpswap", feature = "bonk", feature = "raydium")),
+    allow(unused_variables)
+)]
+pub fn quote_sell(
+    dex_type: &DexType,
+    params: &dyn ProtocolParams,
+    token_amount: u64,
+) -> AnyResult<u64> {
+    let params = params.as_any();
+    let invalid = || anyhow!("Invalid protocol params for {}", dex_type);
+    match dex_type {
+        #[cfg(feature = "pumpfun")]
+        DexType::PumpFun => {
+            let curve = &params.downcast_ref::<PumpFunParams>().ok_or_else(invalid)?.bonding_curve;
+            Ok(crate::utils::calc::pumpfun::get_sell_sol_amount_from_token_amount(
+                curve.virtual_token_reserves as u128,
+                curve.virtual_sol_reserves as u128,
+                curve.creator,
+                token_amount,
+            ))
+        }
+        #[cfg(feature = "pumpswap")]
+        DexType::PumpSwap => {
+            let p = params.downcast_ref::<PumpSwapParams>().ok_or_else(invalid)?;
+            let creator = pumpswap_fee_creator(p);
+            let (base, quote) = (p.pool_base_token_reserves, p.pool_quote_token_reserves);
+            use crate::utils::calc::pumpswap::{
+                buy_quote_input_internal, sell_base_input_internal,
+            };
+            let out = if p.base_mint != WSOL_TOKEN_ACCOUNT {
+                sell_base_input_internal(token_amount, 0, base, quote, &creator).map(|r| r.ui_quote)
+            } else {
+                buy_quote_input_internal(token_amount, 0, base, quote, &creator).map(|r| r.base)
+            };
+            out.map_err(|e| anyhow!("PumpSwap quote failed: {}", e))
+        }
+        #[cfg(feature = "bonk")]
+        DexType::Bonk => {
+            let p = params.downcast_ref::<BonkParams>().ok_or_else(invalid)?;
+            crate::utils::calc::bonk::get_sell_sol_amount_from_token_amount(
+                token_amount,
+                p.virtual_base,
+                p.virtual_quote,
+                p.real_base,
+                p.real_quote,
+                0,
+            )
+            .map_err(|e| anyhow!("Bonk quote failed: {}", e))
+        }
+        #[cfg(feature = "raydium")]
+        DexType::RaydiumCpmm => {
+            let p = params.downcast_ref::<RaydiumCpmmParams>().ok_or_else(invalid)?;
+            let is_base_in = p.base_mint != WSOL_TOKEN_ACCOUNT;
+            Ok(crate::utils::calc::raydium_cpmm::compute_swap_amount(
+                p.base_reserve,
+                p.quote_reserve,
+                is_base_in,
+                token_amount,
+                0,
+            )
+            .amount_out)
+        }
+        #[cfg(feature = "raydium")]
+        DexType::RaydiumAmmV4 => {
+            let p = params.downcast_ref::<RaydiumAmmV4Params>().ok_or_else(invalid)?;
+            let is_base_in = p.coin_mint != WSOL_TOKEN_ACCOUNT;
+            Ok(crate::utils::calc::raydium_amm_v4::compute_swap_amount(
+                p.coin_reserve,
+                p.pc_reserve,
+                is_base_in,
+                token_amount,
+                0,
+            )
+            .amount_out)
+        }
+    }
+}
+
+/// Total protocol fee of a swap on the market in `params`, in basis points of the SOL side
+#[cfg_attr(
+    not(any(feature = "pumpfun", feature = "pumpswap", feature = "bonk", feature = "raydium")),
+    allow(unused_variables)
+)]
+pub fn protocol_fee_basis_points(
+    dex_type: &DexType,
+    params: &dyn ProtocolParams,
+) -> AnyResult<u64> {
+    let params = params.as_any();
+    let invalid = || anyhow!("Invalid protocol params for {}", dex_type);
+    match dex_type {
+        #[cfg(feature = "pumpfun")]
+        DexType::PumpFun => {
+            use crate::constants::pumpfun::global_constants::{CREATOR_FEE, FEE_BASIS_POINTS};
+            let curve = &params.downcast_ref::<PumpFunParams>().ok_or_else(invalid)?.bonding_curve;
+            let creator_fee = if curve.creator != Pubkey::default() { CREATOR_FEE } else { 0 };
+            Ok(FEE_BASIS_POINTS + creator_fee)
+        }
+        #[cfg(feature = "pumpswap")]
+        DexType::PumpSwap => {
+            let p = params.downcast_ref::<PumpSwapParams>().ok_or_else(invalid)?;
+            let fees = crate::trading::pumpswap::fee_config::FeeConfigCache::get_instance().get();
+            let creator_fee = if pumpswap_fee_creator(p) != Pubkey::default() {
+                fees.coin_creator_fee_basis_points
+            } else {
+                0
+            };
+            Ok(fees.lp_fee_basis_points + fees.protocol_fee_basis_points + creator_fee)
+        }
+        #[cfg(feature = "bonk")]
+        DexType::Bonk => {
+            use crate::constants::bonk::accounts::{
+                PLATFORM_FEE_RATE, PROTOCOL_FEE_RATE, SHARE_FEE_RATE,
+            };
+            params.downcast_ref::<BonkParams>().ok_or_else(invalid)?;
+            Ok((PROTOCOL_FEE_RATE + PLATFORM_FEE_RATE + SHARE_FEE_RATE) as u64)
+        }
+        #[cfg(feature = "raydium")]
+        DexType::RaydiumCpmm => {
+            use crate::constants::raydium_cpmm::accounts::{
+                FEE_RATE_DENOMINATOR_VALUE, TRADE_FEE_RATE,
+            };
+            params.downcast_ref::<RaydiumCpmmParams>().ok_or_else(invalid)?;
+            Ok(TRADE_FEE_RATE * 10_000 / FEE_RATE_DENOMINATOR_VALUE as u64)
+        }
+        #[cfg(feature = "raydium")]
+        DexType::RaydiumAmmV4 => {
+            use crate::constants::raydium_amm_v4::accounts::{
+                TRADE_FEE_DENOMINATOR, TRADE_FEE_NUMERATOR,
+            };
+            params.downcast_ref::<RaydiumAmmV4Params>().ok_or_else(invalid)?;
+            Ok(TRADE_FEE_NUMERATOR * 10_000 / TRADE_FEE_DENOMINATOR)
+        }
+    }
+}
+
+/// Only whether the pool has a coin creator matters for the fee, same check as the
+/// PumpSwap instruction builder
+#[cfg(feature = "pumpswap")]
+fn pumpswap_fee_creator(params: &PumpSwapParams) -> Pubkey {
+    use crate::trading::pumpswap::common::coin_creator_vault_ata;
+    let default_creator = Pubkey::default();
+    if coin_creator_vault_ata(default_creator, params.quote_mint) == params.coin_creator_vault_ata {
+        default_creator
+    } else {
+        params.coin_creator_vault_ata
+    }
+}
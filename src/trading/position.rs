@@ -0,0 +1,128 @@
+//! Position tracking
+//!
+//! `PositionTracker` keeps, per mint, the tokens held, the SOL cost of those tokens and the
+//! PnL realised by sells (average cost basis). It is fed by paper trading fills, and can be
+//! fed by the application from its own confirmed trades.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Holdings and PnL of one token
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub mint: Pubkey,
+    /// Tokens held, in base units
+    pub token_amount: u64,
+    /// SOL spent on the tokens still held, in lamports
+    pub cost_lamports: u64,
+    /// PnL of the sells so far, net of fees, in lamports
+    pub realized_pnl_lamports: i64,
+    /// Transaction fees and tips paid, in lamports
+    pub fees_lamports: u64,
+    pub buys: u32,
+    pub sells: u32,
+}
+
+impl Position {
+    /// PnL of the tokens still held if they are worth `value_lamports`
+    pub fn unrealized_pnl(&self, value_lamports: u64) -> i64 {
+        value_lamports as i64 - self.cost_lamports as i64
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.token_amount > 0
+    }
+}
+
+/// Positions by mint
+#[derive(Debug, Default)]
+pub struct PositionTracker {
+    positions: Mutex<HashMap<Pubkey, Position>>,
+}
+
+impl PositionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a buy of `token_amount` tokens for `sol_amount` lamports
+    pub fn record_buy(&self, mint: Pubkey, sol_amount: u64, token_amount: u64, fee_lamports: u64) {
+        let mut positions = self.positions.lock().unwrap();
+        let position =
+            positions.entry(mint).or_insert_with(|| Position { mint, ..Default::default() });
+        position.token_amount = position.token_amount.saturating_add(token_amount);
+        position.cost_lamports = position.cost_lamports.saturating_add(sol_amount);
+        position.realized_pnl_lamports -= fee_lamports as i64;
+        position.fees_lamports = position.fees_lamports.saturating_add(fee_lamports);
+        position.buys += 1;
+    }
+
+    /// Record a sell of `token_amount` tokens for `sol_amount` lamports, returns the PnL it realised
+    ///
+    /// The cost of the sold tokens is taken at the average cost of the position; tokens sold
+    /// beyond the tracked amount have no cost.
+    pub fn record_sell(
+        &self,
+        mint: Pubkey,
+        token_amount: u64,
+        sol_amount: u64,
+        fee_lamports: u64,
+    ) -> i64 {
+        let mut positions = self.positions.lock().unwrap();
+        let position =
+            positions.entry(mint).or_insert_with(|| Position { mint, ..Default::default() });
+        let sold = token_amount.min(position.token_amount);
+        let cost = if position.token_amount == 0 {
+            0
+        } else {
+            (position.cost_lamports as u128 * sold as u128 / position.token_amount as u128) as u64
+        };
+        position.token_amount -= sold;
+        position.cost_lamports -= cost;
+        let pnl = sol_amount as i64 - cost as i64 - fee_lamports as i64;
+        position.realized_pnl_lamports += pnl;
+        position.fees_lamports = position.fees_lamports.saturating_add(fee_lamports);
+        position.sells += 1;
+        pnl
+    }
+
+    pub fn position(&self, mint: &Pubkey) -> Option<Position> {
+        self.positions.lock().unwrap().get(mint).cloned()
+    }
+
+    pub fn positions(&self) -> Vec<Position> {
+        self.positions.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Realised PnL over all positions, in lamports
+    pub fn realized_pnl(&self) -> i64 {
+        self.positions.lock().unwrap().values().map(|p| p.realized_pnl_lamports).sum()
+    }
+
+    pub fn clear(&self) {
+        self.positions.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_cost_pnl() {
+        let tracker = PositionTracker::new();
+        let mint = Pubkey::new_unique();
+        tracker.record_buy(mint, 1_000, 100, 10);
+        tracker.record_buy(mint, 3_000, 100, 10);
+        // Half the tokens at an average cost of 20 per token
+        assert_eq!(tracker.record_sell(mint, 100, 2_500, 10), 2_500 - 2_000 - 10);
+        let position = tracker.position(&mint).unwrap();
+        assert_eq!(position.token_amount, 100);
+        assert_eq!(position.cost_lamports, 2_000);
+        assert_eq!(position.fees_lamports, 30);
+        assert_eq!(position.unrealized_pnl(2_200), 200);
+        assert_eq!(tracker.realized_pnl(), 500 - 30);
+    }
+}
@@ -1,14 +1,13 @@
 use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunTradeEvent;
 use crate::{
     common::{
-        bonding_curve::BondingCurveAccount, global::GlobalAccount, PriorityFee, SolanaRpcClient,
+        bonding_curve::BondingCurveAccount, bonding_curve_cache::BondingCurveCache,
+        global::GlobalAccount, SolanaRpcClient,
     },
     constants::{self, trade::trade::DEFAULT_SLIPPAGE},
 };
 use anyhow::anyhow;
-use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction, instruction::Instruction, pubkey::Pubkey,
-};
+use solana_sdk::pubkey::Pubkey;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 
@@ -133,6 +132,23 @@ pub async fn fetch_bonding_curve_account(
     Ok((Arc::new(bonding_curve), bonding_curve_pda))
 }
 
+/// Get the bonding curve for a mint, served from `BondingCurveCache` when fresh
+///
+/// Falls back to RPC on a cache miss and stores the fetched account.
+pub async fn get_bonding_curve_account(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<Arc<BondingCurveAccount>, anyhow::Error> {
+    let cache = BondingCurveCache::get_instance();
+    if let Some(bonding_curve) = cache.get(mint) {
+        return Ok(bonding_curve);
+    }
+    let (bonding_curve, _) = fetch_bonding_curve_account(rpc, mint).await?;
+    let bonding_curve = Arc::new(BondingCurveAccount::from_bonding_curve(mint, &bonding_curve));
+    cache.insert(*mint, bonding_curve.clone());
+    Ok(bonding_curve)
+}
+
 #[inline]
 pub async fn init_bonding_curve_account(
     mint: &Pubkey,
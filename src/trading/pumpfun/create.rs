@@ -0,0 +1,102 @@
+use anyhow::anyhow;
+use solana_sdk::{
+    signature::Keypair, signature::Signature, signer::Signer, transaction::Transaction,
+};
+
+use crate::{
+    common::{bonding_curve::BondingCurveAccount, SolanaRpcClient, TradeSigner},
+    constants::{
+        pumpfun::{accounts::TOKEN_PROGRAM, global_constants::FEE_RECIPIENT},
+        trade::trade::DEFAULT_SLIPPAGE,
+    },
+    instruction::pumpfun::{buy, create, Buy, Create},
+    trading::{common::add_create_ata_instruction, pumpfun::common::get_creator_vault_pda},
+    utils::calc::{
+        common::calculate_with_slippage_buy, pumpfun::get_buy_token_amount_from_sol_amount,
+    },
+};
+
+/// Create a PumpFun token from an already hosted metadata URI
+///
+/// No metadata is uploaded; `uri` is written to the token as is. The payer becomes the
+/// creator, and when `dev_buy_sol_amount` is set the initial buy is made in the same
+/// transaction so nobody can buy ahead of the creator.
+///
+/// # Arguments
+/// * `rpc` - RPC client
+/// * `payer` - Creator, also pays the fees
+/// * `mint` - Keypair of the new mint
+/// * `name` - Token name
+/// * `symbol` - Token symbol
+/// * `uri` - Metadata URI
+/// * `dev_buy_sol_amount` - Optional initial buy in lamports
+/// * `slippage_basis_points` - Slippage tolerance for the initial buy (100 = 1%)
+///
+/// # Returns
+/// Returns the signature of the confirmed create transaction
+#[allow(clippy::too_many_arguments)]
+pub async fn create_token(
+    rpc: &SolanaRpcClient,
+    payer: &dyn TradeSigner,
+    mint: &Keypair,
+    name: String,
+    symbol: String,
+    uri: String,
+    dev_buy_sol_amount: Option<u64>,
+    slippage_basis_points: Option<u64>,
+) -> Result<Signature, anyhow::Error> {
+    if uri.is_empty() {
+        return Err(anyhow!("Metadata URI cannot be empty"));
+    }
+    let creator = payer.pubkey();
+    let mut instructions = vec![create(
+        payer,
+        mint,
+        Create { _name: name, _symbol: symbol, _uri: uri, _creator: creator },
+    )];
+
+    if let Some(sol_amount) = dev_buy_sol_amount.filter(|amount| *amount > 0) {
+        // Fresh curve, nothing has been bought yet
+        let bonding_curve = BondingCurveAccount::from_dev_trade(&mint.pubkey(), 0, 0, creator);
+        let token_amount = get_buy_token_amount_from_sol_amount(
+            bonding_curve.virtual_token_reserves as u128,
+            bonding_curve.virtual_sol_reserves as u128,
+            bonding_curve.real_token_reserves as u128,
+            creator,
+            sol_amount,
+        );
+        let max_sol_cost = calculate_with_slippage_buy(
+            sol_amount,
+            slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+        )
+        .map_err(|e| anyhow!(e))?;
+        let creator_vault =
+            get_creator_vault_pda(&creator).ok_or(anyhow!("Creator vault not found"))?;
+        add_create_ata_instruction(
+            &mut instructions,
+            &creator,
+            &creator,
+            &mint.pubkey(),
+            &TOKEN_PROGRAM,
+        );
+        instructions.push(buy(
+            payer,
+            &mint.pubkey(),
+            &bonding_curve.account,
+            &creator_vault,
+            &FEE_RECIPIENT,
+            Buy { _amount: token_amount, _max_sol_cost: max_sol_cost },
+        ));
+    }
+
+    let recent_blockhash = rpc.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&creator),
+        &[payer, mint as &dyn TradeSigner],
+        recent_blockhash,
+    );
+
+    let signature = rpc.send_and_confirm_transaction(&transaction).await?;
+    Ok(signature)
+}
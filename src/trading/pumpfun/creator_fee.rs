@@ -0,0 +1,51 @@
+use anyhow::anyhow;
+use solana_sdk::{
+    pubkey::Pubkey, signature::Signature, transaction::Transaction,
+};
+
+use crate::{
+    common::{SolanaRpcClient, TradeSigner},
+    instruction::pumpfun::collect_creator_fee,
+    trading::pumpfun::common::get_creator_vault_pda,
+};
+
+/// Get the claimable creator fees in lamports
+///
+/// The creator vault keeps its rent-exempt minimum, so only the excess is claimable.
+pub async fn get_claimable_creator_fees(
+    rpc: &SolanaRpcClient,
+    creator: &Pubkey,
+) -> Result<u64, anyhow::Error> {
+    let creator_vault =
+        get_creator_vault_pda(creator).ok_or(anyhow!("Creator vault not found"))?;
+    let balance = rpc.get_balance(&creator_vault).await?;
+    let rent_exempt = rpc.get_minimum_balance_for_rent_exemption(0).await?;
+    Ok(balance.saturating_sub(rent_exempt))
+}
+
+/// Claim the accumulated creator fees to the payer
+///
+/// The payer must be the creator of the coins whose fees are being claimed.
+///
+/// # Returns
+/// Returns the signature of the confirmed claim transaction
+pub async fn claim_creator_fees(
+    rpc: &SolanaRpcClient,
+    payer: &dyn TradeSigner,
+) -> Result<Signature, anyhow::Error> {
+    let claimable = get_claimable_creator_fees(rpc, &payer.pubkey()).await?;
+    if claimable == 0 {
+        return Err(anyhow!("No creator fees to claim"));
+    }
+
+    let recent_blockhash = rpc.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[collect_creator_fee(&payer.pubkey())],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+
+    let signature = rpc.send_and_confirm_transaction(&transaction).await?;
+    Ok(signature)
+}
@@ -0,0 +1,82 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    common::{SolanaRpcClient, TradeError},
+    trading::{
+        core::{
+            params::{PumpFunParams, PumpSwapParams},
+            traits::ProtocolParams,
+        },
+        factory::DexType,
+        pumpswap::common::find_pool,
+    },
+};
+
+/// PumpSwap pool a graduated PumpFun token trades on, `None` while it is not migrated yet
+pub async fn find_migrated_pool(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<Option<Pubkey>, anyhow::Error> {
+    match find_pool(rpc, mint).await {
+        Ok(pool) => Ok(Some(pool)),
+        Err(e) if matches!(e.downcast_ref::<TradeError>(), Some(TradeError::PoolNotFound(_))) => {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// PumpSwap protocol and params of a graduated PumpFun token
+///
+/// Fails with `CurveComplete` when the curve is complete but the pool does not exist yet.
+pub async fn migrated_route(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<(DexType, Box<dyn ProtocolParams>), anyhow::Error> {
+    let pool = find_migrated_pool(rpc, mint)
+        .await?
+        .ok_or(TradeError::CurveComplete { mint: Some(*mint), migrated_pool: None })?;
+    Ok((DexType::PumpSwap, Box::new(PumpSwapParams::from_pool_address_by_rpc(rpc, &pool).await?)))
+}
+
+/// Route a PumpFun trade to PumpSwap when its params show a complete bonding curve
+pub(crate) async fn reroute_if_complete(
+    rpc: &SolanaRpcClient,
+    dex_type: DexType,
+    mint: &Pubkey,
+    protocol_params: Box<dyn ProtocolParams>,
+) -> Result<(DexType, Box<dyn ProtocolParams>), anyhow::Error> {
+    let complete = dex_type == DexType::PumpFun
+        && protocol_params
+            .as_any()
+            .downcast_ref::<PumpFunParams>()
+            .is_some_and(|params| params.bonding_curve.complete);
+    if !complete {
+        return Ok((dex_type, protocol_params));
+    }
+    migrated_route(rpc, mint).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::bonding_curve::BondingCurveAccount;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_reroute_keeps_active_curve() {
+        // Unreachable endpoint: an active curve must not touch the RPC
+        let rpc = SolanaRpcClient::new("http://127.0.0.1:1".to_string());
+        let params = PumpFunParams {
+            bonding_curve: Arc::new(BondingCurveAccount::default()),
+            creator_vault: Pubkey::new_unique(),
+            close_token_account_when_sell: None,
+        };
+        let (dex_type, params) =
+            reroute_if_complete(&rpc, DexType::PumpFun, &Pubkey::new_unique(), Box::new(params))
+                .await
+                .unwrap();
+        assert_eq!(dex_type, DexType::PumpFun);
+        assert!(params.as_any().downcast_ref::<PumpFunParams>().is_some());
+    }
+}
@@ -1 +1,5 @@
 pub mod common;
+pub mod create;
+pub mod creator_fee;
+#[cfg(feature = "pumpswap")]
+pub mod migration;
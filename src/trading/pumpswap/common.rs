@@ -1,13 +1,19 @@
-use crate::common::SolanaRpcClient;
+use crate::common::{SolanaRpcClient, TradeError};
 use crate::constants::pumpswap::accounts;
+use crate::trading::pumpswap::pool_cache::PoolCache;
 use anyhow::anyhow;
 use solana_account_decoder::UiAccountEncoding;
 use solana_sdk::pubkey::Pubkey;
 use solana_streamer_sdk::streaming::event_parser::protocols::pumpswap::types::{pool_decode, Pool};
 
-// Find a pool for a specific mint
+// Find a pool for a specific mint, served from `PoolCache` and falling back to RPC on a miss
 pub async fn find_pool(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<Pubkey, anyhow::Error> {
+    let cache = PoolCache::get_instance();
+    if let Some(pool_address) = cache.get(mint) {
+        return Ok(pool_address);
+    }
     let (pool_address, _) = find_by_mint(rpc, mint).await?;
+    cache.insert(*mint, pool_address);
     Ok(pool_address)
 }
 
@@ -149,18 +155,17 @@ pub async fn find_by_mint(
     if let Ok((address, pool)) = find_by_quote_mint(rpc, mint).await {
         return Ok((address, pool));
     }
-    Err(anyhow!("No pool found for mint {}", mint))
+    Err(TradeError::PoolNotFound(format!("No PumpSwap pool for mint {}", mint)).into())
 }
 
 pub async fn get_token_balances(
     pool: &Pool,
     rpc: &SolanaRpcClient,
 ) -> Result<(u64, u64), anyhow::Error> {
-    let base_balance = rpc.get_token_account_balance(&pool.pool_base_token_account).await?;
-    let quote_balance = rpc.get_token_account_balance(&pool.pool_quote_token_account).await?;
-
-    let base_amount = base_balance.amount.parse::<u64>().map_err(|e| anyhow!(e))?;
-    let quote_amount = quote_balance.amount.parse::<u64>().map_err(|e| anyhow!(e))?;
-
-    Ok((base_amount, quote_amount))
+    crate::trading::common::get_multi_token_balances(
+        rpc,
+        &pool.pool_base_token_account,
+        &pool.pool_quote_token_account,
+    )
+    .await
 }
@@ -0,0 +1,110 @@
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::Transaction};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+
+use crate::{
+    common::{wsol_cache::WsolCache, SolanaRpcClient, TradeSigner},
+    constants::pumpswap::accounts,
+    instruction::pumpswap::collect_coin_creator_fee,
+    trading::{common::parse_token_account_amount, pumpswap::common::coin_creator_vault_ata},
+};
+
+/// Get the coin creator fees in the PumpSwap creator vault, in lamports of WSOL
+///
+/// Returns 0 while the vault does not exist, i.e. before the first trade paid a fee.
+pub async fn get_claimable_coin_creator_fees(
+    rpc: &SolanaRpcClient,
+    coin_creator: &Pubkey,
+) -> Result<u64, anyhow::Error> {
+    let vault = coin_creator_vault_ata(*coin_creator, accounts::WSOL_TOKEN_ACCOUNT);
+    let accounts = rpc.get_multiple_accounts(&[vault]).await?;
+    Ok(accounts[0].as_ref().and_then(parse_token_account_amount).unwrap_or(0))
+}
+
+/// Claim the coin creator fees of the payer's graduated coins and unwrap them to SOL
+///
+/// The fees are collected into the payer's WSOL account, which is created if needed and
+/// closed afterwards, so any WSOL already held there is unwrapped as well. With a persistent
+/// WSOL account (`WsolMode::Persistent`) the account stays open and the fees stay WSOL,
+/// credited to its `WsolCache` balance.
+///
+/// # Returns
+/// Returns the signature of the confirmed claim transaction
+pub async fn claim_coin_creator_fees(
+    rpc: &SolanaRpcClient,
+    payer: &dyn TradeSigner,
+) -> Result<Signature, anyhow::Error> {
+    let creator = payer.pubkey();
+    let claimable = get_claimable_coin_creator_fees(rpc, &creator).await?;
+    if claimable == 0 {
+        return Err(anyhow::anyhow!("No coin creator fees to claim"));
+    }
+
+    let wsol_account = get_associated_token_address_with_program_id(
+        &creator,
+        &accounts::WSOL_TOKEN_ACCOUNT,
+        &accounts::TOKEN_PROGRAM,
+    );
+    let persistent = WsolCache::get_instance().is_persistent();
+    let mut instructions = vec![
+        create_associated_token_account_idempotent(
+            &creator,
+            &creator,
+            &accounts::WSOL_TOKEN_ACCOUNT,
+            &accounts::TOKEN_PROGRAM,
+        ),
+        collect_coin_creator_fee(&creator, &accounts::WSOL_TOKEN_ACCOUNT),
+    ];
+    if !persistent {
+        instructions.push(spl_token::instruction::close_account(
+            &accounts::TOKEN_PROGRAM,
+            &wsol_account,
+            &creator,
+            &creator,
+            &[&creator],
+        )?);
+    }
+
+    let recent_blockhash = rpc.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&creator),
+        &[payer],
+        recent_blockhash,
+    );
+    let signature = rpc.send_and_confirm_transaction(&transaction).await?;
+    if persistent {
+        // At least what was claimable before, fees paid since then are credited by `refresh`
+        WsolCache::get_instance().credit(&creator, claimable);
+    }
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockRpc;
+    use solana_sdk::account::Account;
+
+    #[tokio::test]
+    async fn test_claimable_coin_creator_fees() {
+        let creator = Pubkey::new_unique();
+        let rpc = MockRpc::new().client();
+        assert_eq!(get_claimable_coin_creator_fees(&rpc, &creator).await.unwrap(), 0);
+
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&42_000u64.to_le_bytes());
+        let vault = Account {
+            lamports: 2_039_280,
+            data,
+            owner: accounts::TOKEN_PROGRAM,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let vault_address = coin_creator_vault_ata(creator, accounts::WSOL_TOKEN_ACCOUNT);
+        let rpc = MockRpc::new().with_account(vault_address, vault).client();
+        assert_eq!(get_claimable_coin_creator_fees(&rpc, &creator).await.unwrap(), 42_000);
+    }
+}
@@ -0,0 +1,200 @@
+use anyhow::anyhow;
+use solana_streamer_sdk::streaming::event_parser::protocols::pumpswap::{
+    types::{global_config_decode, GlobalConfig},
+    PumpSwapGlobalConfigAccountEvent,
+};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::common::SolanaRpcClient;
+use crate::constants::pumpswap::accounts::{
+    AMM_PROGRAM, COIN_CREATOR_FEE_BASIS_POINTS, GLOBAL_ACCOUNT, LP_FEE_BASIS_POINTS,
+    PROTOCOL_FEE_BASIS_POINTS,
+};
+
+/// 默认费率刷新间隔
+pub const DEFAULT_FEE_CONFIG_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// PumpSwap 交易费率（基点）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeConfig {
+    pub lp_fee_basis_points: u64,
+    pub protocol_fee_basis_points: u64,
+    pub coin_creator_fee_basis_points: u64,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self {
+            lp_fee_basis_points: LP_FEE_BASIS_POINTS,
+            protocol_fee_basis_points: PROTOCOL_FEE_BASIS_POINTS,
+            coin_creator_fee_basis_points: COIN_CREATOR_FEE_BASIS_POINTS,
+        }
+    }
+}
+
+impl FeeConfig {
+    /// 从链上 GlobalConfig 账户读取费率
+    pub fn from_global_config(global_config: &GlobalConfig) -> Self {
+        Self {
+            lp_fee_basis_points: global_config.lp_fee_basis_points,
+            protocol_fee_basis_points: global_config.protocol_fee_basis_points,
+            coin_creator_fee_basis_points: global_config.coin_creator_fee_basis_points,
+        }
+    }
+}
+
+/// FeeConfigCache 单例，缓存 PumpSwap 全局费率
+///
+/// 未加载前使用常量中的默认费率；可通过 RPC 定期刷新，
+/// 或订阅 GlobalConfig 账户事件在费率变更时立即更新。
+pub struct FeeConfigCache {
+    config: Mutex<FeeConfig>,
+    updated_at: Mutex<Option<Instant>>,
+    refresh_interval: Mutex<Duration>,
+}
+
+static FEE_CONFIG_CACHE: OnceLock<Arc<FeeConfigCache>> = OnceLock::new();
+
+impl FeeConfigCache {
+    /// 获取 FeeConfigCache 单例实例
+    pub fn get_instance() -> Arc<FeeConfigCache> {
+        FEE_CONFIG_CACHE
+            .get_or_init(|| {
+                Arc::new(FeeConfigCache {
+                    config: Mutex::new(FeeConfig::default()),
+                    updated_at: Mutex::new(None),
+                    refresh_interval: Mutex::new(DEFAULT_FEE_CONFIG_REFRESH_INTERVAL),
+                })
+            })
+            .clone()
+    }
+
+    /// 设置刷新间隔
+    pub fn set_refresh_interval(&self, interval: Duration) {
+        *self.refresh_interval.lock().unwrap() = interval;
+    }
+
+    /// 获取当前费率
+    pub fn get(&self) -> FeeConfig {
+        *self.config.lock().unwrap()
+    }
+
+    /// 写入费率
+    pub fn set(&self, config: FeeConfig) {
+        *self.config.lock().unwrap() = config;
+        *self.updated_at.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// 使用 GlobalConfig 账户事件更新费率
+    pub fn update_from_global_config(&self, event: &PumpSwapGlobalConfigAccountEvent) {
+        self.set(FeeConfig::from_global_config(&event.global_config));
+    }
+
+    /// 从 RPC 加载 GlobalConfig 账户并更新费率
+    pub async fn refresh(&self, rpc: &SolanaRpcClient) -> Result<FeeConfig, anyhow::Error> {
+        let account = rpc.get_account(&GLOBAL_ACCOUNT).await?;
+        if account.owner != AMM_PROGRAM {
+            return Err(anyhow!("Account is not owned by PumpSwap program"));
+        }
+        let global_config = account
+            .data
+            .get(8..)
+            .and_then(global_config_decode)
+            .ok_or_else(|| anyhow!("Failed to decode global config"))?;
+        let config = FeeConfig::from_global_config(&global_config);
+        self.set(config);
+        Ok(config)
+    }
+
+    /// 超过刷新间隔时从 RPC 刷新，刷新失败时继续使用当前费率
+    pub async fn refresh_if_stale(&self, rpc: &SolanaRpcClient) -> FeeConfig {
+        let interval = *self.refresh_interval.lock().unwrap();
+        let stale = self
+            .updated_at
+            .lock()
+            .unwrap()
+            .map(|updated_at| updated_at.elapsed() > interval)
+            .unwrap_or(true);
+        if stale {
+            if let Err(e) = self.refresh(rpc).await {
+                tracing::warn!(error = %e, "Failed to refresh pumpswap fee config");
+                // 失败后同样等待一个刷新间隔，避免每次报价都请求 RPC
+                *self.updated_at.lock().unwrap() = Some(Instant::now());
+            }
+        }
+        self.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockRpc;
+    use solana_sdk::{account::Account, pubkey::Pubkey};
+
+    fn cache() -> FeeConfigCache {
+        FeeConfigCache {
+            config: Mutex::new(FeeConfig::default()),
+            updated_at: Mutex::new(None),
+            refresh_interval: Mutex::new(DEFAULT_FEE_CONFIG_REFRESH_INTERVAL),
+        }
+    }
+
+    /// GlobalConfig account data: discriminator, admin, lp and protocol fee, disable flags,
+    /// protocol fee recipients, coin creator fee and coin creator authority
+    fn global_config_account(owner: Pubkey, fees: FeeConfig) -> Account {
+        let mut data = vec![0u8; 8];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&fees.lp_fee_basis_points.to_le_bytes());
+        data.extend_from_slice(&fees.protocol_fee_basis_points.to_le_bytes());
+        data.push(0);
+        data.extend_from_slice(&[0u8; 32 * 8]);
+        data.extend_from_slice(&fees.coin_creator_fee_basis_points.to_le_bytes());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        Account { lamports: 1_000_000, data, owner, executable: false, rent_epoch: 0 }
+    }
+
+    #[tokio::test]
+    async fn test_defaults_before_refresh() {
+        let cache = cache();
+        let defaults = FeeConfig {
+            lp_fee_basis_points: LP_FEE_BASIS_POINTS,
+            protocol_fee_basis_points: PROTOCOL_FEE_BASIS_POINTS,
+            coin_creator_fee_basis_points: COIN_CREATOR_FEE_BASIS_POINTS,
+        };
+        assert_eq!(cache.get(), defaults);
+
+        // A failed refresh keeps the defaults
+        let rpc = MockRpc::new().client();
+        assert_eq!(cache.refresh_if_stale(&rpc).await, defaults);
+        assert!(cache.updated_at.lock().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_decodes_global_config() {
+        let fees = FeeConfig {
+            lp_fee_basis_points: 25,
+            protocol_fee_basis_points: 5,
+            coin_creator_fee_basis_points: 7,
+        };
+        let cache = cache();
+        let rpc = MockRpc::new()
+            .with_account(GLOBAL_ACCOUNT, global_config_account(AMM_PROGRAM, fees))
+            .client();
+        assert_eq!(cache.refresh(&rpc).await.unwrap(), fees);
+        assert_eq!(cache.get(), fees);
+
+        let cache = self::cache();
+        let rpc = MockRpc::new()
+            .with_account(GLOBAL_ACCOUNT, global_config_account(Pubkey::new_unique(), fees))
+            .client();
+        assert!(cache.refresh(&rpc).await.is_err());
+
+        let mut account = global_config_account(AMM_PROGRAM, fees);
+        account.data.truncate(account.data.len() - 1);
+        let rpc = MockRpc::new().with_account(GLOBAL_ACCOUNT, account).client();
+        assert!(cache.refresh(&rpc).await.is_err());
+        assert_eq!(cache.get(), FeeConfig::default());
+    }
+}
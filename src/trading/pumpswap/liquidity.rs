@@ -0,0 +1,225 @@
+use anyhow::anyhow;
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signature::Signature, transaction::Transaction,
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+
+use crate::{
+    common::{mint_info_cache::MintInfoCache, SolanaRpcClient, TradeSigner},
+    constants::{pumpswap::accounts, trade::trade::DEFAULT_SLIPPAGE},
+    instruction::pumpswap::{self, LiquidityAccounts},
+    trading::pumpswap::common::{fetch_pool, get_token_balances},
+    utils::calc::pumpswap::{deposit_base_input, withdraw_lp_input},
+};
+
+/// Deposit liquidity into a PumpSwap pool
+///
+/// The quote amount is derived from the pool ratio so the deposit is proportional.
+/// WSOL is wrapped automatically when either side of the pool is SOL, and the LP token
+/// account (Token-2022) is created if needed.
+///
+/// # Arguments
+/// * `rpc` - RPC client
+/// * `payer` - Liquidity provider, also pays the fees
+/// * `pool_address` - PumpSwap pool address
+/// * `base_amount` - Base tokens to deposit
+/// * `slippage_basis_points` - Slippage tolerance in basis points (100 = 1%)
+///
+/// # Returns
+/// Returns the signature of the confirmed deposit transaction
+pub async fn deposit(
+    rpc: &SolanaRpcClient,
+    payer: &dyn TradeSigner,
+    pool_address: &Pubkey,
+    base_amount: u64,
+    slippage_basis_points: Option<u64>,
+) -> Result<Signature, anyhow::Error> {
+    let pool = fetch_pool(rpc, pool_address).await?;
+    let (base_reserve, quote_reserve) = get_token_balances(&pool, rpc).await?;
+    let result = deposit_base_input(
+        base_amount,
+        slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+        base_reserve,
+        quote_reserve,
+        pool.lp_supply,
+    )
+    .map_err(|e| anyhow!(e))?;
+    if result.lp_token == 0 {
+        return Err(anyhow!("Deposit amount too small to mint LP tokens"));
+    }
+
+    let mint_info_cache = MintInfoCache::get_instance();
+    let base_token_program =
+        mint_info_cache.get_or_fetch(rpc, &pool.base_mint).await?.token_program;
+    let quote_token_program =
+        mint_info_cache.get_or_fetch(rpc, &pool.quote_mint).await?.token_program;
+    let user = payer.pubkey();
+
+    let mut instructions = vec![
+        create_associated_token_account_idempotent(
+            &user,
+            &user,
+            &pool.base_mint,
+            &base_token_program,
+        ),
+        create_associated_token_account_idempotent(
+            &user,
+            &user,
+            &pool.quote_mint,
+            &quote_token_program,
+        ),
+        create_associated_token_account_idempotent(
+            &user,
+            &user,
+            &pool.lp_mint,
+            &spl_token_2022::ID,
+        ),
+    ];
+    // Wrap SOL for the WSOL side of the pool
+    if pool.base_mint == accounts::WSOL_TOKEN_ACCOUNT {
+        wrap_sol(&mut instructions, &user, result.max_base)?;
+    } else if pool.quote_mint == accounts::WSOL_TOKEN_ACCOUNT {
+        wrap_sol(&mut instructions, &user, result.max_quote)?;
+    }
+    let liquidity = LiquidityAccounts {
+        pool_address,
+        pool: &pool,
+        user: &user,
+        base_token_program: &base_token_program,
+        quote_token_program: &quote_token_program,
+    };
+    instructions.push(pumpswap::deposit(
+        &liquidity,
+        result.lp_token,
+        result.max_base,
+        result.max_quote,
+    ));
+    if pool.base_mint == accounts::WSOL_TOKEN_ACCOUNT
+        || pool.quote_mint == accounts::WSOL_TOKEN_ACCOUNT
+    {
+        unwrap_sol(&mut instructions, &user)?;
+    }
+
+    send_and_confirm(rpc, payer, &instructions).await
+}
+
+/// Withdraw liquidity from a PumpSwap pool by burning LP tokens
+///
+/// WSOL received is unwrapped back to SOL.
+///
+/// # Arguments
+/// * `rpc` - RPC client
+/// * `payer` - Liquidity provider, also pays the fees
+/// * `pool_address` - PumpSwap pool address
+/// * `lp_token_amount` - LP tokens to burn
+/// * `slippage_basis_points` - Slippage tolerance in basis points (100 = 1%)
+///
+/// # Returns
+/// Returns the signature of the confirmed withdraw transaction
+pub async fn withdraw(
+    rpc: &SolanaRpcClient,
+    payer: &dyn TradeSigner,
+    pool_address: &Pubkey,
+    lp_token_amount: u64,
+    slippage_basis_points: Option<u64>,
+) -> Result<Signature, anyhow::Error> {
+    let pool = fetch_pool(rpc, pool_address).await?;
+    let (base_reserve, quote_reserve) = get_token_balances(&pool, rpc).await?;
+    let result = withdraw_lp_input(
+        lp_token_amount,
+        slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+        base_reserve,
+        quote_reserve,
+        pool.lp_supply,
+    )
+    .map_err(|e| anyhow!(e))?;
+
+    let mint_info_cache = MintInfoCache::get_instance();
+    let base_token_program =
+        mint_info_cache.get_or_fetch(rpc, &pool.base_mint).await?.token_program;
+    let quote_token_program =
+        mint_info_cache.get_or_fetch(rpc, &pool.quote_mint).await?.token_program;
+    let user = payer.pubkey();
+
+    let liquidity = LiquidityAccounts {
+        pool_address,
+        pool: &pool,
+        user: &user,
+        base_token_program: &base_token_program,
+        quote_token_program: &quote_token_program,
+    };
+    let mut instructions = vec![
+        create_associated_token_account_idempotent(
+            &user,
+            &user,
+            &pool.base_mint,
+            &base_token_program,
+        ),
+        create_associated_token_account_idempotent(
+            &user,
+            &user,
+            &pool.quote_mint,
+            &quote_token_program,
+        ),
+        pumpswap::withdraw(&liquidity, lp_token_amount, result.min_base, result.min_quote),
+    ];
+    if pool.base_mint == accounts::WSOL_TOKEN_ACCOUNT
+        || pool.quote_mint == accounts::WSOL_TOKEN_ACCOUNT
+    {
+        unwrap_sol(&mut instructions, &user)?;
+    }
+
+    send_and_confirm(rpc, payer, &instructions).await
+}
+
+fn wrap_sol(
+    instructions: &mut Vec<Instruction>,
+    user: &Pubkey,
+    amount: u64,
+) -> Result<(), anyhow::Error> {
+    let wsol_account = get_associated_token_address_with_program_id(
+        user,
+        &accounts::WSOL_TOKEN_ACCOUNT,
+        &accounts::TOKEN_PROGRAM,
+    );
+    instructions.push(transfer(user, &wsol_account, amount));
+    instructions
+        .push(spl_token::instruction::sync_native(&accounts::TOKEN_PROGRAM, &wsol_account)?);
+    Ok(())
+}
+
+fn unwrap_sol(instructions: &mut Vec<Instruction>, user: &Pubkey) -> Result<(), anyhow::Error> {
+    let wsol_account = get_associated_token_address_with_program_id(
+        user,
+        &accounts::WSOL_TOKEN_ACCOUNT,
+        &accounts::TOKEN_PROGRAM,
+    );
+    instructions.push(spl_token::instruction::close_account(
+        &accounts::TOKEN_PROGRAM,
+        &wsol_account,
+        user,
+        user,
+        &[user],
+    )?);
+    Ok(())
+}
+
+async fn send_and_confirm(
+    rpc: &SolanaRpcClient,
+    payer: &dyn TradeSigner,
+    instructions: &[Instruction],
+) -> Result<Signature, anyhow::Error> {
+    let recent_blockhash = rpc.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    let signature = rpc.send_and_confirm_transaction(&transaction).await?;
+    Ok(signature)
+}
@@ -1 +1,6 @@
 pub mod common;
+pub mod creator_fee;
+pub mod fee_config;
+pub mod liquidity;
+pub mod pool_cache;
+pub mod reserves_cache;
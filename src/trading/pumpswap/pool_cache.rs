@@ -0,0 +1,236 @@
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+use solana_streamer_sdk::streaming::event_parser::protocols::pumpswap::PumpSwapCreatePoolEvent;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::constants::pumpswap::accounts;
+
+/// 持久化时合并写盘的间隔
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// PoolCache 单例，缓存 mint -> PumpSwap 池子地址的映射
+///
+/// 设置持久化文件后，映射的变更会在后台合并写回磁盘（最多延迟 `SAVE_DELAY`），
+/// 不阻塞交易路径；重启后可直接加载，只有缓存未命中时才会回退到 getProgramAccounts 扫描。
+pub struct PoolCache {
+    pools: Mutex<HashMap<Pubkey, Pubkey>>,
+    persist_path: Mutex<Option<PathBuf>>,
+    save_scheduled: AtomicBool,
+}
+
+static POOL_CACHE: OnceLock<Arc<PoolCache>> = OnceLock::new();
+
+impl PoolCache {
+    /// 获取 PoolCache 单例实例
+    pub fn get_instance() -> Arc<PoolCache> {
+        POOL_CACHE
+            .get_or_init(|| {
+                Arc::new(PoolCache {
+                    pools: Mutex::new(HashMap::new()),
+                    persist_path: Mutex::new(None),
+                    save_scheduled: AtomicBool::new(false),
+                })
+            })
+            .clone()
+    }
+
+    /// 启用磁盘持久化，文件存在时加载其中的映射
+    pub fn enable_persistence(&self, path: impl AsRef<Path>) -> Result<usize, anyhow::Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut loaded = 0;
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let entries: HashMap<String, String> = serde_json::from_str(&content)?;
+            // 全部解析成功后再写入，损坏的文件不会只加载一部分
+            let entries = entries
+                .iter()
+                .map(|(mint, pool)| {
+                    let mint = Pubkey::from_str(mint).map_err(|e| anyhow!(e))?;
+                    let pool = Pubkey::from_str(pool).map_err(|e| anyhow!(e))?;
+                    Ok((mint, pool))
+                })
+                .collect::<Result<Vec<_>, anyhow::Error>>()?;
+            loaded = entries.len();
+            self.pools.lock().unwrap().extend(entries);
+        }
+        *self.persist_path.lock().unwrap() = Some(path);
+        Ok(loaded)
+    }
+
+    /// 将当前映射写入持久化文件
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        let path = match self.persist_path.lock().unwrap().clone() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let entries: HashMap<String, String> = self
+            .pools
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(mint, pool)| (mint.to_string(), pool.to_string()))
+            .collect();
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_string(&entries)?)?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    /// 安排一次后台写盘，期间的其他变更合并到同一次写入
+    ///
+    /// 不在 tokio 运行时中时直接同步写入。
+    fn schedule_save(&self) {
+        if self.persist_path.lock().unwrap().is_none() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            if let Err(e) = self.save() {
+                tracing::warn!(error = %e, "Failed to persist pumpswap pool cache");
+            }
+            return;
+        };
+        if self.save_scheduled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        runtime.spawn(async {
+            tokio::time::sleep(SAVE_DELAY).await;
+            let cache = PoolCache::get_instance();
+            // 写盘期间的变更再安排一次
+            cache.save_scheduled.store(false, Ordering::Release);
+            let result = tokio::task::spawn_blocking(move || cache.save()).await;
+            if let Err(e) = result.map_err(anyhow::Error::from).and_then(|result| result) {
+                tracing::warn!(error = %e, "Failed to persist pumpswap pool cache");
+            }
+        });
+    }
+
+    /// 获取 mint 对应的池子地址
+    pub fn get(&self, mint: &Pubkey) -> Option<Pubkey> {
+        self.pools.lock().unwrap().get(mint).copied()
+    }
+
+    /// 添加映射，启用持久化时在后台写回磁盘
+    pub fn insert(&self, mint: Pubkey, pool: Pubkey) {
+        let previous = self.pools.lock().unwrap().insert(mint, pool);
+        if previous != Some(pool) {
+            self.schedule_save();
+        }
+    }
+
+    /// 根据 CreatePool 事件填充缓存
+    pub fn update_from_create_pool(&self, event: &PumpSwapCreatePoolEvent) {
+        if event.base_mint == accounts::WSOL_TOKEN_ACCOUNT {
+            self.insert(event.quote_mint, event.pool);
+        } else {
+            self.insert(event.base_mint, event.pool);
+        }
+    }
+
+    /// 移除 mint 的映射
+    pub fn remove(&self, mint: &Pubkey) -> bool {
+        let removed = self.pools.lock().unwrap().remove(mint).is_some();
+        if removed {
+            self.schedule_save();
+        }
+        removed
+    }
+
+    /// 缓存条目数量
+    pub fn len(&self) -> usize {
+        self.pools.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pools.lock().unwrap().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> PoolCache {
+        PoolCache {
+            pools: Mutex::new(HashMap::new()),
+            persist_path: Mutex::new(None),
+            save_scheduled: AtomicBool::new(false),
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("pool-cache-{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_persistence_round_trip() {
+        let path = temp_path("round-trip");
+        let _ = std::fs::remove_file(&path);
+        let (mint, pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (removed_mint, removed_pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let writer = cache();
+        assert_eq!(writer.enable_persistence(&path).unwrap(), 0);
+        // 不在 tokio 运行时中，变更同步写盘
+        writer.insert(mint, pool);
+        writer.insert(removed_mint, removed_pool);
+        assert!(writer.remove(&removed_mint));
+        assert!(path.exists());
+        assert!(!path.with_extension("tmp").exists());
+
+        let reader = cache();
+        assert_eq!(reader.enable_persistence(&path).unwrap(), 1);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reader.get(&mint), Some(pool));
+        assert_eq!(reader.get(&removed_mint), None);
+    }
+
+    #[test]
+    fn test_corrupt_file_is_rejected() {
+        let path = temp_path("corrupt");
+        let reader = cache();
+
+        std::fs::write(&path, "{\"not json").unwrap();
+        assert!(reader.enable_persistence(&path).is_err());
+
+        let entries: HashMap<String, String> = [
+            (Pubkey::new_unique().to_string(), Pubkey::new_unique().to_string()),
+            (Pubkey::new_unique().to_string(), "not a pubkey".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        std::fs::write(&path, serde_json::to_string(&entries).unwrap()).unwrap();
+        assert!(reader.enable_persistence(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(reader.is_empty());
+        assert!(reader.persist_path.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_save_is_debounced() {
+        let path = temp_path("debounced");
+        let _ = std::fs::remove_file(&path);
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        // 后台写盘使用单例
+        let shared = PoolCache::get_instance();
+        shared.enable_persistence(&path).unwrap();
+        shared.insert(first, Pubkey::new_unique());
+        shared.insert(second, Pubkey::new_unique());
+        assert!(!path.exists());
+
+        tokio::time::sleep(SAVE_DELAY + Duration::from_millis(500)).await;
+        *shared.persist_path.lock().unwrap() = None;
+        let reader = cache();
+        reader.enable_persistence(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reader.get(&first), shared.get(&first));
+        assert_eq!(reader.get(&second), shared.get(&second));
+        assert!(reader.get(&first).is_some());
+    }
+}
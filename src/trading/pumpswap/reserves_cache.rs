@@ -0,0 +1,260 @@
+use solana_sdk::pubkey::Pubkey;
+use solana_streamer_sdk::streaming::event_parser::protocols::pumpswap::{
+    types::Pool, PumpSwapBuyEvent, PumpSwapDepositEvent, PumpSwapSellEvent, PumpSwapWithdrawEvent,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::common::SolanaRpcClient;
+use crate::trading::pumpswap::common::{fetch_pool, get_token_balances};
+
+/// 默认储备缓存有效期
+pub const DEFAULT_RESERVES_CACHE_TTL: Duration = Duration::from_secs(5);
+
+struct CachedReserves {
+    base_reserve: u64,
+    quote_reserve: u64,
+    updated_at: Instant,
+}
+
+/// PoolReservesCache 单例，按池子地址缓存 PumpSwap 池子账户和储备量
+///
+/// 储备量可通过 Buy/Sell/Deposit/Withdraw 事件流持续刷新，
+/// 报价时直接使用缓存，避免买入路径上的 fetch_pool 和余额查询两次 RPC 请求。
+pub struct PoolReservesCache {
+    pools: Mutex<HashMap<Pubkey, Pool>>,
+    reserves: Mutex<HashMap<Pubkey, CachedReserves>>,
+    ttl: Mutex<Duration>,
+}
+
+static POOL_RESERVES_CACHE: OnceLock<Arc<PoolReservesCache>> = OnceLock::new();
+
+impl PoolReservesCache {
+    /// 获取 PoolReservesCache 单例实例
+    pub fn get_instance() -> Arc<PoolReservesCache> {
+        POOL_RESERVES_CACHE
+            .get_or_init(|| {
+                Arc::new(PoolReservesCache {
+                    pools: Mutex::new(HashMap::new()),
+                    reserves: Mutex::new(HashMap::new()),
+                    ttl: Mutex::new(DEFAULT_RESERVES_CACHE_TTL),
+                })
+            })
+            .clone()
+    }
+
+    /// 设置储备缓存有效期
+    pub fn set_ttl(&self, ttl: Duration) {
+        *self.ttl.lock().unwrap() = ttl;
+    }
+
+    /// 获取缓存的池子账户
+    pub fn get_pool(&self, pool_address: &Pubkey) -> Option<Pool> {
+        self.pools.lock().unwrap().get(pool_address).cloned()
+    }
+
+    /// 写入池子账户
+    pub fn insert_pool(&self, pool_address: Pubkey, pool: Pool) {
+        self.pools.lock().unwrap().insert(pool_address, pool);
+    }
+
+    /// 获取未过期的储备量 (base_reserve, quote_reserve)
+    pub fn get_reserves(&self, pool_address: &Pubkey) -> Option<(u64, u64)> {
+        let ttl = *self.ttl.lock().unwrap();
+        self.reserves
+            .lock()
+            .unwrap()
+            .get(pool_address)
+            .filter(|entry| entry.updated_at.elapsed() <= ttl)
+            .map(|entry| (entry.base_reserve, entry.quote_reserve))
+    }
+
+    /// 写入储备量
+    pub fn insert_reserves(&self, pool_address: Pubkey, base_reserve: u64, quote_reserve: u64) {
+        self.reserves.lock().unwrap().insert(
+            pool_address,
+            CachedReserves { base_reserve, quote_reserve, updated_at: Instant::now() },
+        );
+    }
+
+    /// 获取池子账户和储备量，缓存未命中的部分从 RPC 加载
+    pub async fn get_or_fetch(
+        &self,
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<(Pool, u64, u64), anyhow::Error> {
+        let pool = match self.get_pool(pool_address) {
+            Some(pool) => pool,
+            None => {
+                let pool = fetch_pool(rpc, pool_address).await?;
+                self.insert_pool(*pool_address, pool.clone());
+                pool
+            }
+        };
+        if let Some((base_reserve, quote_reserve)) = self.get_reserves(pool_address) {
+            return Ok((pool, base_reserve, quote_reserve));
+        }
+        let (base_reserve, quote_reserve) = get_token_balances(&pool, rpc).await?;
+        self.insert_reserves(*pool_address, base_reserve, quote_reserve);
+        Ok((pool, base_reserve, quote_reserve))
+    }
+
+    /// 使用买入事件刷新储备量（事件中的储备为交易前数值）
+    pub fn update_from_buy(&self, event: &PumpSwapBuyEvent) {
+        self.insert_reserves(
+            event.pool,
+            event.pool_base_token_reserves.saturating_sub(event.base_amount_out),
+            event.pool_quote_token_reserves.saturating_add(event.quote_amount_in_with_lp_fee),
+        );
+    }
+
+    /// 使用卖出事件刷新储备量（事件中的储备为交易前数值）
+    pub fn update_from_sell(&self, event: &PumpSwapSellEvent) {
+        self.insert_reserves(
+            event.pool,
+            event.pool_base_token_reserves.saturating_add(event.base_amount_in),
+            event.pool_quote_token_reserves.saturating_sub(event.quote_amount_out_without_lp_fee),
+        );
+    }
+
+    /// 使用添加流动性事件刷新储备量
+    pub fn update_from_deposit(&self, event: &PumpSwapDepositEvent) {
+        self.insert_reserves(
+            event.pool,
+            event.pool_base_token_reserves.saturating_add(event.base_amount_in),
+            event.pool_quote_token_reserves.saturating_add(event.quote_amount_in),
+        );
+    }
+
+    /// 使用移除流动性事件刷新储备量
+    pub fn update_from_withdraw(&self, event: &PumpSwapWithdrawEvent) {
+        self.insert_reserves(
+            event.pool,
+            event.pool_base_token_reserves.saturating_sub(event.base_amount_out),
+            event.pool_quote_token_reserves.saturating_sub(event.quote_amount_out),
+        );
+    }
+
+    /// 移除指定池子的储备缓存
+    pub fn invalidate(&self, pool_address: &Pubkey) -> bool {
+        self.reserves.lock().unwrap().remove(pool_address).is_some()
+    }
+
+    /// 清理所有过期的储备条目
+    pub fn purge_expired(&self) {
+        let ttl = *self.ttl.lock().unwrap();
+        self.reserves.lock().unwrap().retain(|_, entry| entry.updated_at.elapsed() <= ttl);
+    }
+
+    /// 清空缓存
+    pub fn clear(&self) {
+        self.pools.lock().unwrap().clear();
+        self.reserves.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> PoolReservesCache {
+        PoolReservesCache {
+            pools: Mutex::new(HashMap::new()),
+            reserves: Mutex::new(HashMap::new()),
+            ttl: Mutex::new(DEFAULT_RESERVES_CACHE_TTL),
+        }
+    }
+
+    #[test]
+    fn test_update_from_events() {
+        let cache = cache();
+        let pool = Pubkey::new_unique();
+
+        cache.update_from_buy(&PumpSwapBuyEvent {
+            pool,
+            pool_base_token_reserves: 1_000_000,
+            pool_quote_token_reserves: 500_000,
+            base_amount_out: 10_000,
+            quote_amount_in_with_lp_fee: 5_100,
+            ..Default::default()
+        });
+        assert_eq!(cache.get_reserves(&pool), Some((990_000, 505_100)));
+
+        cache.update_from_sell(&PumpSwapSellEvent {
+            pool,
+            pool_base_token_reserves: 990_000,
+            pool_quote_token_reserves: 505_100,
+            base_amount_in: 10_000,
+            quote_amount_out_without_lp_fee: 5_000,
+            ..Default::default()
+        });
+        assert_eq!(cache.get_reserves(&pool), Some((1_000_000, 500_100)));
+
+        cache.update_from_deposit(&PumpSwapDepositEvent {
+            pool,
+            pool_base_token_reserves: 1_000_000,
+            pool_quote_token_reserves: 500_100,
+            base_amount_in: 200_000,
+            quote_amount_in: 100_020,
+            ..Default::default()
+        });
+        assert_eq!(cache.get_reserves(&pool), Some((1_200_000, 600_120)));
+
+        cache.update_from_withdraw(&PumpSwapWithdrawEvent {
+            pool,
+            pool_base_token_reserves: 1_200_000,
+            pool_quote_token_reserves: 600_120,
+            base_amount_out: 200_000,
+            quote_amount_out: 100_020,
+            ..Default::default()
+        });
+        assert_eq!(cache.get_reserves(&pool), Some((1_000_000, 500_100)));
+    }
+
+    #[test]
+    fn test_update_from_events_saturates() {
+        let cache = cache();
+        let pool = Pubkey::new_unique();
+
+        cache.update_from_buy(&PumpSwapBuyEvent {
+            pool,
+            pool_base_token_reserves: 100,
+            pool_quote_token_reserves: u64::MAX - 1,
+            base_amount_out: 200,
+            quote_amount_in_with_lp_fee: 10,
+            ..Default::default()
+        });
+        assert_eq!(cache.get_reserves(&pool), Some((0, u64::MAX)));
+
+        cache.update_from_sell(&PumpSwapSellEvent {
+            pool,
+            pool_base_token_reserves: u64::MAX,
+            pool_quote_token_reserves: 100,
+            base_amount_in: 1,
+            quote_amount_out_without_lp_fee: 200,
+            ..Default::default()
+        });
+        assert_eq!(cache.get_reserves(&pool), Some((u64::MAX, 0)));
+
+        cache.update_from_deposit(&PumpSwapDepositEvent {
+            pool,
+            pool_base_token_reserves: u64::MAX,
+            pool_quote_token_reserves: u64::MAX,
+            base_amount_in: 1,
+            quote_amount_in: 1,
+            ..Default::default()
+        });
+        assert_eq!(cache.get_reserves(&pool), Some((u64::MAX, u64::MAX)));
+
+        cache.update_from_withdraw(&PumpSwapWithdrawEvent {
+            pool,
+            pool_base_token_reserves: 5,
+            pool_quote_token_reserves: 5,
+            base_amount_out: 6,
+            quote_amount_out: 6,
+            ..Default::default()
+        });
+        assert_eq!(cache.get_reserves(&pool), Some((0, 0)));
+    }
+}
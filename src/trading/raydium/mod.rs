@@ -0,0 +1,3 @@
+pub mod pool_finder;
+
+pub use pool_finder::{find_pools, RaydiumPool};
@@ -0,0 +1,202 @@
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+use solana_streamer_sdk::streaming::event_parser::protocols::{
+    raydium_amm_v4::types::{amm_info_decode, AmmInfo, AMM_INFO_SIZE},
+    raydium_clmm::{
+        parser::RAYDIUM_CLMM_PROGRAM_ID,
+        types::{pool_state_decode as clmm_pool_state_decode, PoolState as ClmmPoolState},
+    },
+    raydium_cpmm::types::{
+        pool_state_decode as cpmm_pool_state_decode, PoolState as CpmmPoolState,
+    },
+};
+
+use crate::common::SolanaRpcClient;
+use crate::constants::{raydium_amm_v4, raydium_cpmm};
+
+/// Account sizes used as `dataSize` filters (Anchor programs include the 8-byte discriminator)
+const CPMM_POOL_STATE_ACCOUNT_SIZE: u64 = 637;
+const CLMM_POOL_STATE_ACCOUNT_SIZE: u64 = 1544;
+
+/// Mint field offsets in the raw account data
+const AMM_V4_COIN_MINT_OFFSET: usize = 400;
+const AMM_V4_PC_MINT_OFFSET: usize = 432;
+const CPMM_TOKEN0_MINT_OFFSET: usize = 168;
+const CPMM_TOKEN1_MINT_OFFSET: usize = 200;
+const CLMM_TOKEN_MINT0_OFFSET: usize = 73;
+const CLMM_TOKEN_MINT1_OFFSET: usize = 105;
+
+const AMM_V4: Pubkey = raydium_amm_v4::accounts::RAYDIUM_AMM_V4;
+const CPMM: Pubkey = raydium_cpmm::accounts::RAYDIUM_CPMM;
+
+/// `getProgramAccounts` queries of `find_pools`: program, account size and mint offset
+const POOL_QUERIES: [(Pubkey, u64, usize); 6] = [
+    (AMM_V4, AMM_INFO_SIZE as u64, AMM_V4_COIN_MINT_OFFSET),
+    (AMM_V4, AMM_INFO_SIZE as u64, AMM_V4_PC_MINT_OFFSET),
+    (CPMM, CPMM_POOL_STATE_ACCOUNT_SIZE, CPMM_TOKEN0_MINT_OFFSET),
+    (CPMM, CPMM_POOL_STATE_ACCOUNT_SIZE, CPMM_TOKEN1_MINT_OFFSET),
+    (RAYDIUM_CLMM_PROGRAM_ID, CLMM_POOL_STATE_ACCOUNT_SIZE, CLMM_TOKEN_MINT0_OFFSET),
+    (RAYDIUM_CLMM_PROGRAM_ID, CLMM_POOL_STATE_ACCOUNT_SIZE, CLMM_TOKEN_MINT1_OFFSET),
+];
+
+/// A decoded Raydium pool containing a given mint
+#[derive(Debug, Clone)]
+pub enum RaydiumPool {
+    /// Raydium AMM V4 pool, use with `RaydiumAmmV4Params::from_amm_info_and_reserves`
+    AmmV4 { address: Pubkey, amm_info: AmmInfo },
+    /// Raydium CPMM pool, use with `RaydiumCpmmParams::from_pool_state`
+    Cpmm { address: Pubkey, pool_state: CpmmPoolState },
+    /// Raydium CLMM pool (decoded only, trading is not supported yet)
+    Clmm { address: Pubkey, pool_state: Box<ClmmPoolState> },
+}
+
+impl RaydiumPool {
+    /// Pool account address
+    pub fn address(&self) -> Pubkey {
+        match self {
+            RaydiumPool::AmmV4 { address, .. }
+            | RaydiumPool::Cpmm { address, .. }
+            | RaydiumPool::Clmm { address, .. } => *address,
+        }
+    }
+
+    /// The two mints of the pool, in pool order
+    pub fn mints(&self) -> (Pubkey, Pubkey) {
+        match self {
+            RaydiumPool::AmmV4 { amm_info, .. } => (amm_info.coin_mint, amm_info.pc_mint),
+            RaydiumPool::Cpmm { pool_state, .. } => {
+                (pool_state.token0_mint, pool_state.token1_mint)
+            }
+            RaydiumPool::Clmm { pool_state, .. } => {
+                (pool_state.token_mint0, pool_state.token_mint1)
+            }
+        }
+    }
+}
+
+/// Find all Raydium AMM V4, CPMM and CLMM pools that contain `mint`
+///
+/// Uses `getProgramAccounts` with `dataSize` + `memcmp` filters on both mint fields of
+/// each pool layout. All queries run concurrently; a program whose query fails is skipped,
+/// and an error is returned only if every query fails.
+pub async fn find_pools(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<Vec<RaydiumPool>, anyhow::Error> {
+    let results =
+        futures::future::join_all(POOL_QUERIES.iter().map(|(program_id, data_size, offset)| {
+            get_pool_accounts(rpc, program_id, *data_size, *offset, mint)
+        }))
+        .await;
+
+    let mut pools = Vec::new();
+    let mut last_error = None;
+    let mut succeeded = false;
+    for ((program_id, _, _), result) in POOL_QUERIES.iter().zip(results) {
+        let accounts = match result {
+            Ok(accounts) => {
+                succeeded = true;
+                accounts
+            }
+            Err(e) => {
+                last_error = Some(e);
+                continue;
+            }
+        };
+        pools.extend(
+            accounts
+                .into_iter()
+                .filter_map(|(address, data)| decode_pool(program_id, address, &data)),
+        );
+    }
+    match last_error {
+        Some(e) if !succeeded => Err(e),
+        _ => Ok(pools),
+    }
+}
+
+/// Decode a pool account returned by a query on `program_id`
+fn decode_pool(program_id: &Pubkey, address: Pubkey, data: &[u8]) -> Option<RaydiumPool> {
+    if *program_id == AMM_V4 {
+        amm_info_decode(data).map(|amm_info| RaydiumPool::AmmV4 { address, amm_info })
+    } else if *program_id == CPMM {
+        data.get(8..)
+            .and_then(cpmm_pool_state_decode)
+            .map(|pool_state| RaydiumPool::Cpmm { address, pool_state })
+    } else {
+        data.get(8..)
+            .and_then(clmm_pool_state_decode)
+            .map(|pool_state| RaydiumPool::Clmm { address, pool_state: Box::new(pool_state) })
+    }
+}
+
+/// Filters selecting accounts of `data_size` bytes with `mint` at `mint_offset`
+fn pool_account_filters(data_size: u64, mint_offset: usize, mint: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(data_size),
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(mint_offset, &mint.to_bytes())),
+    ]
+}
+
+async fn get_pool_accounts(
+    rpc: &SolanaRpcClient,
+    program_id: &Pubkey,
+    data_size: u64,
+    mint_offset: usize,
+    mint: &Pubkey,
+) -> Result<Vec<(Pubkey, Vec<u8>)>, anyhow::Error> {
+    let config = solana_rpc_client_api::config::RpcProgramAccountsConfig {
+        filters: Some(pool_account_filters(data_size, mint_offset, mint)),
+        account_config: solana_rpc_client_api::config::RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: None,
+            commitment: None,
+            min_context_slot: None,
+        },
+        with_context: None,
+        sort_results: None,
+    };
+    let accounts = rpc.get_program_accounts_with_config(program_id, config).await?;
+    Ok(accounts.into_iter().map(|(address, account)| (address, account.data)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_queries() {
+        let offsets = |program_id: Pubkey| -> Vec<(u64, usize)> {
+            POOL_QUERIES
+                .iter()
+                .filter(|(id, _, _)| *id == program_id)
+                .map(|(_, data_size, offset)| (*data_size, *offset))
+                .collect()
+        };
+        assert_eq!(offsets(AMM_V4), [(752, 400), (752, 432)]);
+        assert_eq!(offsets(CPMM), [(637, 168), (637, 200)]);
+        assert_eq!(offsets(RAYDIUM_CLMM_PROGRAM_ID), [(1544, 73), (1544, 105)]);
+    }
+
+    #[test]
+    fn test_pool_filters_match_decoded_mints() {
+        let mint = Pubkey::new_unique();
+        for (i, (program_id, data_size, offset)) in POOL_QUERIES.iter().enumerate() {
+            let mut data = vec![0u8; *data_size as usize];
+            data[*offset..*offset + 32].copy_from_slice(mint.as_ref());
+            for filter in pool_account_filters(*data_size, *offset, &mint) {
+                match filter {
+                    RpcFilterType::DataSize(size) => assert_eq!(size as usize, data.len()),
+                    RpcFilterType::Memcmp(memcmp) => assert!(memcmp.bytes_match(&data)),
+                    filter => panic!("unexpected filter {:?}", filter),
+                }
+            }
+
+            // The filtered field is the mint the pool decodes: even queries the first one
+            let pool = decode_pool(program_id, Pubkey::new_unique(), &data).unwrap();
+            let mints = pool.mints();
+            assert_eq!(if i % 2 == 0 { mints.0 } else { mints.1 }, mint, "query {}", i);
+        }
+    }
+}
@@ -63,18 +63,9 @@ pub async fn get_pool_token_balances(
     token1_mint: &Pubkey,
 ) -> Result<(u64, u64), anyhow::Error> {
     let token0_vault = get_vault_pda(pool_state, token0_mint).unwrap();
-    let token0_balance = rpc.get_token_account_balance(&token0_vault).await?;
     let token1_vault = get_vault_pda(pool_state, token1_mint).unwrap();
-    let token1_balance = rpc.get_token_account_balance(&token1_vault).await?;
-
-    // 解析余额字符串为 u64
-    let token0_amount =
-        token0_balance.amount.parse::<u64>().map_err(|e| anyhow!("解析 token0 余额失败: {}", e))?;
-
-    let token1_amount =
-        token1_balance.amount.parse::<u64>().map_err(|e| anyhow!("解析 token1 余额失败: {}", e))?;
-
-    Ok((token0_amount, token1_amount))
+    // 一次 getMultipleAccounts 请求获取两个金库余额
+    crate::trading::common::get_multi_token_balances(rpc, &token0_vault, &token1_vault).await
 }
 
 /// 计算代币价格 (token1/token0)
@@ -0,0 +1,264 @@
+//! Token safety checks before entry
+//!
+//! `SolanaTrade::safety_report` reads the mint authorities of a token. A token that can be
+//! bought but not sold only shows itself when selling, so
+//! `SolanaTrade::safety_report_with_sell_check` also simulates a small sell from the
+//! account of a large holder: the holder's wallet is the token owner of a transaction that
+//! is never signed by it, the payer pays its fee, and `simulateTransaction` runs it without
+//! signature verification against the holder's real accounts.
+
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    account::Account, commitment_config::CommitmentConfig, hash::Hash, program_pack::Pack,
+    pubkey::Pubkey, signer::null_signer::NullSigner,
+};
+use spl_token::state::Account as TokenAccount;
+use std::sync::Arc;
+
+use crate::{
+    common::{mint_info_cache::MintInfoCache, SolanaRpcClient, TradeResult},
+    trading::{
+        common::{batch_fetcher::get_multiple_accounts, TransactionBuilder},
+        core::traits::ProtocolParams,
+        factory::DexType,
+        SellParams, TradeFactory,
+    },
+    SolanaTrade,
+};
+
+/// Share of the holder's balance the simulated sell sells, in basis points
+const SIMULATED_SELL_BPS: u64 = 100;
+
+/// What is known about a token before buying it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SafetyReport {
+    pub mint: Pubkey,
+    /// Can mint more supply
+    pub mint_authority: Option<Pubkey>,
+    /// Can freeze token accounts, holders then can't sell
+    pub freeze_authority: Option<Pubkey>,
+    /// Simulated sell from a holder, `None` when not requested
+    pub sell_simulation: Option<SellSimulation>,
+}
+
+impl SafetyReport {
+    /// No freeze authority and, when simulated, a sell went through
+    pub fn is_safe(&self) -> bool {
+        self.freeze_authority.is_none()
+            && self.sell_simulation.as_ref().is_none_or(SellSimulation::can_sell)
+    }
+}
+
+/// Outcome of a sell simulated from a holder's account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SellSimulation {
+    /// Wallet the sell was simulated from, `None` when no holder could sell
+    pub holder: Option<Pubkey>,
+    pub token_amount: u64,
+    /// Error of the simulated sell, or why it couldn't be simulated
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+impl SellSimulation {
+    pub fn can_sell(&self) -> bool {
+        self.holder.is_some() && self.err.is_none()
+    }
+}
+
+impl SolanaTrade {
+    /// Mint and freeze authority of `mint`
+    pub async fn safety_report(&self, mint: &Pubkey) -> TradeResult<SafetyReport> {
+        let mint_info = MintInfoCache::get_instance().get_or_fetch(&self.rpc, mint).await?;
+        Ok(SafetyReport {
+            mint: *mint,
+            mint_authority: mint_info.mint_authority,
+            freeze_authority: mint_info.freeze_authority,
+            sell_simulation: None,
+        })
+    }
+
+    /// `safety_report` plus a simulated sell of 1% of the largest holder's balance
+    ///
+    /// The holder is the largest one owned by a wallet, pools and bonding curves can't
+    /// sign. The sell is built like `sell` would build it from `extension_params` and
+    /// `slippage_basis_points`, so a sell tax beyond the slippage fails the simulation too.
+    /// Nothing is sent.
+    pub async fn safety_report_with_sell_check(
+        &self,
+        dex_type: DexType,
+        mint: &Pubkey,
+        extension_params: Box<dyn ProtocolParams>,
+        slippage_basis_points: Option<u64>,
+    ) -> TradeResult<SafetyReport> {
+        let mut report = self.safety_report(mint).await?;
+        let Some((holder, balance)) = find_wallet_holder(&self.rpc, mint).await? else {
+            report.sell_simulation = Some(SellSimulation {
+                holder: None,
+                token_amount: 0,
+                err: Some("No wallet holds the token".to_string()),
+                logs: vec![],
+                units_consumed: None,
+            });
+            return Ok(report);
+        };
+        let token_amount = ((balance as u128 * SIMULATED_SELL_BPS as u128 / 10_000) as u64).max(1);
+
+        let owner: Arc<NullSigner> = Arc::new(NullSigner::new(&holder));
+        let fee_payer = self.fee_payer.clone().unwrap_or_else(|| self.payer.clone());
+        let sell_params = SellParams {
+            rpc: Some(self.rpc.clone()),
+            payer: owner.clone(),
+            fee_payer: Some(fee_payer.clone()),
+            mint: *mint,
+            token_amount: Some(token_amount),
+            slippage_basis_points,
+            priority_fee: self.trade_config.priority_fee.clone(),
+            lookup_table_key: self.trade_config.lookup_table_key,
+            recent_blockhash: Hash::default(),
+            wait_transaction_confirmed: false,
+            protocol_params: extension_params,
+            lifecycle: None,
+        };
+        let instructions = TradeFactory::create_instruction_builder(dex_type)
+            .build_sell_instructions(&sell_params)
+            .await?;
+        let transaction = TransactionBuilder::new(owner, self.trade_config.priority_fee.clone())
+            .with_fee_payer(Some(fee_payer))
+            .with_instructions(instructions)
+            .with_lookup_table_key(self.trade_config.lookup_table_key)
+            .build(Hash::default())
+            .await?;
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(CommitmentConfig::processed()),
+            ..Default::default()
+        };
+        let result = self
+            .rpc
+            .simulate_transaction_with_config(&transaction, config)
+            .await
+            .map_err(anyhow::Error::from)?
+            .value;
+        if let Some(err) = &result.err {
+            tracing::warn!(%mint, %holder, error = %err, "simulated sell failed");
+        }
+        report.sell_simulation = Some(SellSimulation {
+            holder: Some(holder),
+            token_amount,
+            err: result.err.map(|e| e.to_string()),
+            logs: result.logs.unwrap_or_default(),
+            units_consumed: result.units_consumed,
+        });
+        Ok(report)
+    }
+}
+
+/// Largest holder of `mint` that is a wallet, with its balance
+///
+/// Token accounts owned by program addresses (bonding curves, pool vaults) are skipped.
+pub async fn find_wallet_holder(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<Option<(Pubkey, u64)>, anyhow::Error> {
+    let largest = rpc.get_token_largest_accounts(mint).await?;
+    let addresses = largest
+        .iter()
+        .map(|balance| balance.address.parse::<Pubkey>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let accounts = get_multiple_accounts(rpc, &addresses).await?;
+    Ok(accounts.iter().flatten().find_map(wallet_balance))
+}
+
+/// Owner and balance of a token account owned by a wallet
+fn wallet_balance(account: &Account) -> Option<(Pubkey, u64)> {
+    if account.owner != spl_token::ID && account.owner != spl_token_2022::ID {
+        return None;
+    }
+    let data = account.data.get(..TokenAccount::LEN)?;
+    let token_account = TokenAccount::unpack_from_slice(data).ok()?;
+    if token_account.amount == 0 || !token_account.owner.is_on_curve() || token_account.is_frozen()
+    {
+        return None;
+    }
+    Some((token_account.owner, token_account.amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockRpc;
+    use serde_json::json;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+    use spl_token::state::AccountState;
+
+    fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+        let mut data = vec![0; TokenAccount::LEN];
+        TokenAccount {
+            mint,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        Account {
+            lamports: 2_039_280,
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_wallet_holder() {
+        let mint = Pubkey::new_unique();
+        let (curve, _) = Pubkey::find_program_address(&[b"bonding-curve"], &Pubkey::new_unique());
+        let wallet = Keypair::new().pubkey();
+        let (curve_vault, wallet_ata) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let balances: Vec<_> = [curve_vault, wallet_ata]
+            .iter()
+            .map(|address| {
+                json!({
+                    "address": address.to_string(),
+                    "amount": "0",
+                    "decimals": 6,
+                    "uiAmount": 0.0,
+                    "uiAmountString": "0",
+                })
+            })
+            .collect();
+        let largest = json!({ "context": { "slot": 1 }, "value": balances });
+        let rpc = MockRpc::new()
+            .with_response(RpcRequest::GetTokenLargestAccounts, largest)
+            .with_account(curve_vault, token_account(mint, curve, 800_000_000))
+            .with_account(wallet_ata, token_account(mint, wallet, 20_000_000))
+            .client();
+
+        let holder = find_wallet_holder(&rpc, &mint).await.unwrap();
+        assert_eq!(holder, Some((wallet, 20_000_000)));
+
+        let simulation = SellSimulation {
+            holder: holder.map(|(holder, _)| holder),
+            token_amount: 200_000,
+            err: None,
+            logs: vec![],
+            units_consumed: Some(40_000),
+        };
+        let report = SafetyReport {
+            mint,
+            mint_authority: None,
+            freeze_authority: None,
+            sell_simulation: Some(simulation.clone()),
+        };
+        assert!(report.is_safe());
+        let failed = SellSimulation { err: Some("custom program error".to_string()), ..simulation };
+        assert!(!SafetyReport { sell_simulation: Some(failed), ..report }.is_safe());
+    }
+}
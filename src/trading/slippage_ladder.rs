@@ -0,0 +1,208 @@
+//! Buy retries with increasing slippage
+//!
+//! On fast moving tokens a buy often fails because the price moved past the slippage limit
+//! between quoting and landing. `SolanaTrade::buy_with_slippage_ladder` retries such buys
+//! with the slippage raised by a fixed step up to a hard cap. Every attempt uses a fresh
+//! blockhash and protocol params re-read from RPC, so the minimum output is quoted against
+//! the current reserves. Failures other than slippage end the ladder right away.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "bonk")]
+use super::core::params::BonkParams;
+#[cfg(feature = "pumpfun")]
+use super::core::params::PumpFunParams;
+#[cfg(feature = "pumpswap")]
+use super::core::params::PumpSwapParams;
+#[cfg(feature = "raydium")]
+use super::core::params::{RaydiumAmmV4Params, RaydiumCpmmParams};
+use super::{core::traits::ProtocolParams, factory::DexType};
+#[cfg(feature = "pumpfun")]
+use crate::common::bonding_curve_cache::BondingCurveCache;
+use crate::common::{SolanaRpcClient, TradeError, TradeResult};
+#[cfg(feature = "pumpswap")]
+use crate::trading::pumpswap::reserves_cache::PoolReservesCache;
+use crate::SolanaTrade;
+
+/// Slippage steps of a buy ladder, all in basis points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlippageLadder {
+    /// Slippage of the first attempt
+    pub start: u64,
+    /// Added after every attempt that failed on slippage
+    pub step: u64,
+    /// No attempt uses more; the last attempt uses exactly this
+    pub max: u64,
+}
+
+impl SlippageLadder {
+    pub fn new(start: u64, step: u64, max: u64) -> Self {
+        Self { start, step, max }
+    }
+
+    /// Slippage of every attempt in order
+    pub fn steps(&self) -> Vec<u64> {
+        let mut steps = vec![self.start.min(self.max)];
+        while self.step > 0 && steps[steps.len() - 1] < self.max {
+            steps.push(steps[steps.len() - 1].saturating_add(self.step).min(self.max));
+        }
+        steps
+    }
+}
+
+/// One attempt of a buy ladder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LadderAttempt {
+    pub slippage_basis_points: u64,
+    /// Why the attempt failed, `None` for the attempt that landed
+    pub error: Option<String>,
+}
+
+/// Outcome of `buy_with_slippage_ladder`
+#[derive(Debug)]
+pub struct LadderBuyResult {
+    /// Every attempt in order
+    pub attempts: Vec<LadderAttempt>,
+    /// Result of the last attempt
+    pub result: TradeResult<()>,
+}
+
+impl LadderBuyResult {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    /// Slippage of the attempt that landed
+    pub fn landed_slippage(&self) -> Option<u64> {
+        self.result.as_ref().ok().and(self.attempts.last()).map(|a| a.slippage_basis_points)
+    }
+}
+
+impl SolanaTrade {
+    /// Buy, retrying slippage failures with the slippage raised along `ladder`
+    ///
+    /// Attempts wait for confirmation, which is what surfaces the slippage error. Each retry
+    /// fetches a fresh blockhash and reloads `extension_params` from RPC, bypassing the
+    /// reserve caches; params that cannot be reloaded are reused as they are.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn buy_with_slippage_ladder(
+        &self,
+        dex_type: DexType,
+        mint: Pubkey,
+        sol_amount: u64,
+        ladder: SlippageLadder,
+        custom_buy_tip_fee: Option<f64>,
+        extension_params: Box<dyn ProtocolParams>,
+        lookup_table_key: Option<Pubkey>,
+    ) -> LadderBuyResult {
+        let mut attempts = vec![];
+        let mut params = extension_params;
+        let mut result = Err(TradeError::InvalidParams("Empty slippage ladder".to_string()));
+        for (index, slippage) in ladder.steps().into_iter().enumerate() {
+            if index > 0 {
+                match requote(&self.rpc, &dex_type, &mint, params.as_ref()).await {
+                    Ok(Some(fresh)) => params = fresh,
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!(%mint, error = %e, "re-quote failed, reusing params"),
+                }
+            }
+            result = match self.rpc.get_latest_blockhash().await {
+                Ok(recent_blockhash) => {
+                    self.buy(
+                        dex_type.clone(),
+                        mint,
+                        sol_amount,
+                        Some(slippage),
+                        recent_blockhash,
+                        custom_buy_tip_fee,
+                        params.clone_box(),
+                        lookup_table_key,
+                        true,
+                    )
+                    .await
+                }
+                Err(e) => Err(e.into()),
+            };
+            attempts.push(LadderAttempt {
+                slippage_basis_points: slippage,
+                error: result.as_ref().err().map(ToString::to_string),
+            });
+            match &result {
+                Err(TradeError::SlippageExceeded(reason)) => {
+                    tracing::info!(%mint, slippage, %reason, "buy hit slippage limit");
+                }
+                _ => break,
+            }
+        }
+        LadderBuyResult { attempts, result }
+    }
+}
+
+/// Protocol params of `params`' market with reserves read from RPC, `None` when they cannot
+/// be reloaded
+async fn requote(
+    rpc: &SolanaRpcClient,
+    dex_type: &DexType,
+    mint: &Pubkey,
+    params: &dyn ProtocolParams,
+) -> Result<Option<Box<dyn ProtocolParams>>, anyhow::Error> {
+    #[cfg(not(any(feature = "pumpfun", feature = "bonk")))]
+    let _ = mint;
+    let params = params.as_any();
+    #[cfg(not(any(feature = "pumpfun", feature = "pumpswap", feature = "raydium")))]
+    let _ = params;
+    let fresh: Box<dyn ProtocolParams> = match dex_type {
+        #[cfg(feature = "pumpfun")]
+        DexType::PumpFun => {
+            let close = params
+                .downcast_ref::<PumpFunParams>()
+                .and_then(|params| params.close_token_account_when_sell);
+            BondingCurveCache::get_instance().invalidate(mint);
+            Box::new(PumpFunParams::from_mint_by_rpc(rpc, mint, close).await?)
+        }
+        #[cfg(feature = "pumpswap")]
+        DexType::PumpSwap => {
+            let Some(pool) = params.downcast_ref::<PumpSwapParams>().map(|params| params.pool)
+            else {
+                return Ok(None);
+            };
+            PoolReservesCache::get_instance().invalidate(&pool);
+            Box::new(PumpSwapParams::from_pool_address_by_rpc(rpc, &pool).await?)
+        }
+        #[cfg(feature = "bonk")]
+        DexType::Bonk => Box::new(BonkParams::from_mint_by_rpc(rpc, mint).await?),
+        #[cfg(feature = "raydium")]
+        DexType::RaydiumCpmm => {
+            let Some(pool_state) = params
+                .downcast_ref::<RaydiumCpmmParams>()
+                .map(|params| params.pool_state)
+                .filter(|pool_state| *pool_state != Pubkey::default())
+            else {
+                return Ok(None);
+            };
+            Box::new(RaydiumCpmmParams::from_pool_address_by_rpc(rpc, &pool_state).await?)
+        }
+        #[cfg(feature = "raydium")]
+        DexType::RaydiumAmmV4 => {
+            let Some(amm) = params.downcast_ref::<RaydiumAmmV4Params>().map(|params| params.amm)
+            else {
+                return Ok(None);
+            };
+            Box::new(RaydiumAmmV4Params::from_amm_address_by_rpc(rpc, amm).await?)
+        }
+    };
+    Ok(Some(fresh))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ladder_steps() {
+        assert_eq!(SlippageLadder::new(300, 500, 1_500).steps(), vec![300, 800, 1_300, 1_500]);
+        assert_eq!(SlippageLadder::new(300, 0, 1_500).steps(), vec![300]);
+        assert_eq!(SlippageLadder::new(2_000, 500, 1_500).steps(), vec![1_500]);
+    }
+}
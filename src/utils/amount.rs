@@ -0,0 +1,87 @@
+//! Conversions between raw base units and UI amounts
+//!
+//! Token amounts on chain are integers in the mint's smallest unit; a mint with 6 decimals
+//! stores 1.5 tokens as `1_500_000`. SOL works the same way with 9 decimals (lamports).
+//! `SolanaTrade::to_ui_amount` / `SolanaTrade::from_ui_amount` look the decimals up in
+//! `MintInfoCache`.
+
+/// Decimals of SOL, i.e. lamports per SOL is `10^9`
+pub const SOL_DECIMALS: u8 = 9;
+
+/// `raw` base units as a UI amount, e.g. `1_500_000` with 6 decimals is `1.5`
+pub fn to_ui_amount(raw: u64, decimals: u8) -> f64 {
+    raw as f64 / 10f64.powi(decimals as i32)
+}
+
+/// A UI amount in base units, rounded to the nearest unit
+///
+/// # Errors
+/// Fails for negative or non-finite amounts and amounts that do not fit in a `u64`
+pub fn from_ui_amount(ui_amount: f64, decimals: u8) -> Result<u64, String> {
+    if !ui_amount.is_finite() || ui_amount < 0.0 {
+        return Err(format!("Invalid amount {}", ui_amount));
+    }
+    let raw = (ui_amount * 10f64.powi(decimals as i32)).round();
+    // u64::MAX as f64 rounds up to 2^64, which itself does not fit
+    if raw >= u64::MAX as f64 {
+        return Err(format!("Amount {} with {} decimals overflows u64", ui_amount, decimals));
+    }
+    Ok(raw as u64)
+}
+
+/// Lamports as SOL
+pub fn lamports_to_sol(lamports: u64) -> f64 {
+    to_ui_amount(lamports, SOL_DECIMALS)
+}
+
+/// SOL as lamports, rounded to the nearest lamport
+///
+/// # Errors
+/// Fails for negative or non-finite amounts and amounts that do not fit in a `u64`
+pub fn sol_to_lamports(sol: f64) -> Result<u64, String> {
+    from_ui_amount(sol, SOL_DECIMALS)
+}
+
+/// Exact decimal representation of `raw` base units without trailing zeros,
+/// e.g. `1_500_000` with 6 decimals is `"1.5"`
+pub fn format_ui_amount(raw: u64, decimals: u8) -> String {
+    let Some(scale) = 10u128.checked_pow(decimals as u32) else {
+        return to_ui_amount(raw, decimals).to_string();
+    };
+    let (whole, fraction) = (raw as u128 / scale, raw as u128 % scale);
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+/// Lamports formatted as SOL, e.g. `"0.0015 SOL"`
+pub fn format_sol(lamports: u64) -> String {
+    format!("{} SOL", format_ui_amount(lamports, SOL_DECIMALS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_conversions() {
+        assert_eq!(to_ui_amount(1_500_000, 6), 1.5);
+        assert_eq!(from_ui_amount(1.5, 6), Ok(1_500_000));
+        // 0.1 + 0.2 style float noise is rounded away
+        assert_eq!(from_ui_amount(0.3, 9), Ok(300_000_000));
+        assert!(from_ui_amount(-1.0, 6).is_err());
+        assert!(from_ui_amount(f64::NAN, 6).is_err());
+        assert!(from_ui_amount(1e20, 0).is_err());
+
+        assert_eq!(lamports_to_sol(1_500_000_000), 1.5);
+        assert_eq!(sol_to_lamports(0.001), Ok(1_000_000));
+
+        assert_eq!(format_ui_amount(1_500_000, 6), "1.5");
+        assert_eq!(format_ui_amount(2_000_000, 6), "2");
+        assert_eq!(format_ui_amount(42, 0), "42");
+        assert_eq!(format_sol(1_500_000), "0.0015 SOL");
+        assert_eq!(format_sol(u64::MAX), "18446744073.709551615 SOL");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::constants::bonk::accounts;
+use crate::trading::bonk::common::{get_amount_in_net, get_amount_out};
 
 /// Calculates the amount of tokens to receive when buying with SOL
 ///
@@ -16,7 +17,8 @@ use crate::constants::bonk::accounts;
 ///
 /// # Returns
 ///
-/// The minimum amount of tokens that will be received after fees and slippage
+/// The minimum amount of tokens that will be received after fees and slippage, or an error
+/// when the reserves are inconsistent or the amounts overflow
 pub fn get_buy_token_amount_from_sol_amount(
     amount_in: u64,
     virtual_base: u128,
@@ -24,35 +26,18 @@ pub fn get_buy_token_amount_from_sol_amount(
     real_base: u128,
     real_quote: u128,
     slippage_basis_points: u128,
-) -> u64 {
-    let amount_in_u128 = amount_in as u128;
-
-    // Calculate various fees deducted from input amount
-    let protocol_fee = (amount_in_u128 * accounts::PROTOCOL_FEE_RATE / 10000) as u128;
-    let platform_fee = (amount_in_u128 * accounts::PLATFORM_FEE_RATE / 10000) as u128;
-    let share_fee = (amount_in_u128 * accounts::SHARE_FEE_RATE / 10000) as u128;
-
-    // Calculate net input amount after deducting all fees
-    let amount_in_net = amount_in_u128
-        .checked_sub(protocol_fee)
-        .unwrap()
-        .checked_sub(platform_fee)
-        .unwrap()
-        .checked_sub(share_fee)
-        .unwrap();
-
-    // Calculate total reserves (virtual + real)
-    let input_reserve = virtual_quote.checked_add(real_quote).unwrap();
-    let output_reserve = virtual_base.checked_sub(real_base).unwrap();
-
-    // Apply constant product formula: amount_out = (amount_in * output_reserve) / (input_reserve + amount_in)
-    let numerator = amount_in_net.checked_mul(output_reserve).unwrap();
-    let denominator = input_reserve.checked_add(amount_in_net).unwrap();
-    let mut amount_out = numerator.checked_div(denominator).unwrap();
-
-    // Apply slippage protection
-    amount_out = amount_out - (amount_out * slippage_basis_points) / 10000;
-    amount_out as u64
+) -> Result<u64, String> {
+    get_amount_out(
+        amount_in,
+        accounts::PROTOCOL_FEE_RATE,
+        accounts::PLATFORM_FEE_RATE,
+        accounts::SHARE_FEE_RATE,
+        virtual_base,
+        virtual_quote,
+        real_base,
+        real_quote,
+        slippage_basis_points,
+    )
 }
 
 /// Calculates the amount of SOL to receive when selling tokens
@@ -71,7 +56,8 @@ pub fn get_buy_token_amount_from_sol_amount(
 ///
 /// # Returns
 ///
-/// The minimum amount of SOL that will be received after fees and slippage
+/// The minimum amount of SOL that will be received after fees and slippage, or an error
+/// when the reserves are inconsistent or the amounts overflow
 pub fn get_sell_sol_amount_from_token_amount(
     amount_in: u64,
     virtual_base: u128,
@@ -79,34 +65,105 @@ pub fn get_sell_sol_amount_from_token_amount(
     real_base: u128,
     real_quote: u128,
     slippage_basis_points: u128,
-) -> u64 {
+) -> Result<u64, String> {
+    if slippage_basis_points > 10000 {
+        return Err(format!("Slippage {} bps exceeds 10000", slippage_basis_points));
+    }
     let amount_in_u128 = amount_in as u128;
 
     // For sell operation, input_reserve is token reserves, output_reserve is SOL reserves
-    let input_reserve = virtual_base.checked_sub(real_base).unwrap();
-    let output_reserve = virtual_quote.checked_add(real_quote).unwrap();
+    let input_reserve = virtual_base.checked_sub(real_base).ok_or_else(|| {
+        format!("Real base reserves {} exceed virtual reserves {}", real_base, virtual_base)
+    })?;
+    let output_reserve = virtual_quote
+        .checked_add(real_quote)
+        .ok_or_else(|| "Quote reserves overflowed".to_string())?;
 
     // Use constant product formula to calculate SOL amount received from selling tokens
-    let numerator = amount_in_u128.checked_mul(output_reserve).unwrap();
-    let denominator = input_reserve.checked_add(amount_in_u128).unwrap();
-    let sol_amount_out = numerator.checked_div(denominator).unwrap();
-
-    // Calculate various fees
-    let protocol_fee = (sol_amount_out * accounts::PROTOCOL_FEE_RATE / 10000) as u128;
-    let platform_fee = (sol_amount_out * accounts::PLATFORM_FEE_RATE / 10000) as u128;
-    let share_fee = (sol_amount_out * accounts::SHARE_FEE_RATE / 10000) as u128;
+    let numerator = amount_in_u128
+        .checked_mul(output_reserve)
+        .ok_or_else(|| "Amount out calculation overflowed".to_string())?;
+    let denominator = input_reserve
+        .checked_add(amount_in_u128)
+        .filter(|denominator| *denominator > 0)
+        .ok_or_else(|| "Base reserves are empty".to_string())?;
+    let sol_amount_out = u64::try_from(numerator / denominator)
+        .map_err(|_| "SOL amount out exceeds u64".to_string())?;
 
     // Net SOL amount after deducting fees
-    let sol_amount_net = sol_amount_out
-        .checked_sub(protocol_fee)
-        .unwrap()
-        .checked_sub(platform_fee)
-        .unwrap()
-        .checked_sub(share_fee)
-        .unwrap();
+    let sol_amount_net = get_amount_in_net(
+        sol_amount_out,
+        accounts::PROTOCOL_FEE_RATE,
+        accounts::PLATFORM_FEE_RATE,
+        accounts::SHARE_FEE_RATE,
+    )? as u128;
 
     // Apply slippage protection
     let final_amount = sol_amount_net - (sol_amount_net * slippage_basis_points) / 10000;
 
-    final_amount as u64
+    Ok(final_amount as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VIRTUAL_BASE: u128 = 1_073_025_605_596_382;
+    const VIRTUAL_QUOTE: u128 = 30_000_852_951;
+
+    #[test]
+    fn test_reference_values_and_hostile_pools() {
+        let buy = |amount, slippage| {
+            get_buy_token_amount_from_sol_amount(
+                amount,
+                VIRTUAL_BASE,
+                VIRTUAL_QUOTE,
+                0,
+                0,
+                slippage,
+            )
+        };
+        assert_eq!(buy(2_000_000_000, 0), Ok(66_275_810_509_273));
+        assert_eq!(buy(2_000_000_000, 100), Ok(65_613_052_404_181));
+        let (real_base, real_quote) = (66_275_810_509_273, 1_975_000_000);
+        let sell = |amount, slippage| {
+            get_sell_sol_amount_from_token_amount(
+                amount,
+                VIRTUAL_BASE,
+                VIRTUAL_QUOTE,
+                real_base,
+                real_quote,
+                slippage,
+            )
+        };
+        assert_eq!(sell(66_275_810_509_273, 0), Ok(1_950_312_501));
+        assert_eq!(sell(1_000_000_000_000, 500), Ok(29_766_663));
+
+        // Any combination of extreme values yields an error or an amount, never a panic
+        let values =
+            [0, 1, 10_000, u64::MAX as u128, u64::MAX as u128 + 1, u128::MAX / 2, u128::MAX];
+        for &virtual_base in &values {
+            for &virtual_quote in &values {
+                for &real_base in &values {
+                    for &real_quote in &values {
+                        for amount in [0, 1, u64::MAX] {
+                            for slippage in [0, 10_000, 10_001] {
+                                let args =
+                                    (virtual_base, virtual_quote, real_base, real_quote, slippage);
+                                let (vb, vq, rb, rq, sl) = args;
+                                let _ = get_buy_token_amount_from_sol_amount(
+                                    amount, vb, vq, rb, rq, sl,
+                                );
+                                let _ = get_sell_sol_amount_from_token_amount(
+                                    amount, vb, vq, rb, rq, sl,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        assert!(buy(1, 10_001).is_err());
+        assert!(get_buy_token_amount_from_sol_amount(1, 1, 1, 2, 0, 0).is_err());
+    }
 }
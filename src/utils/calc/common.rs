@@ -38,8 +38,13 @@ pub fn ceil_div(a: u128, b: u128) -> u128 {
 /// * basis_points = 10  -> 0.1% slippage  
 /// * basis_points = 100 -> 1% slippage
 /// * basis_points = 500 -> 5% slippage
-pub fn calculate_with_slippage_buy(amount: u64, basis_points: u64) -> u64 {
-    amount + (amount * basis_points / 10000)
+///
+/// # Errors
+/// Fails when the result does not fit in a `u64`
+pub fn calculate_with_slippage_buy(amount: u64, basis_points: u64) -> Result<u64, String> {
+    let slippage = amount as u128 * basis_points as u128 / 10000;
+    u64::try_from(amount as u128 + slippage)
+        .map_err(|_| format!("Amount {} with {} bps slippage overflows u64", amount, basis_points))
 }
 
 /// Calculate sell amount with slippage protection
@@ -54,10 +59,106 @@ pub fn calculate_with_slippage_buy(amount: u64, basis_points: u64) -> u64 {
 /// * basis_points = 10  -> 0.1% slippage  
 /// * basis_points = 100 -> 1% slippage
 /// * basis_points = 500 -> 5% slippage
-pub fn calculate_with_slippage_sell(amount: u64, basis_points: u64) -> u64 {
+///
+/// # Errors
+/// Fails when `basis_points` exceeds 10000
+pub fn calculate_with_slippage_sell(amount: u64, basis_points: u64) -> Result<u64, String> {
+    if basis_points > 10000 {
+        return Err(format!("Slippage {} bps exceeds 10000", basis_points));
+    }
     if amount <= basis_points / 10000 {
-        1
+        Ok(1)
     } else {
-        amount - (amount * basis_points / 10000)
+        Ok(amount - (amount as u128 * basis_points as u128 / 10000) as u64)
+    }
+}
+
+/// Calculate the amount to sell for a share of a balance given in basis points
+///
+/// # Parameters
+/// * `balance` - Token balance held
+/// * `basis_points` - Share of the balance to sell, 10000 = 100%
+/// * `dust_threshold` - A remainder of at most this many base units is sold as well
+///
+/// # Errors
+/// Fails unless `0 < basis_points <= 10000`, or when the share rounds down to zero
+pub fn calculate_sell_amount_by_bps(
+    balance: u64,
+    basis_points: u64,
+    dust_threshold: u64,
+) -> Result<u64, String> {
+    if basis_points == 0 || basis_points > 10000 {
+        return Err(format!("Basis points {} must be between 1 and 10000", basis_points));
+    }
+    let amount = (balance as u128 * basis_points as u128 / 10000) as u64;
+    // Don't leave a remainder too small to be worth another sell
+    let amount = if balance - amount <= dust_threshold { balance } else { amount };
+    if amount == 0 {
+        return Err(format!("{} bps of {} rounds down to zero", basis_points, balance));
+    }
+    Ok(amount)
+}
+
+/// Calculate the price impact of a trade against a constant product curve, in basis points
+///
+/// Both constant product pools and the bonding curves (on their virtual reserves) price
+/// trades along `x * y = k`, where paying `amount` into a side holding `reserves` moves
+/// the execution price `amount / (reserves + amount)` away from the spot price. Fees are
+/// not included.
+///
+/// # Parameters
+/// * `reserves` - Reserves of the side being paid in: SOL/quote reserves for a buy,
+///   token reserves for a sell
+/// * `amount` - Amount paid in
+///
+/// # Examples
+/// * 1 SOL into a curve holding 30 SOL  -> 322 bps
+/// * 1 SOL into a pool holding 999 SOL  -> 10 bps
+///
+/// # Errors
+/// Fails when `reserves` is zero
+pub fn calculate_price_impact(reserves: u64, amount: u64) -> Result<u64, String> {
+    if reserves == 0 {
+        return Err("Cannot calculate price impact against empty reserves".to_string());
+    }
+    let amount = amount as u128;
+    Ok((amount * 10_000 / (reserves as u128 + amount)) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slippage_bounds() {
+        assert_eq!(calculate_with_slippage_buy(1_000_000, 500), Ok(1_050_000));
+        assert_eq!(calculate_with_slippage_buy(u64::MAX, 0), Ok(u64::MAX));
+        assert!(calculate_with_slippage_buy(u64::MAX, 1).is_err());
+        assert_eq!(calculate_with_slippage_sell(1_000_000, 500), Ok(950_000));
+        assert_eq!(calculate_with_slippage_sell(u64::MAX, 10000), Ok(0));
+        assert_eq!(calculate_with_slippage_sell(1, 10000), Ok(1));
+        assert!(calculate_with_slippage_sell(100, 10001).is_err());
+    }
+
+    #[test]
+    fn test_sell_amount_by_bps() {
+        assert_eq!(calculate_sell_amount_by_bps(u64::MAX, 5000, 0), Ok(u64::MAX / 2));
+        assert_eq!(calculate_sell_amount_by_bps(1_000_001, 3333, 0), Ok(333_300));
+        assert_eq!(calculate_sell_amount_by_bps(1_000, 10000, 0), Ok(1_000));
+        assert_eq!(calculate_sell_amount_by_bps(1_000, 9990, 0), Ok(999));
+        assert_eq!(calculate_sell_amount_by_bps(1_000, 9990, 1), Ok(1_000));
+        assert!(calculate_sell_amount_by_bps(1_000, 0, 0).is_err());
+        assert!(calculate_sell_amount_by_bps(1_000, 10001, 0).is_err());
+        assert!(calculate_sell_amount_by_bps(1, 5000, 0).is_err());
+        assert!(calculate_sell_amount_by_bps(0, 10000, 0).is_err());
+    }
+
+    #[test]
+    fn test_price_impact() {
+        assert_eq!(calculate_price_impact(30_000_000_000, 1_000_000_000), Ok(322));
+        assert_eq!(calculate_price_impact(999_000_000_000, 1_000_000_000), Ok(10));
+        assert_eq!(calculate_price_impact(1_000, 0), Ok(0));
+        assert_eq!(calculate_price_impact(1, u64::MAX), Ok(9_999));
+        assert!(calculate_price_impact(0, 1_000).is_err());
     }
 }
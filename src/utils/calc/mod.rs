@@ -1,6 +1,11 @@
+#[cfg(feature = "pumpfun")]
 pub mod pumpfun;
 pub mod common;
+#[cfg(feature = "pumpswap")]
 pub mod pumpswap;
+#[cfg(feature = "bonk")]
 pub mod bonk;
+#[cfg(feature = "raydium")]
 pub mod raydium_amm_v4;
-pub mod raydium_cpmm;
\ No newline at end of file
+#[cfg(feature = "raydium")]
+pub mod raydium_cpmm;
@@ -1,9 +1,7 @@
 use super::common::{
     calculate_with_slippage_buy, calculate_with_slippage_sell, ceil_div, compute_fee,
 };
-use crate::constants::pumpswap::accounts::{
-    COIN_CREATOR_FEE_BASIS_POINTS, LP_FEE_BASIS_POINTS, PROTOCOL_FEE_BASIS_POINTS,
-};
+use crate::trading::pumpswap::fee_config::FeeConfigCache;
 use solana_sdk::pubkey::Pubkey;
 
 /// Result for buying base tokens with base amount input
@@ -68,6 +66,7 @@ pub fn buy_base_input_internal(
     quote_reserve: u64,
     coin_creator: &Pubkey,
 ) -> Result<BuyBaseInputResult, String> {
+    let fees = FeeConfigCache::get_instance().get();
     if base_reserve == 0 || quote_reserve == 0 {
         return Err("Invalid input: 'baseReserve' or 'quoteReserve' cannot be zero.".to_string());
     }
@@ -86,18 +85,18 @@ pub fn buy_base_input_internal(
     let quote_amount_in = ceil_div(numerator, denominator as u128) as u64;
 
     // Calculate fees
-    let lp_fee = compute_fee(quote_amount_in as u128, LP_FEE_BASIS_POINTS as u128) as u64;
+    let lp_fee = compute_fee(quote_amount_in as u128, fees.lp_fee_basis_points as u128) as u64;
     let protocol_fee =
-        compute_fee(quote_amount_in as u128, PROTOCOL_FEE_BASIS_POINTS as u128) as u64;
+        compute_fee(quote_amount_in as u128, fees.protocol_fee_basis_points as u128) as u64;
     let coin_creator_fee = if *coin_creator == Pubkey::default() {
         0
     } else {
-        compute_fee(quote_amount_in as u128, COIN_CREATOR_FEE_BASIS_POINTS as u128) as u64
+        compute_fee(quote_amount_in as u128, fees.coin_creator_fee_basis_points as u128) as u64
     };
     let total_quote = quote_amount_in + lp_fee + protocol_fee + coin_creator_fee;
 
     // Calculate max quote with slippage
-    let max_quote = calculate_with_slippage_buy(total_quote, slippage_basis_points);
+    let max_quote = calculate_with_slippage_buy(total_quote, slippage_basis_points)?;
 
     Ok(BuyBaseInputResult {
         internal_quote_amount: quote_amount_in,
@@ -124,14 +123,15 @@ pub fn buy_quote_input_internal(
     quote_reserve: u64,
     coin_creator: &Pubkey,
 ) -> Result<BuyQuoteInputResult, String> {
+    let fees = FeeConfigCache::get_instance().get();
     if base_reserve == 0 || quote_reserve == 0 {
         return Err("Invalid input: 'baseReserve' or 'quoteReserve' cannot be zero.".to_string());
     }
 
     // Calculate total fee basis points
-    let total_fee_bps = LP_FEE_BASIS_POINTS
-        + PROTOCOL_FEE_BASIS_POINTS
-        + if *coin_creator == Pubkey::default() { 0 } else { COIN_CREATOR_FEE_BASIS_POINTS };
+    let total_fee_bps = fees.lp_fee_basis_points
+        + fees.protocol_fee_basis_points
+        + if *coin_creator == Pubkey::default() { 0 } else { fees.coin_creator_fee_basis_points };
     let denominator = 10_000 + total_fee_bps;
 
     // Calculate effective quote amount after fees
@@ -148,7 +148,7 @@ pub fn buy_quote_input_internal(
     let base_amount_out = (numerator / denominator_effective) as u64;
 
     // Calculate max quote with slippage
-    let max_quote = calculate_with_slippage_buy(quote, slippage_basis_points);
+    let max_quote = calculate_with_slippage_buy(quote, slippage_basis_points)?;
 
     Ok(BuyQuoteInputResult {
         base: base_amount_out,
@@ -175,6 +175,7 @@ pub fn sell_base_input_internal(
     quote_reserve: u64,
     coin_creator: &Pubkey,
 ) -> Result<SellBaseInputResult, String> {
+    let fees = FeeConfigCache::get_instance().get();
     if base_reserve == 0 || quote_reserve == 0 {
         return Err("Invalid input: 'baseReserve' or 'quoteReserve' cannot be zero.".to_string());
     }
@@ -184,13 +185,13 @@ pub fn sell_base_input_internal(
         / ((base_reserve as u128) + (base as u128))) as u64;
 
     // Calculate fees
-    let lp_fee = compute_fee(quote_amount_out as u128, LP_FEE_BASIS_POINTS as u128) as u64;
+    let lp_fee = compute_fee(quote_amount_out as u128, fees.lp_fee_basis_points as u128) as u64;
     let protocol_fee =
-        compute_fee(quote_amount_out as u128, PROTOCOL_FEE_BASIS_POINTS as u128) as u64;
+        compute_fee(quote_amount_out as u128, fees.protocol_fee_basis_points as u128) as u64;
     let coin_creator_fee = if *coin_creator == Pubkey::default() {
         0
     } else {
-        compute_fee(quote_amount_out as u128, COIN_CREATOR_FEE_BASIS_POINTS as u128) as u64
+        compute_fee(quote_amount_out as u128, fees.coin_creator_fee_basis_points as u128) as u64
     };
 
     // Calculate final quote after fees
@@ -201,7 +202,7 @@ pub fn sell_base_input_internal(
     let final_quote = quote_amount_out - total_fees;
 
     // Calculate min quote with slippage
-    let min_quote = calculate_with_slippage_sell(final_quote, slippage_basis_points);
+    let min_quote = calculate_with_slippage_sell(final_quote, slippage_basis_points)?;
 
     Ok(SellBaseInputResult {
         ui_quote: final_quote,
@@ -210,6 +211,46 @@ pub fn sell_base_input_internal(
     })
 }
 
+/// Calculate base tokens received for spending `quote` on the pool, from known reserves
+///
+/// Pure helper for offline quoting; returns 0 when the reserves are empty.
+///
+/// # Arguments
+/// * `base_reserve` - Base token reserves in the pool
+/// * `quote_reserve` - Quote token reserves in the pool
+/// * `coin_creator` - Coin creator of the pool (affects fee calculation)
+/// * `quote` - Quote amount to spend
+pub fn get_buy_token_amount_from_quote_amount(
+    base_reserve: u64,
+    quote_reserve: u64,
+    coin_creator: &Pubkey,
+    quote: u64,
+) -> u64 {
+    buy_quote_input_internal(quote, 0, base_reserve, quote_reserve, coin_creator)
+        .map(|result| result.base)
+        .unwrap_or(0)
+}
+
+/// Calculate quote tokens received for selling `base` to the pool, from known reserves
+///
+/// Pure helper for offline quoting; returns 0 when the reserves are empty.
+///
+/// # Arguments
+/// * `base_reserve` - Base token reserves in the pool
+/// * `quote_reserve` - Quote token reserves in the pool
+/// * `coin_creator` - Coin creator of the pool (affects fee calculation)
+/// * `base` - Base token amount to sell
+pub fn get_sell_quote_amount_from_token_amount(
+    base_reserve: u64,
+    quote_reserve: u64,
+    coin_creator: &Pubkey,
+    base: u64,
+) -> u64 {
+    sell_base_input_internal(base, 0, base_reserve, quote_reserve, coin_creator)
+        .map(|result| result.ui_quote)
+        .unwrap_or(0)
+}
+
 const MAX_FEE_BASIS_POINTS: u64 = 10_000;
 
 /// Calculate quote amount out including fees
@@ -244,6 +285,7 @@ pub fn sell_quote_input_internal(
     quote_reserve: u64,
     coin_creator: &Pubkey,
 ) -> Result<SellQuoteInputResult, String> {
+    let fees = FeeConfigCache::get_instance().get();
     if base_reserve == 0 || quote_reserve == 0 {
         return Err("Invalid input: 'baseReserve' or 'quoteReserve' cannot be zero.".to_string());
     }
@@ -254,9 +296,9 @@ pub fn sell_quote_input_internal(
     // Calculate raw quote amount including fees
     let raw_quote = calculate_quote_amount_out(
         quote,
-        LP_FEE_BASIS_POINTS,
-        PROTOCOL_FEE_BASIS_POINTS,
-        if *coin_creator == Pubkey::default() { 0 } else { COIN_CREATOR_FEE_BASIS_POINTS },
+        fees.lp_fee_basis_points,
+        fees.protocol_fee_basis_points,
+        if *coin_creator == Pubkey::default() { 0 } else { fees.coin_creator_fee_basis_points },
     );
 
     // Calculate base amount needed using inverse constant product formula
@@ -269,7 +311,206 @@ pub fn sell_quote_input_internal(
             as u64;
 
     // Calculate min quote with slippage
-    let min_quote = calculate_with_slippage_sell(quote, slippage_basis_points);
+    let min_quote = calculate_with_slippage_sell(quote, slippage_basis_points)?;
 
     Ok(SellQuoteInputResult { internal_raw_quote: raw_quote, base: base_amount_in, min_quote })
 }
+
+/// Result for depositing liquidity
+#[derive(Clone, Debug)]
+pub struct DepositResult {
+    /// LP tokens minted to the user
+    pub lp_token: u64,
+    /// Proportional base amount deposited
+    pub base: u64,
+    /// Proportional quote amount deposited
+    pub quote: u64,
+    /// Maximum base amount with slippage protection
+    pub max_base: u64,
+    /// Maximum quote amount with slippage protection
+    pub max_quote: u64,
+}
+
+/// Result for withdrawing liquidity
+#[derive(Clone, Debug)]
+pub struct WithdrawResult {
+    /// Base amount returned for the burned LP tokens
+    pub base: u64,
+    /// Quote amount returned for the burned LP tokens
+    pub quote: u64,
+    /// Minimum base amount with slippage protection
+    pub min_base: u64,
+    /// Minimum quote amount with slippage protection
+    pub min_quote: u64,
+}
+
+/// `amount * numerator / denominator`, rounded up when `round_up`
+///
+/// Fails instead of truncating when the result does not fit in a u64.
+fn proportional_amount(
+    amount: u64,
+    numerator: u64,
+    denominator: u64,
+    round_up: bool,
+) -> Result<u64, String> {
+    let product = (amount as u128) * (numerator as u128);
+    let result = if round_up {
+        ceil_div(product, denominator as u128)
+    } else {
+        product / denominator as u128
+    };
+    u64::try_from(result).map_err(|_| format!("Liquidity amount {} overflows u64", result))
+}
+
+/// Calculate the proportional deposit for a desired amount of LP tokens
+///
+/// # Arguments
+/// * `lp_token` - LP tokens to receive
+/// * `slippage_basis_points` - Slippage tolerance in basis points (100 = 1%)
+/// * `base_reserve` - Base token reserves in the pool
+/// * `quote_reserve` - Quote token reserves in the pool
+/// * `lp_supply` - Current LP token supply
+///
+/// # Returns
+/// * `DepositResult` containing the base/quote amounts to deposit
+pub fn deposit_lp_output(
+    lp_token: u64,
+    slippage_basis_points: u64,
+    base_reserve: u64,
+    quote_reserve: u64,
+    lp_supply: u64,
+) -> Result<DepositResult, String> {
+    if base_reserve == 0 || quote_reserve == 0 || lp_supply == 0 {
+        return Err("Invalid input: pool reserves and LP supply cannot be zero.".to_string());
+    }
+    if lp_token == 0 {
+        return Err("Invalid input: 'lpToken' cannot be zero.".to_string());
+    }
+
+    let base = proportional_amount(lp_token, base_reserve, lp_supply, true)?;
+    let quote = proportional_amount(lp_token, quote_reserve, lp_supply, true)?;
+
+    Ok(DepositResult {
+        lp_token,
+        base,
+        quote,
+        max_base: calculate_with_slippage_buy(base, slippage_basis_points)?,
+        max_quote: calculate_with_slippage_buy(quote, slippage_basis_points)?,
+    })
+}
+
+/// Calculate the proportional deposit for a given base amount
+///
+/// # Arguments
+/// * `base` - Base tokens to deposit
+/// * `slippage_basis_points` - Slippage tolerance in basis points (100 = 1%)
+/// * `base_reserve` - Base token reserves in the pool
+/// * `quote_reserve` - Quote token reserves in the pool
+/// * `lp_supply` - Current LP token supply
+///
+/// # Returns
+/// * `DepositResult` containing LP tokens out and the matching quote amount
+pub fn deposit_base_input(
+    base: u64,
+    slippage_basis_points: u64,
+    base_reserve: u64,
+    quote_reserve: u64,
+    lp_supply: u64,
+) -> Result<DepositResult, String> {
+    if base_reserve == 0 {
+        return Err("Invalid input: 'baseReserve' cannot be zero.".to_string());
+    }
+    let lp_token = proportional_amount(base, lp_supply, base_reserve, false)?;
+    deposit_lp_output(lp_token, slippage_basis_points, base_reserve, quote_reserve, lp_supply)
+}
+
+/// Calculate the amounts returned when burning LP tokens
+///
+/// # Arguments
+/// * `lp_token` - LP tokens to burn
+/// * `slippage_basis_points` - Slippage tolerance in basis points (100 = 1%)
+/// * `base_reserve` - Base token reserves in the pool
+/// * `quote_reserve` - Quote token reserves in the pool
+/// * `lp_supply` - Current LP token supply
+///
+/// # Returns
+/// * `WithdrawResult` containing base/quote amounts out
+pub fn withdraw_lp_input(
+    lp_token: u64,
+    slippage_basis_points: u64,
+    base_reserve: u64,
+    quote_reserve: u64,
+    lp_supply: u64,
+) -> Result<WithdrawResult, String> {
+    if lp_supply == 0 {
+        return Err("Invalid input: 'lpSupply' cannot be zero.".to_string());
+    }
+    if lp_token == 0 || lp_token > lp_supply {
+        return Err("Invalid input: 'lpToken' must be between 1 and the LP supply.".to_string());
+    }
+
+    let base = proportional_amount(lp_token, base_reserve, lp_supply, false)?;
+    let quote = proportional_amount(lp_token, quote_reserve, lp_supply, false)?;
+
+    Ok(WithdrawResult {
+        base,
+        quote,
+        min_base: calculate_with_slippage_sell(base, slippage_basis_points)?,
+        min_quote: calculate_with_slippage_sell(quote, slippage_basis_points)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_RESERVE: u64 = 1_000_000_000_000;
+    const QUOTE_RESERVE: u64 = 50_000_000_000;
+    const LP_SUPPLY: u64 = 200_000_000_000;
+
+    #[test]
+    fn test_deposit_amounts() {
+        let result =
+            deposit_lp_output(1_000_000, 100, BASE_RESERVE, QUOTE_RESERVE, LP_SUPPLY).unwrap();
+        assert_eq!((result.base, result.quote), (5_000_000, 250_000));
+        assert_eq!((result.max_base, result.max_quote), (5_050_000, 252_500));
+
+        // Deposits round up so the pool never mints LP tokens below their value
+        let result = deposit_lp_output(3, 0, BASE_RESERVE, QUOTE_RESERVE, LP_SUPPLY).unwrap();
+        assert_eq!((result.base, result.quote), (15, 1));
+
+        let result =
+            deposit_base_input(5_000_000, 100, BASE_RESERVE, QUOTE_RESERVE, LP_SUPPLY).unwrap();
+        assert_eq!(result.lp_token, 1_000_000);
+        assert_eq!(result.quote, 250_000);
+
+        // Too small to mint a single LP token
+        assert!(deposit_base_input(4, 100, BASE_RESERVE, QUOTE_RESERVE, LP_SUPPLY).is_err());
+        assert!(deposit_lp_output(1, 100, 0, QUOTE_RESERVE, LP_SUPPLY).is_err());
+    }
+
+    #[test]
+    fn test_withdraw_amounts() {
+        let result =
+            withdraw_lp_input(1_000_000, 100, BASE_RESERVE, QUOTE_RESERVE, LP_SUPPLY).unwrap();
+        assert_eq!((result.base, result.quote), (5_000_000, 250_000));
+        assert_eq!((result.min_base, result.min_quote), (4_950_000, 247_500));
+
+        let result =
+            withdraw_lp_input(LP_SUPPLY, 0, BASE_RESERVE, QUOTE_RESERVE, LP_SUPPLY).unwrap();
+        assert_eq!((result.base, result.quote), (BASE_RESERVE, QUOTE_RESERVE));
+
+        assert!(withdraw_lp_input(0, 100, BASE_RESERVE, QUOTE_RESERVE, LP_SUPPLY).is_err());
+        assert!(
+            withdraw_lp_input(LP_SUPPLY + 1, 100, BASE_RESERVE, QUOTE_RESERVE, LP_SUPPLY).is_err()
+        );
+    }
+
+    #[test]
+    fn test_overflowing_amounts_are_errors() {
+        // A deposit worth more than u64::MAX base tokens errors instead of truncating
+        assert!(deposit_lp_output(u64::MAX, 0, u64::MAX, 1, 1).is_err());
+        assert!(deposit_base_input(u64::MAX, 0, 1, 1, u64::MAX).is_err());
+        assert!(deposit_lp_output(u64::MAX, 0, u64::MAX, u64::MAX, u64::MAX).is_ok());
+    }
+}
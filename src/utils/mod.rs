@@ -1,12 +1,26 @@
+pub mod amount;
 pub mod price;
 pub mod calc;
+pub mod vanity;
 
+#[cfg(feature = "pumpfun")]
 use crate::solana_streamer_sdk::streaming::event_parser::protocols::pumpfun::PumpFunTradeEvent;
 use crate::trading;
+#[cfg(feature = "pumpswap")]
+use crate::trading::pumpswap::fee_config::FeeConfigCache;
+#[cfg(feature = "pumpswap")]
+use crate::trading::pumpswap::reserves_cache::PoolReservesCache;
+use crate::common::mint_info_cache::MintInfoCache;
+use crate::common::TradeSigner;
 use crate::SolanaTrade;
 use solana_sdk::pubkey::Pubkey;
+#[cfg(feature = "pumpfun")]
 use solana_sdk::signature::Keypair;
+#[cfg(any(feature = "pumpfun", feature = "pumpswap"))]
+use solana_sdk::signature::Signature;
 use solana_sdk::signer::Signer;
+#[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+use std::collections::HashMap;
 
 impl SolanaTrade {
     #[inline]
@@ -33,20 +47,38 @@ impl SolanaTrade {
         trading::common::utils::get_token_balance(&self.rpc, &self.payer.pubkey(), mint).await
     }
 
+    /// `raw` base units of `mint` as a UI amount, using the mint's cached decimals
+    #[inline]
+    pub async fn to_ui_amount(&self, mint: &Pubkey, raw: u64) -> Result<f64, anyhow::Error> {
+        let info = MintInfoCache::get_instance().get_or_fetch(&self.rpc, mint).await?;
+        Ok(amount::to_ui_amount(raw, info.decimals))
+    }
+
+    /// A UI amount of `mint` in base units, using the mint's cached decimals
+    #[inline]
+    pub async fn from_ui_amount(
+        &self,
+        mint: &Pubkey,
+        ui_amount: f64,
+    ) -> Result<u64, anyhow::Error> {
+        let info = MintInfoCache::get_instance().get_or_fetch(&self.rpc, mint).await?;
+        amount::from_ui_amount(ui_amount, info.decimals).map_err(|e| anyhow::anyhow!(e))
+    }
+
     #[inline]
     pub fn get_payer_pubkey(&self) -> Pubkey {
         self.payer.pubkey()
     }
 
     #[inline]
-    pub fn get_payer(&self) -> &Keypair {
+    pub fn get_payer(&self) -> &dyn TradeSigner {
         self.payer.as_ref()
     }
 
     #[inline]
     pub async fn transfer_sol(
         &self,
-        payer: &Keypair,
+        payer: &dyn TradeSigner,
         receive_wallet: &Pubkey,
         amount: u64,
     ) -> Result<(), anyhow::Error> {
@@ -60,18 +92,51 @@ impl SolanaTrade {
 
     // -------------------------------- PumpFun --------------------------------
 
+    #[inline]
+    pub async fn upload_token_metadata(
+        &self,
+        metadata: &trading::common::TokenMetadata,
+    ) -> Result<String, anyhow::Error> {
+        let uploader = self.metadata_uploader.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("No metadata uploader configured, call with_metadata_uploader first")
+        })?;
+        uploader.upload_metadata(metadata).await
+    }
+
+    /// Prices of `mints` on their bonding curve or PumpSwap pool, fetched with batched RPC calls
+    ///
+    /// Mints that cannot be quoted against SOL are missing from the result.
+    #[cfg(all(feature = "pumpfun", feature = "pumpswap"))]
+    #[inline]
+    pub async fn quote_many(
+        &self,
+        mints: Vec<Pubkey>,
+    ) -> Result<HashMap<Pubkey, trading::common::MintQuote>, anyhow::Error> {
+        trading::common::quote_many(&self.rpc, &mints).await
+    }
+
+    #[inline]
+    pub async fn fetch_token_metadata(
+        &self,
+        mint: &Pubkey,
+    ) -> Result<trading::common::FetchedTokenMetadata, anyhow::Error> {
+        trading::common::fetch_token_metadata(&self.rpc, mint, None).await
+    }
+
+    #[cfg(feature = "pumpfun")]
     #[inline]
     pub fn get_pumpfun_token_buy_price(&self, amount: u64, trade_info: &PumpFunTradeEvent) -> u64 {
         trading::pumpfun::common::get_buy_price(amount, trade_info)
     }
 
+    #[cfg(feature = "pumpfun")]
     #[inline]
     pub async fn get_pumpfun_token_current_price(
         &self,
         mint: &Pubkey,
     ) -> Result<f64, anyhow::Error> {
-        let (bonding_curve, _) =
-            trading::pumpfun::common::fetch_bonding_curve_account(&self.rpc, mint).await?;
+        let bonding_curve =
+            trading::pumpfun::common::get_bonding_curve_account(&self.rpc, mint).await?;
 
         let virtual_sol_reserves = bonding_curve.virtual_sol_reserves;
         let virtual_token_reserves = bonding_curve.virtual_token_reserves;
@@ -79,40 +144,113 @@ impl SolanaTrade {
         Ok(price::pumpfun::price_token_in_sol(virtual_sol_reserves, virtual_token_reserves))
     }
 
+    #[cfg(feature = "pumpfun")]
     #[inline]
     pub async fn get_pumpfun_token_real_sol_reserves(
         &self,
         mint: &Pubkey,
     ) -> Result<u64, anyhow::Error> {
-        let (bonding_curve, _) =
-            trading::pumpfun::common::fetch_bonding_curve_account(&self.rpc, mint).await?;
+        let bonding_curve =
+            trading::pumpfun::common::get_bonding_curve_account(&self.rpc, mint).await?;
 
         let actual_sol_reserves = bonding_curve.real_sol_reserves;
 
         Ok(actual_sol_reserves)
     }
 
+    /// Price impact of buying with `sol_amount` lamports on the bonding curve, in basis points
+    #[cfg(feature = "pumpfun")]
+    #[inline]
+    pub async fn get_pumpfun_buy_price_impact(
+        &self,
+        mint: &Pubkey,
+        sol_amount: u64,
+    ) -> Result<u64, anyhow::Error> {
+        let bonding_curve =
+            trading::pumpfun::common::get_bonding_curve_account(&self.rpc, mint).await?;
+        calc::common::calculate_price_impact(bonding_curve.virtual_sol_reserves, sol_amount)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    #[cfg(feature = "pumpfun")]
     #[inline]
     pub async fn get_pumpfun_token_creator(&self, mint: &Pubkey) -> Result<Pubkey, anyhow::Error> {
-        let (bonding_curve, _) =
-            trading::pumpfun::common::fetch_bonding_curve_account(&self.rpc, mint).await?;
+        let bonding_curve =
+            trading::pumpfun::common::get_bonding_curve_account(&self.rpc, mint).await?;
 
         let creator = bonding_curve.creator;
 
         Ok(creator)
     }
 
+    #[cfg(feature = "pumpfun")]
+    #[inline]
+    pub async fn get_pumpfun_claimable_creator_fees(&self) -> Result<u64, anyhow::Error> {
+        trading::pumpfun::creator_fee::get_claimable_creator_fees(&self.rpc, &self.payer.pubkey())
+            .await
+    }
+
+    #[cfg(feature = "pumpfun")]
+    #[inline]
+    pub async fn claim_pumpfun_creator_fees(&self) -> Result<Signature, anyhow::Error> {
+        trading::pumpfun::creator_fee::claim_creator_fees(&self.rpc, self.payer.as_ref()).await
+    }
+
+    #[cfg(feature = "pumpfun")]
+    #[inline]
+    pub async fn pumpfun_create_with_uri(
+        &self,
+        mint: &Keypair,
+        name: String,
+        symbol: String,
+        uri: String,
+        dev_buy_sol_amount: Option<u64>,
+        slippage_basis_points: Option<u64>,
+    ) -> Result<Signature, anyhow::Error> {
+        trading::pumpfun::create::create_token(
+            &self.rpc,
+            self.payer.as_ref(),
+            mint,
+            name,
+            symbol,
+            uri,
+            dev_buy_sol_amount,
+            slippage_basis_points,
+        )
+        .await
+    }
+
+    #[cfg(feature = "pumpfun")]
+    #[inline]
+    pub async fn pumpfun_create(
+        &self,
+        mint: &Keypair,
+        metadata: &trading::common::TokenMetadata,
+        dev_buy_sol_amount: Option<u64>,
+        slippage_basis_points: Option<u64>,
+    ) -> Result<Signature, anyhow::Error> {
+        let uri = self.upload_token_metadata(metadata).await?;
+        self.pumpfun_create_with_uri(
+            mint,
+            metadata.name.clone(),
+            metadata.symbol.clone(),
+            uri,
+            dev_buy_sol_amount,
+            slippage_basis_points,
+        )
+        .await
+    }
+
     // -------------------------------- PumpSwap --------------------------------
 
+    #[cfg(feature = "pumpswap")]
     #[inline]
     pub async fn get_pumpswap_token_current_price(
         &self,
         pool_address: &Pubkey,
     ) -> Result<f64, anyhow::Error> {
-        let pool = trading::pumpswap::common::fetch_pool(&self.rpc, pool_address).await?;
-
-        let (base_amount, quote_amount) =
-            trading::pumpswap::common::get_token_balances(&pool, &self.rpc).await?;
+        let (_, base_amount, quote_amount) =
+            PoolReservesCache::get_instance().get_or_fetch(&self.rpc, pool_address).await?;
 
         // Calculate price using constant product formula (x * y = k)
         // Price = quote_amount / base_amount
@@ -125,29 +263,132 @@ impl SolanaTrade {
         Ok(price)
     }
 
+    #[cfg(feature = "pumpswap")]
     #[inline]
     pub async fn get_pumpswap_token_real_sol_reserves(
         &self,
         pool_address: &Pubkey,
     ) -> Result<u64, anyhow::Error> {
-        let pool = trading::pumpswap::common::fetch_pool(&self.rpc, pool_address).await?;
-
-        let (_, quote_amount) =
-            trading::pumpswap::common::get_token_balances(&pool, &self.rpc).await?;
+        let (_, _, quote_amount) =
+            PoolReservesCache::get_instance().get_or_fetch(&self.rpc, pool_address).await?;
 
         Ok(quote_amount)
     }
 
+    /// Price impact of buying with `quote_amount` in the pool, in basis points
+    #[cfg(feature = "pumpswap")]
     #[inline]
-    pub async fn get_pumpswap_payer_token_balance(
+    pub async fn get_pumpswap_buy_price_impact(
         &self,
         pool_address: &Pubkey,
+        quote_amount: u64,
     ) -> Result<u64, anyhow::Error> {
-        let pool = trading::pumpswap::common::fetch_pool(&self.rpc, pool_address).await?;
+        let (_, _, quote_reserve) =
+            PoolReservesCache::get_instance().get_or_fetch(&self.rpc, pool_address).await?;
+        calc::common::calculate_price_impact(quote_reserve, quote_amount)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
 
-        let (base_amount, _) =
-            trading::pumpswap::common::get_token_balances(&pool, &self.rpc).await?;
+    #[cfg(feature = "pumpswap")]
+    #[inline]
+    pub async fn get_pumpswap_payer_token_balance(
+        &self,
+        pool_address: &Pubkey,
+    ) -> Result<u64, anyhow::Error> {
+        let (_, base_amount, _) =
+            PoolReservesCache::get_instance().get_or_fetch(&self.rpc, pool_address).await?;
 
         Ok(base_amount)
     }
+
+    #[cfg(feature = "pumpswap")]
+    #[inline]
+    pub async fn get_pumpswap_buy_token_amount(
+        &self,
+        pool_address: &Pubkey,
+        quote_amount: u64,
+    ) -> Result<u64, anyhow::Error> {
+        let (pool, base_reserve, quote_reserve) =
+            PoolReservesCache::get_instance().get_or_fetch(&self.rpc, pool_address).await?;
+        FeeConfigCache::get_instance().refresh_if_stale(&self.rpc).await;
+        Ok(calc::pumpswap::get_buy_token_amount_from_quote_amount(
+            base_reserve,
+            quote_reserve,
+            &pool.coin_creator,
+            quote_amount,
+        ))
+    }
+
+    #[cfg(feature = "pumpswap")]
+    #[inline]
+    pub async fn get_pumpswap_sell_quote_amount(
+        &self,
+        pool_address: &Pubkey,
+        token_amount: u64,
+    ) -> Result<u64, anyhow::Error> {
+        let (pool, base_reserve, quote_reserve) =
+            PoolReservesCache::get_instance().get_or_fetch(&self.rpc, pool_address).await?;
+        FeeConfigCache::get_instance().refresh_if_stale(&self.rpc).await;
+        Ok(calc::pumpswap::get_sell_quote_amount_from_token_amount(
+            base_reserve,
+            quote_reserve,
+            &pool.coin_creator,
+            token_amount,
+        ))
+    }
+
+    /// Coin creator fees the payer can claim from PumpSwap, in lamports
+    #[cfg(feature = "pumpswap")]
+    #[inline]
+    pub async fn get_pumpswap_claimable_creator_fees(&self) -> Result<u64, anyhow::Error> {
+        trading::pumpswap::creator_fee::get_claimable_coin_creator_fees(
+            &self.rpc,
+            &self.payer.pubkey(),
+        )
+        .await
+    }
+
+    /// Claim the payer's PumpSwap coin creator fees, unwrapped to SOL
+    #[cfg(feature = "pumpswap")]
+    #[inline]
+    pub async fn claim_pumpswap_creator_fees(&self) -> Result<Signature, anyhow::Error> {
+        trading::pumpswap::creator_fee::claim_coin_creator_fees(&self.rpc, self.payer.as_ref())
+            .await
+    }
+
+    #[cfg(feature = "pumpswap")]
+    #[inline]
+    pub async fn pumpswap_deposit(
+        &self,
+        pool_address: &Pubkey,
+        base_amount: u64,
+        slippage_basis_points: Option<u64>,
+    ) -> Result<Signature, anyhow::Error> {
+        trading::pumpswap::liquidity::deposit(
+            &self.rpc,
+            self.payer.as_ref(),
+            pool_address,
+            base_amount,
+            slippage_basis_points,
+        )
+        .await
+    }
+
+    #[cfg(feature = "pumpswap")]
+    #[inline]
+    pub async fn pumpswap_withdraw(
+        &self,
+        pool_address: &Pubkey,
+        lp_token_amount: u64,
+        slippage_basis_points: Option<u64>,
+    ) -> Result<Signature, anyhow::Error> {
+        trading::pumpswap::liquidity::withdraw(
+            &self.rpc,
+            self.payer.as_ref(),
+            pool_address,
+            lp_token_amount,
+            slippage_basis_points,
+        )
+        .await
+    }
 }
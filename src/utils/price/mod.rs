@@ -1,7 +1,13 @@
+#[cfg(feature = "bonk")]
 pub mod bonk;
+#[cfg(feature = "pumpfun")]
 pub mod pumpfun;
+#[cfg(feature = "pumpswap")]
 pub mod pumpswap;
+#[cfg(feature = "raydium")]
 pub mod raydium_amm_v4;
+#[cfg(feature = "raydium")]
 pub mod raydium_clmm;
+#[cfg(feature = "raydium")]
 pub mod raydium_cpmm;
 pub mod common;
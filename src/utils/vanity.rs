@@ -0,0 +1,161 @@
+//! Vanity mint addresses
+//!
+//! Grinds mint keypairs until the address ends with a chosen suffix, e.g. `pump`, so a token
+//! created with `pumpfun_create` looks like the ones the pump.fun site launches:
+//!
+//! ```ignore
+//! let grinder = VanityGrinder::new("pump").with_time_budget(Duration::from_secs(120));
+//! let mint = grinder.grind_async().await?;
+//! client.pumpfun_create(&mint, &metadata, Some(dev_buy), None).await?;
+//! ```
+//!
+//! Every extra character multiplies the expected search time by 58 (by about 34 when the
+//! case is ignored); four characters take a few seconds to minutes depending on the cores.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Keypairs tried between two checks of the deadline and the other threads
+const CHECK_INTERVAL: u64 = 1024;
+
+pub const DEFAULT_TIME_BUDGET: Duration = Duration::from_secs(60);
+
+/// Multithreaded search for a keypair whose address ends with a suffix
+#[derive(Debug, Clone)]
+pub struct VanityGrinder {
+    suffix: String,
+    ignore_case: bool,
+    threads: usize,
+    time_budget: Duration,
+}
+
+impl VanityGrinder {
+    /// Search on all available cores for up to `DEFAULT_TIME_BUDGET`
+    pub fn new(suffix: impl Into<String>) -> Self {
+        Self {
+            suffix: suffix.into(),
+            ignore_case: false,
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            time_budget: DEFAULT_TIME_BUDGET,
+        }
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Give up after `time_budget`
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = time_budget;
+        self
+    }
+
+    /// Accept the suffix in any case, which is much faster for longer suffixes
+    pub fn ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self
+    }
+
+    /// Check that the suffix can appear in a base58 address at all
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if self.suffix.is_empty() {
+            return Err(anyhow!("Vanity suffix is empty"));
+        }
+        let valid = |c: char| {
+            if self.ignore_case {
+                BASE58_ALPHABET.chars().any(|a| a.eq_ignore_ascii_case(&c))
+            } else {
+                BASE58_ALPHABET.contains(c)
+            }
+        };
+        if let Some(c) = self.suffix.chars().find(|c| !valid(*c)) {
+            return Err(anyhow!(
+                "Vanity suffix {} contains {:?}, not a base58 character",
+                self.suffix,
+                c
+            ));
+        }
+        Ok(())
+    }
+
+    /// Block until a keypair is found or the time budget is used up
+    pub fn grind(&self) -> Result<Keypair, anyhow::Error> {
+        self.validate()?;
+        let deadline = Instant::now() + self.time_budget;
+        let done = AtomicBool::new(false);
+        let attempts = AtomicU64::new(0);
+        let found = Mutex::new(None);
+
+        std::thread::scope(|scope| {
+            for _ in 0..self.threads {
+                scope.spawn(|| {
+                    while !done.load(Ordering::Relaxed) {
+                        for _ in 0..CHECK_INTERVAL {
+                            let keypair = Keypair::new();
+                            if self.matches(&keypair.pubkey().to_string()) {
+                                done.store(true, Ordering::Relaxed);
+                                found.lock().unwrap().get_or_insert(keypair);
+                                break;
+                            }
+                        }
+                        attempts.fetch_add(CHECK_INTERVAL, Ordering::Relaxed);
+                        if Instant::now() >= deadline {
+                            done.store(true, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+
+        let attempts = attempts.into_inner();
+        match found.into_inner().unwrap() {
+            Some(keypair) => {
+                tracing::debug!(mint = %keypair.pubkey(), attempts, "vanity mint found");
+                Ok(keypair)
+            }
+            None => Err(anyhow!(
+                "No address ending with {} found within {:?} ({} attempts)",
+                self.suffix,
+                self.time_budget,
+                attempts
+            )),
+        }
+    }
+
+    /// `grind` on tokio's blocking pool
+    pub async fn grind_async(self) -> Result<Keypair, anyhow::Error> {
+        tokio::task::spawn_blocking(move || self.grind()).await?
+    }
+
+    fn matches(&self, address: &str) -> bool {
+        if self.ignore_case {
+            let start = address.len().saturating_sub(self.suffix.len());
+            address[start..].eq_ignore_ascii_case(&self.suffix)
+        } else {
+            address.ends_with(&self.suffix)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grind_suffix() {
+        let grinder =
+            VanityGrinder::new("p").with_threads(2).with_time_budget(Duration::from_secs(10));
+        assert!(grinder.grind().unwrap().pubkey().to_string().ends_with('p'));
+
+        assert!(VanityGrinder::new("0ops").grind().is_err());
+        assert!(VanityGrinder::new("l").validate().is_err());
+        assert!(VanityGrinder::new("l").ignore_case().validate().is_ok());
+    }
+}
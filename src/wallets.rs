@@ -0,0 +1,371 @@
+//! Keypair pool and wallet funding
+//!
+//! Multi-wallet sniping spreads its buys over many fresh wallets. [`WalletPool`] generates
+//! them, funds them from a treasury in batched transfers and sweeps their SOL and tokens
+//! back once the run is over:
+//!
+//! ```ignore
+//! let pool = WalletPool::generate(10);
+//! pool.save("wallets.json")?; // the wallets will hold funds, keep their keys
+//! pool.disperse(&rpc, &treasury, 50_000_000, DEFAULT_DISPERSE_BATCH_SIZE).await?;
+//! // ... trade from pool.wallets() ...
+//! let summary = pool.sweep(&rpc, &treasury).await?;
+//! ```
+//!
+//! The treasury pays the fees of the sweep transactions, so the wallets are drained to zero.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use solana_account_decoder::UiAccountData;
+use solana_client::{rpc_request::TokenAccountsFilter, rpc_response::RpcKeyedAccount};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use solana_system_interface::instruction::transfer;
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+
+use crate::common::{wallet::parse_keypair, SolanaRpcClient, TradeSigner};
+use crate::trading::common::batch_fetcher::get_multiple_accounts;
+
+/// Transfers per disperse transaction, well within the transaction size limit
+pub const DEFAULT_DISPERSE_BATCH_SIZE: usize = 20;
+
+/// Token accounts emptied per sweep transaction; each takes up to three instructions
+pub const SWEEP_TOKEN_ACCOUNTS_PER_TX: usize = 5;
+
+/// Fresh wallets used side by side, e.g. to snipe from several addresses
+#[derive(Clone, Default)]
+pub struct WalletPool {
+    wallets: Vec<Arc<Keypair>>,
+}
+
+/// Outcome of `WalletPool::disperse`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DisperseSummary {
+    pub funded: Vec<Pubkey>,
+    /// Wallets whose transfer batch failed
+    pub failed: Vec<Pubkey>,
+    /// Lamports sent from the treasury
+    pub lamports: u64,
+    pub signatures: Vec<Signature>,
+}
+
+/// Outcome of `WalletPool::sweep`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WalletSweepSummary {
+    /// Wallets emptied completely
+    pub swept: Vec<Pubkey>,
+    /// Wallets with a failed sweep transaction, they may still hold funds
+    pub failed: Vec<Pubkey>,
+    /// SOL returned to the treasury, including closed token account rent and wrapped SOL
+    pub lamports: u64,
+    /// Token accounts moved to the treasury and closed
+    pub token_accounts: usize,
+    pub signatures: Vec<Signature>,
+}
+
+/// Token account of a wallet that the sweep empties and closes
+#[derive(Debug, Clone, PartialEq)]
+struct SweptTokenAccount {
+    address: Pubkey,
+    mint: Pubkey,
+    program_id: Pubkey,
+    amount: u64,
+    decimals: u8,
+    /// Rent plus, for wrapped SOL, the wrapped amount; returned to the treasury on close
+    lamports: u64,
+}
+
+impl SweptTokenAccount {
+    /// Parse a `jsonParsed` token account, `None` when `owner` can't empty and close it
+    fn parse(keyed: &RpcKeyedAccount, owner: &Pubkey, program_id: &Pubkey) -> Option<Self> {
+        let UiAccountData::Json(parsed) = &keyed.account.data else {
+            return None;
+        };
+        let info = parsed.parsed.get("info")?;
+        if info["state"].as_str()? == "frozen" {
+            return None;
+        }
+        if let Some(close_authority) = info.get("closeAuthority").and_then(|value| value.as_str()) {
+            if close_authority != owner.to_string() {
+                return None;
+            }
+        }
+        let token_amount = &info["tokenAmount"];
+        Some(Self {
+            address: keyed.pubkey.parse().ok()?,
+            mint: info["mint"].as_str()?.parse().ok()?,
+            program_id: *program_id,
+            amount: token_amount["amount"].as_str()?.parse().ok()?,
+            decimals: token_amount["decimals"].as_u64()?.try_into().ok()?,
+            lamports: keyed.account.lamports,
+        })
+    }
+
+    /// Move the tokens to the treasury's ATA and close the account into the treasury
+    ///
+    /// Wrapped SOL is not moved, closing the account returns it as SOL.
+    fn instructions(
+        &self,
+        owner: &Pubkey,
+        treasury: &Pubkey,
+    ) -> Result<Vec<Instruction>, anyhow::Error> {
+        let mut instructions = vec![];
+        if self.amount > 0 && self.mint != spl_token::native_mint::ID {
+            let destination = get_associated_token_address_with_program_id(
+                treasury,
+                &self.mint,
+                &self.program_id,
+            );
+            instructions.push(create_associated_token_account_idempotent(
+                treasury,
+                treasury,
+                &self.mint,
+                &self.program_id,
+            ));
+            instructions.push(spl_token_2022::instruction::transfer_checked(
+                &self.program_id,
+                &self.address,
+                &self.mint,
+                &destination,
+                owner,
+                &[],
+                self.amount,
+                self.decimals,
+            )?);
+        }
+        instructions.push(spl_token_2022::instruction::close_account(
+            &self.program_id,
+            &self.address,
+            treasury,
+            owner,
+            &[],
+        )?);
+        Ok(instructions)
+    }
+}
+
+impl WalletPool {
+    /// Generate `count` fresh keypairs
+    pub fn generate(count: usize) -> Self {
+        Self { wallets: (0..count).map(|_| Arc::new(Keypair::new())).collect() }
+    }
+
+    pub fn from_keypairs(keypairs: impl IntoIterator<Item = Keypair>) -> Self {
+        Self { wallets: keypairs.into_iter().map(Arc::new).collect() }
+    }
+
+    /// Load a pool written by `save`; entries may be base58 or JSON byte array secret keys
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read wallet file {}", path.display()))?;
+        let secrets: Vec<serde_json::Value> = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid wallet file {}", path.display()))?;
+        let wallets = secrets
+            .iter()
+            .map(|secret| match secret {
+                serde_json::Value::String(secret) => parse_keypair(secret),
+                other => parse_keypair(&other.to_string()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_keypairs(wallets))
+    }
+
+    /// Write the secret keys as a JSON array of base58 strings
+    ///
+    /// The file holds the keys in plaintext; on unix it is only readable by the owner.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), anyhow::Error> {
+        let secrets: Vec<String> =
+            self.wallets.iter().map(|wallet| wallet.to_base58_string()).collect();
+        let content = serde_json::to_string_pretty(&secrets)?;
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let path = path.as_ref();
+        let mut file = options
+            .open(path)
+            .with_context(|| format!("Failed to write wallet file {}", path.display()))?;
+        std::io::Write::write_all(&mut file, content.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.wallets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wallets.is_empty()
+    }
+
+    pub fn wallets(&self) -> &[Arc<Keypair>] {
+        &self.wallets
+    }
+
+    pub fn get(&self, index: usize) -> Option<Arc<Keypair>> {
+        self.wallets.get(index).cloned()
+    }
+
+    pub fn pubkeys(&self) -> Vec<Pubkey> {
+        self.wallets.iter().map(|wallet| wallet.pubkey()).collect()
+    }
+
+    /// SOL balance of every wallet in lamports, fetched with batched `getMultipleAccounts`
+    pub async fn balances(&self, rpc: &SolanaRpcClient) -> Result<Vec<u64>, anyhow::Error> {
+        let accounts = get_multiple_accounts(rpc, &self.pubkeys()).await?;
+        Ok(accounts.iter().map(|account| account.as_ref().map_or(0, |a| a.lamports)).collect())
+    }
+
+    /// Send `lamports` from `treasury` to every wallet, `batch_size` transfers per transaction
+    ///
+    /// `lamports` must cover the rent-exempt minimum of a system account, otherwise the
+    /// transfer to a new wallet fails. A failed batch is logged and reported in
+    /// `DisperseSummary::failed`, the remaining batches still run.
+    pub async fn disperse(
+        &self,
+        rpc: &SolanaRpcClient,
+        treasury: &dyn TradeSigner,
+        lamports: u64,
+        batch_size: usize,
+    ) -> Result<DisperseSummary, anyhow::Error> {
+        let rent_exempt = rpc.get_minimum_balance_for_rent_exemption(0).await?;
+        if lamports < rent_exempt {
+            return Err(anyhow!(
+                "Disperse amount {} is below the rent-exempt minimum of {} lamports",
+                lamports,
+                rent_exempt
+            ));
+        }
+        let from = treasury.pubkey();
+        let mut summary = DisperseSummary::default();
+        for batch in self.pubkeys().chunks(batch_size.max(1)) {
+            let instructions: Vec<_> =
+                batch.iter().map(|to| transfer(&from, to, lamports)).collect();
+            let recent_blockhash = rpc.get_latest_blockhash().await?;
+            let transaction = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&from),
+                &[treasury],
+                recent_blockhash,
+            );
+            match rpc.send_and_confirm_transaction(&transaction).await {
+                Ok(signature) => {
+                    summary.funded.extend_from_slice(batch);
+                    summary.lamports += lamports * batch.len() as u64;
+                    summary.signatures.push(signature);
+                }
+                Err(e) => {
+                    tracing::warn!(wallets = batch.len(), error = %e, "wallet disperse failed");
+                    summary.failed.extend_from_slice(batch);
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Move every token and all SOL of the wallets back to `treasury`
+    ///
+    /// Token balances go to the treasury's associated token accounts, created when missing,
+    /// and the emptied token accounts are closed into the treasury. Frozen accounts and
+    /// accounts with another close authority stay behind. The treasury pays all fees. A
+    /// wallet whose transaction fails is logged and reported in `WalletSweepSummary::failed`,
+    /// the other wallets are still swept.
+    pub async fn sweep(
+        &self,
+        rpc: &SolanaRpcClient,
+        treasury: &dyn TradeSigner,
+    ) -> Result<WalletSweepSummary, anyhow::Error> {
+        let mut summary = WalletSweepSummary::default();
+        for wallet in &self.wallets {
+            match sweep_wallet(rpc, wallet, treasury, &mut summary).await {
+                Ok(()) => summary.swept.push(wallet.pubkey()),
+                Err(e) => {
+                    tracing::warn!(wallet = %wallet.pubkey(), error = %e, "wallet sweep failed");
+                    summary.failed.push(wallet.pubkey());
+                }
+            }
+        }
+        Ok(summary)
+    }
+}
+
+async fn sweep_wallet(
+    rpc: &SolanaRpcClient,
+    wallet: &Keypair,
+    treasury: &dyn TradeSigner,
+    summary: &mut WalletSweepSummary,
+) -> Result<(), anyhow::Error> {
+    let owner = wallet.pubkey();
+    let treasury_pubkey = treasury.pubkey();
+    let signers: [&dyn Signer; 2] = [treasury, wallet];
+
+    let mut token_accounts = vec![];
+    for program_id in [spl_token::ID, spl_token_2022::ID] {
+        let accounts = rpc
+            .get_token_accounts_by_owner(&owner, TokenAccountsFilter::ProgramId(program_id))
+            .await
+            .with_context(|| format!("Failed to list token accounts of {}", owner))?;
+        token_accounts.extend(
+            accounts
+                .iter()
+                .filter_map(|keyed| SweptTokenAccount::parse(keyed, &owner, &program_id)),
+        );
+    }
+    for batch in token_accounts.chunks(SWEEP_TOKEN_ACCOUNTS_PER_TX) {
+        let mut instructions = vec![];
+        for account in batch {
+            instructions.extend(account.instructions(&owner, &treasury_pubkey)?);
+        }
+        let recent_blockhash = rpc.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&treasury_pubkey),
+            &signers,
+            recent_blockhash,
+        );
+        summary.signatures.push(rpc.send_and_confirm_transaction(&transaction).await?);
+        summary.token_accounts += batch.len();
+        summary.lamports += batch.iter().map(|account| account.lamports).sum::<u64>();
+    }
+
+    let balance = rpc.get_balance(&owner).await?;
+    if balance > 0 {
+        let recent_blockhash = rpc.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[transfer(&owner, &treasury_pubkey, balance)],
+            Some(&treasury_pubkey),
+            &signers,
+            recent_blockhash,
+        );
+        summary.signatures.push(rpc.send_and_confirm_transaction(&transaction).await?);
+        summary.lamports += balance;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_pool() {
+        let pool = WalletPool::generate(3);
+        let path = std::env::temp_dir().join(format!("wallet-pool-{}.json", std::process::id()));
+        pool.save(&path).unwrap();
+        let loaded = WalletPool::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded.pubkeys(), pool.pubkeys());
+    }
+}